@@ -1,5 +1,5 @@
-use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
-use spatio::{Point, SetOptions, Spatio};
+use criterion::{BenchmarkId, Criterion, Throughput, black_box, criterion_group, criterion_main};
+use spatio::{Config, Point, SetOptions, Spatio};
 use std::time::Duration;
 
 fn benchmark_basic_operations(c: &mut Criterion) {
@@ -179,6 +179,44 @@ fn benchmark_concurrent_operations(c: &mut Criterion) {
     group.finish();
 }
 
+fn benchmark_lock_contention(c: &mut Criterion) {
+    // Run with and without `--features parking_lot` to compare lock
+    // implementations
+    const THREADS: usize = 8;
+    const OPS_PER_THREAD: usize = 1_000;
+
+    let mut group = c.benchmark_group("lock_contention");
+    group.throughput(Throughput::Elements((THREADS * OPS_PER_THREAD) as u64));
+
+    let db = Spatio::memory().unwrap();
+    for i in 0..1_000 {
+        db.insert(format!("key:{}", i), b"value", None).unwrap();
+    }
+
+    // Mostly reads with one write in ten, from 8 threads at once
+    group.bench_function("8_threads_90_percent_reads", |b| {
+        b.iter(|| {
+            std::thread::scope(|scope| {
+                for thread_id in 0..THREADS {
+                    let db = &db;
+                    scope.spawn(move || {
+                        for i in 0..OPS_PER_THREAD {
+                            let key = format!("key:{}", (thread_id * OPS_PER_THREAD + i) % 1_000);
+                            if i % 10 == 0 {
+                                db.insert(&key, b"updated", None).unwrap();
+                            } else {
+                                black_box(db.get(&key).unwrap());
+                            }
+                        }
+                    });
+                }
+            });
+        })
+    });
+
+    group.finish();
+}
+
 fn benchmark_ttl_operations(c: &mut Criterion) {
     let mut group = c.benchmark_group("ttl_operations");
 
@@ -243,6 +281,43 @@ fn benchmark_large_datasets(c: &mut Criterion) {
     group.finish();
 }
 
+fn benchmark_parallel_queries(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parallel_queries");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(20));
+
+    for parallel in [false, true] {
+        let config = Config::default().with_parallel_queries(parallel);
+        let db = Spatio::memory_with_config(config).unwrap();
+
+        // 100K points on a grid around New York
+        for i in 0..100_000 {
+            let lat = 40.0 + (i / 400) as f64 * 0.004;
+            let lon = -74.5 + (i % 400) as f64 * 0.004;
+            let data = format!("data:{}", i);
+            db.insert_point("grid", &Point::new(lat, lon), data.as_bytes(), None)
+                .unwrap();
+        }
+
+        let name = if parallel { "parallel" } else { "sequential" };
+        group.bench_function(BenchmarkId::new("full_scan_100k", name), |b| {
+            let center = Point::new(40.5, -74.0);
+            b.iter(|| {
+                // A radius above 100km always takes the full-scan path
+                db.find_nearby(
+                    black_box("grid"),
+                    black_box(&center),
+                    black_box(150_000.0),
+                    black_box(usize::MAX),
+                )
+                .unwrap()
+            })
+        });
+    }
+
+    group.finish();
+}
+
 fn benchmark_persistence(c: &mut Criterion) {
     let mut group = c.benchmark_group("persistence");
 
@@ -273,8 +348,10 @@ criterion_group!(
     benchmark_spatial_operations,
     benchmark_trajectory_operations,
     benchmark_concurrent_operations,
+    benchmark_lock_contention,
     benchmark_ttl_operations,
     benchmark_large_datasets,
+    benchmark_parallel_queries,
     benchmark_persistence
 );
 
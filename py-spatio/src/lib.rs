@@ -8,17 +8,38 @@ use pyo3::exceptions::{PyRuntimeError, PyValueError};
 use pyo3::prelude::*;
 use pyo3::types::{PyBytes, PyList, PyTuple};
 use spatio::{
-    spatial::Point as RustPoint,
+    DB as RustDB, Result as RustResult, SpatioError,
+    spatial::{BoundingBox as RustBoundingBox, Point as RustPoint},
     types::{Config as RustConfig, SetOptions as RustSetOptions},
-    Result as RustResult, DB as RustDB,
 };
-use std::time::{Duration, UNIX_EPOCH};
+use std::collections::VecDeque;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// Convert Rust Result to Python Result
 fn handle_error<T>(result: RustResult<T>) -> PyResult<T> {
     result.map_err(|e| PyRuntimeError::new_err(e.to_string()))
 }
 
+/// Convert a SystemTime to seconds since the Unix epoch
+fn unix_timestamp(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs_f64())
+        .unwrap_or(0.0)
+}
+
+/// Run a blocking database operation on the tokio blocking thread pool
+#[cfg(feature = "python-async")]
+async fn run_blocking<T, F>(operation: F) -> PyResult<T>
+where
+    F: FnOnce() -> RustResult<T> + Send + 'static,
+    T: Send + 'static,
+{
+    let result = tokio::task::spawn_blocking(operation)
+        .await
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    handle_error(result)
+}
+
 /// Python wrapper for geographic Point
 #[pyclass(name = "Point")]
 #[derive(Clone, Debug)]
@@ -31,18 +52,11 @@ impl PyPoint {
     /// Create a new Point with latitude and longitude
     #[new]
     fn new(lat: f64, lon: f64) -> PyResult<Self> {
-        if !(-90.0..=90.0).contains(&lat) {
-            return Err(PyValueError::new_err("Latitude must be between -90 and 90"));
-        }
-        if !(-180.0..=180.0).contains(&lon) {
-            return Err(PyValueError::new_err(
-                "Longitude must be between -180 and 180",
-            ));
-        }
-
-        Ok(PyPoint {
-            inner: RustPoint::new(lat, lon),
-        })
+        let inner = RustPoint::try_new(lat, lon).map_err(|e| match e {
+            SpatioError::OutOfRange(msg) => PyValueError::new_err(msg),
+            other => PyRuntimeError::new_err(other.to_string()),
+        })?;
+        Ok(PyPoint { inner })
     }
 
     #[getter]
@@ -69,6 +83,149 @@ impl PyPoint {
     }
 }
 
+/// Python wrapper for a latitude/longitude BoundingBox
+#[pyclass(name = "BoundingBox")]
+#[derive(Clone, Debug)]
+pub struct PyBoundingBox {
+    inner: RustBoundingBox,
+}
+
+#[pymethods]
+impl PyBoundingBox {
+    /// Create a new BoundingBox from its corner coordinates
+    #[new]
+    fn new(min_lat: f64, min_lon: f64, max_lat: f64, max_lon: f64) -> PyResult<Self> {
+        for lat in [min_lat, max_lat] {
+            if !(-90.0..=90.0).contains(&lat) {
+                return Err(PyValueError::new_err("Latitude must be between -90 and 90"));
+            }
+        }
+        for lon in [min_lon, max_lon] {
+            if !(-180.0..=180.0).contains(&lon) {
+                return Err(PyValueError::new_err(
+                    "Longitude must be between -180 and 180",
+                ));
+            }
+        }
+        if min_lat > max_lat || min_lon > max_lon {
+            return Err(PyValueError::new_err(
+                "Minimum coordinates must not exceed maximum coordinates",
+            ));
+        }
+
+        Ok(PyBoundingBox {
+            inner: RustBoundingBox::new(min_lat, min_lon, max_lat, max_lon),
+        })
+    }
+
+    #[getter]
+    fn min_lat(&self) -> f64 {
+        self.inner.min.lat
+    }
+
+    #[getter]
+    fn min_lon(&self) -> f64 {
+        self.inner.min.lon
+    }
+
+    #[getter]
+    fn max_lat(&self) -> f64 {
+        self.inner.max.lat
+    }
+
+    #[getter]
+    fn max_lon(&self) -> f64 {
+        self.inner.max.lon
+    }
+
+    /// Center point of the box
+    #[getter]
+    fn center(&self) -> PyPoint {
+        PyPoint {
+            inner: self.inner.center(),
+        }
+    }
+
+    /// Check if a point lies inside the box (edges included)
+    fn contains(&self, point: &PyPoint) -> bool {
+        self.inner.contains(&point.inner)
+    }
+
+    /// Check if this box overlaps another
+    fn intersects(&self, other: &PyBoundingBox) -> bool {
+        self.inner.intersects(&other.inner)
+    }
+
+    /// Area of the box in square kilometers
+    fn area_km2(&self) -> f64 {
+        self.inner.area_km2()
+    }
+
+    /// Return a new box grown by a distance in meters on every side
+    fn expand_by_meters(&self, meters: f64) -> PyResult<PyBoundingBox> {
+        if !meters.is_finite() || meters < 0.0 {
+            return Err(PyValueError::new_err(
+                "Expansion distance must be a non-negative number",
+            ));
+        }
+
+        Ok(PyBoundingBox {
+            inner: self.inner.expand_by_meters(meters),
+        })
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "BoundingBox(min_lat={}, min_lon={}, max_lat={}, max_lon={})",
+            self.inner.min.lat, self.inner.min.lon, self.inner.max.lat, self.inner.max.lon
+        )
+    }
+
+    fn __str__(&self) -> String {
+        self.__repr__()
+    }
+}
+
+/// Parse bounds given either as a BoundingBox or as four floats
+/// (min_lat, min_lon, max_lat, max_lon).
+///
+/// Returns the bounds and the arguments that follow them.
+fn parse_bounds<'py>(
+    args: &Bound<'py, PyTuple>,
+) -> PyResult<(RustBoundingBox, Vec<Bound<'py, PyAny>>)> {
+    let args: Vec<Bound<'py, PyAny>> = args.iter().collect();
+
+    if let Some(bbox) = args
+        .first()
+        .and_then(|arg| arg.extract::<PyBoundingBox>().ok())
+    {
+        return Ok((bbox.inner, args[1..].to_vec()));
+    }
+
+    if args.len() < 4 {
+        return Err(PyValueError::new_err(
+            "Expected a BoundingBox or min_lat, min_lon, max_lat, max_lon",
+        ));
+    }
+    let bbox = RustBoundingBox::new(
+        args[0].extract()?,
+        args[1].extract()?,
+        args[2].extract()?,
+        args[3].extract()?,
+    );
+    Ok((bbox, args[4..].to_vec()))
+}
+
+/// Extract optional SetOptions at `index` of a batch item tuple
+fn extract_options(tuple: &Bound<'_, PyTuple>, index: usize) -> PyResult<Option<RustSetOptions>> {
+    if tuple.len() <= index {
+        return Ok(None);
+    }
+
+    let opts: Option<PyRef<PySetOptions>> = tuple.get_item(index)?.extract()?;
+    Ok(opts.map(|o| o.inner.clone()))
+}
+
 /// Python wrapper for SetOptions
 #[pyclass(name = "SetOptions")]
 #[derive(Clone, Debug)]
@@ -126,6 +283,26 @@ impl PySetOptions {
             inner: RustSetOptions::with_expiration(system_time),
         })
     }
+
+    /// TTL in seconds, or None if not set
+    #[getter]
+    fn ttl_seconds(&self) -> Option<f64> {
+        self.inner.ttl.map(|ttl| ttl.as_secs_f64())
+    }
+
+    /// Absolute expiration as a Unix timestamp, or None if not set
+    #[getter]
+    fn expires_at(&self) -> Option<f64> {
+        self.inner.expires_at.map(unix_timestamp)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "SetOptions(ttl_seconds={:?}, expires_at={:?})",
+            self.ttl_seconds(),
+            self.expires_at()
+        )
+    }
 }
 
 /// Python wrapper for database Config
@@ -171,10 +348,72 @@ impl PyConfig {
             ));
         }
         self.inner.geohash_precision = precision;
+        self.inner.geohash_search_precisions =
+            RustConfig::with_geohash_precision(precision).geohash_search_precisions;
         Ok(())
     }
 }
 
+/// Iterator over the keys under a prefix, fetched in batches
+#[pyclass(name = "ScanIterator")]
+pub struct PyScanIterator {
+    db: RustDB,
+    prefix: Vec<u8>,
+    count: usize,
+    /// Last key of the previous batch, `None` before the first fetch
+    cursor: Option<Vec<u8>>,
+    buffer: VecDeque<(Vec<u8>, Vec<u8>)>,
+    exhausted: bool,
+}
+
+impl PyScanIterator {
+    /// Fetch the next batch of items into the buffer
+    fn fetch(&mut self) -> PyResult<()> {
+        let (page, next) = handle_error(self.db.scan_prefix(
+            &self.prefix,
+            self.cursor.as_deref(),
+            self.count,
+        ))?;
+
+        self.buffer.extend(
+            page.into_iter()
+                .map(|(key, value)| (key.to_vec(), value.to_vec())),
+        );
+        self.exhausted = next.is_none();
+        self.cursor = next.map(|key| key.to_vec());
+        Ok(())
+    }
+}
+
+#[pymethods]
+impl PyScanIterator {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// Return the next (key, value) tuple, fetching a new batch when needed
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<(PyObject, PyObject)>> {
+        if self.buffer.is_empty() && !self.exhausted {
+            self.fetch()?;
+        }
+
+        Ok(self.buffer.pop_front().map(|(key, value)| {
+            (
+                PyBytes::new(py, &key).into(),
+                PyBytes::new(py, &value).into(),
+            )
+        }))
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "ScanIterator(prefix={:?}, count={})",
+            String::from_utf8_lossy(&self.prefix),
+            self.count
+        )
+    }
+}
+
 /// Main Spatio database class
 #[pyclass(name = "Spatio")]
 pub struct PySpatio {
@@ -249,6 +488,123 @@ impl PySpatio {
         })
     }
 
+    /// Remove all expired keys, returning how many were removed
+    fn cleanup_expired(&self) -> PyResult<usize> {
+        handle_error(self.db.cleanup_expired())
+    }
+
+    /// Seconds until a key expires, or None if it has no TTL or does not exist
+    fn remaining_ttl(&self, key: &Bound<'_, PyBytes>) -> PyResult<Option<f64>> {
+        let ttl = handle_error(self.db.remaining_ttl(key.as_bytes()))?;
+        Ok(ttl.map(|ttl| ttl.as_secs_f64()))
+    }
+
+    /// Unix timestamp at which a key expires, or None if it has no TTL or
+    /// does not exist
+    fn key_expires_at(&self, key: &Bound<'_, PyBytes>) -> PyResult<Option<f64>> {
+        let expires_at = handle_error(self.db.key_expires_at(key.as_bytes()))?;
+        Ok(expires_at.map(unix_timestamp))
+    }
+
+    /// Insert many key-value pairs in a single atomic batch
+    ///
+    /// Items are (key, value) or (key, value, options) tuples. Either all
+    /// items are inserted or none are. Returns the number of items inserted.
+    fn insert_many(&self, py: Python<'_>, items: &Bound<'_, PyList>) -> PyResult<usize> {
+        let mut batch = Vec::with_capacity(items.len());
+        for item in items.iter() {
+            let tuple = item.downcast::<PyTuple>()?;
+            if !(2..=3).contains(&tuple.len()) {
+                return Err(PyValueError::new_err(
+                    "Items must be (key, value) or (key, value, options) tuples",
+                ));
+            }
+
+            let key: Vec<u8> = tuple
+                .get_item(0)?
+                .downcast::<PyBytes>()?
+                .as_bytes()
+                .to_vec();
+            let value: Vec<u8> = tuple
+                .get_item(1)?
+                .downcast::<PyBytes>()?
+                .as_bytes()
+                .to_vec();
+            let opts = extract_options(tuple, 2)?;
+            batch.push((key, value, opts));
+        }
+
+        let db = &self.db;
+        handle_error(py.allow_threads(|| {
+            db.atomic(|atomic| {
+                let count = batch.len();
+                for (key, value, opts) in batch {
+                    atomic.insert(key, value, opts)?;
+                }
+                Ok(count)
+            })
+        }))
+    }
+
+    /// Insert many geographic points in a single atomic batch
+    ///
+    /// Items are (prefix, point, value) or (prefix, point, value, options)
+    /// tuples. Either all items are inserted or none are. Returns the number
+    /// of items inserted.
+    fn insert_points_many(&self, py: Python<'_>, items: &Bound<'_, PyList>) -> PyResult<usize> {
+        let mut batch = Vec::with_capacity(items.len());
+        for item in items.iter() {
+            let tuple = item.downcast::<PyTuple>()?;
+            if !(3..=4).contains(&tuple.len()) {
+                return Err(PyValueError::new_err(
+                    "Items must be (prefix, point, value) or (prefix, point, value, options) tuples",
+                ));
+            }
+
+            let prefix: String = tuple.get_item(0)?.extract()?;
+            let point: PyRef<PyPoint> = tuple.get_item(1)?.extract()?;
+            let value: Vec<u8> = tuple
+                .get_item(2)?
+                .downcast::<PyBytes>()?
+                .as_bytes()
+                .to_vec();
+            let opts = extract_options(tuple, 3)?;
+            batch.push((prefix, point.inner, value, opts));
+        }
+
+        let db = &self.db;
+        handle_error(py.allow_threads(|| {
+            db.atomic(|atomic| {
+                let count = batch.len();
+                for (prefix, point, value, opts) in batch {
+                    atomic.insert_point(&prefix, &point, &value, opts)?;
+                }
+                Ok(count)
+            })
+        }))
+    }
+
+    /// Iterate over all keys starting with a prefix
+    ///
+    /// Items are fetched lazily in batches of `count`, so large prefixes
+    /// are never loaded into memory at once. Yields (key, value) tuples
+    /// in key order.
+    #[pyo3(signature = (prefix, count=100))]
+    fn scan(&self, prefix: &Bound<'_, PyBytes>, count: usize) -> PyResult<PyScanIterator> {
+        if count == 0 {
+            return Err(PyValueError::new_err("count must be at least 1"));
+        }
+
+        Ok(PyScanIterator {
+            db: self.db.clone(),
+            prefix: prefix.as_bytes().to_vec(),
+            count,
+            cursor: None,
+            buffer: VecDeque::with_capacity(count),
+            exhausted: false,
+        })
+    }
+
     /// Insert a geographic point with automatic spatial indexing
     #[pyo3(signature = (prefix, point, value, options=None))]
     fn insert_point(
@@ -366,34 +722,51 @@ impl PySpatio {
     }
 
     /// Check if any points exist within a bounding box
-    fn intersects_bounds(
-        &self,
-        prefix: &str,
-        min_lat: f64,
-        min_lon: f64,
-        max_lat: f64,
-        max_lon: f64,
-    ) -> PyResult<bool> {
-        handle_error(
-            self.db
-                .intersects_bounds(prefix, min_lat, min_lon, max_lat, max_lon),
-        )
+    ///
+    /// Bounds are given either as a BoundingBox or as
+    /// min_lat, min_lon, max_lat, max_lon.
+    #[pyo3(signature = (prefix, *args))]
+    fn intersects_bounds(&self, prefix: &str, args: &Bound<'_, PyTuple>) -> PyResult<bool> {
+        let (bbox, rest) = parse_bounds(args)?;
+        if !rest.is_empty() {
+            return Err(PyValueError::new_err(
+                "intersects_bounds() got unexpected extra arguments",
+            ));
+        }
+
+        handle_error(self.db.intersects_bounds(
+            prefix,
+            bbox.min.lat,
+            bbox.min.lon,
+            bbox.max.lat,
+            bbox.max.lon,
+        ))
     }
 
     /// Find all points within a bounding box
-    fn find_within_bounds(
-        &self,
-        prefix: &str,
-        min_lat: f64,
-        min_lon: f64,
-        max_lat: f64,
-        max_lon: f64,
-        limit: usize,
-    ) -> PyResult<PyObject> {
-        let results = handle_error(
-            self.db
-                .find_within_bounds(prefix, min_lat, min_lon, max_lat, max_lon, limit),
-        )?;
+    ///
+    /// Bounds are given either as a BoundingBox or as
+    /// min_lat, min_lon, max_lat, max_lon, followed by the result limit.
+    #[pyo3(signature = (prefix, *args))]
+    fn find_within_bounds(&self, prefix: &str, args: &Bound<'_, PyTuple>) -> PyResult<PyObject> {
+        let (bbox, rest) = parse_bounds(args)?;
+        let limit: usize = match rest.as_slice() {
+            [limit] => limit.extract()?,
+            _ => {
+                return Err(PyValueError::new_err(
+                    "find_within_bounds() expects a single limit after the bounds",
+                ));
+            }
+        };
+
+        let results = handle_error(self.db.find_within_bounds(
+            prefix,
+            bbox.min.lat,
+            bbox.min.lon,
+            bbox.max.lat,
+            bbox.max.lon,
+            limit,
+        ))?;
 
         Python::with_gil(|py| {
             let py_list = PyList::empty(py);
@@ -407,6 +780,25 @@ impl PySpatio {
         })
     }
 
+    /// Compute the convex hull of all points under a prefix
+    ///
+    /// Returns the closed exterior ring as a list of points, or None if the
+    /// prefix has fewer than three non-collinear points.
+    fn convex_hull_of_prefix(&self, prefix: &str) -> PyResult<Option<Vec<PyPoint>>> {
+        let hull = handle_error(self.db.convex_hull_of_prefix(prefix))?;
+
+        Ok(hull.map(|polygon| {
+            polygon
+                .exterior()
+                .coords()
+                .iter()
+                .map(|coord| PyPoint {
+                    inner: coord.to_point(),
+                })
+                .collect()
+        }))
+    }
+
     /// Force sync to disk
     fn sync(&self) -> PyResult<()> {
         handle_error(self.db.sync())
@@ -421,14 +813,156 @@ impl PySpatio {
             dict.set_item("key_count", stats.key_count)?;
             dict.set_item("expired_count", stats.expired_count)?;
             dict.set_item("operations_count", stats.operations_count)?;
+            dict.set_item("evicted_count", stats.evicted_count)?;
             Ok(dict.into())
         })
     }
 
-    /// Close the database
+    /// Close the database, flushing pending writes to disk
+    ///
+    /// Further operations raise an error. Closing twice is a no-op.
     fn close(&mut self) -> PyResult<()> {
-        // For now, this is a no-op since DB doesn't implement mutable close
-        Ok(())
+        match self.db.close() {
+            Err(SpatioError::DatabaseClosed) => Ok(()),
+            result => handle_error(result),
+        }
+    }
+
+    fn __enter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    /// Close the database when leaving a `with` block
+    ///
+    /// Exceptions raised inside the block are not suppressed.
+    fn __exit__(
+        &mut self,
+        _exc_type: Option<&Bound<'_, PyAny>>,
+        _exc_value: Option<&Bound<'_, PyAny>>,
+        _traceback: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<bool> {
+        self.close()?;
+        Ok(false)
+    }
+
+    /// Create an in-memory database without blocking the event loop
+    #[cfg(feature = "python-async")]
+    #[staticmethod]
+    fn memory_async(py: Python<'_>) -> PyResult<Bound<'_, PyAny>> {
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let db = run_blocking(RustDB::memory).await?;
+            Ok(PySpatio { db })
+        })
+    }
+
+    /// Open a persistent database without blocking the event loop
+    #[cfg(feature = "python-async")]
+    #[staticmethod]
+    fn open_async(py: Python<'_>, path: String) -> PyResult<Bound<'_, PyAny>> {
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let db = run_blocking(move || RustDB::open(path)).await?;
+            Ok(PySpatio { db })
+        })
+    }
+
+    /// Awaitable version of `insert`
+    #[cfg(feature = "python-async")]
+    #[pyo3(signature = (key, value, options=None))]
+    fn async_insert<'py>(
+        &self,
+        py: Python<'py>,
+        key: &Bound<'py, PyBytes>,
+        value: &Bound<'py, PyBytes>,
+        options: Option<&PySetOptions>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let db = self.db.clone();
+        let key_bytes = key.as_bytes().to_vec();
+        let value_bytes = value.as_bytes().to_vec();
+        let opts = options.map(|o| o.inner.clone());
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            run_blocking(move || db.insert(key_bytes, value_bytes, opts).map(|_| ())).await
+        })
+    }
+
+    /// Awaitable version of `get`
+    #[cfg(feature = "python-async")]
+    fn async_get<'py>(
+        &self,
+        py: Python<'py>,
+        key: &Bound<'py, PyBytes>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let db = self.db.clone();
+        let key_bytes = key.as_bytes().to_vec();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let result = run_blocking(move || db.get(key_bytes)).await?;
+
+            Python::with_gil(|py| -> PyResult<Option<PyObject>> {
+                Ok(result.map(|bytes| PyBytes::new(py, &bytes).into()))
+            })
+        })
+    }
+
+    /// Awaitable version of `find_nearby`
+    #[cfg(feature = "python-async")]
+    fn async_find_nearby<'py>(
+        &self,
+        py: Python<'py>,
+        prefix: String,
+        center: &PyPoint,
+        radius_meters: f64,
+        limit: usize,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let db = self.db.clone();
+        let center = center.inner;
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let results =
+                run_blocking(move || db.find_nearby(&prefix, &center, radius_meters, limit))
+                    .await?;
+
+            Python::with_gil(|py| -> PyResult<PyObject> {
+                let py_list = PyList::empty(py);
+                for (point, value) in results {
+                    let py_point = PyPoint { inner: point };
+                    let py_value = PyBytes::new(py, &value);
+                    let distance = center.distance_to(&point);
+                    let tuple = (py_point, py_value, distance).into_pyobject(py)?;
+                    py_list.append(tuple)?;
+                }
+                Ok(py_list.into())
+            })
+        })
+    }
+
+    /// Awaitable version of `query_trajectory`
+    #[cfg(feature = "python-async")]
+    fn async_query_trajectory<'py>(
+        &self,
+        py: Python<'py>,
+        object_id: String,
+        start_time: f64,
+        end_time: f64,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let db = self.db.clone();
+
+        pyo3_async_runtimes::tokio::future_into_py(py, async move {
+            let results = run_blocking(move || {
+                db.query_trajectory(&object_id, start_time as u64, end_time as u64)
+            })
+            .await?;
+
+            Python::with_gil(|py| -> PyResult<PyObject> {
+                let py_list = PyList::empty(py);
+                for (point, timestamp) in results {
+                    let py_point = PyPoint { inner: point };
+                    let tuple = (py_point, timestamp as f64).into_pyobject(py)?;
+                    py_list.append(tuple)?;
+                }
+                Ok(py_list.into())
+            })
+        })
     }
 
     fn __repr__(&self) -> String {
@@ -441,8 +975,10 @@ impl PySpatio {
 fn _spatio(_py: Python, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<PySpatio>()?;
     m.add_class::<PyPoint>()?;
+    m.add_class::<PyBoundingBox>()?;
     m.add_class::<PySetOptions>()?;
     m.add_class::<PyConfig>()?;
+    m.add_class::<PyScanIterator>()?;
 
     // Add version
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
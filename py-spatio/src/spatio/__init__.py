@@ -25,16 +25,20 @@ Example usage:
 from __future__ import annotations
 
 # Import the compiled Rust extension
+from spatio._spatio import BoundingBox as _BoundingBox
 from spatio._spatio import Config as _Config
 from spatio._spatio import Point as _Point
+from spatio._spatio import ScanIterator as _ScanIterator
 from spatio._spatio import SetOptions as _SetOptions
 from spatio._spatio import Spatio as _Spatio
 from spatio._spatio import __version__
 
 # Re-export main classes
 __all__ = [
+    "BoundingBox",
     "Config",
     "Point",
+    "ScanIterator",
     "SetOptions",
     "Spatio",
     "__version__",
@@ -43,6 +47,8 @@ __all__ = [
 # Type aliases for better API
 Spatio = _Spatio
 Point = _Point
+BoundingBox = _BoundingBox
+ScanIterator = _ScanIterator
 SetOptions = _SetOptions
 Config = _Config
 
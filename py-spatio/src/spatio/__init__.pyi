@@ -0,0 +1,179 @@
+"""Type stubs for the Spatio Python bindings."""
+
+from __future__ import annotations
+
+from types import TracebackType
+from typing import Awaitable
+from typing import Iterator
+from typing import overload
+
+__version__: str
+
+class Point:
+    def __init__(self, lat: float, lon: float) -> None: ...
+    @property
+    def lat(self) -> float: ...
+    @property
+    def lon(self) -> float: ...
+    def distance_to(self, other: Point) -> float: ...
+
+class BoundingBox:
+    def __init__(
+        self, min_lat: float, min_lon: float, max_lat: float, max_lon: float
+    ) -> None: ...
+    @property
+    def min_lat(self) -> float: ...
+    @property
+    def min_lon(self) -> float: ...
+    @property
+    def max_lat(self) -> float: ...
+    @property
+    def max_lon(self) -> float: ...
+    @property
+    def center(self) -> Point: ...
+    def contains(self, point: Point) -> bool: ...
+    def intersects(self, other: BoundingBox) -> bool: ...
+    def area_km2(self) -> float: ...
+    def expand_by_meters(self, meters: float) -> BoundingBox: ...
+
+class SetOptions:
+    def __init__(self) -> None: ...
+    @staticmethod
+    def with_ttl(ttl_seconds: float) -> SetOptions: ...
+    @staticmethod
+    def with_expiration(timestamp: float) -> SetOptions: ...
+    @property
+    def ttl_seconds(self) -> float | None: ...
+    @property
+    def expires_at(self) -> float | None: ...
+
+class Config:
+    def __init__(self) -> None: ...
+    @staticmethod
+    def with_geohash_precision(precision: int) -> Config: ...
+    @property
+    def geohash_precision(self) -> int: ...
+    @geohash_precision.setter
+    def geohash_precision(self, precision: int) -> None: ...
+
+class ScanIterator(Iterator[tuple[bytes, bytes]]):
+    def __iter__(self) -> ScanIterator: ...
+    def __next__(self) -> tuple[bytes, bytes]: ...
+
+class Spatio:
+    @staticmethod
+    def memory() -> Spatio: ...
+    @staticmethod
+    def memory_with_config(config: Config) -> Spatio: ...
+    @staticmethod
+    def open(path: str) -> Spatio: ...
+    @staticmethod
+    def open_with_config(path: str, config: Config) -> Spatio: ...
+    def insert(
+        self, key: bytes, value: bytes, options: SetOptions | None = None
+    ) -> None: ...
+    def get(self, key: bytes) -> bytes | None: ...
+    def delete(self, key: bytes) -> bytes | None: ...
+    def insert_many(
+        self,
+        items: list[tuple[bytes, bytes] | tuple[bytes, bytes, SetOptions | None]],
+    ) -> int: ...
+    def insert_points_many(
+        self,
+        items: list[
+            tuple[str, Point, bytes] | tuple[str, Point, bytes, SetOptions | None]
+        ],
+    ) -> int: ...
+    def scan(self, prefix: bytes, count: int = 100) -> ScanIterator: ...
+    def cleanup_expired(self) -> int: ...
+    def remaining_ttl(self, key: bytes) -> float | None: ...
+    def key_expires_at(self, key: bytes) -> float | None: ...
+    def insert_point(
+        self,
+        prefix: str,
+        point: Point,
+        value: bytes,
+        options: SetOptions | None = None,
+    ) -> None: ...
+    def find_nearby(
+        self, prefix: str, center: Point, radius_meters: float, limit: int
+    ) -> list[tuple[Point, bytes, float]]: ...
+    def insert_trajectory(
+        self,
+        object_id: str,
+        trajectory: list[tuple[Point, float]],
+        options: SetOptions | None = None,
+    ) -> None: ...
+    def query_trajectory(
+        self, object_id: str, start_time: float, end_time: float
+    ) -> list[tuple[Point, float]]: ...
+    def contains_point(
+        self, prefix: str, center: Point, radius_meters: float
+    ) -> bool: ...
+    def count_within_distance(
+        self, prefix: str, center: Point, radius_meters: float
+    ) -> int: ...
+    @overload
+    def intersects_bounds(self, prefix: str, bbox: BoundingBox, /) -> bool: ...
+    @overload
+    def intersects_bounds(
+        self,
+        prefix: str,
+        min_lat: float,
+        min_lon: float,
+        max_lat: float,
+        max_lon: float,
+        /,
+    ) -> bool: ...
+    @overload
+    def find_within_bounds(
+        self, prefix: str, bbox: BoundingBox, limit: int, /
+    ) -> list[tuple[Point, bytes]]: ...
+    @overload
+    def find_within_bounds(
+        self,
+        prefix: str,
+        min_lat: float,
+        min_lon: float,
+        max_lat: float,
+        max_lon: float,
+        limit: int,
+        /,
+    ) -> list[tuple[Point, bytes]]: ...
+    def convex_hull_of_prefix(self, prefix: str) -> list[Point] | None: ...
+    def sync(self) -> None: ...
+    def stats(self) -> dict[str, int]: ...
+    def close(self) -> None: ...
+    def __enter__(self) -> Spatio: ...
+    def __exit__(
+        self,
+        exc_type: type[BaseException] | None,
+        exc_value: BaseException | None,
+        traceback: TracebackType | None,
+    ) -> bool: ...
+
+    # Available when built with the `python-async` feature
+    @staticmethod
+    def memory_async() -> Awaitable[Spatio]: ...
+    @staticmethod
+    def open_async(path: str) -> Awaitable[Spatio]: ...
+    def async_insert(
+        self, key: bytes, value: bytes, options: SetOptions | None = None
+    ) -> Awaitable[None]: ...
+    def async_get(self, key: bytes) -> Awaitable[bytes | None]: ...
+    def async_find_nearby(
+        self, prefix: str, center: Point, radius_meters: float, limit: int
+    ) -> Awaitable[list[tuple[Point, bytes, float]]]: ...
+    def async_query_trajectory(
+        self, object_id: str, start_time: float, end_time: float
+    ) -> Awaitable[list[tuple[Point, float]]]: ...
+
+__all__ = [
+    "BoundingBox",
+    "Config",
+    "Point",
+    "ScanIterator",
+    "SetOptions",
+    "Spatio",
+    "__version__",
+]
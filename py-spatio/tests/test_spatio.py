@@ -2,6 +2,7 @@
 Comprehensive tests for Spatio Python bindings
 """
 
+import asyncio
 import os
 import platform
 import tempfile
@@ -67,6 +68,66 @@ class TestPoint:
         assert "Point(lat=40.7128, lon=-74.006)" in str(point)
 
 
+class TestBoundingBox:
+    """Test BoundingBox class functionality"""
+
+    def test_bounding_box_creation(self):
+        """Test creating a bounding box and reading its corners"""
+        bbox = spatio.BoundingBox(40.0, -75.0, 42.0, -73.0)
+        assert bbox.min_lat == 40.0
+        assert bbox.min_lon == -75.0
+        assert bbox.max_lat == 42.0
+        assert bbox.max_lon == -73.0
+
+        center = bbox.center
+        assert isinstance(center, spatio.Point)
+        assert center.lat == 41.0
+        assert center.lon == -74.0
+
+    @pytest.mark.parametrize(
+        "min_lat, min_lon, max_lat, max_lon",
+        [
+            pytest.param(-91.0, 0.0, 0.0, 1.0, id="latitude out of range"),
+            pytest.param(0.0, 0.0, 1.0, 181.0, id="longitude out of range"),
+            pytest.param(2.0, 0.0, 1.0, 1.0, id="min above max"),
+        ]
+    )
+    def test_invalid_bounding_box(self, min_lat, min_lon, max_lat, max_lon):
+        """Test bounding box validation"""
+        with pytest.raises(ValueError):
+            spatio.BoundingBox(min_lat, min_lon, max_lat, max_lon)
+
+    def test_contains_and_intersects(self):
+        """Test point containment and box overlap"""
+        nyc_area = spatio.BoundingBox(40.5, -74.5, 41.0, -73.5)
+        manhattan = spatio.BoundingBox(40.7, -74.02, 40.88, -73.9)
+        london = spatio.BoundingBox(51.3, -0.5, 51.7, 0.3)
+
+        assert nyc_area.contains(spatio.Point(40.7128, -74.0060))
+        assert not nyc_area.contains(spatio.Point(51.5074, -0.1278))
+        assert nyc_area.intersects(manhattan)
+        assert not nyc_area.intersects(london)
+
+    def test_area_and_expand(self):
+        """Test area calculation and expansion"""
+        bbox = spatio.BoundingBox(0.0, 0.0, 1.0, 1.0)
+        # One degree square at the equator is roughly 12,364 km²
+        assert 12_300 < bbox.area_km2() < 12_400
+
+        expanded = bbox.expand_by_meters(1000.0)
+        assert expanded.min_lat < bbox.min_lat
+        assert expanded.max_lon > bbox.max_lon
+        assert expanded.area_km2() > bbox.area_km2()
+
+        with pytest.raises(ValueError):
+            bbox.expand_by_meters(-1.0)
+
+    def test_bounding_box_repr(self):
+        """Test bounding box string representation"""
+        bbox = spatio.BoundingBox(40.0, -75.0, 42.0, -73.0)
+        assert "BoundingBox(min_lat=40, min_lon=-75, max_lat=42, max_lon=-73)" in str(bbox)
+
+
 class TestSetOptions:
     """Test SetOptions class functionality"""
 
@@ -74,11 +135,15 @@ class TestSetOptions:
         """Test default SetOptions creation"""
         opts = spatio.SetOptions()
         assert opts is not None
+        assert opts.ttl_seconds is None
+        assert opts.expires_at is None
 
     def test_ttl_options(self):
         """Test TTL SetOptions creation"""
         opts = spatio.SetOptions.with_ttl(300.0)  # 5 minutes
         assert opts is not None
+        assert opts.ttl_seconds == 300.0
+        assert opts.expires_at is None
 
     def test_invalid_ttl(self):
         """Test invalid TTL values"""
@@ -93,6 +158,8 @@ class TestSetOptions:
         future_timestamp = time.time() + 300  # 5 minutes from now
         opts = spatio.SetOptions.with_expiration(future_timestamp)
         assert opts is not None
+        assert opts.expires_at == pytest.approx(future_timestamp)
+        assert opts.ttl_seconds is None
 
 
 class TestConfig:
@@ -198,6 +265,27 @@ class TestSpatio:
         # We can't guarantee timing in tests, so we just verify the operation worked
         assert True  # TTL was set successfully
 
+    def test_ttl_inspection_and_cleanup(self):
+        """Test inspecting TTLs and triggering cleanup without sleeping"""
+        db = spatio.Spatio.memory()
+
+        db.insert(b"fresh", b"1", spatio.SetOptions.with_ttl(300.0))
+        db.insert(b"stale", b"2", spatio.SetOptions.with_expiration(1.0))
+        db.insert(b"forever", b"3")
+
+        assert 290.0 < db.remaining_ttl(b"fresh") <= 300.0
+        assert db.key_expires_at(b"fresh") == pytest.approx(time.time() + 300.0, abs=5.0)
+        assert db.remaining_ttl(b"forever") is None
+        assert db.key_expires_at(b"forever") is None
+        assert db.remaining_ttl(b"missing") is None
+
+        # The stale key is already hidden and gets removed by the sweep
+        assert db.remaining_ttl(b"stale") is None
+        assert db.cleanup_expired() == 1
+        assert db.cleanup_expired() == 0
+        assert db.stats()["expired_count"] == 1
+        assert db.get(b"fresh") == b"1"
+
     def test_point_operations(self):
         """Test geographic point operations"""
         db = spatio.Spatio.memory()
@@ -251,6 +339,37 @@ class TestSpatio:
             assert isinstance(point, spatio.Point)
             assert isinstance(value, bytes)
 
+        # Bounds can also be given as a BoundingBox
+        nyc_area = spatio.BoundingBox(40.6, -74.1, 40.8, -73.9)
+        assert db.intersects_bounds("cities", nyc_area)
+        assert len(db.find_within_bounds("cities", nyc_area, 100)) == len(points)
+
+        with pytest.raises(ValueError):
+            db.find_within_bounds("cities", nyc_area)
+
+    def test_convex_hull_of_prefix(self):
+        """Test convex hull computation over a prefix"""
+        db = spatio.Spatio.memory()
+
+        # No hull for an empty prefix
+        assert db.convex_hull_of_prefix("sensors") is None
+
+        db.insert_point("sensors", spatio.Point(40.70, -74.02), b"a")
+        db.insert_point("sensors", spatio.Point(40.80, -74.02), b"b")
+        db.insert_point("sensors", spatio.Point(40.80, -73.90), b"c")
+        db.insert_point("sensors", spatio.Point(40.70, -73.90), b"d")
+        db.insert_point("sensors", spatio.Point(40.75, -73.96), b"inner")
+
+        hull = db.convex_hull_of_prefix("sensors")
+        assert hull is not None
+
+        # Closed ring of the four corners
+        assert len(hull) == 5
+        assert hull[0].lat == hull[-1].lat
+        assert hull[0].lon == hull[-1].lon
+        for point in hull:
+            assert isinstance(point, spatio.Point)
+
     def test_trajectory_operations(self):
         """Test trajectory tracking functionality"""
         db = spatio.Spatio.memory()
@@ -315,6 +434,99 @@ class TestSpatio:
         # Should not raise any errors
         db.sync()
 
+    def test_insert_many(self):
+        """Test batch inserting key-value pairs"""
+        db = spatio.Spatio.memory()
+        opts = spatio.SetOptions.with_ttl(300.0)
+
+        items = [(f"user:{i}".encode(), f"name{i}".encode()) for i in range(1000)]
+        items.append((b"session:1", b"token", opts))
+        items.append((b"session:2", b"token", None))
+
+        assert db.insert_many(items) == 1002
+        assert db.get(b"user:999") == b"name999"
+        assert db.remaining_ttl(b"session:1") is not None
+        assert db.remaining_ttl(b"session:2") is None
+        assert db.insert_many([]) == 0
+
+        # Malformed items reject the whole batch
+        with pytest.raises(ValueError):
+            db.insert_many([(b"ok", b"1"), (b"bad",)])
+        assert db.get(b"ok") is None
+
+    def test_insert_points_many(self):
+        """Test batch inserting points"""
+        db = spatio.Spatio.memory()
+        nyc = spatio.Point(40.7128, -74.0060)
+        brooklyn = spatio.Point(40.6782, -73.9442)
+
+        count = db.insert_points_many([
+            ("cities", nyc, b"New York"),
+            ("cities", brooklyn, b"Brooklyn", spatio.SetOptions.with_ttl(300.0)),
+        ])
+        assert count == 2
+
+        nearby = db.find_nearby("cities", nyc, 20_000.0, 10)
+        assert sorted(value for _, value, _ in nearby) == [b"Brooklyn", b"New York"]
+
+        with pytest.raises(TypeError):
+            db.insert_points_many([("cities", "not a point", b"x")])
+
+    def test_scan_prefix(self):
+        """Test iterating a large prefix in batches"""
+        db = spatio.Spatio.memory()
+        for i in range(10_000):
+            db.insert(f"item:{i:05d}".encode(), str(i).encode())
+        db.insert(b"other:1", b"skip")
+
+        scan = db.scan(b"item:", count=100)
+        assert iter(scan) is scan
+
+        first_key, first_value = next(scan)
+        assert first_key == b"item:00000"
+        assert first_value == b"0"
+
+        # Batches are fetched lazily, so keys written after the scan
+        # started are still picked up by later batches
+        db.insert(b"item:99999", b"late")
+
+        rest = list(scan)
+        assert len(rest) == 10_000
+        assert rest[-1] == (b"item:99999", b"late")
+        assert all(key.startswith(b"item:") for key, _ in rest)
+
+        assert list(db.scan(b"missing:")) == []
+        with pytest.raises(ValueError):
+            db.scan(b"item:", count=0)
+
+    def test_context_manager(self):
+        """Test that a with block closes the database"""
+        with spatio.Spatio.memory() as db:
+            db.insert(b"key", b"value")
+            assert db.get(b"key") == b"value"
+
+        with pytest.raises(RuntimeError):
+            db.get(b"key")
+
+    def test_context_manager_flushes_on_exception(self):
+        """Test that data is flushed when the with block raises"""
+        with tempfile.TemporaryDirectory() as tmpdir:
+            db_path = os.path.normpath(os.path.join(tmpdir, "ctx.db"))
+
+            with pytest.raises(ValueError):
+                with spatio.Spatio.open(db_path) as db:
+                    db.insert(b"key", b"value")
+                    raise ValueError("boom")
+
+            with spatio.Spatio.open(db_path) as reopened:
+                assert reopened.get(b"key") == b"value"
+
+            # On Windows, ensure file is properly released
+            if platform.system() == "Windows":
+                import gc
+
+                gc.collect()
+
     def test_close_operation(self):
         """Test database close operation"""
         db = spatio.Spatio.memory()
@@ -334,11 +546,59 @@ class TestSpatio:
         assert "Spatio" in str(db)
 
 
+@pytest.mark.skipif(
+    not hasattr(spatio.Spatio, "memory_async"),
+    reason="built without the python-async feature",
+)
+class TestAsync:
+    """Test awaitable database methods"""
+
+    def test_async_insert_and_get(self):
+        """Test async key-value round trip"""
+
+        async def run():
+            db = await spatio.Spatio.memory_async()
+            await db.async_insert(b"user:1", b"alice")
+            assert await db.async_get(b"user:1") == b"alice"
+            assert await db.async_get(b"user:2") is None
+
+        asyncio.run(run())
+
+    def test_async_spatial_queries(self):
+        """Test async nearby and trajectory queries"""
+
+        async def run():
+            db = await spatio.Spatio.memory_async()
+            nyc = spatio.Point(40.7128, -74.0060)
+            db.insert_point("cities", nyc, b"New York")
+            db.insert_trajectory("vehicle:1", [(nyc, 100.0), (nyc, 200.0)])
+
+            nearby, trajectory = await asyncio.gather(
+                db.async_find_nearby("cities", nyc, 1000.0, 10),
+                db.async_query_trajectory("vehicle:1", 0.0, 300.0),
+            )
+            assert [value for _, value, _ in nearby] == [b"New York"]
+            assert len(trajectory) == 2
+
+        asyncio.run(run())
+
+    def test_open_async(self):
+        """Test opening a persistent database asynchronously"""
+
+        async def run(path):
+            db = await spatio.Spatio.open_async(path)
+            await db.async_insert(b"key", b"value")
+            assert await db.async_get(b"key") == b"value"
+
+        with tempfile.TemporaryDirectory() as tmp_dir:
+            asyncio.run(run(os.path.join(tmp_dir, "async.db")))
+
+
 class TestErrorHandling:
     """Test error handling and edge cases"""
 
     def test_operations_on_closed_database(self):
-        """Test operations on a closed database still work (limitation)"""
+        """Test operations on a closed database are rejected"""
         db = spatio.Spatio.memory()
         db.close()
 
@@ -348,10 +608,11 @@ class TestErrorHandling:
 
             gc.collect()
 
-        # Current implementation allows operations after close
-        # This is a limitation of the current API
-        db.insert(b"key", b"value")  # Should still work
-        assert db.get(b"key") == b"value"
+        with pytest.raises(RuntimeError):
+            db.insert(b"key", b"value")
+
+        # Closing again is harmless
+        db.close()
 
     def test_invalid_trajectory_data(self):
         """Test invalid trajectory data"""
@@ -0,0 +1,1131 @@
+//! Spatial analysis over sets of points
+//!
+//! These operations work on planar longitude/latitude coordinates, like the
+//! geometry module, so distances are in degrees rather than meters, except
+//! where a method takes a distance in meters.
+
+use crate::error::{Result, SpatioError};
+use crate::geometry::{Coordinate, LineString, LinearRing, Polygon};
+use crate::spatial::{BoundingBox, Point};
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, BinaryHeap};
+use std::f64::consts::TAU;
+
+/// Segments used to draw a full circle in `buffer_trajectory`
+const BUFFER_CIRCLE_SEGMENTS: usize = 32;
+
+/// Spatial analysis utilities
+pub struct SpatialAnalysis;
+
+impl SpatialAnalysis {
+    /// Compute the Voronoi cell of each point, clipped to a bounding box.
+    ///
+    /// Every location in a point's cell is closer to that point than to any
+    /// other, measured in planar longitude/latitude. Fortune's sweep line
+    /// finds which points share a cell edge, and each cell is the bounding
+    /// box cut down by the bisectors with those neighbors.
+    ///
+    /// # Arguments
+    ///
+    /// * `points` - Seed points
+    /// * `bbox` - Area the cells are clipped to
+    ///
+    /// # Returns
+    ///
+    /// Seed and cell pairs in input order. Seeds with non-finite coordinates
+    /// and seeds whose cell lies outside the box are left out. Duplicate
+    /// seeds share the same cell.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{BoundingBox, Point, SpatialAnalysis};
+    ///
+    /// let towers = [Point::new(0.0, 0.0), Point::new(0.0, 2.0)];
+    /// let area = BoundingBox::new(-1.0, -1.0, 1.0, 3.0);
+    ///
+    /// let cells = SpatialAnalysis::voronoi_cells(&towers, &area);
+    /// assert_eq!(cells.len(), 2);
+    ///
+    /// // The towers split the area along the line halfway between them
+    /// let west = cells[0].1.bounds();
+    /// assert_eq!((west.min.lon, west.max.lon), (-1.0, 1.0));
+    /// assert!((cells[0].1.area() - 4.0).abs() < 1e-9);
+    /// ```
+    pub fn voronoi_cells(points: &[Point], bbox: &BoundingBox) -> Vec<(Point, Polygon)> {
+        let mut site_of: FxHashMap<Point, usize> = FxHashMap::default();
+        let mut sites = Vec::new();
+        for point in points.iter().filter(|p| p.is_valid()) {
+            site_of.entry(*point).or_insert_with(|| {
+                sites.push(Coordinate::from_point(point));
+                sites.len() - 1
+            });
+        }
+
+        let neighbors = Sweep::new(&sites).run();
+        let frame = [
+            Coordinate::new(bbox.min.lon, bbox.min.lat),
+            Coordinate::new(bbox.max.lon, bbox.min.lat),
+            Coordinate::new(bbox.max.lon, bbox.max.lat),
+            Coordinate::new(bbox.min.lon, bbox.max.lat),
+        ];
+        let cells: Vec<Option<Polygon>> = neighbors
+            .iter()
+            .enumerate()
+            .map(|(i, near)| {
+                let ring = near.iter().fold(frame.to_vec(), |ring, &j| {
+                    clip_half_plane(&ring, &sites[i], &sites[j])
+                });
+                let ring = LinearRing::new(ring).ok()?;
+                Some(Polygon::new(ring, vec![]))
+            })
+            .collect();
+
+        points
+            .iter()
+            .filter_map(|point| {
+                let cell = cells[*site_of.get(point)?].as_ref()?;
+                Some((*point, cell.clone()))
+            })
+            .collect()
+    }
+
+    /// Compute the area within a distance of any point of a trajectory.
+    ///
+    /// The result is the union of circles of `buffer_meters` around each
+    /// trajectory point. Circles are drawn with 32 segments in a local
+    /// flat projection around the trajectory, which is accurate for
+    /// trajectories spanning up to a few hundred kilometers away from the
+    /// poles and the antimeridian. Timestamps are ignored. Where the
+    /// circles enclose an area they do not cover, the polygon has a hole.
+    ///
+    /// # Arguments
+    ///
+    /// * `trajectory` - Points and timestamps, as passed to
+    ///   `DB::insert_trajectory`
+    /// * `buffer_meters` - Circle radius in meters
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InsufficientPoints` for an empty trajectory,
+    /// `SpatioError::OutOfRange` for an invalid coordinate, and
+    /// `SpatioError::Other` if `buffer_meters` is not positive or the
+    /// circles do not form a single connected area, which happens when
+    /// consecutive points are more than `2 * buffer_meters` apart.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, Point, SpatialAnalysis};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let route = [
+    ///     (Point::new(40.7000, -74.0000), 1640995200),
+    ///     (Point::new(40.7010, -74.0000), 1640995260),
+    ///     (Point::new(40.7020, -74.0000), 1640995320),
+    /// ];
+    /// let corridor = SpatialAnalysis::buffer_trajectory(&route, 100.0)?;
+    ///
+    /// assert!(corridor.contains_point(&Coordinate::new(-74.0005, 40.7015)));
+    /// assert!(!corridor.contains_point(&Coordinate::new(-74.0030, 40.7015)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn buffer_trajectory(trajectory: &[(Point, u64)], buffer_meters: f64) -> Result<Polygon> {
+        if !(buffer_meters > 0.0 && buffer_meters.is_finite()) {
+            return Err(SpatioError::Other(format!(
+                "Trajectory buffer must be a positive distance, got {}",
+                buffer_meters
+            )));
+        }
+        for (point, _) in trajectory {
+            Point::try_new(point.lat, point.lon)?;
+        }
+        let Some((first, _)) = trajectory.first() else {
+            return Err(SpatioError::InsufficientPoints);
+        };
+
+        let projection = LocalProjection::new(trajectory, first);
+        let mut centers: Vec<(f64, f64)> = Vec::with_capacity(trajectory.len());
+        let mut seen = FxHashSet::default();
+        for (point, _) in trajectory {
+            if seen.insert(*point) {
+                centers.push(projection.forward(point));
+            }
+        }
+
+        let arcs = uncovered_arcs(&centers, buffer_meters);
+        let loops = chain_arcs(&centers, buffer_meters, &arcs)?;
+
+        let mut exterior = None;
+        let mut holes = Vec::new();
+        for ring in loops {
+            let coords: Vec<Coordinate> = ring.iter().map(|p| projection.inverse(*p)).collect();
+            let ring = LinearRing::new(coords)?;
+            // Holes are traced clockwise, the outline counter-clockwise
+            if ring.is_clockwise() {
+                holes.push(ring);
+            } else if exterior.replace(ring).is_some() {
+                return Err(SpatioError::Other(
+                    "Trajectory buffer is not connected; points are more than twice the buffer apart"
+                        .to_string(),
+                ));
+            }
+        }
+        let exterior = exterior.ok_or(SpatioError::InsufficientPoints)?;
+        Ok(Polygon::new(exterior, holes))
+    }
+
+    /// Compute the discrete Fréchet distance between two paths in meters.
+    ///
+    /// This is the shortest leash that lets two walkers cover the paths
+    /// from start to end, each moving forward one point at a time, with
+    /// distances measured by Haversine. Unlike comparing point by point it
+    /// respects the order of the points, so a path and its reverse are far
+    /// apart. Takes time proportional to the product of the path lengths.
+    ///
+    /// # Returns
+    ///
+    /// The distance in meters, or infinity if either path is empty
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Point, SpatialAnalysis};
+    ///
+    /// let a = [Point::new(40.0, -74.0), Point::new(40.0, -73.99)];
+    /// let b = [Point::new(40.001, -74.0), Point::new(40.001, -73.99)];
+    ///
+    /// let distance = SpatialAnalysis::discrete_frechet_distance(&a, &b);
+    /// assert!((distance - 111.2).abs() < 1.0);
+    /// ```
+    pub fn discrete_frechet_distance(a: &[Point], b: &[Point]) -> f64 {
+        if a.is_empty() || b.is_empty() {
+            return f64::INFINITY;
+        }
+
+        // Leash lengths for the previous point of `a` against each point
+        // of `b`, replaced row by row
+        let mut previous: Vec<f64> = Vec::with_capacity(b.len());
+        for (j, point) in b.iter().enumerate() {
+            let d = a[0].distance_to(point);
+            previous.push(if j == 0 { d } else { d.max(previous[j - 1]) });
+        }
+        let mut current = vec![0.0; b.len()];
+        for point_a in &a[1..] {
+            for (j, point_b) in b.iter().enumerate() {
+                let reach = if j == 0 {
+                    previous[0]
+                } else {
+                    previous[j].min(previous[j - 1]).min(current[j - 1])
+                };
+                current[j] = point_a.distance_to(point_b).max(reach);
+            }
+            std::mem::swap(&mut previous, &mut current);
+        }
+        previous[b.len() - 1]
+    }
+
+    /// Resample a trajectory to points evenly spaced along its path.
+    ///
+    /// Timestamps are ignored. The first and last points are kept, so
+    /// trajectories of different lengths and sampling rates can be
+    /// compared point for point.
+    ///
+    /// # Returns
+    ///
+    /// `num_points` points, or fewer if the trajectory has no length; empty
+    /// for an empty trajectory
+    pub fn resample_trajectory(trajectory: &[(Point, u64)], num_points: usize) -> Vec<Point> {
+        let coords: Vec<Coordinate> = trajectory
+            .iter()
+            .map(|(point, _)| Coordinate::from_point(point))
+            .collect();
+        let length_m = match LineString::new(coords.clone()) {
+            Ok(line) => line.geodesic_length_km() * 1000.0,
+            Err(_) => 0.0,
+        };
+        if num_points < 2 || length_m <= 0.0 {
+            return trajectory
+                .first()
+                .map(|(p, _)| vec![*p])
+                .unwrap_or_default();
+        }
+
+        let line = LineString::new(coords).expect("a line with length has two points");
+        // Shrink the interval a little so rounding cannot add a point
+        // just short of the end
+        let interval = length_m / (num_points - 1) as f64 * (1.0 + 1e-9);
+        line.resample_geographic(interval)
+            .unwrap_or_else(|_| vec![trajectory[0].0])
+    }
+}
+
+/// Group items by density, as DBSCAN does, given the distance between
+/// every pair.
+///
+/// Items with at least `min_samples` items, themselves included, closer
+/// than `eps` are core items; a cluster is the core items reachable from
+/// one another through such neighbors, plus the items near them. Items in
+/// no cluster are left out. Clusters are ordered by their first item, and
+/// members are in index order.
+pub(crate) fn density_clusters(
+    distances: &[Vec<f64>],
+    eps: f64,
+    min_samples: usize,
+) -> Vec<Vec<usize>> {
+    let n = distances.len();
+    let neighbors =
+        |i: usize| -> Vec<usize> { (0..n).filter(|&j| distances[i][j] < eps).collect() };
+
+    let mut cluster_of: Vec<Option<usize>> = vec![None; n];
+    let mut clusters: Vec<Vec<usize>> = Vec::new();
+    for start in 0..n {
+        if cluster_of[start].is_some() || neighbors(start).len() < min_samples {
+            continue;
+        }
+        let id = clusters.len();
+        let mut members = Vec::new();
+        let mut queue = vec![start];
+        cluster_of[start] = Some(id);
+        while let Some(i) = queue.pop() {
+            members.push(i);
+            let near = neighbors(i);
+            if near.len() < min_samples {
+                // Border items join but do not extend the cluster
+                continue;
+            }
+            for j in near {
+                if cluster_of[j].is_none() {
+                    cluster_of[j] = Some(id);
+                    queue.push(j);
+                }
+            }
+        }
+        members.sort_unstable();
+        clusters.push(members);
+    }
+    clusters
+}
+
+/// Built-in aggregators for `DB::aggregate_spatial`.
+///
+/// Each returns a closure folding one point at a time into an
+/// accumulator that starts from its `Default` value.
+///
+/// # Examples
+///
+/// ```rust
+/// use spatio::{BoundingBox, Point, Spatio, SpatialAggregator};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let db = Spatio::memory()?;
+/// db.insert_point("sensors", &Point::new(40.71, -74.00), b"20.5", None)?;
+/// db.insert_point("sensors", &Point::new(40.75, -73.98), b"23.5", None)?;
+///
+/// let nyc = BoundingBox::new(40.5, -74.3, 40.9, -73.7);
+/// let temperature = |data: &[u8]| std::str::from_utf8(data).ok()?.parse().ok();
+/// let average = db.aggregate_spatial("sensors", &nyc, SpatialAggregator::average(temperature))?;
+/// assert_eq!(average.value(), Some(22.0));
+/// # Ok(())
+/// # }
+/// ```
+pub struct SpatialAggregator;
+
+impl SpatialAggregator {
+    /// Count the points
+    pub fn count() -> impl FnMut(usize, &Point, &[u8]) -> usize {
+        |count, _, _| count + 1
+    }
+
+    /// Sum a field of each point's data.
+    ///
+    /// Points for which `field_extractor` returns `None` are skipped.
+    pub fn sum(
+        field_extractor: impl Fn(&[u8]) -> Option<f64>,
+    ) -> impl FnMut(f64, &Point, &[u8]) -> f64 {
+        move |sum, _, data| sum + field_extractor(data).unwrap_or(0.0)
+    }
+
+    /// Average a field of each point's data.
+    ///
+    /// Points for which `field_extractor` returns `None` are skipped and
+    /// do not count towards the average.
+    pub fn average(
+        field_extractor: impl Fn(&[u8]) -> Option<f64>,
+    ) -> impl FnMut(Average, &Point, &[u8]) -> Average {
+        move |mut average, _, data| {
+            if let Some(value) = field_extractor(data) {
+                average.sum += value;
+                average.count += 1;
+            }
+            average
+        }
+    }
+}
+
+/// Running average built by `SpatialAggregator::average`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Average {
+    /// Sum of the values seen
+    pub sum: f64,
+    /// Number of values seen
+    pub count: usize,
+}
+
+impl Average {
+    /// The average of the values seen, or `None` if there were none
+    pub fn value(&self) -> Option<f64> {
+        (self.count > 0).then(|| self.sum / self.count as f64)
+    }
+}
+
+/// Settings for smoothing GPS noise out of a trajectory, used by
+/// `DB::smooth_trajectory`.
+///
+/// The Kalman filter models the position as a random walk: between two
+/// points the variance of the true position grows by
+/// `process_noise_variance` per second elapsed, and each recorded point is
+/// the true position plus noise of variance `measurement_noise_variance`.
+/// Latitude and longitude are filtered independently. Only the ratio of
+/// the two variances affects the result, so they can be given in any
+/// consistent unit, such as square meters.
+///
+/// # Examples
+///
+/// ```rust
+/// use spatio::{Point, TrajectorySmoothing};
+///
+/// let noisy = [
+///     (Point::new(40.00000, -74.0), 0),
+///     (Point::new(40.00010, -74.0), 10),
+///     (Point::new(39.99990, -74.0), 20),
+/// ];
+/// let smoothed = TrajectorySmoothing::kalman(0.1, 25.0).smooth(&noisy);
+///
+/// // The jitter is damped towards the earlier estimates
+/// assert!((smoothed[2].0.lat - 40.0).abs() < (noisy[2].0.lat - 40.0).abs());
+/// assert_eq!(smoothed[2].1, 20);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrajectorySmoothing {
+    /// Growth of the position variance per second between points
+    pub process_noise_variance: f64,
+    /// Variance of the error in each recorded point
+    pub measurement_noise_variance: f64,
+}
+
+impl TrajectorySmoothing {
+    /// Kalman filter settings with the given process and measurement noise
+    /// variances
+    pub fn kalman(process_noise: f64, measurement_noise: f64) -> Self {
+        Self {
+            process_noise_variance: process_noise,
+            measurement_noise_variance: measurement_noise,
+        }
+    }
+
+    /// Check the variances can drive a filter
+    pub fn validate(&self) -> Result<()> {
+        if !(self.process_noise_variance >= 0.0 && self.process_noise_variance.is_finite()) {
+            return Err(SpatioError::InvalidConfiguration(format!(
+                "Process noise variance must be a non-negative number, got {}",
+                self.process_noise_variance
+            )));
+        }
+        if !(self.measurement_noise_variance > 0.0 && self.measurement_noise_variance.is_finite()) {
+            return Err(SpatioError::InvalidConfiguration(format!(
+                "Measurement noise variance must be a positive number, got {}",
+                self.measurement_noise_variance
+            )));
+        }
+        Ok(())
+    }
+
+    /// Run the filter over a trajectory ordered by timestamp.
+    ///
+    /// Returns the estimated position at each point, with its timestamp.
+    /// The first estimate is the first point itself.
+    pub fn smooth(&self, trajectory: &[(Point, u64)]) -> Vec<(Point, u64)> {
+        let (q, r) = (self.process_noise_variance, self.measurement_noise_variance);
+        let mut estimate: Option<(f64, f64, u64)> = None;
+        // Variance of the estimate, the same for both axes
+        let mut variance = r;
+
+        trajectory
+            .iter()
+            .map(|&(point, timestamp)| {
+                let (lat, lon) = match estimate {
+                    None => (point.lat, point.lon),
+                    Some((lat, lon, previous)) => {
+                        let elapsed = timestamp.saturating_sub(previous) as f64;
+                        let predicted = variance + q * elapsed;
+                        let gain = predicted / (predicted + r);
+                        variance = (1.0 - gain) * predicted;
+                        (
+                            lat + gain * (point.lat - lat),
+                            lon + gain * (point.lon - lon),
+                        )
+                    }
+                };
+                estimate = Some((lat, lon, timestamp));
+                (Point::new(lat, lon), timestamp)
+            })
+            .collect()
+    }
+}
+
+/// Equirectangular projection to meters around a trajectory's mean
+/// latitude and first longitude
+struct LocalProjection {
+    lat0: f64,
+    lon0: f64,
+    /// Meters per degree of latitude, and of longitude at `lat0`
+    lat_scale: f64,
+    lon_scale: f64,
+}
+
+impl LocalProjection {
+    fn new(trajectory: &[(Point, u64)], first: &Point) -> Self {
+        const EARTH_RADIUS_M: f64 = 6_371_000.0;
+        let lat0 = trajectory.iter().map(|(p, _)| p.lat).sum::<f64>() / trajectory.len() as f64;
+        let lat_scale = 1f64.to_radians() * EARTH_RADIUS_M;
+        Self {
+            lat0,
+            lon0: first.lon,
+            lat_scale,
+            lon_scale: lat_scale * lat0.to_radians().cos(),
+        }
+    }
+
+    fn forward(&self, point: &Point) -> (f64, f64) {
+        (
+            (point.lon - self.lon0) * self.lon_scale,
+            (point.lat - self.lat0) * self.lat_scale,
+        )
+    }
+
+    fn inverse(&self, (x, y): (f64, f64)) -> Coordinate {
+        Coordinate::new(
+            self.lon0 + x / self.lon_scale,
+            self.lat0 + y / self.lat_scale,
+        )
+    }
+}
+
+/// An arc of circle `circle` from angle `start` counter-clockwise to
+/// `end`, in radians
+#[derive(Clone, Copy)]
+struct BoundaryArc {
+    circle: usize,
+    start: f64,
+    end: f64,
+}
+
+/// The arcs of each circle not covered by any other circle, which make up
+/// the boundary of the circles' union
+fn uncovered_arcs(centers: &[(f64, f64)], radius: f64) -> Vec<BoundaryArc> {
+    // Only circles in neighboring grid cells can overlap
+    let cell_of = |(x, y): (f64, f64)| {
+        (
+            (x / (2.0 * radius)).floor() as i64,
+            (y / (2.0 * radius)).floor() as i64,
+        )
+    };
+    let mut grid: FxHashMap<(i64, i64), Vec<usize>> = FxHashMap::default();
+    for (i, center) in centers.iter().enumerate() {
+        grid.entry(cell_of(*center)).or_default().push(i);
+    }
+
+    let mut arcs = Vec::new();
+    for (i, &(x, y)) in centers.iter().enumerate() {
+        let (cx, cy) = cell_of((x, y));
+        let mut covered: Vec<(f64, f64)> = Vec::new();
+        for gx in cx - 1..=cx + 1 {
+            for gy in cy - 1..=cy + 1 {
+                for &j in grid.get(&(gx, gy)).into_iter().flatten() {
+                    let (dx, dy) = (centers[j].0 - x, centers[j].1 - y);
+                    let distance = dx.hypot(dy);
+                    if j == i || distance >= 2.0 * radius {
+                        continue;
+                    }
+                    let direction = dy.atan2(dx).rem_euclid(TAU);
+                    let half = (distance / (2.0 * radius)).acos();
+                    let (from, to) = (direction - half, direction + half);
+                    // Split intervals that wrap past angle zero
+                    if from < 0.0 {
+                        covered.push((from + TAU, TAU));
+                        covered.push((0.0, to));
+                    } else if to > TAU {
+                        covered.push((from, TAU));
+                        covered.push((0.0, to - TAU));
+                    } else {
+                        covered.push((from, to));
+                    }
+                }
+            }
+        }
+
+        if covered.is_empty() {
+            arcs.push(BoundaryArc {
+                circle: i,
+                start: 0.0,
+                end: TAU,
+            });
+            continue;
+        }
+
+        // The gaps between merged covered intervals, joined across zero
+        covered.sort_by(|a, b| a.0.total_cmp(&b.0));
+        let mut gaps = Vec::new();
+        let mut reach = covered[0].1;
+        for &(from, to) in &covered[1..] {
+            if from > reach {
+                gaps.push((reach, from));
+            }
+            reach = reach.max(to);
+        }
+        let first = covered[0].0;
+        if reach < TAU || first > 0.0 {
+            gaps.push((reach, first + TAU));
+        }
+        arcs.extend(gaps.into_iter().map(|(start, end)| BoundaryArc {
+            circle: i,
+            start,
+            end,
+        }));
+    }
+    arcs
+}
+
+/// Join boundary arcs end to start into closed rings of projected points
+fn chain_arcs(
+    centers: &[(f64, f64)],
+    radius: f64,
+    arcs: &[BoundaryArc],
+) -> Result<Vec<Vec<(f64, f64)>>> {
+    let at = |circle: usize, angle: f64| {
+        let (x, y) = centers[circle];
+        (x + radius * angle.cos(), y + radius * angle.sin())
+    };
+
+    // Arcs by where they start, on a grid fine enough to tell apart
+    // distinct intersection points
+    let quantum = radius * 1e-6;
+    let key = |(x, y): (f64, f64)| ((x / quantum).round() as i64, (y / quantum).round() as i64);
+    let mut starts: FxHashMap<(i64, i64), Vec<usize>> = FxHashMap::default();
+    for (i, arc) in arcs.iter().enumerate() {
+        starts
+            .entry(key(at(arc.circle, arc.start)))
+            .or_default()
+            .push(i);
+    }
+
+    let mut used = vec![false; arcs.len()];
+    let mut rings = Vec::new();
+    for first in 0..arcs.len() {
+        if used[first] {
+            continue;
+        }
+        let mut ring = Vec::new();
+        let mut current = first;
+        loop {
+            used[current] = true;
+            let arc = arcs[current];
+            let sweep = arc.end - arc.start;
+            let segments = ((sweep / TAU * BUFFER_CIRCLE_SEGMENTS as f64).ceil() as usize).max(1);
+            for k in 0..segments {
+                let angle = arc.start + sweep * k as f64 / segments as f64;
+                ring.push(at(arc.circle, angle));
+            }
+
+            let end = at(arc.circle, arc.end);
+            let (kx, ky) = key(end);
+            let next = (kx - 1..=kx + 1)
+                .flat_map(|gx| (ky - 1..=ky + 1).map(move |gy| (gx, gy)))
+                .filter_map(|cell| starts.get(&cell))
+                .flatten()
+                .copied()
+                .filter(|&j| j == first || !used[j])
+                .min_by(|&a, &b| {
+                    let distance = |j: usize| {
+                        let (x, y) = at(arcs[j].circle, arcs[j].start);
+                        (x - end.0).hypot(y - end.1)
+                    };
+                    distance(a).total_cmp(&distance(b))
+                });
+            match next {
+                Some(j) if j == first => break,
+                Some(j) => current = j,
+                None => {
+                    return Err(SpatioError::Other(
+                        "Could not trace the trajectory buffer boundary".to_string(),
+                    ));
+                }
+            }
+        }
+        rings.push(ring);
+    }
+    Ok(rings)
+}
+
+/// Keep the part of a convex ring closer to `site` than to `other`
+fn clip_half_plane(ring: &[Coordinate], site: &Coordinate, other: &Coordinate) -> Vec<Coordinate> {
+    let (nx, ny) = (other.x - site.x, other.y - site.y);
+    let (mx, my) = ((site.x + other.x) / 2.0, (site.y + other.y) / 2.0);
+    let side = |c: &Coordinate| (c.x - mx) * nx + (c.y - my) * ny;
+
+    let mut clipped = Vec::with_capacity(ring.len() + 1);
+    for (i, current) in ring.iter().enumerate() {
+        let next = &ring[(i + 1) % ring.len()];
+        let (side_current, side_next) = (side(current), side(next));
+        if side_current <= 0.0 {
+            clipped.push(*current);
+        }
+        if (side_current < 0.0 && side_next > 0.0) || (side_current > 0.0 && side_next < 0.0) {
+            let t = side_current / (side_current - side_next);
+            clipped.push(Coordinate::new(
+                current.x + t * (next.x - current.x),
+                current.y + t * (next.y - current.y),
+            ));
+        }
+    }
+    clipped
+}
+
+/// An arc of the beach line, belonging to one site. Ids tell apart the
+/// arcs a site's parabola is split into.
+#[derive(Clone, Copy)]
+struct Arc {
+    site: usize,
+    id: u64,
+}
+
+/// The sweep line reaching the top of the circle through three sites,
+/// where the middle arc disappears
+struct CircleEvent {
+    y: f64,
+    arc: u64,
+    left: u64,
+    right: u64,
+}
+
+impl PartialEq for CircleEvent {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for CircleEvent {}
+
+impl PartialOrd for CircleEvent {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for CircleEvent {
+    // Reversed so the binary heap pops the lowest event first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .y
+            .total_cmp(&self.y)
+            .then_with(|| other.arc.cmp(&self.arc))
+    }
+}
+
+/// Fortune's sweep line, tracking only which sites become adjacent on the
+/// beach line. Adjacent arcs are separated by a Voronoi edge, so those
+/// pairs are the Voronoi neighbors of each site.
+///
+/// The line sweeps upwards in y. Events are invalidated lazily: a circle
+/// event only fires if its arc is still on the beach line between the same
+/// two arcs it was scheduled with.
+struct Sweep<'a> {
+    sites: &'a [Coordinate],
+    arcs: Vec<Arc>,
+    events: BinaryHeap<CircleEvent>,
+    next_id: u64,
+    neighbors: Vec<BTreeSet<usize>>,
+}
+
+impl<'a> Sweep<'a> {
+    fn new(sites: &'a [Coordinate]) -> Self {
+        Self {
+            sites,
+            arcs: Vec::new(),
+            events: BinaryHeap::new(),
+            next_id: 0,
+            neighbors: vec![BTreeSet::new(); sites.len()],
+        }
+    }
+
+    fn run(mut self) -> Vec<BTreeSet<usize>> {
+        let mut order: Vec<usize> = (0..self.sites.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (a, b) = (&self.sites[a], &self.sites[b]);
+            a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x))
+        });
+
+        let mut order = order.into_iter().peekable();
+        loop {
+            let next_site = order.peek().map(|&s| self.sites[s].y);
+            match (self.events.peek(), next_site) {
+                (Some(event), Some(y)) if event.y <= y => self.circle_event(),
+                (Some(_), None) => self.circle_event(),
+                (_, Some(_)) => {
+                    if let Some(site) = order.next() {
+                        self.site_event(site);
+                    }
+                }
+                (None, None) => break,
+            }
+        }
+        self.neighbors
+    }
+
+    fn arc(&mut self, site: usize) -> Arc {
+        self.next_id += 1;
+        Arc {
+            site,
+            id: self.next_id,
+        }
+    }
+
+    fn link(&mut self, a: usize, b: usize) {
+        if a != b {
+            self.neighbors[a].insert(b);
+            self.neighbors[b].insert(a);
+        }
+    }
+
+    fn site_event(&mut self, site: usize) {
+        let new_arc = self.arc(site);
+        if self.arcs.is_empty() {
+            self.arcs.push(new_arc);
+            return;
+        }
+
+        let s = self.sites[site];
+        let i = (0..self.arcs.len())
+            .find(|&i| {
+                i + 1 == self.arcs.len()
+                    || s.x < self.breakpoint(self.arcs[i].site, self.arcs[i + 1].site, s.y)
+            })
+            .unwrap_or(0);
+        let above = self.arcs[i];
+        self.link(above.site, site);
+
+        let at = if self.sites[above.site].y == s.y {
+            // Sites on the first row have no parabola yet, so the new arc
+            // sits beside the one found rather than splitting it
+            let at = if s.x > self.sites[above.site].x {
+                i + 1
+            } else {
+                i
+            };
+            self.arcs.insert(at, new_arc);
+            at
+        } else {
+            let right = self.arc(above.site);
+            self.arcs.splice(i + 1..i + 1, [new_arc, right]);
+            i + 1
+        };
+
+        for middle in at.saturating_sub(2)..=at + 2 {
+            self.schedule(middle);
+        }
+    }
+
+    fn circle_event(&mut self) {
+        let Some(event) = self.events.pop() else {
+            return;
+        };
+        let Some(j) = self.arcs.iter().position(|arc| arc.id == event.arc) else {
+            return;
+        };
+        if j == 0
+            || j + 1 >= self.arcs.len()
+            || self.arcs[j - 1].id != event.left
+            || self.arcs[j + 1].id != event.right
+        {
+            return;
+        }
+
+        self.arcs.remove(j);
+        self.link(self.arcs[j - 1].site, self.arcs[j].site);
+        self.schedule(j - 1);
+        self.schedule(j);
+    }
+
+    /// Schedule the circle event of the arc at `middle`, if its two
+    /// breakpoints are converging
+    fn schedule(&mut self, middle: usize) {
+        if middle == 0 || middle + 1 >= self.arcs.len() {
+            return;
+        }
+        let (left, arc, right) = (
+            self.arcs[middle - 1],
+            self.arcs[middle],
+            self.arcs[middle + 1],
+        );
+        if left.site == right.site {
+            return;
+        }
+
+        let (a, b, c) = (
+            self.sites[left.site],
+            self.sites[arc.site],
+            self.sites[right.site],
+        );
+        let turn = (b.x - a.x) * (c.y - b.y) - (b.y - a.y) * (c.x - b.x);
+        if turn <= 0.0 {
+            return;
+        }
+
+        let d = 2.0 * (a.x * (b.y - c.y) + b.x * (c.y - a.y) + c.x * (a.y - b.y));
+        let (a2, b2, c2) = (
+            a.x * a.x + a.y * a.y,
+            b.x * b.x + b.y * b.y,
+            c.x * c.x + c.y * c.y,
+        );
+        let cx = (a2 * (b.y - c.y) + b2 * (c.y - a.y) + c2 * (a.y - b.y)) / d;
+        let cy = (a2 * (c.x - b.x) + b2 * (a.x - c.x) + c2 * (b.x - a.x)) / d;
+        let radius = (a.x - cx).hypot(a.y - cy);
+
+        self.events.push(CircleEvent {
+            y: cy + radius,
+            arc: arc.id,
+            left: left.id,
+            right: right.id,
+        });
+    }
+
+    /// X coordinate where the arc of `left` gives way to the arc of `right`
+    /// with the sweep line at `sweep_y`
+    fn breakpoint(&self, left: usize, right: usize, sweep_y: f64) -> f64 {
+        let (p, q) = (self.sites[left], self.sites[right]);
+        let (dp, dq) = (2.0 * (p.y - sweep_y), 2.0 * (q.y - sweep_y));
+        if dp == 0.0 && dq == 0.0 {
+            return (p.x + q.x) / 2.0;
+        }
+        if dp == 0.0 {
+            return p.x;
+        }
+        if dq == 0.0 {
+            return q.x;
+        }
+
+        // Solve parabola(p) - parabola(q) = 0, taking the root where the
+        // difference falls as x grows: left of it p is nearer the sweep line
+        let a = 1.0 / dp - 1.0 / dq;
+        let b = -2.0 * (p.x / dp - q.x / dq);
+        let c = (p.x * p.x + p.y * p.y - sweep_y * sweep_y) / dp
+            - (q.x * q.x + q.y * q.y - sweep_y * sweep_y) / dq;
+        if a.abs() < f64::EPSILON * (1.0 / dp.abs() + 1.0 / dq.abs()) {
+            return -c / b;
+        }
+        let root = (b * b - 4.0 * a * c).max(0.0).sqrt();
+        (-b - root) / (2.0 * a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cell of `site` by clipping against every other site
+    fn brute_force_area(points: &[Point], site: usize, bbox: &BoundingBox) -> f64 {
+        let frame = vec![
+            Coordinate::new(bbox.min.lon, bbox.min.lat),
+            Coordinate::new(bbox.max.lon, bbox.min.lat),
+            Coordinate::new(bbox.max.lon, bbox.max.lat),
+            Coordinate::new(bbox.min.lon, bbox.max.lat),
+        ];
+        let own = Coordinate::from_point(&points[site]);
+        let ring = points
+            .iter()
+            .filter(|p| **p != points[site])
+            .fold(frame, |ring, other| {
+                clip_half_plane(&ring, &own, &Coordinate::from_point(other))
+            });
+        LinearRing::new(ring).map(|r| r.area()).unwrap_or(0.0)
+    }
+
+    fn assert_matches_brute_force(points: &[Point], bbox: &BoundingBox) {
+        let cells = SpatialAnalysis::voronoi_cells(points, bbox);
+        let total: f64 = cells.iter().map(|(_, cell)| cell.area()).sum();
+        let box_area = (bbox.max.lat - bbox.min.lat) * (bbox.max.lon - bbox.min.lon);
+        assert!((total - box_area).abs() < 1e-6, "{} != {}", total, box_area);
+
+        for (point, cell) in &cells {
+            let site = points.iter().position(|p| p == point).unwrap();
+            let expected = brute_force_area(points, site, bbox);
+            assert!(
+                (cell.area() - expected).abs() < 1e-9,
+                "cell of {} has area {}, expected {}",
+                point,
+                cell.area(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_voronoi_random_points() {
+        // Small linear congruential generator to keep the test deterministic
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = || {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (state >> 11) as f64 / (1u64 << 53) as f64
+        };
+
+        let bbox = BoundingBox::new(0.0, 0.0, 10.0, 10.0);
+        for size in [1, 2, 3, 10, 50] {
+            let points: Vec<Point> = (0..size)
+                .map(|_| Point::new(next() * 10.0, next() * 10.0))
+                .collect();
+            assert_matches_brute_force(&points, &bbox);
+        }
+    }
+
+    #[test]
+    fn test_voronoi_degenerate_layouts() {
+        let bbox = BoundingBox::new(-1.0, -1.0, 5.0, 5.0);
+
+        // Cocircular sites on a grid, collinear rows and columns
+        let grid: Vec<Point> = (0..4)
+            .flat_map(|lat| (0..4).map(move |lon| Point::new(lat as f64, lon as f64)))
+            .collect();
+        assert_matches_brute_force(&grid, &bbox);
+
+        let row: Vec<Point> = (0..4).map(|lon| Point::new(2.0, lon as f64)).collect();
+        assert_matches_brute_force(&row, &bbox);
+
+        let column: Vec<Point> = (0..4).map(|lat| Point::new(lat as f64, 2.0)).collect();
+        assert_matches_brute_force(&column, &bbox);
+    }
+
+    #[test]
+    fn test_voronoi_skips_and_shares_cells() {
+        let bbox = BoundingBox::new(0.0, 0.0, 2.0, 2.0);
+        let points = [
+            Point::new(1.0, 0.5),
+            Point::new(f64::NAN, 1.0),
+            Point::new(1.0, 1.5),
+            Point::new(1.0, 0.5),
+            // Far enough away that its cell misses the box
+            Point::new(1.0, 10.0),
+        ];
+
+        let cells = SpatialAnalysis::voronoi_cells(&points, &bbox);
+        assert_eq!(cells.len(), 3);
+        assert_eq!(cells[0].0, points[0]);
+        assert_eq!(cells[1].0, points[2]);
+        assert_eq!(cells[2].0, points[3]);
+        assert_eq!(cells[0].1, cells[2].1);
+        assert!((cells[0].1.area() - 2.0).abs() < 1e-9);
+
+        assert!(SpatialAnalysis::voronoi_cells(&[], &bbox).is_empty());
+    }
+
+    #[test]
+    fn test_buffer_trajectory() {
+        let at = |lat: f64, lon: f64| (Point::new(lat, lon), 0);
+
+        // A single point gives a circle
+        let circle = SpatialAnalysis::buffer_trajectory(&[at(40.0, -74.0)], 1000.0).unwrap();
+        let expected = std::f64::consts::PI * 1.0;
+        assert!((circle.geodesic_area_km2() - expected).abs() / expected < 0.02);
+        assert!(circle.holes().is_empty());
+
+        // Points on a 2 km circle, 100 m buffer: a ring with a hole
+        let lat_per_m = 1.0 / 111_195.0;
+        let lon_per_m = lat_per_m / 40f64.to_radians().cos();
+        let loop_points: Vec<(Point, u64)> = (0..200)
+            .map(|i| {
+                let angle = TAU * i as f64 / 200.0;
+                at(
+                    40.0 + 2000.0 * angle.sin() * lat_per_m,
+                    -74.0 + 2000.0 * angle.cos() * lon_per_m,
+                )
+            })
+            .collect();
+        let ring = SpatialAnalysis::buffer_trajectory(&loop_points, 100.0).unwrap();
+        assert_eq!(ring.holes().len(), 1);
+        assert!(!ring.contains_point(&Coordinate::new(-74.0, 40.0)));
+        assert!(ring.contains_point(&Coordinate::new(-74.0 + 2000.0 * lon_per_m, 40.0)));
+
+        // Points farther apart than twice the buffer do not connect
+        let gap = [at(40.0, -74.0), at(40.1, -74.0)];
+        assert!(matches!(
+            SpatialAnalysis::buffer_trajectory(&gap, 100.0),
+            Err(SpatioError::Other(_))
+        ));
+        assert!(matches!(
+            SpatialAnalysis::buffer_trajectory(&[], 100.0),
+            Err(SpatioError::InsufficientPoints)
+        ));
+        assert!(SpatialAnalysis::buffer_trajectory(&[at(40.0, -74.0)], 0.0).is_err());
+        assert!(matches!(
+            SpatialAnalysis::buffer_trajectory(&[at(95.0, -74.0)], 100.0),
+            Err(SpatioError::OutOfRange(_))
+        ));
+    }
+
+    #[test]
+    fn test_discrete_frechet_distance() {
+        let line = |lat: f64, n: usize| -> Vec<Point> {
+            (0..n)
+                .map(|i| Point::new(lat, -74.0 + 0.01 * i as f64))
+                .collect()
+        };
+        let a = line(40.0, 5);
+        assert_eq!(SpatialAnalysis::discrete_frechet_distance(&a, &a), 0.0);
+
+        // A parallel path is as far as the gap between them
+        let b = line(40.001, 5);
+        let gap = a[0].distance_to(&b[0]);
+        assert!((SpatialAnalysis::discrete_frechet_distance(&a, &b) - gap).abs() < 1e-6);
+
+        // Walking the same path backwards needs a leash its full length
+        let reversed: Vec<Point> = a.iter().rev().copied().collect();
+        let length = a[0].distance_to(&a[4]);
+        assert!((SpatialAnalysis::discrete_frechet_distance(&a, &reversed) - length).abs() < 1e-6);
+        assert!(SpatialAnalysis::discrete_frechet_distance(&a, &[]).is_infinite());
+
+        let track: Vec<(Point, u64)> = a.iter().map(|p| (*p, 0)).collect();
+        let resampled = SpatialAnalysis::resample_trajectory(&track, 9);
+        assert_eq!(resampled.len(), 9);
+        assert_eq!(resampled[0], a[0]);
+        assert!(resampled[8].distance_to(&a[4]) < 1e-6);
+        assert_eq!(
+            SpatialAnalysis::resample_trajectory(&track[..1], 9).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_density_clusters() {
+        // Items on a line: a chain 0-1-2, a pair 4-5 and a loner 3
+        let positions = [0.0, 1.0, 2.0, 10.0, 20.0, 21.0];
+        let distances: Vec<Vec<f64>> = positions
+            .iter()
+            .map(|a| positions.iter().map(|b| f64::abs(a - b)).collect())
+            .collect();
+
+        assert_eq!(
+            density_clusters(&distances, 1.5, 2),
+            vec![vec![0, 1, 2], vec![4, 5]]
+        );
+        // The chain's ends are border items reached through its middle
+        assert_eq!(density_clusters(&distances, 1.5, 3), vec![vec![0, 1, 2]]);
+        assert_eq!(
+            density_clusters(&distances, 1.5, 4),
+            Vec::<Vec<usize>>::new()
+        );
+    }
+}
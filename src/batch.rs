@@ -1,7 +1,12 @@
 use crate::DB;
-use crate::error::Result;
+use crate::db::DBInner;
+use crate::error::{Result, SpatioError};
+use crate::spatial::{Point, SpatialKey};
+use crate::sync::RwLockWriteGuard;
 use crate::types::SetOptions;
 use bytes::Bytes;
+use std::collections::BTreeMap;
+use std::time::Duration;
 
 /// Atomic batch for grouping multiple operations together.
 ///
@@ -29,29 +34,67 @@ use bytes::Bytes;
 /// ```
 pub struct AtomicBatch {
     db: DB,
-    operations: Vec<BatchOperation>,
+    /// Pending writes keyed by key; `None` marks a pending delete
+    pending: BTreeMap<Bytes, Option<PendingValue>>,
 }
 
 #[derive(Debug, Clone)]
-enum BatchOperation {
-    Insert {
-        key: Bytes,
-        value: Bytes,
-        opts: Option<SetOptions>,
-    },
-    Delete {
-        key: Bytes,
-    },
+struct PendingValue {
+    value: Bytes,
+    opts: Option<SetOptions>,
+    /// Spatial index entry (prefix and point) for point inserts
+    point: Option<(String, Point)>,
 }
 
 impl AtomicBatch {
     pub(crate) fn new(db: DB) -> Self {
         Self {
             db,
-            operations: Vec::new(),
+            pending: BTreeMap::new(),
         }
     }
 
+    /// Get a value by key, seeing writes made earlier in this batch.
+    ///
+    /// Pending inserts and deletes in the batch take precedence over the
+    /// committed database state, which allows read-modify-write patterns
+    /// inside a single atomic batch.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Spatio;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert("counter", b"1", None)?;
+    ///
+    /// db.atomic(|batch| {
+    ///     batch.insert("counter", b"2", None)?;
+    ///     assert_eq!(batch.get("counter")?.unwrap().as_ref(), b"2");
+    ///
+    ///     batch.delete("counter")?;
+    ///     assert!(batch.get("counter")?.is_none());
+    ///     Ok(())
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn get(&self, key: impl AsRef<[u8]>) -> Result<Option<Bytes>> {
+        match self.pending(key.as_ref()) {
+            Some(pending) => Ok(pending),
+            None => self.db.get(key),
+        }
+    }
+
+    /// The value this batch will leave under `key`, or `None` if the batch
+    /// does not write it. A pending delete is `Some(None)`.
+    pub(crate) fn pending(&self, key: &[u8]) -> Option<Option<Bytes>> {
+        self.pending
+            .get(key)
+            .map(|write| write.as_ref().map(|pending| pending.value.clone()))
+    }
+
     /// Insert a key-value pair in this batch.
     ///
     /// The operation will be queued and executed when the batch is committed.
@@ -87,12 +130,59 @@ impl AtomicBatch {
         value: impl AsRef<[u8]>,
         opts: Option<SetOptions>,
     ) -> Result<()> {
-        let op = BatchOperation::Insert {
-            key: Bytes::copy_from_slice(key.as_ref()),
+        let pending = PendingValue {
             value: Bytes::copy_from_slice(value.as_ref()),
             opts,
+            point: None,
         };
-        self.operations.push(op);
+        self.pending
+            .insert(Bytes::copy_from_slice(key.as_ref()), Some(pending));
+        Ok(())
+    }
+
+    /// Insert a geographic point in this batch.
+    ///
+    /// Works like `DB::insert_point`: the point is stored under its geohash
+    /// key and added to the spatial index when the batch is committed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Point, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    ///
+    /// db.atomic(|batch| {
+    ///     batch.insert_point("cities", &Point::new(40.7128, -74.0060), b"New York", None)?;
+    ///     batch.insert_point("cities", &Point::new(51.5074, -0.1278), b"London", None)?;
+    ///     Ok(())
+    /// })?;
+    ///
+    /// let nearby = db.find_nearby("cities", &Point::new(40.7, -74.0), 10_000.0, 10)?;
+    /// assert_eq!(nearby.len(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn insert_point(
+        &mut self,
+        prefix: &str,
+        point: &Point,
+        value: &[u8],
+        opts: Option<SetOptions>,
+    ) -> Result<()> {
+        let geohash = point
+            .to_geohash(8)
+            .map_err(|_| SpatioError::InvalidGeohash)?;
+        let key = SpatialKey::geohash(prefix, &geohash);
+
+        let pending = PendingValue {
+            value: Bytes::copy_from_slice(value),
+            opts,
+            point: Some((prefix.to_string(), *point)),
+        };
+        self.pending
+            .insert(Bytes::copy_from_slice(key.as_bytes()), Some(pending));
         Ok(())
     }
 
@@ -125,29 +215,67 @@ impl AtomicBatch {
     /// # }
     /// ```
     pub fn delete(&mut self, key: impl AsRef<[u8]>) -> Result<()> {
-        let op = BatchOperation::Delete {
-            key: Bytes::copy_from_slice(key.as_ref()),
-        };
-        self.operations.push(op);
+        self.pending
+            .insert(Bytes::copy_from_slice(key.as_ref()), None);
         Ok(())
     }
 
     /// Commit all operations in this batch atomically.
     ///
     /// This is called automatically when the batch closure returns successfully.
-    /// All operations are applied in the order they were added to the batch.
+    /// Only the last write for each key is applied, so a key that was inserted
+    /// and then deleted within the batch ends up deleted.
     pub(crate) fn commit(self) -> Result<()> {
-        // Apply all operations atomically
-        let mut inner = self.db.write()?;
+        let inner = self.db.write_mut()?;
+        self.apply(inner)
+    }
 
+    /// Commit like `commit`, failing with `SpatioError::LockTimeout` if the
+    /// write lock is not acquired within `timeout`
+    pub(crate) fn commit_with_timeout(self, timeout: Duration) -> Result<()> {
+        let inner = self.db.write_mut_with_timeout(timeout)?;
+        self.apply(inner)
+    }
+
+    /// Commit like `commit` if `check` passes under the write lock, so
+    /// nothing can change between the check and the writes
+    pub(crate) fn commit_if(self, check: impl FnOnce(&DBInner) -> Result<()>) -> Result<()> {
+        let inner = self.db.write_mut()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+        check(&inner)?;
+        self.apply(inner)
+    }
+
+    /// Commit operations queued by `build` from the state it reads under
+    /// the write lock, so no other write can land between the read and
+    /// the commit
+    pub(crate) fn commit_with<R>(
+        mut self,
+        build: impl FnOnce(&DBInner, &mut AtomicBatch) -> Result<R>,
+    ) -> Result<R> {
+        let db = self.db.clone();
+        let inner = db.write_mut()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+        let result = build(&inner, &mut self)?;
+        self.apply(inner)?;
+        Ok(result)
+    }
+
+    /// Apply all operations atomically under the held write lock
+    fn apply(&self, mut inner: RwLockWriteGuard<'_, DBInner>) -> Result<()> {
         // Check if database is closed
         if inner.closed {
-            return Err(crate::error::SpatioError::DatabaseClosed);
+            return Err(SpatioError::DatabaseClosed);
         }
 
-        for operation in &self.operations {
-            match operation {
-                BatchOperation::Insert { key, value, opts } => {
+        let mut inserted = 0;
+        for (key, write) in &self.pending {
+            match write {
+                Some(PendingValue { value, opts, point }) => {
                     let item = match opts {
                         Some(SetOptions { ttl: Some(ttl), .. }) => {
                             crate::types::DbItem::with_ttl(value.clone(), *ttl)
@@ -158,21 +286,28 @@ impl AtomicBatch {
                         }) => crate::types::DbItem::with_expiration(value.clone(), *expires_at),
                         _ => crate::types::DbItem::new(value.clone()),
                     };
+                    inner.make_room(key, value.len())?;
                     inner.insert_item(key.clone(), item);
+                    if let Some((prefix, point)) = point {
+                        inner.index_manager.insert_point(prefix, point, value)?;
+                    }
+                    inserted += 1;
                 }
-                BatchOperation::Delete { key } => {
+                None => {
                     inner.remove_item(key);
                 }
             }
         }
 
+        inner.metrics.record_inserts(inserted);
+
         // Write operations to AOF if needed
-        for operation in &self.operations {
-            match operation {
-                BatchOperation::Insert { key, value, opts } => {
+        for (key, write) in &self.pending {
+            match write {
+                Some(PendingValue { value, opts, .. }) => {
                     inner.write_to_aof_if_needed(key, value.as_ref(), opts.as_ref())?;
                 }
-                BatchOperation::Delete { key } => {
+                None => {
                     inner.write_delete_to_aof_if_needed(key)?;
                 }
             }
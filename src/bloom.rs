@@ -0,0 +1,163 @@
+//! Bloom filter over stored keys
+//!
+//! `DB::exists` consults the filter before taking the database lock, so
+//! lookups of keys that were never stored return without waiting on
+//! writers. The filter can report a key that is not stored, but never
+//! misses one that is, so a positive answer is always confirmed under the
+//! lock.
+//!
+//! Bits live in atomic words and are only set or rebuilt while the
+//! database write lock is held; readers test them without any lock. That
+//! lock-free read path is why this is not built on the `bloomfilter`
+//! crate, whose filter needs `&mut self` to insert and so would need its
+//! own lock.
+
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+
+/// Most hash functions used per key, whatever the requested rate
+const MAX_HASHES: u32 = 30;
+
+/// Bloom filter sized for a fixed number of keys
+pub(crate) struct KeyFilter {
+    bits: Box<[AtomicU64]>,
+    /// Bit positions set per key
+    num_hashes: u32,
+    /// Keys removed since the last rebuild; their bits are still set
+    removed: AtomicUsize,
+    /// Cleared when the database closes so lookups fall through to the
+    /// lock and report the closed database
+    enabled: AtomicBool,
+}
+
+impl KeyFilter {
+    /// Filter giving `false_positive_rate` while it holds at most
+    /// `capacity` keys
+    pub(crate) fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        let capacity = capacity.max(1) as f64;
+        let ln2 = std::f64::consts::LN_2;
+        let num_bits = (-capacity * false_positive_rate.ln() / (ln2 * ln2)).ceil();
+        let num_words = ((num_bits / 64.0).ceil() as usize).max(1);
+        let num_hashes = ((num_words * 64) as f64 / capacity * ln2).round() as u32;
+
+        Self {
+            bits: (0..num_words).map(|_| AtomicU64::new(0)).collect(),
+            num_hashes: num_hashes.clamp(1, MAX_HASHES),
+            removed: AtomicUsize::new(0),
+            enabled: AtomicBool::new(true),
+        }
+    }
+
+    /// Add a key. Called with the database write lock held.
+    pub(crate) fn insert(&self, key: &[u8]) {
+        for bit in self.bit_positions(key) {
+            self.bits[bit / 64].fetch_or(1 << (bit % 64), Ordering::Release);
+        }
+    }
+
+    /// Whether the key may be stored; `false` means it definitely is not
+    pub(crate) fn may_contain(&self, key: &[u8]) -> bool {
+        if !self.enabled.load(Ordering::Acquire) {
+            return true;
+        }
+        self.bit_positions(key)
+            .all(|bit| self.bits[bit / 64].load(Ordering::Acquire) & (1 << (bit % 64)) != 0)
+    }
+
+    /// Record a removed key, returning whether enough bits are stale that
+    /// the filter should be rebuilt from the `live` remaining keys
+    pub(crate) fn note_removed(&self, live: usize) -> bool {
+        self.removed.fetch_add(1, Ordering::Relaxed) + 1 > live
+    }
+
+    /// Reset the bits to exactly those of `keys`. Called with the database
+    /// write lock held.
+    ///
+    /// Each word is replaced by its new value in one store. The new bits
+    /// are a subset of the old ones, so a concurrent lookup sees every
+    /// live key's bits whichever words it reads before or after the store.
+    pub(crate) fn rebuild<'a>(&self, keys: impl IntoIterator<Item = &'a [u8]>) {
+        let mut words = vec![0u64; self.bits.len()];
+        for key in keys {
+            for bit in self.bit_positions(key) {
+                words[bit / 64] |= 1 << (bit % 64);
+            }
+        }
+        for (word, value) in self.bits.iter().zip(words) {
+            word.store(value, Ordering::Release);
+        }
+        self.removed.store(0, Ordering::Relaxed);
+    }
+
+    /// Stop answering lookups, so every key is reported as possibly stored
+    pub(crate) fn disable(&self) {
+        self.enabled.store(false, Ordering::Release);
+    }
+
+    /// Bit positions of a key, derived from one 64-bit hash by double
+    /// hashing
+    fn bit_positions(&self, key: &[u8]) -> impl Iterator<Item = usize> {
+        let hash = key_hash(key);
+        let step = hash.rotate_left(32) | 1;
+        let num_bits = (self.bits.len() * 64) as u64;
+        (0..u64::from(self.num_hashes))
+            .map(move |i| (hash.wrapping_add(i.wrapping_mul(step)) % num_bits) as usize)
+    }
+}
+
+/// FNV-1a hash of a key with a final avalanche step.
+///
+/// The std `DefaultHasher` makes no promise that its output stays the same
+/// across Rust releases, so a fixed function is used instead. The
+/// MurmurHash3 finalizer mixes FNV's output so both halves of the hash,
+/// which double hashing uses separately, depend on every byte.
+fn key_hash(key: &[u8]) -> u64 {
+    const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    let mut hash = key.iter().fold(FNV_OFFSET, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(FNV_PRIME)
+    });
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xff51_afd7_ed55_8ccd);
+    hash ^= hash >> 33;
+    hash = hash.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+    hash ^ (hash >> 33)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_key_filter_has_no_false_negatives() {
+        let filter = KeyFilter::new(1_000, 0.01);
+        let keys: Vec<String> = (0..1_000).map(|i| format!("user:{}", i)).collect();
+        for key in &keys {
+            filter.insert(key.as_bytes());
+        }
+        assert!(keys.iter().all(|key| filter.may_contain(key.as_bytes())));
+
+        // The false positive rate stays near the requested one
+        let false_positives = (0..10_000)
+            .filter(|i| filter.may_contain(format!("missing:{}", i).as_bytes()))
+            .count();
+        assert!(false_positives < 300, "{} false positives", false_positives);
+
+        filter.disable();
+        assert!(filter.may_contain(b"missing:0"));
+    }
+
+    #[test]
+    fn test_key_filter_rebuild_drops_removed_keys() {
+        let filter = KeyFilter::new(100, 0.001);
+        filter.insert(b"a");
+        filter.insert(b"b");
+        assert!(!filter.note_removed(1));
+        assert!(filter.note_removed(0));
+
+        filter.rebuild([b"a".as_ref()]);
+        assert!(filter.may_contain(b"a"));
+        assert!(!filter.may_contain(b"b"));
+        assert!(!filter.note_removed(1));
+    }
+}
@@ -1,16 +1,24 @@
 //! Database builder for flexible configuration
 //!
 //! This module provides a builder pattern for creating databases with
-//! advanced configuration options including custom AOF paths.
+//! advanced configuration options including custom AOF paths, storage
+//! backends, index strategies, compression and read-only access.
 
 use crate::db::{DB, DBInner};
-use crate::error::Result;
-use crate::index::IndexManager;
-use crate::persistence::AOFFile;
+use crate::error::{Result, SpatioError};
+use crate::index::{IndexManager, IndexStrategy};
+#[cfg(feature = "aof")]
+use crate::persistence::{AOFConfig, AOFFile, Compression};
+use crate::storage::StorageBackend;
+use crate::telemetry::Metrics;
 use crate::types::{Config, DbStats};
-use std::collections::BTreeMap;
+use crate::watch::Watchers;
+use indexmap::IndexSet;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::path::PathBuf;
-use std::sync::{Arc, RwLock};
+use std::sync::Mutex;
+use std::time::Duration;
 
 /// Builder for creating database instances with custom configuration.
 ///
@@ -19,8 +27,14 @@ use std::sync::{Arc, RwLock};
 /// - Custom AOF (Append-Only File) paths separate from the logical database path
 /// - In-memory databases
 /// - Full configuration control
+/// - Storage backends, index strategies and AOF compression
+/// - Read-only access and background expiry
 /// - Automatic startup replay
 ///
+/// `build()` checks the options for conflicts, such as `memory()` together
+/// with `aof_path()`, and reports them as
+/// `SpatioError::InvalidConfiguration`.
+///
 /// # Examples
 ///
 /// ## Basic usage with custom AOF path
@@ -70,11 +84,40 @@ use std::sync::{Arc, RwLock};
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Debug)]
 pub struct DBBuilder {
     aof_path: Option<PathBuf>,
     config: Config,
     in_memory: bool,
+    /// Set by `memory()`, which conflicts with `aof_path()` instead of
+    /// being overridden by it
+    memory_only: bool,
+    backend: Option<Box<dyn StorageBackend>>,
+    index_strategy: Option<IndexStrategy>,
+    #[cfg(feature = "aof")]
+    compression: Compression,
+    read_only: bool,
+    metrics_prefix: Option<String>,
+    expiry_interval: Option<Duration>,
+}
+
+impl fmt::Debug for DBBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("DBBuilder");
+        debug
+            .field("aof_path", &self.aof_path)
+            .field("config", &self.config)
+            .field("in_memory", &self.in_memory)
+            .field("memory_only", &self.memory_only)
+            .field("backend", &self.backend.is_some())
+            .field("index_strategy", &self.index_strategy);
+        #[cfg(feature = "aof")]
+        debug.field("compression", &self.compression);
+        debug
+            .field("read_only", &self.read_only)
+            .field("metrics_prefix", &self.metrics_prefix)
+            .field("expiry_interval", &self.expiry_interval)
+            .finish()
+    }
 }
 
 impl DBBuilder {
@@ -95,6 +138,14 @@ impl DBBuilder {
             aof_path: None,
             config: Config::default(),
             in_memory: true,
+            memory_only: false,
+            backend: None,
+            index_strategy: None,
+            #[cfg(feature = "aof")]
+            compression: Compression::None,
+            read_only: false,
+            metrics_prefix: None,
+            expiry_interval: None,
         }
     }
 
@@ -157,6 +208,31 @@ impl DBBuilder {
         self
     }
 
+    /// Require an in-memory database with no persistence.
+    ///
+    /// Unlike `in_memory()`, which a later `aof_path()` call overrides,
+    /// combining `memory()` with `aof_path()` in either order makes
+    /// `build()` fail.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{DBBuilder, SpatioError};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = DBBuilder::new().memory().build()?;
+    ///
+    /// let conflicting = DBBuilder::new().memory().aof_path("data.aof").build();
+    /// assert!(matches!(conflicting, Err(SpatioError::InvalidConfiguration(_))));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn memory(mut self) -> Self {
+        self.memory_only = true;
+        self.in_memory = true;
+        self
+    }
+
     /// Set the database configuration.
     ///
     /// The configuration controls:
@@ -193,21 +269,291 @@ impl DBBuilder {
         self
     }
 
+    /// Set the database configuration.
+    ///
+    /// Same as `config()`. Options set directly on the builder, such as
+    /// `with_metrics_prefix()`, take precedence over the configuration
+    /// regardless of call order.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Database configuration
+    pub fn with_config(self, config: Config) -> Self {
+        self.config(config)
+    }
+
+    /// Attach a storage backend.
+    ///
+    /// Every write that would be persisted to the AOF is also written to
+    /// the backend, and on `build()` the backend's existing contents are
+    /// loaded and spatial indexes rebuilt from them. A backend can be used
+    /// on its own or alongside an AOF path.
+    ///
+    /// # Arguments
+    ///
+    /// * `backend` - Storage backend to write through to
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{DBBuilder, MemoryBackend};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = DBBuilder::new()
+    ///     .with_backend(Box::new(MemoryBackend::new()))
+    ///     .build()?;
+    ///
+    /// db.insert("key", b"value", None)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_backend(mut self, backend: Box<dyn StorageBackend>) -> Self {
+        self.backend = Some(backend);
+        self
+    }
+
+    /// Set the strategy `DB::insert_point` indexes points with.
+    ///
+    /// A geohash strategy sets the index precision, overriding
+    /// `Config::geohash_precision`. With an S2 or H3 strategy,
+    /// `insert_point` behaves like `insert_point_with_s2` or
+    /// `insert_point_with_h3`, so S2 points are queried with
+    /// `find_within_bounds` and H3 points with `find_nearby_h3`.
+    ///
+    /// # Arguments
+    ///
+    /// * `strategy` - Index strategy for new points
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{DBBuilder, IndexStrategy, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = DBBuilder::new()
+    ///     .with_index_strategy(IndexStrategy::Geohash { precision: 6 })
+    ///     .build()?;
+    ///
+    /// let nyc = Point::new(40.7128, -74.0060);
+    /// db.insert_point("sensors", &nyc, b"s-1", None)?;
+    /// let found = db.find_nearby("sensors", &nyc, 1_000.0, 10)?;
+    /// assert_eq!(found.len(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_index_strategy(mut self, strategy: IndexStrategy) -> Self {
+        self.index_strategy = Some(strategy);
+        self
+    }
+
+    /// Compress values written to the AOF.
+    ///
+    /// Existing records are read whatever compression they were written
+    /// with, so this can be turned on for an existing file. Requires
+    /// `aof_path()`.
+    ///
+    /// # Arguments
+    ///
+    /// * `algo` - Compression algorithm for new records
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Compression, DBBuilder};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let temp_path = std::env::temp_dir().join("compressed.aof");
+    /// let db = DBBuilder::new()
+    ///     .aof_path(&temp_path)
+    ///     .with_compression(Compression::Lz4)
+    ///     .build()?;
+    ///
+    /// db.insert("key", vec![b'x'; 1024], None)?;
+    /// # drop(db);
+    /// # std::fs::remove_file(temp_path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "aof")]
+    pub fn with_compression(mut self, algo: Compression) -> Self {
+        self.compression = algo;
+        self
+    }
+
+    /// Open the database read-only.
+    ///
+    /// The AOF and storage backend are loaded as usual, but every write
+    /// returns `SpatioError::ReadOnly` and nothing is written on close.
+    /// Requires an existing AOF file or a storage backend, and cannot be
+    /// combined with an expiry or automatic compaction interval.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{DBBuilder, SpatioError};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let temp_path = std::env::temp_dir().join("read_only_demo.aof");
+    /// # let _ = std::fs::remove_file(&temp_path);
+    /// let db = DBBuilder::new().aof_path(&temp_path).build()?;
+    /// db.insert("key", b"value", None)?;
+    /// drop(db);
+    ///
+    /// let db = DBBuilder::new().aof_path(&temp_path).read_only().build()?;
+    /// assert_eq!(db.get("key")?.unwrap().as_ref(), b"value");
+    /// assert!(matches!(db.insert("key", b"other", None), Err(SpatioError::ReadOnly)));
+    /// # drop(db);
+    /// # std::fs::remove_file(temp_path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn read_only(mut self) -> Self {
+        self.read_only = true;
+        self
+    }
+
+    /// Set the prefix for metric names, overriding
+    /// `Config::metrics_prefix`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Metric name prefix (e.g. "tracker")
+    pub fn with_metrics_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.metrics_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Remove expired keys on a background thread at the given interval,
+    /// overriding `Config::expiry_interval`.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - Time between cleanups
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{DBBuilder, SetOptions};
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = DBBuilder::new()
+    ///     .with_expiry_interval(Duration::from_secs(60))
+    ///     .build()?;
+    ///
+    /// db.insert("session", b"token", Some(SetOptions::with_ttl(Duration::from_secs(30))))?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn with_expiry_interval(mut self, interval: Duration) -> Self {
+        self.expiry_interval = Some(interval);
+        self
+    }
+
+    /// Merge builder options into the configuration and check them for
+    /// invalid values and conflicts
+    fn resolve_config(&self) -> Result<Config> {
+        let invalid = |msg: String| Err(SpatioError::InvalidConfiguration(msg));
+        let mut config = self.config.clone();
+
+        if let Some(prefix) = &self.metrics_prefix {
+            config.metrics_prefix = prefix.clone();
+        }
+        if let Some(interval) = self.expiry_interval {
+            config.expiry_interval = Some(interval);
+        }
+        match self.index_strategy {
+            Some(IndexStrategy::Geohash { precision }) => {
+                if !(1..=12).contains(&precision) {
+                    return invalid(format!(
+                        "geohash precision must be between 1 and 12, got {}",
+                        precision
+                    ));
+                }
+                config.geohash_precision = precision;
+                config.geohash_search_precisions = Config::search_precisions_for(precision);
+            }
+            #[cfg(feature = "s2")]
+            Some(IndexStrategy::S2 { level }) if level > 30 => {
+                return invalid(format!("S2 level must be at most 30, got {}", level));
+            }
+            #[cfg(feature = "h3")]
+            Some(IndexStrategy::H3 { resolution }) if resolution > 15 => {
+                return invalid(format!(
+                    "H3 resolution must be at most 15, got {}",
+                    resolution
+                ));
+            }
+            _ => {}
+        }
+
+        if let Err(msg) = config.validate() {
+            return invalid(msg);
+        }
+
+        if self.memory_only && self.aof_path.is_some() {
+            return invalid("memory() cannot be combined with aof_path()".to_string());
+        }
+
+        let aof_path = self.aof_path.as_ref().filter(|_| !self.in_memory);
+        #[cfg(feature = "aof")]
+        if self.compression != Compression::None && aof_path.is_none() {
+            return invalid("with_compression() requires aof_path()".to_string());
+        }
+        #[cfg(not(feature = "aof"))]
+        if aof_path.is_some() {
+            return invalid("aof_path() requires the `aof` feature".to_string());
+        }
+
+        if self.read_only {
+            if aof_path.is_none() && self.backend.is_none() {
+                return invalid("read_only() requires aof_path() or with_backend()".to_string());
+            }
+            if let Some(path) = aof_path
+                && !path.exists()
+            {
+                return invalid(format!(
+                    "read_only() requires an existing AOF file, but {} does not exist",
+                    path.display()
+                ));
+            }
+            if config.expiry_interval.is_some() {
+                return invalid(
+                    "read_only() cannot be combined with an expiry interval".to_string(),
+                );
+            }
+            if config.auto_compact_interval.is_some() {
+                return invalid(
+                    "read_only() cannot be combined with an auto compact interval".to_string(),
+                );
+            }
+        }
+
+        Ok(config)
+    }
+
     /// Build the database with the configured options.
     ///
     /// This method:
     /// 1. Creates the database instance
     /// 2. Opens the AOF file (if persistence is enabled)
     /// 3. Replays the AOF to restore previous state (startup replay)
-    /// 4. Rebuilds spatial indexes
-    /// 5. Returns a ready-to-use database
+    /// 4. Loads the storage backend's contents (if one is attached)
+    /// 5. Rebuilds spatial indexes
+    /// 6. Returns a ready-to-use database
     ///
     /// # Errors
     ///
-    /// Returns an error if:
+    /// Returns `SpatioError::InvalidConfiguration` if an option is invalid
+    /// or conflicts with another, for example `memory()` with `aof_path()`,
+    /// `with_compression()` without `aof_path()`, or `read_only()` without
+    /// an existing AOF file or storage backend.
+    ///
+    /// Otherwise returns an error if:
     /// - The AOF file cannot be opened or created
     /// - The AOF file is corrupted and cannot be replayed
     /// - File system permissions prevent access
+    /// - The storage backend cannot be read
     ///
     /// # Examples
     ///
@@ -226,29 +572,48 @@ impl DBBuilder {
     /// # }
     /// ```
     pub fn build(self) -> Result<DB> {
+        let config = self.resolve_config()?;
         let mut inner = DBInner {
             keys: BTreeMap::new(),
             expirations: BTreeMap::new(),
-            index_manager: IndexManager::with_config(&self.config),
+            index_manager: IndexManager::with_config(&config),
+            #[cfg(feature = "aof")]
             aof_file: None,
             closed: false,
             stats: DbStats::default(),
-            config: self.config.clone(),
+            metrics: Metrics::new(&config.metrics_prefix),
+            config,
+            access_order: Mutex::new(IndexSet::new()),
+            backend: self.backend,
+            prefix_counts: HashMap::new(),
+            key_versions: HashMap::new(),
+            write_version: 0,
+            ttl_set_at: HashMap::new(),
+            watchers: Watchers::default(),
+            read_only: self.read_only,
+            index_strategy: self.index_strategy.unwrap_or_default(),
+            key_filter: None,
         };
 
         // Initialize persistence if AOF path is specified
+        #[cfg(feature = "aof")]
         if !self.in_memory
             && let Some(aof_path) = self.aof_path
         {
-            let mut aof_file = AOFFile::open(&aof_path)?;
+            let aof_config = AOFConfig {
+                compression: self.compression,
+                ..AOFConfig::default()
+            };
+            let mut aof_file = AOFFile::open_with_config(&aof_path, aof_config)?;
             // Automatic startup replay to restore previous state
             inner.load_from_aof(&mut aof_file)?;
             inner.aof_file = Some(aof_file);
         }
 
-        Ok(DB {
-            inner: Arc::new(RwLock::new(inner)),
-        })
+        // Restore anything already held by the storage backend
+        inner.load_from_backend()?;
+
+        Ok(DB::from_inner(inner))
     }
 }
 
@@ -335,4 +700,40 @@ mod tests {
         assert!(builder.in_memory);
         assert!(builder.aof_path.is_none());
     }
+
+    #[test]
+    fn test_builder_with_backend_restores_items() {
+        use crate::spatial::{Point, SpatialKey};
+        use crate::storage::{MemoryBackend, StorageBackend};
+        use crate::types::DbItem;
+
+        let point = Point::new(40.7128, -74.0060);
+        let spatial_key = SpatialKey::geohash("cities", &point.to_geohash(8).unwrap());
+
+        let mut backend = MemoryBackend::new();
+        backend
+            .put(b"user:1", &DbItem::new(b"alice".to_vec()))
+            .unwrap();
+        backend
+            .put(spatial_key.as_bytes(), &DbItem::new(b"NYC".to_vec()))
+            .unwrap();
+
+        let db = DBBuilder::new()
+            .with_backend(Box::new(backend))
+            .build()
+            .unwrap();
+
+        assert_eq!(db.get("user:1").unwrap().unwrap().as_ref(), b"alice");
+        let nearby = db.find_nearby("cities", &point, 100.0, 10).unwrap();
+        assert_eq!(nearby.len(), 1);
+        assert_eq!(nearby[0].1.as_ref(), b"NYC");
+
+        // Writes and deletes go through to the backend
+        db.insert("user:2", b"bob", None).unwrap();
+        db.delete("user:1").unwrap();
+        let inner = db.read().unwrap();
+        let backend = inner.backend.as_ref().unwrap();
+        assert!(backend.contains_key(b"user:2").unwrap());
+        assert!(!backend.contains_key(b"user:1").unwrap());
+    }
 }
@@ -0,0 +1,22 @@
+//! Wall clock used for expiration
+//!
+//! `SystemTime::now()` panics on `wasm32-unknown-unknown`, which has no
+//! system clock. With the `wasm` feature on that target the time is read
+//! from JavaScript's `Date.now()` instead; everywhere else this is
+//! `SystemTime::now()`.
+
+use std::time::SystemTime;
+#[cfg(all(feature = "wasm", target_arch = "wasm32", target_os = "unknown"))]
+use std::time::{Duration, UNIX_EPOCH};
+
+/// The current time
+#[cfg(not(all(feature = "wasm", target_arch = "wasm32", target_os = "unknown")))]
+pub(crate) fn now() -> SystemTime {
+    SystemTime::now()
+}
+
+/// The current time, from `Date.now()` in milliseconds since the epoch
+#[cfg(all(feature = "wasm", target_arch = "wasm32", target_os = "unknown"))]
+pub(crate) fn now() -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs_f64(js_sys::Date::now() / 1000.0)
+}
@@ -0,0 +1,321 @@
+//! CSV import and export for Spatio
+//!
+//! This module adds bulk loading of points and key-value pairs from CSV
+//! text, and exporting a key prefix back to CSV. Rows are parsed before
+//! anything is written, so a malformed file leaves the database unchanged.
+
+use crate::DB;
+use crate::error::{Result, SpatioError};
+use crate::spatial::Point;
+use crate::types::SetOptions;
+use std::io::Write;
+
+impl DB {
+    /// Import geographic points from CSV text.
+    ///
+    /// The first row must be a header. Each following row is inserted with
+    /// `insert_point` under `prefix`, using the named latitude, longitude
+    /// and value columns.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace for the imported points
+    /// * `csv` - CSV text with a header row
+    /// * `lat_col` - Name of the latitude column
+    /// * `lon_col` - Name of the longitude column
+    /// * `value_col` - Name of the column stored as the point's value
+    /// * `opts` - Optional settings like TTL, applied to every point
+    ///
+    /// # Returns
+    ///
+    /// The number of points imported
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Point, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let csv = "name,lat,lon\nNew York,40.7128,-74.0060\nBrooklyn,40.6782,-73.9442\n";
+    ///
+    /// let count = db.import_csv_points("cities", csv, "lat", "lon", "name", None)?;
+    /// assert_eq!(count, 2);
+    ///
+    /// let nearby = db.find_nearby("cities", &Point::new(40.7128, -74.0060), 1000.0, 10)?;
+    /// assert_eq!(nearby[0].1.as_ref(), b"New York");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.import_csv_points",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+            ),
+            err
+        )
+    )]
+    pub fn import_csv_points(
+        &self,
+        prefix: &str,
+        csv: &str,
+        lat_col: &str,
+        lon_col: &str,
+        value_col: &str,
+        opts: Option<SetOptions>,
+    ) -> Result<usize> {
+        let mut reader = csv::Reader::from_reader(csv.as_bytes());
+        let columns = column_indexes(&mut reader, &[lat_col, lon_col, value_col])?;
+
+        let mut rows = Vec::new();
+        for (row, record) in reader.records().enumerate() {
+            let record = record.map_err(csv_error)?;
+            let lat = parse_coordinate(&record, columns[0], lat_col, row)?;
+            let lon = parse_coordinate(&record, columns[1], lon_col, row)?;
+            let value = field(&record, columns[2], value_col, row)?;
+            rows.push((Point::new(lat, lon), value.to_string()));
+        }
+
+        for (point, value) in &rows {
+            self.insert_point(prefix, point, value.as_bytes(), opts.clone())?;
+        }
+        Ok(rows.len())
+    }
+
+    /// Import key-value pairs from CSV text.
+    ///
+    /// The first row must be a header. Each following row is inserted with
+    /// `insert`, using the named key and value columns.
+    ///
+    /// # Arguments
+    ///
+    /// * `csv` - CSV text with a header row
+    /// * `key_col` - Name of the key column
+    /// * `value_col` - Name of the value column
+    /// * `opts` - Optional settings like TTL, applied to every pair
+    ///
+    /// # Returns
+    ///
+    /// The number of pairs imported
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Spatio;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let csv = "id,name\nuser:1,Alice\nuser:2,Bob\n";
+    ///
+    /// assert_eq!(db.import_csv_kv(csv, "id", "name", None)?, 2);
+    /// assert_eq!(db.get("user:1")?.unwrap().as_ref(), b"Alice");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.import_csv_kv", skip_all, err)
+    )]
+    pub fn import_csv_kv(
+        &self,
+        csv: &str,
+        key_col: &str,
+        value_col: &str,
+        opts: Option<SetOptions>,
+    ) -> Result<usize> {
+        let mut reader = csv::Reader::from_reader(csv.as_bytes());
+        let columns = column_indexes(&mut reader, &[key_col, value_col])?;
+
+        let mut rows = Vec::new();
+        for (row, record) in reader.records().enumerate() {
+            let record = record.map_err(csv_error)?;
+            let key = field(&record, columns[0], key_col, row)?;
+            let value = field(&record, columns[1], value_col, row)?;
+            rows.push((key.to_string(), value.to_string()));
+        }
+
+        for (key, value) in &rows {
+            self.insert(key, value, opts.clone())?;
+        }
+        Ok(rows.len())
+    }
+
+    /// Export every live key under a prefix as CSV.
+    ///
+    /// Writes a `key,value` header followed by one row per key, in key
+    /// order. Values are written as raw bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Key prefix to export
+    /// * `writer` - Destination for the CSV output
+    ///
+    /// # Returns
+    ///
+    /// The number of rows written, excluding the header
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Spatio;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert("user:1", b"Alice", None)?;
+    /// db.insert("user:2", b"Bob, Jr.", None)?;
+    ///
+    /// let mut out = Vec::new();
+    /// assert_eq!(db.export_prefix_to_csv("user:", &mut out)?, 2);
+    /// assert_eq!(
+    ///     String::from_utf8(out)?,
+    ///     "key,value\nuser:1,Alice\nuser:2,\"Bob, Jr.\"\n"
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.export_prefix_to_csv",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+            ),
+            err
+        )
+    )]
+    pub fn export_prefix_to_csv(&self, prefix: &str, writer: &mut dyn Write) -> Result<usize> {
+        let items = self.prefix_items(prefix.as_bytes())?;
+
+        let mut csv_writer = csv::Writer::from_writer(writer);
+        csv_writer
+            .write_record(["key", "value"])
+            .map_err(csv_error)?;
+        for (key, item) in &items {
+            csv_writer
+                .write_record([key.as_ref(), item.value.as_ref()])
+                .map_err(csv_error)?;
+        }
+        csv_writer.flush()?;
+
+        Ok(items.len())
+    }
+}
+
+fn csv_error(err: csv::Error) -> SpatioError {
+    SpatioError::SerializationErrorWithContext(format!("CSV error: {}", err))
+}
+
+/// Resolve header names to column positions
+fn column_indexes(reader: &mut csv::Reader<&[u8]>, names: &[&str]) -> Result<Vec<usize>> {
+    let headers = reader.headers().map_err(csv_error)?;
+    names
+        .iter()
+        .map(|name| {
+            headers
+                .iter()
+                .position(|header| header == *name)
+                .ok_or_else(|| SpatioError::Other(format!("CSV column '{}' not found", name)))
+        })
+        .collect()
+}
+
+fn field<'a>(
+    record: &'a csv::StringRecord,
+    index: usize,
+    name: &str,
+    row: usize,
+) -> Result<&'a str> {
+    record.get(index).ok_or_else(|| {
+        SpatioError::SerializationErrorWithContext(format!(
+            "CSV row {} is missing column '{}'",
+            row + 1,
+            name
+        ))
+    })
+}
+
+fn parse_coordinate(
+    record: &csv::StringRecord,
+    index: usize,
+    name: &str,
+    row: usize,
+) -> Result<f64> {
+    let raw = field(record, index, name, row)?;
+    raw.trim().parse::<f64>().map_err(|_| {
+        SpatioError::SerializationErrorWithContext(format!(
+            "CSV row {} has invalid {} value '{}'",
+            row + 1,
+            name,
+            raw
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_import_csv_points() {
+        let db = DB::memory().unwrap();
+        let csv = "id,latitude,longitude,label\n\
+                   1,40.7128,-74.0060,New York\n\
+                   2,51.5074,-0.1278,\"London, UK\"\n";
+
+        let count = db
+            .import_csv_points("cities", csv, "latitude", "longitude", "label", None)
+            .unwrap();
+        assert_eq!(count, 2);
+
+        let london = db
+            .find_nearby("cities", &Point::new(51.5074, -0.1278), 1000.0, 10)
+            .unwrap();
+        assert_eq!(london.len(), 1);
+        assert_eq!(london[0].1.as_ref(), b"London, UK");
+    }
+
+    #[test]
+    fn test_import_csv_points_is_all_or_nothing() {
+        let db = DB::memory().unwrap();
+        let csv = "lat,lon,name\n40.7128,-74.0060,New York\nnorth,-0.1278,London\n";
+
+        let result = db.import_csv_points("cities", csv, "lat", "lon", "name", None);
+        assert!(matches!(
+            result,
+            Err(SpatioError::SerializationErrorWithContext(_))
+        ));
+        assert_eq!(db.stats().unwrap().key_count, 0);
+
+        let missing = db.import_csv_points("cities", csv, "lat", "lng", "name", None);
+        assert!(matches!(missing, Err(SpatioError::Other(_))));
+    }
+
+    #[test]
+    fn test_import_csv_kv_and_export() {
+        let db = DB::memory().unwrap();
+        let csv = "key,value,ignored\nuser:1,Alice,x\nuser:2,\"Bob \"\"B\"\"\",y\n";
+
+        assert_eq!(db.import_csv_kv(csv, "key", "value", None).unwrap(), 2);
+        db.insert("other", b"skip", None).unwrap();
+        assert_eq!(db.get("user:2").unwrap().unwrap().as_ref(), b"Bob \"B\"");
+
+        let mut out = Vec::new();
+        let rows = db.export_prefix_to_csv("user:", &mut out).unwrap();
+        assert_eq!(rows, 2);
+
+        // Exported CSV imports back to the same pairs
+        let exported = String::from_utf8(out).unwrap();
+        let copy = DB::memory().unwrap();
+        assert_eq!(
+            copy.import_csv_kv(&exported, "key", "value", None).unwrap(),
+            2
+        );
+        assert_eq!(copy.get("user:1").unwrap().unwrap().as_ref(), b"Alice");
+        assert_eq!(copy.get("user:2").unwrap().unwrap().as_ref(), b"Bob \"B\"");
+        assert!(copy.get("other").unwrap().is_none());
+    }
+}
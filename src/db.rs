@@ -1,14 +1,49 @@
+use crate::analysis::{self, SpatialAnalysis, TrajectorySmoothing};
 use crate::batch::AtomicBatch;
+use crate::bloom::KeyFilter;
+use crate::clock;
 use crate::error::{Result, SpatioError};
-use crate::index::IndexManager;
-use crate::persistence::{AOFCommand, AOFFile};
-use crate::spatial::{Point, SpatialKey};
-use crate::types::{Config, DbItem, DbStats, SetOptions};
+use crate::geometry::{Coordinate, Geometry, LineString, Polygon};
+#[cfg(feature = "aof")]
+use crate::index::IndexSnapshot;
+use crate::index::{IndexManager, IndexStrategy};
+use crate::iter::{ItemIterator, KeyIterator};
+use crate::namespace::{Namespace, NamespaceManager, NamespaceStats};
+#[cfg(feature = "aof")]
+use crate::persistence::{AOFCommand, AOFConfig, AOFFile};
+#[cfg(feature = "async")]
+use crate::region::{
+    RegionSubscription, RegionWatcher, SpatialWindowWatcher, WindowSubscription,
+    trajectory_object_id,
+};
+use crate::spatial::{BoundingBox, GeohashUtils, Point, SpatialKey};
+use crate::storage::{MemorySnapshot, StorageBackend, StorageOp, StorageSnapshot};
+use crate::sync::{self, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use crate::telemetry::Metrics;
+use crate::transaction::Transaction;
+#[cfg(feature = "debug_queries")]
+use crate::types::QueryExplanation;
+use crate::types::{
+    BulkLoadStats, CompactionStats, Config, CorridorResult, DbItem, DbStats, EvictionPolicy,
+    GeofenceEvent, GeofenceEventType, IntegrityReport, InvertedIndex, KnnEdge, ScanPage,
+    SetOptions, SpatialJoinResult, StaleEntry, TrajectoryCluster, TrajectoryCrossing,
+    UnservedPoint, ValueType,
+};
+#[cfg(feature = "aof")]
+use crate::types::{CheckpointStats, ReplayStats};
+use crate::view::{DbView, DbViewMut};
+use crate::watch::{CountEvent, KeyEvent, KeyEventType, WatchHandle, Watchers};
 use bytes::Bytes;
-use std::collections::BTreeMap;
+use indexmap::IndexSet;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Bound;
 use std::path::Path;
-use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
-use std::time::SystemTime;
+use std::sync::mpsc::Receiver;
+use std::sync::{Arc, Mutex, Weak};
+use std::time::{Duration, SystemTime};
 
 /// Main Spatio database structure providing spatial and temporal data storage.
 ///
@@ -64,6 +99,8 @@ use std::time::SystemTime;
 #[derive(Clone)]
 pub struct DB {
     pub(crate) inner: Arc<RwLock<DBInner>>,
+    /// Bloom filter of stored keys, read by `exists` without the lock
+    key_filter: Option<Arc<KeyFilter>>,
 }
 
 pub(crate) struct DBInner {
@@ -74,6 +111,7 @@ pub(crate) struct DBInner {
     /// Index manager for spatial operations
     pub index_manager: IndexManager,
     /// Append-only file for persistence
+    #[cfg(feature = "aof")]
     pub aof_file: Option<AOFFile>,
     /// Whether the database is closed
     pub closed: bool,
@@ -81,6 +119,36 @@ pub(crate) struct DBInner {
     pub stats: DbStats,
     /// Configuration
     pub config: Config,
+    /// Evictable keys ordered from least to most recently used.
+    ///
+    /// Only maintained when `config.max_memory_bytes` is set. Guarded by a
+    /// mutex so reads under the shared lock can record accesses.
+    pub access_order: Mutex<IndexSet<Bytes>>,
+    /// Optional storage backend that receives every persisted write
+    pub backend: Option<Box<dyn StorageBackend>>,
+    /// Number of stored keys under each leading key prefix ending in `:`,
+    /// when `config.track_prefix_counts` is set
+    pub prefix_counts: HashMap<Bytes, usize>,
+    /// Version of each live key, changed on every write to it, so
+    /// transactions can detect keys changed since they read them. Keys
+    /// loaded at open without a later write have no entry (version 0).
+    pub key_versions: HashMap<Bytes, u64>,
+    /// Last version handed out in `key_versions`
+    pub write_version: u64,
+    /// When each live key's current TTL was set, for `spatial_window`.
+    /// Keys without a TTL, or loaded at open, have no entry.
+    pub ttl_set_at: HashMap<Bytes, SystemTime>,
+    /// Key and prefix change watchers
+    pub watchers: Watchers,
+    /// Metric names for operation instrumentation
+    pub metrics: Metrics,
+    /// Reject every write with `SpatioError::ReadOnly`
+    pub read_only: bool,
+    /// Strategy `DB::insert_point` indexes new points with
+    pub index_strategy: IndexStrategy,
+    /// Bloom filter of stored keys, when
+    /// `config.bloom_filter_false_positive_rate` is set
+    pub key_filter: Option<Arc<KeyFilter>>,
 }
 
 impl DB {
@@ -109,13 +177,18 @@ impl DB {
     ///
     /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// // Create persistent database with automatic AOF replay on open
-    /// let persistent_db = Spatio::open("my_data.db")?;
+    /// let dir = tempfile::tempdir()?;
+    /// let persistent_db = Spatio::open(dir.path().join("my_data.db"))?;
     ///
     /// // Create in-memory database (no persistence)
     /// let mem_db = Spatio::open(":memory:")?;
     /// # Ok(())
     /// # }
     /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.open", skip_all, err)
+    )]
     pub fn open<P: AsRef<Path>>(path: P) -> Result<Self> {
         Self::open_with_config(path, Config::default())
     }
@@ -147,43 +220,117 @@ impl DB {
     ///     .with_sync_policy(SyncPolicy::Always)
     ///     .with_default_ttl(Duration::from_secs(3600));
     ///
-    /// let db = Spatio::open_with_config("my_database.db", config)?;
+    /// let dir = tempfile::tempdir()?;
+    /// let db = Spatio::open_with_config(dir.path().join("my_database.db"), config)?;
     /// # Ok(())
     /// # }
     /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.open_with_config", skip_all, err)
+    )]
     pub fn open_with_config<P: AsRef<Path>>(path: P, config: Config) -> Result<Self> {
         let path = path.as_ref();
         let is_memory = path.to_str() == Some(":memory:");
+        #[cfg(not(feature = "aof"))]
+        if !is_memory {
+            return Err(SpatioError::Other(format!(
+                "Cannot open {}: file persistence requires the `aof` feature",
+                path.display()
+            )));
+        }
 
+        #[cfg_attr(not(feature = "aof"), allow(unused_mut))]
         let mut inner = DBInner {
             keys: BTreeMap::new(),
             expirations: BTreeMap::new(),
             index_manager: IndexManager::with_config(&config),
+            #[cfg(feature = "aof")]
             aof_file: None,
             closed: false,
             stats: DbStats::default(),
             config: config.clone(),
+            access_order: Mutex::new(IndexSet::new()),
+            backend: None,
+            prefix_counts: HashMap::new(),
+            key_versions: HashMap::new(),
+            write_version: 0,
+            ttl_set_at: HashMap::new(),
+            watchers: Watchers::default(),
+            metrics: Metrics::new(&config.metrics_prefix),
+            read_only: false,
+            index_strategy: IndexStrategy::default(),
+            key_filter: None,
         };
 
         // Initialize persistence if not in-memory
         // This automatically replays the AOF to restore previous state
+        #[cfg(feature = "aof")]
         if !is_memory {
             let mut aof_file = AOFFile::open(path)?;
             inner.load_from_aof(&mut aof_file)?;
             inner.aof_file = Some(aof_file);
         }
 
-        Ok(DB {
-            inner: Arc::new(RwLock::new(inner)),
-        })
+        Ok(DB::from_inner(inner))
+    }
+
+    /// Wrap restored state in a database handle, starting automatic
+    /// compaction and expiry if configured
+    pub(crate) fn from_inner(mut inner: DBInner) -> Self {
+        if let Some(rate) = inner.config.bloom_filter_false_positive_rate {
+            let capacity = inner.config.bloom_filter_capacity.max(inner.keys.len());
+            let filter = KeyFilter::new(capacity, rate);
+            filter.rebuild(inner.keys.keys().map(|key| key.as_ref()));
+            inner.key_filter = Some(Arc::new(filter));
+        }
+        let key_filter = inner.key_filter.clone();
+        let compact_interval = inner.config.auto_compact_interval;
+        let expiry_interval = inner.config.expiry_interval;
+        #[cfg(feature = "aof")]
+        let checkpoint_interval = inner.config.checkpoint_interval;
+        #[cfg(feature = "aof")]
+        let checkpoint_path = inner.config.checkpoint_path.clone();
+        let inner = Arc::new(RwLock::new(inner));
+        if let Some(interval) = compact_interval {
+            spawn_maintenance(Arc::downgrade(&inner), interval, |inner| {
+                let _ = inner.compact_memory();
+            });
+        }
+        if let Some(interval) = expiry_interval {
+            spawn_maintenance(Arc::downgrade(&inner), interval, |inner| {
+                let _ = inner.cleanup_expired();
+            });
+        }
+        #[cfg(feature = "aof")]
+        if let (Some(interval), Some(path)) = (checkpoint_interval, checkpoint_path) {
+            spawn_periodic(Arc::downgrade(&inner), interval, move |lock| {
+                // Only the copy of the items happens under the lock
+                let (items, aof_config) = match sync::read(lock) {
+                    Ok(inner) if !inner.closed => inner.checkpoint_items(),
+                    _ => return false,
+                };
+                let _ = write_checkpoint(&path, items, aof_config);
+                true
+            });
+        }
+        DB { inner, key_filter }
     }
 
     /// Creates a new in-memory Spatio database.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.memory", skip_all, err)
+    )]
     pub fn memory() -> Result<Self> {
         Self::open(":memory:")
     }
 
     /// Create an in-memory database with custom configuration
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.memory_with_config", skip_all, err)
+    )]
     pub fn memory_with_config(config: Config) -> Result<Self> {
         Self::open_with_config(":memory:", config)
     }
@@ -217,486 +364,840 @@ impl DB {
     }
 
     /// Get database statistics
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.stats", skip_all, err)
+    )]
     pub fn stats(&self) -> Result<DbStats> {
         let inner = self.read()?;
         Ok(inner.stats.clone())
     }
 
-    /// Inserts a key-value pair into the database.
+    /// Check stored data for corruption.
     ///
-    /// # Arguments
+    /// Every key is checked: a value may only be empty if the AOF recorded
+    /// it as empty, and spatial keys must decode to a valid point. For
+    /// persistent databases the AOF checksum footers written on `sync` are
+    /// verified as well.
     ///
-    /// * `key` - The key to store
-    /// * `value` - The value to associate with the key
-    /// * `opts` - Optional settings like TTL
+    /// # Returns
+    ///
+    /// An `IntegrityReport` listing the keys that failed a check
+    ///
+    /// # Errors
+    ///
+    /// Only returns an error if the database lock cannot be acquired;
+    /// corruption is reported in the `IntegrityReport` instead.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use spatio::{Spatio, SetOptions};
-    /// use std::time::Duration;
+    /// use spatio::{Point, Spatio};
     ///
     /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let db = Spatio::memory()?;
+    /// db.insert("user:1", b"alice", None)?;
+    /// db.insert_point("cities", &Point::new(40.7128, -74.0060), b"NYC", None)?;
     ///
-    /// // Simple insert
-    /// db.insert("user:123", b"John Doe", None)?;
-    ///
-    /// // Insert with TTL
-    /// let opts = SetOptions::with_ttl(Duration::from_secs(300));
-    /// db.insert("session:abc", b"user_data", Some(opts))?;
+    /// let report = db.verify_integrity()?;
+    /// assert_eq!(report.valid_keys, 2);
+    /// assert!(report.corrupted_keys.is_empty());
+    /// assert!(report.aof_checksum_ok);
     /// # Ok(())
     /// # }
     /// ```
-    pub fn insert(
-        &self,
-        key: impl AsRef<[u8]>,
-        value: impl AsRef<[u8]>,
-        opts: Option<SetOptions>,
-    ) -> Result<Option<Bytes>> {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.verify_integrity", skip_all, err)
+    )]
+    pub fn verify_integrity(&self) -> Result<IntegrityReport> {
         let mut inner = self.write()?;
-        if inner.closed {
-            return Err(SpatioError::DatabaseClosed);
-        }
-
-        let key_bytes = Bytes::copy_from_slice(key.as_ref());
-        let value_bytes = Bytes::copy_from_slice(value.as_ref());
-
-        let item = match opts {
-            Some(SetOptions { ttl: Some(ttl), .. }) => DbItem::with_ttl(value_bytes, ttl),
-            Some(SetOptions {
-                expires_at: Some(expires_at),
-                ..
-            }) => DbItem::with_expiration(value_bytes, expires_at),
-            _ => DbItem::new(value_bytes),
-        };
-
-        let old = inner.insert_item(key_bytes.clone(), item);
-        inner.write_to_aof_if_needed(&key_bytes, value.as_ref(), opts.as_ref())?;
-        Ok(old.map(|item| item.value))
+        Ok(inner.verify_integrity())
     }
 
-    /// Get a value by key
-    pub fn get(&self, key: impl AsRef<[u8]>) -> Result<Option<Bytes>> {
+    /// Find geohash index entries whose point key is no longer stored.
+    ///
+    /// Each indexed point's key is rebuilt from its geohash and looked up
+    /// among the stored keys. Keys that are stored but expired are not
+    /// reported: expiry cleanup removes their index entries with them.
+    ///
+    /// # Returns
+    ///
+    /// The orphaned entries, ordered by prefix and geohash
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database is closed.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Point, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("cities", &Point::new(40.7128, -74.0060), b"NYC", None)?;
+    ///
+    /// assert!(db.find_stale_spatial_entries()?.is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.find_stale_spatial_entries", skip_all, err)
+    )]
+    pub fn find_stale_spatial_entries(&self) -> Result<Vec<StaleEntry>> {
         let inner = self.read()?;
         if inner.closed {
             return Err(SpatioError::DatabaseClosed);
         }
-
-        let key_bytes = Bytes::copy_from_slice(key.as_ref());
-
-        if let Some(item) = inner.get_item(&key_bytes)
-            && !item.is_expired()
-        {
-            return Ok(Some(item.value.clone()));
-        }
-        Ok(None)
+        Ok(inner.stale_spatial_entries())
     }
 
-    /// Delete a key atomically
-    pub fn delete(&self, key: impl AsRef<[u8]>) -> Result<Option<Bytes>> {
-        let mut inner = self.write()?;
+    /// Remove the geohash index entries reported by
+    /// `find_stale_spatial_entries`.
+    ///
+    /// # Returns
+    ///
+    /// Number of index entries removed
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database is closed or read-only.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Point, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("cities", &Point::new(40.7128, -74.0060), b"NYC", None)?;
+    ///
+    /// assert_eq!(db.remove_stale_spatial_entries()?, 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.remove_stale_spatial_entries", skip_all, err)
+    )]
+    pub fn remove_stale_spatial_entries(&self) -> Result<usize> {
+        let mut inner = self.write_mut()?;
         if inner.closed {
             return Err(SpatioError::DatabaseClosed);
         }
-
-        let key_bytes = Bytes::copy_from_slice(key.as_ref());
-
-        if let Some(item) = inner.remove_item(&key_bytes) {
-            inner.write_delete_to_aof_if_needed(&key_bytes)?;
-            Ok(Some(item.value))
-        } else {
-            Ok(None)
+        let stale = inner.stale_spatial_entries();
+        for entry in &stale {
+            inner
+                .index_manager
+                .remove_point(&entry.prefix, &entry.orphaned_point)?;
         }
+        Ok(stale.len())
     }
 
-    /// Execute multiple operations atomically
-    pub fn atomic<F, R>(&self, f: F) -> Result<R>
-    where
-        F: FnOnce(&mut AtomicBatch) -> Result<R>,
-    {
-        let mut batch = AtomicBatch::new(self.clone());
-        let result = f(&mut batch)?;
-        batch.commit()?;
-        Ok(result)
-    }
-
-    /// Insert a geographic point with automatic spatial indexing.
-    ///
-    /// Points are automatically indexed for spatial queries. The system
-    /// chooses the optimal indexing strategy based on data patterns.
+    /// Inserts a key-value pair into the database.
     ///
     /// # Arguments
     ///
-    /// * `prefix` - Namespace for the point (e.g., "cities", "sensors")
-    /// * `point` - Geographic coordinates
-    /// * `data` - Associated data to store with the point
+    /// * `key` - The key to store
+    /// * `value` - The value to associate with the key
     /// * `opts` - Optional settings like TTL
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use spatio::{Spatio, Point};
+    /// use spatio::{Spatio, SetOptions};
+    /// use std::time::Duration;
     ///
     /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let db = Spatio::memory()?;
-    /// let nyc = Point::new(40.7128, -74.0060);
     ///
-    /// db.insert_point("cities", &nyc, b"New York City", None)?;
+    /// // Simple insert
+    /// db.insert("user:123", b"John Doe", None)?;
+    ///
+    /// // Insert with TTL
+    /// let opts = SetOptions::with_ttl(Duration::from_secs(300));
+    /// db.insert("session:abc", b"user_data", Some(opts))?;
     /// # Ok(())
     /// # }
     /// ```
-    pub fn insert_point(
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.insert",
+            skip_all,
+            fields(
+                db.key_length = key.as_ref().len() as i64,
+                db.value_length = value.as_ref().len() as i64,
+                db.has_ttl = has_ttl(&opts),
+            ),
+            err
+        )
+    )]
+    pub fn insert(
         &self,
-        prefix: &str,
-        point: &Point,
-        value: &[u8],
+        key: impl AsRef<[u8]>,
+        value: impl AsRef<[u8]>,
         opts: Option<SetOptions>,
-    ) -> Result<()> {
-        let data_bytes = value;
-        let data_ref = Bytes::copy_from_slice(data_bytes);
-
-        // Generate geohash key for automatic indexing
-        let geohash = point
-            .to_geohash(8)
-            .map_err(|_| SpatioError::InvalidGeohash)?;
-        let key = SpatialKey::geohash(prefix, &geohash);
-        let key_bytes = Bytes::copy_from_slice(key.as_bytes());
-
-        // Single lock acquisition for both operations
-        let mut inner = self.write()?;
-
-        // Insert into main storage
-        let item = match opts {
-            Some(SetOptions { ttl: Some(ttl), .. }) => DbItem::with_ttl(data_ref.clone(), ttl),
-            Some(SetOptions {
-                expires_at: Some(expires_at),
-                ..
-            }) => DbItem::with_expiration(data_ref.clone(), expires_at),
-            _ => DbItem::new(data_ref.clone()),
-        };
-
-        inner.insert_item(key_bytes.clone(), item);
-
-        // Add to spatial index
-        inner.index_manager.insert_point(prefix, point, &data_ref)?;
+    ) -> Result<Option<Bytes>> {
+        let mut inner = self.write_mut()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
 
-        inner.write_to_aof_if_needed(&key_bytes, value, opts.as_ref())?;
-        Ok(())
+        inner.set(key.as_ref(), value.as_ref(), opts)
     }
 
-    /// Find nearby points within a radius.
+    /// Combine a value with the one already stored under a key.
     ///
-    /// Uses spatial indexing for efficient queries. Results are ordered
-    /// by distance from the query point.
+    /// Reads the current value, calls `merge_fn(value, existing)` and
+    /// stores the result, all under the write lock, so concurrent merges
+    /// on the same key never lose an update. The result is written to the
+    /// AOF like any other insert. A key that is missing or expired is
+    /// merged with `None`. An existing expiration is kept.
+    ///
+    /// See `Mergers` for ready-made merge functions.
     ///
     /// # Arguments
     ///
-    /// * `prefix` - Namespace to search in
-    /// * `center` - Center point for the search
-    /// * `radius_meters` - Search radius in meters
-    /// * `limit` - Maximum number of results to return
+    /// * `key` - Key to merge into
+    /// * `value` - Incoming value
+    /// * `merge_fn` - Called with the incoming value and the stored value,
+    ///   returns the value to store. It runs while the lock is held, so it
+    ///   must not call methods on this `DB`.
+    ///
+    /// # Returns
+    ///
+    /// The merged value that was stored
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use spatio::{Spatio, Point};
+    /// use spatio::{Mergers, Spatio};
     ///
     /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let db = Spatio::memory()?;
-    /// let center = Point::new(40.7128, -74.0060);
+    /// db.merge("device:7:events", b"online", Mergers::append_log())?;
+    /// let log = db.merge("device:7:events", b"offline", Mergers::append_log())?;
+    /// assert_eq!(log.as_ref(), b"online\noffline");
     ///
-    /// // Find up to 10 points within 1km
-    /// let nearby = db.find_nearby("cities", &center, 1000.0, 10)?;
+    /// // Any function of (incoming, existing) works, e.g. a counter
+    /// let add = |new: &[u8], existing: Option<&[u8]>| {
+    ///     let parse = |v: &[u8]| std::str::from_utf8(v).ok()?.parse::<u64>().ok();
+    ///     let total = parse(new).unwrap_or(0) + existing.and_then(parse).unwrap_or(0);
+    ///     total.to_string().into_bytes()
+    /// };
+    /// db.merge("visits", b"2", add)?;
+    /// assert_eq!(db.merge("visits", b"3", add)?.as_ref(), b"5");
     /// # Ok(())
     /// # }
     /// ```
-    pub fn find_nearby(
-        &self,
-        prefix: &str,
-        center: &Point,
-        radius_meters: f64,
-        limit: usize,
-    ) -> Result<Vec<(Point, Bytes)>> {
-        let inner = self.read()?;
-        inner
-            .index_manager
-            .find_nearby(prefix, center, radius_meters, limit)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.merge",
+            skip_all,
+            fields(
+                db.key_length = key.as_ref().len() as i64,
+                db.value_length = value.len() as i64,
+            ),
+            err
+        )
+    )]
+    pub fn merge<F>(&self, key: impl AsRef<[u8]>, value: &[u8], merge_fn: F) -> Result<Bytes>
+    where
+        F: Fn(&[u8], Option<&[u8]>) -> Vec<u8>,
+    {
+        let key = key.as_ref();
+        let mut inner = self.write_mut()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        let existing = inner.keys.get(key).filter(|item| !item.is_expired());
+        let merged = merge_fn(value, existing.map(|item| item.value.as_ref()));
+        let opts = existing
+            .and_then(|item| item.expires_at)
+            .map(SetOptions::with_expiration);
+
+        inner.set(key, &merged, opts)?;
+        Ok(Bytes::from(merged))
     }
 
-    /// Insert a trajectory (sequence of points over time).
+    /// Read a value, compute its replacement and store it under one lock.
     ///
-    /// Trajectories represent the movement of objects over time. Each
-    /// point in the trajectory has a timestamp for temporal queries.
+    /// No other write can land between the read and the write, so this is
+    /// safe for updates that depend on the current value. Returning `None`
+    /// from `f` deletes the key. An existing TTL is kept.
     ///
     /// # Arguments
     ///
-    /// * `object_id` - Unique identifier for the moving object
-    /// * `trajectory` - Sequence of (Point, timestamp) pairs
-    /// * `opts` - Optional settings like TTL for the entire trajectory
+    /// * `key` - Key to update
+    /// * `f` - Called with the current value, or `None` if the key is
+    ///   missing or expired; returns the value to store, or `None` to
+    ///   delete. It runs while the lock is held, so it must not call
+    ///   methods on this `DB`.
+    ///
+    /// # Returns
+    ///
+    /// The value that was stored, or `None` if the key was deleted
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use spatio::{Spatio, Point};
+    /// use spatio::Spatio;
     ///
     /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let db = Spatio::memory()?;
+    /// db.insert("stock:widget", b"3", None)?;
     ///
-    /// let trajectory = vec![
-    ///     (Point::new(40.7128, -74.0060), 1640995200), // Start
-    ///     (Point::new(40.7150, -74.0040), 1640995260), // 1 min later
-    ///     (Point::new(40.7172, -74.0020), 1640995320), // 2 min later
-    /// ];
-    ///
-    /// db.insert_trajectory("vehicle:truck001", &trajectory, None)?;
+    /// // Take one item, removing the key when none are left
+    /// let take = |current: Option<&[u8]>| {
+    ///     let n: u32 = std::str::from_utf8(current?).ok()?.parse().ok()?;
+    ///     (n > 1).then(|| (n - 1).to_string().into_bytes())
+    /// };
+    /// assert_eq!(db.atomic_read_modify_write("stock:widget", take)?.unwrap().as_ref(), b"2");
+    /// db.atomic_read_modify_write("stock:widget", take)?;
+    /// assert!(db.atomic_read_modify_write("stock:widget", take)?.is_none());
+    /// assert!(db.get("stock:widget")?.is_none());
     /// # Ok(())
     /// # }
     /// ```
-    pub fn insert_trajectory(
-        &self,
-        object_id: &str,
-        trajectory: &[(Point, u64)],
-        opts: Option<SetOptions>,
-    ) -> Result<()> {
-        for (i, (point, timestamp)) in trajectory.iter().enumerate() {
-            let key = format!("traj:{}:{:010}:{:06}", object_id, timestamp, i);
-            let point_data = bincode::serialize(&(point, timestamp)).map_err(|e| {
-                SpatioError::SerializationErrorWithContext(format!(
-                    "Failed to serialize trajectory point for object '{}': {}",
-                    object_id, e
-                ))
-            })?;
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.atomic_read_modify_write",
+            skip_all,
+            fields(
+                db.key_length = key.as_ref().len() as i64,
+            ),
+            err
+        )
+    )]
+    pub fn atomic_read_modify_write<F>(&self, key: impl AsRef<[u8]>, f: F) -> Result<Option<Bytes>>
+    where
+        F: FnOnce(Option<&[u8]>) -> Option<Vec<u8>>,
+    {
+        let key = key.as_ref();
+        let mut inner = self.write_mut()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
 
-            self.insert(&key, &point_data, opts.clone())?;
+        let existing = inner.keys.get(key).filter(|item| !item.is_expired());
+        let updated = f(existing.map(|item| item.value.as_ref()));
+        let opts = existing
+            .and_then(|item| item.expires_at)
+            .map(SetOptions::with_expiration);
+
+        match updated {
+            Some(value) => {
+                inner.set(key, &value, opts)?;
+                Ok(Some(Bytes::from(value)))
+            }
+            None => {
+                inner.delete_key(key)?;
+                Ok(None)
+            }
         }
-        Ok(())
     }
 
-    /// Query trajectory between timestamps.
+    /// Insert many items at once, for fast initial imports.
     ///
-    /// Returns all trajectory points for an object within the specified
-    /// time range, ordered by timestamp.
+    /// Items are stored in memory first, then the final value of each
+    /// loaded key is appended to the AOF in one pass rather than one write
+    /// and flush per item. The AOF is synced every
+    /// `Config::bulk_load_sync_after_bytes` and once at the end. Watchers
+    /// are notified as for `insert`.
+    ///
+    /// The write lock is held for the whole load. If an item fails to
+    /// store, the items before it are still persisted and the error is
+    /// returned.
     ///
     /// # Arguments
     ///
-    /// * `object_id` - The object to query
-    /// * `start_time` - Start of time range (unix timestamp)
-    /// * `end_time` - End of time range (unix timestamp)
+    /// * `items` - `(key, value, options)` triples, stored in order, so a
+    ///   later duplicate key wins
+    ///
+    /// # Returns
+    ///
+    /// The number of items loaded, the bytes appended to the AOF and the
+    /// time taken
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use spatio::{Spatio, Point};
+    /// use spatio::Spatio;
     ///
     /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let db = Spatio::memory()?;
+    /// let rows = (0..1000).map(|i| (format!("user:{}", i), format!("name {}", i), None));
     ///
-    /// // Query trajectory for first hour
-    /// let path = db.query_trajectory("vehicle:truck001", 1640995200, 1640998800)?;
-    /// println!("Found {} trajectory points", path.len());
+    /// let stats = db.bulk_load(rows)?;
+    /// assert_eq!(stats.items_loaded, 1000);
+    /// assert_eq!(db.get("user:42")?.unwrap().as_ref(), b"name 42");
     /// # Ok(())
     /// # }
     /// ```
-    pub fn query_trajectory(
-        &self,
-        object_id: &str,
-        start_time: u64,
-        end_time: u64,
-    ) -> Result<Vec<(Point, u64)>> {
-        let mut results = Vec::new();
-        let prefix = format!("traj:{}:", object_id);
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.bulk_load", skip_all, err)
+    )]
+    pub fn bulk_load<I, K, V>(&self, items: I) -> Result<BulkLoadStats>
+    where
+        I: IntoIterator<Item = (K, V, Option<SetOptions>)>,
+        K: AsRef<[u8]>,
+        V: AsRef<[u8]>,
+    {
+        let started = clock::now();
+        let mut inner = self.write_mut()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
 
-        let inner = self.read()?;
-        for (key, item) in inner.keys.range(Bytes::from(prefix.clone())..) {
-            if !key.starts_with(prefix.as_bytes()) {
+        let mut loaded = IndexSet::new();
+        let mut failed = None;
+        for (key, value, opts) in items {
+            let key = Bytes::copy_from_slice(key.as_ref());
+            if let Err(e) = inner.store(key.clone(), value.as_ref(), opts.as_ref()) {
+                failed = Some(e);
                 break;
             }
+            loaded.insert(key);
+        }
+        // Persist what was stored even if an item failed, so memory and
+        // the AOF agree
+        let bytes_written = inner.persist_bulk(&loaded)?;
+        if let Some(e) = failed {
+            return Err(e);
+        }
 
-            if item.is_expired() {
-                continue;
-            }
+        Ok(BulkLoadStats {
+            items_loaded: loaded.len(),
+            bytes_written,
+            duration: clock::now().duration_since(started).unwrap_or_default(),
+        })
+    }
 
-            match bincode::deserialize::<(Point, u64)>(&item.value) {
-                Ok((point, timestamp)) => {
-                    if timestamp >= start_time && timestamp <= end_time {
-                        results.push((point, timestamp));
-                    }
-                }
-                Err(e) => {
-                    // Log deserialization error but continue processing other points
-                    eprintln!(
-                        "Warning: Failed to deserialize trajectory point for object '{}': {}",
-                        object_id, e
-                    );
-                }
-            }
+    /// Get a value by key
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.get",
+            skip_all,
+            fields(
+                db.key_length = key.as_ref().len() as i64,
+            ),
+            err
+        )
+    )]
+    pub fn get(&self, key: impl AsRef<[u8]>) -> Result<Option<Bytes>> {
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
         }
+        inner.metrics.record_get();
 
-        results.sort_by_key(|(_, timestamp)| *timestamp);
-        Ok(results)
+        let key_bytes = Bytes::copy_from_slice(key.as_ref());
+
+        if let Some(item) = inner.get_item(&key_bytes)
+            && !item.is_expired()
+        {
+            inner.touch(&key_bytes);
+            return Ok(Some(item.value.clone()));
+        }
+        Ok(None)
     }
 
-    /// Check if there are any points within a circular region.
+    /// Check whether a key is stored and has not expired.
     ///
-    /// This method checks if any points exist within the specified distance
-    /// from a center point in the given namespace.
+    /// With `Config::bloom_filter_false_positive_rate` set, keys the
+    /// filter rules out return `false` without taking the database lock.
+    /// Other keys, including the filter's false positives, are checked
+    /// under the read lock.
     ///
-    /// # Arguments
+    /// # Examples
     ///
-    /// * `prefix` - Namespace to search in
-    /// * `center` - Center point of the circular region
-    /// * `radius_meters` - Radius in meters
+    /// ```rust
+    /// use spatio::{Config, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let config = Config::default().with_bloom_filter(0.01, 10_000);
+    /// let db = Spatio::memory_with_config(config)?;
+    /// db.insert("session:abc", b"user:1", None)?;
+    ///
+    /// assert!(db.exists("session:abc")?);
+    /// assert!(!db.exists("session:xyz")?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.exists",
+            skip_all,
+            fields(
+                db.key_length = key.as_ref().len() as i64,
+            ),
+            err
+        )
+    )]
+    pub fn exists(&self, key: impl AsRef<[u8]>) -> Result<bool> {
+        let key = key.as_ref();
+        if let Some(ref filter) = self.key_filter
+            && !filter.may_contain(key)
+        {
+            return Ok(false);
+        }
+
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+        Ok(inner
+            .get_item(&Bytes::copy_from_slice(key))
+            .is_some_and(|item| !item.is_expired()))
+    }
+
+    /// Get the time left before a key expires.
+    ///
+    /// Returns `None` if the key does not exist, has already expired, or
+    /// was stored without a TTL.
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use spatio::{Spatio, Point};
+    /// use spatio::{SetOptions, Spatio};
+    /// use std::time::Duration;
     ///
     /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let db = Spatio::memory()?;
-    /// let center = Point::new(40.7128, -74.0060);
+    /// db.insert("session:abc", b"token", Some(SetOptions::with_ttl(Duration::from_secs(60))))?;
+    /// db.insert("user:1", b"alice", None)?;
     ///
-    /// // Check if there are any cities within 50km
-    /// let has_nearby = db.contains_point("cities", &center, 50_000.0)?;
+    /// let ttl = db.remaining_ttl("session:abc")?.unwrap();
+    /// assert!(ttl <= Duration::from_secs(60));
+    /// assert!(db.remaining_ttl("user:1")?.is_none());
     /// # Ok(())
     /// # }
     /// ```
-    pub fn contains_point(&self, prefix: &str, center: &Point, radius_meters: f64) -> Result<bool> {
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.remaining_ttl",
+            skip_all,
+            fields(
+                db.key_length = key.as_ref().len() as i64,
+            ),
+            err
+        )
+    )]
+    pub fn remaining_ttl(&self, key: impl AsRef<[u8]>) -> Result<Option<Duration>> {
+        Ok(self
+            .key_expires_at(key)?
+            .and_then(|expires_at| expires_at.duration_since(clock::now()).ok()))
+    }
+
+    /// Get the absolute time at which a key expires.
+    ///
+    /// Returns `None` if the key does not exist, has already expired, or
+    /// was stored without a TTL.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.key_expires_at",
+            skip_all,
+            fields(
+                db.key_length = key.as_ref().len() as i64,
+            ),
+            err
+        )
+    )]
+    pub fn key_expires_at(&self, key: impl AsRef<[u8]>) -> Result<Option<SystemTime>> {
         let inner = self.read()?;
-        inner
-            .index_manager
-            .contains_point(prefix, center, radius_meters)
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        let key_bytes = Bytes::copy_from_slice(key.as_ref());
+        Ok(inner
+            .get_item(&key_bytes)
+            .filter(|item| !item.is_expired())
+            .and_then(|item| item.expires_at))
     }
 
-    /// Check if there are any points within a bounding box.
+    /// Set when an existing key expires, without rewriting its value.
     ///
-    /// This method checks if any points exist within the specified
-    /// rectangular region in the given namespace.
+    /// Only the expiration is changed, and only a small EXPIRE record is
+    /// written to the AOF. A time in the past makes the key expire
+    /// straight away.
     ///
-    /// # Arguments
+    /// # Returns
     ///
-    /// * `prefix` - Namespace to search in
-    /// * `min_lat` - Minimum latitude of bounding box
-    /// * `min_lon` - Minimum longitude of bounding box
-    /// * `max_lat` - Maximum latitude of bounding box
-    /// * `max_lon` - Maximum longitude of bounding box
+    /// `true` if the key exists, `false` if it is missing or already expired
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use spatio::{Spatio, Point};
+    /// use spatio::Spatio;
+    /// use std::time::{Duration, SystemTime};
     ///
     /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let db = Spatio::memory()?;
+    /// db.insert("session:abc", b"token", None)?;
     ///
-    /// // Check if there are any points in Manhattan area
-    /// let has_points = db.intersects_bounds("sensors", 40.7, -74.1, 40.8, -73.9)?;
+    /// let deadline = SystemTime::now() + Duration::from_secs(3600);
+    /// assert!(db.expire_at("session:abc", deadline)?);
+    /// assert_eq!(db.key_expires_at("session:abc")?, Some(deadline));
+    /// assert!(!db.expire_at("session:missing", deadline)?);
     /// # Ok(())
     /// # }
     /// ```
-    pub fn intersects_bounds(
-        &self,
-        prefix: &str,
-        min_lat: f64,
-        min_lon: f64,
-        max_lat: f64,
-        max_lon: f64,
-    ) -> Result<bool> {
-        let inner = self.read()?;
-        inner
-            .index_manager
-            .intersects_bounds(prefix, min_lat, min_lon, max_lat, max_lon)
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.expire_at",
+            skip_all,
+            fields(
+                db.key_length = key.as_ref().len() as i64,
+            ),
+            err
+        )
+    )]
+    pub fn expire_at(&self, key: impl AsRef<[u8]>, expires_at: SystemTime) -> Result<bool> {
+        let mut inner = self.write_mut()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        inner.set_expiration(key.as_ref(), expires_at)
     }
 
-    /// Count points within a distance from a center point.
+    /// Make an existing key expire after `ttl`, without rewriting its value.
     ///
-    /// This method counts how many points exist within the specified
-    /// distance from a center point without returning the actual points.
-    /// More efficient than `find_nearby` when you only need the count.
+    /// Shorthand for `expire_at` with the current time plus `ttl`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Spatio;
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert("session:abc", b"token", None)?;
+    ///
+    /// assert!(db.expire_in("session:abc", Duration::from_secs(300))?);
+    /// assert!(db.remaining_ttl("session:abc")?.is_some());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.expire_in",
+            skip_all,
+            fields(
+                db.key_length = key.as_ref().len() as i64,
+            ),
+            err
+        )
+    )]
+    pub fn expire_in(&self, key: impl AsRef<[u8]>, ttl: Duration) -> Result<bool> {
+        self.expire_at(key, clock::now() + ttl)
+    }
+
+    /// Delete a key atomically
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.delete",
+            skip_all,
+            fields(
+                db.key_length = key.as_ref().len() as i64,
+            ),
+            err
+        )
+    )]
+    pub fn delete(&self, key: impl AsRef<[u8]>) -> Result<Option<Bytes>> {
+        let mut inner = self.write_mut()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        inner.delete_key(key.as_ref())
+    }
+
+    /// Scan keys under a prefix one page at a time.
+    ///
+    /// Returns up to `count` live key-value pairs in key order, starting
+    /// after `cursor` (or at the beginning of the prefix when `cursor` is
+    /// `None`), together with the cursor for the next page. The next
+    /// cursor is `None` once the prefix is exhausted. Only one page is
+    /// copied out of the database per call, so large prefixes can be
+    /// walked without loading every item at once.
     ///
     /// # Arguments
     ///
-    /// * `prefix` - Namespace to search in
-    /// * `center` - Center point for the search
-    /// * `radius_meters` - Search radius in meters
+    /// * `prefix` - Key prefix to scan
+    /// * `cursor` - Cursor returned by the previous call, or `None` to start
+    /// * `count` - Maximum number of items per page (at least one is returned)
     ///
     /// # Examples
     ///
     /// ```rust
-    /// use spatio::{Spatio, Point};
+    /// use spatio::Spatio;
     ///
     /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let db = Spatio::memory()?;
-    /// let center = Point::new(40.7128, -74.0060);
+    /// for i in 0..5 {
+    ///     db.insert(format!("user:{i}"), b"data", None)?;
+    /// }
     ///
-    /// // Count how many sensors are within 1km
-    /// let count = db.count_within_distance("sensors", &center, 1000.0)?;
-    /// println!("Found {} sensors within 1km", count);
+    /// let mut cursor = None;
+    /// let mut seen = 0;
+    /// loop {
+    ///     let (page, next) = db.scan_prefix(b"user:", cursor.as_deref(), 2)?;
+    ///     seen += page.len();
+    ///     match next {
+    ///         Some(next) => cursor = Some(next),
+    ///         None => break,
+    ///     }
+    /// }
+    /// assert_eq!(seen, 5);
     /// # Ok(())
     /// # }
     /// ```
-    pub fn count_within_distance(
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.scan_prefix",
+            skip_all,
+            fields(
+                db.prefix_length = prefix.len() as i64,
+                db.count = count as i64,
+            ),
+            err
+        )
+    )]
+    pub fn scan_prefix(
         &self,
-        prefix: &str,
-        center: &Point,
-        radius_meters: f64,
-    ) -> Result<usize> {
+        prefix: &[u8],
+        cursor: Option<&[u8]>,
+        count: usize,
+    ) -> Result<ScanPage> {
         let inner = self.read()?;
-        inner
-            .index_manager
-            .count_within_distance(prefix, center, radius_meters)
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        let mut entries = inner.scan_filtered(prefix, cursor, &|_, _| true);
+        let page: Vec<(Bytes, Bytes)> = entries
+            .by_ref()
+            .take(count.max(1))
+            .map(|(key, item)| (key.clone(), item.value.clone()))
+            .collect();
+        let next = match entries.next() {
+            Some(_) => page.last().map(|(key, _)| key.clone()),
+            None => None,
+        };
+
+        Ok((page, next))
     }
 
-    /// Find all points within a bounding box.
+    /// Scan keys under a prefix, keeping only items a predicate accepts.
     ///
-    /// This method returns all points that fall within the specified
-    /// rectangular region, up to the specified limit.
+    /// `filter` sees each live key and value while the read lock is held,
+    /// so rejected items are never copied out of the database. `filter`
+    /// must not call methods on this `DB`.
     ///
     /// # Arguments
     ///
-    /// * `prefix` - Namespace to search in
-    /// * `min_lat` - Minimum latitude of bounding box
-    /// * `min_lon` - Minimum longitude of bounding box
-    /// * `max_lat` - Maximum latitude of bounding box
-    /// * `max_lon` - Maximum longitude of bounding box
-    /// * `limit` - Maximum number of results to return
+    /// * `prefix` - Key prefix to scan
+    /// * `filter` - Called with the key and value bytes; `true` keeps the item
+    /// * `limit` - Maximum number of items to return
+    ///
+    /// # Returns
+    ///
+    /// Up to `limit` accepted `(key, value)` pairs, in key order
     ///
     /// # Examples
     ///
+    /// Points are stored under `prefix:gh:<geohash>` keys, so metadata kept
+    /// in their values can be filtered on directly:
+    ///
     /// ```rust
-    /// use spatio::{Spatio, Point};
+    /// use spatio::{Point, Spatio};
     ///
     /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
     /// let db = Spatio::memory()?;
+    /// db.insert_point("poi", &Point::new(40.7580, -73.9855), br#"{"kind":"cafe"}"#, None)?;
+    /// db.insert_point("poi", &Point::new(40.7484, -73.9857), br#"{"kind":"museum"}"#, None)?;
+    /// db.insert_point("poi", &Point::new(40.7061, -74.0087), br#"{"kind":"cafe"}"#, None)?;
     ///
-    /// // Find all sensors in Manhattan area
-    /// let points = db.find_within_bounds("sensors", 40.7, -74.1, 40.8, -73.9, 100)?;
-    /// println!("Found {} sensors in Manhattan", points.len());
+    /// let cafes = db.scan_with_filter(
+    ///     b"poi:gh:",
+    ///     |_key, value| {
+    ///         serde_json::from_slice::<serde_json::Value>(value)
+    ///             .is_ok_and(|meta| meta["kind"] == "cafe")
+    ///     },
+    ///     100,
+    /// )?;
+    /// assert_eq!(cafes.len(), 2);
     /// # Ok(())
     /// # }
     /// ```
-    pub fn find_within_bounds(
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.scan_with_filter",
+            skip_all,
+            fields(
+                db.prefix_length = prefix.len() as i64,
+                db.limit = limit as i64,
+            ),
+            err
+        )
+    )]
+    pub fn scan_with_filter<F>(
         &self,
-        prefix: &str,
-        min_lat: f64,
-        min_lon: f64,
-        max_lat: f64,
-        max_lon: f64,
+        prefix: &[u8],
+        filter: F,
         limit: usize,
-    ) -> Result<Vec<(Point, Bytes)>> {
+    ) -> Result<Vec<(Bytes, Bytes)>>
+    where
+        F: Fn(&[u8], &[u8]) -> bool,
+    {
         let inner = self.read()?;
-        inner
-            .index_manager
-            .find_within_bounds(prefix, min_lat, min_lon, max_lat, max_lon, limit)
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        Ok(inner
+            .scan_filtered(prefix, None, &filter)
+            .take(limit)
+            .map(|(key, item)| (key.clone(), item.value.clone()))
+            .collect())
     }
 
-    /// Force sync to disk
-    /// Force sync all pending writes to disk.
+    /// Page through the items under a prefix that a predicate accepts.
     ///
-    /// This method flushes the AOF buffer and calls fsync to ensure all data
-    /// is durably written to disk. Useful before critical operations or when
-    /// you need to guarantee data persistence.
+    /// Works like `scan_prefix`, but only accepted items count towards a
+    /// page. Each call holds the read lock for one page, so writers are
+    /// not blocked for the whole scan.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Key prefix to scan
+    /// * `cursor` - Cursor returned by the previous call, or `None` to start
+    /// * `filter` - Called with the key and value bytes; `true` keeps the item
+    /// * `count` - Maximum number of items per page (at least one is returned)
+    ///
+    /// # Returns
+    ///
+    /// The accepted `(key, value)` pairs of this page and the cursor for the
+    /// next one (`None` when no accepted items remain)
     ///
     /// # Examples
     ///
@@ -704,36 +1205,83 @@ impl DB {
     /// use spatio::Spatio;
     ///
     /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
-    /// let db = Spatio::open("my_data.db")?;
-    /// db.insert("critical_key", b"important_data", None)?;
+    /// let db = Spatio::memory()?;
+    /// for i in 0..10 {
+    ///     db.insert(format!("reading:{i}"), i.to_string(), None)?;
+    /// }
     ///
-    /// // Ensure data is on disk before continuing
-    /// db.sync()?;
+    /// let even = |_key: &[u8], value: &[u8]| value[0].is_multiple_of(2);
+    /// let mut cursor = None;
+    /// let mut seen = 0;
+    /// loop {
+    ///     let (page, next) = db.scan_with_filter_paginated(b"reading:", cursor.as_deref(), even, 2)?;
+    ///     seen += page.len();
+    ///     match next {
+    ///         Some(next) => cursor = Some(next),
+    ///         None => break,
+    ///     }
+    /// }
+    /// assert_eq!(seen, 5);
     /// # Ok(())
     /// # }
     /// ```
-    pub fn sync(&self) -> Result<()> {
-        let mut inner = self.write()?;
-        if let Some(ref mut aof_file) = inner.aof_file {
-            aof_file.sync()?;
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.scan_with_filter_paginated",
+            skip_all,
+            fields(
+                db.prefix_length = prefix.len() as i64,
+                db.count = count as i64,
+            ),
+            err
+        )
+    )]
+    pub fn scan_with_filter_paginated<F>(
+        &self,
+        prefix: &[u8],
+        cursor: Option<&[u8]>,
+        filter: F,
+        count: usize,
+    ) -> Result<ScanPage>
+    where
+        F: Fn(&[u8], &[u8]) -> bool,
+    {
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
         }
-        Ok(())
+
+        let mut entries = inner.scan_filtered(prefix, cursor, &filter);
+        let page: Vec<(Bytes, Bytes)> = entries
+            .by_ref()
+            .take(count.max(1))
+            .map(|(key, item)| (key.clone(), item.value.clone()))
+            .collect();
+        let next = match entries.next() {
+            Some(_) => page.last().map(|(key, _)| key.clone()),
+            None => None,
+        };
+
+        Ok((page, next))
     }
 
-    /// Gracefully close the database.
+    /// Group the keys under a prefix by a field of their values.
     ///
-    /// This method performs a clean shutdown by:
-    /// 1. Marking the database as closed (rejecting new operations)
-    /// 2. Flushing any pending writes to the AOF
-    /// 3. Syncing the AOF to disk (fsync)
-    /// 4. Releasing resources
+    /// Every live key starting with `prefix` has its value passed to
+    /// `field_extractor`; keys for which it returns `Some(field)` are
+    /// indexed under that field value, and the rest are left out. The
+    /// read lock is held during the scan, so `field_extractor` must not
+    /// call methods on this `DB`.
     ///
-    /// After calling `close()`, any further operations on this database
-    /// instance will return `DatabaseClosed` errors.
+    /// # Arguments
     ///
-    /// **Note:** The database is also automatically closed when dropped,
-    /// so explicitly calling `close()` is optional but recommended for
-    /// explicit error handling.
+    /// * `prefix` - Key prefix to index
+    /// * `field_extractor` - Parses the indexed field out of a value
+    ///
+    /// # Returns
+    ///
+    /// An `InvertedIndex` snapshot for `lookup` and `range_lookup` queries
     ///
     /// # Examples
     ///
@@ -741,321 +1289,8820 @@ impl DB {
     /// use spatio::Spatio;
     ///
     /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
-    /// let mut db = Spatio::open("my_data.db")?;
-    /// db.insert("key", b"value", None)?;
+    /// let db = Spatio::memory()?;
+    /// db.insert("sensor:1", "status=ok;temp=18", None)?;
+    /// db.insert("sensor:2", "status=fault;temp=23", None)?;
+    /// db.insert("sensor:3", "status=ok;temp=25", None)?;
     ///
-    /// // Explicitly close and handle errors
-    /// db.close()?;
+    /// let by_temp = db.build_inverted_index("sensor:", |value| {
+    ///     let text = std::str::from_utf8(value).ok()?;
+    ///     text.split(';').find_map(|field| field.strip_prefix("temp=")).map(String::from)
+    /// })?;
+    ///
+    /// assert_eq!(by_temp.lookup("18"), vec!["sensor:1"]);
+    /// // Temperatures of 20 and above
+    /// assert_eq!(by_temp.range_lookup("20", "99"), vec!["sensor:2", "sensor:3"]);
     /// # Ok(())
     /// # }
     /// ```
-    pub fn close(&mut self) -> Result<()> {
-        let mut inner = self.write()?;
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.build_inverted_index",
+            skip_all,
+            fields(db.prefix = prefix),
+            err
+        )
+    )]
+    pub fn build_inverted_index(
+        &self,
+        prefix: &str,
+        field_extractor: impl Fn(&[u8]) -> Option<String>,
+    ) -> Result<InvertedIndex> {
+        let inner = self.read()?;
         if inner.closed {
             return Err(SpatioError::DatabaseClosed);
         }
 
-        inner.closed = true;
+        let mut entries: BTreeMap<String, Vec<Bytes>> = BTreeMap::new();
+        let live = inner
+            .keys
+            .range(Bytes::copy_from_slice(prefix.as_bytes())..)
+            .take_while(|(key, _)| key.starts_with(prefix.as_bytes()))
+            .filter(|(_, item)| !item.is_expired());
+        for (key, item) in live {
+            if let Some(field) = field_extractor(&item.value) {
+                entries.entry(field).or_default().push(key.clone());
+            }
+        }
+        Ok(InvertedIndex::new(entries))
+    }
+
+    /// Run read-only logic with the read lock held throughout.
+    ///
+    /// Use this for operations that need several reads to see one
+    /// consistent state, such as multi-key lookups or aggregations. The
+    /// closure gets a `DbView` and its result is returned as is.
+    ///
+    /// `f` must not call methods on this `DB`: the lock is already held, so
+    /// doing so deadlocks. Debug builds panic instead of deadlocking.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Spatio;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert("order:1", b"12", None)?;
+    /// db.insert("order:2", b"30", None)?;
+    ///
+    /// let total: u32 = db.view(|view| {
+    ///     Ok(view
+    ///         .scan_prefix(b"order:")
+    ///         .filter_map(|(_, item)| std::str::from_utf8(&item.value).ok()?.parse::<u32>().ok())
+    ///         .sum())
+    /// })?;
+    /// assert_eq!(total, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.view", skip_all, err)
+    )]
+    pub fn view<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&DbView<'_>) -> Result<R>,
+    {
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        let _scope = ViewScope::enter(self);
+        f(&DbView::new(&inner))
+    }
+
+    /// Run logic that reads and writes with the write lock held throughout.
+    ///
+    /// Other readers and writers wait until the closure returns, so a read
+    /// followed by a dependent write cannot be interleaved with another
+    /// thread's write. Writes are applied and persisted as they are made;
+    /// unlike `atomic`, an error part way through does not undo earlier
+    /// writes.
+    ///
+    /// `f` must not call methods on this `DB`: the lock is already held, so
+    /// doing so deadlocks. Debug builds panic instead of deadlocking.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::ReadOnly` for read-only databases, besides any
+    /// error returned by `f`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Spatio;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert("counter", b"1", None)?;
+    ///
+    /// db.view_mut(|view| {
+    ///     let current: u32 = view
+    ///         .view()
+    ///         .get("counter")
+    ///         .and_then(|value| std::str::from_utf8(value).ok()?.parse().ok())
+    ///         .unwrap_or(0);
+    ///     view.insert("counter", (current + 1).to_string(), None)?;
+    ///     Ok(())
+    /// })?;
+    /// assert_eq!(db.get("counter")?.unwrap().as_ref(), b"2");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.view_mut", skip_all, err)
+    )]
+    pub fn view_mut<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&mut DbViewMut<'_>) -> Result<R>,
+    {
+        let mut inner = self.write_mut()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        let _scope = ViewScope::enter(self);
+        f(&mut DbViewMut::new(&mut inner))
+    }
+
+    /// Iterate over every live key without copying.
+    ///
+    /// The returned handle holds the database read lock for its whole
+    /// lifetime. Other threads cannot write until it is dropped, and
+    /// writing from the same thread while holding it deadlocks.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Spatio;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert("user:1", b"Alice", None)?;
+    /// db.insert("user:2", b"Bob", None)?;
+    ///
+    /// let keys = db.iter_keys()?;
+    /// let names: Vec<&[u8]> = keys.iter().map(|key| key.as_ref()).collect();
+    /// assert_eq!(names, vec![b"user:1".as_ref(), b"user:2".as_ref()]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.iter_keys", skip_all, err)
+    )]
+    pub fn iter_keys(&self) -> Result<KeyIterator<'_>> {
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+        Ok(KeyIterator::new(inner))
+    }
+
+    /// Iterate over every live key and item without copying.
+    ///
+    /// Like `iter_keys`, the read lock is held until the handle is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Spatio;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert("user:1", b"Alice", None)?;
+    ///
+    /// let items = db.iter_items()?;
+    /// for (key, item) in &items {
+    ///     println!("{:?} = {:?}", key, item.value);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.iter_items", skip_all, err)
+    )]
+    pub fn iter_items(&self) -> Result<ItemIterator<'_>> {
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+        Ok(ItemIterator::new(inner))
+    }
+
+    /// Take a read-only, point-in-time copy of every live item.
+    ///
+    /// Unlike `iter_items`, the snapshot does not hold the database lock:
+    /// it is taken under the read lock and released right away, and later
+    /// writes do not show up in it. Use it for backups and analytics that
+    /// should see one consistent state without blocking writers while they
+    /// run. Items are stored as `insert` wrote them, including the
+    /// `ValueType` of values written by the typed insert methods.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Spatio;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert("user:1", b"Alice", None)?;
+    ///
+    /// let snapshot = db.snapshot()?;
+    /// db.insert("user:2", b"Bob", None)?;
+    ///
+    /// assert_eq!(snapshot.len()?, 1);
+    /// assert!(snapshot.get(b"user:2")?.is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.snapshot", skip_all, err)
+    )]
+    pub fn snapshot(&self) -> Result<Box<dyn StorageSnapshot>> {
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        let now = clock::now();
+        let items = inner
+            .keys
+            .iter()
+            .filter(|(_, item)| !item.is_expired_at(now))
+            .map(|(key, item)| (key.clone(), item.clone()))
+            .collect();
+        Ok(Box::new(MemorySnapshot::from_items(items)))
+    }
+
+    /// Watch a key for changes.
+    ///
+    /// The returned receiver gets a `KeyEvent` every time the key is set,
+    /// deleted or expired by `cleanup_expired`. Dropping the receiver
+    /// unregisters the watch.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{KeyEventType, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let events = db.watch("vehicle:42")?;
+    ///
+    /// db.insert("vehicle:42", b"moving", None)?;
+    ///
+    /// let event = events.recv()?;
+    /// assert_eq!(event.event_type, KeyEventType::Set);
+    /// assert_eq!(event.new_value.unwrap().as_ref(), b"moving");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.watch",
+            skip_all,
+            fields(
+                db.key_length = key.as_ref().len() as i64,
+            ),
+            err
+        )
+    )]
+    pub fn watch(&self, key: impl AsRef<[u8]>) -> Result<Receiver<KeyEvent>> {
+        let mut inner = self.write()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        Ok(inner
+            .watchers
+            .watch_key(Bytes::copy_from_slice(key.as_ref())))
+    }
+
+    /// Watch every key under a prefix for changes.
+    ///
+    /// Works like `watch`, for all current and future keys starting with
+    /// `prefix`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Spatio;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let events = db.watch_prefix(b"sensor:")?;
+    ///
+    /// db.insert("sensor:1", b"21.5", None)?;
+    /// db.insert("sensor:2", b"19.0", None)?;
+    ///
+    /// assert_eq!(events.try_iter().count(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.watch_prefix",
+            skip_all,
+            fields(
+                db.prefix_length = prefix.len() as i64,
+            ),
+            err
+        )
+    )]
+    pub fn watch_prefix(&self, prefix: &[u8]) -> Result<Receiver<KeyEvent>> {
+        let mut inner = self.write()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        Ok(inner.watchers.watch_prefix(Bytes::copy_from_slice(prefix)))
+    }
+
+    /// Watch the number of keys under a prefix.
+    ///
+    /// The returned receiver gets a `CountEvent` when the count rises above
+    /// `threshold` and again when it falls back to `threshold` or below,
+    /// whether keys are inserted, deleted, evicted or expired. If the count
+    /// is already above the threshold an `Exceeded` event is queued
+    /// straight away. Pass the returned handle to `unwatch_prefix_count` to
+    /// stop watching; dropping the receiver also unregisters the watch.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{CountEventType, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let (handle, events) = db.watch_prefix_count("queue:", 1)?;
+    ///
+    /// db.insert("queue:1", b"job", None)?;
+    /// db.insert("queue:2", b"job", None)?;
+    ///
+    /// let event = events.recv()?;
+    /// assert_eq!(event.event, CountEventType::Exceeded);
+    /// assert_eq!(event.current_count, 2);
+    ///
+    /// assert!(db.unwatch_prefix_count(handle)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.watch_prefix_count",
+            skip_all,
+            fields(
+                db.prefix_length = prefix.len() as i64,
+                db.threshold = threshold as i64,
+            ),
+            err
+        )
+    )]
+    pub fn watch_prefix_count(
+        &self,
+        prefix: &str,
+        threshold: usize,
+    ) -> Result<(WatchHandle, Receiver<CountEvent>)> {
+        let mut inner = self.write()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        let inner = &mut *inner;
+        Ok(inner.watchers.watch_count(
+            Bytes::copy_from_slice(prefix.as_bytes()),
+            threshold,
+            &inner.keys,
+        ))
+    }
+
+    /// Stop a watch registered with `watch_prefix_count`.
+    ///
+    /// Returns whether the watch was still registered.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.unwatch_prefix_count", skip_all, err)
+    )]
+    pub fn unwatch_prefix_count(&self, handle: WatchHandle) -> Result<bool> {
+        let mut inner = self.write()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        Ok(inner.watchers.unwatch_count(handle))
+    }
+
+    /// Remove every key whose TTL has passed.
+    ///
+    /// Expired keys are already hidden from reads; this sweep frees their
+    /// memory, writes DELETE commands to the AOF and notifies watchers with
+    /// `KeyEventType::Expired`.
+    ///
+    /// # Returns
+    ///
+    /// The number of keys removed
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{SetOptions, Spatio};
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let opts = SetOptions::with_ttl(Duration::from_millis(10));
+    /// db.insert("session:abc", b"token", Some(opts))?;
+    ///
+    /// std::thread::sleep(Duration::from_millis(20));
+    /// assert_eq!(db.cleanup_expired()?, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.cleanup_expired", skip_all, err)
+    )]
+    pub fn cleanup_expired(&self) -> Result<usize> {
+        let mut inner = self.write_mut()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        inner.cleanup_expired()
+    }
+
+    /// Purge expired keys and rebuild the spatial index.
+    ///
+    /// Like `cleanup_expired`, every expired key is removed, written to the
+    /// AOF as a DELETE and reported to watchers. The expiration index is
+    /// then pruned and the spatial index rebuilt from the remaining keys,
+    /// dropping entries left behind by expired spatial keys and releasing
+    /// the capacity held by the old index maps.
+    ///
+    /// Set `Config::auto_compact_interval` to run this on a background
+    /// thread instead.
+    ///
+    /// # Returns
+    ///
+    /// The number of keys removed and the key and value bytes they held
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{SetOptions, Spatio};
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let opts = SetOptions::with_ttl(Duration::from_millis(10));
+    /// db.insert("session:abc", b"token", Some(opts))?;
+    ///
+    /// std::thread::sleep(Duration::from_millis(20));
+    /// let stats = db.compact_memory()?;
+    /// assert_eq!(stats.keys_removed, 1);
+    /// assert_eq!(stats.bytes_freed, 16);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.compact_memory", skip_all, err)
+    )]
+    pub fn compact_memory(&self) -> Result<CompactionStats> {
+        let mut inner = self.write_mut()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        inner.compact_memory()
+    }
+
+    /// Write a consistent copy of the database to an AOF file while it
+    /// stays online.
+    ///
+    /// The read lock is held only while the live items are collected,
+    /// which copies no values, so writers are blocked for about as long
+    /// as a scan of the key space. The file is then written without the
+    /// lock, to a temporary path that is synced and renamed over
+    /// `target_path`, so a crash never leaves a partial checkpoint in
+    /// its place. Expired keys are left out, and values are compressed
+    /// like the database's own AOF.
+    ///
+    /// The checkpoint can be opened as a normal database, e.g. with
+    /// `Spatio::open`. Set `Config::checkpoint_interval` to write one
+    /// on a background thread instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_path` - Where to write the checkpoint; an existing file
+    ///   is replaced
+    ///
+    /// # Returns
+    ///
+    /// The number of keys written and the size of the file
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database is closed, `target_path` is the
+    /// database's own AOF, or the file cannot be written.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Point, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("cities", &Point::new(48.8566, 2.3522), b"Paris", None)?;
+    ///
+    /// let backup = std::env::temp_dir().join("checkpoint_demo.aof");
+    /// let stats = db.checkpoint(&backup)?;
+    /// assert_eq!(stats.keys_written, 1);
+    ///
+    /// let restored = Spatio::open(&backup)?;
+    /// assert_eq!(restored.count_within_distance("cities", &Point::new(48.85, 2.35), 5_000.0)?, 1);
+    /// # drop(restored);
+    /// # std::fs::remove_file(backup)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "aof")]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.checkpoint", skip_all, err)
+    )]
+    pub fn checkpoint<P: AsRef<Path>>(&self, target_path: P) -> Result<CheckpointStats> {
+        let target_path = target_path.as_ref();
+        let (items, aof_config) = {
+            let inner = self.read()?;
+            if inner.closed {
+                return Err(SpatioError::DatabaseClosed);
+            }
+            if let Some(ref aof_file) = inner.aof_file
+                && same_file(aof_file.path(), target_path)
+            {
+                return Err(SpatioError::Other(format!(
+                    "Cannot checkpoint to {}: it is the database's own AOF",
+                    target_path.display()
+                )));
+            }
+            inner.checkpoint_items()
+        };
+
+        write_checkpoint(target_path, items, aof_config)
+    }
+
+    /// Apply the commands of an AOF file from `start_offset` on.
+    ///
+    /// This recovers writes logged after a known point, for example
+    /// bringing a database restored from a checkpoint up to date with the
+    /// tail of the live AOF, or reading what is left of a file damaged by
+    /// a crash. Records that cannot be read are handled according to
+    /// `Config::aof_recovery_mode`: the default fails without applying
+    /// anything, `SkipCorrupted` skips them and `Truncate` stops at the
+    /// first one and cuts `aof_file` there.
+    ///
+    /// Applied commands are written to this database's own AOF and
+    /// storage backend, if it has them.
+    ///
+    /// # Arguments
+    ///
+    /// * `aof_file` - The file to replay; must not be this database's AOF
+    /// * `start_offset` - Where to start, which should be the start of a
+    ///   record such as the `final_offset` of an earlier replay
+    ///
+    /// # Returns
+    ///
+    /// How many commands were applied and skipped, and where replay ended
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database is closed or read-only,
+    /// `aof_file` is its own AOF, `start_offset` is past the end of the
+    /// file, or replay stops at a damaged record.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{AOFFile, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let path = std::env::temp_dir().join("replay_demo.aof");
+    /// # let _ = std::fs::remove_file(&path);
+    /// let mut log = AOFFile::open(&path)?;
+    /// log.write_set(b"user:1", b"alice", None)?;
+    /// log.write_set(b"user:2", b"bob", None)?;
+    /// log.sync()?;
+    ///
+    /// let db = Spatio::memory()?;
+    /// let stats = db.replay_aof_from_offset(&mut log, 0)?;
+    /// assert_eq!(stats.commands_replayed, 2);
+    ///
+    /// // Later, apply only what was appended since
+    /// log.write_delete(b"user:1")?;
+    /// let stats = db.replay_aof_from_offset(&mut log, stats.final_offset)?;
+    /// assert_eq!(stats.commands_replayed, 1);
+    /// assert!(db.get("user:1")?.is_none());
+    /// # drop(log);
+    /// # std::fs::remove_file(path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "aof")]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.replay_aof_from_offset",
+            skip_all,
+            fields(db.start_offset = start_offset),
+            err
+        )
+    )]
+    pub fn replay_aof_from_offset(
+        &self,
+        aof_file: &mut AOFFile,
+        start_offset: u64,
+    ) -> Result<ReplayStats> {
+        let mut inner = self.write_mut()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+        if let Some(ref own) = inner.aof_file
+            && same_file(own.path(), aof_file.path())
+        {
+            return Err(SpatioError::Other(
+                "Cannot replay the database's own AOF into itself".to_string(),
+            ));
+        }
+
+        let (commands, stats) =
+            aof_file.replay_from_offset(start_offset, inner.config.aof_recovery_mode)?;
+        for (_, command) in commands {
+            match command {
+                AOFCommand::Set {
+                    key,
+                    value,
+                    expires_at,
+                    value_type,
+                } => {
+                    let item = DbItem {
+                        value: value.clone(),
+                        expires_at,
+                        value_type,
+                    };
+                    inner.restore_item(key.clone(), item);
+                    let opts = expires_at.map(SetOptions::with_expiration);
+                    inner.write_to_aof_if_needed(&key, &value, opts.as_ref())?;
+                }
+                AOFCommand::Delete { key } => {
+                    inner.restore_delete(&key);
+                    inner.write_delete_to_aof_if_needed(&key)?;
+                }
+                AOFCommand::Expire { key, expires_at } => {
+                    if inner.restore_expire(&key, expires_at) {
+                        inner.write_expire_to_aof_if_needed(&key, expires_at)?;
+                    }
+                }
+            }
+        }
+        inner.finish_restore();
+        Ok(stats)
+    }
+
+    /// Count the keys starting with a prefix.
+    ///
+    /// With `Config::track_prefix_counts` (the default), prefixes ending in
+    /// `:` such as `"cities:"` or a namespace's `"tenant::"` are answered
+    /// in O(1) from counters kept up to date on every write; any other
+    /// prefix is counted by scanning its key range. The count includes
+    /// expired keys that have not been removed yet.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Spatio;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert("user:1", b"alice", None)?;
+    /// db.insert("user:2", b"bob", None)?;
+    /// db.insert("order:1", b"book", None)?;
+    ///
+    /// assert_eq!(db.count_prefix(b"user:")?, 2);
+    /// assert_eq!(db.count_prefix(b"ord")?, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.count_prefix",
+            skip_all,
+            fields(
+                db.prefix_length = prefix.len() as i64,
+            ),
+            err
+        )
+    )]
+    pub fn count_prefix(&self, prefix: &[u8]) -> Result<usize> {
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        Ok(inner.count_prefix(prefix))
+    }
+
+    /// Delete every key belonging to a namespace.
+    ///
+    /// Each removed key is written to the AOF as a DELETE command so the
+    /// deletion survives restarts.
+    ///
+    /// # Arguments
+    ///
+    /// * `ns` - The namespace to clear
+    ///
+    /// # Returns
+    ///
+    /// The number of keys that were deleted
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Namespace, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let tenant = Namespace::new("tenant_a");
+    ///
+    /// db.insert(tenant.key("user:1"), b"Alice", None)?;
+    /// db.insert(tenant.key("user:2"), b"Bob", None)?;
+    ///
+    /// assert_eq!(db.delete_namespace(&tenant)?, 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.delete_namespace",
+            skip_all,
+            fields(
+                db.namespace = ns.name(),
+            ),
+            err
+        )
+    )]
+    pub fn delete_namespace(&self, ns: &Namespace) -> Result<usize> {
+        let mut inner = self.write_mut()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        let keys = inner.keys_with_prefix(&ns.prefix());
+        for key in &keys {
+            inner.remove_item(key);
+            inner.write_delete_to_aof_if_needed(key)?;
+        }
+
+        Ok(keys.len())
+    }
+
+    /// Get key count and size statistics for a namespace.
+    ///
+    /// Expired keys that have not been cleaned up yet are reported in
+    /// `expired_count` and excluded from `key_count` and `size_bytes`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Namespace, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let tenant = Namespace::new("tenant_a");
+    /// db.insert(tenant.key("user:1"), b"Alice", None)?;
+    ///
+    /// let stats = db.namespace_stats(&tenant)?;
+    /// assert_eq!(stats.key_count, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.namespace_stats",
+            skip_all,
+            fields(
+                db.namespace = ns.name(),
+            ),
+            err
+        )
+    )]
+    pub fn namespace_stats(&self, ns: &Namespace) -> Result<NamespaceStats> {
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        let prefix = ns.prefix();
+        let mut stats = NamespaceStats::default();
+        for (key, item) in inner.keys.range(prefix.clone()..) {
+            if !key.starts_with(&prefix) {
+                break;
+            }
+
+            if item.is_expired() {
+                stats.expired_count += 1;
+            } else {
+                stats.key_count += 1;
+                stats.size_bytes += key.len() + item.value.len();
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// List the names of all namespaces that currently hold keys.
+    ///
+    /// Namespaces are detected using the default separator (`::`).
+    /// Names are returned sorted and deduplicated.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.list_namespace_prefixes", skip_all, err)
+    )]
+    pub fn list_namespace_prefixes(&self) -> Result<Vec<String>> {
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        Ok(NamespaceManager::new().extract_namespaces(inner.keys.keys()))
+    }
+
+    /// Copy every live key under `from_prefix` to the same key under
+    /// `to_prefix`.
+    ///
+    /// Each new key is the old key with `from_prefix` replaced by
+    /// `to_prefix`. The items are read and written under a single write
+    /// lock and applied as one atomic batch, so the copy reflects one
+    /// consistent state. Existing keys at the destination are overwritten.
+    ///
+    /// Keys are copied as stored. Point keys copied this way are not added
+    /// to the spatial index under the new prefix.
+    ///
+    /// # Arguments
+    ///
+    /// * `from_prefix` - Prefix of the keys to copy
+    /// * `to_prefix` - Prefix the copies are stored under
+    /// * `opts` - Options for the copied keys; `None` preserves each item's
+    ///   original expiration time
+    ///
+    /// # Returns
+    ///
+    /// The number of keys copied
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Spatio;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert("orders:1", b"paid", None)?;
+    /// db.insert("orders:2", b"shipped", None)?;
+    ///
+    /// let copied = db.copy_prefix(b"orders:", b"archive:orders:", None)?;
+    /// assert_eq!(copied, 2);
+    /// assert_eq!(db.get("archive:orders:1")?.unwrap().as_ref(), b"paid");
+    /// assert!(db.get("orders:1")?.is_some());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.copy_prefix", skip_all, err)
+    )]
+    pub fn copy_prefix(
+        &self,
+        from_prefix: &[u8],
+        to_prefix: &[u8],
+        opts: Option<SetOptions>,
+    ) -> Result<usize> {
+        self.relocate_prefix(from_prefix, to_prefix, opts, false)
+    }
+
+    /// Move every live key under `from_prefix` to the same key under
+    /// `to_prefix`.
+    ///
+    /// Works like `copy_prefix`, and also deletes the source keys in the
+    /// same atomic batch, so readers see either the old keys or the new
+    /// ones, never both or neither.
+    ///
+    /// # Arguments
+    ///
+    /// * `from_prefix` - Prefix of the keys to move
+    /// * `to_prefix` - Prefix the keys are moved to
+    /// * `opts` - Options for the moved keys; `None` preserves each item's
+    ///   original expiration time
+    ///
+    /// # Returns
+    ///
+    /// The number of keys moved
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Spatio;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert("orders:1", b"paid", None)?;
+    ///
+    /// let moved = db.move_prefix(b"orders:", b"archive:orders:", None)?;
+    /// assert_eq!(moved, 1);
+    /// assert!(db.get("orders:1")?.is_none());
+    /// assert_eq!(db.get("archive:orders:1")?.unwrap().as_ref(), b"paid");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.move_prefix", skip_all, err)
+    )]
+    pub fn move_prefix(
+        &self,
+        from_prefix: &[u8],
+        to_prefix: &[u8],
+        opts: Option<SetOptions>,
+    ) -> Result<usize> {
+        self.relocate_prefix(from_prefix, to_prefix, opts, true)
+    }
+
+    /// Copy the live keys under `from_prefix` to `to_prefix` in one atomic
+    /// batch, deleting the sources if `remove_source` is set
+    fn relocate_prefix(
+        &self,
+        from_prefix: &[u8],
+        to_prefix: &[u8],
+        opts: Option<SetOptions>,
+        remove_source: bool,
+    ) -> Result<usize> {
+        AtomicBatch::new(self.clone()).commit_with(|inner, batch| {
+            let now = clock::now();
+            let items: Vec<(&Bytes, &DbItem)> = inner
+                .keys
+                .range(Bytes::copy_from_slice(from_prefix)..)
+                .take_while(|(key, _)| key.starts_with(from_prefix))
+                .filter(|(_, item)| !item.is_expired_at(now))
+                .collect();
+
+            // Deletes are queued first so a moved key landing on another
+            // source key is kept, whichever comes first in key order
+            if remove_source {
+                for (key, _) in &items {
+                    batch.delete(key)?;
+                }
+            }
+            for (key, item) in &items {
+                let new_key = [to_prefix, &key[from_prefix.len()..]].concat();
+                let item_opts = match &opts {
+                    Some(opts) => Some(opts.clone()),
+                    None => item.expires_at.map(SetOptions::with_expiration),
+                };
+                batch.insert(new_key, &item.value, item_opts)?;
+            }
+            Ok(items.len())
+        })
+    }
+
+    /// Collect all live items whose key starts with the given prefix
+    pub(crate) fn prefix_items(&self, prefix: &[u8]) -> Result<Vec<(Bytes, DbItem)>> {
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        Ok(inner
+            .keys
+            .range(Bytes::copy_from_slice(prefix)..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .filter(|(_, item)| !item.is_expired())
+            .map(|(key, item)| (key.clone(), item.clone()))
+            .collect())
+    }
+
+    /// Execute multiple operations atomically
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.atomic", skip_all, err)
+    )]
+    pub fn atomic<F, R>(&self, f: F) -> Result<R>
+    where
+        F: FnOnce(&mut AtomicBatch) -> Result<R>,
+    {
+        let mut batch = AtomicBatch::new(self.clone());
+        let result = f(&mut batch)?;
+        batch.commit()?;
+        Ok(result)
+    }
+
+    /// Execute multiple operations atomically, waiting at most `timeout`
+    /// for the write lock.
+    ///
+    /// Works like `atomic`, but if another thread holds the lock for
+    /// longer than `timeout` when the batch commits, nothing is applied
+    /// and `SpatioError::LockTimeout` is returned. Reads made through the
+    /// batch inside `f` wait for the lock as usual.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - Closure that queues the batch operations
+    /// * `timeout` - Longest time to wait for the write lock
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::LockTimeout` if the lock was not acquired in
+    /// time, besides any error returned by `f` or the commit.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Spatio;
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.atomic_with_timeout(
+    ///     |batch| {
+    ///         batch.insert("sensor:temp", b"22.5C", None)?;
+    ///         batch.insert("sensor:humidity", b"65%", None)
+    ///     },
+    ///     Duration::from_millis(50),
+    /// )?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.atomic_with_timeout",
+            skip_all,
+            fields(
+                db.timeout_ms = timeout.as_millis() as i64,
+            ),
+            err
+        )
+    )]
+    pub fn atomic_with_timeout<F, R>(&self, f: F, timeout: Duration) -> Result<R>
+    where
+        F: FnOnce(&mut AtomicBatch) -> Result<R>,
+    {
+        let mut batch = AtomicBatch::new(self.clone());
+        let result = f(&mut batch)?;
+        batch.commit_with_timeout(timeout)?;
+        Ok(result)
+    }
+
+    /// Run a read-modify-write transaction with optimistic concurrency
+    /// control.
+    ///
+    /// `f` reads through the `Transaction` without holding any lock, and
+    /// its writes are buffered. On commit the write lock is taken and the
+    /// writes are only applied if none of the keys `f` read has changed
+    /// since; otherwise `f` runs again on fresh data. This repeats up to
+    /// `Config::max_transaction_retries` times.
+    ///
+    /// `f` may run more than once, so it should have no side effects
+    /// other than through the transaction.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::TransactionConflict` if every attempt
+    /// conflicted, besides any error returned by `f` or the commit. An
+    /// error from `f` is returned at once without retrying.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Spatio;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert("visits", b"41", None)?;
+    ///
+    /// let visits = db.transaction(|tx| {
+    ///     let current = tx.get("visits")?.unwrap_or_default();
+    ///     let n: u64 = std::str::from_utf8(&current).unwrap().parse().unwrap_or(0);
+    ///     tx.insert("visits", (n + 1).to_string(), None)?;
+    ///     Ok(n + 1)
+    /// })?;
+    /// assert_eq!(visits, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.transaction", skip_all, err)
+    )]
+    pub fn transaction<F, R>(&self, mut f: F) -> Result<R>
+    where
+        F: FnMut(&mut Transaction) -> Result<R>,
+    {
+        let max_retries = self.read()?.config.max_transaction_retries;
+        let mut attempt = 0;
+        loop {
+            let mut tx = Transaction::new(self.clone());
+            let result = f(&mut tx)?;
+            match tx.commit() {
+                Err(SpatioError::TransactionConflict) if attempt < max_retries => {
+                    attempt += 1;
+                    #[cfg(feature = "tracing")]
+                    tracing::debug!(attempt, "transaction conflicted, retrying");
+                }
+                Err(e) => return Err(e),
+                Ok(()) => return Ok(result),
+            }
+        }
+    }
+
+    /// Set the geohash precision used to index points under a prefix.
+    ///
+    /// Every prefix is indexed at `Config::geohash_precision` unless given
+    /// its own precision here. Sparse namespaces, like sensors spread over a
+    /// country, query faster with coarse cells, while dense ones, like
+    /// vehicles in a city, benefit from finer cells. Points already indexed
+    /// under the prefix are reindexed at the new precision.
+    ///
+    /// Overrides are kept in the spatial index snapshot, so they survive a
+    /// reopen when `Config::persist_spatial_index` is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace to configure
+    /// * `precision` - Geohash precision, from 1 to 12
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InvalidConfiguration` if `precision` is out of
+    /// range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.set_prefix_precision("sensors", 5)?;
+    ///
+    /// let station = Point::new(48.8566, 2.3522);
+    /// db.insert_point("sensors", &station, b"paris-01", None)?;
+    /// assert_eq!(db.find_nearby("sensors", &station, 1000.0, 10)?.len(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.set_prefix_precision",
+            skip_all,
+            fields(db.prefix = prefix, db.precision = precision as i64),
+            err
+        )
+    )]
+    pub fn set_prefix_precision(&self, prefix: &str, precision: usize) -> Result<()> {
+        let mut inner = self.write_mut()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+        inner.index_manager.set_prefix_precision(prefix, precision)
+    }
+
+    /// Insert a geographic point with automatic spatial indexing.
+    ///
+    /// Points are automatically indexed for spatial queries. The system
+    /// chooses the optimal indexing strategy based on data patterns.
+    ///
+    /// A database built with `DBBuilder::with_index_strategy` set to S2 or
+    /// H3 stores the point as `insert_point_with_s2` or
+    /// `insert_point_with_h3` would instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace for the point (e.g., "cities", "sensors")
+    /// * `point` - Geographic coordinates
+    /// * `data` - Associated data to store with the point
+    /// * `opts` - Optional settings like TTL
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let nyc = Point::new(40.7128, -74.0060);
+    ///
+    /// db.insert_point("cities", &nyc, b"New York City", None)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.insert_point",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.value_length = value.len() as i64,
+                db.has_ttl = has_ttl(&opts),
+            ),
+            err
+        )
+    )]
+    pub fn insert_point(
+        &self,
+        prefix: &str,
+        point: &Point,
+        value: &[u8],
+        opts: Option<SetOptions>,
+    ) -> Result<()> {
+        self.insert_point_inner(prefix, point, value, opts)
+            .map_err(|e| e.context(format!("insert_point: prefix='{}'", prefix)))
+    }
+
+    fn insert_point_inner(
+        &self,
+        prefix: &str,
+        point: &Point,
+        value: &[u8],
+        opts: Option<SetOptions>,
+    ) -> Result<()> {
+        let strategy = self.read()?.index_strategy;
+        match strategy {
+            IndexStrategy::Geohash { .. } => {}
+            #[cfg(feature = "s2")]
+            IndexStrategy::S2 { level } => {
+                return self.insert_point_with_s2(prefix, point, level, value, opts);
+            }
+            #[cfg(feature = "h3")]
+            IndexStrategy::H3 { resolution } => {
+                return self.insert_point_with_h3(prefix, point, resolution, value, opts);
+            }
+        }
+
+        let data_bytes = value;
+        let data_ref = Bytes::copy_from_slice(data_bytes);
+
+        // Generate geohash key for automatic indexing
+        let geohash = point
+            .to_geohash(8)
+            .map_err(|_| SpatioError::InvalidGeohash)?;
+        let key = SpatialKey::geohash(prefix, &geohash);
+        let key_bytes = Bytes::copy_from_slice(key.as_bytes());
+
+        // Single lock acquisition for both operations
+        let mut inner = self.write_mut()?;
+        inner.make_room(&key_bytes, data_ref.len())?;
+
+        // Insert into main storage
+        let item = match opts {
+            Some(SetOptions { ttl: Some(ttl), .. }) => DbItem::with_ttl(data_ref.clone(), ttl),
+            Some(SetOptions {
+                expires_at: Some(expires_at),
+                ..
+            }) => DbItem::with_expiration(data_ref.clone(), expires_at),
+            _ => DbItem::new(data_ref.clone()),
+        };
+
+        inner.insert_item(key_bytes.clone(), item);
+        inner.metrics.record_inserts(1);
+
+        // Add to spatial index
+        inner.index_manager.insert_point(prefix, point, &data_ref)?;
+
+        #[cfg(feature = "async")]
+        {
+            let set_at = inner.ttl_set_at.get(&key_bytes).copied();
+            inner
+                .watchers
+                .point_written(prefix, &key_bytes, point, &data_ref, set_at);
+        }
+
+        inner.write_to_aof_if_needed(&key_bytes, value, opts.as_ref())?;
+        Ok(())
+    }
+
+    /// Insert many geographic points at once.
+    ///
+    /// Points are sorted by geohash before they are stored, so neighbouring
+    /// points land in the spatial index one after another rather than in
+    /// random order, and the AOF is written in one pass as in `bulk_load`.
+    /// For large batches this is faster than calling `insert_point` for
+    /// each point.
+    ///
+    /// A database using the S2 or H3 index strategy inserts the points one
+    /// at a time, as `insert_point` would.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace for the points
+    /// * `points` - `(point, data)` pairs; a later point with the same
+    ///   geohash as an earlier one replaces it
+    /// * `opts` - Optional settings like TTL, applied to every point
+    ///
+    /// # Returns
+    ///
+    /// The number of points inserted
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let points = vec![
+    ///     (Point::new(40.7128, -74.0060), b"New York".to_vec()),
+    ///     (Point::new(51.5074, -0.1278), b"London".to_vec()),
+    /// ];
+    ///
+    /// assert_eq!(db.insert_points_batch("cities", &points, None)?, 2);
+    /// let nearby = db.find_nearby("cities", &Point::new(51.5, -0.12), 10_000.0, 10)?;
+    /// assert_eq!(nearby[0].1.as_ref(), b"London");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.insert_points_batch",
+            skip_all,
+            fields(db.prefix = prefix, db.count = points.len() as i64),
+            err
+        )
+    )]
+    pub fn insert_points_batch(
+        &self,
+        prefix: &str,
+        points: &[(Point, Vec<u8>)],
+        opts: Option<SetOptions>,
+    ) -> Result<usize> {
+        self.insert_points_batch_inner(prefix, points, opts)
+            .map_err(|e| e.context(format!("insert_points_batch: prefix='{}'", prefix)))
+    }
+
+    fn insert_points_batch_inner(
+        &self,
+        prefix: &str,
+        points: &[(Point, Vec<u8>)],
+        opts: Option<SetOptions>,
+    ) -> Result<usize> {
+        if !matches!(self.read()?.index_strategy, IndexStrategy::Geohash { .. }) {
+            for (point, value) in points {
+                self.insert_point_inner(prefix, point, value, opts.clone())?;
+            }
+            return Ok(points.len());
+        }
+
+        // Stable sort, so duplicates keep their order and the last one wins
+        let mut sorted = points
+            .iter()
+            .map(|(point, value)| {
+                let geohash = point
+                    .to_geohash(8)
+                    .map_err(|_| SpatioError::InvalidGeohash)?;
+                Ok((geohash, point, value))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        sorted.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let mut inner = self.write_mut()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        let mut stored = IndexSet::with_capacity(sorted.len());
+        for (geohash, point, value) in sorted {
+            let key = Bytes::copy_from_slice(SpatialKey::geohash(prefix, &geohash).as_bytes());
+            inner.store(key.clone(), value, opts.as_ref())?;
+            let data = Bytes::copy_from_slice(value);
+            inner.index_manager.insert_point(prefix, point, &data)?;
+
+            #[cfg(feature = "async")]
+            {
+                let set_at = inner.ttl_set_at.get(&key).copied();
+                inner
+                    .watchers
+                    .point_written(prefix, &key, point, &data, set_at);
+            }
+            stored.insert(key);
+        }
+        inner.persist_bulk(&stored)?;
+        Ok(points.len())
+    }
+
+    /// Find nearby points within a radius.
+    ///
+    /// Uses spatial indexing for efficient queries. Results are ordered
+    /// by distance from the query point. Points whose TTL has passed are
+    /// skipped even before `cleanup_expired` removes them.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace to search in
+    /// * `center` - Center point for the search
+    /// * `radius_meters` - Search radius in meters
+    /// * `limit` - Maximum number of results to return
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let center = Point::new(40.7128, -74.0060);
+    ///
+    /// // Find up to 10 points within 1km
+    /// let nearby = db.find_nearby("cities", &center, 1000.0, 10)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.find_nearby",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.radius_meters = radius_meters,
+                db.limit = limit as i64,
+            ),
+            err
+        )
+    )]
+    pub fn find_nearby(
+        &self,
+        prefix: &str,
+        center: &Point,
+        radius_meters: f64,
+        limit: usize,
+    ) -> Result<Vec<(Point, Bytes)>> {
+        let context = || format!("find_nearby: prefix='{}'", prefix);
+        let inner = self.read().map_err(|e| e.context(context()))?;
+        let _timer = inner.metrics.time_spatial_query("find_nearby");
+        inner
+            .live_nearby(prefix, center, radius_meters, limit)
+            .map_err(|e| e.context(context()))
+    }
+
+    /// Find nearby points around several centers at once.
+    ///
+    /// Works like calling `find_nearby` for each center, but takes the
+    /// read lock once, so every search sees the same state and writers
+    /// wait only once. With `Config::parallel_queries` set and the
+    /// `parallel` feature enabled, the centers are searched in parallel on
+    /// the rayon thread pool.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace to search in
+    /// * `centers` - Center point and search radius in meters of each query
+    /// * `limit_per_center` - Maximum number of results for each center
+    ///
+    /// # Returns
+    ///
+    /// One list per center, in the order of `centers`, of matching points
+    /// with their values and distances in meters, nearest first
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("stores", &Point::new(40.7580, -73.9855), b"Times Square", None)?;
+    /// db.insert_point("stores", &Point::new(51.5074, -0.1278), b"Trafalgar", None)?;
+    ///
+    /// let customers = [
+    ///     (Point::new(40.7484, -73.9857), 5_000.0),
+    ///     (Point::new(48.8566, 2.3522), 5_000.0),
+    /// ];
+    /// let nearest = db.batch_find_nearby("stores", &customers, 1)?;
+    /// assert_eq!(nearest[0][0].1.as_ref(), b"Times Square");
+    /// assert!(nearest[1].is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.batch_find_nearby",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.centers = centers.len() as i64,
+                db.limit = limit_per_center as i64,
+            ),
+            err
+        )
+    )]
+    pub fn batch_find_nearby(
+        &self,
+        prefix: &str,
+        centers: &[(Point, f64)],
+        limit_per_center: usize,
+    ) -> Result<Vec<Vec<(Point, Bytes, f64)>>> {
+        let context = || format!("batch_find_nearby: prefix='{}'", prefix);
+        let inner = self.read().map_err(|e| e.context(context()))?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+        let _timer = inner.metrics.time_spatial_query("batch_find_nearby");
+        if limit_per_center == 0 {
+            return Ok(vec![Vec::new(); centers.len()]);
+        }
+
+        let search = |(center, radius_meters): &(Point, f64)| {
+            let found = inner.live_nearby(prefix, center, *radius_meters, limit_per_center)?;
+            Ok(found
+                .into_iter()
+                .map(|(point, data)| {
+                    let distance = center.distance_to(&point);
+                    (point, data, distance)
+                })
+                .collect())
+        };
+        #[cfg(feature = "parallel")]
+        let results: Result<Vec<_>> = if inner.config.parallel_queries {
+            centers.par_iter().map(search).collect()
+        } else {
+            centers.iter().map(search).collect()
+        };
+        #[cfg(not(feature = "parallel"))]
+        let results: Result<Vec<_>> = centers.iter().map(search).collect();
+        results.map_err(|e| e.context(context()))
+    }
+
+    /// Explain how `find_nearby` answers a radius query.
+    ///
+    /// Useful when a query returns fewer points than expected: the
+    /// explanation shows how many points the prefix holds, which geohash
+    /// cells were searched, and how many candidates were dropped for being
+    /// too far away or expired. No result limit is applied. Only available
+    /// with the `debug_queries` feature.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace to search in
+    /// * `center` - Center point for the search
+    /// * `radius_meters` - Search radius in meters
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let nyc = Point::new(40.7128, -74.0060);
+    /// db.insert_point("cities", &nyc, b"NYC", None)?;
+    /// db.insert_point("cities", &Point::new(51.5074, -0.1278), b"London", None)?;
+    ///
+    /// let explanation = db.explain_nearby("cities", &nyc, 1000.0)?;
+    /// assert_eq!(explanation.prefix_point_count, 2);
+    /// assert_eq!(explanation.distance_excluded, 1);
+    /// assert_eq!(explanation.results_returned, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "debug_queries")]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.explain_nearby",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.radius_meters = radius_meters,
+            ),
+            err
+        )
+    )]
+    pub fn explain_nearby(
+        &self,
+        prefix: &str,
+        center: &Point,
+        radius_meters: f64,
+    ) -> Result<QueryExplanation> {
+        let context = || format!("explain_nearby: prefix='{}'", prefix);
+        let inner = self.read().map_err(|e| e.context(context()))?;
+        inner
+            .index_manager
+            .explain_nearby(prefix, center, radius_meters, |point| {
+                inner.point_expired(prefix, point)
+            })
+            .map_err(|e| e.context(context()))
+    }
+
+    /// Find the `k` points closest to a location.
+    ///
+    /// Unlike `find_nearby`, no radius is needed: the search starts at
+    /// 1 km and doubles until `k` points are in range, then bisects
+    /// between the last two radii down to the smallest one, within a
+    /// metre, that still holds `k` points. Expired points are skipped. The
+    /// radius never exceeds `Config::max_knn_radius_meters`; if fewer than
+    /// `k` points lie within it, only those are returned.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace to search in
+    /// * `center` - Location to measure from
+    /// * `k` - Number of points wanted
+    ///
+    /// # Returns
+    ///
+    /// Up to `k` `(point, data, distance_meters)` tuples, nearest first
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("cities", &Point::new(40.7128, -74.0060), b"New York", None)?;
+    /// db.insert_point("cities", &Point::new(39.9526, -75.1652), b"Philadelphia", None)?;
+    /// db.insert_point("cities", &Point::new(42.3601, -71.0589), b"Boston", None)?;
+    ///
+    /// let newark = Point::new(40.7357, -74.1724);
+    /// let nearest = db.find_k_nearest("cities", &newark, 2)?;
+    /// assert_eq!(nearest[0].1.as_ref(), b"New York");
+    /// assert_eq!(nearest[1].1.as_ref(), b"Philadelphia");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.find_k_nearest",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.k = k as i64,
+            ),
+            err
+        )
+    )]
+    pub fn find_k_nearest(
+        &self,
+        prefix: &str,
+        center: &Point,
+        k: usize,
+    ) -> Result<Vec<(Point, Bytes, f64)>> {
+        let inner = self.read()?;
+        let _timer = inner.metrics.time_spatial_query("find_k_nearest");
+        if k == 0 {
+            return Ok(Vec::new());
+        }
+
+        let max_radius = inner.config.max_knn_radius_meters;
+        let mut radius = KNN_INITIAL_RADIUS_METERS.min(max_radius);
+        // Largest radius known to hold fewer than `k` points
+        let mut short = 0.0;
+        let mut results = loop {
+            let results = inner.live_nearby(prefix, center, radius, k)?;
+            if results.len() >= k || radius >= max_radius {
+                break results;
+            }
+            short = radius;
+            radius = (radius * 2.0).min(max_radius);
+        };
+
+        // Bisect down to the smallest radius that still holds `k` points
+        if results.len() >= k {
+            while radius - short > KNN_RADIUS_TOLERANCE_METERS {
+                let mid = (short + radius) / 2.0;
+                let found = inner.live_nearby(prefix, center, mid, k)?;
+                if found.len() >= k {
+                    radius = mid;
+                    results = found;
+                } else {
+                    short = mid;
+                }
+            }
+        }
+
+        Ok(results
+            .into_iter()
+            .map(|(point, data)| {
+                let distance = center.distance_to(&point);
+                (point, data, distance)
+            })
+            .collect())
+    }
+
+    /// Find the stored point nearest a GPS fix that also lies along a route.
+    ///
+    /// Candidates are the points within `max_distance_meters` of
+    /// `query_point`, found with the spatial index. Those further than
+    /// `max_distance_meters` from the route, measured with
+    /// `LineString::nearest_geographic_point`, are dropped, and the rest are
+    /// ranked by their distance from the query point plus their distance
+    /// from the route.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace to search in
+    /// * `route` - Route the match must lie along, in longitude/latitude
+    /// * `query_point` - Position to match, such as a GPS fix
+    /// * `max_distance_meters` - Maximum distance from both the query point
+    ///   and the route
+    ///
+    /// # Returns
+    ///
+    /// The best `(point, data, combined_distance_meters)`, or `None` if no
+    /// point is close enough to both
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, LineString, Point, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// // One stop on the avenue, one on a side street just as close to the fix
+    /// db.insert_point("stops", &Point::new(40.7005, -73.9900), b"avenue", None)?;
+    /// db.insert_point("stops", &Point::new(40.7030, -73.9895), b"side street", None)?;
+    ///
+    /// let avenue = LineString::new(vec![
+    ///     Coordinate::new(-74.00, 40.70),
+    ///     Coordinate::new(-73.98, 40.70),
+    /// ])?;
+    /// let fix = Point::new(40.7018, -73.9900);
+    ///
+    /// let (_, data, _) = db
+    ///     .find_nearest_along_route("stops", &avenue, &fix, 500.0)?
+    ///     .unwrap();
+    /// assert_eq!(data.as_ref(), b"avenue");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.find_nearest_along_route",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.radius_meters = max_distance_meters,
+            ),
+            err
+        )
+    )]
+    pub fn find_nearest_along_route(
+        &self,
+        prefix: &str,
+        route: &LineString,
+        query_point: &Point,
+        max_distance_meters: f64,
+    ) -> Result<Option<(Point, Bytes, f64)>> {
+        let context = || format!("find_nearest_along_route: prefix='{}'", prefix);
+        let inner = self.read().map_err(|e| e.context(context()))?;
+        let _timer = inner.metrics.time_spatial_query("find_nearest_along_route");
+        let candidates = inner
+            .index_manager
+            .find_nearby(prefix, query_point, max_distance_meters, usize::MAX)
+            .map_err(|e| e.context(context()))?;
+
+        Ok(candidates
+            .into_iter()
+            .filter_map(|(point, data)| {
+                let (_, route_distance, _) = route.nearest_geographic_point(&point);
+                (route_distance <= max_distance_meters).then(|| {
+                    let distance = query_point.distance_to(&point) + route_distance;
+                    (point, data, distance)
+                })
+            })
+            .min_by(|a, b| a.2.total_cmp(&b.2)))
+    }
+
+    /// Find every pair of points from two namespaces within a distance of
+    /// each other.
+    ///
+    /// Both sets are bucketed by geohash, with cells sized to the distance,
+    /// so each point is only compared with points in its own and adjacent
+    /// cells instead of the whole of the other namespace.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix_a` - First namespace, such as stores
+    /// * `prefix_b` - Second namespace, such as customers
+    /// * `max_distance_meters` - Maximum distance between the points of a pair
+    ///
+    /// # Returns
+    ///
+    /// Matching pairs sorted by distance, nearest first
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("stores", &Point::new(40.7128, -74.0060), b"downtown", None)?;
+    /// db.insert_point("customers", &Point::new(40.7150, -74.0040), b"alice", None)?;
+    /// db.insert_point("customers", &Point::new(40.7831, -73.9712), b"bob", None)?;
+    ///
+    /// let pairs = db.spatial_join("stores", "customers", 1_000.0)?;
+    /// assert_eq!(pairs.len(), 1);
+    /// assert_eq!(pairs[0].data_a.as_ref(), b"downtown");
+    /// assert_eq!(pairs[0].data_b.as_ref(), b"alice");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.spatial_join",
+            skip_all,
+            fields(
+                db.prefix = prefix_a,
+                db.prefix_b = prefix_b,
+                db.radius_meters = max_distance_meters,
+            ),
+            err
+        )
+    )]
+    pub fn spatial_join(
+        &self,
+        prefix_a: &str,
+        prefix_b: &str,
+        max_distance_meters: f64,
+    ) -> Result<Vec<SpatialJoinResult>> {
+        let inner = self.read().map_err(|e| {
+            e.context(format!(
+                "spatial_join: prefix_a='{}', prefix_b='{}'",
+                prefix_a, prefix_b
+            ))
+        })?;
+        let _timer = inner.metrics.time_spatial_query("spatial_join");
+        Ok(inner
+            .index_manager
+            .spatial_join(prefix_a, prefix_b, max_distance_meters))
+    }
+
+    /// Find the points of one namespace with no point of another nearby.
+    ///
+    /// Returns each point of `prefix_a` for which `contains_point` on
+    /// `prefix_b` with `exclusion_radius_meters` is `false`, such as the
+    /// customers more than 5 km from any store.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix_a` - Namespace to filter, such as customers
+    /// * `prefix_b` - Namespace whose points exclude nearby ones, such as
+    ///   stores
+    /// * `exclusion_radius_meters` - Points of `prefix_a` within this
+    ///   distance of a point of `prefix_b` are left out
+    /// * `limit` - Maximum number of results to return
+    ///
+    /// # Returns
+    ///
+    /// The remaining points of `prefix_a` with their data, in no
+    /// particular order
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("stores", &Point::new(40.7128, -74.0060), b"downtown", None)?;
+    /// db.insert_point("customers", &Point::new(40.7150, -74.0040), b"alice", None)?;
+    /// db.insert_point("customers", &Point::new(40.9000, -73.8000), b"bob", None)?;
+    ///
+    /// let unserved = db.spatial_difference("customers", "stores", 5_000.0, 10)?;
+    /// assert_eq!(unserved.len(), 1);
+    /// assert_eq!(unserved[0].1.as_ref(), b"bob");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.spatial_difference",
+            skip_all,
+            fields(
+                db.prefix = prefix_a,
+                db.prefix_b = prefix_b,
+                db.radius_meters = exclusion_radius_meters,
+                db.limit = limit as i64,
+            ),
+            err
+        )
+    )]
+    pub fn spatial_difference(
+        &self,
+        prefix_a: &str,
+        prefix_b: &str,
+        exclusion_radius_meters: f64,
+        limit: usize,
+    ) -> Result<Vec<(Point, Bytes)>> {
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+        let _timer = inner.metrics.time_spatial_query("spatial_difference");
+        inner.spatial_difference(prefix_a, prefix_b, exclusion_radius_meters, limit)
+    }
+
+    /// Like `spatial_difference`, also giving the nearest point of
+    /// `prefix_b` to each point returned.
+    ///
+    /// The nearest point is found by measuring every point of `prefix_b`,
+    /// so this suits a `prefix_b` of modest size, such as a set of stores.
+    ///
+    /// # Returns
+    ///
+    /// Each remaining point of `prefix_a` with the nearest point of
+    /// `prefix_b`, in no particular order
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("stores", &Point::new(40.7128, -74.0060), b"downtown", None)?;
+    /// db.insert_point("stores", &Point::new(40.7580, -73.9855), b"midtown", None)?;
+    /// db.insert_point("customers", &Point::new(40.9000, -73.8000), b"bob", None)?;
+    ///
+    /// let unserved = db.spatial_difference_with_nearest("customers", "stores", 5_000.0, 10)?;
+    /// assert_eq!(unserved[0].data.as_ref(), b"bob");
+    /// assert_eq!(unserved[0].nearest.as_ref().unwrap().1.as_ref(), b"midtown");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.spatial_difference_with_nearest",
+            skip_all,
+            fields(
+                db.prefix = prefix_a,
+                db.prefix_b = prefix_b,
+                db.radius_meters = exclusion_radius_meters,
+                db.limit = limit as i64,
+            ),
+            err
+        )
+    )]
+    pub fn spatial_difference_with_nearest(
+        &self,
+        prefix_a: &str,
+        prefix_b: &str,
+        exclusion_radius_meters: f64,
+        limit: usize,
+    ) -> Result<Vec<UnservedPoint>> {
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+        let _timer = inner
+            .metrics
+            .time_spatial_query("spatial_difference_with_nearest");
+
+        let unserved =
+            inner.spatial_difference(prefix_a, prefix_b, exclusion_radius_meters, limit)?;
+        let candidates = inner.index_manager.entries_for_prefix(prefix_b);
+        Ok(unserved
+            .into_iter()
+            .map(|(point, data)| {
+                let nearest = candidates
+                    .iter()
+                    .map(|(other, other_data)| (other, other_data, point.distance_to(other)))
+                    .min_by(|a, b| a.2.total_cmp(&b.2))
+                    .map(|(other, other_data, distance)| (*other, other_data.clone(), distance));
+                UnservedPoint {
+                    point,
+                    data,
+                    nearest,
+                }
+            })
+            .collect())
+    }
+
+    /// Find points close to a location using locality-sensitive hashing.
+    ///
+    /// Points are bucketed in `num_hash_tables` hash tables by MinHash
+    /// signatures of their geohash prefixes, and only points sharing a
+    /// bucket with `query` in some table are measured. The tables are
+    /// built on the first query of a prefix and reused until its points
+    /// change.
+    ///
+    /// This trades recall for speed: unlike `find_k_nearest`, a true
+    /// nearest neighbor is missed if it shares no bucket with the query,
+    /// which is most likely when it lies across a geohash cell boundary.
+    /// More tables raise recall at the cost of memory and more candidates
+    /// to measure. Every returned point is a real stored point with its
+    /// exact distance, so results are never wrong, only possibly
+    /// incomplete.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace to search in
+    /// * `query` - Location to measure from
+    /// * `k` - Number of points wanted
+    /// * `num_hash_tables` - Hash tables to consult; `Config::lsh_num_tables`
+    ///   is a good default
+    ///
+    /// # Returns
+    ///
+    /// Up to `k` `(point, data, distance_meters)` tuples, nearest first
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `num_hash_tables` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("cities", &Point::new(40.7128, -74.0060), b"New York", None)?;
+    /// db.insert_point("cities", &Point::new(34.0522, -118.2437), b"Los Angeles", None)?;
+    ///
+    /// let near = db.approximate_nearest_neighbor("cities", &Point::new(40.7130, -74.0062), 1, 5)?;
+    /// assert_eq!(near[0].1.as_ref(), b"New York");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.approximate_nearest_neighbor",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.k = k as i64,
+                db.num_hash_tables = num_hash_tables as i64,
+            ),
+            err
+        )
+    )]
+    pub fn approximate_nearest_neighbor(
+        &self,
+        prefix: &str,
+        query: &Point,
+        k: usize,
+        num_hash_tables: usize,
+    ) -> Result<Vec<(Point, Bytes, f64)>> {
+        if num_hash_tables == 0 {
+            return Err(SpatioError::Other(
+                "num_hash_tables must be at least 1".to_string(),
+            ));
+        }
+
+        let context = || format!("approximate_nearest_neighbor: prefix='{}'", prefix);
+        let inner = self.read().map_err(|e| e.context(context()))?;
+        let _timer = inner
+            .metrics
+            .time_spatial_query("approximate_nearest_neighbor");
+        inner
+            .index_manager
+            .approximate_nearest_neighbor(prefix, query, k, num_hash_tables)
+            .map_err(|e| e.context(context()))
+    }
+
+    /// Build the k-nearest-neighbor graph of the points in a namespace.
+    ///
+    /// Each point is joined to its `k` nearest other points, found with
+    /// `find_k_nearest`, so neighbors are limited to
+    /// `Config::max_knn_radius_meters`. The graph is undirected: a pair
+    /// that are among each other's nearest neighbors gives one edge.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace whose points form the graph
+    /// * `k` - Number of neighbors per point
+    ///
+    /// # Returns
+    ///
+    /// Edges sorted by distance, shortest first
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("stations", &Point::new(40.700, -74.000), b"a", None)?;
+    /// db.insert_point("stations", &Point::new(40.701, -74.000), b"b", None)?;
+    /// db.insert_point("stations", &Point::new(40.710, -74.000), b"c", None)?;
+    ///
+    /// // a and b are each other's nearest; c's nearest is b
+    /// let edges = db.knn_graph("stations", 1)?;
+    /// assert_eq!(edges.len(), 2);
+    /// assert!(edges[0].distance < edges[1].distance);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.knn_graph",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.k = k as i64,
+            ),
+            err
+        )
+    )]
+    pub fn knn_graph(&self, prefix: &str, k: usize) -> Result<Vec<KnnEdge>> {
+        let neighbors = self.knn_neighbors(prefix, k)?;
+        let mut edges: Vec<KnnEdge> = neighbors
+            .into_iter()
+            .flat_map(|(point, near)| near.into_iter().map(move |other| knn_edge(point, other)))
+            .collect();
+        sort_knn_edges(&mut edges);
+        Ok(edges)
+    }
+
+    /// Build the mutual k-nearest-neighbor graph of the points in a
+    /// namespace.
+    ///
+    /// Like `knn_graph`, but an edge is only kept when each endpoint is
+    /// among the other's `k` nearest neighbors.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace whose points form the graph
+    /// * `k` - Number of neighbors per point
+    ///
+    /// # Returns
+    ///
+    /// Edges sorted by distance, shortest first
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("stations", &Point::new(40.700, -74.000), b"a", None)?;
+    /// db.insert_point("stations", &Point::new(40.701, -74.000), b"b", None)?;
+    /// db.insert_point("stations", &Point::new(40.710, -74.000), b"c", None)?;
+    ///
+    /// // Only a and b pick each other
+    /// let edges = db.mutual_knn_graph("stations", 1)?;
+    /// assert_eq!(edges.len(), 1);
+    /// assert_eq!(edges[0].from_point, Point::new(40.700, -74.000));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.mutual_knn_graph",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.k = k as i64,
+            ),
+            err
+        )
+    )]
+    pub fn mutual_knn_graph(&self, prefix: &str, k: usize) -> Result<Vec<KnnEdge>> {
+        let neighbors = self.knn_neighbors(prefix, k)?;
+        let directed: HashSet<(Point, Point)> = neighbors
+            .iter()
+            .flat_map(|(point, near)| near.iter().map(move |other| (*point, *other)))
+            .collect();
+
+        let mut edges: Vec<KnnEdge> = directed
+            .iter()
+            .filter(|(point, other)| directed.contains(&(*other, *point)))
+            .map(|(point, other)| knn_edge(*point, *other))
+            .collect();
+        sort_knn_edges(&mut edges);
+        Ok(edges)
+    }
+
+    /// Every point of a prefix with its `k` nearest other points
+    fn knn_neighbors(&self, prefix: &str, k: usize) -> Result<Vec<(Point, Vec<Point>)>> {
+        let points = self.read()?.index_manager.points_for_prefix(prefix);
+        if k == 0 {
+            return Ok(Vec::new());
+        }
+
+        points
+            .into_iter()
+            .map(|point| {
+                // Ask for one extra, since the point finds itself first
+                let near = self
+                    .find_k_nearest(prefix, &point, k + 1)?
+                    .into_iter()
+                    .filter(|(other, _, _)| *other != point)
+                    .take(k)
+                    .map(|(other, _, _)| other)
+                    .collect();
+                Ok((point, near))
+            })
+            .collect()
+    }
+
+    /// Insert a geographic point indexed by its S2 cell.
+    ///
+    /// Works like `insert_point`, but stores the point under an S2 cell key
+    /// and indexes it by cell ID at the given level, so `find_within_bounds`
+    /// only looks up the cells covering the query box. All points under a
+    /// prefix must share the same level.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace for the point (e.g., "sensors")
+    /// * `point` - Geographic coordinates
+    /// * `level` - S2 cell level (0-30, higher = smaller cells)
+    /// * `data` - Associated data to store with the point
+    /// * `opts` - Optional settings like TTL
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let nyc = Point::new(40.7128, -74.0060);
+    ///
+    /// db.insert_point_with_s2("sensors", &nyc, 20, b"sensor-7", None)?;
+    /// let found = db.find_within_bounds("sensors", 40.7, -74.1, 40.8, -73.9, 10)?;
+    /// assert_eq!(found.len(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "s2")]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.insert_point_with_s2",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.s2_level = level,
+                db.value_length = data.len() as i64,
+                db.has_ttl = has_ttl(&opts),
+            ),
+            err
+        )
+    )]
+    pub fn insert_point_with_s2(
+        &self,
+        prefix: &str,
+        point: &Point,
+        level: u8,
+        data: &[u8],
+        opts: Option<SetOptions>,
+    ) -> Result<()> {
+        let data_ref = Bytes::copy_from_slice(data);
+        let cell = point.to_s2_cell(level)?;
+        let key = SpatialKey::s2_cell(prefix, cell);
+        let key_bytes = Bytes::copy_from_slice(key.as_bytes());
+
+        let mut inner = self.write_mut()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        inner.make_room(&key_bytes, data_ref.len())?;
+
+        // Index first so a level mismatch leaves storage untouched
+        inner.index_manager.insert_point_with_strategy(
+            prefix,
+            point,
+            &data_ref,
+            IndexStrategy::S2 { level },
+        )?;
+
+        let item = match opts {
+            Some(SetOptions { ttl: Some(ttl), .. }) => DbItem::with_ttl(data_ref, ttl),
+            Some(SetOptions {
+                expires_at: Some(expires_at),
+                ..
+            }) => DbItem::with_expiration(data_ref, expires_at),
+            _ => DbItem::new(data_ref),
+        };
+        inner.insert_item(key_bytes.clone(), item);
+        inner.metrics.record_inserts(1);
+
+        inner.write_to_aof_if_needed(&key_bytes, data, opts.as_ref())?;
+        Ok(())
+    }
+
+    /// Insert a geographic point indexed by its H3 cell.
+    ///
+    /// Works like `insert_point`, but stores the point under an H3 cell key
+    /// and indexes it in a hexagonal grid at the given resolution. All
+    /// points under a prefix must share the same resolution.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace for the point (e.g., "vehicles")
+    /// * `point` - Geographic coordinates
+    /// * `resolution` - H3 resolution (0-15, higher = smaller cells)
+    /// * `data` - Associated data to store with the point
+    /// * `opts` - Optional settings like TTL
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let nyc = Point::new(40.7128, -74.0060);
+    ///
+    /// db.insert_point_with_h3("vehicles", &nyc, 9, b"truck-42", None)?;
+    /// let nearby = db.find_nearby_h3("vehicles", &nyc, 500.0, 10)?;
+    /// assert_eq!(nearby.len(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "h3")]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.insert_point_with_h3",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.h3_resolution = resolution,
+                db.value_length = data.len() as i64,
+                db.has_ttl = has_ttl(&opts),
+            ),
+            err
+        )
+    )]
+    pub fn insert_point_with_h3(
+        &self,
+        prefix: &str,
+        point: &Point,
+        resolution: u8,
+        data: &[u8],
+        opts: Option<SetOptions>,
+    ) -> Result<()> {
+        let data_ref = Bytes::copy_from_slice(data);
+        let cell = point.to_h3_cell(resolution)?;
+        let key = SpatialKey::h3_cell(prefix, cell);
+        let key_bytes = Bytes::copy_from_slice(key.as_bytes());
+
+        let mut inner = self.write_mut()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        inner.make_room(&key_bytes, data_ref.len())?;
+
+        // Index first so a resolution mismatch leaves storage untouched
+        inner.index_manager.insert_point_with_strategy(
+            prefix,
+            point,
+            &data_ref,
+            IndexStrategy::H3 { resolution },
+        )?;
+
+        let item = match opts {
+            Some(SetOptions { ttl: Some(ttl), .. }) => DbItem::with_ttl(data_ref, ttl),
+            Some(SetOptions {
+                expires_at: Some(expires_at),
+                ..
+            }) => DbItem::with_expiration(data_ref, expires_at),
+            _ => DbItem::new(data_ref),
+        };
+        inner.insert_item(key_bytes.clone(), item);
+        inner.metrics.record_inserts(1);
+
+        inner.write_to_aof_if_needed(&key_bytes, data, opts.as_ref())?;
+        Ok(())
+    }
+
+    /// Find nearby points within a radius using the H3 index.
+    ///
+    /// Only points inserted with `insert_point_with_h3` are searched.
+    /// Results are ordered by distance from the query point.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace to search in
+    /// * `center` - Center point for the search
+    /// * `radius_meters` - Search radius in meters
+    /// * `limit` - Maximum number of results to return
+    #[cfg(feature = "h3")]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.find_nearby_h3",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.radius_meters = radius_meters,
+                db.limit = limit as i64,
+            ),
+            err
+        )
+    )]
+    pub fn find_nearby_h3(
+        &self,
+        prefix: &str,
+        center: &Point,
+        radius_meters: f64,
+        limit: usize,
+    ) -> Result<Vec<(Point, Bytes)>> {
+        let inner = self.read()?;
+        let _timer = inner.metrics.time_spatial_query("find_nearby_h3");
+        inner
+            .index_manager
+            .find_nearby_h3(prefix, center, radius_meters, limit)
+    }
+
+    /// Insert a trajectory (sequence of points over time).
+    ///
+    /// Trajectories represent the movement of objects over time. Each
+    /// point in the trajectory has a timestamp for temporal queries.
+    ///
+    /// # Arguments
+    ///
+    /// * `object_id` - Unique identifier for the moving object
+    /// * `trajectory` - Sequence of (Point, timestamp) pairs
+    /// * `opts` - Optional settings like TTL for the entire trajectory
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    ///
+    /// let trajectory = vec![
+    ///     (Point::new(40.7128, -74.0060), 1640995200), // Start
+    ///     (Point::new(40.7150, -74.0040), 1640995260), // 1 min later
+    ///     (Point::new(40.7172, -74.0020), 1640995320), // 2 min later
+    /// ];
+    ///
+    /// db.insert_trajectory("vehicle:truck001", &trajectory, None)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.insert_trajectory",
+            skip_all,
+            fields(
+                db.object_id = object_id,
+                db.point_count = trajectory.len() as i64,
+                db.has_ttl = has_ttl(&opts),
+            ),
+            err
+        )
+    )]
+    pub fn insert_trajectory(
+        &self,
+        object_id: &str,
+        trajectory: &[(Point, u64)],
+        opts: Option<SetOptions>,
+    ) -> Result<()> {
+        for (i, (point, timestamp)) in trajectory.iter().enumerate() {
+            let key = format!("traj:{}:{:010}:{:06}", object_id, timestamp, i);
+            let point_data = bincode::serialize(&(point, timestamp)).map_err(|e| {
+                SpatioError::SerializationErrorWithContext(format!(
+                    "Failed to serialize trajectory point for object '{}': {}",
+                    object_id, e
+                ))
+            })?;
+
+            self.insert(&key, &point_data, opts.clone())?;
+        }
+        Ok(())
+    }
+
+    /// Watch a circular region for trajectory objects entering or leaving it.
+    ///
+    /// Every point written by `insert_trajectory` for an object whose id
+    /// starts with `prefix` is compared with that object's previous
+    /// position. When the object crosses the region boundary a
+    /// `RegionEvent` is sent on the returned stream. Objects that already
+    /// have trajectory points start from their latest stored position;
+    /// objects seen for the first time are treated as coming from outside.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Object id prefix to watch (e.g. `"vehicle:"`)
+    /// * `center` - Center of the region
+    /// * `radius_meters` - Region radius in meters
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use futures::StreamExt;
+    /// use spatio::{Point, RegionEventType, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let depot = Point::new(40.7128, -74.0060);
+    /// let mut watcher = db.watch_region("truck:", depot, 500.0)?;
+    ///
+    /// db.insert_trajectory("truck:7", &[(depot, 1640995200)], None)?;
+    ///
+    /// let event = futures::executor::block_on(watcher.next()).unwrap();
+    /// assert_eq!(event.object_id, "truck:7");
+    /// assert_eq!(event.event, RegionEventType::Enter);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.watch_region",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.radius_meters = radius_meters,
+            ),
+            err
+        )
+    )]
+    pub fn watch_region(
+        &self,
+        prefix: &str,
+        center: Point,
+        radius_meters: f64,
+    ) -> Result<RegionWatcher> {
+        let mut inner = self.write()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        let (mut subscription, watcher) = RegionSubscription::new(prefix, center, radius_meters);
+
+        // Start every known object from its latest stored position
+        let key_prefix = format!("traj:{}", prefix);
+        for (key, item) in inner.keys.range(Bytes::from(key_prefix.clone())..) {
+            if !key.starts_with(key_prefix.as_bytes()) {
+                break;
+            }
+            if item.is_expired() {
+                continue;
+            }
+            if let Some(object_id) = trajectory_object_id(key)
+                && let Ok((point, timestamp)) = bincode::deserialize::<(Point, u64)>(&item.value)
+            {
+                subscription.seed(object_id, &point, timestamp);
+            }
+        }
+
+        inner.watchers.watch_region(subscription);
+        Ok(watcher)
+    }
+
+    /// Find the most recent points within a radius, over a sliding time
+    /// window.
+    ///
+    /// Returns up to `limit` points within `radius_meters` of `center`
+    /// whose TTL was set at most `window_duration` ago, most recent first.
+    /// Points inserted without a TTL are never part of a window, so
+    /// writers typically give every point a TTL of about the window
+    /// length. Points loaded from disk at open or replayed from another
+    /// AOF have no known set time and are left out until rewritten.
+    ///
+    /// Like `find_nearby`, this uses the geohash point keys written by
+    /// `insert_point` with the default index strategy.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace to search in
+    /// * `center` - Center point for the search
+    /// * `radius_meters` - Search radius in meters
+    /// * `window_duration` - How far back a TTL may have been set
+    /// * `limit` - Maximum number of results to return
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Point, SetOptions, Spatio};
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let center = Point::new(40.7128, -74.0060);
+    /// let ttl = SetOptions::with_ttl(Duration::from_secs(300));
+    ///
+    /// db.insert_point("pings", &center, b"first", Some(ttl.clone()))?;
+    /// db.insert_point("pings", &Point::new(40.7130, -74.0062), b"second", Some(ttl))?;
+    ///
+    /// let recent = db.spatial_window("pings", &center, 1000.0, Duration::from_secs(60), 10)?;
+    /// assert_eq!(recent.len(), 2);
+    /// assert_eq!(recent[0].1.as_ref(), b"second");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.spatial_window",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.radius_meters = radius_meters,
+                db.window_ms = window_duration.as_millis() as i64,
+                db.limit = limit as i64,
+            ),
+            err
+        )
+    )]
+    pub fn spatial_window(
+        &self,
+        prefix: &str,
+        center: &Point,
+        radius_meters: f64,
+        window_duration: Duration,
+        limit: usize,
+    ) -> Result<Vec<(Point, Bytes)>> {
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+        let _timer = inner.metrics.time_spatial_query("spatial_window");
+
+        let points = inner.window_points(prefix, center, radius_meters, window_duration)?;
+        Ok(latest_points(
+            points.into_iter().map(|(_, point)| point).collect(),
+            limit,
+        ))
+    }
+
+    /// Subscribe to `spatial_window` results as new points arrive.
+    ///
+    /// Every time `insert_point` writes a point with a TTL inside the
+    /// region, or a point in the window is deleted or expires, the stream
+    /// yields the updated window: the `limit` most recent points, as
+    /// `spatial_window` would return them. Points that age out of the
+    /// window are dropped from the next update rather than announced.
+    /// Nothing is sent for the window's contents at subscription time.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace to watch
+    /// * `center` - Center of the region
+    /// * `radius_meters` - Region radius in meters
+    /// * `window_duration` - How far back a TTL may have been set
+    /// * `limit` - Maximum number of points in each update
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use futures::StreamExt;
+    /// use spatio::{Point, SetOptions, Spatio};
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let center = Point::new(40.7128, -74.0060);
+    /// let mut updates =
+    ///     db.subscribe_spatial_window("pings", center, 1000.0, Duration::from_secs(60), 5)?;
+    ///
+    /// let ttl = SetOptions::with_ttl(Duration::from_secs(60));
+    /// db.insert_point("pings", &center, b"driver:7", Some(ttl))?;
+    ///
+    /// let window = futures::executor::block_on(updates.next()).unwrap();
+    /// assert_eq!(window.len(), 1);
+    /// assert_eq!(window[0].1.as_ref(), b"driver:7");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "async")]
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.subscribe_spatial_window",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.radius_meters = radius_meters,
+                db.window_ms = window_duration.as_millis() as i64,
+                db.limit = limit as i64,
+            ),
+            err
+        )
+    )]
+    pub fn subscribe_spatial_window(
+        &self,
+        prefix: &str,
+        center: Point,
+        radius_meters: f64,
+        window_duration: Duration,
+        limit: usize,
+    ) -> Result<SpatialWindowWatcher> {
+        let mut inner = self.write()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        let seed = inner.window_points(prefix, &center, radius_meters, window_duration)?;
+        let (subscription, watcher) =
+            WindowSubscription::new(prefix, center, radius_meters, window_duration, limit, seed);
+        inner.watchers.watch_window(subscription);
+        Ok(watcher)
+    }
+
+    /// Query trajectory between timestamps.
+    ///
+    /// Returns all trajectory points for an object within the specified
+    /// time range, ordered by timestamp.
+    ///
+    /// Only the keys between the start and end timestamps are scanned.
+    /// Points are always read from the in-memory key map, even with a
+    /// storage backend attached: the backend's items are loaded into that
+    /// map on open, so `StorageBackend::iter_range` is not consulted.
+    ///
+    /// # Arguments
+    ///
+    /// * `object_id` - The object to query
+    /// * `start_time` - Start of time range (unix timestamp)
+    /// * `end_time` - End of time range (unix timestamp)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    ///
+    /// // Query trajectory for first hour
+    /// let path = db.query_trajectory("vehicle:truck001", 1640995200, 1640998800)?;
+    /// println!("Found {} trajectory points", path.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.query_trajectory",
+            skip_all,
+            fields(
+                db.object_id = object_id,
+                db.start_time = start_time,
+                db.end_time = end_time,
+            ),
+            err
+        )
+    )]
+    pub fn query_trajectory(
+        &self,
+        object_id: &str,
+        start_time: u64,
+        end_time: u64,
+    ) -> Result<Vec<(Point, u64)>> {
+        let mut results = Vec::new();
+        let prefix = format!("traj:{}:", object_id);
+
+        let inner = self
+            .read()
+            .map_err(|e| e.context(format!("query_trajectory: object_id='{}'", object_id)))?;
+        let range = trajectory_key_range(&prefix, start_time, end_time);
+        for (key, item) in inner.keys.range(range) {
+            let Some(suffix) = key.strip_prefix(prefix.as_bytes()) else {
+                break;
+            };
+
+            // Skip points of other objects whose id extends this one, such
+            // as "truck:1" when querying "truck"
+            if !is_trajectory_point_suffix(suffix) || item.is_expired() {
+                continue;
+            }
+
+            match bincode::deserialize::<(Point, u64)>(&item.value) {
+                Ok((point, timestamp)) => {
+                    if timestamp >= start_time && timestamp <= end_time {
+                        results.push((point, timestamp));
+                    }
+                }
+                Err(e) => {
+                    // Log deserialization error but continue processing other points
+                    eprintln!(
+                        "Warning: Failed to deserialize trajectory point for object '{}': {}",
+                        object_id, e
+                    );
+                }
+            }
+        }
+
+        results.sort_by_key(|(_, timestamp)| *timestamp);
+        Ok(results)
+    }
+
+    /// Get an object's whole trajectory split into segments that do not
+    /// cross the antimeridian.
+    ///
+    /// Consecutive points more than 180° of longitude apart are taken to
+    /// cross the date line. Each crossing ends a segment with a point at
+    /// ±180° and starts the next on the opposite side, with latitude and
+    /// timestamp interpolated between the two recorded points.
+    ///
+    /// # Arguments
+    ///
+    /// * `object_id` - The object to query
+    ///
+    /// # Returns
+    ///
+    /// Segments in time order, or no segments if the object has no
+    /// trajectory
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_trajectory("flight:nz1", &[
+    ///     (Point::new(10.0, 170.0), 1000),
+    ///     (Point::new(20.0, -170.0), 2000),
+    /// ], None)?;
+    ///
+    /// let segments = db.normalize_trajectory("flight:nz1")?;
+    /// assert_eq!(segments.len(), 2);
+    /// assert_eq!(segments[0][1], (Point::new(15.0, 180.0), 1500));
+    /// assert_eq!(segments[1][0], (Point::new(15.0, -180.0), 1500));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.normalize_trajectory",
+            skip_all,
+            fields(
+                db.object_id = object_id,
+            ),
+            err
+        )
+    )]
+    pub fn normalize_trajectory(&self, object_id: &str) -> Result<Vec<Vec<(Point, u64)>>> {
+        let track = self.query_trajectory(object_id, 0, u64::MAX)?;
+        let Some(&first) = track.first() else {
+            return Ok(Vec::new());
+        };
+
+        let mut segments = Vec::new();
+        let mut current = vec![first];
+        for pair in track.windows(2) {
+            let ((a, start), (b, end)) = (pair[0], pair[1]);
+            if let Some((fraction, lat)) = Point::antimeridian_crossing(&a, &b) {
+                let boundary = if a.lon >= 0.0 { 180.0 } else { -180.0 };
+                let time = start + ((end - start) as f64 * fraction).round() as u64;
+                let exit = Point::new(lat, boundary);
+                if current.last().map(|(p, _)| p) != Some(&exit) {
+                    current.push((exit, time));
+                }
+                // A recorded point already on the boundary is not left
+                // behind as a segment of its own
+                if current.len() > 1 {
+                    segments.push(std::mem::take(&mut current));
+                } else {
+                    current.clear();
+                }
+                current.push((Point::new(lat, -boundary), time));
+            }
+            current.push(pair[1]);
+        }
+        segments.push(current);
+
+        Ok(segments)
+    }
+
+    /// Find when two objects came within a distance of each other.
+    ///
+    /// Both trajectories are sampled every 30 seconds over the time range
+    /// they share, interpolating linearly between recorded points. Times
+    /// outside either trajectory's recorded span are not sampled.
+    ///
+    /// # Arguments
+    ///
+    /// * `object_a` - First object identifier
+    /// * `object_b` - Second object identifier
+    /// * `start_time` - Start of the time range (inclusive)
+    /// * `end_time` - End of the time range (inclusive)
+    /// * `proximity_meters` - Maximum distance counted as a crossing
+    ///
+    /// # Returns
+    ///
+    /// Crossings sorted by time
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// // Two vehicles driving towards each other along the same street
+    /// db.insert_trajectory("car:a", &[
+    ///     (Point::new(40.70, -74.00), 1020),
+    ///     (Point::new(40.72, -74.00), 1080),
+    /// ], None)?;
+    /// db.insert_trajectory("car:b", &[
+    ///     (Point::new(40.72, -74.00), 1020),
+    ///     (Point::new(40.70, -74.00), 1080),
+    /// ], None)?;
+    ///
+    /// let crossings = db.find_trajectory_crossings("car:a", "car:b", 0, 2000, 100.0)?;
+    /// assert_eq!(crossings.len(), 1);
+    /// assert_eq!(crossings[0].time, 1050);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.find_trajectory_crossings",
+            skip_all,
+            fields(
+                db.object_a = object_a,
+                db.object_b = object_b,
+                db.radius_meters = proximity_meters,
+            ),
+            err
+        )
+    )]
+    pub fn find_trajectory_crossings(
+        &self,
+        object_a: &str,
+        object_b: &str,
+        start_time: u64,
+        end_time: u64,
+        proximity_meters: f64,
+    ) -> Result<Vec<TrajectoryCrossing>> {
+        let track_a = self.query_trajectory(object_a, start_time, end_time)?;
+        let track_b = self.query_trajectory(object_b, start_time, end_time)?;
+
+        let (Some(first_a), Some(first_b)) = (track_a.first(), track_b.first()) else {
+            return Ok(Vec::new());
+        };
+        let (Some(last_a), Some(last_b)) = (track_a.last(), track_b.last()) else {
+            return Ok(Vec::new());
+        };
+        let from = first_a.1.max(first_b.1);
+        let to = last_a.1.min(last_b.1);
+
+        let mut crossings = Vec::new();
+        let mut time = from.div_ceil(TRAJECTORY_BUCKET_SECONDS) * TRAJECTORY_BUCKET_SECONDS;
+        while time <= to {
+            if let (Some(point_a), Some(point_b)) = (
+                interpolate_trajectory(&track_a, time),
+                interpolate_trajectory(&track_b, time),
+            ) {
+                let distance = point_a.distance_to(&point_b);
+                if distance <= proximity_meters {
+                    crossings.push(TrajectoryCrossing {
+                        time,
+                        point_a,
+                        point_b,
+                        distance,
+                    });
+                }
+            }
+            time += TRAJECTORY_BUCKET_SECONDS;
+        }
+
+        Ok(crossings)
+    }
+
+    /// Find the recorded trajectory point nearest to a location.
+    ///
+    /// Useful for map matching a GPS fix against a recorded path, or for
+    /// checking whether an object ever passed within some distance of a
+    /// place. Only recorded points are considered, not the path between
+    /// them. When several points are equally near, the earliest is
+    /// returned.
+    ///
+    /// The whole trajectory is read and scanned linearly; trajectories
+    /// are not spatially indexed yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `object_id` - The object whose trajectory is searched
+    /// * `query_point` - Location to measure from
+    ///
+    /// # Returns
+    ///
+    /// The nearest point with its timestamp and distance in meters, or
+    /// `None` if the object has no trajectory
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_trajectory("vehicle:truck001", &[
+    ///     (Point::new(40.7128, -74.0060), 1640995200),
+    ///     (Point::new(40.7589, -73.9851), 1640995500),
+    /// ], None)?;
+    ///
+    /// let fix = Point::new(40.7580, -73.9855);
+    /// let (point, timestamp, distance) =
+    ///     db.nearest_trajectory_point("vehicle:truck001", &fix)?.unwrap();
+    /// assert_eq!(point, Point::new(40.7589, -73.9851));
+    /// assert_eq!(timestamp, 1640995500);
+    /// assert!(distance < 200.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.nearest_trajectory_point",
+            skip_all,
+            fields(
+                db.object_id = object_id,
+            ),
+            err
+        )
+    )]
+    pub fn nearest_trajectory_point(
+        &self,
+        object_id: &str,
+        query_point: &Point,
+    ) -> Result<Option<(Point, u64, f64)>> {
+        let track = self.query_trajectory(object_id, 0, u64::MAX)?;
+        Ok(track
+            .into_iter()
+            .map(|(point, timestamp)| (point, timestamp, query_point.distance_to(&point)))
+            .min_by(|a, b| a.2.total_cmp(&b.2)))
+    }
+
+    /// Get the distance an object travelled between two timestamps.
+    ///
+    /// Sums the Haversine distances between consecutive trajectory points
+    /// in the time range, so the path between points is taken to be
+    /// straight.
+    ///
+    /// # Arguments
+    ///
+    /// * `object_id` - The object whose trajectory is measured
+    /// * `start_time` - Start of time range (unix timestamp)
+    /// * `end_time` - End of time range (unix timestamp)
+    ///
+    /// # Returns
+    ///
+    /// The distance in meters; zero with fewer than two points in the range
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_trajectory("vehicle:truck001", &[
+    ///     (Point::new(40.7128, -74.0060), 1640995200),
+    ///     (Point::new(40.7589, -73.9851), 1640995500),
+    ///     (Point::new(40.7128, -74.0060), 1640995800),
+    /// ], None)?;
+    ///
+    /// let distance = db.trajectory_total_distance("vehicle:truck001", 1640995200, 1640995800)?;
+    /// assert!(distance > 10_000.0 && distance < 11_000.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.trajectory_total_distance",
+            skip_all,
+            fields(
+                db.object_id = object_id,
+                db.start_time = start_time,
+                db.end_time = end_time,
+            ),
+            err
+        )
+    )]
+    pub fn trajectory_total_distance(
+        &self,
+        object_id: &str,
+        start_time: u64,
+        end_time: u64,
+    ) -> Result<f64> {
+        let track = self.query_trajectory(object_id, start_time, end_time)?;
+        Ok(track
+            .windows(2)
+            .map(|pair| pair[0].0.distance_to(&pair[1].0))
+            .sum())
+    }
+
+    /// Get the time between an object's first and last trajectory points
+    /// in a time range.
+    ///
+    /// # Arguments
+    ///
+    /// * `object_id` - The object whose trajectory is measured
+    /// * `start_time` - Start of time range (unix timestamp)
+    /// * `end_time` - End of time range (unix timestamp)
+    ///
+    /// # Returns
+    ///
+    /// The duration in seconds; zero with fewer than two points in the
+    /// range
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_trajectory("vehicle:truck001", &[
+    ///     (Point::new(40.7128, -74.0060), 1640995200),
+    ///     (Point::new(40.7589, -73.9851), 1640995500),
+    /// ], None)?;
+    ///
+    /// assert_eq!(db.trajectory_duration("vehicle:truck001", 0, u64::MAX)?, 300);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.trajectory_duration",
+            skip_all,
+            fields(
+                db.object_id = object_id,
+                db.start_time = start_time,
+                db.end_time = end_time,
+            ),
+            err
+        )
+    )]
+    pub fn trajectory_duration(
+        &self,
+        object_id: &str,
+        start_time: u64,
+        end_time: u64,
+    ) -> Result<u64> {
+        let track = self.query_trajectory(object_id, start_time, end_time)?;
+        Ok(match (track.first(), track.last()) {
+            (Some((_, first)), Some((_, last))) => last - first,
+            _ => 0,
+        })
+    }
+
+    /// Get how long an object was moving between two timestamps.
+    ///
+    /// Each stretch between consecutive trajectory points counts if the
+    /// object's average speed over it, straight-line distance divided by
+    /// elapsed time, is at least `min_speed_ms`. Slower stretches are
+    /// treated as stops.
+    ///
+    /// # Arguments
+    ///
+    /// * `object_id` - The object whose trajectory is measured
+    /// * `start_time` - Start of time range (unix timestamp)
+    /// * `end_time` - End of time range (unix timestamp)
+    /// * `min_speed_ms` - Slowest speed, in meters per second, that counts
+    ///   as moving
+    ///
+    /// # Returns
+    ///
+    /// The moving time in seconds
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_trajectory("vehicle:truck001", &[
+    ///     (Point::new(40.7128, -74.0060), 1640995200),
+    ///     (Point::new(40.7589, -73.9851), 1640995500),
+    ///     // Parked for ten minutes
+    ///     (Point::new(40.7589, -73.9851), 1640996100),
+    /// ], None)?;
+    ///
+    /// let moving = db.trajectory_moving_duration("vehicle:truck001", 0, u64::MAX, 1.0)?;
+    /// assert_eq!(moving, 300);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.trajectory_moving_duration",
+            skip_all,
+            fields(
+                db.object_id = object_id,
+                db.start_time = start_time,
+                db.end_time = end_time,
+            ),
+            err
+        )
+    )]
+    pub fn trajectory_moving_duration(
+        &self,
+        object_id: &str,
+        start_time: u64,
+        end_time: u64,
+        min_speed_ms: f64,
+    ) -> Result<u64> {
+        let track = self.query_trajectory(object_id, start_time, end_time)?;
+        Ok(track
+            .windows(2)
+            .filter_map(|pair| {
+                let ((from, start), (to, end)) = (pair[0], pair[1]);
+                let elapsed = end - start;
+                let speed = from.distance_to(&to) / elapsed as f64;
+                (elapsed > 0 && speed >= min_speed_ms).then_some(elapsed)
+            })
+            .sum())
+    }
+
+    /// Replace an object's trajectory points with Kalman filtered
+    /// estimates to reduce GPS noise.
+    ///
+    /// The points are run through the filter described by
+    /// `TrajectorySmoothing::kalman` in timestamp order, with the process
+    /// noise scaled by the time between points, and every point whose
+    /// estimate differs from the stored position is rewritten under its
+    /// existing key and timestamp. All rewrites are applied atomically.
+    /// Trajectories crossing the antimeridian are not handled.
+    ///
+    /// # Arguments
+    ///
+    /// * `object_id` - The object whose trajectory is smoothed
+    /// * `process_noise` - Variance growth of the true position per second
+    /// * `measurement_noise` - Variance of each recorded position
+    /// * `opts` - Settings for the rewritten points; `None` keeps each
+    ///   point's expiration
+    ///
+    /// # Returns
+    ///
+    /// The number of points rewritten
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InvalidConfiguration` if `process_noise` is
+    /// negative or `measurement_noise` is not positive.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_trajectory("vehicle:truck001", &[
+    ///     (Point::new(40.71280, -74.0060), 1640995200),
+    ///     (Point::new(40.71295, -74.0060), 1640995205),
+    ///     (Point::new(40.71270, -74.0060), 1640995210),
+    /// ], None)?;
+    ///
+    /// let updated = db.smooth_trajectory("vehicle:truck001", 0.5, 100.0, None)?;
+    /// assert_eq!(updated, 2);
+    ///
+    /// let path = db.query_trajectory("vehicle:truck001", 0, u64::MAX)?;
+    /// assert!((path[2].0.lat - 40.7128).abs() < 0.0001);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.smooth_trajectory",
+            skip_all,
+            fields(
+                db.object_id = object_id,
+                db.has_ttl = has_ttl(&opts),
+            ),
+            err
+        )
+    )]
+    pub fn smooth_trajectory(
+        &self,
+        object_id: &str,
+        process_noise: f64,
+        measurement_noise: f64,
+        opts: Option<SetOptions>,
+    ) -> Result<usize> {
+        let smoothing = TrajectorySmoothing::kalman(process_noise, measurement_noise);
+        smoothing.validate()?;
+        let prefix = format!("traj:{}:", object_id);
+
+        AtomicBatch::new(self.clone()).commit_with(|inner, batch| {
+            let now = clock::now();
+            let mut stored = Vec::new();
+            for (key, item) in inner.keys.range(trajectory_key_range(&prefix, 0, u64::MAX)) {
+                let Some(suffix) = key.strip_prefix(prefix.as_bytes()) else {
+                    break;
+                };
+                if !is_trajectory_point_suffix(suffix) || item.is_expired_at(now) {
+                    continue;
+                }
+                if let Ok(point) = bincode::deserialize::<(Point, u64)>(&item.value) {
+                    stored.push((key, item, point));
+                }
+            }
+            stored.sort_by_key(|(_, _, (_, timestamp))| *timestamp);
+
+            let track: Vec<(Point, u64)> = stored.iter().map(|(_, _, point)| *point).collect();
+            let mut updated = 0;
+            for ((key, item, (point, _)), (estimate, timestamp)) in
+                stored.iter().zip(smoothing.smooth(&track))
+            {
+                if estimate == *point {
+                    continue;
+                }
+                let value = bincode::serialize(&(estimate, timestamp)).map_err(|e| {
+                    SpatioError::SerializationErrorWithContext(format!(
+                        "Failed to serialize trajectory point for object '{}': {}",
+                        object_id, e
+                    ))
+                })?;
+                let item_opts = match &opts {
+                    Some(opts) => Some(opts.clone()),
+                    None => item.expires_at.map(SetOptions::with_expiration),
+                };
+                batch.insert(key, value, item_opts)?;
+                updated += 1;
+            }
+            Ok(updated)
+        })
+    }
+
+    /// Group objects whose trajectories follow similar paths.
+    ///
+    /// Each object's trajectory within `time_range` is resampled to 32
+    /// points evenly spaced along its path, and every pair is compared by
+    /// discrete Fréchet distance. Trajectories are then clustered as in
+    /// DBSCAN: a trajectory with at least `min_samples` trajectories,
+    /// itself included, closer than `eps_meters` anchors a cluster, which
+    /// takes in those neighbors and grows through the ones that anchor
+    /// clusters themselves. Only the shape of the paths matters, not the
+    /// times they were travelled.
+    ///
+    /// Comparing every pair takes time proportional to the square of the
+    /// number of objects.
+    ///
+    /// # Arguments
+    ///
+    /// * `object_ids` - Objects to cluster
+    /// * `time_range` - Start and end of the trajectories to compare (unix
+    ///   timestamps)
+    /// * `eps_meters` - Fréchet distance below which two trajectories are
+    ///   neighbors
+    /// * `min_samples` - Neighbors a trajectory needs to anchor a cluster
+    ///
+    /// # Returns
+    ///
+    /// The clusters, in the order of their first member. Objects without
+    /// points in the time range, or in no cluster, are left out.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::Other` if `eps_meters` is not a positive
+    /// distance.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// // Two buses along the same avenue, one across town
+    /// for (id, lon) in [("bus:1", -74.000), ("bus:2", -74.0005), ("bus:3", -73.90)] {
+    ///     db.insert_trajectory(id, &[
+    ///         (Point::new(40.70, lon), 1000),
+    ///         (Point::new(40.75, lon), 2000),
+    ///     ], None)?;
+    /// }
+    ///
+    /// let clusters = db.cluster_trajectories(&["bus:1", "bus:2", "bus:3"], (0, 5000), 200.0, 2)?;
+    /// assert_eq!(clusters.len(), 1);
+    /// assert_eq!(clusters[0].member_ids, vec!["bus:1", "bus:2"]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.cluster_trajectories",
+            skip_all,
+            fields(
+                db.object_count = object_ids.len() as i64,
+                db.start_time = time_range.0,
+                db.end_time = time_range.1,
+                db.radius_meters = eps_meters,
+            ),
+            err
+        )
+    )]
+    pub fn cluster_trajectories(
+        &self,
+        object_ids: &[&str],
+        time_range: (u64, u64),
+        eps_meters: f64,
+        min_samples: usize,
+    ) -> Result<Vec<TrajectoryCluster>> {
+        if !(eps_meters > 0.0 && eps_meters.is_finite()) {
+            return Err(SpatioError::Other(format!(
+                "Clustering distance must be a positive distance, got {}",
+                eps_meters
+            )));
+        }
+
+        let mut trajectories = Vec::new();
+        for id in object_ids {
+            let track = self.query_trajectory(id, time_range.0, time_range.1)?;
+            if !track.is_empty() {
+                trajectories.push((*id, track));
+            }
+        }
+        let resampled: Vec<Vec<Point>> = trajectories
+            .iter()
+            .map(|(_, track)| {
+                SpatialAnalysis::resample_trajectory(track, TRAJECTORY_CLUSTER_SAMPLES)
+            })
+            .collect();
+
+        let n = resampled.len();
+        let mut distances = vec![vec![0.0; n]; n];
+        for i in 0..n {
+            for j in i + 1..n {
+                let d = SpatialAnalysis::discrete_frechet_distance(&resampled[i], &resampled[j]);
+                distances[i][j] = d;
+                distances[j][i] = d;
+            }
+        }
+
+        Ok(
+            analysis::density_clusters(&distances, eps_meters, min_samples)
+                .into_iter()
+                .map(|members| {
+                    let total = |i: usize| members.iter().map(|&j| distances[i][j]).sum::<f64>();
+                    let medoid = members
+                        .iter()
+                        .copied()
+                        .min_by(|&a, &b| total(a).total_cmp(&total(b)))
+                        .expect("clusters are never empty");
+                    TrajectoryCluster {
+                        member_ids: members
+                            .iter()
+                            .map(|&i| trajectories[i].0.to_string())
+                            .collect(),
+                        representative_trajectory: trajectories[medoid].1.clone(),
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    /// Find the points of a prefix within a corridor around an object's
+    /// recent trajectory.
+    ///
+    /// The reference trajectory is the object's points from
+    /// `time_window_secs` before its latest point up to that point, joined
+    /// by straight segments. Candidates are read from the spatial index
+    /// inside the trajectory's bounding box grown by
+    /// `corridor_width_meters`, then kept if their distance to the nearest
+    /// segment is at most `corridor_width_meters`. Expired points are
+    /// skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `reference_object_id` - Object whose trajectory forms the corridor
+    /// * `search_prefix` - Namespace of the points to search
+    /// * `corridor_width_meters` - Greatest distance from the trajectory
+    /// * `time_window_secs` - How far back from the latest point the
+    ///   trajectory reaches
+    ///
+    /// # Returns
+    ///
+    /// The points inside the corridor, nearest first; empty if the
+    /// reference object has no trajectory
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_trajectory("flight:AB123", &[
+    ///     (Point::new(40.70, -74.00), 1640995200),
+    ///     (Point::new(40.70, -73.90), 1640995800),
+    /// ], None)?;
+    /// db.insert_point("aircraft", &Point::new(40.701, -73.95), b"N12345", None)?;
+    /// db.insert_point("aircraft", &Point::new(40.80, -73.95), b"N67890", None)?;
+    ///
+    /// let nearby = db.find_objects_near_trajectory("flight:AB123", "aircraft", 500.0, 3600)?;
+    /// assert_eq!(nearby.len(), 1);
+    /// assert_eq!(nearby[0].data.as_ref(), b"N12345");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.find_objects_near_trajectory",
+            skip_all,
+            fields(
+                db.object_id = reference_object_id,
+                db.prefix = search_prefix,
+                db.radius_meters = corridor_width_meters,
+            ),
+            err
+        )
+    )]
+    pub fn find_objects_near_trajectory(
+        &self,
+        reference_object_id: &str,
+        search_prefix: &str,
+        corridor_width_meters: f64,
+        time_window_secs: u64,
+    ) -> Result<Vec<CorridorResult>> {
+        let mut track = self.query_trajectory(reference_object_id, 0, u64::MAX)?;
+        let Some(&(_, latest)) = track.last() else {
+            return Ok(Vec::new());
+        };
+        track.retain(|(_, timestamp)| *timestamp >= latest.saturating_sub(time_window_secs));
+
+        // A lone point becomes a zero-length segment
+        let mut coords: Vec<Coordinate> = track
+            .iter()
+            .map(|(point, _)| Coordinate::from_point(point))
+            .collect();
+        if coords.len() == 1 {
+            coords.push(coords[0]);
+            track.push(track[0]);
+        }
+        let route = LineString::new(coords)?;
+        let area = route.bounds().expand_by_meters(corridor_width_meters);
+
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+        let _timer = inner
+            .metrics
+            .time_spatial_query("find_objects_near_trajectory");
+        let candidates = inner.index_manager.find_within_bounds(
+            search_prefix,
+            area.min.lat,
+            area.min.lon,
+            area.max.lat,
+            area.max.lon,
+            usize::MAX,
+        )?;
+
+        let mut results: Vec<CorridorResult> = candidates
+            .into_iter()
+            .filter(|(point, _)| !inner.point_expired(search_prefix, point))
+            .filter_map(|(point, data)| {
+                let (nearest, distance, segment) = route.nearest_geographic_point(&point);
+                if distance > corridor_width_meters {
+                    return None;
+                }
+                let ((start, start_time), (end, end_time)) = (track[segment], track[segment + 1]);
+                let length = start.distance_to(&end);
+                let fraction = if length > 0.0 {
+                    (start.distance_to(&nearest) / length).min(1.0)
+                } else {
+                    0.0
+                };
+                let timestamp =
+                    start_time + ((end_time - start_time) as f64 * fraction).round() as u64;
+                Some(CorridorResult {
+                    object_point: point,
+                    data,
+                    timestamp,
+                    nearest_trajectory_point: nearest,
+                    distance_meters: distance,
+                })
+            })
+            .collect();
+        results.sort_by(|a, b| a.distance_meters.total_cmp(&b.distance_meters));
+        Ok(results)
+    }
+
+    /// Replay when objects entered and left a region.
+    ///
+    /// Each object's trajectory within the time range is interpolated
+    /// linearly between its recorded points and checked against the
+    /// polygon every 10 seconds. When the side of the boundary changes
+    /// between two checks, a binary search finds the first second on the
+    /// new side. Excursions shorter than the check interval may be
+    /// missed. An object's side at its first point in the range is taken
+    /// as its starting state, so it produces no event.
+    ///
+    /// # Arguments
+    ///
+    /// * `polygon` - The region, with `x` = longitude, `y` = latitude
+    /// * `object_ids` - Objects whose trajectories are replayed
+    /// * `start_time` - Start of time range (unix timestamp)
+    /// * `end_time` - End of time range (unix timestamp)
+    ///
+    /// # Returns
+    ///
+    /// The crossings of all objects in time order
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, GeofenceEventType, LinearRing, Point, Polygon, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let depot = Polygon::new(
+    ///     LinearRing::new(vec![
+    ///         Coordinate::new(-74.01, 40.70),
+    ///         Coordinate::new(-74.00, 40.70),
+    ///         Coordinate::new(-74.00, 40.71),
+    ///         Coordinate::new(-74.01, 40.71),
+    ///     ])?,
+    ///     vec![],
+    /// );
+    ///
+    /// // Drives east through the depot at a steady speed
+    /// db.insert_trajectory("truck:1", &[
+    ///     (Point::new(40.705, -74.02), 1000),
+    ///     (Point::new(40.705, -73.99), 1300),
+    /// ], None)?;
+    ///
+    /// let events = db.geofence_events(&depot, &["truck:1"], 0, 2000)?;
+    /// assert_eq!(events.len(), 2);
+    /// assert_eq!(events[0].event_type, GeofenceEventType::Enter);
+    /// assert_eq!(events[0].timestamp, 1100);
+    /// assert_eq!(events[1].event_type, GeofenceEventType::Exit);
+    /// assert_eq!(events[1].timestamp, 1200);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.geofence_events",
+            skip_all,
+            fields(
+                db.object_count = object_ids.len() as i64,
+                db.start_time = start_time,
+                db.end_time = end_time,
+            ),
+            err
+        )
+    )]
+    pub fn geofence_events(
+        &self,
+        polygon: &Polygon,
+        object_ids: &[&str],
+        start_time: u64,
+        end_time: u64,
+    ) -> Result<Vec<GeofenceEvent>> {
+        let mut events = Vec::new();
+        for id in object_ids {
+            let track = self.query_trajectory(id, start_time, end_time)?;
+            let inside_at = |time: u64| {
+                interpolate_trajectory(&track, time)
+                    .is_some_and(|point| polygon.contains_point(&Coordinate::from_point(&point)))
+            };
+            let (Some(&(_, first)), Some(&(_, last))) = (track.first(), track.last()) else {
+                continue;
+            };
+
+            let mut time = first;
+            let mut inside = inside_at(time);
+            while time < last {
+                let next = time.saturating_add(GEOFENCE_SAMPLE_SECONDS).min(last);
+                if inside_at(next) != inside {
+                    // The first second on the new side lies in (time, next]
+                    let (mut low, mut high) = (time, next);
+                    while high - low > 1 {
+                        let mid = low + (high - low) / 2;
+                        if inside_at(mid) == inside {
+                            low = mid;
+                        } else {
+                            high = mid;
+                        }
+                    }
+                    inside = !inside;
+                    events.push(GeofenceEvent {
+                        object_id: id.to_string(),
+                        event_type: if inside {
+                            GeofenceEventType::Enter
+                        } else {
+                            GeofenceEventType::Exit
+                        },
+                        timestamp: high,
+                        crossing_point: interpolate_trajectory(&track, high)
+                            .expect("times within the trajectory interpolate"),
+                    });
+                }
+                time = next;
+            }
+        }
+        events.sort_by_key(|event| event.timestamp);
+        Ok(events)
+    }
+
+    /// Check if there are any points within a circular region.
+    ///
+    /// This method checks if any points exist within the specified distance
+    /// from a center point in the given namespace.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace to search in
+    /// * `center` - Center point of the circular region
+    /// * `radius_meters` - Radius in meters
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let center = Point::new(40.7128, -74.0060);
+    ///
+    /// // Check if there are any cities within 50km
+    /// let has_nearby = db.contains_point("cities", &center, 50_000.0)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.contains_point",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.radius_meters = radius_meters,
+            ),
+            err
+        )
+    )]
+    pub fn contains_point(&self, prefix: &str, center: &Point, radius_meters: f64) -> Result<bool> {
+        let inner = self.read()?;
+        let _timer = inner.metrics.time_spatial_query("contains_point");
+        inner
+            .index_manager
+            .contains_point(prefix, center, radius_meters)
+    }
+
+    /// Check if there are any points within a bounding box.
+    ///
+    /// This method checks if any points exist within the specified
+    /// rectangular region in the given namespace.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace to search in
+    /// * `min_lat` - Minimum latitude of bounding box
+    /// * `min_lon` - Minimum longitude of bounding box
+    /// * `max_lat` - Maximum latitude of bounding box
+    /// * `max_lon` - Maximum longitude of bounding box
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    ///
+    /// // Check if there are any points in Manhattan area
+    /// let has_points = db.intersects_bounds("sensors", 40.7, -74.1, 40.8, -73.9)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.intersects_bounds",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+            ),
+            err
+        )
+    )]
+    pub fn intersects_bounds(
+        &self,
+        prefix: &str,
+        min_lat: f64,
+        min_lon: f64,
+        max_lat: f64,
+        max_lon: f64,
+    ) -> Result<bool> {
+        let inner = self.read()?;
+        let _timer = inner.metrics.time_spatial_query("intersects_bounds");
+        inner
+            .index_manager
+            .intersects_bounds(prefix, min_lat, min_lon, max_lat, max_lon)
+    }
+
+    /// Count points within a distance from a center point.
+    ///
+    /// This method counts how many points exist within the specified
+    /// distance from a center point without returning the actual points.
+    /// More efficient than `find_nearby` when you only need the count.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace to search in
+    /// * `center` - Center point for the search
+    /// * `radius_meters` - Search radius in meters
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let center = Point::new(40.7128, -74.0060);
+    ///
+    /// // Count how many sensors are within 1km
+    /// let count = db.count_within_distance("sensors", &center, 1000.0)?;
+    /// println!("Found {} sensors within 1km", count);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.count_within_distance",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.radius_meters = radius_meters,
+            ),
+            err
+        )
+    )]
+    pub fn count_within_distance(
+        &self,
+        prefix: &str,
+        center: &Point,
+        radius_meters: f64,
+    ) -> Result<usize> {
+        let inner = self.read()?;
+        let _timer = inner.metrics.time_spatial_query("count_within_distance");
+        inner
+            .index_manager
+            .count_within_distance(prefix, center, radius_meters)
+    }
+
+    /// Find all points within a bounding box.
+    ///
+    /// This method returns all points that fall within the specified
+    /// rectangular region, up to the specified limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace to search in
+    /// * `min_lat` - Minimum latitude of bounding box
+    /// * `min_lon` - Minimum longitude of bounding box
+    /// * `max_lat` - Maximum latitude of bounding box
+    /// * `max_lon` - Maximum longitude of bounding box
+    /// * `limit` - Maximum number of results to return
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    ///
+    /// // Find all sensors in Manhattan area
+    /// let points = db.find_within_bounds("sensors", 40.7, -74.1, 40.8, -73.9, 100)?;
+    /// println!("Found {} sensors in Manhattan", points.len());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.find_within_bounds",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.limit = limit as i64,
+            ),
+            err
+        )
+    )]
+    pub fn find_within_bounds(
+        &self,
+        prefix: &str,
+        min_lat: f64,
+        min_lon: f64,
+        max_lat: f64,
+        max_lon: f64,
+        limit: usize,
+    ) -> Result<Vec<(Point, Bytes)>> {
+        let inner = self.read()?;
+        let _timer = inner.metrics.time_spatial_query("find_within_bounds");
+        inner
+            .index_manager
+            .find_within_bounds(prefix, min_lat, min_lon, max_lat, max_lon, limit)
+    }
+
+    /// Find points in or near a polygon using a geohash cell covering.
+    ///
+    /// The polygon is approximated with `GeohashUtils::covering` and every
+    /// point stored in one of the covering cells is returned, without an
+    /// exact point-in-polygon test. All points inside the polygon are
+    /// candidates, but points close to its boundary may be returned even
+    /// if they lie just outside; a higher `precision` tightens the
+    /// approximation. Expired points are skipped.
+    ///
+    /// Only points written with the geohash index strategy are found.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace to search in
+    /// * `polygon` - Region to search, with `x` = longitude, `y` = latitude
+    /// * `precision` - Finest geohash precision of the covering (1-12)
+    /// * `limit` - Maximum number of results to return
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, LinearRing, Point, Polygon, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("cafes", &Point::new(40.7740, -73.9708), b"Boathouse", None)?;
+    /// db.insert_point("cafes", &Point::new(40.7484, -73.9857), b"Midtown", None)?;
+    ///
+    /// let central_park = Polygon::new(
+    ///     LinearRing::new(vec![
+    ///         Coordinate::new(-73.9812, 40.7681),
+    ///         Coordinate::new(-73.9581, 40.8005),
+    ///         Coordinate::new(-73.9495, 40.7968),
+    ///         Coordinate::new(-73.9730, 40.7644),
+    ///     ])?,
+    ///     vec![],
+    /// );
+    ///
+    /// let found = db.find_within_polygon_approximate("cafes", &central_park, 7, 10)?;
+    /// assert_eq!(found.len(), 1);
+    /// assert_eq!(found[0].1.as_ref(), b"Boathouse");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.find_within_polygon_approximate",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.precision = precision as i64,
+                db.limit = limit as i64,
+            ),
+            err
+        )
+    )]
+    pub fn find_within_polygon_approximate(
+        &self,
+        prefix: &str,
+        polygon: &Polygon,
+        precision: usize,
+        limit: usize,
+    ) -> Result<Vec<(Point, Bytes)>> {
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+        let _timer = inner
+            .metrics
+            .time_spatial_query("find_within_polygon_approximate");
+
+        let cells = GeohashUtils::covering(polygon, precision, POLYGON_COVERING_MAX_CELLS);
+        let mut found = inner
+            .index_manager
+            .find_in_geohash_cells(prefix, &cells, usize::MAX);
+        found.retain(|(point, _)| !inner.point_expired(prefix, point));
+        found.truncate(limit);
+        Ok(found)
+    }
+
+    /// Find points inside a polygon.
+    ///
+    /// Candidates are found in the polygon's bounding box, then each is
+    /// checked exactly against the polygon, so points in its holes or in
+    /// the concave parts of its bounding box are left out. Points on the
+    /// boundary may go either way. Expired points are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace to search in
+    /// * `polygon` - Region to search, with `x` = longitude, `y` = latitude
+    /// * `limit` - Maximum number of results to return
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, LinearRing, Point, Polygon, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("cafes", &Point::new(40.72, -73.94), b"inside", None)?;
+    /// // Within the bounding box, but outside the triangle
+    /// db.insert_point("cafes", &Point::new(40.78, -74.00), b"outside", None)?;
+    ///
+    /// let triangle = Polygon::new(
+    ///     LinearRing::new(vec![
+    ///         Coordinate::new(-74.02, 40.70),
+    ///         Coordinate::new(-73.92, 40.70),
+    ///         Coordinate::new(-73.92, 40.80),
+    ///     ])?,
+    ///     vec![],
+    /// );
+    ///
+    /// let found = db.find_within_polygon("cafes", &triangle, 10)?;
+    /// assert_eq!(found.len(), 1);
+    /// assert_eq!(found[0].1.as_ref(), b"inside");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.find_within_polygon",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.limit = limit as i64,
+            ),
+            err
+        )
+    )]
+    pub fn find_within_polygon(
+        &self,
+        prefix: &str,
+        polygon: &Polygon,
+        limit: usize,
+    ) -> Result<Vec<(Point, Bytes)>> {
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+        let _timer = inner.metrics.time_spatial_query("find_within_polygon");
+
+        let bounds = polygon.bounds();
+        let mut found = inner.index_manager.find_within_bounds(
+            prefix,
+            bounds.min.lat,
+            bounds.min.lon,
+            bounds.max.lat,
+            bounds.max.lon,
+            usize::MAX,
+        )?;
+        found.retain(|(point, _)| {
+            polygon.contains_geo_point(point) && !inner.point_expired(prefix, point)
+        });
+        found.truncate(limit);
+        Ok(found)
+    }
+
+    /// Store a geometry, such as a route or a geofence, under an id.
+    ///
+    /// The geometry is indexed by its bounding box so it can be found with
+    /// `find_geometries_intersecting`. Storing another geometry under the
+    /// same id replaces it.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace for the geometry (must not contain ':')
+    /// * `id` - Identifier of the geometry within the prefix
+    /// * `geometry` - The geometry to store
+    /// * `data` - Associated data to store with the geometry
+    /// * `opts` - Optional settings like TTL
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InsufficientPoints` for an empty geometry and
+    /// `SpatioError::InvalidGeohash` for one outside valid coordinates.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, Geometry, LinearRing, Polygon, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let park = Polygon::new(
+    ///     LinearRing::new(vec![
+    ///         Coordinate::new(-73.98, 40.76),
+    ///         Coordinate::new(-73.95, 40.76),
+    ///         Coordinate::new(-73.95, 40.80),
+    ///     ])?,
+    ///     vec![],
+    /// );
+    ///
+    /// db.insert_geometry("parks", "central", &Geometry::Polygon(park), b"Central Park", None)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.insert_geometry",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.geometry_id = id,
+                db.value_length = data.len() as i64,
+                db.has_ttl = has_ttl(&opts),
+            ),
+            err
+        )
+    )]
+    pub fn insert_geometry(
+        &self,
+        prefix: &str,
+        id: &str,
+        geometry: &Geometry,
+        data: &[u8],
+        opts: Option<SetOptions>,
+    ) -> Result<()> {
+        if prefix.contains(':') {
+            return Err(SpatioError::Other(format!(
+                "Geometry prefix must not contain ':', got '{}'",
+                prefix
+            )));
+        }
+        if id.is_empty() {
+            return Err(SpatioError::Other(
+                "Geometry id must not be empty".to_string(),
+            ));
+        }
+        IndexManager::geometry_bounds(geometry)?;
+
+        let key = SpatialKey::geometry(prefix, id);
+        let value = encode_geometry_value(geometry, data);
+
+        let mut inner = self.write_mut()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        inner.set(key.as_bytes(), &value, opts)?;
+        inner
+            .index_manager
+            .insert_geometry(prefix, id, geometry, &Bytes::copy_from_slice(data))
+    }
+
+    /// Find the stored geometries that intersect a bounding box.
+    ///
+    /// Returns the id, geometry and data of each match, ordered by id.
+    /// Geometries only count as intersecting if they share a point with
+    /// the box, not merely if their bounding boxes overlap.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace to search in
+    /// * `query_bbox` - Area to search
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{BoundingBox, Coordinate, Geometry, LineString, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let route = LineString::new(vec![
+    ///     Coordinate::new(-74.0060, 40.7128),
+    ///     Coordinate::new(-73.9857, 40.7484),
+    /// ])?;
+    /// db.insert_geometry("routes", "r1", &Geometry::LineString(route), b"Route 1", None)?;
+    ///
+    /// let midtown = BoundingBox::new(40.74, -74.0, 40.76, -73.98);
+    /// let found = db.find_geometries_intersecting("routes", &midtown)?;
+    /// assert_eq!(found[0].0, "r1");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.find_geometries_intersecting",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+            ),
+            err
+        )
+    )]
+    pub fn find_geometries_intersecting(
+        &self,
+        prefix: &str,
+        query_bbox: &BoundingBox,
+    ) -> Result<Vec<(String, Geometry, Bytes)>> {
+        let inner = self.read()?;
+        let _timer = inner
+            .metrics
+            .time_spatial_query("find_geometries_intersecting");
+
+        let mut found = inner
+            .index_manager
+            .find_geometries_intersecting(prefix, query_bbox);
+        found.retain(|(id, _, _)| {
+            inner
+                .keys
+                .get(SpatialKey::geometry(prefix, id).as_bytes())
+                .is_some_and(|item| !item.is_expired())
+        });
+        Ok(found)
+    }
+
+    /// Find all points within an XYZ Web Mercator tile.
+    ///
+    /// Converts the tile to its bounding box with `BoundingBox::from_tile`
+    /// and queries it like `find_within_bounds`. Points exactly on an edge
+    /// shared by two tiles are returned for both.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace to search in
+    /// * `zoom` - Tile zoom level (0-30)
+    /// * `x` - Tile column
+    /// * `y` - Tile row
+    /// * `limit` - Maximum number of results to return
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tile does not exist at the zoom level.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Point, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let nyc = Point::new(40.7128, -74.0060);
+    /// db.insert_point("cities", &nyc, b"New York", None)?;
+    ///
+    /// let (x, y) = nyc.to_tile(10);
+    /// let points = db.find_within_tile("cities", 10, x, y, 100)?;
+    /// assert_eq!(points.len(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.find_within_tile",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.zoom = zoom,
+                db.limit = limit as i64,
+            ),
+            err
+        )
+    )]
+    pub fn find_within_tile(
+        &self,
+        prefix: &str,
+        zoom: u8,
+        x: u32,
+        y: u32,
+        limit: usize,
+    ) -> Result<Vec<(Point, Bytes)>> {
+        let tile = BoundingBox::from_tile(zoom, x, y)?;
+        self.find_within_bounds(
+            prefix,
+            tile.min.lat,
+            tile.min.lon,
+            tile.max.lat,
+            tile.max.lon,
+            limit,
+        )
+    }
+
+    /// Look up points by a geohash that may contain errors.
+    ///
+    /// Checks every geohash within `hamming_distance` characters of
+    /// `geohash`, as given by `GeohashUtils::fuzzy_decode`, for a point
+    /// stored under `prefix`. Points stored by `insert_point` are keyed by
+    /// their 8-character geohash, so `geohash` should have that length.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace to search in
+    /// * `geohash` - The possibly mistyped geohash
+    /// * `hamming_distance` - Most characters that may differ
+    ///
+    /// # Returns
+    ///
+    /// The geohash and data of each live match, in geohash order; empty if
+    /// `geohash` is not made of geohash characters
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Point, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let nyc = Point::new(40.7128, -74.0060);
+    /// db.insert_point("sensors", &nyc, b"sensor-1", None)?;
+    /// assert_eq!(nyc.to_geohash(8)?, "dr5regw3");
+    ///
+    /// // One character was mistyped
+    /// let matches = db.fuzzy_find_geohash_region("sensors", "dr5regx3", 1)?;
+    /// assert_eq!(matches.len(), 1);
+    /// assert_eq!(matches[0].0, "dr5regw3");
+    /// assert_eq!(matches[0].1.as_ref(), b"sensor-1");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.fuzzy_find_geohash_region",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.hamming_distance = hamming_distance as i64,
+            ),
+            err
+        )
+    )]
+    pub fn fuzzy_find_geohash_region(
+        &self,
+        prefix: &str,
+        geohash: &str,
+        hamming_distance: usize,
+    ) -> Result<Vec<(String, Bytes)>> {
+        let candidates = GeohashUtils::fuzzy_decode(geohash, hamming_distance);
+
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+        let _timer = inner
+            .metrics
+            .time_spatial_query("fuzzy_find_geohash_region");
+
+        Ok(candidates
+            .into_iter()
+            .filter_map(|candidate| {
+                let key = Bytes::from(SpatialKey::geohash(prefix, &candidate));
+                let item = inner.get_item(&key).filter(|item| !item.is_expired())?;
+                Some((candidate, item.value.clone()))
+            })
+            .collect())
+    }
+
+    /// Compute the convex hull of all points indexed under a prefix.
+    ///
+    /// Returns `None` if the prefix has fewer than three non-collinear
+    /// points, since no polygon can enclose them.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace whose points should be enclosed
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("sensors", &Point::new(40.70, -74.02), b"a", None)?;
+    /// db.insert_point("sensors", &Point::new(40.80, -74.02), b"b", None)?;
+    /// db.insert_point("sensors", &Point::new(40.75, -73.90), b"c", None)?;
+    ///
+    /// let hull = db.convex_hull_of_prefix("sensors")?.unwrap();
+    /// assert_eq!(hull.exterior().coords().len(), 4);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.convex_hull_of_prefix",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+            ),
+            err
+        )
+    )]
+    pub fn convex_hull_of_prefix(&self, prefix: &str) -> Result<Option<Polygon>> {
+        let (points, metrics) = {
+            let inner = self.read()?;
+            (
+                inner.index_manager.points_for_prefix(prefix),
+                inner.metrics.clone(),
+            )
+        };
+        let _timer = metrics.time_spatial_query("convex_hull_of_prefix");
+
+        match Polygon::convex_hull(&points) {
+            Ok(hull) => Ok(Some(hull)),
+            Err(SpatioError::InsufficientPoints) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Approximate the area reachable from a point using stored points.
+    ///
+    /// Finds every live point under `prefix` within `max_distance_meters`
+    /// of `origin` and returns the convex hull of those points and the
+    /// origin. This is a rough isochrone. Keep these limits in mind:
+    ///
+    /// * Distance is straight-line, not travel time along a road or path
+    ///   network, so barriers such as rivers are ignored.
+    /// * The hull fills in every concave gap between reachable points,
+    ///   which overstates reach around unreachable areas.
+    /// * The outline is only as detailed as the stored points are dense,
+    ///   and never extends past the outermost point found.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace of the sample points, such as reachable
+    ///   intersections or stops
+    /// * `origin` - Starting point
+    /// * `max_distance_meters` - Largest straight-line distance to include
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InsufficientPoints` if the origin and the
+    /// points found do not include three non-collinear points.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, Point, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("stops", &Point::new(40.7150, -74.0060), b"north", None)?;
+    /// db.insert_point("stops", &Point::new(40.7128, -74.0020), b"east", None)?;
+    /// db.insert_point("stops", &Point::new(40.7800, -74.0060), b"too far", None)?;
+    ///
+    /// let origin = Point::new(40.7128, -74.0060);
+    /// let zone = db.isochrone_from_point("stops", &origin, 1000.0)?;
+    /// assert!(zone.contains_point(&Coordinate::new(-74.0050, 40.7135)));
+    /// assert!(!zone.contains_point(&Coordinate::new(-74.0060, 40.7800)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.isochrone_from_point",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.max_distance_meters = max_distance_meters,
+            ),
+            err
+        )
+    )]
+    pub fn isochrone_from_point(
+        &self,
+        prefix: &str,
+        origin: &Point,
+        max_distance_meters: f64,
+    ) -> Result<Polygon> {
+        let mut points: Vec<Point> = self
+            .find_nearby(prefix, origin, max_distance_meters, usize::MAX)?
+            .into_iter()
+            .map(|(point, _)| point)
+            .collect();
+        points.push(*origin);
+        Polygon::convex_hull(&points)
+    }
+
+    /// Compute the Voronoi cell of every point in a namespace, clipped to
+    /// a bounding box.
+    ///
+    /// Points outside the box still shape the cells inside it. See
+    /// `SpatialAnalysis::voronoi_cells` for how cells are computed.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace whose points seed the cells
+    /// * `bbox` - Area the cells are clipped to
+    ///
+    /// # Returns
+    ///
+    /// Each point's stored data with its cell. Points whose cell lies
+    /// outside the box are left out.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{BoundingBox, Coordinate, Point, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("towers", &Point::new(40.70, -74.02), b"west", None)?;
+    /// db.insert_point("towers", &Point::new(40.70, -73.98), b"east", None)?;
+    ///
+    /// let area = BoundingBox::new(40.65, -74.05, 40.75, -73.95);
+    /// let cells = db.voronoi_for_prefix("towers", &area)?;
+    /// assert_eq!(cells.len(), 2);
+    ///
+    /// let location = Coordinate::new(-74.01, 40.71);
+    /// let (tower, _) = cells
+    ///     .iter()
+    ///     .find(|(_, cell)| cell.contains_point(&location))
+    ///     .unwrap();
+    /// assert_eq!(tower.as_ref(), b"west");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.voronoi_for_prefix",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+            ),
+            err
+        )
+    )]
+    pub fn voronoi_for_prefix(
+        &self,
+        prefix: &str,
+        bbox: &BoundingBox,
+    ) -> Result<Vec<(Bytes, Polygon)>> {
+        let (entries, metrics) = {
+            let inner = self.read()?;
+            (
+                inner.index_manager.entries_for_prefix(prefix),
+                inner.metrics.clone(),
+            )
+        };
+        let _timer = metrics.time_spatial_query("voronoi_for_prefix");
+
+        let points: Vec<Point> = entries.iter().map(|(point, _)| *point).collect();
+        let mut data: HashMap<Point, Bytes> = entries.into_iter().collect();
+        Ok(SpatialAnalysis::voronoi_cells(&points, bbox)
+            .into_iter()
+            .filter_map(|(point, cell)| Some((data.remove(&point)?, cell)))
+            .collect())
+    }
+
+    /// Count the points of a prefix in each geohash cell.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace whose points should be counted
+    /// * `precision` - Geohash precision of the cells (1-12)
+    ///
+    /// # Returns
+    ///
+    /// A map from geohash cell to the number of points inside it. Cells
+    /// without points are omitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InvalidGeohash` if `precision` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("taxis", &Point::new(40.7128, -74.0060), b"a", None)?;
+    /// db.insert_point("taxis", &Point::new(40.7140, -74.0050), b"b", None)?;
+    /// db.insert_point("taxis", &Point::new(51.5074, -0.1278), b"c", None)?;
+    ///
+    /// let density = db.spatial_aggregation("taxis", 4)?;
+    /// assert_eq!(density["dr5r"], 2);
+    /// assert_eq!(density["gcpv"], 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.spatial_aggregation",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.precision = precision as i64,
+            ),
+            err
+        )
+    )]
+    pub fn spatial_aggregation(
+        &self,
+        prefix: &str,
+        precision: usize,
+    ) -> Result<HashMap<String, usize>> {
+        let (points, metrics) = {
+            let inner = self.read()?;
+            (
+                inner.index_manager.points_for_prefix(prefix),
+                inner.metrics.clone(),
+            )
+        };
+        let _timer = metrics.time_spatial_query("spatial_aggregation");
+
+        count_by_geohash(points.iter(), precision)
+    }
+
+    /// Build a density heatmap of a prefix's points inside a bounding box.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace whose points should be counted
+    /// * `bbox` - Only points inside this box are counted
+    /// * `precision` - Geohash precision of the cells (1-12)
+    ///
+    /// # Returns
+    ///
+    /// `(geohash, cell_center, count)` for every cell holding at least one
+    /// point, busiest cells first
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InvalidGeohash` if `precision` is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{BoundingBox, Spatio, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("taxis", &Point::new(40.7128, -74.0060), b"a", None)?;
+    /// db.insert_point("taxis", &Point::new(40.7140, -74.0050), b"b", None)?;
+    /// db.insert_point("taxis", &Point::new(40.7580, -73.9855), b"c", None)?;
+    ///
+    /// let nyc = BoundingBox::new(40.5, -74.3, 40.9, -73.7);
+    /// let heatmap = db.spatial_heatmap("taxis", &nyc, 5)?;
+    /// assert_eq!(heatmap[0].2, 2);
+    /// assert_eq!(heatmap.len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.spatial_heatmap",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+                db.precision = precision as i64,
+            ),
+            err
+        )
+    )]
+    pub fn spatial_heatmap(
+        &self,
+        prefix: &str,
+        bbox: &BoundingBox,
+        precision: usize,
+    ) -> Result<Vec<(String, Point, usize)>> {
+        let (points, metrics) = {
+            let inner = self.read()?;
+            (
+                inner.index_manager.points_for_prefix(prefix),
+                inner.metrics.clone(),
+            )
+        };
+        let _timer = metrics.time_spatial_query("spatial_heatmap");
+
+        let counts = count_by_geohash(points.iter().filter(|p| bbox.contains(p)), precision)?;
+        let mut cells = counts
+            .into_iter()
+            .map(|(cell, count)| {
+                let (center, _, _) =
+                    geohash::decode(&cell).map_err(|_| SpatioError::InvalidGeohash)?;
+                Ok((cell, Point::new(center.y, center.x), count))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // Busiest cells first, ties broken by cell for a stable order
+        cells.sort_by(|a, b| b.2.cmp(&a.2).then_with(|| a.0.cmp(&b.0)));
+        Ok(cells)
+    }
+
+    /// Fold the points of a prefix inside a bounding box into one value.
+    ///
+    /// Computes statistics such as the number of sensors in an area or
+    /// their average reading without returning every point. The
+    /// aggregator starts from `A::default()` and is called once per live
+    /// point with its coordinates and data; `SpatialAggregator` provides
+    /// common aggregators.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace whose points should be aggregated
+    /// * `bbox` - Only points inside this box are aggregated
+    /// * `aggregator` - Combines the value so far with the next point
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{BoundingBox, Point, Spatio, SpatialAggregator};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("sensors", &Point::new(40.71, -74.00), b"a", None)?;
+    /// db.insert_point("sensors", &Point::new(40.75, -73.98), b"b", None)?;
+    /// db.insert_point("sensors", &Point::new(51.50, -0.12), b"c", None)?;
+    ///
+    /// let nyc = BoundingBox::new(40.5, -74.3, 40.9, -73.7);
+    /// assert_eq!(db.aggregate_spatial("sensors", &nyc, SpatialAggregator::count())?, 2);
+    ///
+    /// // Any fold works, e.g. the northernmost latitude
+    /// let north = db.aggregate_spatial("sensors", &nyc, |max: f64, point, _| max.max(point.lat))?;
+    /// assert_eq!(north, 40.75);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.aggregate_spatial",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+            ),
+            err
+        )
+    )]
+    pub fn aggregate_spatial<F, A>(
+        &self,
+        prefix: &str,
+        bbox: &BoundingBox,
+        mut aggregator: F,
+    ) -> Result<A>
+    where
+        F: FnMut(A, &Point, &[u8]) -> A,
+        A: Default,
+    {
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+        let _timer = inner.metrics.time_spatial_query("aggregate_spatial");
+
+        let points = inner.index_manager.find_within_bounds(
+            prefix,
+            bbox.min.lat,
+            bbox.min.lon,
+            bbox.max.lat,
+            bbox.max.lon,
+            usize::MAX,
+        )?;
+        Ok(points
+            .iter()
+            .filter(|(point, _)| !inner.point_expired(prefix, point))
+            .fold(A::default(), |acc, (point, data)| {
+                aggregator(acc, point, data)
+            }))
+    }
+
+    /// Force sync to disk
+    /// Force sync all pending writes to disk.
+    ///
+    /// This method flushes the AOF buffer and calls fsync to ensure all data
+    /// is durably written to disk. Useful before critical operations or when
+    /// you need to guarantee data persistence.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Spatio;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let dir = tempfile::tempdir()?;
+    /// let db = Spatio::open(dir.path().join("my_data.db"))?;
+    /// db.insert("critical_key", b"important_data", None)?;
+    ///
+    /// // Ensure data is on disk before continuing
+    /// db.sync()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.sync", skip_all, err)
+    )]
+    pub fn sync(&self) -> Result<()> {
+        let mut inner = self.write()?;
+        #[cfg(feature = "aof")]
+        if let Some(ref mut aof_file) = inner.aof_file {
+            aof_file.sync()?;
+        }
+        if let Some(ref mut backend) = inner.backend {
+            backend.sync()?;
+        }
+        Ok(())
+    }
+
+    /// Gracefully close the database.
+    ///
+    /// This method performs a clean shutdown by:
+    /// 1. Marking the database as closed (rejecting new operations)
+    /// 2. Flushing any pending writes to the AOF
+    /// 3. Syncing the AOF to disk (fsync)
+    /// 4. Releasing resources
+    ///
+    /// After calling `close()`, any further operations on this database
+    /// instance will return `DatabaseClosed` errors.
+    ///
+    /// **Note:** The database is also automatically closed when dropped,
+    /// so explicitly calling `close()` is optional but recommended for
+    /// explicit error handling.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Spatio;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let dir = tempfile::tempdir()?;
+    /// let mut db = Spatio::open(dir.path().join("my_data.db"))?;
+    /// db.insert("key", b"value", None)?;
+    ///
+    /// // Explicitly close and handle errors
+    /// db.close()?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.close", skip_all, err)
+    )]
+    pub fn close(&mut self) -> Result<()> {
+        let mut inner = self.write()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+
+        inner.closed = true;
+        if let Some(ref filter) = inner.key_filter {
+            filter.disable();
+        }
+        #[cfg(feature = "aof")]
         if let Some(ref mut aof_file) = inner.aof_file {
             aof_file.sync()?;
         }
-        Ok(())
+        inner.save_index_snapshot()?;
+        if let Some(ref mut backend) = inner.backend {
+            backend.close()?;
+        }
+        Ok(())
+    }
+
+    // Internal helper methods
+    pub(crate) fn read(&self) -> Result<RwLockReadGuard<'_, DBInner>> {
+        debug_assert!(!ViewScope::is_active(self), "{}", VIEW_REENTRY_MESSAGE);
+        sync::read(&self.inner)
+    }
+
+    pub(crate) fn write(&self) -> Result<RwLockWriteGuard<'_, DBInner>> {
+        debug_assert!(!ViewScope::is_active(self), "{}", VIEW_REENTRY_MESSAGE);
+        sync::write(&self.inner)
+    }
+
+    /// Take the write lock for an operation that modifies data
+    pub(crate) fn write_mut(&self) -> Result<RwLockWriteGuard<'_, DBInner>> {
+        let inner = self.write()?;
+        if inner.read_only {
+            return Err(SpatioError::ReadOnly);
+        }
+        Ok(inner)
+    }
+
+    /// Like `write_mut`, giving up with `SpatioError::LockTimeout` after
+    /// `timeout`
+    pub(crate) fn write_mut_with_timeout(
+        &self,
+        timeout: Duration,
+    ) -> Result<RwLockWriteGuard<'_, DBInner>> {
+        debug_assert!(!ViewScope::is_active(self), "{}", VIEW_REENTRY_MESSAGE);
+        let inner = sync::try_write_for(&self.inner, timeout)?.ok_or(SpatioError::LockTimeout)?;
+        if inner.read_only {
+            return Err(SpatioError::ReadOnly);
+        }
+        Ok(inner)
+    }
+}
+
+/// Automatic graceful shutdown on drop.
+///
+/// When the last reference to the database is dropped, it automatically performs a graceful shutdown:
+/// - Flushes pending writes
+/// - Syncs to disk (best effort, errors are silently ignored)
+/// - Saves the spatial index snapshot if `Config::persist_spatial_index` is set
+/// - Releases resources
+///
+/// Note: Since DB uses Arc internally, this syncs only when the last clone is dropped.
+/// The database is NOT marked as closed here to allow other clones to continue operating.
+/// Use `close()` explicitly if you need to prevent further operations.
+impl Drop for DB {
+    fn drop(&mut self) {
+        // Only sync if this is the last reference to the database
+        if Arc::strong_count(&self.inner) == 1 {
+            // Best-effort sync on final drop
+            if let Ok(mut inner) = sync::write(&self.inner)
+                && !inner.closed
+            {
+                // Attempt to sync on drop, but don't panic if it fails
+                #[cfg(feature = "aof")]
+                if let Some(ref mut aof_file) = inner.aof_file {
+                    let _ = aof_file.sync();
+                }
+                let _ = inner.save_index_snapshot();
+                if let Some(ref mut backend) = inner.backend {
+                    let _ = backend.sync();
+                }
+            }
+        }
+    }
+}
+
+const VIEW_REENTRY_MESSAGE: &str =
+    "DB methods must not be called from inside DB::view or DB::view_mut on the same database";
+
+thread_local! {
+    /// Databases whose lock the current thread holds inside `view` or
+    /// `view_mut`, identified by the address of their shared state
+    static ACTIVE_VIEWS: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Marks a database as inside `view` or `view_mut` on this thread until
+/// dropped, so re-entrant calls can be caught in debug builds
+struct ViewScope(usize);
+
+impl ViewScope {
+    fn enter(db: &DB) -> Self {
+        let id = Arc::as_ptr(&db.inner) as usize;
+        ACTIVE_VIEWS.with(|views| views.borrow_mut().push(id));
+        ViewScope(id)
+    }
+
+    fn is_active(db: &DB) -> bool {
+        let id = Arc::as_ptr(&db.inner) as usize;
+        ACTIVE_VIEWS.with(|views| views.borrow().contains(&id))
+    }
+}
+
+impl Drop for ViewScope {
+    fn drop(&mut self) {
+        ACTIVE_VIEWS.with(|views| {
+            let mut views = views.borrow_mut();
+            if let Some(pos) = views.iter().rposition(|id| *id == self.0) {
+                views.remove(pos);
+            }
+        });
+    }
+}
+
+/// Upper bound on the geohash cells covering a polygon in
+/// `DB::find_within_polygon_approximate`
+const POLYGON_COVERING_MAX_CELLS: usize = 64;
+
+/// First radius tried by `DB::find_k_nearest`
+const KNN_INITIAL_RADIUS_METERS: f64 = 1_000.0;
+
+/// Precision `DB::find_k_nearest` bisects the radius to
+const KNN_RADIUS_TOLERANCE_METERS: f64 = 1.0;
+
+/// Interval between the positions checked against the region in
+/// `DB::geofence_events`
+const GEOFENCE_SAMPLE_SECONDS: u64 = 10;
+
+/// Points each trajectory is resampled to in `DB::cluster_trajectories`
+const TRAJECTORY_CLUSTER_SAMPLES: usize = 32;
+
+/// Interval between samples in `DB::find_trajectory_crossings`
+const TRAJECTORY_BUCKET_SECONDS: u64 = 30;
+
+/// Position along a time-sorted trajectory at `time`, interpolated
+/// linearly between the recorded points around it
+fn interpolate_trajectory(track: &[(Point, u64)], time: u64) -> Option<Point> {
+    let after = track.partition_point(|(_, t)| *t < time);
+    let (next, next_time) = *track.get(after)?;
+    if next_time == time {
+        return Some(next);
+    }
+    let (prev, prev_time) = *track.get(after.checked_sub(1)?)?;
+
+    let fraction = (time - prev_time) as f64 / (next_time - prev_time) as f64;
+    Some(Point::new(
+        prev.lat + (next.lat - prev.lat) * fraction,
+        prev.lon + (next.lon - prev.lon) * fraction,
+    ))
+}
+
+/// Largest timestamp whose zero-padded trajectory key still sorts in
+/// numeric order
+const MAX_PADDED_TIMESTAMP: u64 = 9_999_999_999;
+
+/// Key range holding the trajectory points under `prefix` recorded in
+/// `[start_time, end_time]`.
+///
+/// Trajectory keys embed the timestamp zero-padded to ten digits, so the
+/// range can be narrowed to the requested times. Longer timestamps do not
+/// sort numerically, so the whole prefix is scanned for them instead.
+fn trajectory_key_range(
+    prefix: &str,
+    start_time: u64,
+    end_time: u64,
+) -> (Bound<Bytes>, Bound<Bytes>) {
+    let whole_prefix = Bound::Included(Bytes::from(prefix.to_string()));
+    if start_time > end_time || end_time >= MAX_PADDED_TIMESTAMP {
+        return (whole_prefix, Bound::Unbounded);
+    }
+
+    (
+        Bound::Included(Bytes::from(format!("{}{:010}", prefix, start_time))),
+        Bound::Excluded(Bytes::from(format!("{}{:010}", prefix, end_time + 1))),
+    )
+}
+
+/// Whether write options carry any expiration, recorded as `db.has_ttl` on spans.
+#[cfg(feature = "tracing")]
+fn has_ttl(opts: &Option<SetOptions>) -> bool {
+    opts.as_ref()
+        .is_some_and(|o| o.ttl.is_some() || o.expires_at.is_some())
+}
+
+/// Undirected edge between two points, with the lesser point first.
+/// Measuring in that order keeps the two copies of an edge found from
+/// either end identical.
+fn knn_edge(a: Point, b: Point) -> KnnEdge {
+    let (from_point, to_point) = if a <= b { (a, b) } else { (b, a) };
+    KnnEdge {
+        from_point,
+        to_point,
+        distance: from_point.distance_to(&to_point),
+    }
+}
+
+/// Sort edges by distance, then endpoints, and drop the second copy of
+/// edges found from both ends
+fn sort_knn_edges(edges: &mut Vec<KnnEdge>) {
+    edges.sort_by(|a, b| {
+        a.distance
+            .total_cmp(&b.distance)
+            .then_with(|| a.from_point.cmp(&b.from_point))
+            .then_with(|| a.to_point.cmp(&b.to_point))
+    });
+    edges.dedup_by(|a, b| a.from_point == b.from_point && a.to_point == b.to_point);
+}
+
+/// Whether the part of a trajectory key after `traj:{object_id}:` is
+/// exactly `{timestamp}:{index}`
+fn is_trajectory_point_suffix(suffix: &[u8]) -> bool {
+    let is_number = |part: &[u8]| !part.is_empty() && part.iter().all(u8::is_ascii_digit);
+    match suffix.iter().position(|&b| b == b':') {
+        Some(pos) => is_number(&suffix[..pos]) && is_number(&suffix[pos + 1..]),
+        None => false,
+    }
+}
+
+/// Stored value of a geometry key: the length of the geometry's WKB as a
+/// little-endian u32, the WKB itself, then the caller's data
+fn encode_geometry_value(geometry: &Geometry, data: &[u8]) -> Vec<u8> {
+    let wkb = geometry.to_bytes();
+    let mut value = Vec::with_capacity(4 + wkb.len() + data.len());
+    value.extend_from_slice(&(wkb.len() as u32).to_le_bytes());
+    value.extend_from_slice(&wkb);
+    value.extend_from_slice(data);
+    value
+}
+
+/// Split a value written by `encode_geometry_value` into the geometry and
+/// the caller's data
+fn decode_geometry_value(value: &Bytes) -> Option<(Geometry, Bytes)> {
+    let len = u32::from_le_bytes(value.get(..4)?.try_into().ok()?) as usize;
+    let wkb = value.get(4..4 + len)?;
+    let geometry = Geometry::from_bytes(wkb).ok()?;
+    Some((geometry, value.slice(4 + len..)))
+}
+
+/// Count points per geohash cell at the given precision
+fn count_by_geohash<'a>(
+    points: impl Iterator<Item = &'a Point>,
+    precision: usize,
+) -> Result<HashMap<String, usize>> {
+    let mut counts = HashMap::new();
+    for point in points {
+        let cell = point
+            .to_geohash(precision)
+            .map_err(|_| SpatioError::InvalidGeohash)?;
+        *counts.entry(cell).or_insert(0) += 1;
+    }
+    Ok(counts)
+}
+
+/// Run `task` under the write lock every `interval` until the database
+/// is dropped or closed
+fn spawn_maintenance(inner: Weak<RwLock<DBInner>>, interval: Duration, task: fn(&mut DBInner)) {
+    spawn_periodic(inner, interval, move |lock| {
+        let Ok(mut inner) = sync::write(lock) else {
+            return false;
+        };
+        if inner.closed {
+            return false;
+        }
+        task(&mut inner);
+        true
+    });
+}
+
+/// Run `task` every `interval` until the database is dropped or `task`
+/// returns `false`, leaving it to take whichever lock it needs.
+///
+/// The thread only holds a weak reference between runs, so it does not
+/// keep the database alive.
+fn spawn_periodic<F>(inner: Weak<RwLock<DBInner>>, interval: Duration, task: F)
+where
+    F: Fn(&RwLock<DBInner>) -> bool + Send + 'static,
+{
+    std::thread::spawn(move || {
+        loop {
+            std::thread::sleep(interval);
+            let Some(inner) = inner.upgrade() else {
+                break;
+            };
+            if !task(&inner) {
+                break;
+            }
+        }
+    });
+}
+
+/// Write checkpoint items to a fresh AOF file at `path`.
+///
+/// The file is built at `<path>.tmp`, synced, then renamed into place.
+/// A spatial index snapshot left next to `path` by an earlier database
+/// is removed, as it does not describe the new file.
+#[cfg(feature = "aof")]
+fn write_checkpoint(
+    path: &Path,
+    items: Vec<(Bytes, DbItem)>,
+    aof_config: AOFConfig,
+) -> Result<CheckpointStats> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = std::path::PathBuf::from(temp_path);
+    remove_if_exists(&temp_path)?;
+
+    let aof_config = AOFConfig {
+        // The file is already compact; a size-triggered rewrite would
+        // only copy it
+        rewrite_size_threshold: u64::MAX,
+        ..aof_config
+    };
+    let mut aof_file = AOFFile::open_with_config(&temp_path, aof_config)?;
+    for (key, item) in &items {
+        let opts = item.expires_at.map(SetOptions::with_expiration);
+        aof_file.write_typed_set(key, &item.value, opts.as_ref(), item.value_type)?;
+    }
+    aof_file.sync()?;
+    let stats = CheckpointStats {
+        keys_written: items.len(),
+        bytes_written: aof_file.size(),
+    };
+    drop(aof_file);
+
+    std::fs::rename(&temp_path, path)?;
+    let mut stale_snapshot = path.as_os_str().to_owned();
+    stale_snapshot.push(".sidx");
+    remove_if_exists(Path::new(&stale_snapshot))?;
+    Ok(stats)
+}
+
+/// Remove a file, treating a missing file as already removed
+#[cfg(feature = "aof")]
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// A point in a sliding window, with the time its TTL was set
+#[derive(Clone)]
+pub(crate) struct WindowPoint {
+    pub set_at: SystemTime,
+    pub point: Point,
+    pub value: Bytes,
+}
+
+/// The `limit` most recently set window points, newest first
+pub(crate) fn latest_points(mut points: Vec<WindowPoint>, limit: usize) -> Vec<(Point, Bytes)> {
+    points.sort_by_key(|point| std::cmp::Reverse(point.set_at));
+    points
+        .into_iter()
+        .take(limit)
+        .map(|point| (point.point, point.value))
+        .collect()
+}
+
+/// Leading prefixes of `key` that end in `:`, e.g. `cities:` and
+/// `cities:gh:` for `cities:gh:dr5regw3`. These are the prefixes kept in
+/// `DBInner::prefix_counts`; a `::` namespace prefix is one of them.
+fn counted_prefixes(key: &[u8]) -> impl Iterator<Item = &[u8]> {
+    key.iter()
+        .enumerate()
+        .filter(|&(_, &b)| b == b':')
+        .map(move |(i, _)| &key[..=i])
+}
+
+/// Whether two paths name the same existing file
+#[cfg(feature = "aof")]
+fn same_file(a: &Path, b: &Path) -> bool {
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+impl DBInner {
+    /// Store a value, persisting it to the AOF, and return the old value
+    pub fn set(
+        &mut self,
+        key: &[u8],
+        value: &[u8],
+        opts: Option<SetOptions>,
+    ) -> Result<Option<Bytes>> {
+        let key_bytes = Bytes::copy_from_slice(key);
+        let old = self.store(key_bytes.clone(), value, opts.as_ref())?;
+        self.write_to_aof_if_needed(&key_bytes, value, opts.as_ref())?;
+        Ok(old.map(|item| item.value))
+    }
+
+    /// Store a value recorded as `value_type`, persisting it to the AOF
+    pub(crate) fn set_typed(
+        &mut self,
+        key: &[u8],
+        value: &[u8],
+        opts: Option<SetOptions>,
+        value_type: ValueType,
+    ) -> Result<()> {
+        let key_bytes = Bytes::copy_from_slice(key);
+        self.store(key_bytes.clone(), value, opts.as_ref())?;
+        if let Some(item) = self.keys.get_mut(&key_bytes) {
+            item.value_type = Some(value_type);
+        }
+        self.write_to_aof_if_needed(&key_bytes, value, opts.as_ref())
+    }
+
+    /// Store a value in memory only, making room for it first, and return
+    /// the old item
+    fn store(
+        &mut self,
+        key: Bytes,
+        value: &[u8],
+        opts: Option<&SetOptions>,
+    ) -> Result<Option<DbItem>> {
+        let value_bytes = Bytes::copy_from_slice(value);
+
+        let item = match opts {
+            Some(SetOptions { ttl: Some(ttl), .. }) => DbItem::with_ttl(value_bytes, *ttl),
+            Some(SetOptions {
+                expires_at: Some(expires_at),
+                ..
+            }) => DbItem::with_expiration(value_bytes, *expires_at),
+            _ => DbItem::new(value_bytes),
+        };
+
+        self.make_room(&key, item.value.len())?;
+        let old = self.insert_item(key, item);
+        self.metrics.record_inserts(1);
+        Ok(old)
+    }
+
+    /// Write the current value of each of `keys` to the storage backend in
+    /// one batch and to the AOF, appending the records in chunks of about
+    /// `Config::bulk_load_sync_after_bytes` and syncing after each.
+    ///
+    /// Keys no longer stored, e.g. evicted during the load, are skipped.
+    /// Returns the bytes appended to the AOF.
+    fn persist_bulk(&mut self, keys: &IndexSet<Bytes>) -> Result<u64> {
+        if let Some(ref mut backend) = self.backend {
+            let ops: Vec<StorageOp> = keys
+                .iter()
+                .filter_map(|key| {
+                    let item = self.keys.get(key)?.clone();
+                    Some(StorageOp::Put {
+                        key: key.clone(),
+                        item,
+                    })
+                })
+                .collect();
+            backend.batch(&ops)?;
+        }
+
+        #[cfg(feature = "aof")]
+        if let Some(ref mut aof_file) = self.aof_file {
+            let sync_after = self.config.bulk_load_sync_after_bytes;
+            let mut bytes_written = 0;
+            let mut pending = Vec::new();
+            let mut pending_bytes = 0;
+            for key in keys {
+                let Some(item) = self.keys.get(key) else {
+                    continue;
+                };
+                pending_bytes += key.len() + item.value.len();
+                pending.push(AOFCommand::Set {
+                    key: key.clone(),
+                    value: item.value.clone(),
+                    expires_at: item.expires_at,
+                    value_type: item.value_type,
+                });
+                if sync_after > 0 && pending_bytes >= sync_after {
+                    bytes_written += aof_file.write_commands(&pending)?;
+                    aof_file.sync()?;
+                    pending.clear();
+                    pending_bytes = 0;
+                }
+            }
+            bytes_written += aof_file.write_commands(&pending)?;
+            aof_file.sync()?;
+            return Ok(bytes_written);
+        }
+        Ok(0)
+    }
+
+    /// Remove a key, persisting the deletion to the AOF, and return its value
+    pub fn delete_key(&mut self, key: &[u8]) -> Result<Option<Bytes>> {
+        let key_bytes = Bytes::copy_from_slice(key);
+
+        if let Some(item) = self.remove_item(&key_bytes) {
+            self.write_delete_to_aof_if_needed(&key_bytes)?;
+            Ok(Some(item.value))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Change the expiration of a live key in place, persisting it as an
+    /// EXPIRE. Returns false if the key is missing or already expired.
+    pub fn set_expiration(&mut self, key: &[u8], expires_at: SystemTime) -> Result<bool> {
+        let Some(item) = self.keys.get_mut(key).filter(|item| !item.is_expired()) else {
+            return Ok(false);
+        };
+        let previous = item.expires_at.replace(expires_at);
+        let key_bytes = Bytes::copy_from_slice(key);
+
+        if let Some(previous) = previous
+            && let Some(keys) = self.expirations.get_mut(&previous)
+        {
+            keys.retain(|k| k != &key_bytes);
+            if keys.is_empty() {
+                self.expirations.remove(&previous);
+            }
+        }
+        self.expirations
+            .entry(expires_at)
+            .or_default()
+            .push(key_bytes.clone());
+        self.bump_version(&key_bytes);
+        self.ttl_set_at.insert(key_bytes.clone(), clock::now());
+
+        self.write_expire_to_aof_if_needed(&key_bytes, expires_at)?;
+        Ok(true)
+    }
+
+    /// Remove every expired key, writing a DELETE to the AOF for each
+    pub fn cleanup_expired(&mut self) -> Result<usize> {
+        let now = clock::now();
+        let expired: Vec<Bytes> = self
+            .expirations
+            .range(..=now)
+            .flat_map(|(_, keys)| keys.iter().cloned())
+            .collect();
+
+        for key in &expired {
+            self.expire_item(key);
+            self.write_delete_to_aof_if_needed(key)?;
+        }
+
+        self.stats.record_expired(expired.len() as u64);
+        self.metrics.record_expired(expired.len() as u64);
+        Ok(expired.len())
+    }
+
+    /// Insert an item into the database
+    pub fn insert_item(&mut self, key: Bytes, item: DbItem) -> Option<DbItem> {
+        // Remove from old expiration index if updating
+        let old_item = if let Some(old) = self.keys.get(&key) {
+            if let Some(expires_at) = old.expires_at
+                && let Some(keys) = self.expirations.get_mut(&expires_at)
+            {
+                keys.retain(|k| k != &key);
+                if keys.is_empty() {
+                    self.expirations.remove(&expires_at);
+                }
+            }
+            Some(old.clone())
+        } else {
+            None
+        };
+
+        // Add to expiration index if TTL is set
+        if let Some(expires_at) = item.expires_at {
+            self.expirations
+                .entry(expires_at)
+                .or_default()
+                .push(key.clone());
+        }
+
+        // Track approximate memory usage and access order
+        if let Some(ref old) = old_item {
+            self.stats.size_bytes -= key.len() + old.value.len();
+        }
+        self.stats.size_bytes += key.len() + item.value.len();
+        self.touch(&key);
+
+        self.watchers
+            .notify(&key, KeyEventType::Set, Some(&item.value));
+
+        if old_item.is_none() {
+            self.count_key(&key, true);
+        }
+        self.bump_version(&key);
+        if item.expires_at.is_some() {
+            self.ttl_set_at.insert(key.clone(), clock::now());
+        } else {
+            self.ttl_set_at.remove(&key);
+        }
+
+        if let Some(ref filter) = self.key_filter {
+            filter.insert(&key);
+        }
+
+        // Insert into main storage
+        self.keys.insert(key, item);
+        self.stats.key_count = self.keys.len();
+        self.metrics.set_key_count(self.keys.len());
+
+        old_item
+    }
+
+    /// Give a key a new version after a write to it
+    fn bump_version(&mut self, key: &Bytes) {
+        self.write_version += 1;
+        self.key_versions.insert(key.clone(), self.write_version);
+    }
+
+    /// Version of a live key, or `None` if it is missing or expired
+    pub fn key_version(&self, key: &[u8]) -> Option<u64> {
+        self.keys
+            .get(key)
+            .filter(|item| !item.is_expired())
+            .map(|_| self.key_versions.get(key).copied().unwrap_or(0))
+    }
+
+    /// Account for a key being added (`added`) or removed in the prefix
+    /// counts and prefix count watchers
+    fn count_key(&mut self, key: &Bytes, added: bool) {
+        self.watchers.count_changed(key, added);
+        if !self.config.track_prefix_counts {
+            return;
+        }
+
+        for prefix in counted_prefixes(key) {
+            match self.prefix_counts.get_mut(prefix) {
+                Some(count) if added => *count += 1,
+                Some(count) => {
+                    *count -= 1;
+                    if *count == 0 {
+                        self.prefix_counts.remove(prefix);
+                    }
+                }
+                None if added => {
+                    self.prefix_counts.insert(Bytes::copy_from_slice(prefix), 1);
+                }
+                None => {}
+            }
+        }
+    }
+
+    /// Number of stored keys starting with `prefix`, from the prefix counts
+    /// when they cover it and by scanning the range otherwise
+    pub fn count_prefix(&self, prefix: &[u8]) -> usize {
+        if prefix.is_empty() {
+            return self.keys.len();
+        }
+        if self.config.track_prefix_counts && prefix.ends_with(b":") {
+            return self.prefix_counts.get(prefix).copied().unwrap_or(0);
+        }
+        self.keys
+            .range(Bytes::copy_from_slice(prefix)..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .count()
+    }
+
+    /// Remove an item from the database
+    pub fn remove_item(&mut self, key: &Bytes) -> Option<DbItem> {
+        let item = self.take_item(key)?;
+        self.watchers.notify(key, KeyEventType::Deleted, None);
+        self.count_key(key, false);
+        Some(item)
+    }
+
+    /// Remove an item whose TTL has passed
+    pub fn expire_item(&mut self, key: &Bytes) -> Option<DbItem> {
+        let item = self.take_item(key)?;
+        self.watchers.notify(key, KeyEventType::Expired, None);
+        self.count_key(key, false);
+        Some(item)
+    }
+
+    /// Live items under `prefix` after `cursor` that `filter` accepts, in
+    /// key order
+    fn scan_filtered<'a>(
+        &'a self,
+        prefix: &'a [u8],
+        cursor: Option<&[u8]>,
+        filter: &'a impl Fn(&[u8], &[u8]) -> bool,
+    ) -> impl Iterator<Item = (&'a Bytes, &'a DbItem)> + 'a {
+        let start = match cursor {
+            Some(cursor) if cursor >= prefix => Bound::Excluded(Bytes::copy_from_slice(cursor)),
+            _ => Bound::Included(Bytes::copy_from_slice(prefix)),
+        };
+        self.keys
+            .range((start, Bound::Unbounded))
+            .take_while(move |(key, _)| key.starts_with(prefix))
+            .filter(move |(key, item)| !item.is_expired() && filter(key, &item.value))
+    }
+
+    /// Live points within `radius_meters` of `center` under `prefix` whose
+    /// TTL was set at most `window` ago, with their keys, in no particular
+    /// order
+    fn window_points(
+        &self,
+        prefix: &str,
+        center: &Point,
+        radius_meters: f64,
+        window: Duration,
+    ) -> Result<Vec<(Bytes, WindowPoint)>> {
+        let since = clock::now()
+            .checked_sub(window)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let found = self
+            .index_manager
+            .find_nearby(prefix, center, radius_meters, usize::MAX)?;
+
+        Ok(found
+            .into_iter()
+            .filter_map(|(point, value)| {
+                let geohash = point.to_geohash(8).ok()?;
+                let key = Bytes::from(SpatialKey::geohash(prefix, &geohash));
+                self.keys.get(&key).filter(|item| !item.is_expired())?;
+                let set_at = *self
+                    .ttl_set_at
+                    .get(&key)
+                    .filter(|set_at| **set_at >= since)?;
+                Some((
+                    key,
+                    WindowPoint {
+                        set_at,
+                        point,
+                        value,
+                    },
+                ))
+            })
+            .collect())
+    }
+
+    /// Live points within `radius_meters` of `center`, nearest first
+    fn live_nearby(
+        &self,
+        prefix: &str,
+        center: &Point,
+        radius_meters: f64,
+        limit: usize,
+    ) -> Result<Vec<(Point, Bytes)>> {
+        let mut found = self
+            .index_manager
+            .find_nearby(prefix, center, radius_meters, limit)?;
+
+        if found
+            .iter()
+            .any(|(point, _)| self.point_expired(prefix, point))
+        {
+            // Expired points may have crowded out live ones, so search again
+            // without the limit before filtering
+            found = self
+                .index_manager
+                .find_nearby(prefix, center, radius_meters, usize::MAX)?;
+            found.retain(|(point, _)| !self.point_expired(prefix, point));
+            found.truncate(limit);
+        }
+        Ok(found)
+    }
+
+    /// Points of `prefix_a` with no point of `prefix_b` within
+    /// `exclusion_radius_meters`, up to `limit` of them
+    fn spatial_difference(
+        &self,
+        prefix_a: &str,
+        prefix_b: &str,
+        exclusion_radius_meters: f64,
+        limit: usize,
+    ) -> Result<Vec<(Point, Bytes)>> {
+        let mut unserved = Vec::new();
+        for (point, data) in self.index_manager.entries_for_prefix(prefix_a) {
+            if unserved.len() >= limit {
+                break;
+            }
+            if !self
+                .index_manager
+                .contains_point(prefix_b, &point, exclusion_radius_meters)?
+            {
+                unserved.push((point, data));
+            }
+        }
+        Ok(unserved)
+    }
+
+    /// Geohash index entries whose point key is not stored
+    fn stale_spatial_entries(&self) -> Vec<StaleEntry> {
+        let mut stale: Vec<StaleEntry> = self
+            .index_manager
+            .iter_geohash_points()
+            .filter_map(|(prefix, point)| {
+                let geohash = point.to_geohash(8).ok()?;
+                let key = SpatialKey::geohash(prefix, &geohash);
+                if self.keys.contains_key(key.as_bytes()) {
+                    return None;
+                }
+                Some(StaleEntry {
+                    prefix: prefix.to_string(),
+                    geohash,
+                    orphaned_point: *point,
+                })
+            })
+            .collect();
+        stale.sort_by(|a, b| (&a.prefix, &a.geohash).cmp(&(&b.prefix, &b.geohash)));
+        stale
+    }
+
+    /// Whether the key stored for a point indexed under `prefix` has
+    /// expired. Points without a key of their own count as live.
+    fn point_expired(&self, prefix: &str, point: &Point) -> bool {
+        point.to_geohash(8).is_ok_and(|geohash| {
+            self.keys
+                .get(SpatialKey::geohash(prefix, &geohash).as_bytes())
+                .is_some_and(|item| item.is_expired())
+        })
+    }
+
+    /// Remove an item and its index entries without notifying watchers
+    fn take_item(&mut self, key: &Bytes) -> Option<DbItem> {
+        if let Some(item) = self.keys.remove(key) {
+            self.key_versions.remove(key);
+            self.ttl_set_at.remove(key);
+            self.key_filter_removed();
+
+            // Remove from expiration index
+            if let Some(expires_at) = item.expires_at
+                && let Some(keys) = self.expirations.get_mut(&expires_at)
+            {
+                keys.retain(|k| k != key);
+                if keys.is_empty() {
+                    self.expirations.remove(&expires_at);
+                }
+            }
+
+            if let Ok(key_str) = std::str::from_utf8(key)
+                && let Some((prefix, id)) = self.parse_geometry_key(key_str)
+            {
+                self.index_manager.remove_geometry(prefix, id);
+            }
+
+            self.stats.key_count = self.keys.len();
+            self.metrics.set_key_count(self.keys.len());
+            self.stats.size_bytes -= key.len() + item.value.len();
+            if self.config.max_memory_bytes.is_some() {
+                self.access_order
+                    .get_mut()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .shift_remove(key);
+            }
+            Some(item)
+        } else {
+            None
+        }
+    }
+
+    /// Note a removed key in the Bloom filter, rebuilding it from the
+    /// remaining keys once more keys have been removed than remain
+    fn key_filter_removed(&self) {
+        if let Some(ref filter) = self.key_filter
+            && filter.note_removed(self.keys.len())
+        {
+            filter.rebuild(self.keys.keys().map(|key| key.as_ref()));
+        }
+    }
+
+    /// Record an access to a key for LRU eviction.
+    ///
+    /// Spatial keys are never evicted, so they are not tracked.
+    pub fn touch(&self, key: &Bytes) {
+        if self.config.max_memory_bytes.is_none() || SpatialKey::is_spatial(key) {
+            return;
+        }
+
+        let mut order = self.access_order.lock().unwrap_or_else(|e| e.into_inner());
+        match order.get_index_of(key) {
+            Some(index) => {
+                let last = order.len() - 1;
+                order.move_index(index, last);
+            }
+            None => {
+                order.insert(key.clone());
+            }
+        }
+    }
+
+    /// Ensure a value of `value_len` bytes can be stored under `key`.
+    ///
+    /// With `EvictionPolicy::LeastRecentlyUsed`, least-recently-used keys are
+    /// evicted and logged to the AOF as deletes until the write fits.
+    /// Otherwise `SpatioError::MemoryLimitExceeded` is returned.
+    pub fn make_room(&mut self, key: &Bytes, value_len: usize) -> Result<()> {
+        let limit = match self.config.max_memory_bytes {
+            Some(limit) => limit,
+            None => return Ok(()),
+        };
+
+        let incoming = key.len() + value_len;
+        if incoming > limit {
+            return Err(SpatioError::MemoryLimitExceeded);
+        }
+
+        loop {
+            // Overwriting a key frees its current entry
+            let existing = self
+                .keys
+                .get(key)
+                .map_or(0, |item| key.len() + item.value.len());
+            if self.stats.size_bytes - existing + incoming <= limit {
+                return Ok(());
+            }
+
+            if self.config.eviction_policy == EvictionPolicy::NoEviction {
+                return Err(SpatioError::MemoryLimitExceeded);
+            }
+
+            let victim = {
+                let order = self
+                    .access_order
+                    .get_mut()
+                    .unwrap_or_else(|e| e.into_inner());
+                order.iter().find(|candidate| *candidate != key).cloned()
+            };
+            let victim = victim.ok_or(SpatioError::MemoryLimitExceeded)?;
+
+            self.remove_item(&victim);
+            self.write_delete_to_aof_if_needed(&victim)?;
+            self.stats.record_evicted(1);
+        }
+    }
+
+    /// Get an item from the database
+    pub fn get_item(&self, key: &Bytes) -> Option<&DbItem> {
+        self.keys.get(key)
+    }
+
+    /// Collect all keys starting with the given prefix
+    pub fn keys_with_prefix(&self, prefix: &[u8]) -> Vec<Bytes> {
+        self.keys
+            .range(Bytes::copy_from_slice(prefix)..)
+            .take_while(|(key, _)| key.starts_with(prefix))
+            .map(|(key, _)| key.clone())
+            .collect()
+    }
+
+    /// Load data from AOF file
+    /// Load database state from the AOF file (startup replay).
+    ///
+    /// This method replays all commands from the append-only file to restore
+    /// the database to its previous state. It's called automatically during
+    /// database initialization.
+    ///
+    /// The replay process:
+    /// 1. Reads all commands from the AOF sequentially
+    /// 2. Applies each SET and DELETE command to rebuild state
+    /// 3. Reconstructs spatial indexes from geographic data
+    /// 4. Updates statistics (key counts, etc.)
+    ///
+    /// # Error Handling
+    ///
+    /// If the AOF is corrupted or unreadable, this method returns an error
+    /// and the database will not open. To recover from corruption:
+    /// - Restore from backup if available
+    /// - Or delete the AOF file to start fresh (data loss)
+    #[cfg(feature = "aof")]
+    pub fn load_from_aof(&mut self, aof_file: &mut AOFFile) -> Result<()> {
+        // Spatial keys written before the snapshot's offset are indexed
+        // from the snapshot rather than one record at a time
+        let mut snapshot = if self.config.persist_spatial_index {
+            self.read_index_snapshot(aof_file)
+        } else {
+            None
+        };
+        let indexed_until = snapshot
+            .as_ref()
+            .map_or(0, |snapshot| snapshot.aof_position().offset);
+
+        let (commands, _) = aof_file.replay_from_offset(0, self.config.aof_recovery_mode)?;
+
+        for (offset, command) in commands {
+            let indexed = offset < indexed_until;
+            if !indexed && let Some(snapshot) = snapshot.take() {
+                self.apply_index_snapshot(snapshot);
+            }
+            match command {
+                AOFCommand::Set {
+                    key,
+                    value,
+                    expires_at,
+                    value_type,
+                } => {
+                    let item = DbItem {
+                        value,
+                        expires_at,
+                        value_type,
+                    };
+                    if indexed {
+                        self.restore_key(key, item);
+                    } else {
+                        self.restore_item(key, item);
+                    }
+                }
+                AOFCommand::Delete { key } => {
+                    if indexed {
+                        self.restore_key_delete(&key);
+                    } else {
+                        self.restore_delete(&key);
+                    }
+                }
+                AOFCommand::Expire { key, expires_at } => {
+                    self.restore_expire(&key, expires_at);
+                }
+            }
+        }
+        if let Some(snapshot) = snapshot {
+            self.apply_index_snapshot(snapshot);
+        }
+
+        self.finish_restore();
+        Ok(())
+    }
+
+    /// Collect the live items for a checkpoint, with the AOF settings to
+    /// write them with
+    #[cfg(feature = "aof")]
+    fn checkpoint_items(&self) -> (Vec<(Bytes, DbItem)>, AOFConfig) {
+        let now = clock::now();
+        let items = self
+            .keys
+            .iter()
+            .filter(|(_, item)| !item.is_expired_at(now))
+            .map(|(key, item)| (key.clone(), item.clone()))
+            .collect();
+        let aof_config = self
+            .aof_file
+            .as_ref()
+            .map(|aof_file| aof_file.config().clone())
+            .unwrap_or_default();
+        (items, aof_config)
+    }
+
+    /// Read the spatial index snapshot kept next to the AOF.
+    ///
+    /// Returns `None` if there is no usable snapshot: missing, unreadable,
+    /// built with another geohash precision, or taken from a file that has
+    /// since been rewritten. Only the checksum footer the snapshot was
+    /// taken at is read to tell.
+    #[cfg(feature = "aof")]
+    fn read_index_snapshot(&self, aof_file: &mut AOFFile) -> Option<IndexSnapshot> {
+        let path = aof_file.index_snapshot_path();
+        if !path.exists() {
+            return None;
+        }
+
+        let snapshot = IndexSnapshot::load_from_path(&path, &self.config).ok()?;
+        aof_file
+            .is_sealed_at(&snapshot.aof_position())
+            .unwrap_or(false)
+            .then_some(snapshot)
+    }
+
+    /// Index the spatial keys replayed so far from a snapshot, looking up
+    /// each entry's data in the stored values. If the snapshot cannot be
+    /// applied, every spatial key is indexed from its value instead.
+    #[cfg(feature = "aof")]
+    fn apply_index_snapshot(&mut self, snapshot: IndexSnapshot) {
+        let keys = &self.keys;
+        let applied = self.index_manager.apply_snapshot(
+            snapshot,
+            |key| keys.get(key).map(|item| item.value.clone()),
+            |key| {
+                keys.get(key)
+                    .and_then(|item| decode_geometry_value(&item.value))
+            },
+        );
+        if applied.is_err() {
+            self.reindex_spatial_keys();
+        }
+    }
+
+    /// Save the spatial index snapshot next to the AOF, if enabled
+    fn save_index_snapshot(&mut self) -> Result<()> {
+        if !self.config.persist_spatial_index || self.read_only {
+            return Ok(());
+        }
+
+        #[cfg(feature = "aof")]
+        if let Some(ref mut aof_file) = self.aof_file {
+            let position = aof_file.seal()?;
+            self.index_manager
+                .save_to_path(&aof_file.index_snapshot_path(), &position)?;
+        }
+        Ok(())
+    }
+
+    /// Load database state from the attached storage backend.
+    ///
+    /// Every stored item is restored and spatial indexes are rebuilt from
+    /// spatial keys, the same way AOF replay does.
+    pub fn load_from_backend(&mut self) -> Result<()> {
+        let items: Vec<(Bytes, DbItem)> = match self.backend {
+            Some(ref backend) => backend.iter()?.collect(),
+            None => return Ok(()),
+        };
+
+        for (key, item) in items {
+            self.restore_item(key, item);
+        }
+
+        self.finish_restore();
+        Ok(())
+    }
+
+    /// Apply a restored SET, rebuilding the spatial index for spatial keys
+    fn restore_item(&mut self, key: Bytes, item: DbItem) {
+        let value = item.value.clone();
+        self.restore_key(key.clone(), item);
+        self.index_spatial_key(&key, &value);
+    }
+
+    /// Apply a restored SET without touching the spatial index
+    fn restore_key(&mut self, key: Bytes, item: DbItem) {
+        self.bump_version(&key);
+        self.ttl_set_at.remove(&key);
+        if let Some(ref filter) = self.key_filter {
+            filter.insert(&key);
+        }
+        self.keys.insert(key, item);
+    }
+
+    /// Add a spatial key to its index; other keys are ignored
+    fn index_spatial_key(&mut self, key: &Bytes, value: &Bytes) {
+        if let Ok(key_str) = std::str::from_utf8(key)
+            && let Some((prefix, geohash)) = self.parse_spatial_key(key_str)
+            && let Ok(point) = self.decode_geohash_to_point(geohash)
+        {
+            let _ = self.index_manager.insert_point(prefix, &point, value);
+        }
+
+        #[cfg(feature = "s2")]
+        if let Ok(key_str) = std::str::from_utf8(key)
+            && let Some((prefix, cell)) = self.parse_s2_key(key_str)
+        {
+            let point = self.decode_s2_to_point(cell);
+            let _ = self.index_manager.insert_point_with_strategy(
+                prefix,
+                &point,
+                value,
+                IndexStrategy::S2 {
+                    level: cell.level() as u8,
+                },
+            );
+        }
+
+        #[cfg(feature = "h3")]
+        if let Ok(key_str) = std::str::from_utf8(key)
+            && let Some((prefix, cell)) = self.parse_h3_key(key_str)
+            && let Ok(point) = self.decode_h3_to_point(cell)
+        {
+            let _ = self.index_manager.insert_point_with_strategy(
+                prefix,
+                &point,
+                value,
+                IndexStrategy::H3 {
+                    resolution: u8::from(cell.resolution()),
+                },
+            );
+        }
+
+        if let Ok(key_str) = std::str::from_utf8(key)
+            && let Some((prefix, id)) = self.parse_geometry_key(key_str)
+            && let Some((geometry, data)) = decode_geometry_value(value)
+        {
+            let _ = self
+                .index_manager
+                .insert_geometry(prefix, id, &geometry, &data);
+        }
+    }
+
+    /// Purge expired keys, prune the expiration index and rebuild the
+    /// spatial index
+    fn compact_memory(&mut self) -> Result<CompactionStats> {
+        let now = clock::now();
+        let expired: Vec<Bytes> = self
+            .expirations
+            .range(..=now)
+            .flat_map(|(_, keys)| keys.iter().cloned())
+            .collect();
+
+        let mut stats = CompactionStats::default();
+        for key in &expired {
+            if let Some(item) = self.expire_item(key) {
+                stats.keys_removed += 1;
+                stats.bytes_freed += key.len() + item.value.len();
+            }
+            self.write_delete_to_aof_if_needed(key)?;
+        }
+        self.stats.record_expired(stats.keys_removed as u64);
+        self.metrics.record_expired(stats.keys_removed as u64);
+
+        // Drop entries that no longer match a stored key's expiration
+        let keys = &self.keys;
+        self.expirations.retain(|expires_at, entries| {
+            entries.retain(|key| {
+                keys.get(key)
+                    .is_some_and(|item| item.expires_at == Some(*expires_at))
+            });
+            entries.shrink_to_fit();
+            !entries.is_empty()
+        });
+
+        self.reindex_spatial_keys();
+
+        Ok(stats)
+    }
+
+    /// Rebuild the spatial indexes from the stored spatial keys
+    fn reindex_spatial_keys(&mut self) {
+        self.index_manager = IndexManager::with_config(&self.config);
+        let spatial: Vec<(Bytes, Bytes)> = self
+            .keys
+            .iter()
+            .filter(|(key, _)| SpatialKey::is_spatial(key))
+            .map(|(key, item)| (key.clone(), item.value.clone()))
+            .collect();
+        for (key, value) in &spatial {
+            self.index_spatial_key(key, value);
+        }
+    }
+
+    /// Apply a restored DELETE, removing spatial keys from their index
+    #[cfg(feature = "aof")]
+    fn restore_delete(&mut self, key: &Bytes) {
+        self.restore_key_delete(key);
+
+        // Remove from spatial index if this was a spatial key
+        if let Ok(key_str) = std::str::from_utf8(key)
+            && let Some((prefix, geohash)) = self.parse_spatial_key(key_str)
+            && let Ok(point) = self.decode_geohash_to_point(geohash)
+        {
+            let _ = self.index_manager.remove_point(prefix, &point);
+        }
+
+        #[cfg(feature = "s2")]
+        if let Ok(key_str) = std::str::from_utf8(key)
+            && let Some((prefix, cell)) = self.parse_s2_key(key_str)
+        {
+            self.index_manager.remove_s2_cell(prefix, cell.0);
+        }
+
+        #[cfg(feature = "h3")]
+        if let Ok(key_str) = std::str::from_utf8(key)
+            && let Some((prefix, cell)) = self.parse_h3_key(key_str)
+        {
+            self.index_manager.remove_h3_cell(prefix, u64::from(cell));
+        }
+
+        if let Ok(key_str) = std::str::from_utf8(key)
+            && let Some((prefix, id)) = self.parse_geometry_key(key_str)
+        {
+            self.index_manager.remove_geometry(prefix, id);
+        }
+    }
+
+    /// Apply a restored DELETE without touching the spatial index
+    #[cfg(feature = "aof")]
+    fn restore_key_delete(&mut self, key: &Bytes) {
+        if self.keys.remove(key).is_some() {
+            self.key_filter_removed();
+        }
+        self.key_versions.remove(key);
+        self.ttl_set_at.remove(key);
+    }
+
+    /// Apply a restored EXPIRE, returning whether the key was present.
+    ///
+    /// The expiration index is rebuilt by `finish_restore`.
+    #[cfg(feature = "aof")]
+    fn restore_expire(&mut self, key: &Bytes, expires_at: SystemTime) -> bool {
+        match self.keys.get_mut(key) {
+            Some(item) => {
+                item.expires_at = Some(expires_at);
+                self.bump_version(key);
+                self.ttl_set_at.remove(key);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Recompute statistics, the expiration index and the LRU order after
+    /// a restore
+    fn finish_restore(&mut self) {
+        self.expirations.clear();
+        for (key, item) in &self.keys {
+            if let Some(expires_at) = item.expires_at {
+                self.expirations
+                    .entry(expires_at)
+                    .or_default()
+                    .push(key.clone());
+            }
+        }
+
+        self.prefix_counts.clear();
+        if self.config.track_prefix_counts {
+            for key in self.keys.keys() {
+                for prefix in counted_prefixes(key) {
+                    *self
+                        .prefix_counts
+                        .entry(Bytes::copy_from_slice(prefix))
+                        .or_default() += 1;
+                }
+            }
+        }
+        self.watchers.recount(&self.keys);
+        self.stats.key_count = self.keys.len();
+        self.metrics.set_key_count(self.keys.len());
+        self.stats.size_bytes = self
+            .keys
+            .iter()
+            .map(|(key, item)| key.len() + item.value.len())
+            .sum();
+
+        if self.config.max_memory_bytes.is_some() {
+            let order = self
+                .access_order
+                .get_mut()
+                .unwrap_or_else(|e| e.into_inner());
+            *order = self
+                .keys
+                .keys()
+                .filter(|key| !SpatialKey::is_spatial(key))
+                .cloned()
+                .collect();
+        }
+    }
+
+    /// Check every stored key, and the AOF checksums if persistent
+    fn verify_integrity(&mut self) -> IntegrityReport {
+        // Keys whose latest AOF write was an empty value
+        #[cfg(feature = "aof")]
+        let (aof_checksum_ok, recorded_empty) = match self.aof_file {
+            Some(ref mut aof_file) => {
+                let checksum_ok = aof_file.verify_checksum().unwrap_or(false);
+                let mut empty = HashSet::new();
+                for command in aof_file.replay().unwrap_or_default() {
+                    match command {
+                        AOFCommand::Set { key, value, .. } if value.is_empty() => {
+                            empty.insert(key);
+                        }
+                        AOFCommand::Set { key, .. } | AOFCommand::Delete { key } => {
+                            empty.remove(&key);
+                        }
+                        AOFCommand::Expire { .. } => {}
+                    }
+                }
+                (checksum_ok, Some(empty))
+            }
+            None => (true, None),
+        };
+        #[cfg(not(feature = "aof"))]
+        let (aof_checksum_ok, recorded_empty) = (true, None::<HashSet<Bytes>>);
+
+        let mut report = IntegrityReport {
+            aof_checksum_ok,
+            ..IntegrityReport::default()
+        };
+
+        for (key, item) in &self.keys {
+            let empty_ok = recorded_empty
+                .as_ref()
+                .is_none_or(|empty| empty.contains(key));
+            let value_ok = !item.value.is_empty() || empty_ok;
+            let spatial_ok = !SpatialKey::is_spatial(key) || self.spatial_key_is_valid(key);
+
+            if value_ok && spatial_ok {
+                report.valid_keys += 1;
+            } else {
+                report.corrupted_keys.push(key.clone());
+            }
+        }
+
+        report
+    }
+
+    /// Whether a spatial key decodes to a valid location
+    fn spatial_key_is_valid(&self, key: &[u8]) -> bool {
+        let Ok(key_str) = std::str::from_utf8(key) else {
+            return false;
+        };
+        let in_range =
+            |point: Point| point.is_valid() && point.lat.abs() <= 90.0 && point.lon.abs() <= 180.0;
+
+        match key_str.split(':').nth(1) {
+            Some("gh") => self
+                .parse_spatial_key(key_str)
+                .and_then(|(_, geohash)| self.decode_geohash_to_point(geohash).ok())
+                .is_some_and(in_range),
+            #[cfg(feature = "h3")]
+            Some("h3") => self
+                .parse_h3_key(key_str)
+                .and_then(|(_, cell)| self.decode_h3_to_point(cell).ok())
+                .is_some_and(in_range),
+            // H3 cells cannot be decoded without the h3 feature
+            #[cfg(not(feature = "h3"))]
+            Some("h3") => true,
+            #[cfg(feature = "s2")]
+            Some("s2") => key_str
+                .split(':')
+                .nth(2)
+                .and_then(|id| id.parse::<u64>().ok())
+                .is_some_and(|id| s2::cellid::CellID(id).is_valid()),
+            // S2 cells cannot be validated without the s2 feature
+            #[cfg(not(feature = "s2"))]
+            Some("s2") => true,
+            Some("geom") => self.parse_geometry_key(key_str).is_some(),
+            _ => false,
+        }
+    }
+
+    /// Parse a spatial key to extract prefix and geohash
+    fn parse_spatial_key<'a>(&self, key: &'a str) -> Option<(&'a str, &'a str)> {
+        // Spatial keys have format: "prefix:gh:geohash" for geographic points
+        let parts: Vec<&str> = key.split(':').collect();
+        if parts.len() >= 3 && parts[1] == "gh" {
+            let prefix = parts[0];
+            let geohash = parts[2];
+            Some((prefix, geohash))
+        } else {
+            None
+        }
+    }
+
+    /// Parse an S2 spatial key to extract prefix and cell
+    #[cfg(feature = "s2")]
+    fn parse_s2_key<'a>(&self, key: &'a str) -> Option<(&'a str, s2::cellid::CellID)> {
+        // S2 keys have format: "prefix:s2:cell" with the cell in decimal
+        let parts: Vec<&str> = key.split(':').collect();
+        if parts.len() >= 3 && parts[1] == "s2" {
+            let cell = s2::cellid::CellID(parts[2].parse().ok()?);
+            cell.is_valid().then_some((parts[0], cell))
+        } else {
+            None
+        }
+    }
+
+    /// Parse a geometry key to extract prefix and id
+    fn parse_geometry_key<'a>(&self, key: &'a str) -> Option<(&'a str, &'a str)> {
+        // Geometry keys have format: "prefix:geom:id", where the id may
+        // itself contain ':'
+        let (prefix, rest) = key.split_once(':')?;
+        let id = rest.strip_prefix("geom:")?;
+        (!id.is_empty()).then_some((prefix, id))
+    }
+
+    /// Decode an S2 cell back to the Point at its center
+    #[cfg(feature = "s2")]
+    fn decode_s2_to_point(&self, cell: s2::cellid::CellID) -> Point {
+        let center = s2::latlng::LatLng::from(s2::point::Point::from(cell));
+        Point::new(center.lat.deg(), center.lng.deg())
+    }
+
+    /// Parse an H3 spatial key to extract prefix and cell
+    #[cfg(feature = "h3")]
+    fn parse_h3_key<'a>(&self, key: &'a str) -> Option<(&'a str, h3o::CellIndex)> {
+        // H3 keys have format: "prefix:h3:cell" with the cell in hex
+        let parts: Vec<&str> = key.split(':').collect();
+        if parts.len() >= 3 && parts[1] == "h3" {
+            let cell = u64::from_str_radix(parts[2], 16).ok()?;
+            let cell = h3o::CellIndex::try_from(cell).ok()?;
+            Some((parts[0], cell))
+        } else {
+            None
+        }
+    }
+
+    /// Decode an H3 cell back to the Point at its center
+    #[cfg(feature = "h3")]
+    fn decode_h3_to_point(&self, cell: h3o::CellIndex) -> Result<Point> {
+        let center = h3o::LatLng::from(cell);
+        Ok(Point::new(center.lat(), center.lng()))
+    }
+
+    /// Decode a geohash back to a Point
+    fn decode_geohash_to_point(&self, geohash: &str) -> Result<Point> {
+        let (coord, _lat_err, _lon_err) =
+            geohash::decode(geohash).map_err(|_| SpatioError::InvalidGeohash)?;
+        Ok(Point::new(coord.y, coord.x))
+    }
+
+    /// The point a geohash, S2 or H3 point key is stored at, or `None`
+    /// for other keys. Cell keys decode to the cell center.
+    pub(crate) fn spatial_key_point(&self, key: &[u8]) -> Option<Point> {
+        let key_str = std::str::from_utf8(key).ok()?;
+        if let Some((_, geohash)) = self.parse_spatial_key(key_str) {
+            return self.decode_geohash_to_point(geohash).ok();
+        }
+        #[cfg(feature = "s2")]
+        if let Some((_, cell)) = self.parse_s2_key(key_str) {
+            return Some(self.decode_s2_to_point(cell));
+        }
+        #[cfg(feature = "h3")]
+        if let Some((_, cell)) = self.parse_h3_key(key_str) {
+            return self.decode_h3_to_point(cell).ok();
+        }
+        None
+    }
+
+    /// Write to AOF file if needed
+    pub fn write_to_aof_if_needed(
+        &mut self,
+        key: &Bytes,
+        value: &[u8],
+        options: Option<&SetOptions>,
+    ) -> Result<()> {
+        if let Some(ref mut backend) = self.backend
+            && let Some(item) = self.keys.get(key)
+        {
+            backend.put(key, item)?;
+        }
+
+        #[cfg(not(feature = "aof"))]
+        let _ = (value, options);
+        #[cfg(feature = "aof")]
+        if let Some(ref mut aof_file) = self.aof_file {
+            let value_type = self.keys.get(key).and_then(|item| item.value_type);
+            aof_file.write_typed_set(key, value, options, value_type)?;
+
+            // Flush based on sync policy
+            match self.config.sync_policy {
+                crate::types::SyncPolicy::Always => {
+                    aof_file.sync()?;
+                }
+                crate::types::SyncPolicy::EverySecond => {
+                    aof_file.flush()?;
+                }
+                crate::types::SyncPolicy::Never => {
+                    // Don't flush
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write an expiration change to the storage backend and AOF if needed
+    pub fn write_expire_to_aof_if_needed(
+        &mut self,
+        key: &Bytes,
+        expires_at: SystemTime,
+    ) -> Result<()> {
+        if let Some(ref mut backend) = self.backend
+            && let Some(item) = self.keys.get(key)
+        {
+            backend.put(key, item)?;
+        }
+
+        #[cfg(not(feature = "aof"))]
+        let _ = expires_at;
+        #[cfg(feature = "aof")]
+        if let Some(ref mut aof_file) = self.aof_file {
+            aof_file.write_expire(key, expires_at)?;
+
+            // Flush based on sync policy
+            match self.config.sync_policy {
+                crate::types::SyncPolicy::Always => {
+                    aof_file.sync()?;
+                }
+                crate::types::SyncPolicy::EverySecond => {
+                    aof_file.flush()?;
+                }
+                crate::types::SyncPolicy::Never => {
+                    // Don't flush
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Write delete operation to AOF if needed
+    pub fn write_delete_to_aof_if_needed(&mut self, key: &Bytes) -> Result<()> {
+        if let Some(ref mut backend) = self.backend {
+            backend.delete(key)?;
+        }
+
+        #[cfg(feature = "aof")]
+        if let Some(ref mut aof_file) = self.aof_file {
+            aof_file.write_delete(key)?;
+
+            // Flush based on sync policy
+            match self.config.sync_policy {
+                crate::types::SyncPolicy::Always => {
+                    aof_file.sync()?;
+                }
+                crate::types::SyncPolicy::EverySecond => {
+                    aof_file.flush()?;
+                }
+                crate::types::SyncPolicy::Never => {
+                    // Don't flush
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+// Re-export for convenience
+pub use DB as Spatio;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::Coordinate;
+    use crate::merge::Mergers;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_drop_only_syncs_on_last_reference() {
+        use std::fs;
+        let temp_path = std::env::temp_dir().join("test_drop_sync.aof");
+        let _ = fs::remove_file(&temp_path);
+
+        // Create database
+        let db = DB::open(&temp_path).unwrap();
+        db.insert("key1", b"value1", None).unwrap();
+
+        // Create clones
+        let db2 = db.clone();
+        let db3 = db.clone();
+
+        // Check strong count
+        assert_eq!(Arc::strong_count(&db.inner), 3);
+
+        // Drop one clone - should NOT sync (still 2 references)
+        drop(db2);
+        assert_eq!(Arc::strong_count(&db.inner), 2);
+
+        // Drop another clone - should NOT sync (still 1 reference)
+        drop(db3);
+        assert_eq!(Arc::strong_count(&db.inner), 1);
+
+        // Drop last reference - SHOULD sync
+        drop(db);
+
+        // Reopen and verify data persisted
+        let db = DB::open(&temp_path).unwrap();
+        assert_eq!(db.get("key1").unwrap().unwrap().as_ref(), b"value1");
+
+        // Cleanup
+        let _ = fs::remove_file(temp_path);
+    }
+
+    #[test]
+    fn test_explicit_close_prevents_operations() {
+        let mut db = DB::memory().unwrap();
+        db.insert("key", b"value", None).unwrap();
+
+        // Close the database
+        db.close().unwrap();
+
+        // Operations should fail
+        assert!(db.insert("key2", b"value2", None).is_err());
+        assert!(db.get("key").is_err());
+        assert!(db.delete("key").is_err());
+    }
+
+    #[test]
+    fn test_clone_shares_state() {
+        let db = DB::memory().unwrap();
+        let db2 = db.clone();
+
+        db.insert("key1", b"value1", None).unwrap();
+        db2.insert("key2", b"value2", None).unwrap();
+
+        // Both clones see both keys
+        assert_eq!(db.get("key1").unwrap().unwrap().as_ref(), b"value1");
+        assert_eq!(db.get("key2").unwrap().unwrap().as_ref(), b"value2");
+        assert_eq!(db2.get("key1").unwrap().unwrap().as_ref(), b"value1");
+        assert_eq!(db2.get("key2").unwrap().unwrap().as_ref(), b"value2");
+    }
+
+    #[test]
+    fn test_lru_eviction_removes_least_recently_used() {
+        // Each entry is a 2-byte key plus a 4-byte value
+        let config = Config::default().with_max_memory(18, EvictionPolicy::LeastRecentlyUsed);
+        let db = DB::memory_with_config(config).unwrap();
+
+        db.insert("k1", b"aaaa", None).unwrap();
+        db.insert("k2", b"bbbb", None).unwrap();
+        db.insert("k3", b"cccc", None).unwrap();
+
+        // Reading k1 makes k2 the least recently used
+        db.get("k1").unwrap();
+        db.insert("k4", b"dddd", None).unwrap();
+
+        assert!(db.get("k2").unwrap().is_none());
+        assert!(db.get("k1").unwrap().is_some());
+        assert!(db.get("k3").unwrap().is_some());
+        assert!(db.get("k4").unwrap().is_some());
+
+        let stats = db.stats().unwrap();
+        assert_eq!(stats.evicted_count, 1);
+        assert_eq!(stats.size_bytes, 18);
+    }
+
+    #[test]
+    fn test_lru_eviction_keeps_spatial_keys() {
+        let config = Config::default().with_max_memory(50, EvictionPolicy::LeastRecentlyUsed);
+        let db = DB::memory_with_config(config).unwrap();
+
+        let point = Point::new(40.7128, -74.0060);
+        db.insert_point("cities", &point, b"NYC", None).unwrap();
+        db.insert("a", vec![0u8; 20], None).unwrap();
+        db.insert("b", vec![0u8; 20], None).unwrap();
+
+        assert_eq!(
+            db.find_nearby("cities", &point, 100.0, 10).unwrap().len(),
+            1
+        );
+        assert!(db.get("a").unwrap().is_none());
+        assert!(db.get("b").unwrap().is_some());
+
+        // Nothing evictable is left to make room for a large value
+        assert!(matches!(
+            db.insert("c", vec![0u8; 30], None),
+            Err(SpatioError::MemoryLimitExceeded)
+        ));
+    }
+
+    #[test]
+    fn test_no_eviction_rejects_writes_over_limit() {
+        let config = Config::default().with_max_memory(12, EvictionPolicy::NoEviction);
+        let db = DB::memory_with_config(config).unwrap();
+
+        db.insert("k1", b"aaaa", None).unwrap();
+        db.insert("k2", b"bbbb", None).unwrap();
+        assert!(matches!(
+            db.insert("k3", b"cccc", None),
+            Err(SpatioError::MemoryLimitExceeded)
+        ));
+
+        // Overwriting with a value of the same size still fits
+        db.insert("k1", b"zzzz", None).unwrap();
+        assert_eq!(db.stats().unwrap().evicted_count, 0);
+    }
+
+    #[test]
+    fn test_eviction_is_persisted_to_aof() {
+        use std::fs;
+        let temp_path = std::env::temp_dir().join("test_lru_eviction.aof");
+        let _ = fs::remove_file(&temp_path);
+
+        let config = Config::default().with_max_memory(12, EvictionPolicy::LeastRecentlyUsed);
+        {
+            let db = DB::open_with_config(&temp_path, config.clone()).unwrap();
+            db.insert("k1", b"aaaa", None).unwrap();
+            db.insert("k2", b"bbbb", None).unwrap();
+            db.insert("k3", b"cccc", None).unwrap();
+        }
+
+        let db = DB::open_with_config(&temp_path, config).unwrap();
+        assert!(db.get("k1").unwrap().is_none());
+        assert!(db.get("k2").unwrap().is_some());
+        assert!(db.get("k3").unwrap().is_some());
+
+        let _ = fs::remove_file(&temp_path);
+    }
+
+    #[test]
+    fn test_spatial_index_snapshot_with_aof_tail() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("snapshot.aof");
+        let snapshot_path = dir.path().join("snapshot.aof.sidx");
+        let nyc = Point::new(40.7128, -74.0060);
+        let brooklyn = Point::new(40.6782, -73.9442);
+        let queens = Point::new(40.7282, -73.7949);
+        let harlem = Point::new(40.8116, -73.9465);
+        let config = Config::default();
+        let no_snapshot = Config::default().with_persist_spatial_index(false);
+
+        // No snapshot is written when disabled
+        {
+            let db = DB::open_with_config(&path, no_snapshot.clone()).unwrap();
+            db.insert_point("cities", &nyc, b"NYC", None).unwrap();
+        }
+        assert!(!snapshot_path.exists());
+        {
+            let db = DB::open_with_config(&path, config.clone()).unwrap();
+            db.insert_point("cities", &queens, b"Queens", None).unwrap();
+            db.insert_point("cities", &harlem, b"Harlem", None).unwrap();
+        }
+        assert!(snapshot_path.exists());
+
+        // Written without refreshing the snapshot, so only the AOF has it
+        {
+            let db = DB::open_with_config(&path, no_snapshot).unwrap();
+            db.insert_point("cities", &brooklyn, b"Brooklyn", None)
+                .unwrap();
+            db.insert(
+                "cities:gh:".to_string() + &nyc.to_geohash(8).unwrap(),
+                b"New York",
+                None,
+            )
+            .unwrap();
+            db.delete("cities:gh:".to_string() + &queens.to_geohash(8).unwrap())
+                .unwrap();
+        }
+
+        let db = DB::open_with_config(&path, config).unwrap();
+        let nearby = db.find_nearby("cities", &nyc, 20_000.0, 10).unwrap();
+        let mut names: Vec<_> = nearby.iter().map(|(_, data)| data.as_ref()).collect();
+        names.sort();
+        assert_eq!(names, vec![&b"Brooklyn"[..], b"Harlem", b"New York"]);
+        // Points from the snapshot keep their exact position
+        assert!(nearby.contains(&(harlem, Bytes::from("Harlem"))));
+        assert_eq!(db.stats().unwrap().key_count, 3);
+        assert!(
+            db.exists("cities:gh:".to_string() + &nyc.to_geohash(8).unwrap())
+                .unwrap()
+        );
+    }
+
+    #[cfg(feature = "s2")]
+    #[test]
+    fn test_s2_points_restored_from_aof() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("s2.aof");
+        let config = Config::default().with_persist_spatial_index(false);
+        {
+            let db = DB::open_with_config(&path, config.clone()).unwrap();
+            db.insert_point_with_s2("sensors", &Point::new(40.7128, -74.0060), 20, b"nyc", None)
+                .unwrap();
+            db.insert_point_with_s2("sensors", &Point::new(51.5074, -0.1278), 20, b"ldn", None)
+                .unwrap();
+        }
+
+        let db = DB::open_with_config(&path, config).unwrap();
+        let found = db
+            .find_within_bounds("sensors", 40.0, -75.0, 41.0, -73.0, 10)
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].1.as_ref(), b"nyc");
+    }
+
+    #[test]
+    fn test_geometries_indexed_and_restored() {
+        use crate::geometry::{Coordinate, LineString};
+
+        let route = Geometry::LineString(
+            LineString::new(vec![
+                Coordinate::new(-74.0060, 40.7128),
+                Coordinate::new(-73.9857, 40.7484),
+            ])
+            .unwrap(),
+        );
+        let stop = Geometry::Point(Coordinate::new(-0.1278, 51.5074));
+        let midtown = BoundingBox::new(40.74, -74.0, 40.76, -73.98);
+
+        let dir = tempfile::tempdir().unwrap();
+        for persist_spatial_index in [false, true] {
+            let path = dir
+                .path()
+                .join(format!("geometry_{}.aof", persist_spatial_index));
+            let config = Config::default().with_persist_spatial_index(persist_spatial_index);
+            {
+                let db = DB::open_with_config(&path, config.clone()).unwrap();
+                db.insert_geometry("routes", "r1", &route, b"Route 1", None)
+                    .unwrap();
+                db.insert_geometry("routes", "r2", &stop, b"Route 2", None)
+                    .unwrap();
+                db.insert_geometry("routes", "r3", &route, b"Route 3", None)
+                    .unwrap();
+                db.delete(SpatialKey::geometry("routes", "r3")).unwrap();
+
+                let found = db.find_geometries_intersecting("routes", &midtown).unwrap();
+                assert_eq!(found.len(), 1);
+                assert_eq!(found[0].0, "r1");
+            }
+
+            let db = DB::open_with_config(&path, config).unwrap();
+            let found = db.find_geometries_intersecting("routes", &midtown).unwrap();
+            assert_eq!(found.len(), 1);
+            assert_eq!(
+                found[0],
+                ("r1".to_string(), route.clone(), Bytes::from("Route 1"))
+            );
+        }
+
+        // Expired geometries are not returned
+        let db = DB::memory().unwrap();
+        let opts = SetOptions::with_ttl(Duration::from_millis(1));
+        db.insert_geometry("routes", "r1", &route, b"Route 1", Some(opts))
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(
+            db.find_geometries_intersecting("routes", &midtown)
+                .unwrap()
+                .is_empty()
+        );
+
+        assert!(db.insert_geometry("a:b", "r1", &route, b"", None).is_err());
+        assert!(matches!(
+            db.insert_geometry("routes", "r1", &Geometry::MultiPoint(vec![]), b"", None),
+            Err(SpatioError::InsufficientPoints)
+        ));
+    }
+
+    #[test]
+    fn test_spatial_aggregation_and_heatmap() {
+        let db = DB::memory().unwrap();
+        for (i, (lat, lon)) in [
+            (40.7128, -74.0060),
+            (40.7135, -74.0070),
+            (40.7140, -74.0050),
+            (40.7580, -73.9855),
+            (51.5074, -0.1278),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            db.insert_point(
+                "taxis",
+                &Point::new(lat, lon),
+                format!("{i}").as_bytes(),
+                None,
+            )
+            .unwrap();
+        }
+
+        let density = db.spatial_aggregation("taxis", 5).unwrap();
+        assert_eq!(density.values().sum::<usize>(), 5);
+        assert_eq!(density["dr5re"], 3);
+        assert!(db.spatial_aggregation("missing", 5).unwrap().is_empty());
+        assert!(db.spatial_aggregation("taxis", 0).is_err());
+        assert!(db.spatial_aggregation("taxis", 13).is_err());
+
+        let nyc = BoundingBox::new(40.5, -74.3, 40.9, -73.7);
+        let heatmap = db.spatial_heatmap("taxis", &nyc, 5).unwrap();
+        assert_eq!(heatmap.len(), 2);
+        assert_eq!(heatmap[0].2, 3);
+        assert_eq!(heatmap[1].2, 1);
+        let (cell, center, _) = &heatmap[0];
+        let bounds = geohash::decode_bbox(cell).unwrap();
+        assert!(center.within_bounds(
+            bounds.min().y,
+            bounds.min().x,
+            bounds.max().y,
+            bounds.max().x
+        ));
+    }
+
+    #[test]
+    fn test_find_trajectory_crossings() {
+        let db = DB::memory().unwrap();
+        // A heads north along a meridian while B flies east across its path
+        db.insert_trajectory(
+            "plane:a",
+            &[
+                (Point::new(40.00, -74.0), 0),
+                (Point::new(40.10, -74.0), 300),
+                (Point::new(40.20, -74.0), 600),
+            ],
+            None,
+        )
+        .unwrap();
+        db.insert_trajectory(
+            "plane:b",
+            &[
+                (Point::new(40.10, -74.1), 60),
+                (Point::new(40.10, -74.0), 300),
+                (Point::new(40.10, -73.9), 540),
+            ],
+            None,
+        )
+        .unwrap();
+
+        let crossings = db
+            .find_trajectory_crossings("plane:a", "plane:b", 0, 1000, 500.0)
+            .unwrap();
+        assert_eq!(
+            crossings.iter().map(|c| c.time).collect::<Vec<_>>(),
+            vec![300]
+        );
+        assert!(crossings[0].distance < 1.0);
+        assert_eq!(crossings[0].point_a, Point::new(40.10, -74.0));
+
+        // A wider proximity catches the approach and departure
+        let near = db
+            .find_trajectory_crossings("plane:a", "plane:b", 0, 1000, 5_000.0)
+            .unwrap();
+        assert!(near.len() > 1);
+        assert!(near.windows(2).all(|w| w[0].time < w[1].time));
+        assert!(near.iter().all(|c| c.distance <= 5_000.0));
+
+        // Only the shared span of both trajectories is sampled
+        assert!(near.iter().all(|c| (60..=540).contains(&c.time)));
+        assert!(
+            db.find_trajectory_crossings("plane:a", "plane:b", 400, 1000, 500.0)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            db.find_trajectory_crossings("plane:a", "missing", 0, 1000, 500.0)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_find_within_tile() {
+        let db = DB::memory().unwrap();
+        let nyc = Point::new(40.7128, -74.0060);
+        let brooklyn = Point::new(40.6782, -73.9442);
+        let london = Point::new(51.5074, -0.1278);
+        db.insert_point("cities", &nyc, b"nyc", None).unwrap();
+        db.insert_point("cities", &brooklyn, b"brooklyn", None)
+            .unwrap();
+        db.insert_point("cities", &london, b"london", None).unwrap();
+
+        let (x, y) = nyc.to_tile(6);
+        let found = db.find_within_tile("cities", 6, x, y, 10).unwrap();
+        assert_eq!(found.len(), 2);
+        assert!(found.iter().all(|(_, value)| value.as_ref() != b"london"));
+
+        // The world tile holds everything, subject to the limit
+        assert_eq!(db.find_within_tile("cities", 0, 0, 0, 10).unwrap().len(), 3);
+        assert_eq!(db.find_within_tile("cities", 0, 0, 0, 1).unwrap().len(), 1);
+
+        assert!(db.find_within_tile("cities", 1, 2, 0, 10).is_err());
+    }
+
+    #[test]
+    fn test_find_k_nearest() {
+        let db = DB::memory().unwrap();
+        let center = Point::new(40.0, -74.0);
+        // Points 0.5, 5, 50 and 400 km north of the center
+        for (i, km) in [0.5, 5.0, 50.0, 400.0].into_iter().enumerate() {
+            let point = Point::new(40.0 + km / 111.2, -74.0);
+            db.insert_point("poi", &point, format!("{i}").as_bytes(), None)
+                .unwrap();
+        }
+
+        let nearest = db.find_k_nearest("poi", &center, 3).unwrap();
+        assert_eq!(nearest.len(), 3);
+        assert_eq!(
+            nearest
+                .iter()
+                .map(|(_, d, _)| d.as_ref())
+                .collect::<Vec<_>>(),
+            vec![b"0", b"1", b"2"]
+        );
+        assert!(nearest.windows(2).all(|w| w[0].2 <= w[1].2));
+        assert!((nearest[2].2 - 50_000.0).abs() < 500.0);
+
+        assert_eq!(db.find_k_nearest("poi", &center, 10).unwrap().len(), 4);
+        assert!(db.find_k_nearest("poi", &center, 0).unwrap().is_empty());
+        assert!(db.find_k_nearest("missing", &center, 3).unwrap().is_empty());
+
+        // The radius cap leaves out the farthest point
+        let capped =
+            DB::memory_with_config(Config::default().with_max_knn_radius(100_000.0)).unwrap();
+        for (point, data, _) in db.find_k_nearest("poi", &center, 4).unwrap() {
+            capped.insert_point("poi", &point, &data, None).unwrap();
+        }
+        assert_eq!(capped.find_k_nearest("poi", &center, 4).unwrap().len(), 3);
+
+        // Expired points neither count towards k nor crowd out live ones
+        let expired = Point::new(40.0 + 0.1 / 111.2, -74.0);
+        db.insert_point(
+            "poi",
+            &expired,
+            b"expired",
+            Some(SetOptions::with_expiration(
+                SystemTime::now() - Duration::from_secs(1),
+            )),
+        )
+        .unwrap();
+        let nearest = db.find_k_nearest("poi", &center, 2).unwrap();
+        assert_eq!(
+            nearest
+                .iter()
+                .map(|(_, d, _)| d.as_ref())
+                .collect::<Vec<_>>(),
+            vec![b"0", b"1"]
+        );
+    }
+
+    #[test]
+    fn test_count_prefix() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("counts.aof");
+        let ns = Namespace::new("tenant");
+        {
+            let db = DB::open(&path).unwrap();
+            db.insert("user:1", b"alice", None).unwrap();
+            db.insert("user:2", b"bob", None).unwrap();
+            db.insert("user:2", b"robert", None).unwrap();
+            db.insert(ns.key("a"), b"1", None).unwrap();
+            db.insert_point("cities", &Point::new(40.7128, -74.0060), b"NYC", None)
+                .unwrap();
+            db.delete("user:1").unwrap();
+
+            assert_eq!(db.count_prefix(b"user:").unwrap(), 1);
+            assert_eq!(db.count_prefix(ns.prefix().as_ref()).unwrap(), 1);
+            assert_eq!(db.count_prefix(b"cities:gh:").unwrap(), 1);
+            assert_eq!(db.count_prefix(b"missing:").unwrap(), 0);
+            assert!(
+                !db.read()
+                    .unwrap()
+                    .prefix_counts
+                    .contains_key(&b"user:1"[..])
+            );
+        }
+
+        // Counts are rebuilt on open, and match a scan when not tracked
+        let prefixes = [&b"user:"[..], b"us", b"tenant::", b"cities:", b""];
+        let tracked: Vec<usize> = {
+            let db = DB::open(&path).unwrap();
+            prefixes
+                .iter()
+                .map(|prefix| db.count_prefix(prefix).unwrap())
+                .collect()
+        };
+        assert_eq!(tracked, vec![1, 1, 1, 1, 3]);
+
+        let config = Config::default().with_track_prefix_counts(false);
+        let untracked = DB::open_with_config(&path, config).unwrap();
+        assert!(untracked.read().unwrap().prefix_counts.is_empty());
+        let scanned: Vec<usize> = prefixes
+            .iter()
+            .map(|prefix| untracked.count_prefix(prefix).unwrap())
+            .collect();
+        assert_eq!(scanned, tracked);
+    }
+
+    #[test]
+    fn test_expire_at_is_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("expire.aof");
+        let later = SystemTime::UNIX_EPOCH + Duration::from_secs(4_000_000_000);
+        {
+            let db = DB::open(&path).unwrap();
+            db.insert("a", b"1", None).unwrap();
+            db.insert("b", b"2", None).unwrap();
+            let ttl = SetOptions::with_ttl(Duration::from_secs(60));
+            db.insert("c", b"3", Some(ttl)).unwrap();
+
+            assert!(db.expire_at("a", later).unwrap());
+            assert!(db.expire_in("c", Duration::from_secs(3600)).unwrap());
+            assert!(!db.expire_at("missing", later).unwrap());
+
+            // A past time expires the key, and the sweep finds it
+            assert!(db.expire_at("b", SystemTime::UNIX_EPOCH).unwrap());
+            assert!(db.get("b").unwrap().is_none());
+            assert!(!db.expire_at("b", later).unwrap());
+            assert_eq!(db.cleanup_expired().unwrap(), 1);
+            assert_eq!(db.read().unwrap().expirations.len(), 2);
+        }
+
+        let db = DB::open(&path).unwrap();
+        assert_eq!(db.key_expires_at("a").unwrap(), Some(later));
+        assert_eq!(db.get("a").unwrap().unwrap().as_ref(), b"1");
+        assert!(db.remaining_ttl("c").unwrap().unwrap() > Duration::from_secs(60));
+        assert!(db.get("b").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_merge() {
+        let db = DB::memory().unwrap();
+
+        // Missing and expired keys merge with None
+        let seen = |new: &[u8], existing: Option<&[u8]>| {
+            let mut merged = existing.unwrap_or(b"none").to_vec();
+            merged.extend_from_slice(new);
+            merged
+        };
+        assert_eq!(db.merge("a", b"+1", seen).unwrap().as_ref(), b"none+1");
+        let expired = Some(SetOptions::with_expiration(SystemTime::UNIX_EPOCH));
+        db.insert("b", b"stale", expired).unwrap();
+        assert_eq!(db.merge("b", b"+1", seen).unwrap().as_ref(), b"none+1");
+        assert!(db.remaining_ttl("b").unwrap().is_none());
+
+        // An existing expiration survives the merge
+        let ttl = Some(SetOptions::with_ttl(Duration::from_secs(60)));
+        db.insert("c", b"1", ttl).unwrap();
+        db.merge("c", b"5", Mergers::numeric_max()).unwrap();
+        assert_eq!(db.get("c").unwrap().unwrap().as_ref(), b"5");
+        assert!(db.remaining_ttl("c").unwrap().is_some());
+
+        // Concurrent merges never lose an update
+        let add = |new: &[u8], existing: Option<&[u8]>| {
+            let parse = |v: &[u8]| std::str::from_utf8(v).unwrap().parse::<u64>().unwrap();
+            (parse(new) + existing.map(parse).unwrap_or(0))
+                .to_string()
+                .into_bytes()
+        };
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..50 {
+                        db.merge("counter", b"1", add).unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(db.get("counter").unwrap().unwrap().as_ref(), b"200");
+    }
+
+    #[test]
+    fn test_atomic_read_modify_write() {
+        let db = DB::memory().unwrap();
+
+        let append = |current: Option<&[u8]>| {
+            let mut value = current.unwrap_or(b"none").to_vec();
+            value.extend_from_slice(b"+1");
+            Some(value)
+        };
+        let stored = db.atomic_read_modify_write("a", append).unwrap();
+        assert_eq!(stored.unwrap().as_ref(), b"none+1");
+
+        // An existing expiration survives the update
+        let ttl = Some(SetOptions::with_ttl(Duration::from_secs(60)));
+        db.insert("b", b"x", ttl).unwrap();
+        db.atomic_read_modify_write("b", append).unwrap();
+        assert_eq!(db.get("b").unwrap().unwrap().as_ref(), b"x+1");
+        assert!(db.remaining_ttl("b").unwrap().is_some());
+
+        // Returning None deletes the key
+        assert!(
+            db.atomic_read_modify_write("b", |_| None)
+                .unwrap()
+                .is_none()
+        );
+        assert!(db.get("b").unwrap().is_none());
+        assert!(
+            db.atomic_read_modify_write("missing", |_| None)
+                .unwrap()
+                .is_none()
+        );
+
+        // Concurrent updates never lose a write
+        let increment = |current: Option<&[u8]>| {
+            let n = current.map_or(0, |v| {
+                std::str::from_utf8(v).unwrap().parse::<u64>().unwrap()
+            });
+            Some((n + 1).to_string().into_bytes())
+        };
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    for _ in 0..50 {
+                        db.atomic_read_modify_write("counter", increment).unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!(db.get("counter").unwrap().unwrap().as_ref(), b"200");
+    }
+
+    #[test]
+    fn test_bulk_load_is_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bulk.aof");
+        let config = Config::default().with_bulk_load_sync_after_bytes(64);
+        let expiring = SystemTime::now() + Duration::from_secs(60);
+        {
+            let db = DB::open_with_config(&path, config).unwrap();
+            db.insert("existing", b"kept", None).unwrap();
+            let mut items: Vec<(String, Vec<u8>, Option<SetOptions>)> = (0..100)
+                .map(|i| (format!("item:{}", i), i.to_string().into_bytes(), None))
+                .collect();
+            items.push(("item:0".to_string(), b"last".to_vec(), None));
+            items.push((
+                "session".to_string(),
+                b"token".to_vec(),
+                Some(SetOptions::with_expiration(expiring)),
+            ));
+
+            let stats = db.bulk_load(items).unwrap();
+            assert_eq!(stats.items_loaded, 101);
+            assert!(stats.bytes_written > 0);
+            assert_eq!(db.get("item:0").unwrap().unwrap().as_ref(), b"last");
+        }
+
+        let db = DB::open(&path).unwrap();
+        assert_eq!(db.stats().unwrap().key_count, 102);
+        assert_eq!(db.get("item:0").unwrap().unwrap().as_ref(), b"last");
+        assert_eq!(db.get("item:99").unwrap().unwrap().as_ref(), b"99");
+        assert_eq!(db.get("existing").unwrap().unwrap().as_ref(), b"kept");
+        assert!(db.remaining_ttl("session").unwrap().is_some());
+
+        let memory = DB::memory().unwrap();
+        let stats = memory
+            .bulk_load([("a", "1", None), ("b", "2", None)])
+            .unwrap();
+        assert_eq!(stats.items_loaded, 2);
+        assert_eq!(stats.bytes_written, 0);
     }
 
-    // Internal helper methods
-    fn read(&self) -> Result<RwLockReadGuard<'_, DBInner>> {
-        self.inner.read().map_err(|_| SpatioError::LockError)
+    #[test]
+    fn test_bulk_load_failure_persists_stored_items() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("bulk_fail.aof");
+        let config = Config::default().with_max_memory(64, EvictionPolicy::NoEviction);
+        {
+            let db = DB::open_with_config(&path, config).unwrap();
+            let result = db.bulk_load([
+                ("a", vec![b'1'; 10], None),
+                ("b", vec![b'2'; 10], None),
+                ("c", vec![b'3'; 100], None),
+                ("d", vec![b'4'; 10], None),
+            ]);
+            assert!(matches!(result, Err(SpatioError::MemoryLimitExceeded)));
+            assert_eq!(db.stats().unwrap().key_count, 2);
+        }
+
+        let db = DB::open(&path).unwrap();
+        assert_eq!(db.stats().unwrap().key_count, 2);
+        assert!(db.exists("b").unwrap());
+        assert!(!db.exists("d").unwrap());
     }
 
-    pub(crate) fn write(&self) -> Result<RwLockWriteGuard<'_, DBInner>> {
-        self.inner.write().map_err(|_| SpatioError::LockError)
+    #[test]
+    fn test_merge_is_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("merge.aof");
+        {
+            let db = DB::open(&path).unwrap();
+            db.merge("log", b"one", Mergers::append_log()).unwrap();
+            db.merge("log", b"two", Mergers::append_log()).unwrap();
+        }
+
+        let db = DB::open(&path).unwrap();
+        assert_eq!(db.get("log").unwrap().unwrap().as_ref(), b"one\ntwo");
     }
-}
 
-/// Automatic graceful shutdown on drop.
-///
-/// When the last reference to the database is dropped, it automatically performs a graceful shutdown:
-/// - Flushes pending writes
-/// - Syncs to disk (best effort, errors are silently ignored)
-/// - Releases resources
-///
-/// Note: Since DB uses Arc internally, this syncs only when the last clone is dropped.
-/// The database is NOT marked as closed here to allow other clones to continue operating.
-/// Use `close()` explicitly if you need to prevent further operations.
-impl Drop for DB {
-    fn drop(&mut self) {
-        // Only sync if this is the last reference to the database
-        if Arc::strong_count(&self.inner) == 1 {
-            // Best-effort sync on final drop
-            if let Ok(mut inner) = self.inner.write()
-                && !inner.closed
-                && let Some(ref mut aof_file) = inner.aof_file
-            {
-                // Attempt to sync on drop, but don't panic if it fails
-                let _ = aof_file.sync();
+    #[test]
+    fn test_compact_memory() {
+        let db = DB::memory().unwrap();
+        let expired = Some(SetOptions::with_expiration(SystemTime::UNIX_EPOCH));
+        db.insert("session:1", b"token", expired.clone()).unwrap();
+        db.insert("user:1", b"alice", None).unwrap();
+        db.insert_point("cities", &Point::new(40.7128, -74.0060), b"NYC", expired)
+            .unwrap();
+        db.insert_point("cities", &Point::new(51.5074, -0.1278), b"London", None)
+            .unwrap();
+
+        let stats = db.compact_memory().unwrap();
+        assert_eq!(stats.keys_removed, 2);
+        assert!(stats.bytes_freed > "session:1token".len());
+        assert_eq!(db.stats().unwrap().key_count, 2);
+        assert_eq!(db.stats().unwrap().expired_count, 2);
+
+        let inner = db.read().unwrap();
+        assert!(inner.expirations.is_empty());
+        assert_eq!(inner.index_manager.stats().total_points, 1);
+        drop(inner);
+
+        assert_eq!(db.compact_memory().unwrap(), CompactionStats::default());
+    }
+
+    #[cfg(feature = "aof")]
+    #[test]
+    fn test_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let live_path = dir.path().join("live.aof");
+        let checkpoint_path = dir.path().join("checkpoint.aof");
+
+        let db = DB::open(&live_path).unwrap();
+        db.insert("user:1", b"alice", None).unwrap();
+        db.insert(
+            "session:1",
+            b"token",
+            Some(SetOptions::with_ttl(Duration::from_secs(300))),
+        )
+        .unwrap();
+        db.insert(
+            "session:2",
+            b"stale",
+            Some(SetOptions::with_expiration(SystemTime::UNIX_EPOCH)),
+        )
+        .unwrap();
+        let paris = Point::new(48.8566, 2.3522);
+        db.insert_point("cities", &paris, b"Paris", None).unwrap();
+
+        let stats = db.checkpoint(&checkpoint_path).unwrap();
+        assert_eq!(stats.keys_written, 3);
+        assert_eq!(
+            stats.bytes_written,
+            std::fs::metadata(&checkpoint_path).unwrap().len()
+        );
+
+        // Writes carry on, and do not reach the checkpoint
+        db.insert("user:2", b"bob", None).unwrap();
+        assert!(db.checkpoint(&live_path).is_err());
+
+        let restored = DB::open(&checkpoint_path).unwrap();
+        assert_eq!(restored.get("user:1").unwrap().unwrap().as_ref(), b"alice");
+        assert!(restored.get("user:2").unwrap().is_none());
+        assert!(restored.get("session:2").unwrap().is_none());
+        let ttl = restored.remaining_ttl("session:1").unwrap().unwrap();
+        assert!(ttl > Duration::from_secs(290));
+        let nearby = restored.find_nearby("cities", &paris, 1_000.0, 10).unwrap();
+        assert_eq!(nearby[0].1.as_ref(), b"Paris");
+        drop(restored);
+
+        // A later checkpoint replaces the file
+        let stats = db.checkpoint(&checkpoint_path).unwrap();
+        assert_eq!(stats.keys_written, 4);
+        let restored = DB::open(&checkpoint_path).unwrap();
+        assert_eq!(restored.get("user:2").unwrap().unwrap().as_ref(), b"bob");
+    }
+
+    #[test]
+    fn test_auto_checkpoint() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("auto.aof");
+        let config = Config::default().with_checkpoint_interval(Duration::from_millis(10), &path);
+        let db = DB::memory_with_config(config).unwrap();
+        db.insert("key", b"value", None).unwrap();
+
+        for _ in 0..100 {
+            if path.exists() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        let restored = DB::open(&path).unwrap();
+        assert_eq!(restored.get("key").unwrap().unwrap().as_ref(), b"value");
+    }
+
+    #[test]
+    fn test_auto_compaction() {
+        let config = Config::default().with_auto_compact_interval(Duration::from_millis(10));
+        let db = DB::memory_with_config(config).unwrap();
+        db.insert(
+            "session:1",
+            b"token",
+            Some(SetOptions::with_expiration(SystemTime::UNIX_EPOCH)),
+        )
+        .unwrap();
+
+        for _ in 0..100 {
+            if db.stats().unwrap().key_count == 0 {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(db.stats().unwrap().key_count, 0);
+    }
+
+    #[test]
+    fn test_remaining_ttl_and_expires_at() {
+        let db = DB::memory().unwrap();
+        let expires_at = SystemTime::now() + Duration::from_secs(120);
+        db.insert("a", b"1", Some(SetOptions::with_expiration(expires_at)))
+            .unwrap();
+        db.insert("b", b"2", None).unwrap();
+        db.insert(
+            "c",
+            b"3",
+            Some(SetOptions::with_expiration(SystemTime::UNIX_EPOCH)),
+        )
+        .unwrap();
+
+        assert_eq!(db.key_expires_at("a").unwrap(), Some(expires_at));
+        let ttl = db.remaining_ttl("a").unwrap().unwrap();
+        assert!(ttl > Duration::from_secs(110) && ttl <= Duration::from_secs(120));
+
+        // No TTL, already expired, and missing keys have nothing to report
+        for key in ["b", "c", "missing"] {
+            assert!(db.key_expires_at(key).unwrap().is_none());
+            assert!(db.remaining_ttl(key).unwrap().is_none());
+        }
+    }
+
+    #[test]
+    fn test_scan_prefix_pages() {
+        let db = DB::memory().unwrap();
+        for i in 0..25 {
+            db.insert(format!("item:{i:03}"), b"v", None).unwrap();
+        }
+        db.insert("other:1", b"v", None).unwrap();
+        db.insert(
+            "item:999",
+            b"expired",
+            Some(SetOptions::with_expiration(SystemTime::UNIX_EPOCH)),
+        )
+        .unwrap();
+
+        let mut cursor: Option<Bytes> = None;
+        let mut keys = Vec::new();
+        let mut pages = 0;
+        loop {
+            let (page, next) = db.scan_prefix(b"item:", cursor.as_deref(), 10).unwrap();
+            assert!(page.len() <= 10);
+            keys.extend(page.into_iter().map(|(key, _)| key));
+            pages += 1;
+            match next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+
+        assert_eq!(pages, 3);
+        assert_eq!(keys.len(), 25);
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(keys.iter().all(|key| key.starts_with(b"item:")));
+
+        // An exactly full last page still reports the end
+        let (page, next) = db.scan_prefix(b"other:", None, 1).unwrap();
+        assert_eq!(page.len(), 1);
+        assert!(next.is_none());
+    }
+
+    #[test]
+    fn test_scan_with_filter() {
+        let db = DB::memory().unwrap();
+        for i in 0..10u8 {
+            db.insert(format!("item:{i}"), [i], None).unwrap();
+        }
+        let expired = Some(SetOptions::with_expiration(SystemTime::UNIX_EPOCH));
+        db.insert("item:x", [0u8], expired).unwrap();
+        db.insert("other", [0u8], None).unwrap();
+
+        let even = |_: &[u8], value: &[u8]| value[0].is_multiple_of(2);
+        let found = db.scan_with_filter(b"item:", even, 100).unwrap();
+        let keys: Vec<_> = found.iter().map(|(key, _)| key.as_ref()).collect();
+        assert_eq!(
+            keys,
+            vec![
+                b"item:0".as_ref(),
+                b"item:2",
+                b"item:4",
+                b"item:6",
+                b"item:8"
+            ]
+        );
+        assert_eq!(db.scan_with_filter(b"item:", even, 2).unwrap().len(), 2);
+        assert!(db.scan_with_filter(b"item:", even, 0).unwrap().is_empty());
+
+        // Keys can be filtered too
+        let by_key = db
+            .scan_with_filter(b"item:", |key, _| key.ends_with(b"7"), 100)
+            .unwrap();
+        assert_eq!(
+            by_key,
+            vec![(Bytes::from("item:7"), Bytes::from(vec![7u8]))]
+        );
+
+        // Pages hold accepted items only, and the scan ends once none are
+        // left even though rejected keys follow
+        let below_five = |_: &[u8], value: &[u8]| value[0] < 5;
+        let mut cursor: Option<Bytes> = None;
+        let mut pages = Vec::new();
+        loop {
+            let (page, next) = db
+                .scan_with_filter_paginated(b"item:", cursor.as_deref(), below_five, 2)
+                .unwrap();
+            pages.push(page.len());
+            match next {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        assert_eq!(pages, vec![2, 2, 1]);
+    }
+
+    #[test]
+    fn test_build_inverted_index() {
+        let db = DB::memory().unwrap();
+        db.insert("sensor:1", b"temp=18", None).unwrap();
+        db.insert("sensor:2", b"temp=23", None).unwrap();
+        db.insert("sensor:3", b"temp=23", None).unwrap();
+        db.insert("sensor:4", b"offline", None).unwrap();
+        let expired = Some(SetOptions::with_expiration(SystemTime::UNIX_EPOCH));
+        db.insert("sensor:5", b"temp=30", expired).unwrap();
+        // Outside the prefix
+        db.insert("sensors", b"temp=23", None).unwrap();
+
+        let index = db
+            .build_inverted_index("sensor:", |value| {
+                let text = std::str::from_utf8(value).ok()?;
+                text.strip_prefix("temp=").map(String::from)
+            })
+            .unwrap();
+
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.lookup("23"), vec!["sensor:2", "sensor:3"]);
+        assert!(index.lookup("30").is_empty());
+        assert!(index.lookup("offline").is_empty());
+
+        assert_eq!(
+            index.range_lookup("10", "99"),
+            vec!["sensor:1", "sensor:2", "sensor:3"]
+        );
+        // The end is exclusive
+        assert_eq!(index.range_lookup("18", "23"), vec!["sensor:1"]);
+        assert!(index.range_lookup("23", "18").is_empty());
+        assert!(index.range_lookup("23", "23").is_empty());
+
+        // The index is a snapshot
+        db.delete("sensor:1").unwrap();
+        assert_eq!(index.lookup("18"), vec!["sensor:1"]);
+
+        let empty = db
+            .build_inverted_index("missing:", |_| Some(String::new()))
+            .unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_verify_integrity_reports_corrupted_keys() {
+        let db = DB::memory().unwrap();
+        db.insert("user:1", b"alice", None).unwrap();
+        db.insert("user:empty", b"", None).unwrap();
+        db.insert_point("cities", &Point::new(40.7128, -74.0060), b"NYC", None)
+            .unwrap();
+
+        // Simulate a damaged spatial key
+        db.write()
+            .unwrap()
+            .insert_item(Bytes::from_static(b"cities:gh:!!"), DbItem::new("bad"));
+
+        let report = db.verify_integrity().unwrap();
+        assert_eq!(report.valid_keys, 3);
+        assert_eq!(
+            report.corrupted_keys,
+            vec![Bytes::from_static(b"cities:gh:!!")]
+        );
+        assert!(report.aof_checksum_ok);
+    }
+
+    #[test]
+    fn test_verify_integrity_checks_aof() {
+        use std::fs;
+        let temp_path = std::env::temp_dir().join("test_verify_integrity.aof");
+        let _ = fs::remove_file(&temp_path);
+
+        let db = DB::open(&temp_path).unwrap();
+        db.insert("user:1", b"alice", None).unwrap();
+        db.insert("user:empty", b"", None).unwrap();
+        db.sync().unwrap();
+
+        // A value that was never written empty is reported as corrupted
+        db.write()
+            .unwrap()
+            .insert_item(Bytes::from_static(b"user:1"), DbItem::new(Bytes::new()));
+
+        let report = db.verify_integrity().unwrap();
+        assert_eq!(report.valid_keys, 1);
+        assert_eq!(report.corrupted_keys, vec![Bytes::from_static(b"user:1")]);
+        assert!(report.aof_checksum_ok);
+        drop(db);
+
+        // Damage the AOF contents behind the checksum footer
+        let mut bytes = fs::read(&temp_path).unwrap();
+        let pos = bytes.windows(5).position(|w| w == b"alice").unwrap();
+        bytes[pos] = b'A';
+        fs::write(&temp_path, &bytes).unwrap();
+
+        let db = DB::open(&temp_path).unwrap();
+        assert!(!db.verify_integrity().unwrap().aof_checksum_ok);
+
+        drop(db);
+        let _ = fs::remove_file(&temp_path);
+    }
+
+    #[test]
+    fn test_errors_carry_operation_context() {
+        let db = DB::memory().unwrap();
+        let err = db
+            .insert_point("cities", &Point::new(120.0, 0.0), b"invalid", None)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "insert_point: prefix='cities': Invalid geohash"
+        );
+        assert!(matches!(err.root_cause(), SpatioError::InvalidGeohash));
+        assert!(err.is_user_error());
+    }
+
+    #[test]
+    fn test_iter_keys_and_items() {
+        let db = DB::memory().unwrap();
+        db.insert("b", b"2", None).unwrap();
+        db.insert("a", b"1", None).unwrap();
+        let opts = SetOptions::with_ttl(Duration::from_millis(1));
+        db.insert("expired", b"gone", Some(opts)).unwrap();
+        std::thread::sleep(Duration::from_millis(5));
+
+        let keys = db.iter_keys().unwrap();
+        let collected: Vec<&Bytes> = keys.iter().collect();
+        assert_eq!(collected, vec![&Bytes::from("a"), &Bytes::from("b")]);
+        // Writers wait while the handle is alive
+        assert!(sync::try_write(&db.inner).is_none());
+        drop(keys);
+
+        let items = db.iter_items().unwrap();
+        let values: Vec<&[u8]> = (&items)
+            .into_iter()
+            .map(|(_, item)| item.value.as_ref())
+            .collect();
+        assert_eq!(values, vec![b"1".as_ref(), b"2".as_ref()]);
+        drop(items);
+
+        db.insert("c", b"3", None).unwrap();
+        assert_eq!(db.iter_keys().unwrap().iter().count(), 3);
+    }
+
+    #[test]
+    fn test_view_and_view_mut() {
+        let db = DB::memory().unwrap();
+        db.insert("a", b"1", None).unwrap();
+        db.insert("b", b"2", None).unwrap();
+
+        let (len, a) = db
+            .view(|view| Ok((view.len(), view.get("a").cloned())))
+            .unwrap();
+        assert_eq!(len, 2);
+        assert_eq!(a.unwrap().as_ref(), b"1");
+
+        let old = db
+            .view_mut(|view| {
+                view.delete("b")?;
+                assert!(!view.view().contains_key("b"));
+                view.insert("a", b"3", None)
+            })
+            .unwrap();
+        assert_eq!(old.unwrap().as_ref(), b"1");
+        assert_eq!(db.get("a").unwrap().unwrap().as_ref(), b"3");
+        assert!(db.get("b").unwrap().is_none());
+
+        // Errors from the closure are returned unchanged
+        let err = db
+            .view(|_| Err::<(), _>(SpatioError::InvalidGeohash))
+            .unwrap_err();
+        assert!(matches!(err, SpatioError::InvalidGeohash));
+
+        // Other databases can still be used inside a view
+        let other = DB::memory().unwrap();
+        db.view(|_| other.insert("key", b"value", None)).unwrap();
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "must not be called from inside DB::view")]
+    fn test_view_reentry_panics_in_debug() {
+        let db = DB::memory().unwrap();
+        let _ = db.view(|_| db.get("key"));
+    }
+
+    #[test]
+    fn test_normalize_trajectory() {
+        let db = DB::memory().unwrap();
+        assert!(db.normalize_trajectory("ship:1").unwrap().is_empty());
+
+        db.insert_trajectory(
+            "ship:1",
+            &[
+                (Point::new(0.0, 170.0), 100),
+                (Point::new(10.0, -170.0), 200),
+                (Point::new(10.0, -175.0), 300),
+                (Point::new(20.0, 175.0), 400),
+            ],
+            None,
+        )
+        .unwrap();
+
+        let segments = db.normalize_trajectory("ship:1").unwrap();
+        assert_eq!(
+            segments,
+            vec![
+                vec![(Point::new(0.0, 170.0), 100), (Point::new(5.0, 180.0), 150)],
+                vec![
+                    (Point::new(5.0, -180.0), 150),
+                    (Point::new(10.0, -170.0), 200),
+                    (Point::new(10.0, -175.0), 300),
+                    (Point::new(15.0, -180.0), 350),
+                ],
+                vec![
+                    (Point::new(15.0, 180.0), 350),
+                    (Point::new(20.0, 175.0), 400)
+                ],
+            ]
+        );
+
+        // Trajectories that stay on one side come back as a single segment
+        let local = [(Point::new(40.0, -74.0), 10), (Point::new(40.1, -74.0), 20)];
+        db.insert_trajectory("ship:2", &local, None).unwrap();
+        assert_eq!(
+            db.normalize_trajectory("ship:2").unwrap(),
+            vec![local.to_vec()]
+        );
+
+        // Starting on the boundary does not leave a one-point segment
+        db.insert_trajectory(
+            "ship:3",
+            &[(Point::new(0.0, 180.0), 10), (Point::new(0.0, -170.0), 20)],
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            db.normalize_trajectory("ship:3").unwrap(),
+            vec![vec![
+                (Point::new(0.0, -180.0), 10),
+                (Point::new(0.0, -170.0), 20)
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_knn_graphs() {
+        let db = DB::memory().unwrap();
+        assert!(db.knn_graph("stations", 2).unwrap().is_empty());
+
+        // Along a meridian, 111 m, 222 m and 778 m apart
+        let a = Point::new(40.700, -74.0);
+        let b = Point::new(40.701, -74.0);
+        let c = Point::new(40.703, -74.0);
+        let d = Point::new(40.710, -74.0);
+        for (point, name) in [(a, "a"), (b, "b"), (c, "c"), (d, "d")] {
+            db.insert_point("stations", &point, name.as_bytes(), None)
+                .unwrap();
+        }
+        let pairs = |edges: Vec<KnnEdge>| {
+            assert!(edges.windows(2).all(|w| w[0].distance <= w[1].distance));
+            assert!(edges.iter().all(|e| e.from_point < e.to_point));
+            edges
+                .iter()
+                .map(|e| (e.from_point, e.to_point))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            pairs(db.knn_graph("stations", 1).unwrap()),
+            vec![(a, b), (b, c), (c, d)]
+        );
+        assert_eq!(
+            pairs(db.knn_graph("stations", 2).unwrap()),
+            vec![(a, b), (b, c), (a, c), (c, d), (b, d)]
+        );
+        assert_eq!(
+            pairs(db.mutual_knn_graph("stations", 1).unwrap()),
+            vec![(a, b)]
+        );
+        assert_eq!(
+            pairs(db.mutual_knn_graph("stations", 2).unwrap()),
+            vec![(a, b), (b, c), (a, c)]
+        );
+
+        let edges = db.knn_graph("stations", 1).unwrap();
+        assert_eq!(edges[0].distance, a.distance_to(&b));
+        assert!(db.knn_graph("stations", 0).unwrap().is_empty());
+        assert!(db.mutual_knn_graph("stations", 0).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_find_nearest_along_route() {
+        let db = DB::memory().unwrap();
+        let route = LineString::new(vec![
+            Coordinate::new(-74.00, 40.70),
+            Coordinate::new(-73.98, 40.70),
+        ])
+        .unwrap();
+        let fix = Point::new(40.7018, -73.9900);
+
+        assert!(
+            db.find_nearest_along_route("stops", &route, &fix, 500.0)
+                .unwrap()
+                .is_none()
+        );
+
+        // Nearest to the fix, but too far from the route
+        db.insert_point("stops", &Point::new(40.7060, -73.9900), b"off", None)
+            .unwrap();
+        assert!(
+            db.find_nearest_along_route("stops", &route, &fix, 500.0)
+                .unwrap()
+                .is_none()
+        );
+
+        // Both within range; the one closer to the route wins overall
+        db.insert_point("stops", &Point::new(40.7005, -73.9900), b"on", None)
+            .unwrap();
+        db.insert_point("stops", &Point::new(40.7036, -73.9900), b"edge", None)
+            .unwrap();
+        let (point, data, distance) = db
+            .find_nearest_along_route("stops", &route, &fix, 500.0)
+            .unwrap()
+            .unwrap();
+        assert_eq!(data.as_ref(), b"on");
+        assert_eq!(point, Point::new(40.7005, -73.9900));
+        let expected = fix.distance_to(&point) + route.nearest_geographic_point(&point).1;
+        assert!((distance - expected).abs() < 1e-9);
+
+        // Points on the route but beyond reach of the fix are not candidates
+        assert!(
+            db.find_nearest_along_route("stops", &route, &Point::new(40.70, -73.90), 500.0)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_find_nearby_skips_expired_points() {
+        let db = DB::memory().unwrap();
+        let center = Point::new(40.7128, -74.0060);
+        let expired = Some(SetOptions::with_expiration(SystemTime::UNIX_EPOCH));
+        db.insert_point("poi", &center, b"gone", expired).unwrap();
+        db.insert_point("poi", &Point::new(40.7138, -74.0060), b"a", None)
+            .unwrap();
+        db.insert_point("poi", &Point::new(40.7148, -74.0060), b"b", None)
+            .unwrap();
+
+        let found = db.find_nearby("poi", &center, 1_000.0, 2).unwrap();
+        let values: Vec<_> = found.iter().map(|(_, data)| data.as_ref()).collect();
+        assert_eq!(values, vec![b"a".as_ref(), b"b".as_ref()]);
+    }
+
+    #[cfg(feature = "debug_queries")]
+    #[test]
+    fn test_explain_nearby() {
+        let db = DB::memory().unwrap();
+        let center = Point::new(40.7128, -74.0060);
+
+        let empty = db.explain_nearby("poi", &center, 1_000.0).unwrap();
+        assert_eq!(empty.index_strategy_used, "none");
+        assert_eq!(empty.prefix_point_count, 0);
+        assert_eq!(empty.candidates_evaluated, 0);
+
+        // Small prefixes are scanned in full
+        let expired = Some(SetOptions::with_expiration(SystemTime::UNIX_EPOCH));
+        db.insert_point("poi", &center, b"here", None).unwrap();
+        db.insert_point("poi", &Point::new(40.7138, -74.0060), b"gone", expired)
+            .unwrap();
+        db.insert_point("poi", &Point::new(51.5074, -0.1278), b"far", None)
+            .unwrap();
+        let small = db.explain_nearby("poi", &center, 1_000.0).unwrap();
+        assert_eq!(small.index_strategy_used, "full_scan");
+        assert!(small.candidate_geohashes.is_empty());
+        assert_eq!(small.prefix_point_count, 3);
+        assert_eq!(small.candidates_evaluated, 3);
+        assert_eq!(small.expired_excluded, 1);
+        assert_eq!(small.distance_excluded, 1);
+        assert_eq!(small.results_returned, 1);
+
+        // Larger prefixes only look at the cells around the center
+        for i in 0..35 {
+            for j in 0..35 {
+                let point = Point::new(40.5 + i as f64 * 0.01, -74.2 + j as f64 * 0.01);
+                db.insert_point("grid", &point, b"cell", None).unwrap();
             }
         }
+        let grid = db.explain_nearby("grid", &center, 1_500.0).unwrap();
+        assert_eq!(grid.index_strategy_used, "geohash_ring_search");
+        assert_eq!(grid.prefix_point_count, 35 * 35);
+        assert!(!grid.candidate_geohashes.is_empty());
+        assert!(grid.candidates_evaluated < grid.prefix_point_count);
+        assert_eq!(grid.expired_excluded, 0);
+        assert_eq!(
+            grid.candidates_evaluated,
+            grid.distance_excluded + grid.results_returned
+        );
+        let found = db
+            .find_nearby("grid", &center, 1_500.0, usize::MAX)
+            .unwrap();
+        assert_eq!(grid.results_returned, found.len());
+        assert!(grid.results_returned > 0);
+    }
+
+    #[test]
+    fn test_query_trajectory_scans_only_time_range() {
+        let db = DB::memory().unwrap();
+        let trajectory: Vec<(Point, u64)> = (0..10)
+            .map(|i| (Point::new(40.0 + i as f64 * 0.01, -74.0), 1000 + i * 60))
+            .collect();
+        db.insert_trajectory("truck", &trajectory, None).unwrap();
+
+        let path = db.query_trajectory("truck", 1060, 1180).unwrap();
+        let times: Vec<u64> = path.iter().map(|(_, t)| *t).collect();
+        assert_eq!(times, vec![1060, 1120, 1180]);
+
+        let inner = db.read().unwrap();
+        let scanned = inner
+            .keys
+            .range(trajectory_key_range("traj:truck:", 1060, 1180))
+            .count();
+        assert_eq!(scanned, 3);
+        drop(inner);
+
+        // Out of range and inverted bounds
+        assert!(db.query_trajectory("truck", 0, 999).unwrap().is_empty());
+        assert!(db.query_trajectory("truck", 1180, 1060).unwrap().is_empty());
+        assert_eq!(db.query_trajectory("truck", 0, u64::MAX).unwrap().len(), 10);
+    }
+
+    #[test]
+    fn test_query_trajectory_ignores_objects_sharing_prefix() {
+        let db = DB::memory().unwrap();
+        let point = Point::new(40.7128, -74.0060);
+        db.insert_trajectory("truck", &[(point, 1_000_000_050)], None)
+            .unwrap();
+        // Its keys start with "traj:truck:1000000050:", inside the range
+        // queried for "truck"
+        db.insert_trajectory("truck:1000000050", &[(point, 1_000_000_060)], None)
+            .unwrap();
+
+        let path = db
+            .query_trajectory("truck", 1_000_000_000, 1_000_000_100)
+            .unwrap();
+        assert_eq!(path, vec![(point, 1_000_000_050)]);
+
+        let path = db.query_trajectory("truck", 0, u64::MAX).unwrap();
+        assert_eq!(path.len(), 1);
+    }
+
+    #[test]
+    fn test_atomic_with_timeout() {
+        let db = DB::memory().unwrap();
+        let (locked_tx, locked_rx) = std::sync::mpsc::channel();
+
+        // Hold the write lock on another thread for a while
+        let holder = {
+            let db = db.clone();
+            std::thread::spawn(move || {
+                db.view_mut(|_| {
+                    locked_tx.send(()).unwrap();
+                    std::thread::sleep(Duration::from_millis(200));
+                    Ok(())
+                })
+                .unwrap();
+            })
+        };
+        locked_rx.recv().unwrap();
+
+        let err = db
+            .atomic_with_timeout(
+                |batch| batch.insert("key", b"value", None),
+                Duration::from_millis(20),
+            )
+            .unwrap_err();
+        assert!(matches!(err, SpatioError::LockTimeout));
+        assert!(err.is_retryable());
+
+        holder.join().unwrap();
+        assert!(db.get("key").unwrap().is_none());
+
+        // With the lock free the batch commits
+        db.atomic_with_timeout(
+            |batch| batch.insert("key", b"value", None),
+            Duration::from_millis(20),
+        )
+        .unwrap();
+        assert_eq!(db.get("key").unwrap().unwrap().as_ref(), b"value");
+    }
+
+    #[test]
+    fn test_spatial_window() {
+        let db = DB::memory().unwrap();
+        let center = Point::new(40.7128, -74.0060);
+        let near = Point::new(40.7130, -74.0062);
+        let far = Point::new(40.7600, -74.0060);
+        let ttl = SetOptions::with_ttl(Duration::from_secs(300));
+
+        db.insert_point("pings", &center, b"old", Some(ttl.clone()))
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(50));
+        db.insert_point("pings", &near, b"new", Some(ttl.clone()))
+            .unwrap();
+        db.insert_point("pings", &far, b"far", Some(ttl)).unwrap();
+        // Points without a TTL are never in a window
+        db.insert_point("pings", &Point::new(40.7127, -74.0059), b"static", None)
+            .unwrap();
+
+        let window = db
+            .spatial_window("pings", &center, 1000.0, Duration::from_secs(60), 10)
+            .unwrap();
+        let values: Vec<&[u8]> = window.iter().map(|(_, v)| v.as_ref()).collect();
+        assert_eq!(values, vec![&b"new"[..], &b"old"[..]]);
+
+        let latest = db
+            .spatial_window("pings", &center, 1000.0, Duration::from_secs(60), 1)
+            .unwrap();
+        assert_eq!(latest[0].1.as_ref(), b"new");
+
+        let recent = db
+            .spatial_window("pings", &center, 1000.0, Duration::from_millis(25), 10)
+            .unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].0, near);
+    }
+
+    #[test]
+    fn test_find_within_polygon_approximate() {
+        let db = DB::memory().unwrap();
+        let polygon = Polygon::new(
+            crate::geometry::LinearRing::new(vec![
+                Coordinate::new(-74.02, 40.70),
+                Coordinate::new(-73.92, 40.70),
+                Coordinate::new(-73.92, 40.80),
+                Coordinate::new(-74.02, 40.80),
+            ])
+            .unwrap(),
+            vec![],
+        );
+
+        db.insert_point("sensors", &Point::new(40.75, -73.97), b"inside", None)
+            .unwrap();
+        db.insert_point("sensors", &Point::new(41.50, -73.97), b"outside", None)
+            .unwrap();
+        let expired = SetOptions::with_expiration(SystemTime::UNIX_EPOCH);
+        db.insert_point(
+            "sensors",
+            &Point::new(40.72, -74.00),
+            b"expired",
+            Some(expired),
+        )
+        .unwrap();
+        db.insert_point("other", &Point::new(40.75, -73.97), b"elsewhere", None)
+            .unwrap();
+
+        let found = db
+            .find_within_polygon_approximate("sensors", &polygon, 6, 10)
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].1.as_ref(), b"inside");
+
+        assert!(
+            db.find_within_polygon_approximate("missing", &polygon, 6, 10)
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_isochrone_from_point() {
+        let db = DB::memory().unwrap();
+        let origin = Point::new(40.7128, -74.0060);
+        db.insert_point("stops", &Point::new(40.7150, -74.0060), b"a", None)
+            .unwrap();
+        let expired = SetOptions::with_expiration(SystemTime::UNIX_EPOCH);
+        db.insert_point("stops", &Point::new(40.7128, -74.0020), b"b", Some(expired))
+            .unwrap();
+
+        // The origin and a single live point cannot enclose an area
+        assert!(matches!(
+            db.isochrone_from_point("stops", &origin, 1000.0),
+            Err(SpatioError::InsufficientPoints)
+        ));
+
+        db.insert_point("stops", &Point::new(40.7110, -74.0030), b"c", None)
+            .unwrap();
+        let zone = db.isochrone_from_point("stops", &origin, 1000.0).unwrap();
+        assert_eq!(zone.exterior().coords().len(), 4);
+        assert!(zone.contains_point(&Coordinate::new(-74.0050, 40.7130)));
+    }
+
+    #[test]
+    fn test_find_within_polygon() {
+        let db = DB::memory().unwrap();
+        let square = |x0: f64, y0: f64, x1: f64, y1: f64| {
+            crate::geometry::LinearRing::new(vec![
+                Coordinate::new(x0, y0),
+                Coordinate::new(x1, y0),
+                Coordinate::new(x1, y1),
+                Coordinate::new(x0, y1),
+            ])
+            .unwrap()
+        };
+        let polygon = Polygon::new(
+            square(-74.10, 40.60, -73.90, 40.80),
+            vec![square(-74.05, 40.65, -73.95, 40.75)],
+        );
+
+        db.insert_point("sensors", &Point::new(40.62, -74.08), b"edge", None)
+            .unwrap();
+        db.insert_point("sensors", &Point::new(40.70, -74.00), b"hole", None)
+            .unwrap();
+        db.insert_point("sensors", &Point::new(41.00, -74.00), b"outside", None)
+            .unwrap();
+        db.insert_point("sensors", &Point::new(40.78, -73.92), b"corner", None)
+            .unwrap();
+        let expired = SetOptions::with_expiration(SystemTime::UNIX_EPOCH);
+        db.insert_point(
+            "sensors",
+            &Point::new(40.78, -74.08),
+            b"expired",
+            Some(expired),
+        )
+        .unwrap();
+
+        let mut found: Vec<Bytes> = db
+            .find_within_polygon("sensors", &polygon, 10)
+            .unwrap()
+            .into_iter()
+            .map(|(_, data)| data)
+            .collect();
+        found.sort();
+        assert_eq!(found, vec![Bytes::from("corner"), Bytes::from("edge")]);
+
+        assert_eq!(
+            db.find_within_polygon("sensors", &polygon, 1)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_set_prefix_precision() {
+        let db = DB::memory().unwrap();
+        let paris = Point::new(48.8566, 2.3522);
+        db.insert_point("sensors", &paris, b"paris-01", None)
+            .unwrap();
+
+        db.set_prefix_precision("sensors", 4).unwrap();
+        let lyon = Point::new(45.7640, 4.8357);
+        db.insert_point("sensors", &lyon, b"lyon-01", None).unwrap();
+
+        let nearby = db.find_nearby("sensors", &paris, 1000.0, 10).unwrap();
+        assert_eq!(nearby.len(), 1);
+        assert_eq!(nearby[0].1.as_ref(), b"paris-01");
+        assert_eq!(
+            db.find_nearby("sensors", &paris, 500_000.0, 10)
+                .unwrap()
+                .len(),
+            2
+        );
+
+        assert!(matches!(
+            db.set_prefix_precision("sensors", 0),
+            Err(SpatioError::InvalidConfiguration(_))
+        ));
+    }
+
+    #[test]
+    fn test_snapshot_is_point_in_time() {
+        let mut db = DB::memory().unwrap();
+        db.insert("user:1", b"Alice", None).unwrap();
+        db.insert("user:2", b"Bob", None).unwrap();
+        let expired = SetOptions::with_expiration(SystemTime::UNIX_EPOCH);
+        db.insert("user:3", b"Carol", Some(expired)).unwrap();
+
+        let mut snapshot = db.snapshot().unwrap();
+        db.insert("user:1", b"Alicia", None).unwrap();
+        db.delete("user:2").unwrap();
+
+        assert_eq!(snapshot.len().unwrap(), 2);
+        assert_eq!(
+            snapshot.get(b"user:1").unwrap().unwrap().value.as_ref(),
+            b"Alice"
+        );
+        assert!(snapshot.contains_key(b"user:2").unwrap());
+        assert!(!snapshot.contains_key(b"user:3").unwrap());
+        assert!(matches!(
+            snapshot.put(b"user:4", &DbItem::new(b"Dave".to_vec())),
+            Err(SpatioError::ReadOnly)
+        ));
+
+        db.close().unwrap();
+        assert!(matches!(db.snapshot(), Err(SpatioError::DatabaseClosed)));
     }
-}
 
-impl DBInner {
-    /// Insert an item into the database
-    pub fn insert_item(&mut self, key: Bytes, item: DbItem) -> Option<DbItem> {
-        // Remove from old expiration index if updating
-        let old_item = if let Some(old) = self.keys.get(&key) {
-            if let Some(expires_at) = old.expires_at
-                && let Some(keys) = self.expirations.get_mut(&expires_at)
-            {
-                keys.retain(|k| k != &key);
-                if keys.is_empty() {
-                    self.expirations.remove(&expires_at);
-                }
-            }
-            Some(old.clone())
-        } else {
+    #[test]
+    fn test_copy_and_move_prefix() {
+        let db = DB::memory().unwrap();
+        let expires_at = SystemTime::now() + Duration::from_secs(3600);
+        db.insert("a:1", b"one", Some(SetOptions::with_expiration(expires_at)))
+            .unwrap();
+        db.insert("a:2", b"two", None).unwrap();
+        let expired = SetOptions::with_expiration(SystemTime::UNIX_EPOCH);
+        db.insert("a:3", b"gone", Some(expired)).unwrap();
+        db.insert("b:1", b"other", None).unwrap();
+
+        // Expirations are kept unless options are given
+        assert_eq!(db.copy_prefix(b"a:", b"c:", None).unwrap(), 2);
+        let inner = db.read().unwrap();
+        assert_eq!(
+            inner.get_item(&Bytes::from("c:1")).unwrap().expires_at,
+            Some(expires_at)
+        );
+        assert_eq!(
+            inner.get_item(&Bytes::from("c:2")).unwrap().expires_at,
             None
-        };
+        );
+        drop(inner);
+        assert!(db.get("c:3").unwrap().is_none());
+        assert_eq!(db.get("a:1").unwrap().unwrap().as_ref(), b"one");
 
-        // Add to expiration index if TTL is set
-        if let Some(expires_at) = item.expires_at {
-            self.expirations
-                .entry(expires_at)
-                .or_default()
-                .push(key.clone());
+        let ttl = SetOptions::with_ttl(Duration::from_secs(60));
+        assert_eq!(db.copy_prefix(b"b:", b"d:", Some(ttl)).unwrap(), 1);
+        assert!(
+            db.read()
+                .unwrap()
+                .get_item(&Bytes::from("d:1"))
+                .unwrap()
+                .expires_at
+                .is_some()
+        );
+
+        // Moving into a prefix nested under the source keeps every key
+        assert_eq!(db.move_prefix(b"a:", b"a:2", None).unwrap(), 2);
+        assert!(db.get("a:1").unwrap().is_none());
+        assert_eq!(db.get("a:21").unwrap().unwrap().as_ref(), b"one");
+        assert_eq!(db.get("a:22").unwrap().unwrap().as_ref(), b"two");
+        assert!(db.get("a:2").unwrap().is_none());
+
+        assert_eq!(db.move_prefix(b"missing:", b"x:", None).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_exists_with_bloom_filter() {
+        let config = Config::default().with_bloom_filter(0.01, 100);
+        let mut db = DB::memory_with_config(config).unwrap();
+        for i in 0..50 {
+            db.insert(format!("key:{}", i), b"value", None).unwrap();
         }
+        assert!((0..50).all(|i| db.exists(format!("key:{}", i)).unwrap()));
+        assert!(!db.exists("key:50").unwrap());
 
-        // Insert into main storage
-        self.keys.insert(key, item);
-        self.stats.key_count = self.keys.len();
+        // Removing most keys rebuilds the filter without losing the rest
+        for i in 0..40 {
+            db.delete(format!("key:{}", i)).unwrap();
+        }
+        assert!(!db.exists("key:0").unwrap());
+        assert!((40..50).all(|i| db.exists(format!("key:{}", i)).unwrap()));
+        let filter = db.key_filter.clone().unwrap();
+        assert!(
+            (0..40)
+                .filter(|i| filter.may_contain(format!("key:{}", i).as_bytes()))
+                .count()
+                < 5
+        );
 
-        old_item
+        let expired = SetOptions::with_expiration(SystemTime::UNIX_EPOCH);
+        db.insert("stale", b"value", Some(expired)).unwrap();
+        assert!(!db.exists("stale").unwrap());
+
+        db.close().unwrap();
+        assert!(matches!(
+            db.exists("key:50"),
+            Err(SpatioError::DatabaseClosed)
+        ));
     }
 
-    /// Remove an item from the database
-    pub fn remove_item(&mut self, key: &Bytes) -> Option<DbItem> {
-        if let Some(item) = self.keys.remove(key) {
-            // Remove from expiration index
-            if let Some(expires_at) = item.expires_at
-                && let Some(keys) = self.expirations.get_mut(&expires_at)
-            {
-                keys.retain(|k| k != key);
-                if keys.is_empty() {
-                    self.expirations.remove(&expires_at);
-                }
-            }
+    #[test]
+    fn test_batch_find_nearby() {
+        let sequential = DB::memory().unwrap();
+        let parallel =
+            DB::memory_with_config(Config::default().with_parallel_queries(true)).unwrap();
+        let nyc = Point::new(40.7128, -74.0060);
+        let brooklyn = Point::new(40.6782, -73.9442);
+        let london = Point::new(51.5074, -0.1278);
+        let expired = SetOptions::with_expiration(SystemTime::UNIX_EPOCH);
+        for db in [&sequential, &parallel] {
+            db.insert_point("stores", &nyc, b"Manhattan", None).unwrap();
+            db.insert_point("stores", &brooklyn, b"Brooklyn", None)
+                .unwrap();
+            db.insert_point("stores", &london, b"London", Some(expired.clone()))
+                .unwrap();
+        }
 
-            self.stats.key_count = self.keys.len();
-            Some(item)
-        } else {
-            None
+        let centers = [(london, 10_000.0), (nyc, 20_000.0), (nyc, 1_000.0)];
+        for db in [&sequential, &parallel] {
+            let results = db.batch_find_nearby("stores", &centers, 5).unwrap();
+            assert_eq!(results.len(), 3);
+            assert!(results[0].is_empty());
+            let names: Vec<&[u8]> = results[1]
+                .iter()
+                .map(|(_, data, _)| data.as_ref())
+                .collect();
+            assert_eq!(names, vec![b"Manhattan".as_ref(), b"Brooklyn".as_ref()]);
+            assert_eq!(results[1][0].2, 0.0);
+            assert!((results[1][1].2 - nyc.distance_to(&brooklyn)).abs() < 1e-6);
+            assert_eq!(results[2].len(), 1);
+
+            let limited = db.batch_find_nearby("stores", &centers[1..2], 1).unwrap();
+            assert_eq!(limited[0].len(), 1);
         }
+        assert!(
+            sequential
+                .batch_find_nearby("stores", &[], 5)
+                .unwrap()
+                .is_empty()
+        );
     }
 
-    /// Get an item from the database
-    pub fn get_item(&self, key: &Bytes) -> Option<&DbItem> {
-        self.keys.get(key)
+    #[test]
+    fn test_batch_find_nearby_zero_limit() {
+        // Enough points that small radii use the geohash ring search
+        let db = DB::memory().unwrap();
+        let points: Vec<(Point, Vec<u8>)> = (0..1_500)
+            .map(|i| {
+                let point = Point::new(
+                    40.0 + (i / 50) as f64 * 0.005,
+                    -74.0 + (i % 50) as f64 * 0.005,
+                );
+                (point, format!("p{i}").into_bytes())
+            })
+            .collect();
+        db.insert_points_batch("grid", &points, None).unwrap();
+
+        let center = Point::new(40.05, -73.88);
+        let results = db
+            .batch_find_nearby("grid", &[(center, 1_000.0), (center, 5_000.0)], 0)
+            .unwrap();
+        assert_eq!(results, vec![Vec::new(), Vec::new()]);
+        assert!(
+            db.find_nearby("grid", &center, 1_000.0, 0)
+                .unwrap()
+                .is_empty()
+        );
     }
 
-    /// Load data from AOF file
-    /// Load database state from the AOF file (startup replay).
-    ///
-    /// This method replays all commands from the append-only file to restore
-    /// the database to its previous state. It's called automatically during
-    /// database initialization.
-    ///
-    /// The replay process:
-    /// 1. Reads all commands from the AOF sequentially
-    /// 2. Applies each SET and DELETE command to rebuild state
-    /// 3. Reconstructs spatial indexes from geographic data
-    /// 4. Updates statistics (key counts, etc.)
-    ///
-    /// # Error Handling
-    ///
-    /// If the AOF is corrupted or unreadable, this method returns an error
-    /// and the database will not open. To recover from corruption:
-    /// - Restore from backup if available
-    /// - Or delete the AOF file to start fresh (data loss)
-    pub fn load_from_aof(&mut self, aof_file: &mut AOFFile) -> Result<()> {
-        let commands = aof_file.replay()?;
+    #[test]
+    fn test_nearest_trajectory_point() {
+        let db = DB::memory().unwrap();
+        let query = Point::new(40.72, -74.0);
+        assert!(
+            db.nearest_trajectory_point("bus:1", &query)
+                .unwrap()
+                .is_none()
+        );
 
-        for command in commands {
-            match command {
-                AOFCommand::Set {
-                    key,
-                    value,
-                    expires_at,
-                } => {
-                    let item = DbItem {
-                        value: value.clone(),
-                        expires_at,
-                    };
-                    self.keys.insert(key.clone(), item);
-
-                    // Rebuild spatial index if this is a spatial key
-                    if let Ok(key_str) = std::str::from_utf8(&key)
-                        && let Some((prefix, geohash)) = self.parse_spatial_key(key_str)
-                        && let Ok(point) = self.decode_geohash_to_point(geohash)
-                    {
-                        let _ = self.index_manager.insert_point(prefix, &point, &value);
-                    }
-                }
-                AOFCommand::Delete { key } => {
-                    self.keys.remove(&key);
-
-                    // Remove from spatial index if this was a spatial key
-                    if let Ok(key_str) = std::str::from_utf8(&key)
-                        && let Some((prefix, geohash)) = self.parse_spatial_key(key_str)
-                        && let Ok(point) = self.decode_geohash_to_point(geohash)
-                    {
-                        let _ = self.index_manager.remove_point(prefix, &point);
-                    }
-                }
-            }
-        }
+        let track = [
+            (Point::new(40.70, -74.0), 100),
+            (Point::new(40.73, -74.0), 200),
+            (Point::new(40.71, -74.0), 300),
+        ];
+        db.insert_trajectory("bus:1", &track, None).unwrap();
+        db.insert_trajectory("bus:10", &[(query, 50)], None)
+            .unwrap();
 
-        self.stats.key_count = self.keys.len();
-        Ok(())
+        let (point, timestamp, distance) = db
+            .nearest_trajectory_point("bus:1", &query)
+            .unwrap()
+            .unwrap();
+        assert_eq!(timestamp, 200);
+        assert_eq!(point, track[1].0);
+        assert!((distance - query.distance_to(&track[1].0)).abs() < 1e-9);
+
+        // Ties go to the earliest point
+        let midway = Point::new(40.705, -74.0);
+        let (_, timestamp, _) = db
+            .nearest_trajectory_point("bus:1", &midway)
+            .unwrap()
+            .unwrap();
+        assert_eq!(timestamp, 100);
     }
 
-    /// Parse a spatial key to extract prefix and geohash
-    fn parse_spatial_key<'a>(&self, key: &'a str) -> Option<(&'a str, &'a str)> {
-        // Spatial keys have format: "prefix:gh:geohash" for geographic points
-        let parts: Vec<&str> = key.split(':').collect();
-        if parts.len() >= 3 && parts[1] == "gh" {
-            let prefix = parts[0];
-            let geohash = parts[2];
-            Some((prefix, geohash))
-        } else {
-            None
+    #[test]
+    fn test_insert_points_batch() {
+        let db = DB::memory().unwrap();
+        let points: Vec<(Point, Vec<u8>)> = (0..200)
+            .map(|i| {
+                let point = Point::new(
+                    40.0 + (i % 20) as f64 * 0.01,
+                    -74.0 + (i / 20) as f64 * 0.01,
+                );
+                (point, format!("p{}", i).into_bytes())
+            })
+            .collect();
+
+        assert_eq!(db.insert_points_batch("grid", &points, None).unwrap(), 200);
+        assert_eq!(
+            db.count_within_distance("grid", &points[0].0, 1.0).unwrap(),
+            1
+        );
+        for (point, value) in points.iter().step_by(37) {
+            let nearby = db.find_nearby("grid", point, 1.0, 1).unwrap();
+            assert_eq!(nearby[0].1.as_ref(), value.as_slice());
         }
+
+        // Options apply to every point
+        let expired = SetOptions::with_expiration(SystemTime::UNIX_EPOCH);
+        let stale = [(Point::new(10.0, 10.0), b"old".to_vec())];
+        db.insert_points_batch("stale", &stale, Some(expired))
+            .unwrap();
+        assert!(
+            db.find_nearby("stale", &stale[0].0, 1.0, 1)
+                .unwrap()
+                .is_empty()
+        );
+
+        let mut db = db;
+        db.close().unwrap();
+        let err = db.insert_points_batch("grid", &points, None).unwrap_err();
+        assert!(matches!(err.root_cause(), SpatioError::DatabaseClosed));
     }
 
-    /// Decode a geohash back to a Point
-    fn decode_geohash_to_point(&self, geohash: &str) -> Result<Point> {
-        let (coord, _lat_err, _lon_err) =
-            geohash::decode(geohash).map_err(|_| SpatioError::InvalidGeohash)?;
-        Ok(Point::new(coord.y, coord.x))
+    #[test]
+    fn test_fuzzy_find_geohash_region() {
+        let db = DB::memory().unwrap();
+        let nyc = Point::new(40.7128, -74.0060);
+        let london = Point::new(51.5074, -0.1278);
+        db.insert_point("sensors", &nyc, b"nyc", None).unwrap();
+        db.insert_point("sensors", &london, b"london", None)
+            .unwrap();
+        db.insert_point("other", &nyc, b"elsewhere", None).unwrap();
+
+        let hash = nyc.to_geohash(8).unwrap();
+        let exact = db.fuzzy_find_geohash_region("sensors", &hash, 0).unwrap();
+        assert_eq!(exact, vec![(hash.clone(), Bytes::from_static(b"nyc"))]);
+
+        // Two characters off is outside distance 1 but within 2
+        let typo = format!("{}zz", &hash[..6]);
+        assert_ne!(&hash[6..], "zz");
+        let near = db.fuzzy_find_geohash_region("sensors", &typo, 1).unwrap();
+        assert!(near.iter().all(|(found, _)| found != &hash));
+        let wider = db.fuzzy_find_geohash_region("sensors", &typo, 2).unwrap();
+        assert_eq!(wider.len(), 1);
+        assert_eq!(wider[0].1.as_ref(), b"nyc");
+
+        // Expired points are skipped
+        let expired = SetOptions::with_expiration(SystemTime::UNIX_EPOCH);
+        let stale = Point::new(10.0, 10.0);
+        db.insert_point("sensors", &stale, b"stale", Some(expired))
+            .unwrap();
+        let stale_hash = stale.to_geohash(8).unwrap();
+        assert!(
+            db.fuzzy_find_geohash_region("sensors", &stale_hash, 0)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            db.fuzzy_find_geohash_region("sensors", "not-a-hash", 1)
+                .unwrap()
+                .is_empty()
+        );
     }
 
-    /// Write to AOF file if needed
-    pub fn write_to_aof_if_needed(
-        &mut self,
-        key: &Bytes,
-        value: &[u8],
-        options: Option<&SetOptions>,
-    ) -> Result<()> {
-        if let Some(ref mut aof_file) = self.aof_file {
-            let value_bytes = Bytes::copy_from_slice(value);
-            aof_file.write_set(key, &value_bytes, options)?;
+    #[test]
+    fn test_aggregate_spatial() {
+        use crate::analysis::SpatialAggregator;
 
-            // Flush based on sync policy
-            match self.config.sync_policy {
-                crate::types::SyncPolicy::Always => {
-                    aof_file.sync()?;
-                }
-                crate::types::SyncPolicy::EverySecond => {
-                    aof_file.flush()?;
-                }
-                crate::types::SyncPolicy::Never => {
-                    // Don't flush
-                }
-            }
-        }
-        Ok(())
+        let db = DB::memory().unwrap();
+        let reading = |data: &[u8]| std::str::from_utf8(data).ok()?.parse::<f64>().ok();
+        db.insert_point("sensors", &Point::new(40.71, -74.00), b"10", None)
+            .unwrap();
+        db.insert_point("sensors", &Point::new(40.72, -74.01), b"20", None)
+            .unwrap();
+        db.insert_point("sensors", &Point::new(40.73, -74.02), b"broken", None)
+            .unwrap();
+        db.insert_point("sensors", &Point::new(51.50, -0.12), b"99", None)
+            .unwrap();
+        let expired = SetOptions::with_expiration(SystemTime::UNIX_EPOCH);
+        db.insert_point("sensors", &Point::new(40.74, -74.03), b"50", Some(expired))
+            .unwrap();
+
+        let nyc = BoundingBox::new(40.5, -74.3, 40.9, -73.7);
+        let count = db
+            .aggregate_spatial("sensors", &nyc, SpatialAggregator::count())
+            .unwrap();
+        assert_eq!(count, 3);
+        let sum = db
+            .aggregate_spatial("sensors", &nyc, SpatialAggregator::sum(reading))
+            .unwrap();
+        assert_eq!(sum, 30.0);
+        let average = db
+            .aggregate_spatial("sensors", &nyc, SpatialAggregator::average(reading))
+            .unwrap();
+        assert_eq!(average.count, 2);
+        assert_eq!(average.value(), Some(15.0));
+
+        let empty = BoundingBox::new(0.0, 0.0, 1.0, 1.0);
+        let none = db
+            .aggregate_spatial("sensors", &empty, SpatialAggregator::average(reading))
+            .unwrap();
+        assert_eq!(none.value(), None);
     }
 
-    /// Write delete operation to AOF if needed
-    pub fn write_delete_to_aof_if_needed(&mut self, key: &Bytes) -> Result<()> {
-        if let Some(ref mut aof_file) = self.aof_file {
-            aof_file.write_delete(key)?;
+    #[test]
+    fn test_spatial_difference() {
+        let db = DB::memory().unwrap();
+        let store = Point::new(40.7128, -74.0060);
+        db.insert_point("stores", &store, b"downtown", None)
+            .unwrap();
+        for i in 0..5 {
+            // 0.01 degrees of latitude is about 1.1 km
+            let customer = Point::new(40.7128 + i as f64 * 0.02, -74.0060);
+            db.insert_point("customers", &customer, format!("c{}", i).as_bytes(), None)
+                .unwrap();
+        }
 
-            // Flush based on sync policy
-            match self.config.sync_policy {
-                crate::types::SyncPolicy::Always => {
-                    aof_file.sync()?;
-                }
-                crate::types::SyncPolicy::EverySecond => {
-                    aof_file.flush()?;
-                }
-                crate::types::SyncPolicy::Never => {
-                    // Don't flush
-                }
-            }
+        let mut unserved = db
+            .spatial_difference("customers", "stores", 3_000.0, 10)
+            .unwrap();
+        unserved.sort_by(|a, b| a.1.cmp(&b.1));
+        let names: Vec<&[u8]> = unserved.iter().map(|(_, data)| data.as_ref()).collect();
+        assert_eq!(names, vec![b"c2".as_ref(), b"c3", b"c4"]);
+
+        assert_eq!(
+            db.spatial_difference("customers", "stores", 3_000.0, 2)
+                .unwrap()
+                .len(),
+            2
+        );
+        // An empty namespace excludes nothing
+        assert_eq!(
+            db.spatial_difference("customers", "missing", 3_000.0, 10)
+                .unwrap()
+                .len(),
+            5
+        );
+
+        let with_nearest = db
+            .spatial_difference_with_nearest("customers", "stores", 3_000.0, 10)
+            .unwrap();
+        assert_eq!(with_nearest.len(), 3);
+        for unserved in &with_nearest {
+            let (nearest, data, distance) = unserved.nearest.as_ref().unwrap();
+            assert_eq!(*nearest, store);
+            assert_eq!(data.as_ref(), b"downtown");
+            assert!((distance - unserved.point.distance_to(&store)).abs() < 1e-9);
+            assert!(*distance > 3_000.0);
         }
-        Ok(())
+        let alone = db
+            .spatial_difference_with_nearest("customers", "missing", 3_000.0, 1)
+            .unwrap();
+        assert!(alone[0].nearest.is_none());
     }
-}
 
-// Re-export for convenience
-pub use DB as Spatio;
+    #[test]
+    fn test_trajectory_distance_and_duration() {
+        let db = DB::memory().unwrap();
+        let a = Point::new(40.0, -74.0);
+        let b = Point::new(40.01, -74.0);
+        let c = Point::new(40.02, -74.0);
+        // Moving, stopped, then moving again
+        let track = [(a, 100), (b, 200), (b, 500), (c, 600)];
+        db.insert_trajectory("bus:1", &track, None).unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::sync::Arc;
+        let leg = a.distance_to(&b);
+        let total = db.trajectory_total_distance("bus:1", 0, u64::MAX).unwrap();
+        assert!((total - leg - b.distance_to(&c)).abs() < 1e-6);
+        let first_leg = db.trajectory_total_distance("bus:1", 100, 200).unwrap();
+        assert!((first_leg - leg).abs() < 1e-6);
+        assert_eq!(
+            db.trajectory_total_distance("bus:1", 100, 150).unwrap(),
+            0.0
+        );
+
+        assert_eq!(db.trajectory_duration("bus:1", 0, u64::MAX).unwrap(), 500);
+        assert_eq!(db.trajectory_duration("bus:1", 150, 550).unwrap(), 300);
+        assert_eq!(db.trajectory_duration("missing", 0, u64::MAX).unwrap(), 0);
+
+        assert_eq!(
+            db.trajectory_moving_duration("bus:1", 0, u64::MAX, 1.0)
+                .unwrap(),
+            200
+        );
+        assert_eq!(
+            db.trajectory_moving_duration("bus:1", 0, u64::MAX, 0.0)
+                .unwrap(),
+            500
+        );
+        // About 11 m/s on each leg
+        assert_eq!(
+            db.trajectory_moving_duration("bus:1", 0, u64::MAX, 20.0)
+                .unwrap(),
+            0
+        );
+    }
 
     #[test]
-    fn test_drop_only_syncs_on_last_reference() {
-        use std::fs;
-        let temp_path = std::env::temp_dir().join("test_drop_sync.aof");
-        let _ = fs::remove_file(&temp_path);
+    fn test_find_objects_near_trajectory() {
+        let db = DB::memory().unwrap();
+        let track = [
+            (Point::new(40.0, -74.0), 1_000),
+            (Point::new(40.0, -73.9), 2_000),
+            (Point::new(40.1, -73.9), 3_000),
+        ];
+        db.insert_trajectory("flight:1", &track, None).unwrap();
 
-        // Create database
-        let db = DB::open(&temp_path).unwrap();
-        db.insert("key1", b"value1", None).unwrap();
+        // Beside the middle of the first leg, and near the second leg
+        db.insert_point("aircraft", &Point::new(40.001, -73.95), b"first", None)
+            .unwrap();
+        db.insert_point("aircraft", &Point::new(40.05, -73.9005), b"second", None)
+            .unwrap();
+        db.insert_point("aircraft", &Point::new(40.05, -73.95), b"far", None)
+            .unwrap();
+        let expired = SetOptions::with_expiration(SystemTime::UNIX_EPOCH);
+        db.insert_point(
+            "aircraft",
+            &Point::new(40.0, -73.96),
+            b"gone",
+            Some(expired),
+        )
+        .unwrap();
 
-        // Create clones
-        let db2 = db.clone();
-        let db3 = db.clone();
+        let found = db
+            .find_objects_near_trajectory("flight:1", "aircraft", 500.0, u64::MAX)
+            .unwrap();
+        let names: Vec<&[u8]> = found.iter().map(|r| r.data.as_ref()).collect();
+        assert_eq!(names, vec![b"second".as_ref(), b"first"]);
+        assert!(found[0].distance_meters < found[1].distance_meters);
 
-        // Check strong count
-        assert_eq!(Arc::strong_count(&db.inner), 3);
+        let first = &found[1];
+        assert!((first.timestamp as i64 - 1_500).abs() <= 5);
+        assert!((first.nearest_trajectory_point.lat - 40.0).abs() < 1e-3);
+        assert!((first.distance_meters - 111.0).abs() < 2.0);
+        assert!((found[0].timestamp as i64 - 2_500).abs() <= 5);
 
-        // Drop one clone - should NOT sync (still 2 references)
-        drop(db2);
-        assert_eq!(Arc::strong_count(&db.inner), 2);
+        // Only the last leg falls inside a short window
+        let recent = db
+            .find_objects_near_trajectory("flight:1", "aircraft", 500.0, 1_000)
+            .unwrap();
+        assert_eq!(recent.len(), 1);
+        assert_eq!(recent[0].data.as_ref(), b"second");
 
-        // Drop another clone - should NOT sync (still 1 reference)
-        drop(db3);
-        assert_eq!(Arc::strong_count(&db.inner), 1);
+        // A window of zero keeps only the latest point
+        assert!(
+            db.find_objects_near_trajectory("flight:1", "aircraft", 500.0, 0)
+                .unwrap()
+                .is_empty()
+        );
+        assert!(
+            db.find_objects_near_trajectory("missing", "aircraft", 500.0, 1_000)
+                .unwrap()
+                .is_empty()
+        );
+    }
 
-        // Drop last reference - SHOULD sync
-        drop(db);
+    #[test]
+    fn test_smooth_trajectory() {
+        let db = DB::memory().unwrap();
+        // A straight northward track with alternating sideways jitter
+        let noisy: Vec<(Point, u64)> = (0..20)
+            .map(|i| {
+                let jitter = if i % 2 == 0 { 0.0001 } else { -0.0001 };
+                (Point::new(40.0 + i as f64 * 0.0001, -74.0 + jitter), i * 10)
+            })
+            .collect();
+        db.insert_trajectory("car:1", &noisy, None).unwrap();
+        let ttl = SetOptions::with_expiration(SystemTime::now() + Duration::from_secs(3600));
+        db.insert_trajectory("car:2", &noisy, Some(ttl)).unwrap();
 
-        // Reopen and verify data persisted
-        let db = DB::open(&temp_path).unwrap();
-        assert_eq!(db.get("key1").unwrap().unwrap().as_ref(), b"value1");
+        let updated = db.smooth_trajectory("car:1", 0.01, 25.0, None).unwrap();
+        assert_eq!(updated, 19);
 
-        // Cleanup
-        let _ = fs::remove_file(temp_path);
+        let smoothed = db.query_trajectory("car:1", 0, u64::MAX).unwrap();
+        assert_eq!(smoothed.len(), 20);
+        assert_eq!(smoothed[0], noisy[0]);
+        let sideways = |track: &[(Point, u64)]| -> f64 {
+            track[10..].iter().map(|(p, _)| (p.lon + 74.0).abs()).sum()
+        };
+        assert!(sideways(&smoothed) < sideways(&noisy) / 2.0);
+        assert!(
+            smoothed
+                .iter()
+                .zip(&noisy)
+                .all(|(smooth, raw)| smooth.1 == raw.1)
+        );
+
+        // Expirations are kept, and the other object is untouched
+        let key = format!("traj:car:2:{:010}:{:06}", 50, 5);
+        assert!(db.remaining_ttl(&key).unwrap().is_some());
+        assert_eq!(db.smooth_trajectory("car:2", 0.01, 25.0, None).unwrap(), 19);
+        assert!(db.remaining_ttl(&key).unwrap().is_some());
+
+        assert!(matches!(
+            db.smooth_trajectory("car:1", -1.0, 25.0, None),
+            Err(SpatioError::InvalidConfiguration(_))
+        ));
+        assert!(db.smooth_trajectory("car:1", 0.1, 0.0, None).is_err());
+        assert_eq!(db.smooth_trajectory("missing", 0.1, 25.0, None).unwrap(), 0);
     }
 
     #[test]
-    fn test_explicit_close_prevents_operations() {
-        let mut db = DB::memory().unwrap();
-        db.insert("key", b"value", None).unwrap();
+    fn test_cluster_trajectories() {
+        let db = DB::memory().unwrap();
+        // Three runs up one avenue at different rates, one of them
+        // reversed, and one along a street far away
+        let north = |id: &str, lon: f64, steps: u64| {
+            let track: Vec<(Point, u64)> = (0..=steps)
+                .map(|i| {
+                    (
+                        Point::new(40.70 + 0.05 * i as f64 / steps as f64, lon),
+                        i * 60,
+                    )
+                })
+                .collect();
+            db.insert_trajectory(id, &track, None).unwrap();
+        };
+        north("bus:1", -74.0000, 5);
+        north("bus:2", -74.0004, 12);
+        north("bus:3", -74.0008, 3);
+        let reversed: Vec<(Point, u64)> = (0..=5)
+            .map(|i| (Point::new(40.75 - 0.01 * i as f64, -74.0002), i * 60))
+            .collect();
+        db.insert_trajectory("bus:4", &reversed, None).unwrap();
+        north("bus:5", -73.9000, 5);
+        db.insert_trajectory("bus:6", &[(Point::new(40.7, -74.0), 10_000)], None)
+            .unwrap();
 
-        // Close the database
-        db.close().unwrap();
+        let ids = ["bus:1", "bus:2", "bus:3", "bus:4", "bus:5", "bus:6"];
+        let clusters = db.cluster_trajectories(&ids, (0, 5_000), 150.0, 2).unwrap();
+        assert_eq!(clusters.len(), 1);
+        assert_eq!(clusters[0].member_ids, vec!["bus:1", "bus:2", "bus:3"]);
+        // The middle run is closest to both others
+        let middle = db.query_trajectory("bus:2", 0, 5_000).unwrap();
+        assert_eq!(clusters[0].representative_trajectory, middle);
 
-        // Operations should fail
-        assert!(db.insert("key2", b"value2", None).is_err());
-        assert!(db.get("key").is_err());
-        assert!(db.delete("key").is_err());
+        // Every trajectory anchors its own cluster with one sample
+        let singles = db.cluster_trajectories(&ids, (0, 5_000), 150.0, 1).unwrap();
+        assert_eq!(singles.len(), 3);
+        assert_eq!(singles[1].member_ids, vec!["bus:4"]);
+
+        assert!(db.cluster_trajectories(&ids, (0, 5_000), 0.0, 2).is_err());
     }
 
     #[test]
-    fn test_clone_shares_state() {
+    fn test_geofence_events() {
+        use crate::geometry::LinearRing;
+
         let db = DB::memory().unwrap();
-        let db2 = db.clone();
+        let square = Polygon::new(
+            LinearRing::new(vec![
+                Coordinate::new(0.0, 0.0),
+                Coordinate::new(1.0, 0.0),
+                Coordinate::new(1.0, 1.0),
+                Coordinate::new(0.0, 1.0),
+            ])
+            .unwrap(),
+            vec![],
+        );
 
-        db.insert("key1", b"value1", None).unwrap();
-        db2.insert("key2", b"value2", None).unwrap();
+        // Starts inside, leaves through the east edge, then swings north
+        // and comes back in through the same edge
+        db.insert_trajectory(
+            "boat:1",
+            &[
+                (Point::new(0.5, 0.5), 0),
+                (Point::new(0.5, 1.5), 1_000),
+                (Point::new(1.5, 1.5), 2_000),
+                (Point::new(0.25, 0.75), 3_000),
+            ],
+            None,
+        )
+        .unwrap();
+        // Never comes near
+        db.insert_trajectory(
+            "boat:2",
+            &[(Point::new(5.0, 5.0), 0), (Point::new(6.0, 6.0), 3_000)],
+            None,
+        )
+        .unwrap();
 
-        // Both clones see both keys
-        assert_eq!(db.get("key1").unwrap().unwrap().as_ref(), b"value1");
-        assert_eq!(db.get("key2").unwrap().unwrap().as_ref(), b"value2");
-        assert_eq!(db2.get("key1").unwrap().unwrap().as_ref(), b"value1");
-        assert_eq!(db2.get("key2").unwrap().unwrap().as_ref(), b"value2");
+        let events = db
+            .geofence_events(&square, &["boat:1", "boat:2", "boat:3"], 0, 5_000)
+            .unwrap();
+        let summary: Vec<(GeofenceEventType, u64)> =
+            events.iter().map(|e| (e.event_type, e.timestamp)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (GeofenceEventType::Exit, 500),
+                (GeofenceEventType::Enter, 2_667),
+            ]
+        );
+        assert!(events.iter().all(|e| e.object_id == "boat:1"));
+        assert!((events[0].crossing_point.lon - 1.0).abs() < 0.01);
+        assert!((events[1].crossing_point.lon - 1.0).abs() < 0.01);
+        assert!(events[1].crossing_point.lat < 1.0);
+
+        // Only the part of the trajectory inside the time range counts
+        let later = db
+            .geofence_events(&square, &["boat:1"], 1_000, 5_000)
+            .unwrap();
+        assert_eq!(later.len(), 1);
+        assert_eq!(later[0].event_type, GeofenceEventType::Enter);
+    }
+
+    #[test]
+    fn test_stale_spatial_entries() {
+        let mut db = DB::memory().unwrap();
+        let nyc = Point::new(40.7128, -74.0060);
+        let london = Point::new(51.5074, -0.1278);
+        db.insert_point("cities", &nyc, b"NYC", None).unwrap();
+        db.insert_point("cities", &london, b"London", None).unwrap();
+        assert!(db.find_stale_spatial_entries().unwrap().is_empty());
+
+        // Drop London's key behind the index's back
+        let geohash = london.to_geohash(8).unwrap();
+        let key = SpatialKey::geohash("cities", &geohash);
+        db.write().unwrap().keys.remove(key.as_bytes());
+
+        let stale = db.find_stale_spatial_entries().unwrap();
+        assert_eq!(
+            stale,
+            vec![StaleEntry {
+                prefix: "cities".to_string(),
+                geohash,
+                orphaned_point: london,
+            }]
+        );
+
+        assert_eq!(db.remove_stale_spatial_entries().unwrap(), 1);
+        assert!(db.find_stale_spatial_entries().unwrap().is_empty());
+        let nearby = db.find_nearby("cities", &london, 1_000.0, 10).unwrap();
+        assert!(nearby.is_empty());
+        assert_eq!(
+            db.find_nearby("cities", &nyc, 1_000.0, 10).unwrap().len(),
+            1
+        );
+
+        db.close().unwrap();
+        assert!(db.find_stale_spatial_entries().is_err());
+        assert!(db.remove_stale_spatial_entries().is_err());
     }
 }
@@ -0,0 +1,253 @@
+//! Human-readable database dumps for debugging
+//!
+//! This module writes stored keys and database statistics as NDJSON, one
+//! JSON object per line, so they can be read directly or piped through
+//! tools such as `jq`. Dumps show raw stored bytes and are meant for
+//! inspection, not as a backup format.
+
+use crate::DB;
+use crate::error::{Result, SpatioError};
+use serde_json::{Map, Value, json};
+use std::io::Write;
+use std::time::SystemTime;
+
+impl DB {
+    /// Write stored keys as NDJSON, one object per key in key order.
+    ///
+    /// Each line has the `key` and `value`, as text when they are valid
+    /// UTF-8 and as hex otherwise (`key_encoding` and `value_encoding`
+    /// say which), `expires_at` in seconds since the Unix epoch or `null`,
+    /// and whether the key has `expired` but not yet been swept. Geohash,
+    /// S2 and H3 point keys also get the decoded `lat` and `lon`.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - Destination of the dump
+    /// * `prefix` - Only dump keys starting with this prefix, or all keys
+    /// * `max_items` - Maximum number of keys to write
+    ///
+    /// # Returns
+    ///
+    /// The number of keys written
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Point, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert("user:1", b"alice", None)?;
+    /// db.insert("user:2", [0xff, 0x00], None)?;
+    /// db.insert_point("cities", &Point::new(40.7128, -74.0060), b"NYC", None)?;
+    ///
+    /// let mut out = Vec::new();
+    /// assert_eq!(db.debug_dump(&mut out, Some(b"user:"), 100)?, 2);
+    ///
+    /// let lines: Vec<serde_json::Value> = String::from_utf8(out)?
+    ///     .lines()
+    ///     .map(serde_json::from_str)
+    ///     .collect::<Result<_, _>>()?;
+    /// assert_eq!(lines[0]["value"], "alice");
+    /// assert_eq!(lines[1]["value"], "ff00");
+    /// assert_eq!(lines[1]["value_encoding"], "hex");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.debug_dump",
+            skip_all,
+            fields(db.max_items = max_items as i64),
+            err
+        )
+    )]
+    pub fn debug_dump(
+        &self,
+        writer: &mut dyn Write,
+        prefix: Option<&[u8]>,
+        max_items: usize,
+    ) -> Result<usize> {
+        // Build the lines under the lock, then write without holding it
+        let lines: Vec<Value> = {
+            let inner = self.read()?;
+            if inner.closed {
+                return Err(SpatioError::DatabaseClosed);
+            }
+            let prefix = prefix.unwrap_or_default();
+            inner
+                .keys
+                .range(bytes::Bytes::copy_from_slice(prefix)..)
+                .take_while(|(key, _)| key.starts_with(prefix))
+                .take(max_items)
+                .map(|(key, item)| {
+                    let mut line = Map::new();
+                    let (key_text, key_encoding) = readable(key);
+                    let (value_text, value_encoding) = readable(&item.value);
+                    line.insert("key".into(), key_text.into());
+                    line.insert("key_encoding".into(), key_encoding.into());
+                    line.insert("value".into(), value_text.into());
+                    line.insert("value_encoding".into(), value_encoding.into());
+                    line.insert("expires_at".into(), json!(item.expires_at.map(unix_secs)));
+                    line.insert("expired".into(), item.is_expired().into());
+                    if let Some(point) = inner.spatial_key_point(key) {
+                        line.insert("lat".into(), point.lat.into());
+                        line.insert("lon".into(), point.lon.into());
+                    }
+                    Value::Object(line)
+                })
+                .collect()
+        };
+
+        for line in &lines {
+            writeln!(writer, "{}", line)?;
+        }
+        Ok(lines.len())
+    }
+
+    /// Write database statistics as a single NDJSON line.
+    ///
+    /// The object holds `db` with the fields of `DbStats`, `index` with
+    /// the spatial index and point counts, and `aof_size_bytes`, which is
+    /// `null` for databases without an AOF.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Point, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("cities", &Point::new(40.7128, -74.0060), b"NYC", None)?;
+    ///
+    /// let mut out = Vec::new();
+    /// db.debug_dump_stats(&mut out)?;
+    ///
+    /// let stats: serde_json::Value = serde_json::from_slice(&out)?;
+    /// assert_eq!(stats["db"]["key_count"], 1);
+    /// assert_eq!(stats["index"]["total_points"], 1);
+    /// assert!(stats["aof_size_bytes"].is_null());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(name = "db.debug_dump_stats", skip_all, err)
+    )]
+    pub fn debug_dump_stats(&self, writer: &mut dyn Write) -> Result<()> {
+        let line = {
+            let inner = self.read()?;
+            if inner.closed {
+                return Err(SpatioError::DatabaseClosed);
+            }
+            let index = inner.index_manager.stats();
+            #[cfg(feature = "aof")]
+            let aof_size_bytes = inner.aof_file.as_ref().map(|aof| aof.size());
+            #[cfg(not(feature = "aof"))]
+            let aof_size_bytes: Option<u64> = None;
+            json!({
+                "db": inner.stats,
+                "index": {
+                    "index_count": index.index_count,
+                    "total_points": index.total_points,
+                },
+                "aof_size_bytes": aof_size_bytes,
+            })
+        };
+
+        writeln!(writer, "{}", line)?;
+        Ok(())
+    }
+}
+
+/// Bytes as UTF-8 text if possible, otherwise as lowercase hex, with the
+/// encoding used
+fn readable(bytes: &[u8]) -> (String, &'static str) {
+    match std::str::from_utf8(bytes) {
+        Ok(text) => (text.to_string(), "utf8"),
+        Err(_) => (
+            bytes.iter().map(|byte| format!("{:02x}", byte)).collect(),
+            "hex",
+        ),
+    }
+}
+
+/// Seconds since the Unix epoch, negative before it
+fn unix_secs(time: SystemTime) -> f64 {
+    match time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(since) => since.as_secs_f64(),
+        Err(before) => -before.duration().as_secs_f64(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DB;
+    use crate::spatial::Point;
+    use crate::types::SetOptions;
+    use serde_json::Value;
+    use std::time::{Duration, SystemTime};
+
+    fn dump_lines(db: &DB, prefix: Option<&[u8]>, max_items: usize) -> Vec<Value> {
+        let mut out = Vec::new();
+        let written = db.debug_dump(&mut out, prefix, max_items).unwrap();
+        let lines: Vec<Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(written, lines.len());
+        lines
+    }
+
+    #[test]
+    fn test_debug_dump() {
+        let db = DB::memory().unwrap();
+        let expires_at = SystemTime::UNIX_EPOCH + Duration::from_secs(4_000_000_000);
+        db.insert("a", b"plain", Some(SetOptions::with_expiration(expires_at)))
+            .unwrap();
+        db.insert([0xfe, 0x01], b"binary key", None).unwrap();
+        let stale = SetOptions::with_expiration(SystemTime::UNIX_EPOCH);
+        db.insert("b", b"stale", Some(stale)).unwrap();
+        db.insert_point("cities", &Point::new(40.7128, -74.0060), b"NYC", None)
+            .unwrap();
+
+        let lines = dump_lines(&db, None, 100);
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0]["key"], "a");
+        assert_eq!(lines[0]["expires_at"], 4_000_000_000.0);
+        assert_eq!(lines[0]["expired"], false);
+        assert_eq!(lines[1]["expired"], true);
+        assert!(lines[1]["lat"].is_null());
+
+        let city = &lines[2];
+        assert!(city["key"].as_str().unwrap().starts_with("cities:gh:"));
+        assert!((city["lat"].as_f64().unwrap() - 40.7128).abs() < 1e-3);
+        assert!((city["lon"].as_f64().unwrap() + 74.0060).abs() < 1e-3);
+        assert!(city["expires_at"].is_null());
+
+        assert_eq!(lines[3]["key"], "fe01");
+        assert_eq!(lines[3]["key_encoding"], "hex");
+        assert_eq!(lines[3]["value"], "binary key");
+
+        assert_eq!(dump_lines(&db, None, 2).len(), 2);
+        assert_eq!(dump_lines(&db, Some(b"cities:"), 100).len(), 1);
+    }
+
+    #[test]
+    fn test_debug_dump_stats_with_aof() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DB::open(dir.path().join("stats.db")).unwrap();
+        db.insert("key", b"value", None).unwrap();
+        db.sync().unwrap();
+
+        let mut out = Vec::new();
+        db.debug_dump_stats(&mut out).unwrap();
+        let stats: Value = serde_json::from_slice(&out).unwrap();
+        assert_eq!(stats["db"]["key_count"], 1);
+        assert_eq!(stats["index"]["total_points"], 0);
+        #[cfg(feature = "aof")]
+        assert!(stats["aof_size_bytes"].as_u64().unwrap() > 0);
+    }
+}
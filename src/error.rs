@@ -7,6 +7,8 @@ pub enum SpatioError {
     DatabaseClosed,
     /// Lock acquisition failed
     LockError,
+    /// Lock could not be acquired before the deadline
+    LockTimeout,
     /// Invalid geohash
     InvalidGeohash,
     /// Serialization/deserialization error
@@ -21,10 +23,120 @@ pub enum SpatioError {
     UnexpectedEof,
     /// Invalid data format
     InvalidFormat,
+    /// Not enough distinct points to build the requested geometry
+    InsufficientPoints,
+    /// Write rejected because it would exceed the configured memory limit
+    MemoryLimitExceeded,
+    /// Write rejected because the database was opened read-only
+    ReadOnly,
+    /// Builder or configuration options are invalid or conflict
+    InvalidConfiguration(String),
+    /// Coordinate outside the valid latitude or longitude range
+    OutOfRange(String),
+    /// Key or key component that cannot be stored as given
+    InvalidKey(String),
+    /// Transaction gave up after keys it read kept changing before commit
+    TransactionConflict,
     /// I/O error from persistence layer
     Io(std::io::Error),
     /// Generic error with message
     Other(String),
+    /// Another error annotated with the operation or key that caused it
+    WithContext {
+        source: Box<SpatioError>,
+        context: String,
+    },
+}
+
+impl SpatioError {
+    /// Wrap this error with a description of where it happened.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - Operation or key the error came from (e.g. `"insert_point: prefix='cities'"`)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::SpatioError;
+    ///
+    /// let err = SpatioError::InvalidGeohash.context("insert_point: prefix='cities'");
+    /// assert_eq!(err.to_string(), "insert_point: prefix='cities': Invalid geohash");
+    /// assert!(matches!(err.root_cause(), SpatioError::InvalidGeohash));
+    /// ```
+    pub fn context(self, ctx: impl Into<String>) -> SpatioError {
+        SpatioError::WithContext {
+            source: Box::new(self),
+            context: ctx.into(),
+        }
+    }
+
+    /// The underlying error with any context removed, for matching on the
+    /// variant
+    pub fn root_cause(&self) -> &SpatioError {
+        match self {
+            SpatioError::WithContext { source, .. } => source.root_cause(),
+            _ => self,
+        }
+    }
+
+    /// Whether the operation may succeed if retried unchanged.
+    ///
+    /// True for lock failures and timeouts, an AOF rewrite in progress and I/O errors
+    /// that signal a timeout or interruption. Closed databases, invalid
+    /// input and corrupt data are never retryable.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::SpatioError;
+    ///
+    /// assert!(SpatioError::LockError.is_retryable());
+    /// assert!(!SpatioError::DatabaseClosed.is_retryable());
+    /// ```
+    pub fn is_retryable(&self) -> bool {
+        match self.root_cause() {
+            SpatioError::LockError
+            | SpatioError::LockTimeout
+            | SpatioError::RewriteInProgress
+            | SpatioError::TransactionConflict => true,
+            SpatioError::Io(err) => matches!(
+                err.kind(),
+                std::io::ErrorKind::TimedOut
+                    | std::io::ErrorKind::Interrupted
+                    | std::io::ErrorKind::WouldBlock
+            ),
+            _ => false,
+        }
+    }
+
+    /// Whether the error was caused by the caller's input or usage rather
+    /// than by the database or the system.
+    ///
+    /// True for invalid geohashes, timestamps, geometries, coordinates and
+    /// configuration, and for writes to a read-only database. `Other`
+    /// carries free-form messages of either kind and is not counted.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::SpatioError;
+    ///
+    /// assert!(SpatioError::InvalidGeohash.is_user_error());
+    /// assert!(!SpatioError::InvalidFormat.is_user_error());
+    /// ```
+    pub fn is_user_error(&self) -> bool {
+        matches!(
+            self.root_cause(),
+            SpatioError::InvalidGeohash
+                | SpatioError::InvalidTimestamp
+                | SpatioError::InsufficientPoints
+                | SpatioError::ReadOnly
+                | SpatioError::InvalidConfiguration(_)
+                | SpatioError::OutOfRange(_)
+                | SpatioError::InvalidKey(_)
+        )
+    }
 }
 
 impl fmt::Display for SpatioError {
@@ -32,6 +144,7 @@ impl fmt::Display for SpatioError {
         match self {
             SpatioError::DatabaseClosed => write!(f, "Database is closed"),
             SpatioError::LockError => write!(f, "Failed to acquire lock"),
+            SpatioError::LockTimeout => write!(f, "Timed out waiting for lock"),
             SpatioError::InvalidGeohash => write!(f, "Invalid geohash"),
             SpatioError::SerializationError => write!(f, "Serialization error"),
             SpatioError::SerializationErrorWithContext(context) => {
@@ -41,8 +154,20 @@ impl fmt::Display for SpatioError {
             SpatioError::InvalidTimestamp => write!(f, "Invalid timestamp value"),
             SpatioError::UnexpectedEof => write!(f, "Unexpected end of file"),
             SpatioError::InvalidFormat => write!(f, "Invalid data format"),
+            SpatioError::InsufficientPoints => {
+                write!(f, "Insufficient points to build geometry")
+            }
+            SpatioError::MemoryLimitExceeded => write!(f, "Memory limit exceeded"),
+            SpatioError::ReadOnly => write!(f, "Database is read-only"),
+            SpatioError::InvalidConfiguration(msg) => write!(f, "Invalid configuration: {}", msg),
+            SpatioError::OutOfRange(msg) => write!(f, "Coordinate out of range: {}", msg),
+            SpatioError::InvalidKey(msg) => write!(f, "Invalid key: {}", msg),
+            SpatioError::TransactionConflict => {
+                write!(f, "Transaction conflicted with concurrent writes")
+            }
             SpatioError::Io(err) => write!(f, "I/O error: {}", err),
             SpatioError::Other(msg) => write!(f, "{}", msg),
+            SpatioError::WithContext { source, context } => write!(f, "{}: {}", context, source),
         }
     }
 }
@@ -51,6 +176,7 @@ impl std::error::Error for SpatioError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             SpatioError::Io(err) => Some(err),
+            SpatioError::WithContext { source, .. } => Some(source.as_ref()),
             _ => None,
         }
     }
@@ -64,3 +190,55 @@ impl From<std::io::Error> for SpatioError {
 
 /// Result type alias for Spatio operations
 pub type Result<T> = std::result::Result<T, SpatioError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_categories() {
+        let timeout = std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out");
+        let not_found = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+
+        assert!(SpatioError::LockError.is_retryable());
+        assert!(SpatioError::LockTimeout.is_retryable());
+        assert!(SpatioError::RewriteInProgress.is_retryable());
+        assert!(SpatioError::TransactionConflict.is_retryable());
+        assert!(SpatioError::Io(timeout).is_retryable());
+        assert!(!SpatioError::Io(not_found).is_retryable());
+        assert!(!SpatioError::DatabaseClosed.is_retryable());
+        assert!(!SpatioError::InvalidGeohash.is_retryable());
+        assert!(!SpatioError::InvalidFormat.is_retryable());
+
+        assert!(SpatioError::InvalidGeohash.is_user_error());
+        assert!(SpatioError::InvalidConfiguration("bad".to_string()).is_user_error());
+        assert!(SpatioError::ReadOnly.is_user_error());
+        assert!(SpatioError::OutOfRange("lat".to_string()).is_user_error());
+        assert!(SpatioError::InvalidKey("a:b".to_string()).is_user_error());
+        assert!(!SpatioError::LockError.is_user_error());
+        assert!(!SpatioError::SerializationError.is_user_error());
+        assert!(!SpatioError::Other("bad".to_string()).is_user_error());
+
+        // Context does not change the category
+        assert!(SpatioError::LockError.context("get").is_retryable());
+        assert!(SpatioError::InvalidGeohash.context("get").is_user_error());
+    }
+
+    #[test]
+    fn test_error_context() {
+        let err = SpatioError::LockError
+            .context("find_nearby: prefix='cities'")
+            .context("outer");
+        assert_eq!(
+            err.to_string(),
+            "outer: find_nearby: prefix='cities': Failed to acquire lock"
+        );
+        assert!(matches!(err.root_cause(), SpatioError::LockError));
+
+        let source = std::error::Error::source(&err).unwrap();
+        assert_eq!(
+            source.to_string(),
+            "find_nearby: prefix='cities': Failed to acquire lock"
+        );
+    }
+}
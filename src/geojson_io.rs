@@ -0,0 +1,645 @@
+//! GeoJSON import and export for Spatio
+//!
+//! This module adds bulk loading of a GeoJSON `FeatureCollection` file,
+//! and writing a namespace out as one. The file is streamed one feature at
+//! a time in both directions, so collections larger than memory can be
+//! imported. Features are written as they are read: if the file turns out
+//! to be malformed part way through, the features before the error stay in
+//! the database.
+
+use crate::DB;
+use crate::error::{Result, SpatioError};
+use crate::geometry::{Coordinate, Geometry};
+use crate::spatial::SpatialKey;
+use crate::types::{DbItem, ExportStats, ImportStats, SetOptions, ValueFilter};
+use serde::de::{self, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde_json::{Value, json};
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::time::SystemTime;
+
+impl DB {
+    /// Import the features of a GeoJSON `FeatureCollection` file.
+    ///
+    /// `Point` features are inserted with `insert_point`, so their keys
+    /// come from their location. `LineString` and `Polygon` features are
+    /// inserted with `insert_geometry` under the feature's `id`, or a
+    /// random UUID if it has none. Each feature's `properties` are stored
+    /// as its value, as JSON text. Features with other geometry types, or
+    /// with a missing or invalid geometry, are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - Path of the GeoJSON file
+    /// * `prefix` - Namespace for the imported features (must not contain ':')
+    /// * `opts` - Optional settings like TTL, applied to every feature
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read, is not a GeoJSON
+    /// `FeatureCollection`, or a feature cannot be stored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Point, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let path = std::env::temp_dir().join("spatio_import_demo.geojson");
+    /// std::fs::write(&path, r#"{
+    ///     "type": "FeatureCollection",
+    ///     "features": [{
+    ///         "type": "Feature",
+    ///         "geometry": {"type": "Point", "coordinates": [-74.0060, 40.7128]},
+    ///         "properties": {"name": "New York"}
+    ///     }]
+    /// }"#)?;
+    ///
+    /// let db = Spatio::memory()?;
+    /// let stats = db.import_geojson_file(&path, "cities", None)?;
+    /// assert_eq!(stats.features_imported, 1);
+    ///
+    /// let nearby = db.find_nearby("cities", &Point::new(40.7128, -74.0060), 1000.0, 10)?;
+    /// assert_eq!(nearby[0].1.as_ref(), br#"{"name":"New York"}"#);
+    /// # std::fs::remove_file(path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.import_geojson_file",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+            ),
+            err
+        )
+    )]
+    pub fn import_geojson_file(
+        &self,
+        path: &Path,
+        prefix: &str,
+        opts: Option<SetOptions>,
+    ) -> Result<ImportStats> {
+        let mut reader = CountingReader {
+            inner: BufReader::new(File::open(path)?),
+            bytes_read: 0,
+        };
+        let mut stats = ImportStats::default();
+        let mut failure = None;
+
+        let mut on_feature = |feature: Value| -> Result<()> {
+            if self.import_feature(&feature, prefix, opts.as_ref())? {
+                stats.features_imported += 1;
+            } else {
+                stats.features_skipped += 1;
+            }
+            Ok(())
+        };
+
+        let mut deserializer = serde_json::Deserializer::from_reader(&mut reader);
+        let parsed = deserializer
+            .deserialize_map(FeatureCollectionVisitor {
+                on_feature: &mut on_feature,
+                failure: &mut failure,
+            })
+            .and_then(|()| deserializer.end());
+
+        if let Some(error) = failure {
+            return Err(error);
+        }
+        parsed.map_err(|e| {
+            SpatioError::SerializationErrorWithContext(format!(
+                "Invalid GeoJSON in {}: {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+        stats.bytes_read = reader.bytes_read;
+        Ok(stats)
+    }
+
+    /// Export the points and geometries of a namespace as a GeoJSON
+    /// `FeatureCollection` file.
+    ///
+    /// Each feature's `properties` hold its database `key`, its
+    /// `expires_at` time in seconds since the Unix epoch, or `null` if it
+    /// does not expire, and its `value` as text. Geometries also carry
+    /// their id as the feature `id`. Expired items are left out.
+    ///
+    /// Features are written one at a time through a buffered writer rather
+    /// than collected first. The read lock is held for the whole export, so
+    /// writers wait until it finishes.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace to export
+    /// * `path` - Path of the file to create or overwrite
+    /// * `limit` - Maximum number of features to write
+    /// * `filter` - Only features whose value it accepts are written
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Point, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_point("cities", &Point::new(40.7128, -74.0060), b"New York", None)?;
+    /// db.insert_point("cities", &Point::new(51.5074, -0.1278), b"London", None)?;
+    ///
+    /// let path = std::env::temp_dir().join("spatio_export_demo.geojson");
+    /// let only_london = Box::new(|value: &[u8]| value == b"London");
+    /// let stats = db.snapshot_to_geojson("cities", &path, None, Some(only_london))?;
+    /// assert_eq!(stats.features_exported, 1);
+    ///
+    /// let copy = Spatio::memory()?;
+    /// assert_eq!(copy.import_geojson_file(&path, "cities", None)?.features_imported, 1);
+    /// # std::fs::remove_file(path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.snapshot_to_geojson",
+            skip_all,
+            fields(
+                db.prefix = prefix,
+            ),
+            err
+        )
+    )]
+    pub fn snapshot_to_geojson(
+        &self,
+        prefix: &str,
+        path: &Path,
+        limit: Option<usize>,
+        filter: Option<ValueFilter>,
+    ) -> Result<ExportStats> {
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+        let live = |key: &str| {
+            inner
+                .keys
+                .get(key.as_bytes())
+                .filter(|item| !item.is_expired())
+        };
+        let accepted = |data: &[u8]| filter.as_ref().is_none_or(|filter| filter(data));
+
+        let points = inner
+            .index_manager
+            .iter_prefix_points(prefix)
+            .filter_map(|(point, data)| {
+                let key = SpatialKey::geohash(prefix, &point.to_geohash(8).ok()?);
+                let item = live(&key)?;
+                let geometry = Geometry::Point(Coordinate::from_point(point));
+                Some((None, geometry, key, item, data))
+            });
+        let geometries = inner
+            .index_manager
+            .iter_prefix_geometries(prefix)
+            .filter_map(|(id, geometry, data)| {
+                let key = SpatialKey::geometry(prefix, id);
+                let item = live(&key)?;
+                Some((Some(id), geometry.clone(), key, item, data))
+            });
+
+        let mut writer = CountingWriter {
+            inner: BufWriter::new(File::create(path)?),
+            bytes_written: 0,
+        };
+        writer.write_all(br#"{"type":"FeatureCollection","features":["#)?;
+        let mut stats = ExportStats::default();
+        let features = points
+            .chain(geometries)
+            .filter(|(_, _, _, _, data)| accepted(data))
+            .take(limit.unwrap_or(usize::MAX));
+        for (id, geometry, key, item, data) in features {
+            if stats.features_exported > 0 {
+                writer.write_all(b",")?;
+            }
+            serde_json::to_writer(&mut writer, &feature_value(id, &geometry, &key, item, data))
+                .map_err(|e| SpatioError::SerializationErrorWithContext(e.to_string()))?;
+            stats.features_exported += 1;
+        }
+        writer.write_all(b"]}")?;
+        writer.flush()?;
+
+        stats.bytes_written = writer.bytes_written;
+        Ok(stats)
+    }
+
+    /// Store one feature, returning false if its geometry is unsupported
+    fn import_feature(
+        &self,
+        feature: &Value,
+        prefix: &str,
+        opts: Option<&SetOptions>,
+    ) -> Result<bool> {
+        if feature.get("type").and_then(Value::as_str) != Some("Feature") {
+            return Ok(false);
+        }
+        let Some(geometry) = feature
+            .get("geometry")
+            .filter(|geometry| !geometry.is_null())
+            .and_then(|geometry| Geometry::from_geojson_value(geometry).ok())
+        else {
+            return Ok(false);
+        };
+
+        let properties = feature.get("properties").unwrap_or(&Value::Null);
+        let value = serde_json::to_vec(properties)
+            .map_err(|e| SpatioError::SerializationErrorWithContext(e.to_string()))?;
+
+        let stored = match geometry {
+            Geometry::Point(coord) => {
+                self.insert_point(prefix, &coord.to_point(), &value, opts.cloned())
+            }
+            Geometry::LineString(_) | Geometry::Polygon(_) => {
+                let id = match feature.get("id") {
+                    Some(Value::String(id)) if !id.is_empty() => id.clone(),
+                    Some(Value::Number(id)) => id.to_string(),
+                    _ => uuid::Uuid::new_v4().to_string(),
+                };
+                self.insert_geometry(prefix, &id, &geometry, &value, opts.cloned())
+            }
+            _ => return Ok(false),
+        };
+
+        match stored {
+            Ok(()) => Ok(true),
+            // Coordinates outside the valid range
+            Err(e)
+                if matches!(
+                    e.root_cause(),
+                    SpatioError::InvalidGeohash | SpatioError::InsufficientPoints
+                ) =>
+            {
+                Ok(false)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Build the GeoJSON feature for one exported item
+fn feature_value(
+    id: Option<&str>,
+    geometry: &Geometry,
+    key: &str,
+    item: &DbItem,
+    data: &[u8],
+) -> Value {
+    let expires_at = item.expires_at.map(|expires_at| {
+        expires_at
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+    });
+    let mut feature = json!({
+        "type": "Feature",
+        "geometry": geometry.to_geojson_value(),
+        "properties": {
+            "key": key,
+            "expires_at": expires_at,
+            "value": String::from_utf8_lossy(data),
+        },
+    });
+    if let Some(id) = id {
+        feature["id"] = Value::from(id);
+    }
+    feature
+}
+
+/// Writer that counts the bytes passing through it
+struct CountingWriter<W> {
+    inner: W,
+    bytes_written: usize,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.bytes_written += n;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reader that counts the bytes passing through it
+struct CountingReader<R> {
+    inner: R,
+    bytes_read: usize,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.bytes_read += n;
+        Ok(n)
+    }
+}
+
+/// Walks the top-level object of a `FeatureCollection`, handing each
+/// feature to `on_feature` as soon as it is parsed
+struct FeatureCollectionVisitor<'a, F> {
+    on_feature: &'a mut F,
+    /// Error returned by `on_feature`, which stops the import
+    failure: &'a mut Option<SpatioError>,
+}
+
+impl<'de, F> Visitor<'de> for FeatureCollectionVisitor<'_, F>
+where
+    F: FnMut(Value) -> Result<()>,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a GeoJSON FeatureCollection object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> std::result::Result<(), A::Error> {
+        let mut is_collection = false;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "type" => {
+                    let kind: String = map.next_value()?;
+                    if kind != "FeatureCollection" {
+                        return Err(de::Error::custom(format!(
+                            "expected a FeatureCollection, got {}",
+                            kind
+                        )));
+                    }
+                    is_collection = true;
+                }
+                "features" => map.next_value_seed(FeaturesSeed {
+                    on_feature: &mut *self.on_feature,
+                    failure: &mut *self.failure,
+                })?,
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        if !is_collection {
+            return Err(de::Error::missing_field("type"));
+        }
+        Ok(())
+    }
+}
+
+/// The `features` array of a `FeatureCollection`
+struct FeaturesSeed<'a, F> {
+    on_feature: &'a mut F,
+    failure: &'a mut Option<SpatioError>,
+}
+
+impl<'de, F> DeserializeSeed<'de> for FeaturesSeed<'_, F>
+where
+    F: FnMut(Value) -> Result<()>,
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(
+        self,
+        deserializer: D,
+    ) -> std::result::Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, F> Visitor<'de> for FeaturesSeed<'_, F>
+where
+    F: FnMut(Value) -> Result<()>,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an array of GeoJSON features")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> std::result::Result<(), A::Error> {
+        while let Some(feature) = seq.next_element::<Value>()? {
+            if let Err(error) = (self.on_feature)(feature) {
+                let message = error.to_string();
+                *self.failure = Some(error);
+                return Err(de::Error::custom(message));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spatial::{BoundingBox, Point};
+
+    const COLLECTION: &str = r#"{
+        "type": "FeatureCollection",
+        "name": "demo",
+        "features": [
+            {
+                "type": "Feature",
+                "geometry": {"type": "Point", "coordinates": [-74.0060, 40.7128]},
+                "properties": {"name": "New York"}
+            },
+            {
+                "type": "Feature",
+                "id": "broadway",
+                "geometry": {
+                    "type": "LineString",
+                    "coordinates": [[-74.0060, 40.7128], [-73.9857, 40.7484]]
+                },
+                "properties": {"name": "Broadway"}
+            },
+            {
+                "type": "Feature",
+                "id": 7,
+                "geometry": {
+                    "type": "Polygon",
+                    "coordinates": [[[-73.98, 40.76], [-73.95, 40.76], [-73.95, 40.80], [-73.98, 40.76]]]
+                },
+                "properties": null
+            },
+            {
+                "type": "Feature",
+                "geometry": {
+                    "type": "Polygon",
+                    "coordinates": [[[1.0, 1.0], [2.0, 1.0], [2.0, 2.0], [1.0, 2.0]]]
+                },
+                "properties": {}
+            },
+            {
+                "type": "Feature",
+                "geometry": {"type": "MultiPoint", "coordinates": [[0.0, 0.0]]},
+                "properties": {}
+            },
+            {"type": "Feature", "geometry": null, "properties": {}},
+            {
+                "type": "Feature",
+                "geometry": {"type": "Point", "coordinates": [200.0, 95.0]},
+                "properties": {}
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_import_geojson_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("features.geojson");
+        std::fs::write(&path, COLLECTION).unwrap();
+
+        let db = DB::memory().unwrap();
+        let stats = db.import_geojson_file(&path, "map", None).unwrap();
+        assert_eq!(
+            stats,
+            ImportStats {
+                features_imported: 4,
+                features_skipped: 3,
+                bytes_read: COLLECTION.len(),
+            }
+        );
+
+        let nearby = db
+            .find_nearby("map", &Point::new(40.7128, -74.0060), 100.0, 10)
+            .unwrap();
+        assert_eq!(nearby[0].1.as_ref(), br#"{"name":"New York"}"#);
+
+        let found = db
+            .find_geometries_intersecting("map", &BoundingBox::new(40.70, -74.1, 40.80, -73.9))
+            .unwrap();
+        let ids: Vec<_> = found.iter().map(|(id, _, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["7", "broadway"]);
+        assert_eq!(found[0].2.as_ref(), b"null");
+
+        // The feature without an id got a generated one
+        let generated = db
+            .find_geometries_intersecting("map", &BoundingBox::new(0.0, 0.0, 3.0, 3.0))
+            .unwrap();
+        assert_eq!(generated.len(), 1);
+        assert!(uuid::Uuid::parse_str(&generated[0].0).is_ok());
+    }
+
+    #[test]
+    fn test_import_geojson_file_rejects_invalid_input() {
+        let dir = tempfile::tempdir().unwrap();
+        let db = DB::memory().unwrap();
+
+        let path = dir.path().join("feature.geojson");
+        std::fs::write(&path, r#"{"type": "Feature", "features": []}"#).unwrap();
+        assert!(matches!(
+            db.import_geojson_file(&path, "map", None),
+            Err(SpatioError::SerializationErrorWithContext(_))
+        ));
+
+        let path = dir.path().join("truncated.geojson");
+        std::fs::write(&path, &COLLECTION[..COLLECTION.len() / 2]).unwrap();
+        assert!(db.import_geojson_file(&path, "map", None).is_err());
+
+        assert!(matches!(
+            db.import_geojson_file(&dir.path().join("missing.geojson"), "map", None),
+            Err(SpatioError::Io(_))
+        ));
+    }
+
+    #[test]
+    fn test_import_geojson_file_stops_on_write_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("features.geojson");
+        std::fs::write(&path, COLLECTION).unwrap();
+
+        // The prefix is only rejected once a geometry is stored
+        let db = DB::memory().unwrap();
+        let err = db.import_geojson_file(&path, "a:b", None).unwrap_err();
+        assert!(matches!(err, SpatioError::Other(_)));
+    }
+
+    #[test]
+    fn test_snapshot_to_geojson() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.geojson");
+
+        let db = DB::memory().unwrap();
+        let nyc = Point::new(40.7128, -74.0060);
+        let expires_at = SystemTime::UNIX_EPOCH + std::time::Duration::from_secs(4_000_000_000);
+        db.insert_point(
+            "map",
+            &nyc,
+            b"New York",
+            Some(SetOptions::with_expiration(expires_at)),
+        )
+        .unwrap();
+        db.insert_point("map", &Point::new(51.5074, -0.1278), b"London", None)
+            .unwrap();
+        let stale = SetOptions::with_expiration(SystemTime::UNIX_EPOCH);
+        db.insert_point("map", &Point::new(10.0, 10.0), b"stale", Some(stale))
+            .unwrap();
+        let route = Geometry::from_geojson(
+            r#"{"type":"LineString","coordinates":[[-74.0,40.7],[-73.9,40.8]]}"#,
+        )
+        .unwrap();
+        db.insert_geometry("map", "broadway", &route, b"route", None)
+            .unwrap();
+        db.insert_point("other", &nyc, b"elsewhere", None).unwrap();
+
+        let stats = db.snapshot_to_geojson("map", &path, None, None).unwrap();
+        let text = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(stats.features_exported, 3);
+        assert_eq!(stats.bytes_written, text.len());
+
+        let collection: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(collection["type"], "FeatureCollection");
+        let features = collection["features"].as_array().unwrap();
+        let by_value = |value: &str| {
+            features
+                .iter()
+                .find(|f| f["properties"]["value"] == value)
+                .unwrap()
+        };
+        let new_york = by_value("New York");
+        assert_eq!(new_york["geometry"]["type"], "Point");
+        assert_eq!(new_york["properties"]["expires_at"], 4_000_000_000u64);
+        assert_eq!(
+            new_york["properties"]["key"],
+            SpatialKey::geohash("map", &nyc.to_geohash(8).unwrap())
+        );
+        assert!(by_value("London")["properties"]["expires_at"].is_null());
+        let broadway = by_value("route");
+        assert_eq!(broadway["id"], "broadway");
+        assert_eq!(broadway["properties"]["key"], "map:geom:broadway");
+        assert_eq!(broadway["geometry"]["type"], "LineString");
+
+        // The limit and filter apply together
+        let points_only = Box::new(|value: &[u8]| value != b"route");
+        let stats = db
+            .snapshot_to_geojson("map", &path, Some(1), Some(points_only))
+            .unwrap();
+        assert_eq!(stats.features_exported, 1);
+        let collection: Value =
+            serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(collection["features"][0]["geometry"]["type"], "Point");
+
+        let stats = db.snapshot_to_geojson("empty", &path, None, None).unwrap();
+        assert_eq!(stats.features_exported, 0);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            r#"{"type":"FeatureCollection","features":[]}"#
+        );
+    }
+}
@@ -0,0 +1,3035 @@
+//! Geometry types for Spatio
+//!
+//! This module provides planar geometry primitives such as coordinates,
+//! linear rings and polygons. Coordinates use `x` for longitude and `y`
+//! for latitude, matching the GeoJSON axis order.
+
+use crate::error::{Result, SpatioError};
+use crate::spatial::{BoundingBox, Point};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "geojson")]
+use serde_json::{Value, json};
+
+/// Mean Earth radius in kilometers, matching `Point::distance_to`
+const EARTH_RADIUS_KM: f64 = 6_371.0;
+
+/// A planar coordinate pair.
+///
+/// `x` holds the longitude and `y` the latitude when the coordinate
+/// represents a geographic location.
+///
+/// # Examples
+///
+/// ```rust
+/// use spatio::{Coordinate, Point};
+///
+/// let nyc = Point::new(40.7128, -74.0060);
+/// let coord = Coordinate::from_point(&nyc);
+/// assert_eq!(coord.x, -74.0060);
+/// assert_eq!(coord.y, 40.7128);
+/// assert_eq!(coord.to_point(), nyc);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Coordinate {
+    /// X coordinate (longitude in degrees)
+    pub x: f64,
+    /// Y coordinate (latitude in degrees)
+    pub y: f64,
+}
+
+impl Coordinate {
+    /// Create a new coordinate
+    pub fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    /// Create a coordinate from a geographic point
+    pub fn from_point(point: &Point) -> Self {
+        Self::new(point.lon, point.lat)
+    }
+
+    /// Convert this coordinate to a geographic point
+    pub fn to_point(&self) -> Point {
+        Point::new(self.y, self.x)
+    }
+}
+
+/// An open sequence of connected coordinates.
+///
+/// # Examples
+///
+/// ```rust
+/// use spatio::{Coordinate, LineString};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let route = LineString::new(vec![
+///     Coordinate::new(-74.0060, 40.7128),
+///     Coordinate::new(-73.9857, 40.7484),
+/// ])?;
+/// assert_eq!(route.coords().len(), 2);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LineString {
+    coords: Vec<Coordinate>,
+}
+
+impl LineString {
+    /// Create a new line string.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InsufficientPoints` if fewer than two
+    /// coordinates are given.
+    pub fn new(coords: Vec<Coordinate>) -> Result<Self> {
+        if coords.len() < 2 {
+            return Err(SpatioError::InsufficientPoints);
+        }
+        Ok(Self { coords })
+    }
+
+    /// Get the coordinates of this line string
+    pub fn coords(&self) -> &[Coordinate] {
+        &self.coords
+    }
+
+    /// Length of the line string in kilometers, summing Haversine distances
+    pub fn geodesic_length_km(&self) -> f64 {
+        geodesic_length_km(&self.coords)
+    }
+
+    /// Cartesian length of the line string in coordinate units
+    pub fn length(&self) -> f64 {
+        self.coords
+            .windows(2)
+            .map(|w| cartesian_distance(&w[0], &w[1]))
+            .sum()
+    }
+
+    /// Get the coordinate at a distance along the line string.
+    ///
+    /// Distances are Cartesian, in coordinate units, and are clamped to the
+    /// start and end of the line.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, LineString};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let line = LineString::new(vec![
+    ///     Coordinate::new(0.0, 0.0),
+    ///     Coordinate::new(10.0, 0.0),
+    ///     Coordinate::new(10.0, 10.0),
+    /// ])?;
+    ///
+    /// assert_eq!(line.interpolate(15.0), Coordinate::new(10.0, 5.0));
+    /// assert_eq!(line.interpolate(100.0), Coordinate::new(10.0, 10.0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn interpolate(&self, distance: f64) -> Coordinate {
+        interpolate_by(&self.coords, distance, cartesian_distance)
+    }
+
+    /// Resample the line string at uniform intervals.
+    ///
+    /// Points are placed every `interval_distance` coordinate units starting
+    /// at the first vertex. The last vertex is always included.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the interval is not a positive number, or is so small
+    /// that the result would exceed a million points.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, LineString};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let line = LineString::new(vec![Coordinate::new(0.0, 0.0), Coordinate::new(10.0, 0.0)])?;
+    ///
+    /// let resampled = line.resample(2.5)?;
+    /// assert_eq!(resampled.coords().len(), 5);
+    /// assert_eq!(resampled.coords()[1], Coordinate::new(2.5, 0.0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn resample(&self, interval_distance: f64) -> Result<LineString> {
+        LineString::new(resample_by(
+            &self.coords,
+            interval_distance,
+            cartesian_distance,
+        )?)
+    }
+
+    /// Resample the line string at uniform geographic intervals.
+    ///
+    /// Points are placed every `interval_meters` along the line using
+    /// Haversine distances, starting at the first vertex. The last vertex is
+    /// always included.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the interval is not a positive number, or is so small
+    /// that the result would exceed a million points.
+    pub fn resample_geographic(&self, interval_meters: f64) -> Result<Vec<Point>> {
+        let coords = resample_by(&self.coords, interval_meters, haversine_distance)?;
+        Ok(coords.iter().map(Coordinate::to_point).collect())
+    }
+
+    /// Get the bounding box of this line string
+    pub fn bounds(&self) -> BoundingBox {
+        coords_bounds(&self.coords)
+    }
+
+    /// Find every coordinate where this line string meets another.
+    ///
+    /// Each pair of segments is intersected with the parametric line
+    /// formula. Segments that are parallel within a small tolerance only
+    /// meet if they are collinear, in which case both ends of the shared
+    /// stretch are returned. Points where the lines meet at a shared vertex
+    /// are reported once.
+    ///
+    /// # Returns
+    ///
+    /// Crossing coordinates sorted by Cartesian distance along `self`
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, LineString};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let road = LineString::new(vec![Coordinate::new(0.0, 0.0), Coordinate::new(10.0, 0.0)])?;
+    /// let river = LineString::new(vec![
+    ///     Coordinate::new(8.0, -1.0),
+    ///     Coordinate::new(8.0, 1.0),
+    ///     Coordinate::new(2.0, 1.0),
+    ///     Coordinate::new(2.0, -1.0),
+    /// ])?;
+    ///
+    /// assert_eq!(
+    ///     road.crossing_points(&river),
+    ///     vec![Coordinate::new(2.0, 0.0), Coordinate::new(8.0, 0.0)]
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn crossing_points(&self, other: &LineString) -> Vec<Coordinate> {
+        let mut crossings: Vec<(f64, Coordinate)> = Vec::new();
+        let mut along = 0.0;
+
+        for sa in self.coords.windows(2) {
+            let length = cartesian_distance(&sa[0], &sa[1]);
+            for sb in other.coords.windows(2) {
+                for t in segment_crossings(&sa[0], &sa[1], &sb[0], &sb[1]) {
+                    let point = Coordinate::new(
+                        sa[0].x + t * (sa[1].x - sa[0].x),
+                        sa[0].y + t * (sa[1].y - sa[0].y),
+                    );
+                    crossings.push((along + t * length, point));
+                }
+            }
+            along += length;
+        }
+
+        crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+        crossings.dedup_by(|a, b| {
+            (a.1.x - b.1.x).abs() <= CROSSING_EPSILON && (a.1.y - b.1.y).abs() <= CROSSING_EPSILON
+        });
+        crossings.into_iter().map(|(_, point)| point).collect()
+    }
+
+    /// Check whether this line string touches or crosses another,
+    /// stopping at the first pair of segments that meet
+    pub fn intersects_with(&self, other: &LineString) -> bool {
+        lines_cross(&self.coords, &other.coords)
+    }
+
+    /// Split the line string wherever it crosses the antimeridian.
+    ///
+    /// Segments more than 180° of longitude apart are taken to cross the
+    /// date line. Each crossing ends one part at ±180° and starts the next
+    /// on the opposite side, at the latitude interpolated along the
+    /// segment. A line with no crossings is returned whole.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, LineString};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let flight = LineString::new(vec![
+    ///     Coordinate::new(170.0, 10.0),
+    ///     Coordinate::new(-170.0, 20.0),
+    /// ])?;
+    ///
+    /// let parts = flight.split_at_antimeridian();
+    /// assert_eq!(parts.len(), 2);
+    /// assert_eq!(parts[0].coords()[1], Coordinate::new(180.0, 15.0));
+    /// assert_eq!(parts[1].coords()[0], Coordinate::new(-180.0, 15.0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn split_at_antimeridian(&self) -> Vec<LineString> {
+        let mut parts = Vec::new();
+        let mut current = vec![self.coords[0]];
+
+        for pair in self.coords.windows(2) {
+            let (a, b) = (pair[0].to_point(), pair[1].to_point());
+            if let Some((_, lat)) = Point::antimeridian_crossing(&a, &b) {
+                let boundary = if a.lon >= 0.0 { 180.0 } else { -180.0 };
+                push_distinct(&mut current, Coordinate::new(boundary, lat));
+                parts.push(std::mem::take(&mut current));
+                current.push(Coordinate::new(-boundary, lat));
+            }
+            push_distinct(&mut current, pair[1]);
+        }
+        parts.push(current);
+
+        // Parts that collapsed to a single vertex on the boundary are dropped
+        parts
+            .into_iter()
+            .filter_map(|coords| LineString::new(coords).ok())
+            .collect()
+    }
+
+    /// Find the closest point on the line string to a coordinate.
+    ///
+    /// Each segment is projected onto with the perpendicular foot clamped to
+    /// the segment's endpoints. Distances are Cartesian, in coordinate units.
+    ///
+    /// # Returns
+    ///
+    /// The snapped coordinate, its distance from `point`, and the index of
+    /// the segment it lies on (segment `i` joins vertices `i` and `i + 1`).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, LineString};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let line = LineString::new(vec![
+    ///     Coordinate::new(0.0, 0.0),
+    ///     Coordinate::new(10.0, 0.0),
+    ///     Coordinate::new(10.0, 10.0),
+    /// ])?;
+    ///
+    /// let (snapped, distance, segment) = line.nearest_point_on_line(&Coordinate::new(4.0, 3.0));
+    /// assert_eq!(snapped, Coordinate::new(4.0, 0.0));
+    /// assert_eq!(distance, 3.0);
+    /// assert_eq!(segment, 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn nearest_point_on_line(&self, point: &Coordinate) -> (Coordinate, f64, usize) {
+        self.nearest_by(point, 1.0, cartesian_distance)
+    }
+
+    /// Find the closest point on the line string to a geographic point.
+    ///
+    /// Segments are projected onto in a local equirectangular frame centered
+    /// on `point`, and candidates are compared by Haversine distance.
+    ///
+    /// # Returns
+    ///
+    /// The snapped point, its distance from `point` in meters, and the index
+    /// of the segment it lies on.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, LineString, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let route = LineString::new(vec![
+    ///     Coordinate::new(-74.00, 40.70),
+    ///     Coordinate::new(-73.98, 40.70),
+    /// ])?;
+    ///
+    /// let gps_fix = Point::new(40.701, -73.99);
+    /// let (snapped, distance_m, segment) = route.nearest_geographic_point(&gps_fix);
+    /// assert!((snapped.lon - -73.99).abs() < 1e-9);
+    /// assert!((distance_m - 111.2).abs() < 1.0);
+    /// assert_eq!(segment, 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn nearest_geographic_point(&self, point: &Point) -> (Point, f64, usize) {
+        let origin = Coordinate::from_point(point);
+        let lon_scale = point.lat.to_radians().cos();
+        let (snapped, distance, segment) = self.nearest_by(&origin, lon_scale, haversine_distance);
+        (snapped.to_point(), distance, segment)
+    }
+
+    /// Project `point` onto each segment and keep the closest foot.
+    ///
+    /// `x_scale` stretches the x axis during projection, which lets the
+    /// geographic variant account for converging meridians.
+    fn nearest_by<F>(
+        &self,
+        point: &Coordinate,
+        x_scale: f64,
+        distance: F,
+    ) -> (Coordinate, f64, usize)
+    where
+        F: Fn(&Coordinate, &Coordinate) -> f64,
+    {
+        let mut best = (self.coords[0], f64::INFINITY, 0);
+
+        for (i, w) in self.coords.windows(2).enumerate() {
+            let (a, b) = (w[0], w[1]);
+            let dx = (b.x - a.x) * x_scale;
+            let dy = b.y - a.y;
+            let len_sq = dx * dx + dy * dy;
+
+            let t = if len_sq > 0.0 {
+                (((point.x - a.x) * x_scale * dx + (point.y - a.y) * dy) / len_sq).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+
+            let foot = Coordinate::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t);
+            let d = distance(point, &foot);
+            if d < best.1 {
+                best = (foot, d, i);
+            }
+        }
+
+        best
+    }
+
+    /// Simplify the line string using the Visvalingam-Whyatt algorithm.
+    ///
+    /// Vertices whose effective triangle area is below `tolerance_m2` are
+    /// removed. The first and last vertices are always preserved.
+    ///
+    /// # Arguments
+    ///
+    /// * `tolerance_m2` - Minimum triangle area in square meters
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tolerance is negative or not finite.
+    pub fn simplify(&self, tolerance_m2: f64) -> Result<LineString> {
+        let tolerance = tolerance_to_degrees(tolerance_m2, &self.bounds())?;
+        LineString::new(visvalingam(&self.coords, tolerance, 2))
+    }
+}
+
+/// A closed ring of coordinates.
+///
+/// The first and last coordinates of a ring are always equal. Rings are
+/// closed automatically on construction if needed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LinearRing {
+    coords: Vec<Coordinate>,
+}
+
+impl LinearRing {
+    /// Create a new ring, closing it if the first and last coordinates differ.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InsufficientPoints` if the ring has fewer than
+    /// three distinct vertices.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, LinearRing};
+    ///
+    /// let ring = LinearRing::new(vec![
+    ///     Coordinate::new(0.0, 0.0),
+    ///     Coordinate::new(1.0, 0.0),
+    ///     Coordinate::new(1.0, 1.0),
+    /// ])
+    /// .unwrap();
+    ///
+    /// // The ring is closed automatically
+    /// assert_eq!(ring.coords().len(), 4);
+    /// ```
+    pub fn new(mut coords: Vec<Coordinate>) -> Result<Self> {
+        if let (Some(first), Some(last)) = (coords.first(), coords.last())
+            && first != last
+        {
+            coords.push(*first);
+        }
+
+        if coords.len() < 4 {
+            return Err(SpatioError::InsufficientPoints);
+        }
+
+        Ok(Self { coords })
+    }
+
+    /// Get the coordinates of this ring, including the closing coordinate
+    pub fn coords(&self) -> &[Coordinate] {
+        &self.coords
+    }
+
+    /// Signed area of the ring in squared coordinate units.
+    ///
+    /// This is a Cartesian (shoelace) area computed directly on longitude and
+    /// latitude degrees, so it is not a physical area. It is useful for
+    /// orientation checks: the result is positive for counter-clockwise rings
+    /// and negative for clockwise rings. Use `geodesic_area_km2` for a real
+    /// surface area.
+    pub fn area(&self) -> f64 {
+        let sum: f64 = self
+            .coords
+            .windows(2)
+            .map(|w| w[0].x * w[1].y - w[1].x * w[0].y)
+            .sum();
+        sum / 2.0
+    }
+
+    /// Check whether the ring runs clockwise, based on the sign of its
+    /// shoelace area.
+    ///
+    /// Degenerate rings with zero area are not clockwise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, LinearRing};
+    ///
+    /// let ring = LinearRing::new(vec![
+    ///     Coordinate::new(0.0, 0.0),
+    ///     Coordinate::new(0.0, 1.0),
+    ///     Coordinate::new(1.0, 1.0),
+    /// ])
+    /// .unwrap();
+    /// assert!(ring.is_clockwise());
+    /// assert!(!ring.normalize_winding(false).is_clockwise());
+    /// ```
+    pub fn is_clockwise(&self) -> bool {
+        self.area() < 0.0
+    }
+
+    /// Get a copy of the ring running in the requested direction,
+    /// reversing the coordinates if needed
+    pub fn normalize_winding(&self, should_be_clockwise: bool) -> LinearRing {
+        let mut ring = self.clone();
+        if ring.is_clockwise() != should_be_clockwise {
+            ring.coords.reverse();
+        }
+        ring
+    }
+
+    /// Unsigned area enclosed by the ring on a spherical Earth, in km².
+    ///
+    /// Uses the spherical excess of the ring, treating coordinates as
+    /// longitude/latitude degrees.
+    pub fn geodesic_area_km2(&self) -> f64 {
+        let sum: f64 = self
+            .coords
+            .windows(2)
+            .map(|w| {
+                let (a, b) = (w[0], w[1]);
+                (b.x - a.x).to_radians() * (2.0 + a.y.to_radians().sin() + b.y.to_radians().sin())
+            })
+            .sum();
+        (sum * EARTH_RADIUS_KM * EARTH_RADIUS_KM / 2.0).abs()
+    }
+
+    /// Length of the ring in kilometers, summing Haversine distances
+    pub fn geodesic_length_km(&self) -> f64 {
+        geodesic_length_km(&self.coords)
+    }
+
+    /// Simplify the ring with a tolerance already expressed in degrees².
+    ///
+    /// The closing vertex is kept fixed, so the result is always closed and
+    /// retains at least three distinct vertices.
+    fn simplify_degrees(&self, tolerance_deg2: f64) -> Result<LinearRing> {
+        LinearRing::new(visvalingam(&self.coords, tolerance_deg2, 4))
+    }
+
+    /// Check whether a coordinate lies inside the ring using ray casting
+    pub fn contains_point(&self, point: &Coordinate) -> bool {
+        let mut inside = false;
+        for w in self.coords.windows(2) {
+            let (a, b) = (w[0], w[1]);
+            if (a.y > point.y) != (b.y > point.y) {
+                let x_cross = (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x;
+                if point.x < x_cross {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+
+    /// Get the bounding box of this ring
+    pub fn bounds(&self) -> BoundingBox {
+        coords_bounds(&self.coords)
+    }
+}
+
+/// A polygon with an exterior ring and optional interior holes.
+///
+/// # Examples
+///
+/// ```rust
+/// use spatio::{Coordinate, LinearRing, Polygon};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let exterior = LinearRing::new(vec![
+///     Coordinate::new(0.0, 0.0),
+///     Coordinate::new(4.0, 0.0),
+///     Coordinate::new(4.0, 4.0),
+///     Coordinate::new(0.0, 4.0),
+/// ])?;
+/// let polygon = Polygon::new(exterior, vec![]);
+///
+/// assert_eq!(polygon.area(), 16.0);
+/// assert!(polygon.contains_point(&Coordinate::new(2.0, 2.0)));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Polygon {
+    exterior: LinearRing,
+    holes: Vec<LinearRing>,
+}
+
+impl Polygon {
+    /// Create a new polygon from an exterior ring and holes
+    pub fn new(exterior: LinearRing, holes: Vec<LinearRing>) -> Self {
+        Self { exterior, holes }
+    }
+
+    /// Get the exterior ring
+    pub fn exterior(&self) -> &LinearRing {
+        &self.exterior
+    }
+
+    /// Get the interior rings (holes)
+    pub fn holes(&self) -> &[LinearRing] {
+        &self.holes
+    }
+
+    /// Cartesian area in squared coordinate units, excluding holes.
+    ///
+    /// For geographic polygons this is measured in degrees², which does not
+    /// correspond to a physical area. Use `geodesic_area_km2` instead.
+    pub fn area(&self) -> f64 {
+        let holes: f64 = self.holes.iter().map(|h| h.area().abs()).sum();
+        self.exterior.area().abs() - holes
+    }
+
+    /// Surface area of the polygon on a spherical Earth in km², excluding holes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, LinearRing, Polygon};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// // One degree square on the equator
+    /// let ring = LinearRing::new(vec![
+    ///     Coordinate::new(0.0, 0.0),
+    ///     Coordinate::new(1.0, 0.0),
+    ///     Coordinate::new(1.0, 1.0),
+    ///     Coordinate::new(0.0, 1.0),
+    /// ])?;
+    /// let polygon = Polygon::new(ring, vec![]);
+    ///
+    /// let area = polygon.geodesic_area_km2();
+    /// assert!((area - 12_363.0).abs() < 10.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn geodesic_area_km2(&self) -> f64 {
+        let holes: f64 = self.holes.iter().map(|h| h.geodesic_area_km2()).sum();
+        self.exterior.geodesic_area_km2() - holes
+    }
+
+    /// Perimeter of the exterior ring in kilometers using Haversine distances
+    pub fn geodesic_perimeter_km(&self) -> f64 {
+        self.exterior.geodesic_length_km()
+    }
+
+    /// Check whether a coordinate lies inside the polygon and outside all holes
+    pub fn contains_point(&self, point: &Coordinate) -> bool {
+        self.exterior.contains_point(point) && !self.holes.iter().any(|h| h.contains_point(point))
+    }
+
+    /// Check whether a geographic point lies inside the polygon and outside
+    /// all holes.
+    ///
+    /// Same as `contains_point` with the point's longitude as `x` and
+    /// latitude as `y`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, LinearRing, Point, Polygon};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let area = Polygon::new(
+    ///     LinearRing::new(vec![
+    ///         Coordinate::new(-74.02, 40.70),
+    ///         Coordinate::new(-73.92, 40.70),
+    ///         Coordinate::new(-73.92, 40.80),
+    ///     ])?,
+    ///     vec![],
+    /// );
+    ///
+    /// assert!(area.contains_geo_point(&Point::new(40.72, -73.94)));
+    /// assert!(!area.contains_geo_point(&Point::new(40.78, -74.00)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn contains_geo_point(&self, point: &Point) -> bool {
+        self.contains_point(&Coordinate::from_point(point))
+    }
+
+    /// Get the bounding box of the exterior ring
+    pub fn bounds(&self) -> BoundingBox {
+        self.exterior.bounds()
+    }
+
+    /// Center of mass of the polygon, excluding holes.
+    ///
+    /// Same as `centroid_weighted`.
+    pub fn centroid(&self) -> Coordinate {
+        self.centroid_weighted()
+    }
+
+    /// Center of mass of the polygon, excluding holes, from the
+    /// area-weighted formula
+    /// `Cx = (1/6A) Σ (xᵢ + xᵢ₊₁)(xᵢyᵢ₊₁ − xᵢ₊₁yᵢ)` and likewise for `Cy`.
+    ///
+    /// Unlike the mean of the vertices, this does not drift towards
+    /// densely sampled edges. Ring winding does not matter. Polygons with
+    /// zero area fall back to the mean of the exterior's distinct vertices.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, LinearRing, Polygon};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// // L shape made of a 2x1 block with a 1x1 block on top of its left end
+    /// let l_shape = Polygon::new(
+    ///     LinearRing::new(vec![
+    ///         Coordinate::new(0.0, 0.0),
+    ///         Coordinate::new(2.0, 0.0),
+    ///         Coordinate::new(2.0, 1.0),
+    ///         Coordinate::new(1.0, 1.0),
+    ///         Coordinate::new(1.0, 2.0),
+    ///         Coordinate::new(0.0, 2.0),
+    ///     ])?,
+    ///     vec![],
+    /// );
+    ///
+    /// let centroid = l_shape.centroid_weighted();
+    /// assert!((centroid.x - 5.0 / 6.0).abs() < 1e-12);
+    /// assert!((centroid.y - 5.0 / 6.0).abs() < 1e-12);
+    /// assert!(l_shape.contains_point(&centroid));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn centroid_weighted(&self) -> Coordinate {
+        // Measure from the first vertex to keep the products small
+        let exterior = self.exterior.coords();
+        let origin = exterior[0];
+
+        let (mut area, mut sum_x, mut sum_y) = ring_moments(exterior, &origin);
+        for hole in &self.holes {
+            let (hole_area, hole_x, hole_y) = ring_moments(hole.coords(), &origin);
+            area -= hole_area;
+            sum_x -= hole_x;
+            sum_y -= hole_y;
+        }
+
+        if area.abs() <= f64::EPSILON * self.exterior.area().abs().max(1.0) {
+            let distinct = &exterior[..exterior.len() - 1];
+            return Coordinate::new(
+                distinct.iter().map(|c| c.x).sum::<f64>() / distinct.len() as f64,
+                distinct.iter().map(|c| c.y).sum::<f64>() / distinct.len() as f64,
+            );
+        }
+        Coordinate::new(origin.x + sum_x / area, origin.y + sum_y / area)
+    }
+
+    /// Get a copy of the polygon with the winding GeoJSON requires: the
+    /// exterior counter-clockwise and every hole clockwise
+    pub fn normalize_winding(&self) -> Polygon {
+        Polygon::new(
+            self.exterior.normalize_winding(false),
+            self.holes
+                .iter()
+                .map(|h| h.normalize_winding(true))
+                .collect(),
+        )
+    }
+
+    /// Check that every ring is closed, has at least three distinct
+    /// vertices and winds the way GeoJSON requires.
+    ///
+    /// Rings built with `LinearRing::new` are always closed and long
+    /// enough, but deserialized rings are not checked on construction.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InsufficientPoints` for a ring with too few
+    /// vertices, and `SpatioError::Other` for an open ring, an exterior
+    /// that is not counter-clockwise or a hole that is not clockwise.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, LinearRing, Polygon};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let clockwise = LinearRing::new(vec![
+    ///     Coordinate::new(0.0, 0.0),
+    ///     Coordinate::new(0.0, 4.0),
+    ///     Coordinate::new(4.0, 4.0),
+    ///     Coordinate::new(4.0, 0.0),
+    /// ])?;
+    /// let polygon = Polygon::new(clockwise, vec![]);
+    ///
+    /// assert!(polygon.validate().is_err());
+    /// assert!(polygon.normalize_winding().validate().is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validate(&self) -> Result<()> {
+        let rings = std::iter::once(("exterior ring".to_string(), &self.exterior, false)).chain(
+            self.holes
+                .iter()
+                .enumerate()
+                .map(|(i, hole)| (format!("hole {}", i), hole, true)),
+        );
+
+        for (name, ring, should_be_clockwise) in rings {
+            let coords = ring.coords();
+            if coords.first() != coords.last() {
+                return Err(SpatioError::Other(format!("The {} is not closed", name)));
+            }
+            if coords.len() < 4 {
+                return Err(SpatioError::InsufficientPoints);
+            }
+            if ring.area() == 0.0 || ring.is_clockwise() != should_be_clockwise {
+                let direction = if should_be_clockwise {
+                    "clockwise"
+                } else {
+                    "counter-clockwise"
+                };
+                return Err(SpatioError::Other(format!(
+                    "The {} must be {}",
+                    name, direction
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Simplify the polygon using the Visvalingam-Whyatt algorithm.
+    ///
+    /// Vertices whose effective triangle area is below `tolerance_m2` are
+    /// removed from the exterior ring and each hole. The tolerance is
+    /// converted to degrees² at the latitude of the polygon's center. Rings
+    /// stay closed and keep at least three distinct vertices.
+    ///
+    /// # Arguments
+    ///
+    /// * `tolerance_m2` - Minimum triangle area in square meters
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tolerance is negative or not finite.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, LinearRing, Polygon};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let ring = LinearRing::new(vec![
+    ///     Coordinate::new(0.0, 0.0),
+    ///     Coordinate::new(0.5, 0.0001), // barely off the edge
+    ///     Coordinate::new(1.0, 0.0),
+    ///     Coordinate::new(1.0, 1.0),
+    ///     Coordinate::new(0.0, 1.0),
+    /// ])?;
+    /// let polygon = Polygon::new(ring, vec![]);
+    ///
+    /// let simplified = polygon.simplify(1_000_000.0)?;
+    /// assert_eq!(simplified.exterior().coords().len(), 5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn simplify(&self, tolerance_m2: f64) -> Result<Polygon> {
+        let tolerance = tolerance_to_degrees(tolerance_m2, &self.bounds())?;
+        let exterior = self.exterior.simplify_degrees(tolerance)?;
+        let holes = self
+            .holes
+            .iter()
+            .map(|hole| hole.simplify_degrees(tolerance))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Polygon::new(exterior, holes))
+    }
+
+    /// Compute the convex hull of a set of points.
+    ///
+    /// Uses Andrew's monotone chain algorithm, which runs in O(n log n).
+    /// The resulting exterior ring is counter-clockwise.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InsufficientPoints` if the input does not
+    /// contain at least three non-collinear points.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Point, Polygon};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let points = vec![
+    ///     Point::new(0.0, 0.0),
+    ///     Point::new(0.0, 2.0),
+    ///     Point::new(2.0, 2.0),
+    ///     Point::new(2.0, 0.0),
+    ///     Point::new(1.0, 1.0), // interior point is dropped
+    /// ];
+    ///
+    /// let hull = Polygon::convex_hull(&points)?;
+    /// assert_eq!(hull.exterior().coords().len(), 5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn convex_hull(points: &[Point]) -> Result<Polygon> {
+        let mut coords: Vec<Coordinate> = points.iter().map(Coordinate::from_point).collect();
+        coords.sort_by(|a, b| {
+            a.x.partial_cmp(&b.x)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then(a.y.partial_cmp(&b.y).unwrap_or(std::cmp::Ordering::Equal))
+        });
+        coords.dedup();
+
+        if coords.len() < 3 {
+            return Err(SpatioError::InsufficientPoints);
+        }
+
+        fn cross(o: &Coordinate, a: &Coordinate, b: &Coordinate) -> f64 {
+            (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+        }
+
+        let mut hull: Vec<Coordinate> = Vec::with_capacity(coords.len() * 2);
+
+        // Lower hull
+        for c in &coords {
+            while hull.len() >= 2 && cross(&hull[hull.len() - 2], &hull[hull.len() - 1], c) <= 0.0 {
+                hull.pop();
+            }
+            hull.push(*c);
+        }
+
+        // Upper hull
+        let lower_len = hull.len() + 1;
+        for c in coords.iter().rev().skip(1) {
+            while hull.len() >= lower_len
+                && cross(&hull[hull.len() - 2], &hull[hull.len() - 1], c) <= 0.0
+            {
+                hull.pop();
+            }
+            hull.push(*c);
+        }
+
+        // The last point equals the first, which closes the ring
+        if hull.len() < 4 {
+            return Err(SpatioError::InsufficientPoints);
+        }
+
+        Ok(Polygon::new(LinearRing::new(hull)?, Vec::new()))
+    }
+}
+
+/// Any supported geometry, including multi-geometries and collections.
+///
+/// # Examples
+///
+/// ```rust
+/// use spatio::{Coordinate, Geometry, GeometryOps, LineString};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let stops = Geometry::MultiPoint(vec![
+///     Coordinate::new(-74.0060, 40.7128),
+///     Coordinate::new(-73.9857, 40.7484),
+/// ]);
+/// assert_eq!(
+///     stops.to_wkt(),
+///     "MULTIPOINT ((-74.006 40.7128), (-73.9857 40.7484))"
+/// );
+///
+/// let route = Geometry::LineString(LineString::new(vec![
+///     Coordinate::new(-74.0060, 40.7128),
+///     Coordinate::new(-73.9857, 40.7484),
+/// ])?);
+/// assert!(route.intersects(&stops));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Geometry {
+    /// A single coordinate
+    Point(Coordinate),
+    /// A single line string
+    LineString(LineString),
+    /// A single polygon
+    Polygon(Polygon),
+    /// A set of coordinates
+    MultiPoint(Vec<Coordinate>),
+    /// A set of line strings, such as a road network
+    MultiLineString(Vec<LineString>),
+    /// A set of polygons, such as an administrative boundary with islands
+    MultiPolygon(Vec<Polygon>),
+    /// A heterogeneous collection of geometries
+    GeometryCollection(Vec<Geometry>),
+}
+
+/// Spatial predicates and overlay operations shared by geometry types
+pub trait GeometryOps {
+    /// Check whether two geometries share at least one point.
+    ///
+    /// Boundaries count, so geometries that only touch intersect.
+    fn intersects(&self, other: &Self) -> bool;
+
+    /// Compute the area shared by two convex polygons.
+    ///
+    /// Uses Sutherland-Hodgman clipping, which is only correct for convex
+    /// input. Returns `None` if either polygon is concave (its exterior
+    /// turns both ways, or winds around more than once), has holes, or if
+    /// the polygons share no area; polygons that only touch along an edge
+    /// or at a corner have no intersection.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, Geometry, GeometryOps, LinearRing, Polygon};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let rect = |x0: f64, y0: f64, x1: f64, y1: f64| -> spatio::Result<Polygon> {
+    ///     let ring = LinearRing::new(vec![
+    ///         Coordinate::new(x0, y0),
+    ///         Coordinate::new(x1, y0),
+    ///         Coordinate::new(x1, y1),
+    ///         Coordinate::new(x0, y1),
+    ///     ])?;
+    ///     Ok(Polygon::new(ring, vec![]))
+    /// };
+    ///
+    /// let shared = Geometry::intersection(&rect(0.0, 0.0, 2.0, 2.0)?, &rect(1.0, 1.0, 3.0, 3.0)?);
+    /// assert_eq!(shared.unwrap().area(), 1.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn intersection(a: &Polygon, b: &Polygon) -> Option<Polygon>
+    where
+        Self: Sized,
+    {
+        let subject = convex_ring(a)?;
+        let clip = convex_ring(b)?;
+        ring_polygon(clip_convex(subject, &clip))
+    }
+
+    /// Compute the area covered by either of two overlapping convex
+    /// polygons.
+    ///
+    /// The result is a single polygon, which need not be convex. Returns
+    /// `None` under the same conditions as `intersection`: concave input,
+    /// holes, or polygons that share no area, whose union would not be a
+    /// single polygon.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, Geometry, GeometryOps, LinearRing, Polygon};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let rect = |x0: f64, y0: f64, x1: f64, y1: f64| -> spatio::Result<Polygon> {
+    ///     let ring = LinearRing::new(vec![
+    ///         Coordinate::new(x0, y0),
+    ///         Coordinate::new(x1, y0),
+    ///         Coordinate::new(x1, y1),
+    ///         Coordinate::new(x0, y1),
+    ///     ])?;
+    ///     Ok(Polygon::new(ring, vec![]))
+    /// };
+    ///
+    /// let merged = Geometry::union(&rect(0.0, 0.0, 2.0, 2.0)?, &rect(1.0, 1.0, 3.0, 3.0)?);
+    /// assert_eq!(merged.unwrap().area(), 7.0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn union(a: &Polygon, b: &Polygon) -> Option<Polygon>
+    where
+        Self: Sized,
+    {
+        let ring_a = convex_ring(a)?;
+        let ring_b = convex_ring(b)?;
+        let shared = clip_convex(ring_a.clone(), &ring_b);
+        ring_polygon(convex_union(&ring_a, &ring_b, &shared)?)
+    }
+}
+
+// WKB geometry type codes
+const WKB_POINT: u32 = 1;
+const WKB_LINESTRING: u32 = 2;
+const WKB_POLYGON: u32 = 3;
+const WKB_MULTIPOINT: u32 = 4;
+const WKB_MULTILINESTRING: u32 = 5;
+const WKB_MULTIPOLYGON: u32 = 6;
+const WKB_GEOMETRYCOLLECTION: u32 = 7;
+
+/// Deepest geometry nesting `Geometry::from_bytes` accepts, so hostile
+/// input cannot exhaust the stack
+const MAX_WKB_DEPTH: usize = 64;
+
+impl Geometry {
+    /// Get the geometry type name as used by WKT and GeoJSON
+    pub fn geometry_type(&self) -> &'static str {
+        match self {
+            Geometry::Point(_) => "Point",
+            Geometry::LineString(_) => "LineString",
+            Geometry::Polygon(_) => "Polygon",
+            Geometry::MultiPoint(_) => "MultiPoint",
+            Geometry::MultiLineString(_) => "MultiLineString",
+            Geometry::MultiPolygon(_) => "MultiPolygon",
+            Geometry::GeometryCollection(_) => "GeometryCollection",
+        }
+    }
+
+    /// Get the bounding box of the geometry, or `None` if it is empty
+    pub fn bounds(&self) -> Option<BoundingBox> {
+        match self {
+            Geometry::Point(c) => Some(BoundingBox::new(c.y, c.x, c.y, c.x)),
+            Geometry::LineString(line) => Some(line.bounds()),
+            Geometry::Polygon(polygon) => Some(polygon.bounds()),
+            Geometry::MultiPoint(coords) if coords.is_empty() => None,
+            Geometry::MultiPoint(coords) => Some(coords_bounds(coords)),
+            Geometry::MultiLineString(lines) => lines
+                .iter()
+                .map(LineString::bounds)
+                .reduce(|a, b| merge_bounds(&a, &b)),
+            Geometry::MultiPolygon(polygons) => polygons
+                .iter()
+                .map(Polygon::bounds)
+                .reduce(|a, b| merge_bounds(&a, &b)),
+            Geometry::GeometryCollection(geometries) => geometries
+                .iter()
+                .filter_map(Geometry::bounds)
+                .reduce(|a, b| merge_bounds(&a, &b)),
+        }
+    }
+
+    /// Cartesian area in squared coordinate units.
+    ///
+    /// Only polygonal parts contribute; points and lines have zero area.
+    pub fn area(&self) -> f64 {
+        match self {
+            Geometry::Polygon(polygon) => polygon.area(),
+            Geometry::MultiPolygon(polygons) => polygons.iter().map(Polygon::area).sum(),
+            Geometry::GeometryCollection(geometries) => geometries.iter().map(Geometry::area).sum(),
+            _ => 0.0,
+        }
+    }
+
+    /// Cartesian length in coordinate units.
+    ///
+    /// Only linear parts contribute; points and polygons have zero length.
+    pub fn length(&self) -> f64 {
+        match self {
+            Geometry::LineString(line) => line.length(),
+            Geometry::MultiLineString(lines) => lines.iter().map(LineString::length).sum(),
+            Geometry::GeometryCollection(geometries) => {
+                geometries.iter().map(Geometry::length).sum()
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// Check whether a coordinate lies inside any polygonal part of the geometry.
+    ///
+    /// Points and lines contain a coordinate only if it lies exactly on them.
+    pub fn contains_point(&self, point: &Coordinate) -> bool {
+        self.parts().iter().any(|part| match part {
+            Part::Point(c) => c == point,
+            Part::Line(coords) => line_covers(coords, point),
+            Part::Polygon(polygon) => polygon.contains_point(point),
+        })
+    }
+
+    /// Convert the geometry to Well-Known Text
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, Geometry};
+    ///
+    /// let point = Geometry::Point(Coordinate::new(-74.0060, 40.7128));
+    /// assert_eq!(point.to_wkt(), "POINT (-74.006 40.7128)");
+    /// ```
+    pub fn to_wkt(&self) -> String {
+        let name = self.geometry_type().to_uppercase();
+        match self.wkt_body() {
+            Some(body) => format!("{} {}", name, body),
+            None => format!("{} EMPTY", name),
+        }
+    }
+
+    /// WKT text after the type name, or `None` for empty geometries
+    fn wkt_body(&self) -> Option<String> {
+        fn coord(c: &Coordinate) -> String {
+            format!("{} {}", c.x, c.y)
+        }
+        fn coords(cs: &[Coordinate]) -> String {
+            format!("({})", cs.iter().map(coord).collect::<Vec<_>>().join(", "))
+        }
+        fn polygon(p: &Polygon) -> String {
+            let rings: Vec<String> = std::iter::once(p.exterior())
+                .chain(p.holes())
+                .map(|ring| coords(ring.coords()))
+                .collect();
+            format!("({})", rings.join(", "))
+        }
+        fn join(items: Vec<String>) -> Option<String> {
+            if items.is_empty() {
+                None
+            } else {
+                Some(format!("({})", items.join(", ")))
+            }
+        }
+
+        match self {
+            Geometry::Point(c) => Some(format!("({})", coord(c))),
+            Geometry::LineString(line) => Some(coords(line.coords())),
+            Geometry::Polygon(p) => Some(polygon(p)),
+            Geometry::MultiPoint(cs) => {
+                join(cs.iter().map(|c| format!("({})", coord(c))).collect())
+            }
+            Geometry::MultiLineString(lines) => {
+                join(lines.iter().map(|l| coords(l.coords())).collect())
+            }
+            Geometry::MultiPolygon(polygons) => join(polygons.iter().map(polygon).collect()),
+            Geometry::GeometryCollection(geometries) => {
+                join(geometries.iter().map(Geometry::to_wkt).collect())
+            }
+        }
+    }
+
+    /// Convert the geometry to a GeoJSON geometry object
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, Geometry};
+    ///
+    /// let point = Geometry::Point(Coordinate::new(-74.0060, 40.7128));
+    /// let geojson = point.to_geojson().unwrap();
+    /// assert_eq!(geojson, r#"{"coordinates":[-74.006,40.7128],"type":"Point"}"#);
+    /// ```
+    #[cfg(feature = "geojson")]
+    pub fn to_geojson(&self) -> Result<String> {
+        serde_json::to_string(&self.to_geojson_value())
+            .map_err(|e| SpatioError::SerializationErrorWithContext(e.to_string()))
+    }
+
+    #[cfg(feature = "geojson")]
+    pub(crate) fn to_geojson_value(&self) -> Value {
+        fn coord(c: &Coordinate) -> Value {
+            json!([c.x, c.y])
+        }
+        fn coords(cs: &[Coordinate]) -> Value {
+            Value::Array(cs.iter().map(coord).collect())
+        }
+        fn polygon(p: &Polygon) -> Value {
+            Value::Array(
+                std::iter::once(p.exterior())
+                    .chain(p.holes())
+                    .map(|ring| coords(ring.coords()))
+                    .collect(),
+            )
+        }
+
+        let type_name = self.geometry_type();
+        match self {
+            Geometry::GeometryCollection(geometries) => json!({
+                "type": type_name,
+                "geometries": geometries.iter().map(Geometry::to_geojson_value).collect::<Vec<_>>(),
+            }),
+            _ => {
+                let coordinates = match self {
+                    Geometry::Point(c) => coord(c),
+                    Geometry::LineString(line) => coords(line.coords()),
+                    Geometry::Polygon(p) => polygon(p),
+                    Geometry::MultiPoint(cs) => coords(cs),
+                    Geometry::MultiLineString(lines) => {
+                        Value::Array(lines.iter().map(|l| coords(l.coords())).collect())
+                    }
+                    Geometry::MultiPolygon(polygons) => {
+                        Value::Array(polygons.iter().map(polygon).collect())
+                    }
+                    Geometry::GeometryCollection(_) => unreachable!(),
+                };
+                json!({ "type": type_name, "coordinates": coordinates })
+            }
+        }
+    }
+
+    /// Parse a geometry from a GeoJSON geometry object
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Geometry;
+    ///
+    /// let geojson = r#"{"type":"MultiPoint","coordinates":[[-74.0,40.7],[-73.9,40.8]]}"#;
+    /// let geometry = Geometry::from_geojson(geojson).unwrap();
+    /// assert_eq!(geometry.geometry_type(), "MultiPoint");
+    /// ```
+    #[cfg(feature = "geojson")]
+    pub fn from_geojson(geojson: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(geojson)
+            .map_err(|e| SpatioError::SerializationErrorWithContext(e.to_string()))?;
+        Self::from_geojson_value(&value)
+    }
+
+    #[cfg(feature = "geojson")]
+    pub(crate) fn from_geojson_value(value: &Value) -> Result<Self> {
+        fn coord(v: &Value) -> Result<Coordinate> {
+            match v.as_array().map(|a| a.as_slice()) {
+                Some([x, y, ..]) => match (x.as_f64(), y.as_f64()) {
+                    (Some(x), Some(y)) => Ok(Coordinate::new(x, y)),
+                    _ => Err(SpatioError::Other(
+                        "GeoJSON position must contain numbers".into(),
+                    )),
+                },
+                _ => Err(SpatioError::Other(
+                    "GeoJSON position must have at least 2 elements".into(),
+                )),
+            }
+        }
+        fn array(v: &Value) -> Result<&Vec<Value>> {
+            v.as_array()
+                .ok_or_else(|| SpatioError::Other("GeoJSON coordinates must be arrays".into()))
+        }
+        fn coords(v: &Value) -> Result<Vec<Coordinate>> {
+            array(v)?.iter().map(coord).collect()
+        }
+        fn polygon(v: &Value) -> Result<Polygon> {
+            let mut rings = array(v)?
+                .iter()
+                .map(|ring| LinearRing::new(coords(ring)?))
+                .collect::<Result<Vec<_>>>()?;
+            if rings.is_empty() {
+                return Err(SpatioError::InsufficientPoints);
+            }
+            let exterior = rings.remove(0);
+            Ok(Polygon::new(exterior, rings))
+        }
+
+        let object = value
+            .as_object()
+            .ok_or_else(|| SpatioError::Other("GeoJSON must be an object".into()))?;
+        let type_field = object
+            .get("type")
+            .and_then(|v| v.as_str())
+            .ok_or_else(|| SpatioError::Other("GeoJSON must have a 'type' field".into()))?;
+
+        if type_field == "GeometryCollection" {
+            let geometries = object
+                .get("geometries")
+                .and_then(|v| v.as_array())
+                .ok_or_else(|| {
+                    SpatioError::Other("GeometryCollection must have a geometries array".into())
+                })?;
+            return Ok(Geometry::GeometryCollection(
+                geometries
+                    .iter()
+                    .map(Self::from_geojson_value)
+                    .collect::<Result<_>>()?,
+            ));
+        }
+
+        let coordinates = object.get("coordinates").ok_or_else(|| {
+            SpatioError::Other("GeoJSON geometry must have a coordinates field".into())
+        })?;
+
+        match type_field {
+            "Point" => Ok(Geometry::Point(coord(coordinates)?)),
+            "LineString" => Ok(Geometry::LineString(LineString::new(coords(coordinates)?)?)),
+            "Polygon" => Ok(Geometry::Polygon(polygon(coordinates)?)),
+            "MultiPoint" => Ok(Geometry::MultiPoint(coords(coordinates)?)),
+            "MultiLineString" => Ok(Geometry::MultiLineString(
+                array(coordinates)?
+                    .iter()
+                    .map(|line| LineString::new(coords(line)?))
+                    .collect::<Result<_>>()?,
+            )),
+            "MultiPolygon" => Ok(Geometry::MultiPolygon(
+                array(coordinates)?
+                    .iter()
+                    .map(polygon)
+                    .collect::<Result<_>>()?,
+            )),
+            other => Err(SpatioError::Other(format!(
+                "Unsupported GeoJSON geometry type: {}",
+                other
+            ))),
+        }
+    }
+
+    /// Encode the geometry as little-endian Well-Known Binary
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, Geometry};
+    ///
+    /// let point = Geometry::Point(Coordinate::new(1.0, 2.0));
+    /// let bytes = point.to_bytes();
+    /// assert_eq!(bytes.len(), 21);
+    /// assert_eq!(Geometry::from_bytes(&bytes).unwrap(), point);
+    /// ```
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut buf = Vec::new();
+        self.write_wkb(&mut buf);
+        buf
+    }
+
+    fn write_wkb(&self, buf: &mut Vec<u8>) {
+        fn coord(buf: &mut Vec<u8>, c: &Coordinate) {
+            buf.extend_from_slice(&c.x.to_le_bytes());
+            buf.extend_from_slice(&c.y.to_le_bytes());
+        }
+        fn coords(buf: &mut Vec<u8>, cs: &[Coordinate]) {
+            buf.extend_from_slice(&(cs.len() as u32).to_le_bytes());
+            cs.iter().for_each(|c| coord(buf, c));
+        }
+        fn header(buf: &mut Vec<u8>, geometry_type: u32) {
+            buf.push(1);
+            buf.extend_from_slice(&geometry_type.to_le_bytes());
+        }
+        fn count(buf: &mut Vec<u8>, n: usize) {
+            buf.extend_from_slice(&(n as u32).to_le_bytes());
+        }
+        fn polygon(buf: &mut Vec<u8>, p: &Polygon) {
+            header(buf, WKB_POLYGON);
+            count(buf, 1 + p.holes().len());
+            coords(buf, p.exterior().coords());
+            p.holes().iter().for_each(|h| coords(buf, h.coords()));
+        }
+
+        match self {
+            Geometry::Point(c) => {
+                header(buf, WKB_POINT);
+                coord(buf, c);
+            }
+            Geometry::LineString(line) => {
+                header(buf, WKB_LINESTRING);
+                coords(buf, line.coords());
+            }
+            Geometry::Polygon(p) => polygon(buf, p),
+            Geometry::MultiPoint(cs) => {
+                header(buf, WKB_MULTIPOINT);
+                count(buf, cs.len());
+                cs.iter().for_each(|c| Geometry::Point(*c).write_wkb(buf));
+            }
+            Geometry::MultiLineString(lines) => {
+                header(buf, WKB_MULTILINESTRING);
+                count(buf, lines.len());
+                for line in lines {
+                    header(buf, WKB_LINESTRING);
+                    coords(buf, line.coords());
+                }
+            }
+            Geometry::MultiPolygon(polygons) => {
+                header(buf, WKB_MULTIPOLYGON);
+                count(buf, polygons.len());
+                polygons.iter().for_each(|p| polygon(buf, p));
+            }
+            Geometry::GeometryCollection(geometries) => {
+                header(buf, WKB_GEOMETRYCOLLECTION);
+                count(buf, geometries.len());
+                geometries.iter().for_each(|g| g.write_wkb(buf));
+            }
+        }
+    }
+
+    /// Decode a geometry from Well-Known Binary in either byte order
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::UnexpectedEof` if the input is truncated and
+    /// `SpatioError::InvalidFormat` if it is not valid WKB or nests
+    /// geometry collections more than 64 deep.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = WkbReader {
+            data: bytes,
+            pos: 0,
+            little_endian: true,
+        };
+        let geometry = reader.read_geometry(0)?;
+        if reader.pos != bytes.len() {
+            return Err(SpatioError::InvalidFormat);
+        }
+        Ok(geometry)
+    }
+
+    /// Break the geometry into its primitive parts
+    fn parts(&self) -> Vec<Part<'_>> {
+        let mut parts = Vec::new();
+        self.collect_parts(&mut parts);
+        parts
+    }
+
+    fn collect_parts<'a>(&'a self, parts: &mut Vec<Part<'a>>) {
+        match self {
+            Geometry::Point(c) => parts.push(Part::Point(*c)),
+            Geometry::LineString(line) => parts.push(Part::Line(line.coords())),
+            Geometry::Polygon(p) => parts.push(Part::Polygon(p)),
+            Geometry::MultiPoint(cs) => parts.extend(cs.iter().map(|c| Part::Point(*c))),
+            Geometry::MultiLineString(lines) => {
+                parts.extend(lines.iter().map(|l| Part::Line(l.coords())))
+            }
+            Geometry::MultiPolygon(polygons) => parts.extend(polygons.iter().map(Part::Polygon)),
+            Geometry::GeometryCollection(geometries) => {
+                geometries.iter().for_each(|g| g.collect_parts(parts))
+            }
+        }
+    }
+}
+
+impl GeometryOps for Geometry {
+    fn intersects(&self, other: &Self) -> bool {
+        match (self.bounds(), other.bounds()) {
+            (Some(a), Some(b)) if a.intersects(&b) => {}
+            _ => return false,
+        }
+
+        let theirs = other.parts();
+        self.parts()
+            .iter()
+            .any(|a| theirs.iter().any(|b| a.intersects(b)))
+    }
+}
+
+/// A primitive component of a geometry used by spatial predicates
+enum Part<'a> {
+    Point(Coordinate),
+    Line(&'a [Coordinate]),
+    Polygon(&'a Polygon),
+}
+
+impl Part<'_> {
+    fn intersects(&self, other: &Part<'_>) -> bool {
+        match (self, other) {
+            (Part::Point(a), Part::Point(b)) => a == b,
+            (Part::Point(p), Part::Line(l)) | (Part::Line(l), Part::Point(p)) => line_covers(l, p),
+            (Part::Point(p), Part::Polygon(poly)) | (Part::Polygon(poly), Part::Point(p)) => {
+                polygon_covers(poly, p)
+            }
+            (Part::Line(a), Part::Line(b)) => lines_cross(a, b),
+            (Part::Line(l), Part::Polygon(poly)) | (Part::Polygon(poly), Part::Line(l)) => {
+                polygon_covers(poly, &l[0]) || polygon_rings(poly).any(|r| lines_cross(l, r))
+            }
+            (Part::Polygon(a), Part::Polygon(b)) => {
+                polygon_covers(a, &b.exterior().coords()[0])
+                    || polygon_covers(b, &a.exterior().coords()[0])
+                    || polygon_rings(a).any(|ra| polygon_rings(b).any(|rb| lines_cross(ra, rb)))
+            }
+        }
+    }
+}
+
+fn polygon_rings(polygon: &Polygon) -> impl Iterator<Item = &[Coordinate]> {
+    std::iter::once(polygon.exterior())
+        .chain(polygon.holes())
+        .map(LinearRing::coords)
+}
+
+/// Whether a point lies inside the polygon or on any of its rings
+fn polygon_covers(polygon: &Polygon, point: &Coordinate) -> bool {
+    polygon.contains_point(point) || polygon_rings(polygon).any(|r| line_covers(r, point))
+}
+
+fn line_covers(line: &[Coordinate], point: &Coordinate) -> bool {
+    line.windows(2).any(|w| on_segment(&w[0], &w[1], point))
+}
+
+fn lines_cross(a: &[Coordinate], b: &[Coordinate]) -> bool {
+    a.windows(2).any(|sa| {
+        b.windows(2)
+            .any(|sb| segments_intersect(&sa[0], &sa[1], &sb[0], &sb[1]))
+    })
+}
+
+/// Twice the signed area of the triangle `a`, `b`, `c`
+fn orientation(a: &Coordinate, b: &Coordinate, c: &Coordinate) -> f64 {
+    (b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+fn on_segment(a: &Coordinate, b: &Coordinate, p: &Coordinate) -> bool {
+    orientation(a, b, p) == 0.0
+        && p.x >= a.x.min(b.x)
+        && p.x <= a.x.max(b.x)
+        && p.y >= a.y.min(b.y)
+        && p.y <= a.y.max(b.y)
+}
+
+fn segments_intersect(p1: &Coordinate, p2: &Coordinate, q1: &Coordinate, q2: &Coordinate) -> bool {
+    let d1 = orientation(q1, q2, p1);
+    let d2 = orientation(q1, q2, p2);
+    let d3 = orientation(p1, p2, q1);
+    let d4 = orientation(p1, p2, q2);
+
+    if d1 * d2 < 0.0 && d3 * d4 < 0.0 {
+        return true;
+    }
+
+    on_segment(q1, q2, p1)
+        || on_segment(q1, q2, p2)
+        || on_segment(p1, p2, q1)
+        || on_segment(p1, p2, q2)
+}
+
+/// Tolerance on segment parameters and coordinates when intersecting
+/// line strings
+const CROSSING_EPSILON: f64 = 1e-12;
+
+/// Parameters along `p1`-`p2` where it meets `q1`-`q2`: none, the single
+/// crossing, or both ends of the stretch shared by collinear segments.
+fn segment_crossings(
+    p1: &Coordinate,
+    p2: &Coordinate,
+    q1: &Coordinate,
+    q2: &Coordinate,
+) -> Vec<f64> {
+    let (rx, ry) = (p2.x - p1.x, p2.y - p1.y);
+    let (sx, sy) = (q2.x - q1.x, q2.y - q1.y);
+    let (qx, qy) = (q1.x - p1.x, q1.y - p1.y);
+    let r_len = rx.hypot(ry);
+    let s_len = sx.hypot(sy);
+    if r_len == 0.0 || s_len == 0.0 {
+        return Vec::new();
+    }
+
+    let within = |t: f64| (-CROSSING_EPSILON..=1.0 + CROSSING_EPSILON).contains(&t);
+    let denom = rx * sy - ry * sx;
+
+    if denom.abs() > CROSSING_EPSILON * r_len * s_len {
+        let t = (qx * sy - qy * sx) / denom;
+        let u = (qx * ry - qy * rx) / denom;
+        return if within(t) && within(u) {
+            vec![t.clamp(0.0, 1.0)]
+        } else {
+            Vec::new()
+        };
+    }
+
+    // Parallel: only collinear segments can meet
+    if (qx * ry - qy * rx).abs() > CROSSING_EPSILON * r_len * qx.hypot(qy).max(r_len) {
+        return Vec::new();
+    }
+    let r_len2 = r_len * r_len;
+    let t0 = (qx * rx + qy * ry) / r_len2;
+    let t1 = t0 + (sx * rx + sy * ry) / r_len2;
+    let (start, end) = (t0.min(t1).max(0.0), t0.max(t1).min(1.0));
+    if start > end + CROSSING_EPSILON {
+        Vec::new()
+    } else if end - start <= CROSSING_EPSILON {
+        vec![start]
+    } else {
+        vec![start, end]
+    }
+}
+
+/// Tolerance for treating clipped coordinates and areas as zero
+const CLIP_EPSILON: f64 = 1e-12;
+
+/// Exterior of a convex polygon without holes, counter-clockwise and
+/// without the closing coordinate.
+///
+/// Returns `None` for concave rings: a convex ring never turns both ways
+/// and its turning angles add up to a winding number of exactly one.
+fn convex_ring(polygon: &Polygon) -> Option<Vec<Coordinate>> {
+    if !polygon.holes().is_empty() {
+        return None;
+    }
+
+    let mut coords = polygon.exterior().coords().to_vec();
+    coords.pop();
+    coords.dedup();
+    if coords.len() < 3 {
+        return None;
+    }
+
+    let n = coords.len();
+    let mut turn_sign = 0.0;
+    let mut turning = 0.0;
+    for i in 0..n {
+        let (a, b, c) = (&coords[i], &coords[(i + 1) % n], &coords[(i + 2) % n]);
+        let cross = orientation(a, b, c);
+        let dot = (b.x - a.x) * (c.x - b.x) + (b.y - a.y) * (c.y - b.y);
+        if cross != 0.0 {
+            if cross * turn_sign < 0.0 {
+                return None;
+            }
+            turn_sign = cross.signum();
+        }
+        turning += cross.atan2(dot);
+    }
+
+    let winding = (turning / std::f64::consts::TAU).round();
+    if turn_sign == 0.0 || winding.abs() != 1.0 {
+        return None;
+    }
+
+    if turn_sign < 0.0 {
+        coords.reverse();
+    }
+    Some(coords)
+}
+
+/// Clip a ring against a convex counter-clockwise ring (Sutherland-Hodgman)
+fn clip_convex(subject: Vec<Coordinate>, clip: &[Coordinate]) -> Vec<Coordinate> {
+    let mut output = subject;
+    for (i, edge_start) in clip.iter().enumerate() {
+        let edge_end = &clip[(i + 1) % clip.len()];
+        let input = std::mem::take(&mut output);
+        let Some(mut prev) = input.last() else {
+            break;
+        };
+
+        for current in &input {
+            let side_current = orientation(edge_start, edge_end, current);
+            let side_prev = orientation(edge_start, edge_end, prev);
+            if side_current >= 0.0 {
+                if side_prev < 0.0 {
+                    output.push(line_crossing(prev, current, side_prev, side_current));
+                }
+                output.push(*current);
+            } else if side_prev >= 0.0 {
+                output.push(line_crossing(prev, current, side_prev, side_current));
+            }
+            prev = current;
+        }
+    }
+    output
+}
+
+/// Point where the segment from `a` to `b` crosses a line, given the
+/// signed distances of its ends from the line
+fn line_crossing(a: &Coordinate, b: &Coordinate, side_a: f64, side_b: f64) -> Coordinate {
+    let t = side_a / (side_a - side_b);
+    Coordinate::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+}
+
+/// Boundary of the union of two convex counter-clockwise rings that share
+/// the area `shared`.
+///
+/// The union of two convex shapes is star-shaped around any point they
+/// share, so its boundary is every vertex not inside the other ring plus
+/// every edge crossing, ordered by angle around the shared centroid.
+fn convex_union(
+    a: &[Coordinate],
+    b: &[Coordinate],
+    shared: &[Coordinate],
+) -> Option<Vec<Coordinate>> {
+    let shared = ring_polygon(shared.to_vec())?;
+    let shared = &shared.exterior().coords()[1..];
+    let center = Coordinate::new(
+        shared.iter().map(|c| c.x).sum::<f64>() / shared.len() as f64,
+        shared.iter().map(|c| c.y).sum::<f64>() / shared.len() as f64,
+    );
+
+    let strictly_inside = |ring: &[Coordinate], p: &Coordinate| {
+        (0..ring.len()).all(|i| orientation(&ring[i], &ring[(i + 1) % ring.len()], p) > 0.0)
+    };
+
+    let mut boundary: Vec<Coordinate> = a
+        .iter()
+        .filter(|p| !strictly_inside(b, p))
+        .chain(b.iter().filter(|p| !strictly_inside(a, p)))
+        .copied()
+        .collect();
+
+    for i in 0..a.len() {
+        let (a1, a2) = (&a[i], &a[(i + 1) % a.len()]);
+        for j in 0..b.len() {
+            let (b1, b2) = (&b[j], &b[(j + 1) % b.len()]);
+            let (d1, d2) = (orientation(b1, b2, a1), orientation(b1, b2, a2));
+            let (d3, d4) = (orientation(a1, a2, b1), orientation(a1, a2, b2));
+            if d1 * d2 < 0.0 && d3 * d4 < 0.0 {
+                boundary.push(line_crossing(a1, a2, d1, d2));
+            }
+        }
+    }
+
+    let angle = |p: &Coordinate| (p.y - center.y).atan2(p.x - center.x);
+    boundary.sort_by(|p, q| angle(p).total_cmp(&angle(q)));
+    Some(boundary)
+}
+
+/// Polygon from a ring without its closing coordinate, dropping repeated
+/// coordinates; `None` if nothing with a positive area is left
+fn ring_polygon(mut coords: Vec<Coordinate>) -> Option<Polygon> {
+    let same = |p: &Coordinate, q: &Coordinate| {
+        (p.x - q.x).abs() <= CLIP_EPSILON && (p.y - q.y).abs() <= CLIP_EPSILON
+    };
+    coords.dedup_by(|p, q| same(p, q));
+    while coords.len() > 1 && same(&coords[0], &coords[coords.len() - 1]) {
+        coords.pop();
+    }
+    if coords.len() < 3 {
+        return None;
+    }
+
+    let ring = LinearRing::new(coords).ok()?;
+    (ring.area() > CLIP_EPSILON).then(|| Polygon::new(ring, Vec::new()))
+}
+
+fn merge_bounds(a: &BoundingBox, b: &BoundingBox) -> BoundingBox {
+    BoundingBox::new(
+        a.min.lat.min(b.min.lat),
+        a.min.lon.min(b.min.lon),
+        a.max.lat.max(b.max.lat),
+        a.max.lon.max(b.max.lon),
+    )
+}
+
+/// Cursor over WKB input that tracks the byte order of the current geometry
+struct WkbReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    little_endian: bool,
+}
+
+impl WkbReader<'_> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let end = self.pos + N;
+        let bytes = self
+            .data
+            .get(self.pos..end)
+            .ok_or(SpatioError::UnexpectedEof)?;
+        self.pos = end;
+        let mut out = [0u8; N];
+        out.copy_from_slice(bytes);
+        Ok(out)
+    }
+
+    fn read_u32(&mut self) -> Result<u32> {
+        let bytes = self.take::<4>()?;
+        Ok(if self.little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn read_f64(&mut self) -> Result<f64> {
+        let bytes = self.take::<8>()?;
+        Ok(if self.little_endian {
+            f64::from_le_bytes(bytes)
+        } else {
+            f64::from_be_bytes(bytes)
+        })
+    }
+
+    fn read_coord(&mut self) -> Result<Coordinate> {
+        Ok(Coordinate::new(self.read_f64()?, self.read_f64()?))
+    }
+
+    fn read_coords(&mut self) -> Result<Vec<Coordinate>> {
+        let n = self.read_u32()?;
+        (0..n).map(|_| self.read_coord()).collect()
+    }
+
+    fn read_polygon_body(&mut self) -> Result<Polygon> {
+        let n = self.read_u32()?;
+        let mut rings = (0..n)
+            .map(|_| LinearRing::new(self.read_coords()?))
+            .collect::<Result<Vec<_>>>()?;
+        if rings.is_empty() {
+            return Err(SpatioError::InsufficientPoints);
+        }
+        let exterior = rings.remove(0);
+        Ok(Polygon::new(exterior, rings))
+    }
+
+    /// Read one geometry nested `depth` collections deep
+    fn read_geometry(&mut self, depth: usize) -> Result<Geometry> {
+        if depth >= MAX_WKB_DEPTH {
+            return Err(SpatioError::InvalidFormat);
+        }
+        self.little_endian = match self.take::<1>()?[0] {
+            0 => false,
+            1 => true,
+            _ => return Err(SpatioError::InvalidFormat),
+        };
+
+        match self.read_u32()? {
+            WKB_POINT => Ok(Geometry::Point(self.read_coord()?)),
+            WKB_LINESTRING => Ok(Geometry::LineString(LineString::new(self.read_coords()?)?)),
+            WKB_POLYGON => Ok(Geometry::Polygon(self.read_polygon_body()?)),
+            WKB_MULTIPOINT => {
+                let n = self.read_u32()?;
+                let points = (0..n)
+                    .map(|_| match self.read_geometry(depth + 1)? {
+                        Geometry::Point(c) => Ok(c),
+                        _ => Err(SpatioError::InvalidFormat),
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(Geometry::MultiPoint(points))
+            }
+            WKB_MULTILINESTRING => {
+                let n = self.read_u32()?;
+                let lines = (0..n)
+                    .map(|_| match self.read_geometry(depth + 1)? {
+                        Geometry::LineString(l) => Ok(l),
+                        _ => Err(SpatioError::InvalidFormat),
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(Geometry::MultiLineString(lines))
+            }
+            WKB_MULTIPOLYGON => {
+                let n = self.read_u32()?;
+                let polygons = (0..n)
+                    .map(|_| match self.read_geometry(depth + 1)? {
+                        Geometry::Polygon(p) => Ok(p),
+                        _ => Err(SpatioError::InvalidFormat),
+                    })
+                    .collect::<Result<_>>()?;
+                Ok(Geometry::MultiPolygon(polygons))
+            }
+            WKB_GEOMETRYCOLLECTION => {
+                let n = self.read_u32()?;
+                let geometries = (0..n)
+                    .map(|_| self.read_geometry(depth + 1))
+                    .collect::<Result<_>>()?;
+                Ok(Geometry::GeometryCollection(geometries))
+            }
+            _ => Err(SpatioError::InvalidFormat),
+        }
+    }
+}
+
+fn coords_bounds(coords: &[Coordinate]) -> BoundingBox {
+    let mut bbox = BoundingBox::new(f64::MAX, f64::MAX, f64::MIN, f64::MIN);
+    for coord in coords {
+        bbox.min.lat = bbox.min.lat.min(coord.y);
+        bbox.min.lon = bbox.min.lon.min(coord.x);
+        bbox.max.lat = bbox.max.lat.max(coord.y);
+        bbox.max.lon = bbox.max.lon.max(coord.x);
+    }
+    bbox
+}
+
+/// Unsigned area of a closed ring and its first moments, relative to
+/// `origin`. The ring's centroid is `origin + (sum_x, sum_y) / area`.
+fn ring_moments(coords: &[Coordinate], origin: &Coordinate) -> (f64, f64, f64) {
+    let (mut area, mut sum_x, mut sum_y) = (0.0, 0.0, 0.0);
+    for w in coords.windows(2) {
+        let (x0, y0) = (w[0].x - origin.x, w[0].y - origin.y);
+        let (x1, y1) = (w[1].x - origin.x, w[1].y - origin.y);
+        let cross = x0 * y1 - x1 * y0;
+        area += cross;
+        sum_x += (x0 + x1) * cross;
+        sum_y += (y0 + y1) * cross;
+    }
+
+    // Flip clockwise rings so every ring counts positively
+    let sign = if area < 0.0 { -1.0 } else { 1.0 };
+    (sign * area / 2.0, sign * sum_x / 6.0, sign * sum_y / 6.0)
+}
+
+/// Append a coordinate unless it repeats the last one
+fn push_distinct(coords: &mut Vec<Coordinate>, coord: Coordinate) {
+    if coords.last() != Some(&coord) {
+        coords.push(coord);
+    }
+}
+
+fn cartesian_distance(a: &Coordinate, b: &Coordinate) -> f64 {
+    ((a.x - b.x).powi(2) + (a.y - b.y).powi(2)).sqrt()
+}
+
+fn haversine_distance(a: &Coordinate, b: &Coordinate) -> f64 {
+    a.to_point().distance_to(&b.to_point())
+}
+
+fn geodesic_length_km(coords: &[Coordinate]) -> f64 {
+    coords
+        .windows(2)
+        .map(|w| haversine_distance(&w[0], &w[1]))
+        .sum::<f64>()
+        / 1000.0
+}
+
+/// Walk along `coords` and return the coordinate `distance` from the start.
+fn interpolate_by<F>(coords: &[Coordinate], distance: f64, metric: F) -> Coordinate
+where
+    F: Fn(&Coordinate, &Coordinate) -> f64,
+{
+    let mut remaining = distance.max(0.0);
+    for w in coords.windows(2) {
+        let (a, b) = (w[0], w[1]);
+        let segment = metric(&a, &b);
+        if remaining <= segment {
+            let t = if segment > 0.0 {
+                remaining / segment
+            } else {
+                0.0
+            };
+            return Coordinate::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t);
+        }
+        remaining -= segment;
+    }
+    coords[coords.len() - 1]
+}
+
+/// Upper bound on the coordinates a single resample may produce.
+const MAX_RESAMPLE_POINTS: usize = 1_000_000;
+
+/// Place coordinates every `interval` along `coords`, always ending on the
+/// last vertex.
+///
+/// Walks the segments once, so the cost is linear in the input plus output.
+fn resample_by<F>(coords: &[Coordinate], interval: f64, metric: F) -> Result<Vec<Coordinate>>
+where
+    F: Fn(&Coordinate, &Coordinate) -> f64,
+{
+    if !interval.is_finite() || interval <= 0.0 {
+        return Err(SpatioError::Other(
+            "Resampling interval must be a positive number".to_string(),
+        ));
+    }
+
+    let segments: Vec<f64> = coords.windows(2).map(|w| metric(&w[0], &w[1])).collect();
+    let total: f64 = segments.iter().sum();
+    let steps = (total / interval).floor();
+    if !steps.is_finite() || steps >= MAX_RESAMPLE_POINTS as f64 {
+        return Err(SpatioError::Other(format!(
+            "Resampling interval is too small: more than {} points",
+            MAX_RESAMPLE_POINTS
+        )));
+    }
+    let steps = steps as usize;
+
+    let mut result = Vec::with_capacity(steps + 2);
+    // Index of the current segment and the distance to its start vertex
+    let (mut segment, mut walked) = (0, 0.0);
+    for i in 0..=steps {
+        let target = i as f64 * interval;
+        while segment < segments.len() && target > walked + segments[segment] {
+            walked += segments[segment];
+            segment += 1;
+        }
+        let Some(&length) = segments.get(segment) else {
+            result.push(coords[coords.len() - 1]);
+            continue;
+        };
+        let (a, b) = (coords[segment], coords[segment + 1]);
+        let t = if length > 0.0 {
+            (target - walked) / length
+        } else {
+            0.0
+        };
+        result.push(Coordinate::new(
+            a.x + (b.x - a.x) * t,
+            a.y + (b.y - a.y) * t,
+        ));
+    }
+
+    let last = coords[coords.len() - 1];
+    if result.last() != Some(&last) {
+        result.push(last);
+    }
+    Ok(result)
+}
+
+/// Convert an area in m² to degrees² at the center latitude of `bounds`.
+fn tolerance_to_degrees(tolerance_m2: f64, bounds: &BoundingBox) -> Result<f64> {
+    if !tolerance_m2.is_finite() || tolerance_m2 < 0.0 {
+        return Err(SpatioError::Other(
+            "Simplification tolerance must be a non-negative number".to_string(),
+        ));
+    }
+
+    let meters_per_degree = EARTH_RADIUS_KM * 1000.0 * std::f64::consts::PI / 180.0;
+    let center_lat = ((bounds.min.lat + bounds.max.lat) / 2.0).to_radians();
+    // Guard against division by zero at the poles
+    let lon_scale = center_lat.cos().max(1e-12);
+
+    Ok(tolerance_m2 / (meters_per_degree * meters_per_degree * lon_scale))
+}
+
+/// Visvalingam-Whyatt simplification with fixed endpoints.
+///
+/// Repeatedly removes the vertex with the smallest effective area until all
+/// remaining areas reach `tolerance` or only `min_len` vertices are left.
+fn visvalingam(coords: &[Coordinate], tolerance: f64, min_len: usize) -> Vec<Coordinate> {
+    fn triangle_area(a: &Coordinate, b: &Coordinate, c: &Coordinate) -> f64 {
+        ((b.x - a.x) * (c.y - a.y) - (c.x - a.x) * (b.y - a.y)).abs() / 2.0
+    }
+
+    let n = coords.len();
+    if n <= min_len.max(2) {
+        return coords.to_vec();
+    }
+
+    let mut prev: Vec<usize> = (0..n).map(|i| i.saturating_sub(1)).collect();
+    let mut next: Vec<usize> = (0..n).map(|i| (i + 1).min(n - 1)).collect();
+    let mut areas = vec![f64::INFINITY; n];
+    for i in 1..n - 1 {
+        areas[i] = triangle_area(&coords[i - 1], &coords[i], &coords[i + 1]);
+    }
+
+    let mut remaining = n;
+    while remaining > min_len {
+        let (idx, &min_area) = match areas
+            .iter()
+            .enumerate()
+            .min_by(|a, b| a.1.partial_cmp(b.1).unwrap_or(std::cmp::Ordering::Equal))
+        {
+            Some(found) => found,
+            None => break,
+        };
+        if min_area >= tolerance {
+            break;
+        }
+
+        // Unlink the vertex and mark it as removed
+        let (p, nx) = (prev[idx], next[idx]);
+        next[p] = nx;
+        prev[nx] = p;
+        areas[idx] = f64::INFINITY;
+        remaining -= 1;
+
+        // Neighbors never drop below the area of the removed vertex, which
+        // keeps the elimination order monotonic
+        for neighbor in [p, nx] {
+            if neighbor != 0 && neighbor != n - 1 {
+                let area = triangle_area(
+                    &coords[prev[neighbor]],
+                    &coords[neighbor],
+                    &coords[next[neighbor]],
+                );
+                areas[neighbor] = area.max(min_area);
+            }
+        }
+    }
+
+    let mut result = Vec::with_capacity(remaining);
+    let mut i = 0;
+    loop {
+        result.push(coords[i]);
+        if i == n - 1 {
+            break;
+        }
+        i = next[i];
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(size: f64) -> Polygon {
+        let ring = LinearRing::new(vec![
+            Coordinate::new(0.0, 0.0),
+            Coordinate::new(size, 0.0),
+            Coordinate::new(size, size),
+            Coordinate::new(0.0, size),
+        ])
+        .unwrap();
+        Polygon::new(ring, vec![])
+    }
+
+    fn rect(x0: f64, y0: f64, x1: f64, y1: f64) -> Polygon {
+        let ring = LinearRing::new(vec![
+            Coordinate::new(x0, y0),
+            Coordinate::new(x1, y0),
+            Coordinate::new(x1, y1),
+            Coordinate::new(x0, y1),
+        ])
+        .unwrap();
+        Polygon::new(ring, vec![])
+    }
+
+    #[test]
+    fn test_convex_intersection_of_rectangles() {
+        // Overlapping corners share the square [1, 2] x [1, 2]
+        let shared = Geometry::intersection(&square(2.0), &rect(1.0, 1.0, 3.0, 3.0)).unwrap();
+        assert_eq!(shared.area(), 1.0);
+        let bounds = shared.bounds();
+        assert_eq!(
+            (
+                bounds.min.lon,
+                bounds.min.lat,
+                bounds.max.lon,
+                bounds.max.lat
+            ),
+            (1.0, 1.0, 2.0, 2.0)
+        );
+
+        // A strip crossing the square keeps only its middle
+        let strip = Geometry::intersection(&square(4.0), &rect(-1.0, 1.0, 5.0, 2.0)).unwrap();
+        assert_eq!(strip.area(), 4.0);
+
+        // A contained rectangle is its own intersection
+        let inner = rect(1.0, 1.0, 2.0, 3.0);
+        assert_eq!(
+            Geometry::intersection(&square(4.0), &inner).unwrap().area(),
+            inner.area()
+        );
+
+        // Orientation of the input does not matter
+        let clockwise = Polygon::new(
+            LinearRing::new(vec![
+                Coordinate::new(1.0, 1.0),
+                Coordinate::new(1.0, 3.0),
+                Coordinate::new(3.0, 3.0),
+                Coordinate::new(3.0, 1.0),
+            ])
+            .unwrap(),
+            vec![],
+        );
+        assert_eq!(
+            Geometry::intersection(&square(2.0), &clockwise)
+                .unwrap()
+                .area(),
+            1.0
+        );
+
+        // Disjoint and touching rectangles share no area
+        assert!(Geometry::intersection(&square(1.0), &rect(2.0, 2.0, 3.0, 3.0)).is_none());
+        assert!(Geometry::intersection(&square(1.0), &rect(1.0, 0.0, 2.0, 1.0)).is_none());
+    }
+
+    #[test]
+    fn test_convex_union_of_rectangles() {
+        let merged = Geometry::union(&square(2.0), &rect(1.0, 1.0, 3.0, 3.0)).unwrap();
+        assert_eq!(merged.area(), 7.0);
+        // Six corners of the outline plus the closing coordinate
+        assert_eq!(merged.exterior().coords().len(), 9);
+        assert!(merged.contains_point(&Coordinate::new(0.5, 0.5)));
+        assert!(merged.contains_point(&Coordinate::new(2.5, 2.5)));
+        assert!(!merged.contains_point(&Coordinate::new(2.5, 0.5)));
+
+        // Side by side rectangles merge into one
+        let wide = Geometry::union(&rect(0.0, 0.0, 2.0, 1.0), &rect(1.0, 0.0, 3.0, 1.0)).unwrap();
+        assert_eq!(wide.area(), 3.0);
+
+        // A contained rectangle adds nothing
+        assert_eq!(
+            Geometry::union(&square(4.0), &rect(1.0, 1.0, 2.0, 2.0))
+                .unwrap()
+                .area(),
+            16.0
+        );
+
+        assert!(Geometry::union(&square(1.0), &rect(2.0, 2.0, 3.0, 3.0)).is_none());
+    }
+
+    #[test]
+    fn test_convex_overlay_rejects_concave_input() {
+        let l_shape = Polygon::new(
+            LinearRing::new(vec![
+                Coordinate::new(0.0, 0.0),
+                Coordinate::new(2.0, 0.0),
+                Coordinate::new(2.0, 1.0),
+                Coordinate::new(1.0, 1.0),
+                Coordinate::new(1.0, 2.0),
+                Coordinate::new(0.0, 2.0),
+            ])
+            .unwrap(),
+            vec![],
+        );
+        assert!(Geometry::intersection(&l_shape, &square(1.0)).is_none());
+        assert!(Geometry::union(&square(1.0), &l_shape).is_none());
+
+        // A pentagram turns one way throughout but winds around twice
+        let star: Vec<Coordinate> = (0..5)
+            .map(|i| {
+                let angle =
+                    std::f64::consts::FRAC_PI_2 + (i * 2) as f64 * std::f64::consts::TAU / 5.0;
+                Coordinate::new(angle.cos(), angle.sin())
+            })
+            .collect();
+        let star = Polygon::new(LinearRing::new(star).unwrap(), vec![]);
+        assert!(Geometry::intersection(&star, &square(1.0)).is_none());
+
+        let with_hole = Polygon::new(
+            square(4.0).exterior().clone(),
+            vec![rect(1.0, 1.0, 2.0, 2.0).exterior().clone()],
+        );
+        assert!(Geometry::intersection(&with_hole, &square(1.0)).is_none());
+    }
+
+    #[test]
+    fn test_centroid_weighted() {
+        let polygon = |coords: &[(f64, f64)]| {
+            let ring = coords.iter().map(|&(x, y)| Coordinate::new(x, y)).collect();
+            Polygon::new(LinearRing::new(ring).unwrap(), vec![])
+        };
+        let assert_near = |c: Coordinate, x: f64, y: f64| {
+            assert!(
+                (c.x - x).abs() < 1e-12 && (c.y - y).abs() < 1e-12,
+                "{:?}",
+                c
+            );
+        };
+
+        assert_near(square(2.0).centroid(), 1.0, 1.0);
+
+        // The extra vertices along the bottom edge pull the vertex mean
+        // down, but do not move the center of mass
+        let dense = polygon(&[
+            (0.0, 0.0),
+            (0.5, 0.0),
+            (1.0, 0.0),
+            (1.5, 0.0),
+            (2.0, 0.0),
+            (2.0, 2.0),
+            (0.0, 2.0),
+        ]);
+        assert_near(dense.centroid_weighted(), 1.0, 1.0);
+
+        let l_shape = polygon(&[
+            (0.0, 0.0),
+            (2.0, 0.0),
+            (2.0, 1.0),
+            (1.0, 1.0),
+            (1.0, 2.0),
+            (0.0, 2.0),
+        ]);
+        let centroid = l_shape.centroid_weighted();
+        assert_near(centroid, 5.0 / 6.0, 5.0 / 6.0);
+        assert!(l_shape.contains_point(&centroid));
+
+        // A 3x3 block with a 1x1 notch cut from the top middle
+        let u_shape = polygon(&[
+            (0.0, 0.0),
+            (3.0, 0.0),
+            (3.0, 3.0),
+            (2.0, 3.0),
+            (2.0, 2.0),
+            (1.0, 2.0),
+            (1.0, 3.0),
+            (0.0, 3.0),
+        ]);
+        let centroid = u_shape.centroid();
+        assert_near(centroid, 1.5, 11.0 / 8.0);
+        assert!(u_shape.contains_point(&centroid));
+
+        // Winding does not matter, and geographic offsets are handled
+        let reversed = Polygon::new(l_shape.exterior().normalize_winding(true), vec![]);
+        assert_near(reversed.centroid_weighted(), 5.0 / 6.0, 5.0 / 6.0);
+        let shifted = polygon(&[(-74.0, 40.0), (-73.0, 40.0), (-73.0, 41.0), (-74.0, 41.0)]);
+        assert_near(shifted.centroid(), -73.5, 40.5);
+
+        // Holes are removed from the mass
+        let with_hole = Polygon::new(
+            square(4.0).exterior().clone(),
+            vec![rect(0.0, 0.0, 2.0, 2.0).exterior().normalize_winding(true)],
+        );
+        assert_near(with_hole.centroid(), 7.0 / 3.0, 7.0 / 3.0);
+
+        // Zero area falls back to the vertex mean
+        let flat = polygon(&[(0.0, 0.0), (1.0, 0.0), (2.0, 0.0)]);
+        assert_near(flat.centroid(), 1.0, 0.0);
+    }
+
+    #[test]
+    fn test_ring_winding() {
+        let ccw = square(2.0);
+        assert!(!ccw.exterior().is_clockwise());
+
+        let cw = ccw.exterior().normalize_winding(true);
+        assert!(cw.is_clockwise());
+        assert_eq!(cw.area(), -4.0);
+        assert_eq!(cw.coords().first(), cw.coords().last());
+        assert_eq!(cw.normalize_winding(true), cw);
+        assert_eq!(cw.normalize_winding(false), *ccw.exterior());
+    }
+
+    #[test]
+    fn test_polygon_normalize_winding_and_validate() {
+        let outer = square(4.0).exterior().normalize_winding(true);
+        let hole = rect(1.0, 1.0, 2.0, 2.0).exterior().clone();
+        let polygon = Polygon::new(outer, vec![hole]);
+
+        assert!(matches!(polygon.validate(), Err(SpatioError::Other(_))));
+        let normalized = polygon.normalize_winding();
+        assert!(normalized.validate().is_ok());
+        assert!(!normalized.exterior().is_clockwise());
+        assert!(normalized.holes()[0].is_clockwise());
+        assert_eq!(normalized.area(), polygon.area());
+
+        // A counter-clockwise hole is rejected
+        let bad_hole = Polygon::new(
+            square(4.0).exterior().clone(),
+            vec![rect(1.0, 1.0, 2.0, 2.0).exterior().clone()],
+        );
+        assert!(bad_hole.validate().is_err());
+
+        // Rings that bypass LinearRing::new can be open or too short
+        let open: LinearRing = serde_json::from_str(
+            r#"{"coords": [{"x": 0.0, "y": 0.0}, {"x": 1.0, "y": 0.0}, {"x": 1.0, "y": 1.0}, {"x": 0.0, "y": 1.0}]}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            Polygon::new(open, vec![]).validate(),
+            Err(SpatioError::Other(_))
+        ));
+
+        let short: LinearRing = serde_json::from_str(
+            r#"{"coords": [{"x": 0.0, "y": 0.0}, {"x": 1.0, "y": 0.0}, {"x": 0.0, "y": 0.0}]}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            Polygon::new(short, vec![]).validate(),
+            Err(SpatioError::InsufficientPoints)
+        ));
+
+        // Zero area has no winding
+        let flat = LinearRing::new(vec![
+            Coordinate::new(0.0, 0.0),
+            Coordinate::new(1.0, 0.0),
+            Coordinate::new(2.0, 0.0),
+        ])
+        .unwrap();
+        assert!(Polygon::new(flat, vec![]).validate().is_err());
+    }
+
+    #[test]
+    fn test_ring_closes_automatically() {
+        let ring = LinearRing::new(vec![
+            Coordinate::new(0.0, 0.0),
+            Coordinate::new(1.0, 0.0),
+            Coordinate::new(1.0, 1.0),
+        ])
+        .unwrap();
+        assert_eq!(ring.coords().first(), ring.coords().last());
+        assert_eq!(ring.coords().len(), 4);
+    }
+
+    #[test]
+    fn test_ring_requires_three_vertices() {
+        let result = LinearRing::new(vec![Coordinate::new(0.0, 0.0), Coordinate::new(1.0, 0.0)]);
+        assert!(matches!(result, Err(SpatioError::InsufficientPoints)));
+    }
+
+    #[test]
+    fn test_polygon_area_and_containment() {
+        let polygon = square(4.0);
+        assert_eq!(polygon.area(), 16.0);
+        assert!(polygon.contains_point(&Coordinate::new(1.0, 1.0)));
+        assert!(!polygon.contains_point(&Coordinate::new(5.0, 1.0)));
+
+        let hole = LinearRing::new(vec![
+            Coordinate::new(1.0, 1.0),
+            Coordinate::new(2.0, 1.0),
+            Coordinate::new(2.0, 2.0),
+            Coordinate::new(1.0, 2.0),
+        ])
+        .unwrap();
+        let with_hole = Polygon::new(polygon.exterior().clone(), vec![hole]);
+        assert_eq!(with_hole.area(), 15.0);
+        assert!(!with_hole.contains_point(&Coordinate::new(1.5, 1.5)));
+        assert!(with_hole.contains_point(&Coordinate::new(3.0, 3.0)));
+    }
+
+    fn lon_lat_polygon(coords: &[(f64, f64)]) -> Polygon {
+        let ring = LinearRing::new(
+            coords
+                .iter()
+                .map(|&(lon, lat)| Coordinate::new(lon, lat))
+                .collect(),
+        )
+        .unwrap();
+        Polygon::new(ring, vec![])
+    }
+
+    fn assert_within_percent(actual: f64, expected: f64, percent: f64) {
+        let error = (actual - expected).abs() / expected * 100.0;
+        assert!(
+            error < percent,
+            "{actual} differs from {expected} by {error:.2}%"
+        );
+    }
+
+    #[test]
+    fn test_geodesic_area_known_regions() {
+        // Colorado: 269,601 km²
+        let colorado = lon_lat_polygon(&[
+            (-109.05, 37.0),
+            (-102.05, 37.0),
+            (-102.05, 41.0),
+            (-109.05, 41.0),
+        ]);
+        assert_within_percent(colorado.geodesic_area_km2(), 269_601.0, 5.0);
+
+        // Wyoming: 253,335 km²
+        let wyoming = lon_lat_polygon(&[
+            (-111.05, 41.0),
+            (-104.05, 41.0),
+            (-104.05, 45.0),
+            (-111.05, 45.0),
+        ]);
+        assert_within_percent(wyoming.geodesic_area_km2(), 253_335.0, 5.0);
+
+        // Egypt (simplified outline): 1,001,450 km²
+        let egypt = lon_lat_polygon(&[
+            (25.0, 31.6),
+            (25.0, 22.0),
+            (36.9, 22.0),
+            (34.2, 27.8),
+            (34.9, 29.5),
+            (34.2, 31.3),
+            (32.0, 31.1),
+            (29.0, 30.9),
+        ]);
+        assert_within_percent(egypt.geodesic_area_km2(), 1_001_450.0, 5.0);
+
+        // Orientation does not affect the result
+        let clockwise = lon_lat_polygon(&[
+            (-109.05, 37.0),
+            (-109.05, 41.0),
+            (-102.05, 41.0),
+            (-102.05, 37.0),
+        ]);
+        assert_within_percent(
+            clockwise.geodesic_area_km2(),
+            colorado.geodesic_area_km2(),
+            0.001,
+        );
+    }
+
+    #[test]
+    fn test_geodesic_area_excludes_holes() {
+        let outer = LinearRing::new(vec![
+            Coordinate::new(0.0, 0.0),
+            Coordinate::new(2.0, 0.0),
+            Coordinate::new(2.0, 2.0),
+            Coordinate::new(0.0, 2.0),
+        ])
+        .unwrap();
+        let hole = LinearRing::new(vec![
+            Coordinate::new(0.5, 0.5),
+            Coordinate::new(1.5, 0.5),
+            Coordinate::new(1.5, 1.5),
+            Coordinate::new(0.5, 1.5),
+        ])
+        .unwrap();
+
+        let solid = Polygon::new(outer.clone(), vec![]);
+        let holed = Polygon::new(outer, vec![hole.clone()]);
+        let expected = solid.geodesic_area_km2() - hole.geodesic_area_km2();
+        assert!((holed.geodesic_area_km2() - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_geodesic_perimeter() {
+        // One degree square on the equator: four sides of ~111.19 km
+        let square = lon_lat_polygon(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]);
+        assert_within_percent(square.geodesic_perimeter_km(), 444.76, 0.1);
+
+        // Colorado: roughly 2 * (7° of longitude at 37-41°N + 4° of latitude)
+        let colorado = lon_lat_polygon(&[
+            (-109.05, 37.0),
+            (-102.05, 37.0),
+            (-102.05, 41.0),
+            (-109.05, 41.0),
+        ]);
+        assert_within_percent(colorado.geodesic_perimeter_km(), 2_094.0, 5.0);
+    }
+
+    #[test]
+    fn test_nearest_point_on_line() {
+        let line = LineString::new(vec![
+            Coordinate::new(0.0, 0.0),
+            Coordinate::new(10.0, 0.0),
+            Coordinate::new(10.0, 10.0),
+        ])
+        .unwrap();
+
+        // Perpendicular foot on the second segment
+        let (snapped, distance, segment) = line.nearest_point_on_line(&Coordinate::new(12.0, 5.0));
+        assert_eq!(snapped, Coordinate::new(10.0, 5.0));
+        assert_eq!(distance, 2.0);
+        assert_eq!(segment, 1);
+
+        // Beyond the end of the line clamps to the last vertex
+        let (snapped, distance, segment) = line.nearest_point_on_line(&Coordinate::new(10.0, 13.0));
+        assert_eq!(snapped, Coordinate::new(10.0, 10.0));
+        assert_eq!(distance, 3.0);
+        assert_eq!(segment, 1);
+
+        // Before the start clamps to the first vertex
+        let (snapped, _, segment) = line.nearest_point_on_line(&Coordinate::new(-3.0, -4.0));
+        assert_eq!(snapped, Coordinate::new(0.0, 0.0));
+        assert_eq!(segment, 0);
+    }
+
+    #[test]
+    fn test_nearest_geographic_point() {
+        // North-south route at 60°N, where a degree of longitude is half as long
+        let route = LineString::new(vec![
+            Coordinate::new(10.0, 60.0),
+            Coordinate::new(10.0, 60.1),
+            Coordinate::new(10.2, 60.1),
+        ])
+        .unwrap();
+
+        let fix = Point::new(60.05, 10.01);
+        let (snapped, distance_m, segment) = route.nearest_geographic_point(&fix);
+        assert_eq!(segment, 0);
+        assert!((snapped.lat - 60.05).abs() < 1e-6);
+        assert!((snapped.lon - 10.0).abs() < 1e-9);
+        assert!((distance_m - fix.distance_to(&snapped)).abs() < 1e-6);
+        assert!((distance_m - 555.0).abs() < 5.0);
+    }
+
+    #[test]
+    fn test_linestring_interpolate_and_resample() {
+        let line = LineString::new(vec![
+            Coordinate::new(0.0, 0.0),
+            Coordinate::new(3.0, 0.0),
+            Coordinate::new(3.0, 4.0),
+        ])
+        .unwrap();
+        assert_eq!(line.length(), 7.0);
+        assert_eq!(line.interpolate(0.0), Coordinate::new(0.0, 0.0));
+        assert_eq!(line.interpolate(5.0), Coordinate::new(3.0, 2.0));
+        assert_eq!(line.interpolate(-1.0), Coordinate::new(0.0, 0.0));
+
+        // Uneven remainder still ends on the last vertex
+        let resampled = line.resample(2.0).unwrap();
+        assert_eq!(
+            resampled.coords(),
+            &[
+                Coordinate::new(0.0, 0.0),
+                Coordinate::new(2.0, 0.0),
+                Coordinate::new(3.0, 1.0),
+                Coordinate::new(3.0, 3.0),
+                Coordinate::new(3.0, 4.0),
+            ]
+        );
+
+        // Interval longer than the line keeps only the endpoints
+        assert_eq!(line.resample(100.0).unwrap().coords().len(), 2);
+
+        assert!(line.resample(0.0).is_err());
+        assert!(line.resample(f64::INFINITY).is_err());
+        assert!(line.resample(1e-9).is_err());
+    }
+
+    #[test]
+    fn test_resample_geographic() {
+        // About 11.1 km due north along a meridian
+        let line =
+            LineString::new(vec![Coordinate::new(0.0, 0.0), Coordinate::new(0.0, 0.1)]).unwrap();
+
+        let points = line.resample_geographic(1_000.0).unwrap();
+        assert_eq!(points.len(), 13);
+        for w in points[..12].windows(2) {
+            assert!((w[0].distance_to(&w[1]) - 1_000.0).abs() < 0.01);
+        }
+        assert_eq!(points.last(), Some(&Point::new(0.1, 0.0)));
+
+        assert!(line.resample_geographic(-5.0).is_err());
+    }
+
+    #[test]
+    fn test_crossing_points() {
+        let line = |coords: &[(f64, f64)]| {
+            LineString::new(coords.iter().map(|&(x, y)| Coordinate::new(x, y)).collect()).unwrap()
+        };
+
+        // Crossings come back in order along self, not along other
+        let road = line(&[(0.0, 0.0), (10.0, 0.0), (10.0, 10.0)]);
+        let track = line(&[(12.0, 5.0), (8.0, 5.0), (5.0, 2.0), (5.0, -2.0)]);
+        assert_eq!(
+            road.crossing_points(&track),
+            vec![Coordinate::new(5.0, 0.0), Coordinate::new(10.0, 5.0)]
+        );
+        assert_eq!(
+            track.crossing_points(&road),
+            vec![Coordinate::new(10.0, 5.0), Coordinate::new(5.0, 0.0)]
+        );
+        assert!(road.intersects_with(&track));
+
+        // Meeting at a shared vertex is reported once
+        let through_corner = line(&[(8.0, 2.0), (12.0, -2.0)]);
+        assert_eq!(
+            road.crossing_points(&through_corner),
+            vec![Coordinate::new(10.0, 0.0)]
+        );
+
+        // Touching at an endpoint counts
+        let touching = line(&[(3.0, 0.0), (3.0, 5.0)]);
+        assert_eq!(
+            road.crossing_points(&touching),
+            vec![Coordinate::new(3.0, 0.0)]
+        );
+
+        // Collinear overlap gives both ends of the shared stretch
+        let overlap = line(&[(4.0, 0.0), (-2.0, 0.0)]);
+        assert_eq!(
+            road.crossing_points(&overlap),
+            vec![Coordinate::new(0.0, 0.0), Coordinate::new(4.0, 0.0)]
+        );
+
+        // Parallel and disjoint lines do not meet
+        let parallel = line(&[(0.0, 1.0), (9.0, 1.0)]);
+        let apart = line(&[(11.0, -1.0), (20.0, -1.0)]);
+        let collinear_apart = line(&[(11.0, 0.0), (20.0, 0.0)]);
+        for other in [&parallel, &apart] {
+            assert!(road.crossing_points(other).is_empty());
+            assert!(!road.intersects_with(other));
+        }
+        assert!(
+            line(&[(0.0, 0.0), (10.0, 0.0)])
+                .crossing_points(&collinear_apart)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_split_at_antimeridian() {
+        // Out across the date line heading east, then back again
+        let line = LineString::new(vec![
+            Coordinate::new(170.0, 0.0),
+            Coordinate::new(-170.0, 10.0),
+            Coordinate::new(-175.0, 10.0),
+            Coordinate::new(175.0, 20.0),
+        ])
+        .unwrap();
+
+        let parts = line.split_at_antimeridian();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(
+            parts[0].coords(),
+            &[Coordinate::new(170.0, 0.0), Coordinate::new(180.0, 5.0)]
+        );
+        assert_eq!(
+            parts[1].coords(),
+            &[
+                Coordinate::new(-180.0, 5.0),
+                Coordinate::new(-170.0, 10.0),
+                Coordinate::new(-175.0, 10.0),
+                Coordinate::new(-180.0, 15.0),
+            ]
+        );
+        assert_eq!(
+            parts[2].coords(),
+            &[Coordinate::new(180.0, 15.0), Coordinate::new(175.0, 20.0)]
+        );
+
+        // No crossing leaves the line whole
+        let local =
+            LineString::new(vec![Coordinate::new(0.0, 0.0), Coordinate::new(10.0, 0.0)]).unwrap();
+        assert_eq!(local.split_at_antimeridian(), vec![local.clone()]);
+
+        // A vertex already on the boundary does not produce a one-point part
+        let edge = LineString::new(vec![
+            Coordinate::new(180.0, 0.0),
+            Coordinate::new(-170.0, 0.0),
+        ])
+        .unwrap();
+        let parts = edge.split_at_antimeridian();
+        assert_eq!(parts.len(), 1);
+        assert_eq!(
+            parts[0].coords(),
+            &[Coordinate::new(-180.0, 0.0), Coordinate::new(-170.0, 0.0)]
+        );
+    }
+
+    #[test]
+    fn test_linestring_simplify() {
+        let line = LineString::new(vec![
+            Coordinate::new(0.0, 0.0),
+            Coordinate::new(0.001, 0.000001),
+            Coordinate::new(0.002, 0.0),
+            Coordinate::new(0.003, 0.01),
+            Coordinate::new(0.004, 0.0),
+        ])
+        .unwrap();
+
+        // Zero tolerance keeps everything
+        assert_eq!(line.simplify(0.0).unwrap(), line);
+
+        // Small tolerance removes the nearly collinear vertex only
+        let simplified = line.simplify(1_000.0).unwrap();
+        assert_eq!(simplified.coords().len(), 4);
+        assert!(
+            !simplified
+                .coords()
+                .contains(&Coordinate::new(0.001, 0.000001))
+        );
+
+        // Huge tolerance collapses to the endpoints
+        let collapsed = line.simplify(1e15).unwrap();
+        assert_eq!(
+            collapsed.coords(),
+            &[Coordinate::new(0.0, 0.0), Coordinate::new(0.004, 0.0)]
+        );
+
+        assert!(line.simplify(-1.0).is_err());
+        assert!(line.simplify(f64::NAN).is_err());
+    }
+
+    #[test]
+    fn test_polygon_simplify_preserves_closure() {
+        let mut coords = Vec::new();
+        for i in 0..64 {
+            let angle = i as f64 * std::f64::consts::TAU / 64.0;
+            coords.push(Coordinate::new(
+                angle.cos() * 0.01,
+                45.0 + angle.sin() * 0.01,
+            ));
+        }
+        let circle = Polygon::new(LinearRing::new(coords).unwrap(), vec![]);
+
+        let simplified = circle.simplify(50_000.0).unwrap();
+        let ring = simplified.exterior().coords();
+        assert!(ring.len() < circle.exterior().coords().len());
+        assert!(ring.len() >= 4);
+        assert_eq!(ring.first(), ring.last());
+        assert_eq!(ring.first(), circle.exterior().coords().first());
+
+        // Never collapses below a triangle
+        let minimal = circle.simplify(1e15).unwrap();
+        assert_eq!(minimal.exterior().coords().len(), 4);
+    }
+
+    fn sample_collection() -> Geometry {
+        let line = LineString::new(vec![
+            Coordinate::new(10.0, 10.0),
+            Coordinate::new(13.0, 14.0),
+        ])
+        .unwrap();
+        let hole = LinearRing::new(vec![
+            Coordinate::new(1.0, 1.0),
+            Coordinate::new(2.0, 1.0),
+            Coordinate::new(2.0, 2.0),
+            Coordinate::new(1.0, 2.0),
+        ])
+        .unwrap();
+        let holed = Polygon::new(square(4.0).exterior().clone(), vec![hole]);
+
+        Geometry::GeometryCollection(vec![
+            Geometry::Point(Coordinate::new(-1.0, -1.0)),
+            Geometry::MultiPoint(vec![Coordinate::new(5.0, 5.0), Coordinate::new(6.0, 6.0)]),
+            Geometry::MultiLineString(vec![line]),
+            Geometry::MultiPolygon(vec![holed, square(1.0)]),
+        ])
+    }
+
+    #[test]
+    fn test_multi_geometry_measures() {
+        let collection = sample_collection();
+
+        assert_eq!(
+            collection.bounds(),
+            Some(BoundingBox::new(-1.0, -1.0, 14.0, 13.0))
+        );
+        assert_eq!(collection.area(), 15.0 + 1.0);
+        assert_eq!(collection.length(), 5.0);
+        assert_eq!(Geometry::MultiPolygon(vec![]).bounds(), None);
+
+        assert!(collection.contains_point(&Coordinate::new(3.0, 3.0)));
+        assert!(collection.contains_point(&Coordinate::new(0.5, 0.5)));
+        assert!(collection.contains_point(&Coordinate::new(5.0, 5.0)));
+        assert!(collection.contains_point(&Coordinate::new(11.5, 12.0)));
+        // Inside the hole of the first polygon
+        assert!(!collection.contains_point(&Coordinate::new(1.5, 1.5)));
+    }
+
+    #[test]
+    fn test_multi_geometry_wkt() {
+        assert_eq!(
+            sample_collection().to_wkt(),
+            "GEOMETRYCOLLECTION (POINT (-1 -1), MULTIPOINT ((5 5), (6 6)), \
+             MULTILINESTRING ((10 10, 13 14)), \
+             MULTIPOLYGON (((0 0, 4 0, 4 4, 0 4, 0 0), (1 1, 2 1, 2 2, 1 2, 1 1)), \
+             ((0 0, 1 0, 1 1, 0 1, 0 0))))"
+        );
+        assert_eq!(Geometry::MultiPoint(vec![]).to_wkt(), "MULTIPOINT EMPTY");
+    }
+
+    #[cfg(feature = "geojson")]
+    #[test]
+    fn test_multi_geometry_geojson_roundtrip() {
+        let collection = sample_collection();
+        let geojson = collection.to_geojson().unwrap();
+        assert_eq!(Geometry::from_geojson(&geojson).unwrap(), collection);
+
+        let multi_polygon = r#"{"type":"MultiPolygon","coordinates":[
+            [[[0,0],[1,0],[1,1],[0,1],[0,0]]],
+            [[[5,5],[6,5],[6,6],[5,5]]]
+        ]}"#;
+        match Geometry::from_geojson(multi_polygon).unwrap() {
+            Geometry::MultiPolygon(polygons) => assert_eq!(polygons.len(), 2),
+            other => panic!("unexpected geometry {:?}", other),
+        }
+
+        assert!(Geometry::from_geojson(r#"{"type":"Circle","coordinates":[0,0]}"#).is_err());
+        assert!(Geometry::from_geojson(r#"{"type":"MultiPoint"}"#).is_err());
+    }
+
+    #[test]
+    fn test_multi_geometry_wkb_roundtrip() {
+        let collection = sample_collection();
+        let bytes = collection.to_bytes();
+        assert_eq!(Geometry::from_bytes(&bytes).unwrap(), collection);
+
+        // Truncated and trailing input is rejected
+        assert!(matches!(
+            Geometry::from_bytes(&bytes[..bytes.len() - 1]),
+            Err(SpatioError::UnexpectedEof)
+        ));
+        let mut trailing = bytes.clone();
+        trailing.push(0);
+        assert!(matches!(
+            Geometry::from_bytes(&trailing),
+            Err(SpatioError::InvalidFormat)
+        ));
+
+        // Big-endian multipoint containing a single point
+        let mut big_endian = vec![0u8];
+        big_endian.extend_from_slice(&4u32.to_be_bytes());
+        big_endian.extend_from_slice(&1u32.to_be_bytes());
+        big_endian.push(0);
+        big_endian.extend_from_slice(&1u32.to_be_bytes());
+        big_endian.extend_from_slice(&1.5f64.to_be_bytes());
+        big_endian.extend_from_slice(&(-2.5f64).to_be_bytes());
+        assert_eq!(
+            Geometry::from_bytes(&big_endian).unwrap(),
+            Geometry::MultiPoint(vec![Coordinate::new(1.5, -2.5)])
+        );
+
+        // Deeply nested collections are rejected instead of overflowing
+        // the stack
+        let nested = |depth: usize| {
+            let mut bytes = Vec::with_capacity(depth * 9);
+            for level in 0..depth {
+                bytes.push(1);
+                bytes.extend_from_slice(&7u32.to_le_bytes());
+                let count = if level + 1 < depth { 1u32 } else { 0 };
+                bytes.extend_from_slice(&count.to_le_bytes());
+            }
+            bytes
+        };
+        assert!(Geometry::from_bytes(&nested(MAX_WKB_DEPTH)).is_ok());
+        assert!(Geometry::from_bytes(&nested(MAX_WKB_DEPTH + 1)).is_err());
+        assert!(matches!(
+            Geometry::from_bytes(&nested(200_000)),
+            Err(SpatioError::InvalidFormat)
+        ));
+    }
+
+    #[test]
+    fn test_geometry_intersects() {
+        let collection = sample_collection();
+
+        let crossing = Geometry::LineString(
+            LineString::new(vec![Coordinate::new(-2.0, 3.0), Coordinate::new(0.5, 3.0)]).unwrap(),
+        );
+        assert!(collection.intersects(&crossing));
+
+        let in_hole = Geometry::Point(Coordinate::new(1.5, 1.5));
+        assert!(!collection.intersects(&in_hole));
+
+        let far_away = Geometry::MultiPolygon(vec![Polygon::new(
+            LinearRing::new(vec![
+                Coordinate::new(50.0, 50.0),
+                Coordinate::new(51.0, 50.0),
+                Coordinate::new(51.0, 51.0),
+            ])
+            .unwrap(),
+            vec![],
+        )]);
+        assert!(!collection.intersects(&far_away));
+
+        // A polygon fully inside another intersects it
+        let inner = Geometry::Polygon(Polygon::new(
+            LinearRing::new(vec![
+                Coordinate::new(3.0, 3.0),
+                Coordinate::new(3.5, 3.0),
+                Coordinate::new(3.5, 3.5),
+            ])
+            .unwrap(),
+            vec![],
+        ));
+        assert!(collection.intersects(&inner));
+        assert!(inner.intersects(&collection));
+
+        // Touching line endpoints
+        let touching = Geometry::MultiLineString(vec![
+            LineString::new(vec![
+                Coordinate::new(13.0, 14.0),
+                Coordinate::new(20.0, 20.0),
+            ])
+            .unwrap(),
+        ]);
+        assert!(collection.intersects(&touching));
+
+        assert!(!collection.intersects(&Geometry::GeometryCollection(vec![])));
+    }
+
+    #[test]
+    fn test_convex_hull() {
+        let points = vec![
+            Point::new(0.0, 0.0),
+            Point::new(0.0, 2.0),
+            Point::new(2.0, 2.0),
+            Point::new(2.0, 0.0),
+            Point::new(1.0, 1.0),
+            Point::new(0.5, 1.5),
+            Point::new(1.0, 0.0), // collinear on an edge
+        ];
+
+        let hull = Polygon::convex_hull(&points).unwrap();
+        assert_eq!(hull.exterior().coords().len(), 5);
+        assert_eq!(hull.area(), 4.0);
+        // Counter-clockwise orientation
+        assert!(hull.exterior().area() > 0.0);
+    }
+
+    #[test]
+    fn test_convex_hull_insufficient_points() {
+        let two = vec![Point::new(0.0, 0.0), Point::new(1.0, 1.0)];
+        assert!(matches!(
+            Polygon::convex_hull(&two),
+            Err(SpatioError::InsufficientPoints)
+        ));
+
+        let collinear = vec![
+            Point::new(0.0, 0.0),
+            Point::new(1.0, 1.0),
+            Point::new(2.0, 2.0),
+        ];
+        assert!(matches!(
+            Polygon::convex_hull(&collinear),
+            Err(SpatioError::InsufficientPoints)
+        ));
+
+        let duplicates = vec![Point::new(1.0, 1.0); 10];
+        assert!(Polygon::convex_hull(&duplicates).is_err());
+    }
+}
@@ -1,9 +1,28 @@
 use crate::error::{Result, SpatioError};
-use crate::spatial::Point;
-use crate::types::Config;
+use crate::geometry::{Coordinate, Geometry, GeometryOps, LinearRing, Polygon};
+#[cfg(feature = "aof")]
+use crate::persistence::AOFPosition;
+#[cfg(feature = "s2")]
+use crate::spatial::S2Covering;
+#[cfg(feature = "aof")]
+use crate::spatial::SpatialKey;
+use crate::spatial::{BoundingBox, GeohashUtils, Point};
+use crate::sync::{self, RwLock};
+#[cfg(feature = "debug_queries")]
+use crate::types::QueryExplanation;
+use crate::types::{Config, SpatialJoinResult};
 use bytes::Bytes;
 use geohash;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use rustc_hash::{FxHashMap, FxHashSet};
+#[cfg(feature = "aof")]
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::Bound;
+#[cfg(feature = "aof")]
+use std::path::Path;
+use std::sync::Arc;
 
 /// Threshold for large search radius in meters
 const LARGE_RADIUS_THRESHOLD: f64 = 100_000.0;
@@ -17,6 +36,45 @@ pub const DEFAULT_GEOHASH_PRECISION: usize = 8;
 /// Default geohash precisions for neighbor search
 pub const DEFAULT_SEARCH_PRECISIONS: &[usize] = &[6, 7, 8];
 
+/// Upper bound on the cells in an S2 bounding box covering
+#[cfg(feature = "s2")]
+const S2_COVERING_MAX_CELLS: usize = 16;
+
+/// MinHash values combined into each LSH table's bucket key. More rows
+/// make buckets more selective but lower the chance that close points
+/// share one.
+const LSH_ROWS_PER_TABLE: usize = 2;
+
+/// Format version of the index snapshot written by `save_to_path`
+#[cfg(feature = "aof")]
+const INDEX_SNAPSHOT_VERSION: u32 = 4;
+
+/// Spatial indexing strategy used for a prefix.
+///
+/// Geohash is the default. S2 cells make bounding box queries cheap by
+/// looking up only the cells covering the box. H3 hexagonal cells are
+/// available with the `h3` feature and give more uniform cell sizes and
+/// simpler neighbor lookups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexStrategy {
+    /// Geohash cells at the given precision (1-12)
+    Geohash { precision: usize },
+    /// S2 cells at the given level (0-30)
+    #[cfg(feature = "s2")]
+    S2 { level: u8 },
+    /// H3 hexagonal cells at the given resolution (0-15)
+    #[cfg(feature = "h3")]
+    H3 { resolution: u8 },
+}
+
+impl Default for IndexStrategy {
+    fn default() -> Self {
+        IndexStrategy::Geohash {
+            precision: DEFAULT_GEOHASH_PRECISION,
+        }
+    }
+}
+
 /// Simplified index manager focused on spatial operations only.
 ///
 /// This manages spatial indexes for efficient geographic queries.
@@ -26,14 +84,109 @@ pub struct IndexManager {
     spatial_indexes: FxHashMap<String, SpatialIndex>,
     /// Geohash precision for indexing
     geohash_precision: usize,
+    /// Geohash precision overrides by prefix
+    prefix_precisions: FxHashMap<String, usize>,
     /// Geohash precisions to use for neighbor search
     search_precisions: Vec<usize>,
+    /// Whether large full scans run on the rayon thread pool
+    #[cfg(feature = "parallel")]
+    parallel_queries: bool,
+    /// S2 indexes organized by prefix
+    #[cfg(feature = "s2")]
+    s2_indexes: FxHashMap<String, S2Index>,
+    /// H3 indexes organized by prefix
+    #[cfg(feature = "h3")]
+    h3_indexes: FxHashMap<String, H3Index>,
+    /// Geometry indexes organized by prefix
+    geometry_indexes: FxHashMap<String, GeometryIndex>,
+    /// LSH tables per prefix, built on the first approximate query and
+    /// dropped whenever the prefix's geohash index changes
+    lsh_indexes: RwLock<FxHashMap<String, Arc<LshIndex>>>,
+    /// Hash tables built for a prefix by default
+    lsh_num_tables: usize,
+}
+
+/// Snapshot entries of one prefix: cell key and point
+#[cfg(feature = "aof")]
+type SnapshotEntries<K> = Vec<(K, Point)>;
+
+/// Spatial index snapshot written by `IndexManager::save_to_path`.
+///
+/// The snapshot records where each point and geometry is indexed, but not
+/// the data stored with it. That is read from the stored values when the
+/// snapshot is applied with `IndexManager::apply_snapshot`, looking each
+/// entry up by the key it is stored under.
+#[cfg(feature = "aof")]
+#[derive(Serialize, Deserialize)]
+pub struct IndexSnapshot {
+    version: u32,
+    geohash_precision: usize,
+    /// Geohash precision overrides by prefix
+    prefix_precisions: Vec<(String, usize)>,
+    /// AOF position the snapshot reflects
+    aof_position: AOFPosition,
+    /// Geohash entries per prefix
+    geohash: Vec<(String, SnapshotEntries<String>)>,
+    /// S2 level and entries per prefix
+    s2: Vec<(String, u8, SnapshotEntries<u64>)>,
+    /// H3 resolution and entries per prefix
+    h3: Vec<(String, u8, SnapshotEntries<u64>)>,
+    /// Geometry ids per prefix
+    geometry: Vec<(String, Vec<String>)>,
 }
 
 /// A spatial index for a specific prefix/namespace
 struct SpatialIndex {
-    /// Points stored with their geohash keys
-    points: FxHashMap<String, (Point, Bytes)>,
+    /// Points stored with their geohash keys; ordered so the points in a
+    /// cell form a contiguous range
+    points: BTreeMap<String, (Point, Bytes)>,
+}
+
+/// An S2 index for a specific prefix/namespace
+#[cfg(feature = "s2")]
+struct S2Index {
+    /// Level shared by every cell in this index
+    level: u8,
+    /// Points stored by S2 cell ID; ordered so the cells under a covering
+    /// cell form a contiguous range
+    points: BTreeMap<u64, (Point, Bytes)>,
+}
+
+/// A geometry index for a specific prefix/namespace
+struct GeometryIndex {
+    /// Geometries by id
+    entries: BTreeMap<String, GeometryEntry>,
+    /// Largest half-height and half-width of any bounding box stored so
+    /// far, in degrees. A geometry can only reach a query box if its
+    /// center lies within the box widened by this much.
+    max_half_extent: (f64, f64),
+}
+
+/// A stored geometry with its representative point and bounding box
+struct GeometryEntry {
+    /// Center of the bounding box
+    center: Point,
+    bounds: BoundingBox,
+    geometry: Geometry,
+    data: Bytes,
+}
+
+/// Locality-sensitive hash tables over the geohash keys of a prefix.
+///
+/// Each table buckets keys by MinHash signatures of their set of geohash
+/// prefixes, so keys sharing a long prefix are likely to share a bucket.
+struct LshIndex {
+    /// Keys by bucket, one map per table
+    tables: Vec<FxHashMap<u64, Vec<String>>>,
+}
+
+/// An H3 index for a specific prefix/namespace
+#[cfg(feature = "h3")]
+struct H3Index {
+    /// Resolution shared by every cell in this index
+    resolution: h3o::Resolution,
+    /// Points stored with their H3 cell indexes
+    points: FxHashMap<u64, (Point, Bytes)>,
 }
 
 impl IndexManager {
@@ -42,42 +195,43 @@ impl IndexManager {
         Self {
             spatial_indexes: FxHashMap::default(),
             geohash_precision: DEFAULT_GEOHASH_PRECISION,
+            prefix_precisions: FxHashMap::default(),
             search_precisions: DEFAULT_SEARCH_PRECISIONS.to_vec(),
+            #[cfg(feature = "parallel")]
+            parallel_queries: false,
+            #[cfg(feature = "s2")]
+            s2_indexes: FxHashMap::default(),
+            #[cfg(feature = "h3")]
+            h3_indexes: FxHashMap::default(),
+            geometry_indexes: FxHashMap::default(),
+            lsh_indexes: RwLock::new(FxHashMap::default()),
+            lsh_num_tables: Config::default().lsh_num_tables,
         }
     }
 
     /// Create a new index manager with custom configuration
     pub fn with_config(config: &Config) -> Self {
-        // Generate search precisions with proper bounds and deduplication
-        let mut search_precisions = Vec::new();
-
-        // Add precision-2 if valid and different
-        let p_minus_2 = config.geohash_precision.saturating_sub(2).max(1);
-        if p_minus_2 >= 1 {
-            search_precisions.push(p_minus_2);
-        }
-
-        // Add precision-1 if valid and different from previous
-        let p_minus_1 = config.geohash_precision.saturating_sub(1).max(1);
-        if p_minus_1 >= 1 && !search_precisions.contains(&p_minus_1) {
-            search_precisions.push(p_minus_1);
-        }
-
-        // Add the main precision if different from previous
-        let main_precision = config.geohash_precision.min(12);
-        if !search_precisions.contains(&main_precision) {
-            search_precisions.push(main_precision);
-        }
-
-        // Ensure we have at least one precision
-        if search_precisions.is_empty() {
-            search_precisions.push(1);
-        }
+        // Fall back to the precision itself if the config was built by hand
+        let search_precisions = if config.geohash_search_precisions.is_empty() {
+            vec![config.geohash_precision.clamp(1, 12)]
+        } else {
+            config.geohash_search_precisions.clone()
+        };
 
         Self {
             spatial_indexes: FxHashMap::default(),
             geohash_precision: config.geohash_precision,
+            prefix_precisions: FxHashMap::default(),
             search_precisions,
+            #[cfg(feature = "parallel")]
+            parallel_queries: config.parallel_queries,
+            #[cfg(feature = "s2")]
+            s2_indexes: FxHashMap::default(),
+            #[cfg(feature = "h3")]
+            h3_indexes: FxHashMap::default(),
+            geometry_indexes: FxHashMap::default(),
+            lsh_indexes: RwLock::new(FxHashMap::default()),
+            lsh_num_tables: config.lsh_num_tables.max(1),
         }
     }
 
@@ -91,21 +245,205 @@ impl IndexManager {
         radius_meters > LARGE_RADIUS_THRESHOLD || index.points.len() < SMALL_DATASET_THRESHOLD
     }
 
+    /// Geohash precision points under `prefix` are indexed at
+    pub fn prefix_precision(&self, prefix: &str) -> usize {
+        self.prefix_precisions
+            .get(prefix)
+            .copied()
+            .unwrap_or(self.geohash_precision)
+    }
+
+    /// Index points under `prefix` at `precision` instead of the default.
+    ///
+    /// Points already indexed under the prefix are rekeyed at the new
+    /// precision. Setting the default precision removes the override.
+    pub fn set_prefix_precision(&mut self, prefix: &str, precision: usize) -> Result<()> {
+        if !(1..=12).contains(&precision) {
+            return Err(SpatioError::InvalidConfiguration(format!(
+                "Geohash precision must be between 1 and 12, got {}",
+                precision
+            )));
+        }
+        if precision == self.prefix_precision(prefix) {
+            return Ok(());
+        }
+
+        if precision == self.geohash_precision {
+            self.prefix_precisions.remove(prefix);
+        } else {
+            self.prefix_precisions.insert(prefix.to_string(), precision);
+        }
+
+        if let Some(index) = self.spatial_indexes.get_mut(prefix) {
+            let entries = std::mem::take(&mut index.points);
+            for (point, data) in entries.into_values() {
+                let geohash = point
+                    .to_geohash(precision)
+                    .map_err(|_| SpatioError::InvalidGeohash)?;
+                index.points.insert(geohash, (point, data));
+            }
+            self.invalidate_lsh_index(prefix);
+        }
+        Ok(())
+    }
+
     /// Insert a point into the spatial index
     pub fn insert_point(&mut self, prefix: &str, point: &Point, data: &Bytes) -> Result<()> {
+        let precision = self.prefix_precision(prefix);
         let index = self
             .spatial_indexes
             .entry(prefix.to_string())
             .or_insert_with(SpatialIndex::new);
 
         let geohash = point
-            .to_geohash(self.geohash_precision)
+            .to_geohash(precision)
             .map_err(|_| SpatioError::InvalidGeohash)?;
 
         index.points.insert(geohash, (*point, data.clone()));
+        self.invalidate_lsh_index(prefix);
         Ok(())
     }
 
+    /// Insert a point into the index using an explicit strategy
+    pub fn insert_point_with_strategy(
+        &mut self,
+        prefix: &str,
+        point: &Point,
+        data: &Bytes,
+        strategy: IndexStrategy,
+    ) -> Result<()> {
+        match strategy {
+            IndexStrategy::Geohash { precision } => {
+                let geohash = point
+                    .to_geohash(precision)
+                    .map_err(|_| SpatioError::InvalidGeohash)?;
+                self.spatial_indexes
+                    .entry(prefix.to_string())
+                    .or_insert_with(SpatialIndex::new)
+                    .points
+                    .insert(geohash, (*point, data.clone()));
+                self.invalidate_lsh_index(prefix);
+                Ok(())
+            }
+            #[cfg(feature = "s2")]
+            IndexStrategy::S2 { level } => {
+                let cell = point.to_s2_cell(level)?;
+                let index = self
+                    .s2_indexes
+                    .entry(prefix.to_string())
+                    .or_insert_with(|| S2Index {
+                        level,
+                        points: BTreeMap::new(),
+                    });
+
+                if index.level != level {
+                    return Err(SpatioError::Other(format!(
+                        "Prefix '{}' is already indexed at S2 level {}",
+                        prefix, index.level
+                    )));
+                }
+
+                index.points.insert(cell.0, (*point, data.clone()));
+                Ok(())
+            }
+            #[cfg(feature = "h3")]
+            IndexStrategy::H3 { resolution } => {
+                let cell = point.to_h3_cell(resolution)?;
+                let resolution = h3o::Resolution::try_from(resolution)
+                    .map_err(|_| SpatioError::Other("H3 resolution must be <= 15".to_string()))?;
+
+                let index = self
+                    .h3_indexes
+                    .entry(prefix.to_string())
+                    .or_insert_with(|| H3Index {
+                        resolution,
+                        points: FxHashMap::default(),
+                    });
+
+                if index.resolution != resolution {
+                    return Err(SpatioError::Other(format!(
+                        "Prefix '{}' is already indexed at H3 resolution {}",
+                        prefix, index.resolution
+                    )));
+                }
+
+                index.points.insert(cell, (*point, data.clone()));
+                Ok(())
+            }
+        }
+    }
+
+    /// Find nearby points within a radius using the H3 index.
+    ///
+    /// Searches the disk of cells around the center cell that is wide
+    /// enough to cover the radius, then filters by exact distance.
+    #[cfg(feature = "h3")]
+    pub fn find_nearby_h3(
+        &self,
+        prefix: &str,
+        center: &Point,
+        radius_meters: f64,
+        limit: usize,
+    ) -> Result<Vec<(Point, Bytes)>> {
+        let index = match self.h3_indexes.get(prefix) {
+            Some(index) => index,
+            None => return Ok(Vec::new()),
+        };
+
+        let center_cell = h3o::CellIndex::try_from(center.to_h3_cell(u8::from(index.resolution))?)
+            .map_err(|_| SpatioError::InvalidFormat)?;
+
+        // Adjacent hexagon centers are ~1.7 edge lengths apart, so one ring
+        // per edge length over-covers the radius. The extra ring accounts
+        // for the query point's offset from its cell center.
+        let edge_length = index.resolution.edge_length_m();
+        let k = (radius_meters / edge_length).ceil() as u32 + 1;
+        let disk_size = 3 * (k as usize) * (k as usize + 1) + 1;
+
+        let mut results = Vec::new();
+        if disk_size > index.points.len() {
+            // The disk would contain more cells than there are points
+            for (point, data) in index.points.values() {
+                if center.distance_to(point) <= radius_meters {
+                    results.push((*point, data.clone()));
+                }
+            }
+        } else {
+            for cell in center_cell.grid_disk::<Vec<_>>(k) {
+                if let Some((point, data)) = index.points.get(&u64::from(cell))
+                    && center.distance_to(point) <= radius_meters
+                {
+                    results.push((*point, data.clone()));
+                }
+            }
+        }
+
+        results.sort_by(|a, b| {
+            center
+                .distance_to(&a.0)
+                .partial_cmp(&center.distance_to(&b.0))
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// Remove a point from the S2 index by its cell
+    #[cfg(feature = "s2")]
+    pub fn remove_s2_cell(&mut self, prefix: &str, cell: u64) {
+        if let Some(index) = self.s2_indexes.get_mut(prefix) {
+            index.points.remove(&cell);
+        }
+    }
+
+    /// Remove a point from the H3 index by its cell
+    #[cfg(feature = "h3")]
+    pub fn remove_h3_cell(&mut self, prefix: &str, cell: u64) {
+        if let Some(index) = self.h3_indexes.get_mut(prefix) {
+            index.points.remove(&cell);
+        }
+    }
+
     /// Find nearby points within a radius
     pub fn find_nearby(
         &self,
@@ -115,13 +453,37 @@ impl IndexManager {
         limit: usize,
     ) -> Result<Vec<(Point, Bytes)>> {
         let index = match self.spatial_indexes.get(prefix) {
-            Some(index) => index,
-            None => return Ok(Vec::new()),
+            Some(index) if limit > 0 => index,
+            _ => return Ok(Vec::new()),
         };
 
         let mut results = Vec::with_capacity(limit.min(1000));
 
         // For large search radii or small datasets, use full scan instead of geohash optimization
+        #[cfg(feature = "parallel")]
+        if self.should_use_full_scan(prefix, radius_meters)
+            && self.parallel_queries
+            && index.points.len() > SMALL_DATASET_THRESHOLD
+        {
+            // Distances are independent, so compute them in parallel and
+            // sort the matches afterwards
+            let mut matches: Vec<(f64, Point, Bytes)> = index
+                .points
+                .par_iter()
+                .filter_map(|(_, (point, data))| {
+                    let distance = center.distance_to(point);
+                    (distance <= radius_meters).then(|| (distance, *point, data.clone()))
+                })
+                .collect();
+
+            matches.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+            matches.truncate(limit);
+            return Ok(matches
+                .into_iter()
+                .map(|(_, point, data)| (point, data))
+                .collect());
+        }
+
         if self.should_use_full_scan(prefix, radius_meters) {
             // Check all points in the index
             for (point, data) in index.points.values() {
@@ -134,90 +496,217 @@ impl IndexManager {
                 }
             }
         } else {
-            // Use geohash-based search for efficiency
-            let mut candidates = FxHashSet::default();
-            candidates.reserve(27); // 9 directions * 3 precisions
-
-            // Try multiple precision levels for better coverage
-            for precision in &self.search_precisions {
-                if let Ok(center_geohash) = center.to_geohash(*precision) {
-                    candidates.insert(center_geohash.clone());
-
-                    // Add neighbors at this precision
-                    for direction in &[
-                        geohash::Direction::N,
-                        geohash::Direction::S,
-                        geohash::Direction::E,
-                        geohash::Direction::W,
-                        geohash::Direction::NE,
-                        geohash::Direction::NW,
-                        geohash::Direction::SE,
-                        geohash::Direction::SW,
-                    ] {
-                        if let Ok(neighbor) = geohash::neighbor(&center_geohash, *direction) {
-                            candidates.insert(neighbor);
-                        }
-                    }
-                }
+            results = self.ring_search(index, center, radius_meters, limit)?;
+        }
+
+        // Sort by distance and limit results
+        results.sort_by(|a, b| {
+            let dist_a = center.distance_to(&a.0);
+            let dist_b = center.distance_to(&b.0);
+            dist_a
+                .partial_cmp(&dist_b)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+
+        results.truncate(limit);
+        Ok(results)
+    }
+
+    /// Search rings of geohash cells outward from the center's cell.
+    ///
+    /// Cells are taken at the coarsest search precision. Expansion stops
+    /// once the nearest possible point in the next ring is farther than
+    /// the radius, or than the `limit`-th match found so far. If the rings
+    /// would visit more cells than there are points, a full scan is
+    /// cheaper and is used instead.
+    fn ring_search(
+        &self,
+        index: &SpatialIndex,
+        center: &Point,
+        radius_meters: f64,
+        limit: usize,
+    ) -> Result<Vec<(Point, Bytes)>> {
+        let precision = self.search_precisions.first().copied().unwrap_or(1);
+        let center_hash = center
+            .to_geohash(precision)
+            .map_err(|_| SpatioError::InvalidGeohash)?;
+        let center_cell =
+            geohash::decode_bbox(&center_hash).map_err(|_| SpatioError::InvalidGeohash)?;
+
+        let mut visited_cells = FxHashSet::default();
+        let mut matched_keys = FxHashSet::default();
+        let mut matches: Vec<(f64, &Point, &Bytes)> = Vec::new();
+
+        for ring in 0.. {
+            let cutoff = if matches.len() >= limit {
+                matches[limit - 1].0
+            } else {
+                radius_meters
+            };
+            if ring_min_distance(center, &center_cell, ring) > cutoff {
+                break;
             }
 
-            // Collect candidates with distances for sorting
-            let mut candidates_with_distance = Vec::new();
+            let cells = GeohashUtils::ring(&center_hash, ring);
+            if cells.is_empty() {
+                break;
+            }
+            visited_cells.extend(cells.iter().cloned());
+            if visited_cells.len() > index.points.len() {
+                return Ok(Self::scan_within_radius(
+                    index,
+                    center,
+                    radius_meters,
+                    limit,
+                ));
+            }
 
-            // Check all candidate geohashes
-            for geohash in &candidates {
-                // Check if any point starts with this geohash prefix
-                for (stored_geohash, (point, data)) in &index.points {
-                    if stored_geohash.starts_with(geohash) || geohash.starts_with(stored_geohash) {
-                        let distance = center.distance_to(point);
-                        if distance <= radius_meters {
-                            candidates_with_distance.push((distance, *point, data.clone()));
-                        }
+            for cell in &cells {
+                for (key, (point, data)) in points_in_cell(index, cell) {
+                    let distance = center.distance_to(point);
+                    if distance <= radius_meters && matched_keys.insert(key) {
+                        matches.push((distance, point, data));
                     }
                 }
             }
+            matches.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        }
 
-            // Sort by distance and take closest results, naturally handling duplicates
-            candidates_with_distance
-                .sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        matches.truncate(limit);
+        Ok(matches
+            .into_iter()
+            .map(|(_, point, data)| (*point, data.clone()))
+            .collect())
+    }
 
-            // Take unique points (deduplicate by point coordinates) up to limit
-            let mut seen_points = FxHashSet::default();
-            for (_, point, data) in candidates_with_distance {
-                let point_key = (point.lat.to_bits(), point.lon.to_bits());
-                if seen_points.insert(point_key) {
-                    results.push((point, data));
-                    if results.len() >= limit {
-                        break;
-                    }
+    /// Check every point in the index, returning the nearest matches
+    fn scan_within_radius(
+        index: &SpatialIndex,
+        center: &Point,
+        radius_meters: f64,
+        limit: usize,
+    ) -> Vec<(Point, Bytes)> {
+        let mut matches: Vec<(f64, Point, Bytes)> = index
+            .points
+            .values()
+            .filter_map(|(point, data)| {
+                let distance = center.distance_to(point);
+                (distance <= radius_meters).then(|| (distance, *point, data.clone()))
+            })
+            .collect();
+        matches.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        matches.truncate(limit);
+        matches
+            .into_iter()
+            .map(|(_, point, data)| (point, data))
+            .collect()
+    }
+
+    /// Explain how `find_nearby` would answer a radius query.
+    ///
+    /// Follows the same full scan or ring search decision, but without a
+    /// result limit, and counts what happens to each candidate. `is_expired`
+    /// reports whether a candidate's TTL has passed.
+    #[cfg(feature = "debug_queries")]
+    pub fn explain_nearby(
+        &self,
+        prefix: &str,
+        center: &Point,
+        radius_meters: f64,
+        is_expired: impl Fn(&Point) -> bool,
+    ) -> Result<QueryExplanation> {
+        let mut explanation = QueryExplanation {
+            prefix_point_count: 0,
+            candidate_geohashes: Vec::new(),
+            candidates_evaluated: 0,
+            expired_excluded: 0,
+            distance_excluded: 0,
+            results_returned: 0,
+            index_strategy_used: "none",
+        };
+        let index = match self.spatial_indexes.get(prefix) {
+            Some(index) if !index.points.is_empty() => index,
+            _ => return Ok(explanation),
+        };
+        explanation.prefix_point_count = index.points.len();
+
+        let evaluate = |explanation: &mut QueryExplanation, point: &Point| {
+            explanation.candidates_evaluated += 1;
+            if center.distance_to(point) > radius_meters {
+                explanation.distance_excluded += 1;
+            } else if is_expired(point) {
+                explanation.expired_excluded += 1;
+            } else {
+                explanation.results_returned += 1;
+            }
+        };
+
+        let full_scan = self.should_use_full_scan(prefix, radius_meters);
+        if !full_scan {
+            let precision = self.search_precisions.first().copied().unwrap_or(1);
+            let center_hash = center
+                .to_geohash(precision)
+                .map_err(|_| SpatioError::InvalidGeohash)?;
+            let center_cell =
+                geohash::decode_bbox(&center_hash).map_err(|_| SpatioError::InvalidGeohash)?;
+
+            let mut visited_cells = FxHashSet::default();
+            let mut rings = Vec::new();
+            for ring in 0.. {
+                if ring_min_distance(center, &center_cell, ring) > radius_meters {
+                    break;
+                }
+                let cells = GeohashUtils::ring(&center_hash, ring);
+                if cells.is_empty() {
+                    break;
+                }
+                visited_cells.extend(cells.iter().cloned());
+                rings.extend(cells);
+                if visited_cells.len() > index.points.len() {
+                    // find_nearby gives up on the rings at this point
+                    rings.clear();
+                    break;
                 }
             }
 
-            // If we didn't find enough results, fall back to full scan
-            if results.is_empty() {
-                for (point, data) in index.points.values() {
-                    let distance = center.distance_to(point);
-                    if distance <= radius_meters {
-                        results.push((*point, data.clone()));
+            if !rings.is_empty() {
+                explanation.index_strategy_used = "geohash_ring_search";
+                let mut seen_keys = FxHashSet::default();
+                let mut seen_cells = FxHashSet::default();
+                for cell in rings {
+                    if !seen_cells.insert(cell.clone()) {
+                        continue;
                     }
+                    for (key, (point, _)) in points_in_cell(index, &cell) {
+                        if seen_keys.insert(key) {
+                            evaluate(&mut explanation, point);
+                        }
+                    }
+                    explanation.candidate_geohashes.push(cell);
                 }
+                return Ok(explanation);
             }
         }
 
-        // Sort by distance and limit results
-        results.sort_by(|a, b| {
-            let dist_a = center.distance_to(&a.0);
-            let dist_b = center.distance_to(&b.0);
-            dist_a
-                .partial_cmp(&dist_b)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-
-        results.truncate(limit);
-        Ok(results)
+        #[cfg(feature = "parallel")]
+        let parallel = self.parallel_queries && index.points.len() > SMALL_DATASET_THRESHOLD;
+        #[cfg(not(feature = "parallel"))]
+        let parallel = false;
+        explanation.index_strategy_used = if full_scan && parallel {
+            "parallel_full_scan"
+        } else {
+            "full_scan"
+        };
+        for (point, _) in index.points.values() {
+            evaluate(&mut explanation, point);
+        }
+        Ok(explanation)
     }
 
-    /// Find all points within a bounding box
+    /// Find all points within a bounding box.
+    ///
+    /// Prefixes indexed with `IndexStrategy::S2` only look up the cells
+    /// covering the box; geohash indexes are scanned in full.
     pub fn find_within_bounds(
         &self,
         prefix: &str,
@@ -227,13 +716,31 @@ impl IndexManager {
         max_lon: f64,
         limit: usize,
     ) -> Result<Vec<(Point, Bytes)>> {
+        let mut results = Vec::new();
+
+        #[cfg(feature = "s2")]
+        if let Some(index) = self.s2_indexes.get(prefix) {
+            let bbox = BoundingBox::new(min_lat, min_lon, max_lat, max_lon);
+            let covering =
+                S2Covering::for_bounding_box(&bbox, 0, index.level, S2_COVERING_MAX_CELLS);
+            for cell in covering {
+                let cells = index.points.range(cell.range_min().0..=cell.range_max().0);
+                for (point, data) in cells.map(|(_, entry)| entry) {
+                    if point.within_bounds(min_lat, min_lon, max_lat, max_lon) {
+                        results.push((*point, data.clone()));
+                        if results.len() >= limit {
+                            return Ok(results);
+                        }
+                    }
+                }
+            }
+        }
+
         let index = match self.spatial_indexes.get(prefix) {
             Some(index) => index,
-            None => return Ok(Vec::new()),
+            None => return Ok(results),
         };
 
-        let mut results = Vec::new();
-
         // Check all points in the index
         for (point, data) in index.points.values() {
             if point.within_bounds(min_lat, min_lon, max_lat, max_lon) {
@@ -247,6 +754,34 @@ impl IndexManager {
         Ok(results)
     }
 
+    /// Find points stored in any of the given geohash cells, such as a
+    /// `GeohashUtils::covering`. Cells may have different precisions.
+    pub fn find_in_geohash_cells(
+        &self,
+        prefix: &str,
+        cells: &[String],
+        limit: usize,
+    ) -> Vec<(Point, Bytes)> {
+        let Some(index) = self.spatial_indexes.get(prefix) else {
+            return Vec::new();
+        };
+
+        // A point stored under a coarser geohash is in several cells
+        let mut seen = FxHashSet::default();
+        let mut results = Vec::new();
+        for cell in cells {
+            for (key, (point, data)) in points_in_cell(index, cell) {
+                if seen.insert(key) {
+                    results.push((*point, data.clone()));
+                    if results.len() >= limit {
+                        return results;
+                    }
+                }
+            }
+        }
+        results
+    }
+
     /// Check if there are any points within a circular region
     pub fn contains_point(&self, prefix: &str, center: &Point, radius_meters: f64) -> Result<bool> {
         let index = match self.spatial_indexes.get(prefix) {
@@ -414,17 +949,333 @@ impl IndexManager {
         Ok(count)
     }
 
+    /// Insert a geometry into the index.
+    ///
+    /// The center of the geometry's bounding box is indexed as its
+    /// representative point and the bounding box is kept alongside it.
+    /// Inserting an id that is already indexed replaces the geometry.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InsufficientPoints` if the geometry is empty,
+    /// or `SpatioError::InvalidGeohash` if its bounding box lies outside
+    /// valid coordinates.
+    pub fn insert_geometry(
+        &mut self,
+        prefix: &str,
+        id: &str,
+        geometry: &Geometry,
+        data: &Bytes,
+    ) -> Result<()> {
+        let bounds = Self::geometry_bounds(geometry)?;
+        let center = bounds.center();
+
+        let index = self
+            .geometry_indexes
+            .entry(prefix.to_string())
+            .or_insert_with(|| GeometryIndex {
+                entries: BTreeMap::new(),
+                max_half_extent: (0.0, 0.0),
+            });
+
+        let (half_lat, half_lon) = &mut index.max_half_extent;
+        *half_lat = half_lat.max((bounds.max.lat - bounds.min.lat) / 2.0);
+        *half_lon = half_lon.max((bounds.max.lon - bounds.min.lon) / 2.0);
+
+        index.entries.insert(
+            id.to_string(),
+            GeometryEntry {
+                center,
+                bounds,
+                geometry: geometry.clone(),
+                data: data.clone(),
+            },
+        );
+        Ok(())
+    }
+
+    /// Bounding box of a geometry that can be indexed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InsufficientPoints` if the geometry is empty,
+    /// or `SpatioError::InvalidGeohash` if its bounding box lies outside
+    /// valid coordinates.
+    pub(crate) fn geometry_bounds(geometry: &Geometry) -> Result<BoundingBox> {
+        let bounds = geometry.bounds().ok_or(SpatioError::InsufficientPoints)?;
+        for corner in [bounds.min, bounds.max] {
+            corner
+                .to_geohash(1)
+                .map_err(|_| SpatioError::InvalidGeohash)?;
+        }
+        Ok(bounds)
+    }
+
+    /// Remove a geometry from the index
+    pub fn remove_geometry(&mut self, prefix: &str, id: &str) {
+        if let Some(index) = self.geometry_indexes.get_mut(prefix) {
+            index.entries.remove(id);
+        }
+    }
+
+    /// Find the geometries under a prefix that intersect a bounding box.
+    ///
+    /// Candidates are narrowed by their representative point and bounding
+    /// box before the exact intersection test. Results are ordered by id.
+    pub fn find_geometries_intersecting(
+        &self,
+        prefix: &str,
+        query: &BoundingBox,
+    ) -> Vec<(String, Geometry, Bytes)> {
+        let Some(index) = self.geometry_indexes.get(prefix) else {
+            return Vec::new();
+        };
+
+        let (half_lat, half_lon) = index.max_half_extent;
+        let reach = BoundingBox::new(
+            query.min.lat - half_lat,
+            query.min.lon - half_lon,
+            query.max.lat + half_lat,
+            query.max.lon + half_lon,
+        );
+        let query_geometry = bounding_box_geometry(query);
+
+        index
+            .entries
+            .iter()
+            .filter(|(_, entry)| reach.contains(&entry.center) && entry.bounds.intersects(query))
+            .filter(|(_, entry)| {
+                query_geometry
+                    .as_ref()
+                    .is_none_or(|q| entry.geometry.intersects(q))
+            })
+            .map(|(id, entry)| (id.clone(), entry.geometry.clone(), entry.data.clone()))
+            .collect()
+    }
+
+    /// Get all points indexed under a prefix
+    pub fn points_for_prefix(&self, prefix: &str) -> Vec<Point> {
+        match self.spatial_indexes.get(prefix) {
+            Some(index) => index.points.values().map(|(point, _)| *point).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Get all points indexed under a prefix with their data
+    pub fn entries_for_prefix(&self, prefix: &str) -> Vec<(Point, Bytes)> {
+        match self.spatial_indexes.get(prefix) {
+            Some(index) => index.points.values().cloned().collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Iterate the points indexed under a prefix with their data, without
+    /// copying them
+    pub fn iter_prefix_points(&self, prefix: &str) -> impl Iterator<Item = &(Point, Bytes)> {
+        self.spatial_indexes
+            .get(prefix)
+            .into_iter()
+            .flat_map(|index| index.points.values())
+    }
+
+    /// Iterate every point in the geohash indexes with its prefix
+    pub fn iter_geohash_points(&self) -> impl Iterator<Item = (&str, &Point)> {
+        self.spatial_indexes.iter().flat_map(|(prefix, index)| {
+            index
+                .points
+                .values()
+                .map(move |(point, _)| (prefix.as_str(), point))
+        })
+    }
+
+    /// Iterate the geometries indexed under a prefix with their ids and
+    /// data, ordered by id
+    pub fn iter_prefix_geometries(
+        &self,
+        prefix: &str,
+    ) -> impl Iterator<Item = (&str, &Geometry, &Bytes)> {
+        self.geometry_indexes
+            .get(prefix)
+            .into_iter()
+            .flat_map(|index| index.entries.iter())
+            .map(|(id, entry)| (id.as_str(), &entry.geometry, &entry.data))
+    }
+
+    /// Find every pair of points from two prefixes within a distance of
+    /// each other, nearest pairs first.
+    ///
+    /// Points are bucketed by geohash at the finest precision whose cells
+    /// are at least `max_distance_meters` across, so each point of
+    /// `prefix_a` is only compared with the points of `prefix_b` in its own
+    /// cell and the 8 around it. Points too close to a cell corner for
+    /// that to be exact, and whole joins whose distance is too large for
+    /// any precision, fall back to comparing against every point.
+    pub fn spatial_join(
+        &self,
+        prefix_a: &str,
+        prefix_b: &str,
+        max_distance_meters: f64,
+    ) -> Vec<SpatialJoinResult> {
+        let (Some(index_a), Some(index_b)) = (
+            self.spatial_indexes.get(prefix_a),
+            self.spatial_indexes.get(prefix_b),
+        ) else {
+            return Vec::new();
+        };
+
+        // Cells must not be finer than any key of `prefix_b`, and are
+        // narrowest at the highest latitude either side reaches
+        let key_precision = index_b.points.keys().map(String::len).min().unwrap_or(1);
+        let max_lat = index_a
+            .points
+            .values()
+            .chain(index_b.points.values())
+            .map(|(point, _)| point.lat.abs())
+            .fold(0.0, f64::max);
+        let precision = join_precision(max_distance_meters, max_lat, key_precision);
+
+        let mut results = Vec::new();
+        for (point_a, data_a) in index_a.points.values() {
+            let mut compare = |point_b: &Point, data_b: &Bytes| {
+                let distance = point_a.distance_to(point_b);
+                if distance <= max_distance_meters {
+                    results.push(SpatialJoinResult {
+                        point_a: *point_a,
+                        data_a: data_a.clone(),
+                        point_b: *point_b,
+                        data_b: data_b.clone(),
+                        distance,
+                    });
+                }
+            };
+
+            match precision.and_then(|p| join_cells(point_a, p, max_distance_meters)) {
+                Some(cells) => {
+                    for cell in &cells {
+                        for (_, (point_b, data_b)) in points_in_cell(index_b, cell) {
+                            compare(point_b, data_b);
+                        }
+                    }
+                }
+                None => {
+                    for (point_b, data_b) in index_b.points.values() {
+                        compare(point_b, data_b);
+                    }
+                }
+            }
+        }
+
+        results.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        results
+    }
+
     /// Remove a point from the spatial index
     pub fn remove_point(&mut self, prefix: &str, point: &Point) -> Result<()> {
+        let precision = self.prefix_precision(prefix);
         if let Some(index) = self.spatial_indexes.get_mut(prefix) {
             let geohash = point
-                .to_geohash(self.geohash_precision)
+                .to_geohash(precision)
                 .map_err(|_| SpatioError::InvalidGeohash)?;
             index.points.remove(&geohash);
+            self.invalidate_lsh_index(prefix);
         }
         Ok(())
     }
 
+    /// Find up to `k` points near `query` using locality-sensitive
+    /// hashing, nearest first.
+    ///
+    /// Only points sharing an LSH bucket with the query in at least one of
+    /// the first `num_tables` tables are measured, so results can miss
+    /// closer points, especially ones across a geohash cell boundary from
+    /// the query. The tables are built on first use, with at least
+    /// `Config::lsh_num_tables` tables, and cached until the prefix changes.
+    pub fn approximate_nearest_neighbor(
+        &self,
+        prefix: &str,
+        query: &Point,
+        k: usize,
+        num_tables: usize,
+    ) -> Result<Vec<(Point, Bytes, f64)>> {
+        let Some(index) = self.spatial_indexes.get(prefix) else {
+            return Ok(Vec::new());
+        };
+        if k == 0 || num_tables == 0 {
+            return Ok(Vec::new());
+        }
+
+        let lsh = self.lsh_index(prefix, index, num_tables)?;
+        let query_hash = query
+            .to_geohash(self.prefix_precision(prefix))
+            .map_err(|_| SpatioError::InvalidGeohash)?;
+
+        let mut candidates = FxHashSet::default();
+        for (table, buckets) in lsh.tables.iter().take(num_tables).enumerate() {
+            if let Some(keys) = buckets.get(&lsh_bucket(&query_hash, table)) {
+                candidates.extend(keys.iter().map(String::as_str));
+            }
+        }
+
+        let mut results: Vec<(Point, Bytes, f64)> = candidates
+            .into_iter()
+            .filter_map(|key| index.points.get(key))
+            .map(|(point, data)| (*point, data.clone(), query.distance_to(point)))
+            .collect();
+        results.sort_by(|a, b| a.2.total_cmp(&b.2));
+        results.truncate(k);
+        Ok(results)
+    }
+
+    /// Build the LSH tables of a prefix now instead of on the next
+    /// approximate query, replacing any cached tables
+    pub fn rebuild_lsh_index(&self, prefix: &str) -> Result<()> {
+        let mut cache = sync::write(&self.lsh_indexes)?;
+        match self.spatial_indexes.get(prefix) {
+            Some(index) => {
+                let lsh = LshIndex::build(index, self.lsh_num_tables);
+                cache.insert(prefix.to_string(), Arc::new(lsh));
+            }
+            None => {
+                cache.remove(prefix);
+            }
+        }
+        Ok(())
+    }
+
+    /// Cached LSH tables of a prefix, building them if missing or if fewer
+    /// than `num_tables` were built
+    fn lsh_index(
+        &self,
+        prefix: &str,
+        index: &SpatialIndex,
+        num_tables: usize,
+    ) -> Result<Arc<LshIndex>> {
+        if let Some(lsh) = sync::read(&self.lsh_indexes)?.get(prefix)
+            && lsh.tables.len() >= num_tables
+        {
+            return Ok(Arc::clone(lsh));
+        }
+
+        let mut cache = sync::write(&self.lsh_indexes)?;
+        // Another query may have built the tables while we waited
+        if let Some(lsh) = cache.get(prefix)
+            && lsh.tables.len() >= num_tables
+        {
+            return Ok(Arc::clone(lsh));
+        }
+        let lsh = Arc::new(LshIndex::build(index, num_tables.max(self.lsh_num_tables)));
+        cache.insert(prefix.to_string(), Arc::clone(&lsh));
+        Ok(lsh)
+    }
+
+    /// Drop the cached LSH tables of a prefix after its points change
+    fn invalidate_lsh_index(&mut self, prefix: &str) {
+        let cache = sync::get_mut(&mut self.lsh_indexes);
+        if !cache.is_empty() {
+            cache.remove(prefix);
+        }
+    }
+
     /// Get statistics about spatial indexes
     pub fn stats(&self) -> IndexStats {
         let mut total_points = 0;
@@ -434,17 +1285,411 @@ impl IndexManager {
             total_points += index.points.len();
         }
 
+        #[cfg(feature = "s2")]
+        let (index_count, total_points) = (
+            index_count + self.s2_indexes.len(),
+            total_points
+                + self
+                    .s2_indexes
+                    .values()
+                    .map(|i| i.points.len())
+                    .sum::<usize>(),
+        );
+
+        #[cfg(feature = "h3")]
+        let (index_count, total_points) = (
+            index_count + self.h3_indexes.len(),
+            total_points
+                + self
+                    .h3_indexes
+                    .values()
+                    .map(|i| i.points.len())
+                    .sum::<usize>(),
+        );
+
         IndexStats {
             index_count,
             total_points,
         }
     }
+
+    /// Save every spatial index to a snapshot file.
+    ///
+    /// The snapshot records the AOF position it reflects, so a later open
+    /// can apply it and only index the records written after that point.
+    /// The file is written to a temporary path and renamed into place.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the snapshot cannot be serialized or written.
+    #[cfg(feature = "aof")]
+    pub fn save_to_path(&self, path: &Path, aof_position: &AOFPosition) -> Result<()> {
+        let geohash = self
+            .spatial_indexes
+            .iter()
+            .map(|(prefix, index)| {
+                let entries = index
+                    .points
+                    .iter()
+                    .map(|(hash, (point, _))| (hash.clone(), *point))
+                    .collect();
+                (prefix.clone(), entries)
+            })
+            .collect();
+
+        #[cfg(feature = "s2")]
+        let s2 = self
+            .s2_indexes
+            .iter()
+            .map(|(prefix, index)| {
+                let entries = index
+                    .points
+                    .iter()
+                    .map(|(cell, (point, _))| (*cell, *point))
+                    .collect();
+                (prefix.clone(), index.level, entries)
+            })
+            .collect();
+        #[cfg(not(feature = "s2"))]
+        let s2 = Vec::new();
+
+        #[cfg(feature = "h3")]
+        let h3 = self
+            .h3_indexes
+            .iter()
+            .map(|(prefix, index)| {
+                let entries = index
+                    .points
+                    .iter()
+                    .map(|(cell, (point, _))| (*cell, *point))
+                    .collect();
+                (prefix.clone(), u8::from(index.resolution), entries)
+            })
+            .collect();
+        #[cfg(not(feature = "h3"))]
+        let h3 = Vec::new();
+
+        let geometry = self
+            .geometry_indexes
+            .iter()
+            .map(|(prefix, index)| (prefix.clone(), index.entries.keys().cloned().collect()))
+            .collect();
+
+        let snapshot = IndexSnapshot {
+            version: INDEX_SNAPSHOT_VERSION,
+            geohash_precision: self.geohash_precision,
+            prefix_precisions: self
+                .prefix_precisions
+                .iter()
+                .map(|(prefix, precision)| (prefix.clone(), *precision))
+                .collect(),
+            aof_position: *aof_position,
+            geohash,
+            s2,
+            h3,
+            geometry,
+        };
+        let bytes = bincode::serialize(&snapshot).map_err(|e| {
+            SpatioError::SerializationErrorWithContext(format!(
+                "Failed to serialize spatial index snapshot: {}",
+                e
+            ))
+        })?;
+
+        let temp_path = path.with_extension("sidx.tmp");
+        std::fs::write(&temp_path, bytes)?;
+        std::fs::rename(&temp_path, path)?;
+        Ok(())
+    }
+
+    /// Add the entries of a snapshot to the indexes.
+    ///
+    /// `point_value` returns the value stored under a point's key and
+    /// `geometry_value` the geometry and data stored under a geometry's
+    /// key. Entries whose key they do not find are skipped, as the key
+    /// was removed without the snapshot being refreshed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the snapshot holds an invalid H3 resolution.
+    #[cfg(feature = "aof")]
+    pub fn apply_snapshot(
+        &mut self,
+        snapshot: IndexSnapshot,
+        point_value: impl Fn(&[u8]) -> Option<Bytes>,
+        geometry_value: impl Fn(&[u8]) -> Option<(Geometry, Bytes)>,
+    ) -> Result<()> {
+        self.prefix_precisions.extend(snapshot.prefix_precisions);
+
+        for (prefix, entries) in snapshot.geohash {
+            let index = self
+                .spatial_indexes
+                .entry(prefix.clone())
+                .or_insert_with(|| SpatialIndex {
+                    points: BTreeMap::new(),
+                });
+            for (hash, point) in entries {
+                let Ok(key_hash) = point.to_geohash(8) else {
+                    continue;
+                };
+                if let Some(data) = point_value(SpatialKey::geohash(&prefix, &key_hash).as_bytes())
+                {
+                    index.points.insert(hash, (point, data));
+                }
+            }
+            self.invalidate_lsh_index(&prefix);
+        }
+
+        #[cfg(feature = "s2")]
+        for (prefix, level, entries) in snapshot.s2 {
+            let index = self
+                .s2_indexes
+                .entry(prefix.clone())
+                .or_insert_with(|| S2Index {
+                    level,
+                    points: BTreeMap::new(),
+                });
+            for (cell, point) in entries {
+                let key = SpatialKey::s2_cell(&prefix, s2::cellid::CellID(cell));
+                if let Some(data) = point_value(key.as_bytes()) {
+                    index.points.insert(cell, (point, data));
+                }
+            }
+        }
+
+        #[cfg(feature = "h3")]
+        for (prefix, resolution, entries) in snapshot.h3 {
+            let resolution =
+                h3o::Resolution::try_from(resolution).map_err(|_| SpatioError::InvalidFormat)?;
+            let index = self
+                .h3_indexes
+                .entry(prefix.clone())
+                .or_insert_with(|| H3Index {
+                    resolution,
+                    points: FxHashMap::default(),
+                });
+            for (cell, point) in entries {
+                if let Some(data) = point_value(SpatialKey::h3_cell(&prefix, cell).as_bytes()) {
+                    index.points.insert(cell, (point, data));
+                }
+            }
+        }
+
+        for (prefix, ids) in snapshot.geometry {
+            for id in ids {
+                let key = SpatialKey::geometry(&prefix, &id);
+                if let Some((geometry, data)) = geometry_value(key.as_bytes()) {
+                    self.insert_geometry(&prefix, &id, &geometry, &data)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "aof")]
+impl IndexSnapshot {
+    /// Read a snapshot written by `IndexManager::save_to_path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or decoded, or if it was
+    /// written with a different format version or geohash precision than
+    /// `config` uses.
+    pub fn load_from_path(path: &Path, config: &Config) -> Result<Self> {
+        let bytes = std::fs::read(path)?;
+        let snapshot: IndexSnapshot = bincode::deserialize(&bytes).map_err(|e| {
+            SpatioError::SerializationErrorWithContext(format!(
+                "Failed to deserialize spatial index snapshot: {}",
+                e
+            ))
+        })?;
+
+        if snapshot.version != INDEX_SNAPSHOT_VERSION {
+            return Err(SpatioError::InvalidFormat);
+        }
+        if snapshot.geohash_precision != config.geohash_precision {
+            return Err(SpatioError::Other(format!(
+                "Spatial index snapshot uses geohash precision {}, expected {}",
+                snapshot.geohash_precision, config.geohash_precision
+            )));
+        }
+        Ok(snapshot)
+    }
+
+    /// AOF position the snapshot reflects
+    pub fn aof_position(&self) -> AOFPosition {
+        self.aof_position
+    }
+}
+
+/// The rectangle covered by a bounding box, or `None` if it is degenerate
+fn bounding_box_geometry(bbox: &BoundingBox) -> Option<Geometry> {
+    let ring = LinearRing::new(vec![
+        Coordinate::new(bbox.min.lon, bbox.min.lat),
+        Coordinate::new(bbox.max.lon, bbox.min.lat),
+        Coordinate::new(bbox.max.lon, bbox.max.lat),
+        Coordinate::new(bbox.min.lon, bbox.max.lat),
+    ])
+    .ok()?;
+    Some(Geometry::Polygon(Polygon::new(ring, Vec::new())))
+}
+
+/// Points stored in a geohash cell, including points stored under a
+/// coarser geohash that contains the cell
+fn points_in_cell<'a>(
+    index: &'a SpatialIndex,
+    cell: &str,
+) -> impl Iterator<Item = (&'a str, &'a (Point, Bytes))> {
+    let contained = index
+        .points
+        .range::<str, _>((Bound::Included(cell), Bound::Unbounded))
+        .take_while(move |(key, _)| key.starts_with(cell));
+    let containing =
+        (1..cell.len()).filter_map(move |len| index.points.get_key_value(&cell[..len]));
+    contained
+        .chain(containing)
+        .map(|(key, entry)| (key.as_str(), entry))
+}
+
+impl LshIndex {
+    fn build(index: &SpatialIndex, num_tables: usize) -> Self {
+        let tables = (0..num_tables)
+            .map(|table| {
+                let mut buckets: FxHashMap<u64, Vec<String>> = FxHashMap::default();
+                for key in index.points.keys() {
+                    buckets
+                        .entry(lsh_bucket(key, table))
+                        .or_default()
+                        .push(key.clone());
+                }
+                buckets
+            })
+            .collect();
+        Self { tables }
+    }
+}
+
+/// Bucket of a geohash in one LSH table.
+///
+/// Each row is a MinHash of the geohash's prefixes: the smallest hash of
+/// any prefix under that row's hash function. Two geohashes get the same
+/// row value with probability equal to the Jaccard similarity of their
+/// prefix sets, which grows with the length of the prefix they share.
+fn lsh_bucket(geohash: &str, table: usize) -> u64 {
+    (0..LSH_ROWS_PER_TABLE).fold(0, |bucket, row| {
+        let seed = mix64((table * LSH_ROWS_PER_TABLE + row) as u64 + 1);
+        let min_hash = (1..=geohash.len())
+            .map(|len| mix64(hash_str(&geohash[..len]) ^ seed))
+            .min()
+            .unwrap_or(0);
+        mix64(bucket ^ min_hash)
+    })
+}
+
+/// FNV-1a hash of a string, stable across runs and platforms
+fn hash_str(value: &str) -> u64 {
+    value.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// SplitMix64 finalizer, spreading the bits of `value`
+fn mix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Finest geohash precision, up to `max_precision`, whose cells are at
+/// least `distance_meters` tall and wide at latitude `max_lat`. `None` if
+/// even the coarsest cells are too small.
+fn join_precision(distance_meters: f64, max_lat: f64, max_precision: usize) -> Option<usize> {
+    const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+    let lon_scale = max_lat.to_radians().cos();
+    (1..=max_precision.min(12)).rev().find(|&precision| {
+        let bits = 5 * precision as i32;
+        let width = 360.0 / 2f64.powi((bits + 1) / 2);
+        let height = 180.0 / 2f64.powi(bits / 2);
+        height.to_radians() * EARTH_RADIUS_M >= distance_meters
+            && width.to_radians() * EARTH_RADIUS_M * lon_scale >= distance_meters
+    })
+}
+
+/// The geohash cell of `point` at `precision` and the 8 around it, or
+/// `None` if a point outside them could still be within `distance_meters`
+fn join_cells(point: &Point, precision: usize, distance_meters: f64) -> Option<Vec<String>> {
+    let hash = point.to_geohash(precision).ok()?;
+    let cell = geohash::decode_bbox(&hash).ok()?;
+    if ring_min_distance(point, &cell, 2) <= distance_meters {
+        return None;
+    }
+
+    let mut cells = GeohashUtils::ring(&hash, 1);
+    if !cells.contains(&hash) {
+        cells.push(hash);
+    }
+    Some(cells)
+}
+
+/// Lower bound on the distance from `center` to any point in the given
+/// ring of cells around `cell`, the geohash cell containing `center`.
+///
+/// Rings before `ring` fill a latitude/longitude box around the center, so
+/// anything in the ring lies outside that box. The bound is the distance to
+/// the nearest box edge, or infinity once the box covers the globe.
+fn ring_min_distance(center: &Point, cell: &geo::Rect<f64>, ring: usize) -> f64 {
+    const EARTH_RADIUS_M: f64 = 6_371_000.0;
+
+    if ring == 0 {
+        return 0.0;
+    }
+
+    let inner = (ring - 1) as f64;
+    let north = cell.max().y + inner * cell.height();
+    let south = cell.min().y - inner * cell.height();
+    let east = cell.max().x + inner * cell.width();
+    let west = cell.min().x - inner * cell.width();
+
+    let north_gap = if north >= 90.0 {
+        f64::INFINITY
+    } else {
+        (north - center.lat).to_radians() * EARTH_RADIUS_M
+    };
+    let south_gap = if south <= -90.0 {
+        f64::INFINITY
+    } else {
+        (center.lat - south).to_radians() * EARTH_RADIUS_M
+    };
+
+    // Distance to the meridian `dlon` degrees away. Past 90 degrees the
+    // nearest point of that meridian is the closer pole.
+    let meridian_gap = |dlon: f64| {
+        if dlon >= 90.0 {
+            (90.0 - center.lat.abs()).to_radians() * EARTH_RADIUS_M
+        } else {
+            (dlon.to_radians().sin() * center.lat.to_radians().cos()).asin() * EARTH_RADIUS_M
+        }
+    };
+    let (east_gap, west_gap) = if east - west >= 360.0 {
+        (f64::INFINITY, f64::INFINITY)
+    } else {
+        (
+            meridian_gap(east - center.lon),
+            meridian_gap(center.lon - west),
+        )
+    };
+
+    // Shrink slightly so rounding never skips a point on the boundary
+    north_gap.min(south_gap).min(east_gap).min(west_gap) * (1.0 - 1e-9)
 }
 
 impl SpatialIndex {
     fn new() -> Self {
         Self {
-            points: FxHashMap::default(),
+            points: BTreeMap::new(),
         }
     }
 }
@@ -536,6 +1781,311 @@ mod tests {
         Ok(())
     }
 
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_full_scan_matches_sequential() -> Result<()> {
+        let mut sequential = IndexManager::with_config(&Config::default());
+        let mut parallel =
+            IndexManager::with_config(&Config::default().with_parallel_queries(true));
+
+        for i in 0..(SMALL_DATASET_THRESHOLD * 3) {
+            let point = Point::new(
+                40.0 + (i / 60) as f64 * 0.01,
+                -74.0 + (i % 60) as f64 * 0.01,
+            );
+            let data = Bytes::from(format!("p{i}"));
+            sequential.insert_point("grid", &point, &data)?;
+            parallel.insert_point("grid", &point, &data)?;
+        }
+
+        // A radius above LARGE_RADIUS_THRESHOLD forces the full scan
+        let center = Point::new(40.2, -73.7);
+        let radius = LARGE_RADIUS_THRESHOLD * 2.0;
+        let expected = sequential.find_nearby("grid", &center, radius, usize::MAX)?;
+        let actual = parallel.find_nearby("grid", &center, radius, usize::MAX)?;
+        assert_eq!(expected.len(), SMALL_DATASET_THRESHOLD * 3);
+        assert_eq!(
+            actual
+                .iter()
+                .map(|(p, _)| center.distance_to(p))
+                .collect::<Vec<_>>(),
+            expected
+                .iter()
+                .map(|(p, _)| center.distance_to(p))
+                .collect::<Vec<_>>()
+        );
+
+        // With a limit the parallel scan keeps the nearest points
+        let nearest = parallel.find_nearby("grid", &center, radius, 5)?;
+        assert_eq!(nearest.len(), 5);
+        assert_eq!(nearest[0].0, actual[0].0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ring_search_matches_full_scan() -> Result<()> {
+        let mut manager = IndexManager::new();
+        for i in 0..(SMALL_DATASET_THRESHOLD * 2) {
+            let point = Point::new(
+                40.0 + (i / 50) as f64 * 0.005,
+                -74.0 + (i % 50) as f64 * 0.005,
+            );
+            manager.insert_point("grid", &point, &Bytes::from(format!("p{i}")))?;
+        }
+        let index = &manager.spatial_indexes["grid"];
+
+        // Radii spanning several rings of precision-6 cells
+        let center = Point::new(40.05, -73.88);
+        for radius in [100.0, 2_000.0, 10_000.0] {
+            for limit in [1, 10, usize::MAX] {
+                let expected = IndexManager::scan_within_radius(index, &center, radius, limit);
+                let actual = manager.find_nearby("grid", &center, radius, limit)?;
+                // Equidistant points may come back in either order
+                assert_eq!(
+                    actual
+                        .iter()
+                        .map(|(p, _)| center.distance_to(p))
+                        .collect::<Vec<_>>(),
+                    expected
+                        .iter()
+                        .map(|(p, _)| center.distance_to(p))
+                        .collect::<Vec<_>>(),
+                    "radius {radius}, limit {limit}"
+                );
+            }
+        }
+
+        // The nearest point is several rings away from an empty area
+        let far = Point::new(39.9, -74.0);
+        let nearest = manager.find_nearby("grid", &far, 20_000.0, 1)?;
+        assert_eq!(nearest[0].0, Point::new(40.0, -74.0));
+
+        // A zero limit returns nothing rather than searching rings
+        assert!(manager.find_nearby("grid", &center, 1_000.0, 0)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_ring_min_distance_is_a_lower_bound() {
+        let center = Point::new(40.0123, -73.9876);
+        let cell = geohash::decode_bbox(&center.to_geohash(6).unwrap()).unwrap();
+        assert_eq!(ring_min_distance(&center, &cell, 0), 0.0);
+
+        for ring in 1..5 {
+            let bound = ring_min_distance(&center, &cell, ring);
+            assert!(bound > 0.0);
+            for hash in GeohashUtils::ring(&center.to_geohash(6).unwrap(), ring) {
+                let corner = geohash::decode_bbox(&hash).unwrap();
+                for (lat, lon) in [
+                    (corner.min().y, corner.min().x),
+                    (corner.max().y, corner.max().x),
+                    (corner.min().y, corner.max().x),
+                    (corner.max().y, corner.min().x),
+                ] {
+                    assert!(center.distance_to(&Point::new(lat, lon)) >= bound);
+                }
+            }
+        }
+
+        // Near the pole the box soon reaches it, leaving only the south edge
+        let polar = Point::new(89.9, 10.0);
+        let polar_cell = geohash::decode_bbox(&polar.to_geohash(2).unwrap()).unwrap();
+        assert!(ring_min_distance(&polar, &polar_cell, 2).is_finite());
+    }
+
+    #[cfg(feature = "s2")]
+    #[test]
+    fn test_s2_find_within_bounds() -> Result<()> {
+        let mut manager = IndexManager::new();
+        let strategy = IndexStrategy::S2 { level: 16 };
+        for i in 0..100 {
+            let point = Point::new(40.0 + i as f64 * 0.1, -74.0 + i as f64 * 0.1);
+            manager.insert_point_with_strategy(
+                "grid",
+                &point,
+                &Bytes::from(format!("p{i}")),
+                strategy,
+            )?;
+        }
+
+        let found = manager.find_within_bounds("grid", 40.95, -73.05, 42.05, -71.95, 100)?;
+        assert_eq!(found.len(), 11);
+        assert!(
+            found
+                .iter()
+                .all(|(p, _)| p.within_bounds(40.95, -73.05, 42.05, -71.95))
+        );
+
+        let limited = manager.find_within_bounds("grid", 40.95, -73.05, 42.05, -71.95, 3)?;
+        assert_eq!(limited.len(), 3);
+
+        // A prefix keeps the level it was first indexed at
+        assert!(
+            manager
+                .insert_point_with_strategy(
+                    "grid",
+                    &Point::new(0.0, 0.0),
+                    &Bytes::new(),
+                    IndexStrategy::S2 { level: 10 },
+                )
+                .is_err()
+        );
+
+        Ok(())
+    }
+
+    #[cfg(feature = "aof")]
+    #[test]
+    fn test_snapshot_round_trip() -> Result<()> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("index.sidx");
+        let config = Config::default();
+        let mut manager = IndexManager::with_config(&config);
+        let nyc = Point::new(40.7128, -74.0060);
+        let london = Point::new(51.5074, -0.1278);
+        manager.insert_point("cities", &nyc, &Bytes::from("NYC"))?;
+        manager.insert_point("cities", &london, &Bytes::from("London"))?;
+        let park = Geometry::Polygon(Polygon::new(
+            LinearRing::new(vec![
+                Coordinate::new(-73.98, 40.76),
+                Coordinate::new(-73.95, 40.76),
+                Coordinate::new(-73.95, 40.80),
+            ])?,
+            Vec::new(),
+        ));
+        manager.insert_geometry("parks", "central", &park, &Bytes::from("Central Park"))?;
+
+        let position = AOFPosition {
+            offset: 42,
+            checksum: 7,
+        };
+        manager.save_to_path(&path, &position)?;
+
+        let snapshot = IndexSnapshot::load_from_path(&path, &config)?;
+        assert_eq!(snapshot.aof_position(), position);
+
+        // Data comes from the stored values; London's key is gone
+        let nyc_key = SpatialKey::geohash("cities", &nyc.to_geohash(8)?);
+        let park_key = SpatialKey::geometry("parks", "central");
+        let mut loaded = IndexManager::with_config(&config);
+        loaded.apply_snapshot(
+            snapshot,
+            |key| (key == nyc_key.as_bytes()).then(|| Bytes::from("stored NYC")),
+            |key| (key == park_key.as_bytes()).then(|| (park.clone(), Bytes::from("stored"))),
+        )?;
+        assert_eq!(loaded.stats().total_points, 1);
+        let nearby = loaded.find_nearby("cities", &nyc, 1000.0, 10)?;
+        assert_eq!(nearby.len(), 1);
+        assert_eq!(nearby[0].0, nyc);
+        assert_eq!(nearby[0].1.as_ref(), b"stored NYC");
+        let parks = loaded
+            .find_geometries_intersecting("parks", &BoundingBox::new(40.7, -74.0, 40.8, -73.9));
+        assert_eq!(parks.len(), 1);
+        assert_eq!(parks[0].1, park);
+
+        // Only positions are stored, not the data
+        let bytes = std::fs::read(&path)?;
+        assert!(!bytes.windows(6).any(|w| w == b"London"));
+
+        // A snapshot built with another precision is rejected
+        assert!(IndexSnapshot::load_from_path(&path, &Config::with_geohash_precision(10)).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_prefix_precision_overrides() -> Result<()> {
+        let config = Config::default();
+        let mut manager = IndexManager::with_config(&config);
+        let nyc = Point::new(40.7128, -74.0060);
+        manager.insert_point("sensors", &nyc, &Bytes::from("NYC"))?;
+        manager.insert_point("cities", &nyc, &Bytes::from("NYC"))?;
+
+        // Existing points are rekeyed at the new precision
+        manager.set_prefix_precision("sensors", 5)?;
+        assert_eq!(manager.prefix_precision("sensors"), 5);
+        assert_eq!(manager.prefix_precision("cities"), config.geohash_precision);
+        let keys: Vec<_> = manager.spatial_indexes["sensors"].points.keys().collect();
+        assert_eq!(keys, vec![&nyc.to_geohash(5)?]);
+
+        let boston = Point::new(42.3601, -71.0589);
+        manager.insert_point("sensors", &boston, &Bytes::from("Boston"))?;
+        assert_eq!(manager.find_nearby("sensors", &nyc, 1000.0, 10)?.len(), 1);
+        manager.remove_point("sensors", &boston)?;
+        assert_eq!(manager.stats().total_points, 2);
+
+        assert!(matches!(
+            manager.set_prefix_precision("sensors", 13),
+            Err(SpatioError::InvalidConfiguration(_))
+        ));
+
+        // Overrides are kept in the snapshot
+        #[cfg(feature = "aof")]
+        {
+            let dir = tempfile::tempdir()?;
+            let path = dir.path().join("index.sidx");
+            let position = AOFPosition {
+                offset: 0,
+                checksum: 0,
+            };
+            manager.save_to_path(&path, &position)?;
+            let snapshot = IndexSnapshot::load_from_path(&path, &config)?;
+            manager = IndexManager::with_config(&config);
+            manager.apply_snapshot(snapshot, |_| Some(Bytes::from("NYC")), |_| None)?;
+            assert_eq!(manager.prefix_precision("sensors"), 5);
+        }
+
+        // Setting the default precision drops the override
+        manager.set_prefix_precision("sensors", config.geohash_precision)?;
+        assert!(manager.prefix_precisions.is_empty());
+        assert_eq!(manager.find_nearby("sensors", &nyc, 1000.0, 10)?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_find_geometries_intersecting() -> Result<()> {
+        let mut manager = IndexManager::new();
+        let route = Geometry::LineString(crate::geometry::LineString::new(vec![
+            Coordinate::new(-74.0, 40.0),
+            Coordinate::new(-70.0, 44.0),
+        ])?);
+        let stop = Geometry::Point(Coordinate::new(-73.99, 40.01));
+        manager.insert_geometry("transit", "route", &route, &Bytes::from("r"))?;
+        manager.insert_geometry("transit", "stop", &stop, &Bytes::from("s"))?;
+
+        // The route's center is far outside the box but its end is inside
+        let near_start = BoundingBox::new(39.9, -74.1, 40.1, -73.9);
+        let found = manager.find_geometries_intersecting("transit", &near_start);
+        let ids: Vec<_> = found.iter().map(|(id, _, _)| id.as_str()).collect();
+        assert_eq!(ids, vec!["route", "stop"]);
+
+        // Inside the route's bounding box but away from the line
+        let off_line = BoundingBox::new(43.0, -74.0, 43.5, -73.5);
+        assert!(
+            manager
+                .find_geometries_intersecting("transit", &off_line)
+                .is_empty()
+        );
+
+        manager.remove_geometry("transit", "route");
+        assert_eq!(
+            manager
+                .find_geometries_intersecting("transit", &near_start)
+                .len(),
+            1
+        );
+
+        let empty = Geometry::MultiPoint(Vec::new());
+        assert!(matches!(
+            manager.insert_geometry("transit", "empty", &empty, &Bytes::new()),
+            Err(SpatioError::InsufficientPoints)
+        ));
+        Ok(())
+    }
+
     #[test]
     fn test_constants_are_reasonable() {
         // Ensure constants are within valid geohash precision range
@@ -582,6 +2132,152 @@ mod tests {
         assert_eq!(manager.search_precisions, vec![10, 11, 12]);
     }
 
+    #[test]
+    fn test_search_precisions_from_config() {
+        let config = Config::with_geohash_precision(10).with_search_precisions(vec![5, 10]);
+        let mut manager = IndexManager::with_config(&config);
+        assert_eq!(manager.search_precisions, vec![5, 10]);
+
+        let nyc = Point::new(40.7128, -74.0060);
+        manager
+            .insert_point("cities", &nyc, &Bytes::from("NYC"))
+            .unwrap();
+        assert_eq!(
+            manager
+                .find_nearby("cities", &nyc, 1000.0, 10)
+                .unwrap()
+                .len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_spatial_join_matches_brute_force() {
+        // Small linear congruential generator to keep the test deterministic
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        let mut next = || {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            (state >> 11) as f64 / (1u64 << 53) as f64
+        };
+
+        let mut manager = IndexManager::new();
+        let mut a = Vec::new();
+        let mut b = Vec::new();
+        // Clusters around a city, the antimeridian and near the pole
+        for (lat, lon, spread) in [(40.7, -74.0, 0.05), (0.0, 179.99, 0.05), (89.9, 0.0, 0.1)] {
+            for i in 0..40 {
+                let point = Point::new(
+                    (lat + (next() - 0.5) * spread).min(90.0),
+                    ((lon + (next() - 0.5) * spread + 180.0).rem_euclid(360.0)) - 180.0,
+                );
+                let data = Bytes::from(format!("{}:{}", lat, i));
+                let (prefix, list) = if i % 2 == 0 {
+                    ("a", &mut a)
+                } else {
+                    ("b", &mut b)
+                };
+                manager.insert_point(prefix, &point, &data).unwrap();
+                list.push(point);
+            }
+        }
+
+        for distance in [0.0, 50.0, 500.0, 2_000.0, 20_000_000.0] {
+            let joined = manager.spatial_join("a", "b", distance);
+            let expected = a
+                .iter()
+                .flat_map(|pa| b.iter().map(move |pb| pa.distance_to(pb)))
+                .filter(|d| *d <= distance)
+                .count();
+            assert_eq!(joined.len(), expected, "distance {}", distance);
+            assert!(joined.iter().all(|r| r.distance <= distance));
+            assert!(joined.windows(2).all(|w| w[0].distance <= w[1].distance));
+        }
+
+        assert!(manager.spatial_join("a", "missing", 1_000.0).is_empty());
+        assert!(manager.spatial_join("missing", "b", 1_000.0).is_empty());
+    }
+
+    #[test]
+    fn test_approximate_nearest_neighbor() {
+        let mut manager = IndexManager::new();
+        assert!(
+            manager
+                .approximate_nearest_neighbor("cities", &Point::new(0.0, 0.0), 3, 5)
+                .unwrap()
+                .is_empty()
+        );
+
+        // A grid of points around Manhattan and one far away
+        for i in 0..20 {
+            for j in 0..20 {
+                let point = Point::new(40.70 + i as f64 * 0.001, -74.00 + j as f64 * 0.001);
+                let data = Bytes::from(format!("{},{}", i, j));
+                manager.insert_point("cities", &point, &data).unwrap();
+            }
+        }
+        let la = Point::new(34.0522, -118.2437);
+        manager
+            .insert_point("cities", &la, &Bytes::from("LA"))
+            .unwrap();
+
+        let query = Point::new(40.7101, -73.9901);
+        let near = manager
+            .approximate_nearest_neighbor("cities", &query, 3, 5)
+            .unwrap();
+        assert!(!near.is_empty() && near.len() <= 3);
+        assert!(near.windows(2).all(|w| w[0].2 <= w[1].2));
+        assert!(near.iter().all(|(p, _, d)| *d == query.distance_to(p)));
+        // Far points never share enough prefixes to be candidates
+        assert!(near.iter().all(|(p, _, _)| *p != la));
+
+        // More tables can only add candidates
+        let more = manager
+            .approximate_nearest_neighbor("cities", &query, 400, 20)
+            .unwrap();
+        let fewer = manager
+            .approximate_nearest_neighbor("cities", &query, 400, 2)
+            .unwrap();
+        assert!(more.len() >= fewer.len());
+        assert!(fewer.iter().all(|f| more.contains(f)));
+
+        // Exact stored point is always found
+        let stored = Point::new(40.70 + 5.0 * 0.001, -74.00 + 5.0 * 0.001);
+        let found = manager
+            .approximate_nearest_neighbor("cities", &stored, 1, 1)
+            .unwrap();
+        assert_eq!(found[0].0, stored);
+        assert_eq!(found[0].2, 0.0);
+
+        // Changes to the prefix invalidate the cached tables
+        let added = Point::new(40.7101, -73.9901);
+        manager
+            .insert_point("cities", &added, &Bytes::from("new"))
+            .unwrap();
+        let found = manager
+            .approximate_nearest_neighbor("cities", &query, 1, 5)
+            .unwrap();
+        assert_eq!(found[0].1, Bytes::from("new"));
+
+        manager.remove_point("cities", &added).unwrap();
+        manager.rebuild_lsh_index("cities").unwrap();
+        let found = manager
+            .approximate_nearest_neighbor("cities", &query, 1, 5)
+            .unwrap();
+        assert_ne!(found[0].0, added);
+    }
+
+    #[test]
+    fn test_join_precision() {
+        // Precision 6 cells are about 1.2 km x 0.6 km at the equator
+        assert_eq!(join_precision(500.0, 0.0, 12), Some(6));
+        assert_eq!(join_precision(500.0, 0.0, 4), Some(4));
+        // Cells narrow towards the poles
+        assert_eq!(join_precision(500.0, 80.0, 12), Some(5));
+        assert_eq!(join_precision(10_000_000.0, 0.0, 12), None);
+    }
+
     #[test]
     fn test_search_precisions_edge_cases() {
         // Test precision 1 (should not produce 0 or negative values)
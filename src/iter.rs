@@ -0,0 +1,110 @@
+//! Borrowing iterators over every key in the database
+//!
+//! `DB::iter_keys` and `DB::iter_items` return handles that keep the
+//! database read lock for as long as they live, so keys and items can be
+//! walked by reference without copying them out. Because the items borrow
+//! from the lock the handle owns, iteration goes through a reference to
+//! the handle (`for key in &keys` or `keys.iter()`).
+
+use crate::db::DBInner;
+use crate::sync::RwLockReadGuard;
+use crate::types::DbItem;
+use bytes::Bytes;
+use std::collections::btree_map;
+
+/// Every live key in the database, in key order.
+///
+/// Holds the database read lock until dropped: writes from other threads
+/// block and a write from the same thread deadlocks, so drop the handle
+/// before modifying the database.
+pub struct KeyIterator<'a> {
+    guard: RwLockReadGuard<'a, DBInner>,
+}
+
+impl<'a> KeyIterator<'a> {
+    pub(crate) fn new(guard: RwLockReadGuard<'a, DBInner>) -> Self {
+        Self { guard }
+    }
+
+    /// Iterate over the keys by reference
+    pub fn iter(&self) -> Keys<'_> {
+        Keys::new(self.guard.keys.iter())
+    }
+}
+
+impl<'b> IntoIterator for &'b KeyIterator<'_> {
+    type Item = &'b Bytes;
+    type IntoIter = Keys<'b>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Every live key and item in the database, in key order.
+///
+/// Holds the database read lock until dropped, like `KeyIterator`.
+pub struct ItemIterator<'a> {
+    guard: RwLockReadGuard<'a, DBInner>,
+}
+
+impl<'a> ItemIterator<'a> {
+    pub(crate) fn new(guard: RwLockReadGuard<'a, DBInner>) -> Self {
+        Self { guard }
+    }
+
+    /// Iterate over the keys and items by reference
+    pub fn iter(&self) -> Items<'_> {
+        Items::new(self.guard.keys.iter())
+    }
+}
+
+impl<'b> IntoIterator for &'b ItemIterator<'_> {
+    type Item = (&'b Bytes, &'b DbItem);
+    type IntoIter = Items<'b>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Borrowing iterator over keys, skipping expired items
+pub struct Keys<'a> {
+    inner: btree_map::Iter<'a, Bytes, DbItem>,
+}
+
+impl<'a> Keys<'a> {
+    pub(crate) fn new(inner: btree_map::Iter<'a, Bytes, DbItem>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a> Iterator for Keys<'a> {
+    type Item = &'a Bytes;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .by_ref()
+            .find(|(_, item)| !item.is_expired())
+            .map(|(key, _)| key)
+    }
+}
+
+/// Borrowing iterator over key and item pairs, skipping expired items
+pub struct Items<'a> {
+    inner: btree_map::Iter<'a, Bytes, DbItem>,
+}
+
+impl<'a> Items<'a> {
+    pub(crate) fn new(inner: btree_map::Iter<'a, Bytes, DbItem>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<'a> Iterator for Items<'a> {
+    type Item = (&'a Bytes, &'a DbItem);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.by_ref().find(|(_, item)| !item.is_expired())
+    }
+}
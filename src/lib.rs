@@ -127,19 +127,52 @@
 //! # }
 //! ```
 
+pub mod analysis;
 pub mod batch;
 pub mod builder;
 pub mod db;
 pub mod error;
+pub mod geometry;
 pub mod index;
+pub mod iter;
+pub mod merge;
 pub mod namespace;
+pub mod projection;
 pub mod spatial;
 pub mod storage;
+pub mod transaction;
 pub mod types;
+pub mod view;
+pub mod watch;
+
+mod bloom;
+mod clock;
+mod debug_dump;
+mod sync;
+mod telemetry;
+mod typed;
 
 #[cfg(feature = "aof")]
 pub mod persistence;
 
+#[cfg(feature = "csv")]
+mod csv_io;
+
+#[cfg(feature = "geojson")]
+mod geojson_io;
+
+#[cfg(feature = "redis-import")]
+mod redis_io;
+
+#[cfg(feature = "async")]
+pub mod region;
+
+#[cfg(feature = "resp-server")]
+pub mod resp;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
 // Core exports - Main API
 pub use builder::DBBuilder;
 pub use db::DB;
@@ -148,30 +181,90 @@ pub use error::{Result, SpatioError};
 // Main database type alias for cleaner API
 pub type Spatio = DB;
 
+// Borrowing iterators over the whole database
+pub use iter::{ItemIterator, KeyIterator};
+
+// Lock-scoped access for multi-step operations
+pub use view::{DbView, DbViewMut};
+
 // Spatial types and operations
-pub use spatial::{BoundingBox, Point};
+pub use spatial::{BoundingBox, GeohashUtils, Point};
+
+#[cfg(feature = "s2")]
+pub use spatial::S2Covering;
+
+// Analysis over sets of points
+pub use analysis::{Average, SpatialAggregator, SpatialAnalysis, TrajectorySmoothing};
+
+// Map projections
+pub use projection::CoordinateSystem;
+
+// Geometry types
+pub use geometry::{Coordinate, Geometry, GeometryOps, LineString, LinearRing, Polygon};
 
 // Configuration and options
-pub use types::{Config, DbStats, SetOptions, SyncPolicy};
+pub use types::{
+    BulkLoadStats, CompactionStats, Config, CorridorResult, DbStats, EvictionPolicy, ExportStats,
+    GeofenceEvent, GeofenceEventType, ImportStats, IntegrityReport, InvertedIndex, KnnEdge,
+    RecoveryMode, ScanPage, SetOptions, SpatialJoinResult, StaleEntry, SyncPolicy,
+    TrajectoryCluster, TrajectoryCrossing, TypeCheckResult, UnservedPoint, ValueFilter, ValueType,
+};
+
+#[cfg(feature = "aof")]
+pub use types::{CheckpointStats, ReplayStats};
+
+#[cfg(feature = "debug_queries")]
+pub use types::QueryExplanation;
+
+#[cfg(feature = "redis-import")]
+pub use types::RedisImportStats;
 
 // Namespace support for data organization
-pub use namespace::{Namespace, NamespaceManager};
+pub use namespace::{Namespace, NamespaceManager, NamespaceStats};
 
 // Storage backend abstraction
-pub use storage::{MemoryBackend, StorageBackend, StorageOp, StorageStats};
+pub use storage::{
+    MemoryBackend, MemorySnapshot, StorageBackend, StorageOp, StorageSnapshot, StorageStats,
+};
 
 #[cfg(feature = "aof")]
 pub use storage::AOFBackend;
 
+#[cfg(feature = "rocksdb")]
+pub use storage::RocksDBBackend;
+
+// Key change notifications
+pub use watch::{CountEvent, CountEventType, KeyEvent, KeyEventType, WatchHandle};
+
+#[cfg(feature = "async")]
+pub use region::{RegionEvent, RegionEventType, RegionWatcher, SpatialWindowWatcher};
+
+// Redis protocol server
+#[cfg(feature = "resp-server")]
+pub use resp::{RespCommand, RespServer, RespValue};
+
 // Batch operations
 pub use batch::AtomicBatch;
 
+// Optimistic transactions for DB::transaction
+pub use transaction::Transaction;
+
+// Merge functions for DB::merge
+pub use merge::Mergers;
+
 // AOF persistence (when feature enabled)
 #[cfg(feature = "aof")]
-pub use persistence::{AOFConfig, AOFFile};
+pub use persistence::{AOFConfig, AOFFile, Compression};
 
 // Geohash configuration constants
-pub use index::{DEFAULT_GEOHASH_PRECISION, DEFAULT_SEARCH_PRECISIONS};
+pub use index::{DEFAULT_GEOHASH_PRECISION, DEFAULT_SEARCH_PRECISIONS, IndexStrategy};
+
+// OpenTelemetry bridge crates, re-exported so applications can install a
+// `tracing_opentelemetry` layer built against the same versions spatio uses
+#[cfg(feature = "tracing")]
+pub use opentelemetry;
+#[cfg(feature = "tracing")]
+pub use tracing_opentelemetry;
 
 /// Version information
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -192,7 +285,7 @@ pub mod prelude {
     pub use crate::{DBBuilder, Result, Spatio, SpatioError};
 
     // Spatial types
-    pub use crate::{BoundingBox, Point};
+    pub use crate::{BoundingBox, Point, Polygon};
 
     // Configuration
     pub use crate::{Config, SetOptions, SyncPolicy};
@@ -0,0 +1,122 @@
+//! Pre-built merge functions for `DB::merge`
+//!
+//! A merge function receives the incoming value and the stored value, if
+//! any, and returns the value to store. Writers that merge instead of
+//! overwriting can apply their updates in any interleaving and still agree
+//! on the result, as long as the merge function is commutative.
+
+/// Factory for common merge functions.
+///
+/// # Examples
+///
+/// ```rust
+/// use spatio::{Mergers, Spatio};
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let db = Spatio::memory()?;
+/// db.merge("sensor:1:peak", b"21.5", Mergers::numeric_max())?;
+/// db.merge("sensor:1:peak", b"19.0", Mergers::numeric_max())?;
+/// assert_eq!(db.get("sensor:1:peak")?.unwrap().as_ref(), b"21.5");
+/// # Ok(())
+/// # }
+/// ```
+pub struct Mergers;
+
+impl Mergers {
+    /// Always keep the incoming value, like a plain `insert`
+    pub fn last_write_wins() -> impl Fn(&[u8], Option<&[u8]>) -> Vec<u8> {
+        |new, _| new.to_vec()
+    }
+
+    /// Append the incoming value to the stored one as a new line.
+    ///
+    /// The first value is stored as is; each later one is added after a
+    /// `\n`, so the stored value reads as a log of entries.
+    pub fn append_log() -> impl Fn(&[u8], Option<&[u8]>) -> Vec<u8> {
+        |new, existing| match existing {
+            Some(existing) => {
+                let mut merged = Vec::with_capacity(existing.len() + 1 + new.len());
+                merged.extend_from_slice(existing);
+                merged.push(b'\n');
+                merged.extend_from_slice(new);
+                merged
+            }
+            None => new.to_vec(),
+        }
+    }
+
+    /// Keep whichever of the two values is the larger number.
+    ///
+    /// Values are parsed as decimal text (e.g. `b"42"` or `b"-3.5"`) and
+    /// the winner is stored byte for byte. A value that does not parse
+    /// loses to one that does; if neither parses, the incoming value wins.
+    pub fn numeric_max() -> impl Fn(&[u8], Option<&[u8]>) -> Vec<u8> {
+        |new, existing| {
+            let parse = |value: &[u8]| {
+                std::str::from_utf8(value)
+                    .ok()
+                    .and_then(|text| text.trim().parse::<f64>().ok())
+                    .filter(|number| !number.is_nan())
+            };
+            let keep_existing = match existing.map(|existing| (existing, parse(existing))) {
+                Some((_, Some(current))) => parse(new).is_none_or(|incoming| current > incoming),
+                _ => false,
+            };
+            match existing {
+                Some(existing) if keep_existing => existing.to_vec(),
+                _ => new.to_vec(),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_last_write_wins() {
+        let merge = Mergers::last_write_wins();
+        assert_eq!(merge(b"new", Some(b"old")), b"new");
+        assert_eq!(merge(b"new", None), b"new");
+    }
+
+    #[test]
+    fn test_append_log() {
+        let merge = Mergers::append_log();
+        let first = merge(b"started", None);
+        assert_eq!(first, b"started");
+        let second = merge(b"stopped", Some(&first));
+        assert_eq!(second, b"started\nstopped");
+    }
+
+    #[test]
+    fn test_numeric_max() {
+        let merge = Mergers::numeric_max();
+        assert_eq!(merge(b"5", None), b"5");
+        assert_eq!(merge(b"5", Some(b"7")), b"7");
+        assert_eq!(merge(b"10", Some(b"7")), b"10");
+        assert_eq!(merge(b"-1.5", Some(b"-2")), b"-1.5");
+        // Ties keep the incoming value
+        assert_eq!(merge(b"7.0", Some(b"7")), b"7.0");
+        // Numbers beat anything that does not parse
+        assert_eq!(merge(b"abc", Some(b"3")), b"3");
+        assert_eq!(merge(b"3", Some(b"abc")), b"3");
+        assert_eq!(merge(b"NaN", Some(b"3")), b"3");
+        assert_eq!(merge(b"xyz", Some(b"abc")), b"xyz");
+    }
+
+    #[test]
+    fn test_numeric_max_is_order_independent() {
+        let merge = Mergers::numeric_max();
+        let values: [&[u8]; 4] = [b"3", b"17", b"-4", b"12.5"];
+        let fold = |order: &[usize]| {
+            order.iter().fold(None::<Vec<u8>>, |stored, &i| {
+                Some(merge(values[i], stored.as_deref()))
+            })
+        };
+        assert_eq!(fold(&[0, 1, 2, 3]), Some(b"17".to_vec()));
+        assert_eq!(fold(&[3, 2, 1, 0]), Some(b"17".to_vec()));
+        assert_eq!(fold(&[2, 0, 3, 1]), Some(b"17".to_vec()));
+    }
+}
@@ -3,7 +3,9 @@
 //! This module provides namespace-aware key management for data isolation
 //! and logical data organization within a single Spatio instance.
 
+use crate::DB;
 use crate::error::{Result, SpatioError};
+use crate::types::SetOptions;
 use bytes::Bytes;
 use std::fmt;
 
@@ -214,6 +216,17 @@ impl Default for Namespace {
     }
 }
 
+/// Statistics about the keys stored in a single namespace
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NamespaceStats {
+    /// Number of live (non-expired) keys in the namespace
+    pub key_count: usize,
+    /// Number of keys that have expired but not yet been cleaned up
+    pub expired_count: usize,
+    /// Total size of live keys and values in bytes
+    pub size_bytes: usize,
+}
+
 /// Namespace-aware key manager
 ///
 /// This provides utilities for working with multiple namespaces and
@@ -308,6 +321,66 @@ impl NamespaceManager {
             false
         }
     }
+
+    /// Copy all keys from one namespace into another
+    ///
+    /// Every live key in `from` is re-inserted under `to` with the source
+    /// prefix replaced. All writes are applied in a single atomic batch.
+    ///
+    /// # Arguments
+    ///
+    /// * `db` - The database holding both namespaces
+    /// * `from` - The source namespace
+    /// * `to` - The destination namespace
+    /// * `opts` - Options for the copied keys; `None` preserves each item's
+    ///   original expiration time
+    ///
+    /// # Returns
+    ///
+    /// The number of keys copied
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{NamespaceManager, Spatio};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let manager = NamespaceManager::new();
+    /// let live = manager.namespace("live");
+    /// let backup = manager.namespace("backup");
+    ///
+    /// db.insert(live.key("user:1"), b"Alice", None)?;
+    ///
+    /// let copied = manager.copy_namespace(&db, &live, &backup, None)?;
+    /// assert_eq!(copied, 1);
+    /// assert!(db.get(backup.key("user:1"))?.is_some());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn copy_namespace(
+        &self,
+        db: &DB,
+        from: &Namespace,
+        to: &Namespace,
+        opts: Option<SetOptions>,
+    ) -> Result<usize> {
+        let items = db.prefix_items(&from.prefix())?;
+
+        db.atomic(|batch| {
+            for (key, item) in &items {
+                let Some(suffix) = from.strip_prefix(key) else {
+                    continue;
+                };
+                let item_opts = match &opts {
+                    Some(opts) => Some(opts.clone()),
+                    None => item.expires_at.map(SetOptions::with_expiration),
+                };
+                batch.insert(to.key(suffix), &item.value, item_opts)?;
+            }
+            Ok(items.len())
+        })
+    }
 }
 
 impl Default for NamespaceManager {
@@ -442,6 +515,47 @@ mod tests {
         assert!(!manager.key_belongs_to_namespace(b"simple_key", "namespace_a"));
     }
 
+    #[test]
+    fn test_copy_namespace() {
+        use std::time::{Duration, SystemTime};
+
+        let db = DB::memory().unwrap();
+        let manager = NamespaceManager::new();
+        let from = manager.namespace("live");
+        let to = manager.namespace("archive");
+
+        let expires_at = SystemTime::now() + Duration::from_secs(3600);
+        db.insert(from.key("user:1"), b"Alice", None).unwrap();
+        db.insert(
+            from.key("session:1"),
+            b"token",
+            Some(SetOptions::with_expiration(expires_at)),
+        )
+        .unwrap();
+        db.insert("unrelated", b"value", None).unwrap();
+
+        let copied = manager.copy_namespace(&db, &from, &to, None).unwrap();
+        assert_eq!(copied, 2);
+
+        assert_eq!(
+            db.get(to.key("user:1")).unwrap().unwrap().as_ref(),
+            b"Alice"
+        );
+        assert_eq!(
+            db.get(to.key("session:1")).unwrap().unwrap().as_ref(),
+            b"token"
+        );
+
+        // Source keys are left untouched
+        assert!(db.get(from.key("user:1")).unwrap().is_some());
+
+        // Original expiration is preserved when no options are given
+        let items = db.prefix_items(&to.key("session:1")).unwrap();
+        assert_eq!(items[0].1.expires_at, Some(expires_at));
+        let items = db.prefix_items(&to.key("user:1")).unwrap();
+        assert!(items[0].1.expires_at.is_none());
+    }
+
     #[test]
     fn test_namespace_display() {
         let ns = Namespace::new("test_namespace");
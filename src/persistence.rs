@@ -1,22 +1,38 @@
 use crate::error::{Result, SpatioError};
-use crate::types::SetOptions;
+use crate::types::{RecoveryMode, ReplayStats, SetOptions, ValueType};
 use bytes::{BufMut, Bytes, BytesMut};
+use crc32fast::Hasher;
+use serde::{Deserialize, Serialize};
 use std::fs::{File, OpenOptions};
 use std::io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-/// AOF configuration for rewriting
+/// Compression applied to values written to the AOF
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    /// Store values as-is
+    #[default]
+    None,
+    /// LZ4 block compression; values that do not shrink are stored as-is
+    Lz4,
+}
+
+/// AOF configuration for rewriting and compression
 #[derive(Debug, Clone)]
 pub struct AOFConfig {
     /// Trigger rewrite when file size exceeds this many bytes
     pub rewrite_size_threshold: u64,
+    /// Compression for SET values. Replay reads both compressed and
+    /// uncompressed records, so this can be changed between opens.
+    pub compression: Compression,
 }
 
 impl Default for AOFConfig {
     fn default() -> Self {
         Self {
             rewrite_size_threshold: 64 * 1024 * 1024, // 64MB
+            compression: Compression::None,
         }
     }
 }
@@ -30,6 +46,25 @@ pub struct AOFFile {
     config: AOFConfig,
     last_rewrite_size: u64,
     rewrite_in_progress: bool,
+    /// Running CRC32 of every byte in the file
+    checksum: Hasher,
+    /// Whether commands were written since the last checksum footer
+    unsealed: bool,
+    /// Position just past the last checksum footer written by this handle
+    last_footer: Option<AOFPosition>,
+}
+
+/// A point in the AOF that derived state, such as the spatial index
+/// snapshot, was taken at. It always ends with a checksum footer, see
+/// `AOFFile::seal`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AOFPosition {
+    /// Number of bytes from the start of the file
+    pub offset: u64,
+    /// CRC32 recorded by the footer ending at `offset`, covering every
+    /// byte before it, so a rewritten file is not mistaken for the one
+    /// the position was taken from
+    pub checksum: u32,
 }
 
 #[derive(Debug)]
@@ -38,10 +73,38 @@ pub enum AOFCommand {
         key: Bytes,
         value: Bytes,
         expires_at: Option<SystemTime>,
+        /// Type recorded by a typed insert, such as `DB::insert_string`
+        value_type: Option<ValueType>,
     },
     Delete {
         key: Bytes,
     },
+    /// Change the expiration of a key without rewriting its value
+    Expire {
+        key: Bytes,
+        expires_at: SystemTime,
+    },
+}
+
+/// A single record read back from the AOF
+enum AOFRecord {
+    Command(AOFCommand),
+    /// Checksum footer holding the CRC32 of all bytes before it
+    Checksum(u32),
+}
+
+/// Reader that feeds every byte it returns into a running CRC32
+struct ChecksumReader<R> {
+    inner: R,
+    hasher: Hasher,
+}
+
+impl<R: Read> Read for ChecksumReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
 }
 
 impl AOFFile {
@@ -54,13 +117,14 @@ impl AOFFile {
     pub fn open_with_config<P: AsRef<Path>>(path: P, config: AOFConfig) -> Result<Self> {
         let path = path.as_ref().to_path_buf();
 
-        let file = OpenOptions::new()
+        let mut file = OpenOptions::new()
             .create(true)
             .append(true)
             .read(true)
             .open(&path)?;
 
         let size = file.metadata()?.len();
+        let checksum = Self::checksum_of(&mut file)?;
         let writer_file = file.try_clone()?;
         let writer = BufWriter::new(writer_file);
 
@@ -72,9 +136,23 @@ impl AOFFile {
             config,
             last_rewrite_size: size,
             rewrite_in_progress: false,
+            checksum,
+            unsealed: false,
+            last_footer: None,
         })
     }
 
+    /// Compute the CRC32 of a file's current contents
+    fn checksum_of(file: &mut File) -> Result<Hasher> {
+        file.seek(SeekFrom::Start(0))?;
+        let mut reader = ChecksumReader {
+            inner: BufReader::new(file),
+            hasher: Hasher::new(),
+        };
+        std::io::copy(&mut reader, &mut std::io::sink())?;
+        Ok(reader.hasher)
+    }
+
     /// Get current file size
     pub fn size(&self) -> u64 {
         self.size
@@ -86,6 +164,18 @@ impl AOFFile {
         key: &[u8],
         value: &[u8],
         options: Option<&SetOptions>,
+    ) -> Result<()> {
+        self.write_typed_set(key, value, options, None)
+    }
+
+    /// Write a SET command to the AOF, recording the type the value was
+    /// stored as by a typed insert
+    pub fn write_typed_set(
+        &mut self,
+        key: &[u8],
+        value: &[u8],
+        options: Option<&SetOptions>,
+        value_type: Option<ValueType>,
     ) -> Result<()> {
         let expires_at = match options {
             Some(opts) => opts.expires_at,
@@ -96,6 +186,7 @@ impl AOFFile {
             key: Bytes::copy_from_slice(key),
             value: Bytes::copy_from_slice(value),
             expires_at,
+            value_type,
         };
 
         self.write_command(&command)
@@ -109,6 +200,39 @@ impl AOFFile {
         self.write_command(&command)
     }
 
+    /// Write an EXPIRE command to the AOF
+    pub fn write_expire(&mut self, key: &[u8], expires_at: SystemTime) -> Result<()> {
+        let command = AOFCommand::Expire {
+            key: Bytes::copy_from_slice(key),
+            expires_at,
+        };
+        self.write_command(&command)
+    }
+
+    /// Write several commands with a single append to the file, returning
+    /// the number of bytes written
+    pub fn write_commands(&mut self, commands: &[AOFCommand]) -> Result<u64> {
+        if self.rewrite_in_progress {
+            return Err(SpatioError::RewriteInProgress);
+        }
+
+        let mut buf = Vec::new();
+        for command in commands {
+            buf.extend_from_slice(&self.serialize_command(command)?);
+        }
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        self.write_raw(&buf)?;
+        self.unsealed = true;
+
+        if self.should_rewrite() {
+            self.maybe_trigger_rewrite()?;
+        }
+
+        Ok(buf.len() as u64)
+    }
+
     /// Write a command to the AOF file
     fn write_command(&mut self, command: &AOFCommand) -> Result<()> {
         if self.rewrite_in_progress {
@@ -116,8 +240,8 @@ impl AOFFile {
         }
 
         let serialized = self.serialize_command(command)?;
-        self.writer.write_all(&serialized)?;
-        self.size += serialized.len() as u64;
+        self.write_raw(&serialized)?;
+        self.unsealed = true;
 
         // Check if we should trigger a rewrite
         if self.should_rewrite() {
@@ -127,6 +251,31 @@ impl AOFFile {
         Ok(())
     }
 
+    /// Append bytes to the file, keeping the size and checksum current
+    fn write_raw(&mut self, bytes: &[u8]) -> Result<()> {
+        self.writer.write_all(bytes)?;
+        self.checksum.update(bytes);
+        self.size += bytes.len() as u64;
+        Ok(())
+    }
+
+    /// Append a checksum footer covering everything written so far,
+    /// returning the position just past it
+    fn write_checksum_footer(&mut self) -> Result<AOFPosition> {
+        let mut buf = BytesMut::with_capacity(5);
+        let checksum = self.checksum.clone().finalize();
+        buf.put_u8(2); // Record type: CHECKSUM
+        buf.put_u32(checksum);
+        self.write_raw(&buf)?;
+        self.unsealed = false;
+        let footer = AOFPosition {
+            offset: self.size,
+            checksum,
+        };
+        self.last_footer = Some(footer);
+        Ok(footer)
+    }
+
     /// Check if AOF should be rewritten based on size threshold
     fn should_rewrite(&self) -> bool {
         !self.rewrite_in_progress && self.size >= self.config.rewrite_size_threshold
@@ -211,16 +360,33 @@ impl AOFFile {
                 key,
                 value,
                 expires_at,
+                value_type,
             } => {
-                buf.put_u8(0); // Command type: SET
+                if let Some(value_type) = value_type {
+                    // Command type: TYPED, followed by the tag and the SET
+                    buf.put_u8(5);
+                    buf.put_u8(value_type.tag());
+                }
+
+                let compressed = match self.config.compression {
+                    Compression::None => None,
+                    Compression::Lz4 => Some(lz4_flex::compress_prepend_size(value))
+                        .filter(|compressed| compressed.len() < value.len()),
+                };
+
+                match compressed {
+                    Some(_) => buf.put_u8(3), // Command type: SET with LZ4 value
+                    None => buf.put_u8(0),    // Command type: SET
+                }
 
                 // Key length and data
                 buf.put_u32(key.len() as u32);
                 buf.put(key.as_ref());
 
                 // Value length and data
+                let value = compressed.as_deref().unwrap_or(value);
                 buf.put_u32(value.len() as u32);
-                buf.put(value.as_ref());
+                buf.put(value);
 
                 // Expiration
                 match expires_at {
@@ -244,6 +410,19 @@ impl AOFFile {
                 buf.put_u32(key.len() as u32);
                 buf.put(key.as_ref());
             }
+            AOFCommand::Expire { key, expires_at } => {
+                buf.put_u8(4); // Command type: EXPIRE
+
+                // Key length and data
+                buf.put_u32(key.len() as u32);
+                buf.put(key.as_ref());
+
+                let timestamp = expires_at
+                    .duration_since(UNIX_EPOCH)
+                    .map_err(|_| SpatioError::InvalidTimestamp)?
+                    .as_secs();
+                buf.put_u64(timestamp);
+            }
         }
 
         Ok(buf.to_vec())
@@ -251,23 +430,167 @@ impl AOFFile {
 
     /// Replay AOF commands and return them
     pub fn replay(&mut self) -> Result<Vec<AOFCommand>> {
-        self.file.seek(SeekFrom::Start(0))?;
-        let mut reader = BufReader::new(&mut self.file);
+        Ok(self
+            .replay_with_offsets()?
+            .into_iter()
+            .map(|(_, command)| command)
+            .collect())
+    }
+
+    /// Replay AOF commands along with the byte offset each one starts at
+    pub fn replay_with_offsets(&mut self) -> Result<Vec<(u64, AOFCommand)>> {
+        let (commands, _) = self.replay_from_offset(0, RecoveryMode::StopAtError)?;
+        Ok(commands)
+    }
+
+    /// Replay the commands from `start_offset` on, with the byte offset
+    /// each one starts at.
+    ///
+    /// `start_offset` should be the start of a record, such as the
+    /// `final_offset` of an earlier replay. `mode` decides what happens at
+    /// a record that cannot be read; see `RecoveryMode`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `start_offset` is past the end of the file, the
+    /// file cannot be read, or with `RecoveryMode::StopAtError`, a record
+    /// is damaged.
+    pub fn replay_from_offset(
+        &mut self,
+        start_offset: u64,
+        mode: RecoveryMode,
+    ) -> Result<(Vec<(u64, AOFCommand)>, ReplayStats)> {
+        self.writer.flush()?;
+        let len = self.file.metadata()?.len();
+        if start_offset > len {
+            return Err(SpatioError::Other(format!(
+                "Replay offset {} is past the end of the AOF ({} bytes)",
+                start_offset, len
+            )));
+        }
+
         let mut commands = Vec::new();
+        let mut stats = ReplayStats::default();
+        let mut offset = start_offset;
+        while offset < len {
+            let (end, error) = self.read_records(offset, len, &mut commands)?;
+            offset = end;
+            let Some(error) = error else {
+                break;
+            };
+            match mode {
+                RecoveryMode::StopAtError => return Err(error),
+                RecoveryMode::SkipCorrupted => {
+                    stats.commands_skipped += 1;
+                    offset = self.next_readable_offset(offset + 1, len);
+                }
+                RecoveryMode::Truncate => {
+                    self.truncate(offset)?;
+                    break;
+                }
+            }
+        }
 
-        loop {
-            match Self::deserialize_command_static(&mut reader) {
-                Ok(command) => commands.push(command),
-                Err(SpatioError::UnexpectedEof) => break, // End of file
-                Err(e) => return Err(e),
+        stats.commands_replayed = commands.len();
+        stats.final_offset = offset;
+        Ok((commands, stats))
+    }
+
+    /// Read records from `offset` up to `len` into `commands`.
+    ///
+    /// Returns the offset reading stopped at, with the error if it stopped
+    /// at a record that could not be read rather than at `len`.
+    fn read_records(
+        &mut self,
+        offset: u64,
+        len: u64,
+        commands: &mut Vec<(u64, AOFCommand)>,
+    ) -> Result<(u64, Option<SpatioError>)> {
+        self.file.seek(SeekFrom::Start(offset))?;
+        let mut reader = BufReader::new(&mut self.file).take(len - offset);
+        let mut offset = offset;
+        while offset < len {
+            match Self::deserialize_record(&mut reader) {
+                Ok(record) => {
+                    if let AOFRecord::Command(command) = record {
+                        commands.push((offset, command));
+                    }
+                    offset = len - reader.limit();
+                }
+                Err(e) => return Ok((offset, Some(e))),
             }
         }
+        Ok((offset, None))
+    }
 
-        Ok(commands)
+    /// Read the record at `offset`, returning it with the offset of the
+    /// next one. Reads stop at `len`, so a record cut short fails.
+    fn read_record_at(&mut self, offset: u64, len: u64) -> Result<(AOFRecord, u64)> {
+        self.file.seek(SeekFrom::Start(offset))?;
+        let mut reader = BufReader::new(&mut self.file).take(len - offset);
+        let record = Self::deserialize_record(&mut reader)?;
+        Ok((record, len - reader.limit()))
+    }
+
+    /// Find the first offset from `from` holding a readable command that
+    /// is followed by another readable record or the end of the file.
+    ///
+    /// Checksum footers are not accepted as a resync point, since any five
+    /// bytes starting with their type byte parse as one. Returns `len` if
+    /// nothing readable is left.
+    fn next_readable_offset(&mut self, from: u64, len: u64) -> u64 {
+        (from..len)
+            .find(|&offset| match self.read_record_at(offset, len) {
+                Ok((AOFRecord::Command(_), next)) => {
+                    next == len || self.read_record_at(next, len).is_ok()
+                }
+                _ => false,
+            })
+            .unwrap_or(len)
+    }
+
+    /// Cut the file at `offset`, dropping everything after it
+    fn truncate(&mut self, offset: u64) -> Result<()> {
+        self.writer.flush()?;
+        self.file.set_len(offset)?;
+        self.file.sync_all()?;
+        self.size = offset;
+        self.checksum = Self::checksum_of(&mut self.file)?;
+        // Records before the cut may follow the last footer
+        self.unsealed = true;
+        Ok(())
+    }
+
+    /// Verify every checksum footer against the bytes written before it.
+    ///
+    /// Returns `false` if a footer does not match or a record cannot be
+    /// parsed. Commands written after the last footer have not been synced
+    /// yet and are only checked for a readable format.
+    pub fn verify_checksum(&mut self) -> Result<bool> {
+        self.writer.flush()?;
+        self.file.seek(SeekFrom::Start(0))?;
+        let mut reader = ChecksumReader {
+            inner: BufReader::new(&mut self.file),
+            hasher: Hasher::new(),
+        };
+
+        loop {
+            let expected = reader.hasher.clone().finalize();
+            match Self::deserialize_record(&mut reader) {
+                Ok(AOFRecord::Command(_)) => {}
+                Ok(AOFRecord::Checksum(stored)) => {
+                    if stored != expected {
+                        return Ok(false);
+                    }
+                }
+                Err(SpatioError::UnexpectedEof) => return Ok(true),
+                Err(_) => return Ok(false),
+            }
+        }
     }
 
-    /// Deserialize a command from the reader
-    fn deserialize_command_static(reader: &mut BufReader<&mut File>) -> Result<AOFCommand> {
+    /// Deserialize a record from the reader
+    fn deserialize_record<R: Read>(reader: &mut R) -> Result<AOFRecord> {
         let mut cmd_type_buf = [0u8; 1];
         if reader.read_exact(&mut cmd_type_buf).is_err() {
             return Err(SpatioError::UnexpectedEof);
@@ -275,10 +598,15 @@ impl AOFFile {
         let cmd_type = cmd_type_buf[0];
 
         match cmd_type {
-            0 => {
-                // SET command
+            0 | 3 => {
+                // SET command, with an LZ4 compressed value for type 3
                 let key = Self::read_bytes(reader)?;
-                let value = Self::read_bytes(reader)?;
+                let mut value = Self::read_bytes(reader)?;
+                if cmd_type == 3 {
+                    value = lz4_flex::decompress_size_prepended(&value)
+                        .map(Bytes::from)
+                        .map_err(|_| SpatioError::InvalidFormat)?;
+                }
 
                 let mut has_exp_buf = [0u8; 1];
                 reader.read_exact(&mut has_exp_buf)?;
@@ -293,29 +621,72 @@ impl AOFFile {
                     None
                 };
 
-                Ok(AOFCommand::Set {
+                Ok(AOFRecord::Command(AOFCommand::Set {
                     key,
                     value,
                     expires_at,
-                })
+                    value_type: None,
+                }))
+            }
+            5 => {
+                // TYPED prefix: the type tag, then a SET command
+                let mut tag_buf = [0u8; 1];
+                reader.read_exact(&mut tag_buf)?;
+                let tag = ValueType::from_tag(tag_buf[0]).ok_or(SpatioError::InvalidFormat)?;
+                match Self::deserialize_record(reader)? {
+                    AOFRecord::Command(AOFCommand::Set {
+                        key,
+                        value,
+                        expires_at,
+                        ..
+                    }) => Ok(AOFRecord::Command(AOFCommand::Set {
+                        key,
+                        value,
+                        expires_at,
+                        value_type: Some(tag),
+                    })),
+                    _ => Err(SpatioError::InvalidFormat),
+                }
             }
             1 => {
                 // DELETE command
                 let key = Self::read_bytes(reader)?;
-                Ok(AOFCommand::Delete { key })
+                Ok(AOFRecord::Command(AOFCommand::Delete { key }))
+            }
+            4 => {
+                // EXPIRE command
+                let key = Self::read_bytes(reader)?;
+                let mut timestamp_buf = [0u8; 8];
+                reader.read_exact(&mut timestamp_buf)?;
+                let timestamp = u64::from_be_bytes(timestamp_buf);
+                Ok(AOFRecord::Command(AOFCommand::Expire {
+                    key,
+                    expires_at: UNIX_EPOCH + Duration::from_secs(timestamp),
+                }))
+            }
+            2 => {
+                // CHECKSUM footer
+                let mut checksum_buf = [0u8; 4];
+                reader.read_exact(&mut checksum_buf)?;
+                Ok(AOFRecord::Checksum(u32::from_be_bytes(checksum_buf)))
             }
             _ => Err(SpatioError::InvalidFormat),
         }
     }
 
     /// Helper to read length-prefixed bytes
-    fn read_bytes(reader: &mut BufReader<&mut File>) -> Result<Bytes> {
+    fn read_bytes<R: Read>(reader: &mut R) -> Result<Bytes> {
         let mut len_buf = [0u8; 4];
         reader.read_exact(&mut len_buf)?;
         let len = u32::from_be_bytes(len_buf) as usize;
 
-        let mut buf = vec![0u8; len];
-        reader.read_exact(&mut buf)?;
+        // Read through `take` rather than into a buffer of the stated
+        // length, so a damaged length cannot allocate gigabytes
+        let mut buf = Vec::new();
+        reader.take(len as u64).read_to_end(&mut buf)?;
+        if buf.len() != len {
+            return Err(SpatioError::UnexpectedEof);
+        }
 
         Ok(Bytes::from(buf))
     }
@@ -327,7 +698,13 @@ impl AOFFile {
     }
 
     /// Flush and sync to disk
+    ///
+    /// A checksum footer is appended first if commands were written since
+    /// the last one, so the synced file can be verified later.
     pub fn sync(&mut self) -> Result<()> {
+        if self.unsealed {
+            self.write_checksum_footer()?;
+        }
         self.writer.flush()?;
         self.file.sync_all()?;
         Ok(())
@@ -337,6 +714,57 @@ impl AOFFile {
     pub fn path(&self) -> &Path {
         &self.path
     }
+
+    /// Get the configuration the file was opened with
+    pub fn config(&self) -> &AOFConfig {
+        &self.config
+    }
+
+    /// Path of the spatial index snapshot kept next to this file
+    pub fn index_snapshot_path(&self) -> PathBuf {
+        let mut path = self.path.clone().into_os_string();
+        path.push(".sidx");
+        PathBuf::from(path)
+    }
+
+    /// End the file with a checksum footer and return the position after
+    /// it, flushing buffered writes.
+    ///
+    /// No footer is added if the last one written by this handle is still
+    /// at the end. An empty file is returned as position 0.
+    pub fn seal(&mut self) -> Result<AOFPosition> {
+        let position = match self.last_footer {
+            _ if self.size == 0 => AOFPosition {
+                offset: 0,
+                checksum: 0,
+            },
+            Some(footer) if !self.unsealed && footer.offset == self.size => footer,
+            _ => self.write_checksum_footer()?,
+        };
+        self.writer.flush()?;
+        Ok(position)
+    }
+
+    /// Check whether the file still holds the footer a position from
+    /// `seal` ended with.
+    ///
+    /// Only the footer is read, not the bytes it covers. Returns `false`
+    /// if the file is shorter than the position or holds a different
+    /// footer there, e.g. after a rewrite.
+    pub fn is_sealed_at(&mut self, position: &AOFPosition) -> Result<bool> {
+        if position.offset == 0 {
+            return Ok(true);
+        }
+        self.writer.flush()?;
+        let len = self.file.metadata()?.len();
+        if position.offset < 5 || position.offset > len {
+            return Ok(false);
+        }
+        Ok(matches!(
+            self.read_record_at(position.offset - 5, position.offset),
+            Ok((AOFRecord::Checksum(checksum), _)) if checksum == position.checksum
+        ))
+    }
 }
 
 impl Drop for AOFFile {
@@ -386,10 +814,12 @@ mod tests {
                 key,
                 value,
                 expires_at,
+                value_type,
             } => {
                 assert_eq!(key.as_ref(), b"key1");
                 assert_eq!(value.as_ref(), b"value1");
                 assert!(expires_at.is_none());
+                assert!(value_type.is_none());
             }
             _ => panic!("Expected SET command"),
         }
@@ -402,6 +832,57 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_expire_command_replay() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut aof = AOFFile::open(temp_file.path()).unwrap();
+
+        let expires_at = UNIX_EPOCH + Duration::from_secs(2_000_000_000);
+        aof.write_expire(b"key1", expires_at).unwrap();
+        aof.sync().unwrap();
+
+        match &aof.replay().unwrap()[..] {
+            [
+                AOFCommand::Expire {
+                    key,
+                    expires_at: at,
+                },
+            ] => {
+                assert_eq!(key.as_ref(), b"key1");
+                assert_eq!(*at, expires_at);
+            }
+            other => panic!("Expected one EXPIRE command, got {:?}", other),
+        }
+        assert!(aof.verify_checksum().unwrap());
+    }
+
+    #[test]
+    fn test_position_tracking() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut aof = AOFFile::open(temp_file.path()).unwrap();
+
+        assert_eq!(aof.seal().unwrap().offset, 0);
+        aof.write_set(b"key1", b"value1", None).unwrap();
+        let position = aof.seal().unwrap();
+        // Sealing again adds nothing
+        assert_eq!(aof.seal().unwrap(), position);
+        aof.write_set(b"key2", b"value2", None).unwrap();
+        aof.flush().unwrap();
+
+        // Later records start at or after the position
+        let commands = aof.replay_with_offsets().unwrap();
+        assert_eq!(commands[0].0, 0);
+        assert_eq!(commands[1].0, position.offset);
+        assert!(aof.is_sealed_at(&position).unwrap());
+        assert!(aof.verify_checksum().unwrap());
+
+        // A different file with the same length does not match
+        drop(aof);
+        std::fs::write(temp_file.path(), vec![0u8; position.offset as usize * 2]).unwrap();
+        let mut aof = AOFFile::open(temp_file.path()).unwrap();
+        assert!(!aof.is_sealed_at(&position).unwrap());
+    }
+
     #[test]
     fn test_expiration_serialization() {
         let temp_file = NamedTempFile::new().unwrap();
@@ -434,11 +915,91 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_lz4_compressed_values() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let config = AOFConfig {
+            compression: Compression::Lz4,
+            ..AOFConfig::default()
+        };
+        let large = vec![b'a'; 4096];
+
+        {
+            let mut aof = AOFFile::open_with_config(temp_file.path(), config).unwrap();
+            aof.write_set(b"large", &large, None).unwrap();
+            // Too small to shrink, so stored uncompressed
+            aof.write_set(b"small", b"v", None).unwrap();
+            aof.sync().unwrap();
+            assert!(aof.size() < 1024);
+        }
+
+        // Replay decompresses regardless of the reader's configuration
+        let mut aof = AOFFile::open(temp_file.path()).unwrap();
+        assert!(aof.verify_checksum().unwrap());
+        let commands = aof.replay().unwrap();
+        assert_eq!(commands.len(), 2);
+        match (&commands[0], &commands[1]) {
+            (
+                AOFCommand::Set {
+                    value: large_value, ..
+                },
+                AOFCommand::Set {
+                    value: small_value, ..
+                },
+            ) => {
+                assert_eq!(large_value.as_ref(), large.as_slice());
+                assert_eq!(small_value.as_ref(), b"v");
+            }
+            _ => panic!("Expected SET commands"),
+        }
+    }
+
+    #[test]
+    fn test_typed_set_replay() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let config = AOFConfig {
+            compression: Compression::Lz4,
+            ..AOFConfig::default()
+        };
+        let large = vec![b'a'; 4096];
+
+        {
+            let mut aof = AOFFile::open_with_config(temp_file.path(), config).unwrap();
+            aof.write_typed_set(b"name", b"alice", None, Some(ValueType::Utf8String))
+                .unwrap();
+            aof.write_typed_set(b"blob", &large, None, Some(ValueType::Bytes))
+                .unwrap();
+            aof.write_set(b"plain", &[0xF5, 1], None).unwrap();
+            aof.sync().unwrap();
+        }
+
+        let mut aof = AOFFile::open(temp_file.path()).unwrap();
+        let commands = aof.replay().unwrap();
+        let sets: Vec<_> = commands
+            .iter()
+            .map(|command| match command {
+                AOFCommand::Set {
+                    value, value_type, ..
+                } => (value.to_vec(), *value_type),
+                _ => panic!("Expected SET commands"),
+            })
+            .collect();
+        assert_eq!(
+            sets,
+            vec![
+                (b"alice".to_vec(), Some(ValueType::Utf8String)),
+                (large, Some(ValueType::Bytes)),
+                (vec![0xF5, 1], None),
+            ]
+        );
+    }
+
     #[test]
     fn test_synchronous_rewrite() {
         let temp_file = NamedTempFile::new().unwrap();
         let config = AOFConfig {
             rewrite_size_threshold: 100, // Small threshold
+            ..AOFConfig::default()
         };
 
         let mut aof = AOFFile::open_with_config(temp_file.path(), config).unwrap();
@@ -453,4 +1014,134 @@ mod tests {
 
         // Rewrite should have been triggered automatically (synchronous)
     }
+
+    #[test]
+    fn test_replay_from_offset() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut aof = AOFFile::open(temp_file.path()).unwrap();
+        aof.write_set(b"key1", b"value1", None).unwrap();
+        let offset = aof.size();
+        aof.write_set(b"key2", b"value2", None).unwrap();
+        aof.sync().unwrap();
+
+        let (commands, stats) = aof
+            .replay_from_offset(offset, RecoveryMode::StopAtError)
+            .unwrap();
+        assert_eq!(commands.len(), 1);
+        assert_eq!(commands[0].0, offset);
+        assert_eq!(stats.commands_replayed, 1);
+        assert_eq!(stats.final_offset, aof.size());
+
+        let (commands, _) = aof
+            .replay_from_offset(aof.size(), RecoveryMode::StopAtError)
+            .unwrap();
+        assert!(commands.is_empty());
+        assert!(
+            aof.replay_from_offset(aof.size() + 1, RecoveryMode::StopAtError)
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_recovery_from_partial_write() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut aof = AOFFile::open(temp_file.path()).unwrap();
+        aof.write_set(b"key1", b"value1", None).unwrap();
+        aof.write_set(b"key2", b"value2", None).unwrap();
+        aof.flush().unwrap();
+        let good_len = aof.size();
+        drop(aof);
+
+        // A crash half way through a third record
+        let mut bytes = std::fs::read(temp_file.path()).unwrap();
+        let partial = bytes[..bytes.len() / 2 - 3].to_vec();
+        bytes.extend_from_slice(&partial);
+        std::fs::write(temp_file.path(), &bytes).unwrap();
+
+        let mut aof = AOFFile::open(temp_file.path()).unwrap();
+        assert!(aof.replay().is_err());
+
+        let (commands, stats) = aof.replay_from_offset(0, RecoveryMode::Truncate).unwrap();
+        assert_eq!(commands.len(), 2);
+        assert_eq!(stats.final_offset, good_len);
+        assert_eq!(std::fs::metadata(temp_file.path()).unwrap().len(), good_len);
+
+        // Writes after the cut replay and verify cleanly
+        aof.write_set(b"key3", b"value3", None).unwrap();
+        aof.sync().unwrap();
+        assert_eq!(aof.replay().unwrap().len(), 3);
+        assert!(aof.verify_checksum().unwrap());
+    }
+
+    #[test]
+    fn test_recovery_skips_corrupted_records() {
+        let temp_file = NamedTempFile::new().unwrap();
+        let mut aof = AOFFile::open(temp_file.path()).unwrap();
+        aof.write_set(b"key1", b"value1", None).unwrap();
+        let damaged = aof.size();
+        aof.write_set(b"key2", b"value2", None).unwrap();
+        aof.write_set(b"key3", b"value3", None).unwrap();
+        aof.flush().unwrap();
+        drop(aof);
+
+        // Damage the second record's type byte
+        let mut bytes = std::fs::read(temp_file.path()).unwrap();
+        bytes[damaged as usize] = 0xEE;
+        std::fs::write(temp_file.path(), &bytes).unwrap();
+
+        let mut aof = AOFFile::open(temp_file.path()).unwrap();
+        let (commands, stats) = aof
+            .replay_from_offset(0, RecoveryMode::SkipCorrupted)
+            .unwrap();
+        let keys: Vec<_> = commands
+            .iter()
+            .map(|(_, command)| match command {
+                AOFCommand::Set { key, .. }
+                | AOFCommand::Delete { key }
+                | AOFCommand::Expire { key, .. } => key.clone(),
+            })
+            .collect();
+        assert_eq!(keys, vec![&b"key1"[..], &b"key3"[..]]);
+        assert_eq!(stats.commands_replayed, 2);
+        assert_eq!(stats.commands_skipped, 1);
+        assert_eq!(stats.final_offset, bytes.len() as u64);
+        // Skipping leaves the file as it was
+        assert_eq!(std::fs::read(temp_file.path()).unwrap(), bytes);
+    }
+
+    #[test]
+    fn test_checksum_footer_verification() {
+        let temp_file = NamedTempFile::new().unwrap();
+        {
+            let mut aof = AOFFile::open(temp_file.path()).unwrap();
+            aof.write_set(b"key1", b"value1", None).unwrap();
+            aof.sync().unwrap();
+            aof.write_delete(b"key1").unwrap();
+            aof.sync().unwrap();
+            // Nothing new to seal, so no extra footer is written
+            let size = aof.size();
+            aof.sync().unwrap();
+            assert_eq!(aof.size(), size);
+
+            assert!(aof.verify_checksum().unwrap());
+            assert_eq!(aof.replay().unwrap().len(), 2);
+        }
+
+        // Reopening resumes the running checksum
+        {
+            let mut aof = AOFFile::open(temp_file.path()).unwrap();
+            aof.write_set(b"key2", b"value2", None).unwrap();
+            aof.sync().unwrap();
+            assert!(aof.verify_checksum().unwrap());
+        }
+
+        // Flip a byte inside the first value
+        let mut bytes = std::fs::read(temp_file.path()).unwrap();
+        let pos = bytes.windows(6).position(|w| w == b"value1").unwrap();
+        bytes[pos] ^= 0xFF;
+        std::fs::write(temp_file.path(), &bytes).unwrap();
+
+        let mut aof = AOFFile::open(temp_file.path()).unwrap();
+        assert!(!aof.verify_checksum().unwrap());
+    }
 }
@@ -0,0 +1,320 @@
+//! Map projections for Spatio
+//!
+//! Points are stored as WGS84 latitude/longitude. This module converts them
+//! to and from projected coordinate systems used by engineering and
+//! surveying tools.
+
+use crate::error::{Result, SpatioError};
+use crate::spatial::Point;
+
+/// WGS84 semi-major axis in meters
+const WGS84_A: f64 = 6_378_137.0;
+
+/// WGS84 flattening
+const WGS84_F: f64 = 1.0 / 298.257_223_563;
+
+/// UTM scale factor on the central meridian
+const UTM_K0: f64 = 0.9996;
+
+/// Easting of every zone's central meridian
+const UTM_FALSE_EASTING: f64 = 500_000.0;
+
+/// Northing added in the southern hemisphere
+const UTM_FALSE_NORTHING: f64 = 10_000_000.0;
+
+/// Latitude band letters from 80°S northwards, 8° each (X spans 12°)
+const UTM_BANDS: &[u8] = b"CDEFGHJKLMNPQRSTUVWX";
+
+/// Conversions between WGS84 points and projected coordinates.
+pub struct CoordinateSystem;
+
+impl CoordinateSystem {
+    /// Project a point to Universal Transverse Mercator coordinates.
+    ///
+    /// Uses the standard transverse Mercator series on the WGS84
+    /// ellipsoid, including the zone exceptions around Norway and
+    /// Svalbard.
+    ///
+    /// # Arguments
+    ///
+    /// * `point` - The point to project
+    ///
+    /// # Returns
+    ///
+    /// `(zone_number, zone_letter, easting, northing)` in meters
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the point lies outside the UTM latitude range
+    /// of 80°S to 84°N or has an invalid longitude.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{CoordinateSystem, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (zone, letter, easting, northing) =
+    ///     CoordinateSystem::to_utm(&Point::new(40.7128, -74.0060))?;
+    /// assert_eq!((zone, letter), (18, 'T'));
+    /// assert!((easting - 583_959.37).abs() < 0.01);
+    /// assert!((northing - 4_507_350.99).abs() < 0.01);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn to_utm(point: &Point) -> Result<(u32, char, f64, f64)> {
+        let (lat, lon) = (point.lat, point.lon);
+        if !(-80.0..=84.0).contains(&lat) {
+            return Err(SpatioError::Other(format!(
+                "Latitude {} is outside the UTM range of -80 to 84",
+                lat
+            )));
+        }
+        if !(-180.0..=180.0).contains(&lon) {
+            return Err(SpatioError::Other(format!(
+                "Longitude {} must be between -180 and 180",
+                lon
+            )));
+        }
+
+        let zone = utm_zone(lat, lon);
+        let band = (((lat + 80.0) / 8.0) as usize).min(UTM_BANDS.len() - 1);
+        let letter = UTM_BANDS[band] as char;
+
+        let e2 = WGS84_F * (2.0 - WGS84_F);
+        let ep2 = e2 / (1.0 - e2);
+        let phi = lat.to_radians();
+        let (sin_phi, cos_phi, tan_phi) = (phi.sin(), phi.cos(), phi.tan());
+
+        let n = WGS84_A / (1.0 - e2 * sin_phi * sin_phi).sqrt();
+        let t = tan_phi * tan_phi;
+        let c = ep2 * cos_phi * cos_phi;
+        let a = cos_phi * (lon - central_meridian(zone)).to_radians();
+        let m = meridian_arc(phi, e2);
+
+        let easting = UTM_K0
+            * n
+            * (a + (1.0 - t + c) * a.powi(3) / 6.0
+                + (5.0 - 18.0 * t + t * t + 72.0 * c - 58.0 * ep2) * a.powi(5) / 120.0)
+            + UTM_FALSE_EASTING;
+        let mut northing = UTM_K0
+            * (m + n
+                * tan_phi
+                * (a * a / 2.0
+                    + (5.0 - t + 9.0 * c + 4.0 * c * c) * a.powi(4) / 24.0
+                    + (61.0 - 58.0 * t + t * t + 600.0 * c - 330.0 * ep2) * a.powi(6) / 720.0));
+        if lat < 0.0 {
+            northing += UTM_FALSE_NORTHING;
+        }
+
+        Ok((zone, letter, easting, northing))
+    }
+
+    /// Convert Universal Transverse Mercator coordinates back to a point.
+    ///
+    /// The zone letter only selects the hemisphere: `N` and later letters
+    /// are northern, earlier letters southern.
+    ///
+    /// # Arguments
+    ///
+    /// * `zone` - Zone number (1-60)
+    /// * `letter` - Latitude band letter (C-X, excluding I and O)
+    /// * `easting` - Easting in meters (100,000-900,000)
+    /// * `northing` - Northing in meters (0-10,000,000)
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the zone, letter, easting or northing is out of
+    /// range.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::CoordinateSystem;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let point = CoordinateSystem::from_utm(18, 'T', 583_959.37, 4_507_350.99)?;
+    /// assert!((point.lat - 40.7128).abs() < 1e-4);
+    /// assert!((point.lon + 74.0060).abs() < 1e-4);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_utm(zone: u32, letter: char, easting: f64, northing: f64) -> Result<Point> {
+        if !(1..=60).contains(&zone) {
+            return Err(SpatioError::Other(format!(
+                "UTM zone {} must be between 1 and 60",
+                zone
+            )));
+        }
+        let letter = letter.to_ascii_uppercase();
+        if !letter.is_ascii() || !UTM_BANDS.contains(&(letter as u8)) {
+            return Err(SpatioError::Other(format!(
+                "UTM zone letter '{}' must be C-X, excluding I and O",
+                letter
+            )));
+        }
+        if !(100_000.0..=900_000.0).contains(&easting) {
+            return Err(SpatioError::Other(format!(
+                "UTM easting {} must be between 100000 and 900000",
+                easting
+            )));
+        }
+        if !(0.0..=UTM_FALSE_NORTHING).contains(&northing) {
+            return Err(SpatioError::Other(format!(
+                "UTM northing {} must be between 0 and 10000000",
+                northing
+            )));
+        }
+
+        let e2 = WGS84_F * (2.0 - WGS84_F);
+        let ep2 = e2 / (1.0 - e2);
+        let e1 = (1.0 - (1.0 - e2).sqrt()) / (1.0 + (1.0 - e2).sqrt());
+
+        let x = easting - UTM_FALSE_EASTING;
+        let y = if letter >= 'N' {
+            northing
+        } else {
+            northing - UTM_FALSE_NORTHING
+        };
+
+        // Footpoint latitude: where the meridian arc equals y / k0
+        let mu = y
+            / UTM_K0
+            / (WGS84_A * (1.0 - e2 / 4.0 - 3.0 * e2 * e2 / 64.0 - 5.0 * e2.powi(3) / 256.0));
+        let phi1 = mu
+            + (3.0 * e1 / 2.0 - 27.0 * e1.powi(3) / 32.0) * (2.0 * mu).sin()
+            + (21.0 * e1 * e1 / 16.0 - 55.0 * e1.powi(4) / 32.0) * (4.0 * mu).sin()
+            + (151.0 * e1.powi(3) / 96.0) * (6.0 * mu).sin()
+            + (1097.0 * e1.powi(4) / 512.0) * (8.0 * mu).sin();
+
+        let (sin_phi1, cos_phi1, tan_phi1) = (phi1.sin(), phi1.cos(), phi1.tan());
+        let denom = 1.0 - e2 * sin_phi1 * sin_phi1;
+        let n1 = WGS84_A / denom.sqrt();
+        let r1 = WGS84_A * (1.0 - e2) / denom.powf(1.5);
+        let t1 = tan_phi1 * tan_phi1;
+        let c1 = ep2 * cos_phi1 * cos_phi1;
+        let d = x / (n1 * UTM_K0);
+
+        let lat = phi1
+            - (n1 * tan_phi1 / r1)
+                * (d * d / 2.0
+                    - (5.0 + 3.0 * t1 + 10.0 * c1 - 4.0 * c1 * c1 - 9.0 * ep2) * d.powi(4) / 24.0
+                    + (61.0 + 90.0 * t1 + 298.0 * c1 + 45.0 * t1 * t1
+                        - 252.0 * ep2
+                        - 3.0 * c1 * c1)
+                        * d.powi(6)
+                        / 720.0);
+        let lon = (d - (1.0 + 2.0 * t1 + c1) * d.powi(3) / 6.0
+            + (5.0 - 2.0 * c1 + 28.0 * t1 - 3.0 * c1 * c1 + 8.0 * ep2 + 24.0 * t1 * t1)
+                * d.powi(5)
+                / 120.0)
+            / cos_phi1;
+
+        Ok(Point::new(
+            lat.to_degrees(),
+            central_meridian(zone) + lon.to_degrees(),
+        ))
+    }
+}
+
+/// UTM zone for a point, including the Norway and Svalbard exceptions
+fn utm_zone(lat: f64, lon: f64) -> u32 {
+    if (56.0..64.0).contains(&lat) && (3.0..12.0).contains(&lon) {
+        return 32;
+    }
+    if (72.0..=84.0).contains(&lat) && (0.0..42.0).contains(&lon) {
+        return match lon {
+            lon if lon < 9.0 => 31,
+            lon if lon < 21.0 => 33,
+            lon if lon < 33.0 => 35,
+            _ => 37,
+        };
+    }
+    (((lon + 180.0) / 6.0) as u32 + 1).min(60)
+}
+
+/// Longitude of a zone's central meridian in degrees
+fn central_meridian(zone: u32) -> f64 {
+    zone as f64 * 6.0 - 183.0
+}
+
+/// Distance along the meridian from the equator to latitude `phi`
+fn meridian_arc(phi: f64, e2: f64) -> f64 {
+    let (e4, e6) = (e2 * e2, e2.powi(3));
+    WGS84_A
+        * ((1.0 - e2 / 4.0 - 3.0 * e4 / 64.0 - 5.0 * e6 / 256.0) * phi
+            - (3.0 * e2 / 8.0 + 3.0 * e4 / 32.0 + 45.0 * e6 / 1024.0) * (2.0 * phi).sin()
+            + (15.0 * e4 / 256.0 + 45.0 * e6 / 1024.0) * (4.0 * phi).sin()
+            - (35.0 * e6 / 3072.0) * (6.0 * phi).sin())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_utm_reference_points() {
+        // The equator at the Greenwich meridian is 3° west of zone 31's center
+        let (zone, letter, easting, northing) =
+            CoordinateSystem::to_utm(&Point::new(0.0, 0.0)).unwrap();
+        assert_eq!((zone, letter), (31, 'N'));
+        assert!((easting - 166_021.443).abs() < 0.01);
+        assert!(northing.abs() < 0.01);
+
+        // Points on a central meridian sit at the false easting
+        let (zone, letter, easting, northing) =
+            CoordinateSystem::to_utm(&Point::new(-33.0, 153.0)).unwrap();
+        assert_eq!((zone, letter), (56, 'H'));
+        assert!((easting - 500_000.0).abs() < 1e-6);
+        assert!(northing < UTM_FALSE_NORTHING);
+    }
+
+    #[test]
+    fn test_utm_round_trip() {
+        for (lat, lon) in [
+            (40.7128, -74.0060),
+            (-33.8688, 151.2093),
+            (51.5074, -0.1278),
+            (60.0, 5.0),
+            (78.2232, 15.6267),
+            (-79.9, -179.9),
+            (83.9, 179.9),
+        ] {
+            let (zone, letter, easting, northing) =
+                CoordinateSystem::to_utm(&Point::new(lat, lon)).unwrap();
+            let point = CoordinateSystem::from_utm(zone, letter, easting, northing).unwrap();
+            assert!((point.lat - lat).abs() < 1e-6, "{lat}, {lon}");
+            assert!((point.lon - lon).abs() < 1e-6, "{lat}, {lon}");
+        }
+    }
+
+    #[test]
+    fn test_utm_zone_exceptions() {
+        // Southwestern Norway is widened into zone 32
+        let (zone, letter, _, _) = CoordinateSystem::to_utm(&Point::new(60.0, 5.0)).unwrap();
+        assert_eq!((zone, letter), (32, 'V'));
+
+        // Svalbard uses the odd zones 31-37
+        let (zone, letter, _, _) = CoordinateSystem::to_utm(&Point::new(78.2232, 15.6267)).unwrap();
+        assert_eq!((zone, letter), (33, 'X'));
+
+        // 180° belongs to zone 60
+        let (zone, _, _, _) = CoordinateSystem::to_utm(&Point::new(0.0, 180.0)).unwrap();
+        assert_eq!(zone, 60);
+    }
+
+    #[test]
+    fn test_utm_validation() {
+        assert!(CoordinateSystem::to_utm(&Point::new(85.0, 0.0)).is_err());
+        assert!(CoordinateSystem::to_utm(&Point::new(-80.5, 0.0)).is_err());
+
+        assert!(CoordinateSystem::from_utm(0, 'N', 500_000.0, 0.0).is_err());
+        assert!(CoordinateSystem::from_utm(61, 'N', 500_000.0, 0.0).is_err());
+        assert!(CoordinateSystem::from_utm(31, 'I', 500_000.0, 0.0).is_err());
+        assert!(CoordinateSystem::from_utm(31, 'Z', 500_000.0, 0.0).is_err());
+        assert!(CoordinateSystem::from_utm(31, 'N', 50_000.0, 0.0).is_err());
+        assert!(CoordinateSystem::from_utm(31, 'N', 500_000.0, -1.0).is_err());
+        assert!(CoordinateSystem::from_utm(31, 'N', 500_000.0, 10_000_001.0).is_err());
+        assert!(CoordinateSystem::from_utm(31, 'n', 500_000.0, 0.0).is_ok());
+    }
+}
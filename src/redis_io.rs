@@ -0,0 +1,289 @@
+//! Redis import for Spatio
+//!
+//! This module adds migrating keys out of a Redis server. Keys are listed
+//! in batches with `SCAN`, and each batch's types, TTLs and values are
+//! fetched with pipelines, so large keyspaces are imported without holding
+//! them in memory at once. Keys are written as they are read: if the
+//! connection fails part way through, the keys before the error stay in
+//! the database.
+
+use crate::DB;
+use crate::error::{Result, SpatioError};
+use crate::spatial::Point;
+use crate::types::{RedisImportStats, SetOptions};
+use redis::{Connection, Value};
+use std::time::Duration;
+
+/// Keys requested from each `SCAN` call
+const SCAN_BATCH_SIZE: usize = 500;
+
+/// A Redis key found by `SCAN`, with what is needed to import it
+struct RedisKey {
+    name: Vec<u8>,
+    /// Options to insert with, `None` if the key expired or vanished
+    opts: Option<Option<SetOptions>>,
+    kind: RedisKeyKind,
+}
+
+/// Redis value types handled by the import
+enum RedisKeyKind {
+    String,
+    SortedSet,
+    Unsupported,
+}
+
+impl DB {
+    /// Import keys from a Redis server.
+    ///
+    /// Every key matching `key_pattern` (a Redis glob, as for `SCAN MATCH`)
+    /// is copied into the database:
+    ///
+    /// - Strings are inserted under the same key with `insert`.
+    /// - Geo sets (sorted sets written by `GEOADD`) become points: each
+    ///   member is inserted with `insert_point`, using the Redis key as the
+    ///   prefix and the member name as the value.
+    /// - Other types (hashes, lists, sets, streams) are skipped.
+    ///
+    /// Keys with a TTL in Redis keep the remaining time to live, through
+    /// `SetOptions::with_ttl`. `opts` applies to keys without one.
+    ///
+    /// # Arguments
+    ///
+    /// * `redis_url` - Connection URL, e.g. `redis://127.0.0.1:6379/0`
+    /// * `key_pattern` - Glob pattern selecting the keys to import (`*` for all)
+    /// * `opts` - Optional settings like TTL for keys without a Redis TTL
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server cannot be reached, a Redis command
+    /// fails, or a key cannot be stored.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use spatio::Spatio;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// let stats = db.import_from_redis("redis://127.0.0.1:6379", "user:*", None)?;
+    /// println!("imported {} keys", stats.keys_imported);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.import_from_redis",
+            skip_all,
+            fields(db.key_pattern = key_pattern),
+            err
+        )
+    )]
+    pub fn import_from_redis(
+        &self,
+        redis_url: &str,
+        key_pattern: &str,
+        opts: Option<SetOptions>,
+    ) -> Result<RedisImportStats> {
+        let client = redis::Client::open(redis_url).map_err(redis_error)?;
+        let mut con = client.get_connection().map_err(redis_error)?;
+
+        let mut stats = RedisImportStats::default();
+        let mut cursor = 0u64;
+        loop {
+            let (next, names): (u64, Vec<Vec<u8>>) = redis::cmd("SCAN")
+                .arg(cursor)
+                .arg("MATCH")
+                .arg(key_pattern)
+                .arg("COUNT")
+                .arg(SCAN_BATCH_SIZE)
+                .query(&mut con)
+                .map_err(redis_error)?;
+
+            // SCAN may return a key more than once; importing it twice is
+            // harmless apart from the counts
+            let keys = describe_keys(&mut con, names, opts.as_ref())?;
+            self.import_redis_strings(&mut con, &keys, &mut stats)?;
+            self.import_redis_geo_sets(&mut con, &keys, &mut stats)?;
+            stats.keys_skipped += keys
+                .iter()
+                .filter(|key| key.opts.is_none() || matches!(key.kind, RedisKeyKind::Unsupported))
+                .count();
+
+            if next == 0 {
+                return Ok(stats);
+            }
+            cursor = next;
+        }
+    }
+
+    /// Copy the string keys of a batch with one `GET` pipeline
+    fn import_redis_strings(
+        &self,
+        con: &mut Connection,
+        keys: &[RedisKey],
+        stats: &mut RedisImportStats,
+    ) -> Result<()> {
+        let strings: Vec<_> = importable(keys, |kind| matches!(kind, RedisKeyKind::String));
+        if strings.is_empty() {
+            return Ok(());
+        }
+
+        let mut pipe = redis::pipe();
+        for (key, _) in &strings {
+            pipe.cmd("GET").arg(&key.name);
+        }
+        let values: Vec<Option<Vec<u8>>> = pipe.query(con).map_err(redis_error)?;
+
+        for ((key, opts), value) in strings.into_iter().zip(values) {
+            match value {
+                Some(value) => {
+                    self.insert(&key.name, &value, opts.clone())?;
+                    stats.keys_imported += 1;
+                    stats.bytes_transferred += key.name.len() + value.len();
+                }
+                // Deleted since TYPE was checked
+                None => stats.keys_skipped += 1,
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy the geo sets of a batch as points.
+    ///
+    /// Members come from a `ZRANGE` pipeline and their positions from a
+    /// `GEOPOS` pipeline. Sorted sets whose key is not UTF-8, or with
+    /// members `GEOPOS` cannot place, are not geo sets and are skipped.
+    fn import_redis_geo_sets(
+        &self,
+        con: &mut Connection,
+        keys: &[RedisKey],
+        stats: &mut RedisImportStats,
+    ) -> Result<()> {
+        let mut sets = Vec::new();
+        for (key, opts) in importable(keys, |kind| matches!(kind, RedisKeyKind::SortedSet)) {
+            match std::str::from_utf8(&key.name) {
+                Ok(prefix) => sets.push((prefix, opts)),
+                Err(_) => stats.keys_skipped += 1,
+            }
+        }
+        if sets.is_empty() {
+            return Ok(());
+        }
+
+        let mut pipe = redis::pipe();
+        for (prefix, _) in &sets {
+            pipe.cmd("ZRANGE").arg(prefix).arg(0).arg(-1);
+        }
+        let members: Vec<Vec<Vec<u8>>> = pipe.query(con).map_err(redis_error)?;
+
+        // GEOPOS needs at least one member; a set that came back empty was
+        // deleted since TYPE was checked
+        let mut pipe = redis::pipe();
+        for ((prefix, _), members) in sets.iter().zip(&members) {
+            if !members.is_empty() {
+                pipe.cmd("GEOPOS").arg(prefix).arg(members);
+            }
+        }
+        let positions: Vec<Vec<Option<(f64, f64)>>> = if members.iter().any(|m| !m.is_empty()) {
+            pipe.query(con).map_err(redis_error)?
+        } else {
+            Vec::new()
+        };
+        let mut positions = positions.into_iter();
+
+        for ((prefix, opts), members) in sets.into_iter().zip(members) {
+            if members.is_empty() {
+                stats.keys_skipped += 1;
+                continue;
+            }
+            let points: Option<Vec<Point>> = positions
+                .next()
+                .unwrap_or_default()
+                .into_iter()
+                .map(|position| position.and_then(|(lon, lat)| Point::try_new(lat, lon).ok()))
+                .collect();
+            let points = match points {
+                Some(points) if points.len() == members.len() => points,
+                _ => {
+                    stats.keys_skipped += 1;
+                    continue;
+                }
+            };
+
+            for (point, member) in points.iter().zip(&members) {
+                self.insert_point(prefix, point, member, opts.clone())?;
+                stats.bytes_transferred += member.len();
+            }
+            stats.keys_imported += 1;
+            stats.bytes_transferred += prefix.len();
+        }
+        Ok(())
+    }
+}
+
+/// Look up the type and remaining TTL of each key with one pipeline.
+///
+/// A key's options are its Redis TTL when it has one, otherwise `opts`.
+fn describe_keys(
+    con: &mut Connection,
+    names: Vec<Vec<u8>>,
+    opts: Option<&SetOptions>,
+) -> Result<Vec<RedisKey>> {
+    if names.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut pipe = redis::pipe();
+    for name in &names {
+        pipe.cmd("TYPE").arg(name).cmd("PTTL").arg(name);
+    }
+    let replies: Vec<Value> = pipe.query(con).map_err(redis_error)?;
+
+    let mut replies = replies.into_iter();
+    let mut keys = Vec::with_capacity(names.len());
+    for name in names {
+        let (kind, ttl) = match (replies.next(), replies.next()) {
+            (Some(kind), Some(ttl)) => (kind, ttl),
+            _ => {
+                return Err(SpatioError::Other(
+                    "Redis error: incomplete pipeline reply".to_string(),
+                ));
+            }
+        };
+        let kind: String = redis::from_redis_value(kind).map_err(redis_error)?;
+        let ttl: i64 = redis::from_redis_value(ttl).map_err(redis_error)?;
+
+        let kind = match kind.as_str() {
+            "string" => RedisKeyKind::String,
+            "zset" => RedisKeyKind::SortedSet,
+            _ => RedisKeyKind::Unsupported,
+        };
+        // -1 means no TTL; -2 means the key is gone, and 0 that it is
+        // about to be
+        let opts = match ttl {
+            -1 => Some(opts.cloned()),
+            millis if millis > 0 => Some(Some(SetOptions::with_ttl(Duration::from_millis(
+                millis as u64,
+            )))),
+            _ => None,
+        };
+        keys.push(RedisKey { name, opts, kind });
+    }
+    Ok(keys)
+}
+
+/// Keys of the wanted kind that still exist, with their options
+fn importable(
+    keys: &[RedisKey],
+    wanted: impl Fn(&RedisKeyKind) -> bool,
+) -> Vec<(&RedisKey, &Option<SetOptions>)> {
+    keys.iter()
+        .filter(|key| wanted(&key.kind))
+        .filter_map(|key| key.opts.as_ref().map(|opts| (key, opts)))
+        .collect()
+}
+
+fn redis_error(err: impl std::fmt::Display) -> SpatioError {
+    SpatioError::Other(format!("Redis error: {}", err))
+}
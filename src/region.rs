@@ -0,0 +1,353 @@
+//! Geofencing streams for Spatio
+//!
+//! This module turns trajectory inserts into a stream of enter and exit
+//! events for a circular region, and point inserts into a stream of
+//! sliding-window results for one. `RegionWatcher` and
+//! `SpatialWindowWatcher` implement `futures::Stream`, so they can be
+//! consumed from any async runtime (tokio, async-std, ...) without tying
+//! Spatio to one.
+
+use crate::clock;
+use crate::db::{WindowPoint, latest_points};
+use crate::spatial::Point;
+use bytes::Bytes;
+use futures::Stream;
+use futures::channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{Duration, SystemTime};
+
+/// Direction of a region boundary crossing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegionEventType {
+    /// The object moved from outside the region to inside it
+    Enter,
+    /// The object moved from inside the region to outside it
+    Exit,
+}
+
+/// An object crossing a watched region's boundary
+#[derive(Debug, Clone, PartialEq)]
+pub struct RegionEvent {
+    /// Trajectory object id, as passed to `insert_trajectory`
+    pub object_id: String,
+    /// Whether the object entered or left the region
+    pub event: RegionEventType,
+    /// The trajectory point that crossed the boundary
+    pub point: Point,
+    /// Timestamp of that trajectory point
+    pub timestamp: u64,
+}
+
+/// Stream of `RegionEvent`s returned by `DB::watch_region`.
+///
+/// The stream ends when the database is dropped. Dropping the watcher
+/// unregisters it.
+pub struct RegionWatcher {
+    receiver: UnboundedReceiver<RegionEvent>,
+}
+
+impl Stream for RegionWatcher {
+    type Item = RegionEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+/// A registered region watch, fed by trajectory inserts
+pub(crate) struct RegionSubscription {
+    prefix: String,
+    center: Point,
+    radius_meters: f64,
+    /// Last seen timestamp per object and whether it was inside
+    objects: HashMap<String, (u64, bool)>,
+    sender: UnboundedSender<RegionEvent>,
+}
+
+impl RegionSubscription {
+    /// Create a subscription and the watcher that receives its events
+    pub fn new(prefix: &str, center: Point, radius_meters: f64) -> (Self, RegionWatcher) {
+        let (sender, receiver) = mpsc::unbounded();
+        let subscription = Self {
+            prefix: prefix.to_string(),
+            center,
+            radius_meters,
+            objects: HashMap::new(),
+            sender,
+        };
+        (subscription, RegionWatcher { receiver })
+    }
+
+    /// Record an object's position without emitting an event
+    pub fn seed(&mut self, object_id: &str, point: &Point, timestamp: u64) {
+        let inside = self.contains(point);
+        self.objects
+            .insert(object_id.to_string(), (timestamp, inside));
+    }
+
+    /// Process a trajectory key write.
+    ///
+    /// Objects without a known previous position are treated as coming
+    /// from outside the region. Points older than the last one seen for
+    /// an object are ignored. Returns `false` once the watcher has been
+    /// dropped.
+    pub fn observe(&mut self, key: &[u8], value: &[u8]) -> bool {
+        if self.sender.is_closed() {
+            return false;
+        }
+
+        let Some(object_id) = trajectory_object_id(key) else {
+            return true;
+        };
+        if !object_id.starts_with(&self.prefix) {
+            return true;
+        }
+        let Ok((point, timestamp)) = bincode::deserialize::<(Point, u64)>(value) else {
+            return true;
+        };
+
+        let inside = self.contains(&point);
+        let was_inside = match self.objects.get(object_id) {
+            Some(&(last_timestamp, _)) if timestamp < last_timestamp => return true,
+            Some(&(_, was_inside)) => was_inside,
+            None => false,
+        };
+        self.objects
+            .insert(object_id.to_string(), (timestamp, inside));
+
+        if inside != was_inside {
+            let event = RegionEvent {
+                object_id: object_id.to_string(),
+                event: if inside {
+                    RegionEventType::Enter
+                } else {
+                    RegionEventType::Exit
+                },
+                point,
+                timestamp,
+            };
+            return self.sender.unbounded_send(event).is_ok();
+        }
+        true
+    }
+
+    fn contains(&self, point: &Point) -> bool {
+        self.center.distance_to(point) <= self.radius_meters
+    }
+}
+
+/// Stream of `DB::spatial_window` results returned by
+/// `DB::subscribe_spatial_window`.
+///
+/// Each item is the full, updated window. The stream ends when the
+/// database is dropped. Dropping the watcher unregisters it.
+pub struct SpatialWindowWatcher {
+    receiver: UnboundedReceiver<Vec<(Point, Bytes)>>,
+}
+
+impl Stream for SpatialWindowWatcher {
+    type Item = Vec<(Point, Bytes)>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.receiver).poll_next(cx)
+    }
+}
+
+/// A registered sliding-window watch, fed by point inserts
+pub(crate) struct WindowSubscription {
+    prefix: String,
+    center: Point,
+    radius_meters: f64,
+    window: Duration,
+    limit: usize,
+    /// Points currently in the region, by storage key
+    points: HashMap<Bytes, WindowPoint>,
+    sender: UnboundedSender<Vec<(Point, Bytes)>>,
+}
+
+impl WindowSubscription {
+    /// Create a subscription starting from the points already in the
+    /// window, and the watcher that receives its updates
+    pub fn new(
+        prefix: &str,
+        center: Point,
+        radius_meters: f64,
+        window: Duration,
+        limit: usize,
+        seed: Vec<(Bytes, WindowPoint)>,
+    ) -> (Self, SpatialWindowWatcher) {
+        let (sender, receiver) = mpsc::unbounded();
+        let subscription = Self {
+            prefix: prefix.to_string(),
+            center,
+            radius_meters,
+            window,
+            limit,
+            points: seed.into_iter().collect(),
+            sender,
+        };
+        (subscription, SpatialWindowWatcher { receiver })
+    }
+
+    /// Process a point written by `insert_point` under `key`.
+    ///
+    /// `set_at` is when its TTL was set, or `None` if it has no TTL, in
+    /// which case it leaves the window. Returns `false` once the watcher
+    /// has been dropped.
+    pub fn observe(
+        &mut self,
+        prefix: &str,
+        key: &Bytes,
+        point: &Point,
+        value: &Bytes,
+        set_at: Option<SystemTime>,
+    ) -> bool {
+        if self.sender.is_closed() {
+            return false;
+        }
+        if prefix != self.prefix {
+            return true;
+        }
+
+        match set_at {
+            Some(set_at) if self.center.distance_to(point) <= self.radius_meters => {
+                let point = WindowPoint {
+                    set_at,
+                    point: *point,
+                    value: value.clone(),
+                };
+                self.points.insert(key.clone(), point);
+                self.publish()
+            }
+            _ => self.forget(key),
+        }
+    }
+
+    /// Drop a deleted or expired key from the window, sending an update
+    /// if it was in it. Returns `false` once the watcher has been dropped.
+    pub fn forget(&mut self, key: &Bytes) -> bool {
+        if self.points.remove(key).is_some() {
+            return self.publish();
+        }
+        !self.sender.is_closed()
+    }
+
+    /// Drop points older than the window and send the latest `limit`
+    fn publish(&mut self) -> bool {
+        let since = clock::now()
+            .checked_sub(self.window)
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        self.points.retain(|_, point| point.set_at >= since);
+
+        let points = self.points.values().cloned().collect();
+        let update = latest_points(points, self.limit);
+        self.sender.unbounded_send(update).is_ok()
+    }
+}
+
+/// Extract the object id from a `traj:{object_id}:{timestamp}:{index}` key
+pub(crate) fn trajectory_object_id(key: &[u8]) -> Option<&str> {
+    let key = std::str::from_utf8(key).ok()?.strip_prefix("traj:")?;
+    let (rest, _index) = key.rsplit_once(':')?;
+    let (object_id, _timestamp) = rest.rsplit_once(':')?;
+    Some(object_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DB;
+    use crate::spatial::SpatialKey;
+    use crate::types::SetOptions;
+    use futures::StreamExt;
+    use futures::executor::block_on;
+
+    #[test]
+    fn test_trajectory_object_id() {
+        assert_eq!(
+            trajectory_object_id(b"traj:vehicle:42:1640995200:000001"),
+            Some("vehicle:42")
+        );
+        assert_eq!(trajectory_object_id(b"user:1"), None);
+    }
+
+    #[test]
+    fn test_watch_region_enter_and_exit() {
+        let db = DB::memory().unwrap();
+        let center = Point::new(40.7128, -74.0060);
+        let outside = Point::new(40.7600, -74.0060);
+
+        // Known starting position outside the region
+        db.insert_trajectory("vehicle:1", &[(outside, 100)], None)
+            .unwrap();
+        let mut watcher = db.watch_region("vehicle:", center, 1000.0).unwrap();
+
+        db.insert_trajectory(
+            "vehicle:1",
+            &[(outside, 200), (center, 300), (center, 400), (outside, 500)],
+            None,
+        )
+        .unwrap();
+        // Other objects and stale points do not produce events
+        db.insert_trajectory("drone:1", &[(center, 300)], None)
+            .unwrap();
+        db.insert_trajectory("vehicle:1", &[(center, 50)], None)
+            .unwrap();
+
+        let enter = block_on(watcher.next()).unwrap();
+        assert_eq!(enter.object_id, "vehicle:1");
+        assert_eq!(enter.event, RegionEventType::Enter);
+        assert_eq!(enter.timestamp, 300);
+
+        let exit = block_on(watcher.next()).unwrap();
+        assert_eq!(exit.event, RegionEventType::Exit);
+        assert_eq!(exit.point, outside);
+        assert_eq!(exit.timestamp, 500);
+
+        drop(db);
+        assert!(block_on(watcher.next()).is_none());
+    }
+
+    #[test]
+    fn test_subscribe_spatial_window() {
+        let db = DB::memory().unwrap();
+        let center = Point::new(40.7128, -74.0060);
+        let near = Point::new(40.7130, -74.0062);
+        let ttl = SetOptions::with_ttl(Duration::from_secs(60));
+
+        db.insert_point("pings", &center, b"before", Some(ttl.clone()))
+            .unwrap();
+        let mut updates = db
+            .subscribe_spatial_window("pings", center, 1000.0, Duration::from_secs(60), 5)
+            .unwrap();
+
+        // Points outside the region or namespace do not produce updates
+        db.insert_point(
+            "pings",
+            &Point::new(40.7600, -74.0060),
+            b"far",
+            Some(ttl.clone()),
+        )
+        .unwrap();
+        db.insert_point("other", &near, b"elsewhere", Some(ttl.clone()))
+            .unwrap();
+        db.insert_point("pings", &near, b"after", Some(ttl))
+            .unwrap();
+
+        let window = block_on(updates.next()).unwrap();
+        let values: Vec<&[u8]> = window.iter().map(|(_, v)| v.as_ref()).collect();
+        assert_eq!(values, vec![&b"after"[..], &b"before"[..]]);
+
+        // Removing a point in the window sends the window without it
+        let geohash = near.to_geohash(8).unwrap();
+        db.delete(SpatialKey::geohash("pings", &geohash)).unwrap();
+        let window = block_on(updates.next()).unwrap();
+        assert_eq!(window.len(), 1);
+        assert_eq!(window[0].1.as_ref(), b"before");
+
+        drop(db);
+        assert!(block_on(updates.next()).is_none());
+    }
+}
@@ -0,0 +1,809 @@
+//! RESP (Redis Serialization Protocol) server for Spatio
+//!
+//! This module lets Redis clients and tools talk to a database over TCP.
+//! It covers the key-value commands most clients rely on, plus two
+//! commands for points:
+//!
+//! - `PING [message]`
+//! - `SET key value [EX seconds | PX milliseconds]`
+//! - `GET key`
+//! - `DEL key [key ...]`
+//! - `TTL key`
+//! - `EXPIRE key seconds`
+//! - `GEOSET prefix longitude latitude member` stores `member` as a point
+//!   with `DB::insert_point`
+//! - `GEORADIUSMEMBER prefix longitude latitude radius [m|km|ft|mi] [COUNT count]`
+//!   returns the members within the radius, nearest first, using
+//!   `DB::find_nearby`
+//!
+//! Commands run on the Tokio worker handling the connection. Database
+//! operations are short and in memory, so they are not moved to a
+//! blocking thread.
+
+use crate::DB;
+use crate::error::{Result, SpatioError};
+use crate::spatial::Point;
+use crate::types::SetOptions;
+use bytes::Bytes;
+use std::net::SocketAddr;
+use std::time::{Duration, SystemTime};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Largest bulk string accepted, matching Redis's default `proto-max-bulk-len`
+const MAX_BULK_LENGTH: usize = 512 * 1024 * 1024;
+
+/// Deepest array nesting accepted. Requests are flat arrays; the limit
+/// only stops malicious input from exhausting the stack.
+const MAX_NESTING: usize = 32;
+
+/// A RESP2 value, as sent by clients and returned in replies
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RespValue {
+    /// `+OK`
+    SimpleString(String),
+    /// `-ERR message`
+    Error(String),
+    /// `:42`
+    Integer(i64),
+    /// `$5\r\nhello`, or `$-1` for nil
+    BulkString(Option<Bytes>),
+    /// `*2\r\n...`, or `*-1` for nil
+    Array(Option<Vec<RespValue>>),
+}
+
+impl RespValue {
+    /// Parse one value from the start of a buffer.
+    ///
+    /// # Returns
+    ///
+    /// The value and the number of bytes it used, or `None` if the buffer
+    /// ends before the value is complete
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the buffer does not hold valid RESP.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::RespValue;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (value, used) = RespValue::parse(b"*1\r\n$4\r\nPING\r\n")?.unwrap();
+    /// assert_eq!(used, 14);
+    /// assert_eq!(value, RespValue::Array(Some(vec![RespValue::BulkString(Some("PING".into()))])));
+    ///
+    /// assert!(RespValue::parse(b"*1\r\n$4\r\nPI")?.is_none());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn parse(buf: &[u8]) -> Result<Option<(RespValue, usize)>> {
+        Self::parse_nested(buf, 0)
+    }
+
+    fn parse_nested(buf: &[u8], depth: usize) -> Result<Option<(RespValue, usize)>> {
+        let Some((&kind, rest)) = buf.split_first() else {
+            return Ok(None);
+        };
+        let Some(line_end) = rest.windows(2).position(|w| w == b"\r\n") else {
+            return Ok(None);
+        };
+        let line = std::str::from_utf8(&rest[..line_end])
+            .map_err(|_| protocol_error("invalid header line"))?;
+        let header_len = 1 + line_end + 2;
+
+        match kind {
+            b'+' => Ok(Some((
+                RespValue::SimpleString(line.to_string()),
+                header_len,
+            ))),
+            b'-' => Ok(Some((RespValue::Error(line.to_string()), header_len))),
+            b':' => {
+                let value = line
+                    .parse()
+                    .map_err(|_| protocol_error("invalid integer"))?;
+                Ok(Some((RespValue::Integer(value), header_len)))
+            }
+            b'$' => {
+                let len: i64 = line
+                    .parse()
+                    .map_err(|_| protocol_error("invalid bulk length"))?;
+                if len == -1 {
+                    return Ok(Some((RespValue::BulkString(None), header_len)));
+                }
+                let len = usize::try_from(len)
+                    .ok()
+                    .filter(|len| *len <= MAX_BULK_LENGTH)
+                    .ok_or_else(|| protocol_error("invalid bulk length"))?;
+
+                let end = header_len + len;
+                if buf.len() < end + 2 {
+                    return Ok(None);
+                }
+                if &buf[end..end + 2] != b"\r\n" {
+                    return Err(protocol_error("bulk string is not terminated"));
+                }
+                let value = Bytes::copy_from_slice(&buf[header_len..end]);
+                Ok(Some((RespValue::BulkString(Some(value)), end + 2)))
+            }
+            b'*' => {
+                let count: i64 = line
+                    .parse()
+                    .map_err(|_| protocol_error("invalid multibulk length"))?;
+                if count == -1 {
+                    return Ok(Some((RespValue::Array(None), header_len)));
+                }
+                if count < 0 {
+                    return Err(protocol_error("invalid multibulk length"));
+                }
+                if depth >= MAX_NESTING {
+                    return Err(protocol_error("arrays nested too deeply"));
+                }
+
+                let mut items = Vec::new();
+                let mut used = header_len;
+                for _ in 0..count {
+                    match RespValue::parse_nested(&buf[used..], depth + 1)? {
+                        Some((item, len)) => {
+                            items.push(item);
+                            used += len;
+                        }
+                        None => return Ok(None),
+                    }
+                }
+                Ok(Some((RespValue::Array(Some(items)), used)))
+            }
+            _ => Err(protocol_error("expected a RESP type byte")),
+        }
+    }
+
+    /// Append the wire encoding of this value to `out`
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            RespValue::SimpleString(s) => {
+                out.push(b'+');
+                out.extend_from_slice(s.as_bytes());
+            }
+            RespValue::Error(s) => {
+                out.push(b'-');
+                out.extend_from_slice(s.as_bytes());
+            }
+            RespValue::Integer(n) => {
+                out.push(b':');
+                out.extend_from_slice(n.to_string().as_bytes());
+            }
+            RespValue::BulkString(None) => out.extend_from_slice(b"$-1"),
+            RespValue::BulkString(Some(data)) => {
+                out.push(b'$');
+                out.extend_from_slice(data.len().to_string().as_bytes());
+                out.extend_from_slice(b"\r\n");
+                out.extend_from_slice(data);
+            }
+            RespValue::Array(None) => out.extend_from_slice(b"*-1"),
+            RespValue::Array(Some(items)) => {
+                out.push(b'*');
+                out.extend_from_slice(items.len().to_string().as_bytes());
+                out.extend_from_slice(b"\r\n");
+                for item in items {
+                    item.encode(out);
+                }
+                return;
+            }
+        }
+        out.extend_from_slice(b"\r\n");
+    }
+}
+
+/// A command understood by `RespServer`
+#[derive(Debug, Clone, PartialEq)]
+pub enum RespCommand {
+    /// `PING [message]`
+    Ping(Option<Bytes>),
+    /// `SET key value [EX seconds | PX milliseconds]`
+    Set {
+        key: Bytes,
+        value: Bytes,
+        ttl: Option<Duration>,
+    },
+    /// `GET key`
+    Get(Bytes),
+    /// `DEL key [key ...]`
+    Del(Vec<Bytes>),
+    /// `TTL key`
+    Ttl(Bytes),
+    /// `EXPIRE key seconds`
+    Expire { key: Bytes, seconds: i64 },
+    /// `GEOSET prefix longitude latitude member`
+    GeoSet {
+        prefix: String,
+        point: Point,
+        member: Bytes,
+    },
+    /// `GEORADIUSMEMBER prefix longitude latitude radius [unit] [COUNT count]`
+    GeoRadiusMember {
+        prefix: String,
+        center: Point,
+        radius_meters: f64,
+        count: usize,
+    },
+}
+
+impl RespCommand {
+    /// Parse a command from a client request.
+    ///
+    /// Requests are arrays of bulk strings whose first element names the
+    /// command, in any case.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error, worded like the matching Redis error reply, if the
+    /// command is unknown or its arguments are invalid.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{RespCommand, RespValue};
+    /// use std::time::Duration;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let (request, _) = RespValue::parse(b"*5\r\n$3\r\nSET\r\n$1\r\nk\r\n$1\r\nv\r\n$2\r\nEX\r\n$2\r\n60\r\n")?.unwrap();
+    /// let command = RespCommand::parse(request)?;
+    /// assert_eq!(
+    ///     command,
+    ///     RespCommand::Set { key: "k".into(), value: "v".into(), ttl: Some(Duration::from_secs(60)) }
+    /// );
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn parse(request: RespValue) -> Result<RespCommand> {
+        let args = match request {
+            RespValue::Array(Some(items)) if !items.is_empty() => items
+                .into_iter()
+                .map(|item| match item {
+                    RespValue::BulkString(Some(arg)) => Ok(arg),
+                    _ => Err(protocol_error("expected an array of bulk strings")),
+                })
+                .collect::<Result<Vec<_>>>()?,
+            _ => return Err(protocol_error("expected an array of bulk strings")),
+        };
+
+        let command = String::from_utf8_lossy(&args[0]).into_owned();
+        let name = command.to_ascii_lowercase();
+        let args = &args[1..];
+        let arity = |valid: bool| {
+            if valid {
+                Ok(())
+            } else {
+                Err(SpatioError::Other(format!(
+                    "ERR wrong number of arguments for '{}' command",
+                    name
+                )))
+            }
+        };
+
+        match name.as_str() {
+            "ping" => {
+                arity(args.len() <= 1)?;
+                Ok(RespCommand::Ping(args.first().cloned()))
+            }
+            "set" => {
+                arity(args.len() >= 2)?;
+                let ttl = match &args[2..] {
+                    [] => None,
+                    [option, amount] => {
+                        let amount = parse_integer(amount)?;
+                        if amount <= 0 {
+                            return Err(SpatioError::Other(
+                                "ERR invalid expire time in 'set' command".to_string(),
+                            ));
+                        }
+                        match option.to_ascii_lowercase().as_slice() {
+                            b"ex" => Some(Duration::from_secs(amount as u64)),
+                            b"px" => Some(Duration::from_millis(amount as u64)),
+                            _ => return Err(syntax_error()),
+                        }
+                    }
+                    _ => return Err(syntax_error()),
+                };
+                Ok(RespCommand::Set {
+                    key: args[0].clone(),
+                    value: args[1].clone(),
+                    ttl,
+                })
+            }
+            "get" => {
+                arity(args.len() == 1)?;
+                Ok(RespCommand::Get(args[0].clone()))
+            }
+            "del" => {
+                arity(!args.is_empty())?;
+                Ok(RespCommand::Del(args.to_vec()))
+            }
+            "ttl" => {
+                arity(args.len() == 1)?;
+                Ok(RespCommand::Ttl(args[0].clone()))
+            }
+            "expire" => {
+                arity(args.len() == 2)?;
+                Ok(RespCommand::Expire {
+                    key: args[0].clone(),
+                    seconds: parse_integer(&args[1])?,
+                })
+            }
+            "geoset" => {
+                arity(args.len() == 4)?;
+                Ok(RespCommand::GeoSet {
+                    prefix: parse_prefix(&args[0])?,
+                    point: parse_point(&args[1], &args[2])?,
+                    member: args[3].clone(),
+                })
+            }
+            "georadiusmember" => {
+                arity(args.len() >= 4)?;
+                let mut radius_meters = parse_float(&args[3])?;
+                let mut count = usize::MAX;
+                let mut options = args[4..].iter();
+                while let Some(option) = options.next() {
+                    match option.to_ascii_lowercase().as_slice() {
+                        b"m" => {}
+                        b"km" => radius_meters *= 1000.0,
+                        b"ft" => radius_meters *= 0.3048,
+                        b"mi" => radius_meters *= 1609.34,
+                        b"count" => {
+                            let value = options.next().ok_or_else(syntax_error)?;
+                            count = usize::try_from(parse_integer(value)?)
+                                .ok()
+                                .filter(|count| *count > 0)
+                                .ok_or_else(|| {
+                                    SpatioError::Other("ERR COUNT must be > 0".to_string())
+                                })?;
+                        }
+                        _ => return Err(syntax_error()),
+                    }
+                }
+                if radius_meters < 0.0 {
+                    return Err(SpatioError::Other(
+                        "ERR radius cannot be negative".to_string(),
+                    ));
+                }
+                Ok(RespCommand::GeoRadiusMember {
+                    prefix: parse_prefix(&args[0])?,
+                    center: parse_point(&args[1], &args[2])?,
+                    radius_meters,
+                    count,
+                })
+            }
+            _ => Err(SpatioError::Other(format!(
+                "ERR unknown command '{}'",
+                command
+            ))),
+        }
+    }
+
+    /// Run the command against a database and build the reply.
+    ///
+    /// Database errors become `-ERR` replies.
+    pub fn execute(self, db: &DB) -> RespValue {
+        self.execute_inner(db)
+            .unwrap_or_else(|e| RespValue::Error(format!("ERR {}", e)))
+    }
+
+    fn execute_inner(self, db: &DB) -> Result<RespValue> {
+        match self {
+            RespCommand::Ping(None) => Ok(RespValue::SimpleString("PONG".to_string())),
+            RespCommand::Ping(Some(message)) => Ok(RespValue::BulkString(Some(message))),
+            RespCommand::Set { key, value, ttl } => {
+                db.insert(&key, &value, ttl.map(SetOptions::with_ttl))?;
+                Ok(RespValue::SimpleString("OK".to_string()))
+            }
+            RespCommand::Get(key) => Ok(RespValue::BulkString(db.get(&key)?)),
+            RespCommand::Del(keys) => {
+                let mut deleted = 0;
+                for key in keys {
+                    if db.delete(&key)?.is_some() {
+                        deleted += 1;
+                    }
+                }
+                Ok(RespValue::Integer(deleted))
+            }
+            RespCommand::Ttl(key) => {
+                let expires_at =
+                    db.view(|view| Ok(view.get_item(&key).map(|item| item.expires_at)))?;
+                let ttl = match expires_at {
+                    None => -2,
+                    Some(None) => -1,
+                    Some(Some(expires_at)) => {
+                        let remaining = expires_at
+                            .duration_since(SystemTime::now())
+                            .unwrap_or_default();
+                        // Round to the nearest second, as Redis does
+                        ((remaining.as_millis() + 500) / 1000) as i64
+                    }
+                };
+                Ok(RespValue::Integer(ttl))
+            }
+            RespCommand::Expire { key, seconds } => {
+                let updated = if seconds > 0 {
+                    db.expire_in(&key, Duration::from_secs(seconds as u64))?
+                } else {
+                    // A non-positive timeout deletes the key, as in Redis
+                    db.view_mut(|view| {
+                        if view.view().get(&key).is_none() {
+                            return Ok(false);
+                        }
+                        view.delete(&key)?;
+                        Ok(true)
+                    })?
+                };
+                Ok(RespValue::Integer(updated as i64))
+            }
+            RespCommand::GeoSet {
+                prefix,
+                point,
+                member,
+            } => {
+                db.insert_point(&prefix, &point, &member, None)?;
+                Ok(RespValue::Integer(1))
+            }
+            RespCommand::GeoRadiusMember {
+                prefix,
+                center,
+                radius_meters,
+                count,
+            } => {
+                let members = db
+                    .find_nearby(&prefix, &center, radius_meters, count)?
+                    .into_iter()
+                    .map(|(_, member)| RespValue::BulkString(Some(member)))
+                    .collect();
+                Ok(RespValue::Array(Some(members)))
+            }
+        }
+    }
+}
+
+/// TCP server that answers Redis clients from a database.
+///
+/// Each connection is handled on its own Tokio task. Pipelined requests are
+/// answered in order, with the replies to one read sent in a single write.
+pub struct RespServer;
+
+impl RespServer {
+    /// Bind to an address and serve connections until an error occurs.
+    ///
+    /// Must be called from within a Tokio runtime.
+    ///
+    /// # Arguments
+    ///
+    /// * `addr` - Address to listen on, e.g. `127.0.0.1:6379`
+    /// * `db` - Database the commands run against
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the address cannot be bound or accepting a
+    /// connection fails. Errors on a single connection only close that
+    /// connection.
+    ///
+    /// # Examples
+    ///
+    /// ```rust,no_run
+    /// use spatio::{RespServer, Spatio};
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// RespServer::listen("127.0.0.1:6379".parse()?, db).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn listen(addr: SocketAddr, db: DB) -> Result<()> {
+        let listener = TcpListener::bind(addr).await?;
+        Self::serve(listener, db).await
+    }
+
+    /// Serve connections from an already bound listener.
+    ///
+    /// Useful when binding to port 0 and reading the assigned port first.
+    pub async fn serve(listener: TcpListener, db: DB) -> Result<()> {
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let db = db.clone();
+            tokio::spawn(async move {
+                // The client is gone either way; nothing to report to
+                let _ = handle_connection(stream, db).await;
+            });
+        }
+    }
+}
+
+/// Answer requests on one connection until the client disconnects
+async fn handle_connection(mut stream: TcpStream, db: DB) -> Result<()> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let mut replies = Vec::new();
+
+    loop {
+        let mut used = 0;
+        loop {
+            match RespValue::parse(&buf[used..]) {
+                Ok(Some((request, len))) => {
+                    used += len;
+                    let reply = match RespCommand::parse(request) {
+                        Ok(command) => command.execute(&db),
+                        Err(e) => RespValue::Error(e.to_string()),
+                    };
+                    reply.encode(&mut replies);
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    // The stream cannot be resynchronized, so close it
+                    RespValue::Error(e.to_string()).encode(&mut replies);
+                    stream.write_all(&replies).await?;
+                    return Ok(());
+                }
+            }
+        }
+        buf.drain(..used);
+
+        if !replies.is_empty() {
+            stream.write_all(&replies).await?;
+            replies.clear();
+        }
+
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        buf.extend_from_slice(&chunk[..read]);
+    }
+}
+
+fn protocol_error(msg: &str) -> SpatioError {
+    SpatioError::Other(format!("ERR Protocol error: {}", msg))
+}
+
+fn syntax_error() -> SpatioError {
+    SpatioError::Other("ERR syntax error".to_string())
+}
+
+fn parse_integer(arg: &[u8]) -> Result<i64> {
+    std::str::from_utf8(arg)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .ok_or_else(|| {
+            SpatioError::Other("ERR value is not an integer or out of range".to_string())
+        })
+}
+
+fn parse_float(arg: &[u8]) -> Result<f64> {
+    std::str::from_utf8(arg)
+        .ok()
+        .and_then(|s| s.parse::<f64>().ok())
+        .filter(|value| value.is_finite())
+        .ok_or_else(|| SpatioError::Other("ERR value is not a valid float".to_string()))
+}
+
+fn parse_prefix(arg: &[u8]) -> Result<String> {
+    String::from_utf8(arg.to_vec())
+        .map_err(|_| SpatioError::Other("ERR prefix must be valid UTF-8".to_string()))
+}
+
+fn parse_point(lon: &[u8], lat: &[u8]) -> Result<Point> {
+    Point::try_new(parse_float(lat)?, parse_float(lon)?).map_err(|_| {
+        SpatioError::Other(format!(
+            "ERR invalid longitude,latitude pair {},{}",
+            String::from_utf8_lossy(lon),
+            String::from_utf8_lossy(lat)
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(args: &[&str]) -> RespValue {
+        RespValue::Array(Some(
+            args.iter()
+                .map(|arg| RespValue::BulkString(Some(Bytes::copy_from_slice(arg.as_bytes()))))
+                .collect(),
+        ))
+    }
+
+    fn run(db: &DB, args: &[&str]) -> RespValue {
+        match RespCommand::parse(request(args)) {
+            Ok(command) => command.execute(db),
+            Err(e) => RespValue::Error(e.to_string()),
+        }
+    }
+
+    fn bulk(value: &str) -> RespValue {
+        RespValue::BulkString(Some(Bytes::copy_from_slice(value.as_bytes())))
+    }
+
+    #[test]
+    fn test_parse_and_encode_round_trip() {
+        let values = vec![
+            RespValue::SimpleString("OK".to_string()),
+            RespValue::Error("ERR bad".to_string()),
+            RespValue::Integer(-42),
+            RespValue::BulkString(None),
+            bulk(""),
+            bulk("with\r\nnewline"),
+            RespValue::Array(None),
+            RespValue::Array(Some(vec![
+                bulk("SET"),
+                RespValue::Integer(1),
+                RespValue::Array(Some(vec![])),
+            ])),
+        ];
+
+        for value in values {
+            let mut encoded = Vec::new();
+            value.encode(&mut encoded);
+            assert_eq!(
+                RespValue::parse(&encoded).unwrap(),
+                Some((value.clone(), encoded.len()))
+            );
+            // Every proper prefix is incomplete rather than an error
+            for end in 0..encoded.len() {
+                assert_eq!(RespValue::parse(&encoded[..end]).unwrap(), None);
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_pipelined_requests() {
+        let mut buf = Vec::new();
+        request(&["SET", "k", "v"]).encode(&mut buf);
+        request(&["GET", "k"]).encode(&mut buf);
+
+        let (first, used) = RespValue::parse(&buf).unwrap().unwrap();
+        assert_eq!(first, request(&["SET", "k", "v"]));
+        let (second, rest) = RespValue::parse(&buf[used..]).unwrap().unwrap();
+        assert_eq!(second, request(&["GET", "k"]));
+        assert_eq!(used + rest, buf.len());
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_input() {
+        assert!(RespValue::parse(b"GET k\r\n").is_err());
+        assert!(RespValue::parse(b"$-2\r\n").is_err());
+        assert!(RespValue::parse(b"$abc\r\n").is_err());
+        assert!(RespValue::parse(b"$3\r\nabcde\r\n").is_err());
+        assert!(RespValue::parse(b"*-5\r\n").is_err());
+        assert!(RespValue::parse(b":12x\r\n").is_err());
+        assert!(RespValue::parse(&b"*1\r\n".repeat(MAX_NESTING + 1)).is_err());
+    }
+
+    #[test]
+    fn test_parse_commands() {
+        let parse = |args: &[&str]| RespCommand::parse(request(args));
+
+        assert_eq!(
+            parse(&["set", "k", "v", "PX", "1500"]).unwrap(),
+            RespCommand::Set {
+                key: Bytes::from_static(b"k"),
+                value: Bytes::from_static(b"v"),
+                ttl: Some(Duration::from_millis(1500)),
+            }
+        );
+        assert_eq!(
+            parse(&[
+                "GEORADIUSMEMBER",
+                "poi",
+                "-74.0",
+                "40.7",
+                "2",
+                "km",
+                "COUNT",
+                "3"
+            ])
+            .unwrap(),
+            RespCommand::GeoRadiusMember {
+                prefix: "poi".to_string(),
+                center: Point::new(40.7, -74.0),
+                radius_meters: 2000.0,
+                count: 3,
+            }
+        );
+
+        let error = |args: &[&str]| parse(args).unwrap_err().to_string();
+        assert_eq!(
+            error(&["GET"]),
+            "ERR wrong number of arguments for 'get' command"
+        );
+        assert_eq!(error(&["FLUSHALL"]), "ERR unknown command 'FLUSHALL'");
+        assert_eq!(error(&["SET", "k", "v", "NX"]), "ERR syntax error");
+        assert_eq!(
+            error(&["SET", "k", "v", "EX", "0"]),
+            "ERR invalid expire time in 'set' command"
+        );
+        assert_eq!(
+            error(&["EXPIRE", "k", "soon"]),
+            "ERR value is not an integer or out of range"
+        );
+        assert!(error(&["GEOSET", "poi", "200", "40", "m"]).starts_with("ERR invalid longitude"));
+        assert_eq!(
+            error(&["GEORADIUSMEMBER", "poi", "0", "0", "1", "COUNT", "0"]),
+            "ERR COUNT must be > 0"
+        );
+        assert!(RespCommand::parse(RespValue::Integer(1)).is_err());
+    }
+
+    #[test]
+    fn test_execute_key_commands() {
+        let db = DB::memory().unwrap();
+        let ok = RespValue::SimpleString("OK".to_string());
+
+        assert_eq!(
+            run(&db, &["PING"]),
+            RespValue::SimpleString("PONG".to_string())
+        );
+        assert_eq!(run(&db, &["PING", "hi"]), bulk("hi"));
+
+        assert_eq!(run(&db, &["SET", "a", "1"]), ok);
+        assert_eq!(run(&db, &["GET", "a"]), bulk("1"));
+        assert_eq!(run(&db, &["GET", "missing"]), RespValue::BulkString(None));
+
+        assert_eq!(run(&db, &["TTL", "a"]), RespValue::Integer(-1));
+        assert_eq!(run(&db, &["TTL", "missing"]), RespValue::Integer(-2));
+        assert_eq!(run(&db, &["SET", "b", "2", "EX", "100"]), ok);
+        assert_eq!(run(&db, &["TTL", "b"]), RespValue::Integer(100));
+
+        assert_eq!(run(&db, &["EXPIRE", "a", "50"]), RespValue::Integer(1));
+        assert_eq!(run(&db, &["TTL", "a"]), RespValue::Integer(50));
+        assert_eq!(run(&db, &["GET", "a"]), bulk("1"));
+        assert_eq!(
+            run(&db, &["EXPIRE", "missing", "50"]),
+            RespValue::Integer(0)
+        );
+        assert_eq!(run(&db, &["EXPIRE", "b", "0"]), RespValue::Integer(1));
+        assert_eq!(run(&db, &["GET", "b"]), RespValue::BulkString(None));
+
+        assert_eq!(run(&db, &["SET", "c", "3"]), ok);
+        assert_eq!(
+            run(&db, &["DEL", "a", "c", "missing"]),
+            RespValue::Integer(2)
+        );
+        assert_eq!(run(&db, &["GET", "c"]), RespValue::BulkString(None));
+    }
+
+    #[test]
+    fn test_execute_geo_commands() {
+        let db = DB::memory().unwrap();
+        assert_eq!(
+            run(&db, &["GEOSET", "poi", "-74.0060", "40.7128", "city-hall"]),
+            RespValue::Integer(1)
+        );
+        assert_eq!(
+            run(
+                &db,
+                &["GEOSET", "poi", "-73.9857", "40.7484", "empire-state"]
+            ),
+            RespValue::Integer(1)
+        );
+
+        let near = |args: &[&str]| {
+            let mut full = vec!["GEORADIUSMEMBER", "poi", "-74.0060", "40.7128"];
+            full.extend_from_slice(args);
+            run(&db, &full)
+        };
+        assert_eq!(
+            near(&["500"]),
+            RespValue::Array(Some(vec![bulk("city-hall")]))
+        );
+        assert_eq!(
+            near(&["5", "km"]),
+            RespValue::Array(Some(vec![bulk("city-hall"), bulk("empire-state")]))
+        );
+        assert_eq!(
+            near(&["5", "km", "COUNT", "1"]),
+            RespValue::Array(Some(vec![bulk("city-hall")]))
+        );
+        assert_eq!(
+            near(&["1", "ft"]),
+            RespValue::Array(Some(vec![bulk("city-hall")]))
+        );
+    }
+}
@@ -4,13 +4,34 @@
 //! and basic spatial operations.
 
 use crate::error::{Result, SpatioError};
+use crate::geometry::{Coordinate, Geometry, GeometryOps, LineString, LinearRing, Polygon};
 use geo;
 use geohash;
+#[cfg(feature = "s2")]
 use s2::cellid::CellID;
+#[cfg(feature = "s2")]
+use s2::latlng::LatLng;
+#[cfg(feature = "s2")]
+use s2::rect::Rect;
+#[cfg(feature = "s2")]
+use s2::region::RegionCoverer;
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "geojson")]
 use serde_json::{Map, Value};
+use std::cmp::Ordering;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+
+/// Highest supported XYZ tile zoom level
+const MAX_TILE_ZOOM: u8 = 30;
+
+/// Latitude where Web Mercator tiles end; the world tile at zoom 0 is
+/// square between these latitudes
+const MAX_MERCATOR_LAT: f64 = 85.051_128_779_806_59;
+
+/// Geohash base-32 alphabet, in the order of the cell bits each character
+/// encodes
+const GEOHASH_BASE32: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
 
 /// A geographic point representing a location on Earth's surface.
 ///
@@ -37,7 +58,13 @@ use std::fmt;
 /// # Ok(())
 /// # }
 /// ```
-#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+///
+/// Points implement `Eq`, `Hash` and `Ord`, so they can be used as keys in
+/// `HashMap`, `HashSet` and `BTreeMap`. Equality compares coordinate bits
+/// after treating `-0.0` as `0.0` and every NaN as the same value, so unlike
+/// plain `f64` comparison a NaN coordinate equals itself. Points are ordered
+/// by latitude, then longitude.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Point {
     /// Latitude in decimal degrees (-90.0 to +90.0)
     pub lat: f64,
@@ -68,6 +95,89 @@ impl Point {
         Self { lat, lon }
     }
 
+    /// Creates a point without validating the coordinates.
+    ///
+    /// Equivalent to `Point::new`; the name makes it explicit at call sites
+    /// such as bulk imports that out-of-range values are accepted as-is.
+    /// Use `normalize` to bring such points back into range.
+    pub fn from_raw(lat: f64, lon: f64) -> Self {
+        Self { lat, lon }
+    }
+
+    /// Creates a point, checking that the coordinates are in range.
+    ///
+    /// # Arguments
+    ///
+    /// * `lat` - Latitude in decimal degrees (-90° to +90°)
+    /// * `lon` - Longitude in decimal degrees (-180° to +180°)
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::OutOfRange` if either coordinate is outside its
+    /// range or is not a finite number.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Point, SpatioError};
+    ///
+    /// assert!(Point::try_new(40.7128, -74.0060).is_ok());
+    /// assert!(matches!(Point::try_new(91.0, 0.0), Err(SpatioError::OutOfRange(_))));
+    /// assert!(matches!(Point::try_new(0.0, 190.0), Err(SpatioError::OutOfRange(_))));
+    /// ```
+    pub fn try_new(lat: f64, lon: f64) -> Result<Self> {
+        if !(-90.0..=90.0).contains(&lat) {
+            return Err(SpatioError::OutOfRange(
+                "Latitude must be between -90 and 90".to_string(),
+            ));
+        }
+        if !(-180.0..=180.0).contains(&lon) {
+            return Err(SpatioError::OutOfRange(
+                "Longitude must be between -180 and 180".to_string(),
+            ));
+        }
+        Ok(Self { lat, lon })
+    }
+
+    /// Get a copy of the point with longitude wrapped into [-180, 180] and
+    /// latitude clamped to [-90, 90].
+    ///
+    /// Longitudes already in range, including ±180, are left unchanged.
+    /// NaN coordinates stay NaN.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Point;
+    ///
+    /// let wrapped = Point::from_raw(95.0, 190.0).normalize();
+    /// assert_eq!(wrapped, Point::new(90.0, -170.0));
+    /// assert_eq!(Point::new(0.0, -540.0).normalize(), Point::new(0.0, -180.0));
+    /// ```
+    pub fn normalize(&self) -> Point {
+        let lon = if (-180.0..=180.0).contains(&self.lon) {
+            self.lon
+        } else {
+            (self.lon + 180.0).rem_euclid(360.0) - 180.0
+        };
+        Point::new(self.lat.clamp(-90.0, 90.0), lon)
+    }
+
+    /// Coordinates with `-0.0` and NaN payloads collapsed, used for
+    /// equality, hashing and ordering
+    fn canonical(&self) -> (f64, f64) {
+        let canonical = |value: f64| {
+            if value.is_nan() {
+                f64::NAN
+            } else if value == 0.0 {
+                0.0
+            } else {
+                value
+            }
+        };
+        (canonical(self.lat), canonical(self.lon))
+    }
+
     /// Validate that the point has valid coordinates (not NaN or infinity)
     ///
     /// # Examples
@@ -317,18 +427,89 @@ impl Point {
     /// # Ok(())
     /// # }
     /// ```
+    #[cfg(feature = "s2")]
     pub fn to_s2_cell(&self, level: u8) -> Result<CellID> {
         if level > 30 {
             return Err(SpatioError::Other("S2 level must be <= 30".to_string()));
         }
 
-        // Simplified S2 cell ID generation
-        // Map lat/lon to cell coordinates and combine with level
-        let lat_norm = ((self.lat + 90.0) / 180.0 * ((1u64 << level) as f64)) as u64;
-        let lon_norm = ((self.lon + 180.0) / 360.0 * ((1u64 << level) as f64)) as u64;
+        let leaf = CellID::from(LatLng::from_degrees(self.lat, self.lon));
+        Ok(leaf.parent(level as u64))
+    }
 
-        let cell_value = (level as u64) << 56 | lat_norm << 28 | lon_norm;
-        Ok(CellID(cell_value))
+    /// Generate an H3 cell index for this point.
+    ///
+    /// H3 is a hexagonal hierarchical grid. Hexagons have more uniform
+    /// sizes than geohash cells and every neighbor shares an edge, which
+    /// makes neighbor lookups simpler.
+    ///
+    /// # Arguments
+    ///
+    /// * `resolution` - H3 resolution (0-15, higher = smaller cells)
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Point;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let point = Point::new(40.7128, -74.0060);
+    /// let cell = point.to_h3_cell(9)?;
+    /// println!("H3 cell: {:x}", cell);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "h3")]
+    pub fn to_h3_cell(&self, resolution: u8) -> Result<u64> {
+        let resolution = h3o::Resolution::try_from(resolution)
+            .map_err(|_| SpatioError::Other("H3 resolution must be <= 15".to_string()))?;
+        let lat_lng = h3o::LatLng::new(self.lat, self.lon)
+            .map_err(|e| SpatioError::Other(format!("Invalid coordinates for H3: {}", e)))?;
+        Ok(u64::from(lat_lng.to_cell(resolution)))
+    }
+
+    /// Get the XYZ Web Mercator tile containing this point.
+    ///
+    /// Latitudes beyond the Web Mercator limit of about ±85.05° fall in the
+    /// top or bottom row of tiles, and longitude 180° in the last column.
+    ///
+    /// # Arguments
+    ///
+    /// * `zoom` - Tile zoom level (0-30); zoom `z` has `2^z` tiles per side
+    ///
+    /// # Panics
+    ///
+    /// Panics if `zoom` is greater than 30.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Point;
+    ///
+    /// let nyc = Point::new(40.7128, -74.0060);
+    /// assert_eq!(nyc.to_tile(0), (0, 0));
+    /// assert_eq!(nyc.to_tile(10), (301, 385));
+    /// ```
+    pub fn to_tile(&self, zoom: u8) -> (u32, u32) {
+        assert!(
+            zoom <= MAX_TILE_ZOOM,
+            "Tile zoom must be between 0 and {}",
+            MAX_TILE_ZOOM
+        );
+
+        let tiles = (1u64 << zoom) as f64;
+        let lat = self
+            .lat
+            .clamp(-MAX_MERCATOR_LAT, MAX_MERCATOR_LAT)
+            .to_radians();
+        let x = (self.lon + 180.0) / 360.0 * tiles;
+        let y = (1.0 - lat.tan().asinh() / std::f64::consts::PI) / 2.0 * tiles;
+
+        let last = tiles - 1.0;
+        (
+            x.floor().clamp(0.0, last) as u32,
+            y.floor().clamp(0.0, last) as u32,
+        )
     }
 
     /// Check if this point is within the given bounding box.
@@ -450,26 +631,138 @@ impl Point {
     pub fn contains_point(&self, other: &Point, radius_meters: f64) -> bool {
         self.distance_to(other) <= radius_meters
     }
+
+    /// Check if the segment between two consecutive points crosses the
+    /// antimeridian (±180° longitude).
+    ///
+    /// Consecutive points are assumed to be joined the short way round, so
+    /// a crossing is any pair more than 180° of longitude apart.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Point;
+    ///
+    /// let fiji = Point::new(-17.7, 178.0);
+    /// let samoa = Point::new(-13.8, -172.0);
+    /// assert!(Point::crosses_antimeridian(&fiji, &samoa));
+    ///
+    /// let sydney = Point::new(-33.8688, 151.2093);
+    /// assert!(!Point::crosses_antimeridian(&fiji, &sydney));
+    /// ```
+    pub fn crosses_antimeridian(a: &Point, b: &Point) -> bool {
+        (a.lon - b.lon).abs() > 180.0
+    }
+
+    /// Where the segment from `a` to `b` meets the antimeridian, as the
+    /// fraction of the way from `a` and the latitude there. `None` if the
+    /// segment does not cross.
+    pub(crate) fn antimeridian_crossing(a: &Point, b: &Point) -> Option<(f64, f64)> {
+        if !Point::crosses_antimeridian(a, b) {
+            return None;
+        }
+
+        // Unwrap b onto a's side of the line so the segment is continuous
+        let (boundary, b_lon) = if a.lon >= 0.0 {
+            (180.0, b.lon + 360.0)
+        } else {
+            (-180.0, b.lon - 360.0)
+        };
+        let fraction = (boundary - a.lon) / (b_lon - a.lon);
+        Some((fraction, a.lat + fraction * (b.lat - a.lat)))
+    }
 }
 
-/// A bounding box defined by minimum and maximum latitude and longitude coordinates.
+/// A bounding box defined by its minimum and maximum corners.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct BoundingBox {
-    pub min_lat: f64,
-    pub min_lon: f64,
-    pub max_lat: f64,
-    pub max_lon: f64,
+    /// South-west corner, at the minimum latitude and longitude
+    pub min: Point,
+    /// North-east corner, at the maximum latitude and longitude
+    pub max: Point,
 }
 
 impl BoundingBox {
     /// Create a new bounding box
     pub fn new(min_lat: f64, min_lon: f64, max_lat: f64, max_lon: f64) -> Self {
         Self {
-            min_lat,
-            min_lon,
-            max_lat,
-            max_lon,
+            min: Point::new(min_lat, min_lon),
+            max: Point::new(max_lat, max_lon),
+        }
+    }
+
+    /// Create a bounding box from its south-west and north-east corners.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{BoundingBox, Point};
+    ///
+    /// let bbox = BoundingBox::from_corners(&Point::new(40.5, -74.5), &Point::new(41.0, -73.5));
+    /// assert_eq!(bbox, BoundingBox::new(40.5, -74.5, 41.0, -73.5));
+    /// assert_eq!(bbox.min.lat, 40.5);
+    /// ```
+    pub fn from_corners(min: &Point, max: &Point) -> Self {
+        Self {
+            min: *min,
+            max: *max,
+        }
+    }
+
+    /// Get the area covered by an XYZ Web Mercator tile.
+    ///
+    /// Tile `(0, 0)` is the north-west corner of the map at every zoom.
+    ///
+    /// # Arguments
+    ///
+    /// * `zoom` - Tile zoom level (0-30)
+    /// * `x` - Tile column, counted eastwards from longitude -180°
+    /// * `y` - Tile row, counted southwards from latitude ~85.05°
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `zoom` is greater than 30 or `x` or `y` is
+    /// outside `0..2^zoom`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::BoundingBox;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let tile = BoundingBox::from_tile(1, 1, 0)?;
+    /// assert_eq!((tile.min.lon, tile.max.lon), (0.0, 180.0));
+    /// assert_eq!(tile.min.lat, 0.0);
+    /// assert!((tile.max.lat - 85.0511).abs() < 1e-4);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_tile(zoom: u8, x: u32, y: u32) -> Result<BoundingBox> {
+        if zoom > MAX_TILE_ZOOM {
+            return Err(SpatioError::Other(format!(
+                "Tile zoom must be between 0 and {}, got {}",
+                MAX_TILE_ZOOM, zoom
+            )));
+        }
+        let tiles = 1u64 << zoom;
+        if u64::from(x) >= tiles || u64::from(y) >= tiles {
+            return Err(SpatioError::Other(format!(
+                "Tile {}/{}/{} is outside the {} tiles per side at zoom {}",
+                zoom, x, y, tiles, zoom
+            )));
         }
+
+        let tiles = tiles as f64;
+        let lon = |x: f64| x / tiles * 360.0 - 180.0;
+        let lat = |y: f64| {
+            (std::f64::consts::PI * (1.0 - 2.0 * y / tiles))
+                .sinh()
+                .atan()
+                .to_degrees()
+        };
+
+        let (x, y) = (f64::from(x), f64::from(y));
+        Ok(BoundingBox::new(lat(y + 1.0), lon(x), lat(y), lon(x + 1.0)))
     }
 
     /// Check if this bounding box intersects with another bounding box.
@@ -485,10 +778,577 @@ impl BoundingBox {
     /// assert!(nyc_area.intersects(&ny_state));
     /// ```
     pub fn intersects(&self, other: &BoundingBox) -> bool {
-        !(self.max_lat < other.min_lat
-            || self.min_lat > other.max_lat
-            || self.max_lon < other.min_lon
-            || self.min_lon > other.max_lon)
+        !(self.max.lat < other.min.lat
+            || self.min.lat > other.max.lat
+            || self.max.lon < other.min.lon
+            || self.min.lon > other.max.lon)
+    }
+
+    /// Check if a point lies inside this bounding box (edges included).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{BoundingBox, Point};
+    ///
+    /// let nyc_area = BoundingBox::new(40.5, -74.5, 41.0, -73.5);
+    ///
+    /// assert!(nyc_area.contains(&Point::new(40.7128, -74.0060)));
+    /// assert!(!nyc_area.contains(&Point::new(51.5074, -0.1278)));
+    /// ```
+    pub fn contains(&self, point: &Point) -> bool {
+        point.lat >= self.min.lat
+            && point.lat <= self.max.lat
+            && point.lon >= self.min.lon
+            && point.lon <= self.max.lon
+    }
+
+    /// Get the center point of this bounding box.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{BoundingBox, Point};
+    ///
+    /// let bbox = BoundingBox::new(40.0, -75.0, 42.0, -73.0);
+    /// assert_eq!(bbox.center(), Point::new(41.0, -74.0));
+    /// ```
+    pub fn center(&self) -> Point {
+        Point::new(
+            (self.min.lat + self.max.lat) / 2.0,
+            (self.min.lon + self.max.lon) / 2.0,
+        )
+    }
+
+    /// Calculate the area of this bounding box on a spherical Earth in
+    /// square kilometers.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::BoundingBox;
+    ///
+    /// // One degree square at the equator is roughly 12,364 km²
+    /// let bbox = BoundingBox::new(0.0, 0.0, 1.0, 1.0);
+    /// assert!((bbox.area_km2() - 12_364.0).abs() < 10.0);
+    /// ```
+    pub fn area_km2(&self) -> f64 {
+        const EARTH_RADIUS_KM: f64 = 6_371.0;
+        let dlon = (self.max.lon - self.min.lon).to_radians();
+        let dsin = self.max.lat.to_radians().sin() - self.min.lat.to_radians().sin();
+        (EARTH_RADIUS_KM * EARTH_RADIUS_KM * dlon * dsin).abs()
+    }
+
+    /// Grow this bounding box by a distance in meters on every side.
+    ///
+    /// The longitude margin is computed at the latitude farthest from the
+    /// equator, so every point within `meters` of the original box is
+    /// covered. Coordinates are clamped to the valid latitude and
+    /// longitude ranges.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{BoundingBox, Point};
+    ///
+    /// let bbox = BoundingBox::new(40.70, -74.02, 40.72, -74.00);
+    /// let expanded = bbox.expand_by_meters(1000.0);
+    ///
+    /// assert!(expanded.contains(&Point::new(40.728, -74.01)));
+    /// assert!(!bbox.contains(&Point::new(40.728, -74.01)));
+    /// ```
+    pub fn expand_by_meters(&self, meters: f64) -> BoundingBox {
+        const EARTH_RADIUS_M: f64 = 6_371_000.0;
+        let dlat = (meters / EARTH_RADIUS_M).to_degrees();
+
+        let min_lat = (self.min.lat - dlat).max(-90.0);
+        let max_lat = (self.max.lat + dlat).min(90.0);
+        let widest_lat = min_lat.abs().max(max_lat.abs()).to_radians();
+        let dlon = if widest_lat.cos() > f64::EPSILON {
+            (meters / (EARTH_RADIUS_M * widest_lat.cos())).to_degrees()
+        } else {
+            // At a pole every longitude is within reach
+            360.0
+        };
+
+        BoundingBox::new(
+            min_lat,
+            (self.min.lon - dlon).max(-180.0),
+            max_lat,
+            (self.max.lon + dlon).min(180.0),
+        )
+    }
+}
+
+/// Geohash cell utilities.
+pub struct GeohashUtils;
+
+impl GeohashUtils {
+    /// Get the geohash cells exactly `ring_distance` steps from a cell.
+    ///
+    /// Ring 0 is the cell itself, ring 1 its 8 neighbors, ring 2 the 16
+    /// cells around those, and so on. Cells are returned at the same
+    /// precision as `geohash`. Cells past a pole are omitted and longitudes
+    /// wrap around the antimeridian, so rings near the poles or spanning
+    /// the globe hold fewer cells.
+    ///
+    /// # Arguments
+    ///
+    /// * `geohash` - The center cell
+    /// * `ring_distance` - Number of cell steps from the center
+    ///
+    /// # Returns
+    ///
+    /// The cells of the ring, or an empty vector if `geohash` is invalid
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::GeohashUtils;
+    ///
+    /// assert_eq!(GeohashUtils::ring("dr5ru", 0), vec!["dr5ru"]);
+    /// assert_eq!(GeohashUtils::ring("dr5ru", 1).len(), 8);
+    /// assert_eq!(GeohashUtils::ring("dr5ru", 2).len(), 16);
+    /// ```
+    pub fn ring(geohash: &str, ring_distance: usize) -> Vec<String> {
+        // The decoder panics on lengths it cannot represent, so reject
+        // them before decoding
+        if !(1..=12).contains(&geohash.len()) {
+            return Vec::new();
+        }
+        let Ok(cell) = geohash::decode_bbox(geohash) else {
+            return Vec::new();
+        };
+        if ring_distance == 0 {
+            return vec![geohash.to_string()];
+        }
+
+        let (height, width) = (cell.height(), cell.width());
+        let center = cell.center();
+        let n = ring_distance as i64;
+
+        let mut cells = Vec::with_capacity(8 * ring_distance);
+        for dy in -n..=n {
+            // Rows strictly inside the ring only contribute their two ends
+            let step = if dy.abs() == n { 1 } else { (2 * n) as usize };
+            for dx in (-n..=n).step_by(step) {
+                let lat = center.y + dy as f64 * height;
+                if !(-90.0..=90.0).contains(&lat) {
+                    continue;
+                }
+                let lon = (center.x + dx as f64 * width + 180.0).rem_euclid(360.0) - 180.0;
+                if let Ok(hash) = geohash::encode(geo::Coord { x: lon, y: lat }, geohash.len())
+                    && !cells.contains(&hash)
+                {
+                    cells.push(hash);
+                }
+            }
+        }
+        cells
+    }
+
+    /// Get the area covered by a geohash cell.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InvalidGeohash` if `geohash` is not a valid
+    /// geohash.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{GeohashUtils, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let cell = GeohashUtils::bounding_box("dr5r")?;
+    /// assert!(cell.contains(&Point::new(40.7128, -74.0060)));
+    /// assert!(cell.max.lat - cell.min.lat < 0.2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn bounding_box(geohash: &str) -> Result<BoundingBox> {
+        // The geohash crate panics when decoding an empty hash
+        if geohash.is_empty() {
+            return Err(SpatioError::InvalidGeohash);
+        }
+        let rect = geohash::decode_bbox(geohash).map_err(|_| SpatioError::InvalidGeohash)?;
+        let (min, max) = (rect.min(), rect.max());
+        Ok(BoundingBox::new(min.y, min.x, max.y, max.x))
+    }
+
+    /// Check whether a point falls in a geohash cell.
+    ///
+    /// A point on the edge shared by two cells belongs to the one its own
+    /// geohash names, so this agrees with `Point::to_geohash` at the cell's
+    /// precision.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InvalidGeohash` if `geohash` is not a valid
+    /// geohash.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{GeohashUtils, Point};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// assert!(GeohashUtils::contains_point("dr5r", &Point::new(40.7128, -74.0060))?);
+    /// assert!(!GeohashUtils::contains_point("dr5r", &Point::new(51.5074, -0.1278))?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn contains_point(geohash: &str, point: &Point) -> Result<bool> {
+        Self::bounding_box(geohash)?;
+        let hash = point
+            .to_geohash(geohash.len())
+            .map_err(|_| SpatioError::InvalidGeohash)?;
+        Ok(hash == geohash)
+    }
+
+    /// Check whether a geohash cell overlaps a bounding box, edges
+    /// included.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InvalidGeohash` if `geohash` is not a valid
+    /// geohash.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{BoundingBox, GeohashUtils};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let manhattan = BoundingBox::new(40.70, -74.02, 40.88, -73.91);
+    /// assert!(GeohashUtils::intersects_bbox("dr5r", &manhattan)?);
+    /// assert!(!GeohashUtils::intersects_bbox("gcpv", &manhattan)?);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn intersects_bbox(geohash: &str, bbox: &BoundingBox) -> Result<bool> {
+        Ok(Self::bounding_box(geohash)?.intersects(bbox))
+    }
+
+    /// Get every geohash within a Hamming distance of another.
+    ///
+    /// Useful for recovering geohashes that were mistyped or corrupted in
+    /// transit: each result differs from `geohash` in at most
+    /// `max_hamming_distance` characters, each replaced by another
+    /// character of the base-32 alphabet. The result always includes
+    /// `geohash` itself.
+    ///
+    /// The number of results grows as `31^d` times the ways of choosing
+    /// `d` positions, so distances above 2 quickly get expensive.
+    ///
+    /// # Arguments
+    ///
+    /// * `geohash` - The geohash to match
+    /// * `max_hamming_distance` - Most characters that may differ
+    ///
+    /// # Returns
+    ///
+    /// The matching geohashes in sorted order, or an empty vector if
+    /// `geohash` is empty or holds a character outside the alphabet
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::GeohashUtils;
+    ///
+    /// let matches = GeohashUtils::fuzzy_decode("dr5ru", 1);
+    /// assert_eq!(matches.len(), 1 + 5 * 31);
+    /// assert!(matches.iter().any(|hash| hash == "dr5rv"));
+    /// assert!(matches.iter().any(|hash| hash == "er5ru"));
+    /// ```
+    pub fn fuzzy_decode(geohash: &str, max_hamming_distance: usize) -> Vec<String> {
+        let original = geohash.as_bytes();
+        if original.is_empty() || !original.iter().all(|c| GEOHASH_BASE32.contains(c)) {
+            return Vec::new();
+        }
+
+        // Each variant records the first position it may still change, so
+        // positions are changed in increasing order and no variant is
+        // produced twice
+        let mut matches = vec![geohash.to_string()];
+        let mut frontier = vec![(original.to_vec(), 0)];
+        for _ in 0..max_hamming_distance.min(original.len()) {
+            let mut next = Vec::new();
+            for (hash, start) in &frontier {
+                for pos in *start..hash.len() {
+                    for &c in GEOHASH_BASE32.iter().filter(|&&c| c != original[pos]) {
+                        let mut variant = hash.clone();
+                        variant[pos] = c;
+                        next.push((variant, pos + 1));
+                    }
+                }
+            }
+            matches.extend(
+                next.iter()
+                    .map(|(hash, _)| hash.iter().map(|&c| c as char).collect::<String>()),
+            );
+            frontier = next;
+        }
+        matches.sort_unstable();
+        matches
+    }
+
+    /// Approximate a polygon with geohash cells.
+    ///
+    /// Starts from the smallest cell containing the polygon's bounding box
+    /// and repeatedly splits cells into their 32 child cells, dropping
+    /// children that lie fully outside the polygon. Cells fully inside the
+    /// polygon are kept whole, so the result mixes precisions. Coarser
+    /// cells are split first, and splitting stops at `precision` or when
+    /// the next split would take the covering past `max_cells`.
+    ///
+    /// Every point of the polygon lies in one of the returned cells; cells
+    /// along its boundary also cover some area outside it. Polygon
+    /// coordinates are `x` = longitude and `y` = latitude.
+    ///
+    /// # Arguments
+    ///
+    /// * `polygon` - The region to cover
+    /// * `precision` - Finest geohash precision to split down to (1-12)
+    /// * `max_cells` - Upper bound on the number of cells; exceeded only
+    ///   when the polygon already touches more of the 32 top-level cells
+    ///
+    /// # Returns
+    ///
+    /// The cells in sorted order, or an empty vector if `precision` is out
+    /// of range or the polygon lies outside valid coordinates
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Coordinate, GeohashUtils, LinearRing, Point, Polygon};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let central_park = Polygon::new(
+    ///     LinearRing::new(vec![
+    ///         Coordinate::new(-73.9812, 40.7681),
+    ///         Coordinate::new(-73.9581, 40.8005),
+    ///         Coordinate::new(-73.9495, 40.7968),
+    ///         Coordinate::new(-73.9730, 40.7644),
+    ///     ])?,
+    ///     vec![],
+    /// );
+    ///
+    /// let cells = GeohashUtils::covering(&central_park, 7, 64);
+    /// assert!(cells.len() <= 64);
+    ///
+    /// let bethesda = Point::new(40.7740, -73.9708).to_geohash(7)?;
+    /// assert!(cells.iter().any(|cell| bethesda.starts_with(cell.as_str())));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn covering(polygon: &Polygon, precision: usize, max_cells: usize) -> Vec<String> {
+        if !(1..=12).contains(&precision) {
+            return Vec::new();
+        }
+
+        // The smallest cell holding both corners holds the whole box
+        let bounds = polygon.bounds();
+        let corner = |lat: f64, lon: f64| geohash::encode(geo::Coord { x: lon, y: lat }, precision);
+        let (Ok(sw), Ok(ne)) = (
+            corner(bounds.min.lat, bounds.min.lon),
+            corner(bounds.max.lat, bounds.max.lon),
+        ) else {
+            return Vec::new();
+        };
+        let shared = sw
+            .chars()
+            .zip(ne.chars())
+            .take_while(|(a, b)| a == b)
+            .count();
+        let start = sw[..shared].to_string();
+
+        let target = Geometry::Polygon(polygon.clone());
+        let boundary = Geometry::MultiLineString(
+            std::iter::once(polygon.exterior())
+                .chain(polygon.holes())
+                .filter_map(|ring| LineString::new(ring.coords().to_vec()).ok())
+                .collect(),
+        );
+        // Touching children, each with whether it is fully inside
+        let children = |cell: &str| -> Vec<(String, bool)> {
+            let cells: Vec<String> = GEOHASH_BASE32
+                .iter()
+                .map(|&c| format!("{}{}", cell, c as char))
+                .collect();
+            cells
+                .into_iter()
+                .filter_map(|child| {
+                    let (area, outline, corner) = cell_shapes(&child)?;
+                    if !area.intersects(&target) {
+                        return None;
+                    }
+                    // With no boundary crossing, the cell is inside if any
+                    // corner is
+                    let inside = !outline.intersects(&boundary) && polygon.contains_point(&corner);
+                    Some((child, inside))
+                })
+                .collect()
+        };
+
+        let mut done = Vec::new();
+        let mut pending = std::collections::VecDeque::new();
+        if start.is_empty() {
+            for (cell, inside) in children("") {
+                if inside || precision == 1 {
+                    done.push(cell);
+                } else {
+                    pending.push_back(cell);
+                }
+            }
+        } else {
+            pending.push_back(start);
+        }
+
+        while let Some(cell) = pending.pop_front() {
+            if cell.len() >= precision {
+                done.push(cell);
+                continue;
+            }
+            let split = children(&cell);
+            if done.len() + pending.len() + split.len() > max_cells {
+                pending.push_front(cell);
+                break;
+            }
+            for (child, inside) in split {
+                if inside {
+                    done.push(child);
+                } else {
+                    pending.push_back(child);
+                }
+            }
+        }
+
+        done.extend(pending);
+        done.sort();
+        done
+    }
+}
+
+/// A geohash cell as a rectangle, as its closed outline, and one of its
+/// corners
+fn cell_shapes(cell: &str) -> Option<(Geometry, Geometry, Coordinate)> {
+    let rect = geohash::decode_bbox(cell).ok()?;
+    let (min, max) = (rect.min(), rect.max());
+    let corners = vec![
+        Coordinate::new(min.x, min.y),
+        Coordinate::new(max.x, min.y),
+        Coordinate::new(max.x, max.y),
+        Coordinate::new(min.x, max.y),
+    ];
+    let corner = corners[0];
+    let ring = LinearRing::new(corners).ok()?;
+    let outline = LineString::new(ring.coords().to_vec()).ok()?;
+    Some((
+        Geometry::Polygon(Polygon::new(ring, Vec::new())),
+        Geometry::LineString(outline),
+        corner,
+    ))
+}
+
+/// S2 cell coverings of geographic regions.
+///
+/// A covering is a small set of S2 cells, possibly at different levels,
+/// whose union contains the region. Looking up only those cells avoids
+/// scanning every indexed point.
+#[cfg(feature = "s2")]
+pub struct S2Covering;
+
+#[cfg(feature = "s2")]
+impl S2Covering {
+    /// Compute S2 cells covering a bounding box.
+    ///
+    /// # Arguments
+    ///
+    /// * `bbox` - The region to cover
+    /// * `min_level` - Coarsest cell level to use (0-30)
+    /// * `max_level` - Finest cell level to use (0-30)
+    /// * `max_cells` - Desired upper bound on the number of cells; the
+    ///   covering may exceed it if `min_level` forces smaller cells
+    ///
+    /// # Returns
+    ///
+    /// Cell IDs whose union contains the bounding box
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{BoundingBox, Point, S2Covering};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let manhattan = BoundingBox::new(40.70, -74.02, 40.80, -73.93);
+    /// let cells = S2Covering::for_bounding_box(&manhattan, 8, 14, 8);
+    ///
+    /// let times_square = Point::new(40.758, -73.9855).to_s2_cell(30)?;
+    /// assert!(cells.iter().any(|cell| cell.contains(&times_square)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn for_bounding_box(
+        bbox: &BoundingBox,
+        min_level: u8,
+        max_level: u8,
+        max_cells: usize,
+    ) -> Vec<CellID> {
+        let max_level = max_level.min(30);
+        let coverer = RegionCoverer {
+            min_level: min_level.min(max_level),
+            max_level,
+            level_mod: 1,
+            max_cells,
+        };
+        let rect = Rect::from_degrees(bbox.min.lat, bbox.min.lon, bbox.max.lat, bbox.max.lon);
+        coverer.covering(&rect).0
+    }
+}
+
+impl PartialEq for Point {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Point {}
+
+impl Hash for Point {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let (lat, lon) = self.canonical();
+        lat.to_bits().hash(state);
+        lon.to_bits().hash(state);
+    }
+}
+
+impl PartialOrd for Point {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Point {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (lat, lon) = self.canonical();
+        let (other_lat, other_lon) = other.canonical();
+        lat.total_cmp(&other_lat)
+            .then_with(|| lon.total_cmp(&other_lon))
+    }
+}
+
+impl From<(f64, f64)> for Point {
+    /// Create a point from a `(lat, lon)` tuple
+    fn from((lat, lon): (f64, f64)) -> Self {
+        Point::new(lat, lon)
+    }
+}
+
+impl From<Point> for (f64, f64) {
+    /// Split a point into a `(lat, lon)` tuple
+    fn from(point: Point) -> Self {
+        (point.lat, point.lon)
     }
 }
 
@@ -541,9 +1401,195 @@ impl SpatialKey {
     /// let key = SpatialKey::s2_cell("sensors", cell_id);
     /// assert_eq!(key, "sensors:s2:1234567890");
     /// ```
+    #[cfg(feature = "s2")]
     pub fn s2_cell(prefix: &str, cell_id: CellID) -> String {
         format!("{}:s2:{}", prefix, cell_id.0)
     }
+
+    /// Generate an H3 cell-based key for database storage.
+    ///
+    /// The cell index is written in hexadecimal, the canonical H3 notation.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace prefix for the key
+    /// * `cell` - The H3 cell index
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::spatial::SpatialKey;
+    ///
+    /// let key = SpatialKey::h3_cell("vehicles", 0x8a2a1072b59ffff);
+    /// assert_eq!(key, "vehicles:h3:8a2a1072b59ffff");
+    /// ```
+    #[cfg(feature = "h3")]
+    pub fn h3_cell(prefix: &str, cell: u64) -> String {
+        format!("{}:h3:{:x}", prefix, cell)
+    }
+
+    /// Generate a key for data kept per XYZ tile, such as a rendered
+    /// vector tile.
+    ///
+    /// Tile keys are not spatially indexed by the database; use
+    /// `Point::to_tile` to find the tile a point belongs to.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace prefix for the key
+    /// * `zoom` - Tile zoom level
+    /// * `x` - Tile column
+    /// * `y` - Tile row
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::spatial::SpatialKey;
+    ///
+    /// let key = SpatialKey::tile("roads", 12, 1205, 1539);
+    /// assert_eq!(key, "roads:tile:12/1205/1539");
+    /// ```
+    pub fn tile(prefix: &str, zoom: u8, x: u32, y: u32) -> String {
+        format!("{}:tile:{}/{}/{}", prefix, zoom, x, y)
+    }
+
+    /// Generate a key for a geometry stored by id.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace prefix for the key
+    /// * `id` - Identifier of the geometry within the prefix
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::spatial::SpatialKey;
+    ///
+    /// let key = SpatialKey::geometry("parks", "central");
+    /// assert_eq!(key, "parks:geom:central");
+    /// ```
+    pub fn geometry(prefix: &str, id: &str) -> String {
+        format!("{}:geom:{}", prefix, id)
+    }
+
+    /// Longest component `hierarchical` accepts, in bytes
+    pub const MAX_COMPONENT_LEN: usize = 256;
+
+    /// Generate a key from a prefix and a path of components, joined with
+    /// `:`, such as `"fleet:region:west:truck42"`.
+    ///
+    /// Every part is validated so the key splits back into the same parts
+    /// with `parse_hierarchical`.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace prefix for the key
+    /// * `components` - Path below the prefix, outermost first
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InvalidKey` if the prefix or a component
+    /// contains `:` or a NUL byte, or is longer than `MAX_COMPONENT_LEN`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::spatial::SpatialKey;
+    ///
+    /// let key = SpatialKey::hierarchical("fleet", &["region", "west", "truck42"])?;
+    /// assert_eq!(key, "fleet:region:west:truck42");
+    ///
+    /// assert!(SpatialKey::hierarchical("fleet", &["west:east"]).is_err());
+    /// # Ok::<(), spatio::SpatioError>(())
+    /// ```
+    pub fn hierarchical(prefix: &str, components: &[&str]) -> Result<String> {
+        Self::hierarchical_with_max_len(prefix, components, Self::MAX_COMPONENT_LEN)
+    }
+
+    /// Like `hierarchical`, with a custom limit on the length of each part.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - Namespace prefix for the key
+    /// * `components` - Path below the prefix, outermost first
+    /// * `max_len` - Longest prefix or component accepted, in bytes
+    pub fn hierarchical_with_max_len(
+        prefix: &str,
+        components: &[&str],
+        max_len: usize,
+    ) -> Result<String> {
+        let mut key = String::with_capacity(
+            prefix.len() + components.iter().map(|c| c.len() + 1).sum::<usize>(),
+        );
+        for (i, part) in std::iter::once(&prefix).chain(components).enumerate() {
+            if part.contains(':') || part.contains('\0') {
+                return Err(SpatioError::InvalidKey(format!(
+                    "component {:?} contains ':' or a NUL byte",
+                    part
+                )));
+            }
+            if part.len() > max_len {
+                return Err(SpatioError::InvalidKey(format!(
+                    "component of {} bytes exceeds the {} byte limit",
+                    part.len(),
+                    max_len
+                )));
+            }
+            if i > 0 {
+                key.push(':');
+            }
+            key.push_str(part);
+        }
+        Ok(key)
+    }
+
+    /// Split a key built by `hierarchical` back into its prefix and
+    /// components.
+    ///
+    /// Returns `None` if the key has no `:` separator or contains a NUL
+    /// byte.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::spatial::SpatialKey;
+    ///
+    /// let (prefix, path) = SpatialKey::parse_hierarchical("fleet:region:west").unwrap();
+    /// assert_eq!(prefix, "fleet");
+    /// assert_eq!(path, vec!["region", "west"]);
+    ///
+    /// assert!(SpatialKey::parse_hierarchical("fleet").is_none());
+    /// ```
+    pub fn parse_hierarchical(key: &str) -> Option<(String, Vec<String>)> {
+        if key.contains('\0') {
+            return None;
+        }
+        let (prefix, rest) = key.split_once(':')?;
+        Some((
+            prefix.to_string(),
+            rest.split(':').map(str::to_string).collect(),
+        ))
+    }
+
+    /// Check whether a stored key was generated by one of the spatial key
+    /// builders (geohash, S2, H3 or geometry).
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::spatial::SpatialKey;
+    ///
+    /// assert!(SpatialKey::is_spatial(b"cities:gh:dr5regw3"));
+    /// assert!(!SpatialKey::is_spatial(b"user:123"));
+    /// ```
+    pub fn is_spatial(key: &[u8]) -> bool {
+        let mut parts = key.split(|&b| b == b':');
+        parts.next();
+        matches!(
+            parts.next(),
+            Some(b"gh") | Some(b"s2") | Some(b"h3") | Some(b"geom")
+        )
+    }
 }
 
 #[cfg(test)]
@@ -574,6 +1620,7 @@ mod tests {
         assert_eq!(geohash.len(), 8);
     }
 
+    #[cfg(feature = "s2")]
     #[test]
     fn test_s2_cell_generation() {
         let point = Point::new(40.7128, -74.0060);
@@ -581,6 +1628,218 @@ mod tests {
         assert!(s2_cell.0 > 0);
     }
 
+    #[test]
+    fn test_point_eq_hash_ord() {
+        use std::collections::{BTreeSet, HashSet};
+
+        let nyc = Point::new(40.7128, -74.0060);
+        let london = Point::new(51.5074, -0.1278);
+
+        let set: HashSet<Point> = [nyc, london, nyc].into_iter().collect();
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&Point::new(40.7128, -74.0060)));
+
+        // Ordered by latitude, then longitude
+        let ordered: Vec<Point> = [london, nyc, Point::new(40.7128, -75.0)]
+            .into_iter()
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        assert_eq!(ordered, vec![Point::new(40.7128, -75.0), nyc, london]);
+
+        // Signed zeros and NaNs compare equal so Eq and Hash stay consistent
+        assert_eq!(Point::new(0.0, -0.0), Point::new(-0.0, 0.0));
+        let nan = Point::new(f64::NAN, 0.0);
+        assert_eq!(nan, nan);
+        let set: HashSet<Point> = [Point::new(0.0, 0.0), Point::new(-0.0, -0.0), nan, nan]
+            .into_iter()
+            .collect();
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn test_point_tuple_conversions() {
+        let point = Point::from((40.7128, -74.0060));
+        assert_eq!(point, Point::new(40.7128, -74.0060));
+
+        let (lat, lon): (f64, f64) = point.into();
+        assert_eq!((lat, lon), (40.7128, -74.0060));
+    }
+
+    #[test]
+    fn test_geohash_ring() {
+        let center = "dr5ru";
+        let ring1 = GeohashUtils::ring(center, 1);
+        let mut neighbors: Vec<String> = {
+            let n = geohash::neighbors(center).unwrap();
+            vec![n.n, n.ne, n.e, n.se, n.s, n.sw, n.w, n.nw]
+        };
+        let mut sorted = ring1.clone();
+        sorted.sort();
+        neighbors.sort();
+        assert_eq!(sorted, neighbors);
+
+        // Ring 2 surrounds ring 1 without overlapping it
+        let ring2 = GeohashUtils::ring(center, 2);
+        assert_eq!(ring2.len(), 16);
+        assert!(
+            ring2
+                .iter()
+                .all(|cell| !ring1.contains(cell) && cell != center)
+        );
+
+        // Rows past the pole are dropped
+        let polar = Point::new(89.99, 0.0).to_geohash(3).unwrap();
+        assert_eq!(GeohashUtils::ring(&polar, 1).len(), 5);
+
+        assert!(GeohashUtils::ring("not a geohash!", 1).is_empty());
+        assert!(GeohashUtils::ring("", 1).is_empty());
+        assert!(GeohashUtils::ring("", 0).is_empty());
+        assert!(GeohashUtils::ring("dr5rudr5rudr5", 1).is_empty());
+    }
+
+    #[test]
+    fn test_geohash_cell_helpers() {
+        let nyc = Point::new(40.7128, -74.0060);
+        let cell = nyc.to_geohash(6).unwrap();
+        let bbox = GeohashUtils::bounding_box(&cell).unwrap();
+        assert!(bbox.contains(&nyc));
+        assert!(GeohashUtils::contains_point(&cell, &nyc).unwrap());
+
+        // The north-east corner belongs to the neighboring cells
+        assert!(GeohashUtils::contains_point(&cell, &bbox.min).unwrap());
+        assert!(!GeohashUtils::contains_point(&cell, &bbox.max).unwrap());
+
+        let touching = BoundingBox::new(
+            bbox.max.lat,
+            bbox.max.lon,
+            bbox.max.lat + 1.0,
+            bbox.max.lon + 1.0,
+        );
+        assert!(GeohashUtils::intersects_bbox(&cell, &touching).unwrap());
+        let apart = BoundingBox::new(0.0, 0.0, 1.0, 1.0);
+        assert!(!GeohashUtils::intersects_bbox(&cell, &apart).unwrap());
+
+        assert!(matches!(
+            GeohashUtils::bounding_box("not a geohash!"),
+            Err(SpatioError::InvalidGeohash)
+        ));
+        assert!(GeohashUtils::contains_point("ai", &nyc).is_err());
+        assert!(GeohashUtils::intersects_bbox("", &apart).is_err());
+    }
+
+    #[test]
+    fn test_fuzzy_decode() {
+        assert_eq!(GeohashUtils::fuzzy_decode("dr5", 0), vec!["dr5"]);
+
+        let matches = GeohashUtils::fuzzy_decode("dr5", 2);
+        assert_eq!(matches.len(), 1 + 3 * 31 + 3 * 31 * 31);
+        assert!(matches.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(matches.iter().all(|hash| {
+            hash.chars()
+                .zip("dr5".chars())
+                .filter(|(a, b)| a != b)
+                .count()
+                <= 2
+        }));
+
+        // The distance is capped by the length
+        assert_eq!(GeohashUtils::fuzzy_decode("d", 5).len(), 32);
+        assert!(GeohashUtils::fuzzy_decode("", 1).is_empty());
+        assert!(GeohashUtils::fuzzy_decode("dr5a", 1).is_empty());
+    }
+
+    #[test]
+    fn test_geohash_covering() {
+        let square = |x0: f64, y0: f64, x1: f64, y1: f64| {
+            LinearRing::new(vec![
+                Coordinate::new(x0, y0),
+                Coordinate::new(x1, y0),
+                Coordinate::new(x1, y1),
+                Coordinate::new(x0, y1),
+            ])
+            .unwrap()
+        };
+        // A square with a hole in the middle
+        let polygon = Polygon::new(
+            square(-74.02, 40.70, -73.92, 40.80),
+            vec![square(-73.99, 40.73, -73.95, 40.77)],
+        );
+
+        let cells = GeohashUtils::covering(&polygon, 6, 200);
+        assert!(!cells.is_empty() && cells.len() <= 200);
+        assert!(cells.iter().all(|cell| cell.len() <= 6));
+
+        let covered = |lat: f64, lon: f64| {
+            let hash = Point::new(lat, lon).to_geohash(6).unwrap();
+            cells.iter().any(|cell| hash.starts_with(cell.as_str()))
+        };
+        for i in 0..=10 {
+            for j in 0..=10 {
+                let lat = 40.70 + 0.01 * i as f64;
+                let lon = -74.02 + 0.01 * j as f64;
+                if polygon.contains_point(&Coordinate::new(lon, lat)) {
+                    assert!(covered(lat, lon), "({}, {}) not covered", lat, lon);
+                }
+            }
+        }
+        // Far outside the polygon and deep inside the hole are left out
+        assert!(!covered(40.90, -74.30));
+        assert!(!covered(40.75, -73.97));
+
+        // A tighter budget gives fewer, coarser cells
+        let coarse = GeohashUtils::covering(&polygon, 6, 16);
+        assert!(coarse.len() <= 16 && coarse.len() < cells.len());
+
+        // Cells fully inside a large polygon are kept coarser than
+        // `precision`
+        let large = Polygon::new(square(-76.0, 39.0, -72.0, 43.0), vec![]);
+        let cells = GeohashUtils::covering(&large, 5, 500);
+        assert!(cells.len() <= 500);
+        assert!(cells.iter().any(|cell| cell.len() < 5));
+        assert!(cells.iter().any(|cell| cell.len() == 5));
+
+        assert!(GeohashUtils::covering(&polygon, 0, 16).is_empty());
+        assert!(GeohashUtils::covering(&polygon, 13, 16).is_empty());
+    }
+
+    #[cfg(feature = "s2")]
+    #[test]
+    fn test_s2_covering_contains_box() {
+        let bbox = BoundingBox::new(40.70, -74.02, 40.80, -73.93);
+        let cells = S2Covering::for_bounding_box(&bbox, 6, 12, 8);
+        assert!(!cells.is_empty());
+        assert!(cells.iter().all(|cell| (6..=12).contains(&cell.level())));
+
+        for point in [
+            Point::new(40.70, -74.02),
+            Point::new(40.75, -73.98),
+            Point::new(40.80, -73.93),
+        ] {
+            let leaf = point.to_s2_cell(30).unwrap();
+            assert!(cells.iter().any(|cell| cell.contains(&leaf)));
+        }
+
+        let london = Point::new(51.5074, -0.1278).to_s2_cell(30).unwrap();
+        assert!(!cells.iter().any(|cell| cell.contains(&london)));
+    }
+
+    #[cfg(feature = "h3")]
+    #[test]
+    fn test_h3_cell_generation() {
+        let point = Point::new(40.7128, -74.0060);
+        let cell = point.to_h3_cell(9).unwrap();
+        let nearby = Point::new(40.71281, -74.00601).to_h3_cell(9).unwrap();
+        assert_eq!(cell, nearby);
+        assert_ne!(cell, Point::new(51.5074, -0.1278).to_h3_cell(9).unwrap());
+
+        assert!(point.to_h3_cell(16).is_err());
+        assert!(Point::new(f64::NAN, 0.0).to_h3_cell(9).is_err());
+
+        let key = SpatialKey::h3_cell("cities", cell);
+        assert_eq!(key, format!("cities:h3:{:x}", cell));
+    }
+
     #[test]
     fn test_within_bounds() {
         let point = Point::new(40.7128, -74.0060);
@@ -597,8 +1856,132 @@ mod tests {
         let geohash_key = SpatialKey::geohash("cities", "dr5regw3");
         assert_eq!(geohash_key, "cities:gh:dr5regw3");
 
-        let s2_key = SpatialKey::s2_cell("sensors", CellID(1234567890));
-        assert_eq!(s2_key, "sensors:s2:1234567890");
+        #[cfg(feature = "s2")]
+        {
+            let s2_key = SpatialKey::s2_cell("sensors", CellID(1234567890));
+            assert_eq!(s2_key, "sensors:s2:1234567890");
+        }
+
+        let geometry_key = SpatialKey::geometry("parks", "central");
+        assert_eq!(geometry_key, "parks:geom:central");
+        assert!(SpatialKey::is_spatial(geometry_key.as_bytes()));
+
+        assert_eq!(SpatialKey::tile("roads", 3, 2, 5), "roads:tile:3/2/5");
+    }
+
+    #[test]
+    fn test_hierarchical_key_validation() {
+        let key = SpatialKey::hierarchical("fleet", &["west", "truck42"]).unwrap();
+        assert_eq!(key, "fleet:west:truck42");
+        assert_eq!(
+            SpatialKey::parse_hierarchical(&key),
+            Some((
+                "fleet".to_string(),
+                vec!["west".to_string(), "truck42".to_string()]
+            ))
+        );
+
+        for bad in ["a:b", "a\0b"] {
+            assert!(matches!(
+                SpatialKey::hierarchical("fleet", &[bad]),
+                Err(SpatioError::InvalidKey(_))
+            ));
+        }
+        assert!(SpatialKey::hierarchical("fl:eet", &["west"]).is_err());
+
+        let long = "x".repeat(SpatialKey::MAX_COMPONENT_LEN + 1);
+        assert!(SpatialKey::hierarchical("fleet", &[&long]).is_err());
+        assert!(SpatialKey::hierarchical_with_max_len("fleet", &["west"], 5).is_ok());
+        assert!(SpatialKey::hierarchical_with_max_len("fleet", &["west42"], 5).is_err());
+
+        assert_eq!(SpatialKey::parse_hierarchical("fleet"), None);
+        assert_eq!(SpatialKey::parse_hierarchical("fleet:a\0b"), None);
+    }
+
+    #[test]
+    fn test_point_range_helpers() {
+        assert_eq!(
+            Point::try_new(-90.0, 180.0).unwrap(),
+            Point::new(-90.0, 180.0)
+        );
+        for (lat, lon) in [
+            (90.5, 0.0),
+            (0.0, -180.5),
+            (f64::NAN, 0.0),
+            (0.0, f64::INFINITY),
+        ] {
+            assert!(matches!(
+                Point::try_new(lat, lon),
+                Err(SpatioError::OutOfRange(_))
+            ));
+        }
+
+        let raw = Point::from_raw(120.0, 370.0);
+        assert_eq!((raw.lat, raw.lon), (120.0, 370.0));
+        assert_eq!(raw.normalize(), Point::new(90.0, 10.0));
+        assert_eq!(
+            Point::new(-100.0, -190.0).normalize(),
+            Point::new(-90.0, 170.0)
+        );
+        assert_eq!(Point::new(0.0, 180.0).normalize(), Point::new(0.0, 180.0));
+        assert_eq!(Point::new(0.0, 540.0).normalize(), Point::new(0.0, -180.0));
+        assert_eq!(Point::new(10.0, 20.0).normalize(), Point::new(10.0, 20.0));
+        assert!(Point::new(f64::NAN, f64::NAN).normalize().lon.is_nan());
+    }
+
+    #[test]
+    fn test_crosses_antimeridian() {
+        let east = Point::new(0.0, 170.0);
+        let west = Point::new(10.0, -170.0);
+        assert!(Point::crosses_antimeridian(&east, &west));
+        assert!(Point::crosses_antimeridian(&west, &east));
+        assert!(!Point::crosses_antimeridian(&east, &Point::new(0.0, 10.0)));
+        assert!(!Point::crosses_antimeridian(
+            &Point::new(0.0, -90.0),
+            &Point::new(0.0, 90.0)
+        ));
+
+        assert_eq!(Point::antimeridian_crossing(&east, &west), Some((0.5, 5.0)));
+        assert_eq!(Point::antimeridian_crossing(&west, &east), Some((0.5, 5.0)));
+        assert_eq!(
+            Point::antimeridian_crossing(&east, &Point::new(0.0, 10.0)),
+            None
+        );
+    }
+
+    #[test]
+    fn test_tiles() {
+        let nyc = Point::new(40.7128, -74.0060);
+        for zoom in [0, 1, 5, 10, 18, 30] {
+            let (x, y) = nyc.to_tile(zoom);
+            let tile = BoundingBox::from_tile(zoom, x, y).unwrap();
+            assert!(tile.contains(&nyc), "zoom {}", zoom);
+        }
+
+        // Quadrants at zoom 1
+        assert_eq!(Point::new(10.0, -10.0).to_tile(1), (0, 0));
+        assert_eq!(Point::new(10.0, 10.0).to_tile(1), (1, 0));
+        assert_eq!(Point::new(-10.0, -10.0).to_tile(1), (0, 1));
+        assert_eq!(Point::new(-10.0, 10.0).to_tile(1), (1, 1));
+
+        // Edges of the map stay within the tile grid
+        assert_eq!(Point::new(90.0, 180.0).to_tile(2), (3, 0));
+        assert_eq!(Point::new(-90.0, -180.0).to_tile(2), (0, 3));
+
+        let world = BoundingBox::from_tile(0, 0, 0).unwrap();
+        assert_eq!((world.min.lon, world.max.lon), (-180.0, 180.0));
+        assert!((world.max.lat - MAX_MERCATOR_LAT).abs() < 1e-9);
+        assert!((world.min.lat + MAX_MERCATOR_LAT).abs() < 1e-9);
+
+        assert!(BoundingBox::from_tile(2, 4, 0).is_err());
+        assert!(BoundingBox::from_tile(2, 0, 4).is_err());
+        assert!(BoundingBox::from_tile(31, 0, 0).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Tile zoom must be between 0 and 30")]
+    fn test_to_tile_rejects_large_zoom() {
+        Point::new(0.0, 0.0).to_tile(31);
     }
 
     #[test]
@@ -688,6 +2071,33 @@ mod tests {
         assert!(bbox1.intersects(&bbox1));
     }
 
+    #[test]
+    fn test_bounding_box_geometry() {
+        let bbox = BoundingBox::new(40.0, -75.0, 42.0, -73.0);
+
+        assert_eq!(bbox.center(), Point::new(41.0, -74.0));
+        assert!(bbox.contains(&Point::new(41.0, -74.0)));
+        assert!(bbox.contains(&Point::new(40.0, -75.0)));
+        assert!(!bbox.contains(&Point::new(39.9, -74.0)));
+
+        // Boxes of equal angular size shrink towards the poles
+        let equator = BoundingBox::new(0.0, 0.0, 1.0, 1.0);
+        let north = BoundingBox::new(60.0, 0.0, 61.0, 1.0);
+        assert!(north.area_km2() < equator.area_km2() * 0.6);
+
+        let expanded = bbox.expand_by_meters(10_000.0);
+        assert!(expanded.min.lat < bbox.min.lat && expanded.max.lat > bbox.max.lat);
+        assert!(expanded.min.lon < bbox.min.lon && expanded.max.lon > bbox.max.lon);
+        let north_edge = Point::new(bbox.max.lat, -74.0);
+        let beyond = Point::new(expanded.max.lat, -74.0);
+        assert!((north_edge.distance_to(&beyond) - 10_000.0).abs() < 1.0);
+
+        // Expansion is clamped to valid coordinates
+        let polar = BoundingBox::new(89.9, 179.9, 90.0, 180.0).expand_by_meters(50_000.0);
+        assert_eq!(polar.max.lat, 90.0);
+        assert_eq!(polar.max.lon, 180.0);
+    }
+
     #[test]
     #[cfg(feature = "geojson")]
     fn test_point_to_geojson_invalid_coordinates() {
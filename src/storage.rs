@@ -3,12 +3,17 @@
 //! This module provides a trait-based abstraction for storage backends,
 //! allowing different storage implementations while maintaining a consistent API.
 
-use crate::error::Result;
+use crate::error::{Result, SpatioError};
+#[cfg(feature = "rocksdb")]
+use crate::spatial::SpatialKey;
 use crate::types::DbItem;
 #[cfg(feature = "aof")]
 use crate::types::SetOptions;
+#[cfg(feature = "rocksdb")]
+use crate::types::ValueType;
 use bytes::Bytes;
 use std::collections::BTreeMap;
+use std::ops::Bound;
 use std::time::SystemTime;
 
 /// Trait for storage backend implementations
@@ -58,8 +63,137 @@ pub trait StorageBackend: Send + Sync {
     /// Iterator over all key-value pairs
     fn iter(&self) -> Result<Box<dyn Iterator<Item = (Bytes, DbItem)> + '_>>;
 
+    /// Iterator over key-value pairs with keys in the half-open range
+    /// `[start, end)`, in key order
+    ///
+    /// The default implementation filters `iter()`; backends with ordered
+    /// storage should override it with a range scan. An empty iterator is
+    /// returned when `start >= end`.
+    fn iter_range<'a>(
+        &'a self,
+        start: &[u8],
+        end: &[u8],
+    ) -> Result<Box<dyn Iterator<Item = (Bytes, DbItem)> + 'a>> {
+        let (start, end) = (start.to_vec(), end.to_vec());
+        let mut items: Vec<(Bytes, DbItem)> = self
+            .iter()?
+            .filter(|(key, _)| key.as_ref() >= start.as_slice() && key.as_ref() < end.as_slice())
+            .collect();
+        items.sort_by(|a, b| a.0.cmp(&b.0));
+        Ok(Box::new(items.into_iter()))
+    }
+
     /// Cleanup expired items (for TTL support)
     fn cleanup_expired(&mut self, now: SystemTime) -> Result<usize>;
+
+    /// Read-only view of the data as it is now, unaffected by later writes
+    ///
+    /// The default implementation copies every item from `iter()` into
+    /// memory, which is only consistent if nothing writes to the backend
+    /// while it runs. Backends that can be written concurrently should
+    /// override it with a native snapshot.
+    fn snapshot(&self) -> Result<Box<dyn StorageSnapshot>> {
+        Ok(Box::new(MemorySnapshot::from_items(self.iter()?.collect())))
+    }
+}
+
+/// Read-only, point-in-time view of a storage backend
+///
+/// Reads see the data as it was when the snapshot was taken. Writes fail
+/// with `SpatioError::ReadOnly`.
+pub trait StorageSnapshot: StorageBackend {}
+
+/// Snapshot holding its own copy of the data in memory
+///
+/// Returned by `MemoryBackend::snapshot`, `AOFBackend::snapshot`,
+/// `RocksDBBackend::snapshot` and `DB::snapshot`.
+pub struct MemorySnapshot {
+    data: MemoryBackend,
+}
+
+impl MemorySnapshot {
+    /// Snapshot of the given items
+    pub(crate) fn from_items(data: BTreeMap<Bytes, DbItem>) -> Self {
+        Self {
+            data: MemoryBackend {
+                data,
+                stats: StorageStats::default(),
+            },
+        }
+    }
+}
+
+impl StorageSnapshot for MemorySnapshot {}
+
+impl StorageBackend for MemorySnapshot {
+    fn put(&mut self, _key: &[u8], _item: &DbItem) -> Result<()> {
+        Err(SpatioError::ReadOnly)
+    }
+
+    fn get(&self, key: &[u8]) -> Result<Option<DbItem>> {
+        self.data.get(key)
+    }
+
+    fn delete(&mut self, _key: &[u8]) -> Result<Option<DbItem>> {
+        Err(SpatioError::ReadOnly)
+    }
+
+    fn contains_key(&self, key: &[u8]) -> Result<bool> {
+        self.data.contains_key(key)
+    }
+
+    fn keys_with_prefix(&self, prefix: &[u8]) -> Result<Vec<Bytes>> {
+        self.data.keys_with_prefix(prefix)
+    }
+
+    fn scan_prefix(&self, prefix: &[u8]) -> Result<BTreeMap<Bytes, DbItem>> {
+        self.data.scan_prefix(prefix)
+    }
+
+    fn len(&self) -> Result<usize> {
+        self.data.len()
+    }
+
+    fn is_empty(&self) -> Result<bool> {
+        self.data.is_empty()
+    }
+
+    fn sync(&mut self) -> Result<()> {
+        // Nothing is ever written
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<()> {
+        self.data.close()
+    }
+
+    fn stats(&self) -> Result<StorageStats> {
+        self.data.stats()
+    }
+
+    fn batch(&mut self, _ops: &[StorageOp]) -> Result<()> {
+        Err(SpatioError::ReadOnly)
+    }
+
+    fn iter(&self) -> Result<Box<dyn Iterator<Item = (Bytes, DbItem)> + '_>> {
+        self.data.iter()
+    }
+
+    fn iter_range<'a>(
+        &'a self,
+        start: &[u8],
+        end: &[u8],
+    ) -> Result<Box<dyn Iterator<Item = (Bytes, DbItem)> + 'a>> {
+        self.data.iter_range(start, end)
+    }
+
+    fn cleanup_expired(&mut self, _now: SystemTime) -> Result<usize> {
+        Err(SpatioError::ReadOnly)
+    }
+
+    fn snapshot(&self) -> Result<Box<dyn StorageSnapshot>> {
+        Ok(Box::new(Self::from_items(self.data.data.clone())))
+    }
 }
 
 /// Storage operation for batch processing
@@ -295,6 +429,23 @@ impl StorageBackend for MemoryBackend {
         ))
     }
 
+    fn iter_range<'a>(
+        &'a self,
+        start: &[u8],
+        end: &[u8],
+    ) -> Result<Box<dyn Iterator<Item = (Bytes, DbItem)> + 'a>> {
+        // BTreeMap::range panics on inverted bounds
+        if start >= end {
+            return Ok(Box::new(std::iter::empty()));
+        }
+
+        Ok(Box::new(
+            self.data
+                .range::<[u8], _>((Bound::Included(start), Bound::Excluded(end)))
+                .map(|(k, v)| (k.clone(), v.clone())),
+        ))
+    }
+
     fn cleanup_expired(&mut self, now: SystemTime) -> Result<usize> {
         let mut expired_keys = Vec::new();
 
@@ -316,6 +467,12 @@ impl StorageBackend for MemoryBackend {
 
         Ok(count)
     }
+
+    /// Clones the map; keys and values are reference counted, so only the
+    /// tree itself is copied
+    fn snapshot(&self) -> Result<Box<dyn StorageSnapshot>> {
+        Ok(Box::new(MemorySnapshot::from_items(self.data.clone())))
+    }
 }
 
 /// Persistent storage backend using AOF (Append-Only File)
@@ -349,7 +506,7 @@ impl StorageBackend for AOFBackend {
         // Write to AOF first for durability
         let opts = item.expires_at.map(SetOptions::with_expiration);
         self.aof_writer
-            .write_set(&Bytes::copy_from_slice(key), &item.value, opts.as_ref())?;
+            .write_typed_set(key, &item.value, opts.as_ref(), item.value_type)?;
 
         // Then update in-memory state
         self.memory.put(key, item)
@@ -406,7 +563,12 @@ impl StorageBackend for AOFBackend {
             match op {
                 StorageOp::Put { key, item } => {
                     let opts = item.expires_at.map(SetOptions::with_expiration);
-                    self.aof_writer.write_set(key, &item.value, opts.as_ref())?;
+                    self.aof_writer.write_typed_set(
+                        key,
+                        &item.value,
+                        opts.as_ref(),
+                        item.value_type,
+                    )?;
                 }
                 StorageOp::Delete { key } => {
                     self.aof_writer.write_delete(key)?;
@@ -422,6 +584,14 @@ impl StorageBackend for AOFBackend {
         self.memory.iter()
     }
 
+    fn iter_range<'a>(
+        &'a self,
+        start: &[u8],
+        end: &[u8],
+    ) -> Result<Box<dyn Iterator<Item = (Bytes, DbItem)> + 'a>> {
+        self.memory.iter_range(start, end)
+    }
+
     fn cleanup_expired(&mut self, now: SystemTime) -> Result<usize> {
         // For AOF backend, we might want to write deletions to AOF
         let expired_keys = {
@@ -443,6 +613,324 @@ impl StorageBackend for AOFBackend {
 
         self.memory.cleanup_expired(now)
     }
+
+    fn snapshot(&self) -> Result<Box<dyn StorageSnapshot>> {
+        self.memory.snapshot()
+    }
+}
+
+/// Column family holding plain key-value data
+#[cfg(feature = "rocksdb")]
+const KV_COLUMN_FAMILY: &str = "kv";
+
+/// Column family holding spatial index entries (geohash, S2 and H3 keys)
+#[cfg(feature = "rocksdb")]
+const SPATIAL_COLUMN_FAMILY: &str = "spatial";
+
+/// Persistent storage backend using RocksDB
+///
+/// Spatial keys (see `SpatialKey::is_spatial`) are stored in their own
+/// column family, separate from key-value data. Items are stored with
+/// their expiration time so TTLs survive a restart.
+#[cfg(feature = "rocksdb")]
+pub struct RocksDBBackend {
+    db: rocksdb::DB,
+    stats: StorageStats,
+}
+
+#[cfg(feature = "rocksdb")]
+impl RocksDBBackend {
+    /// Open or create a RocksDB database at the given directory
+    pub fn open<P: AsRef<std::path::Path>>(path: P) -> Result<Self> {
+        let mut opts = rocksdb::Options::default();
+        opts.create_if_missing(true);
+        opts.create_missing_column_families(true);
+
+        let db = rocksdb::DB::open_cf(&opts, path, [KV_COLUMN_FAMILY, SPATIAL_COLUMN_FAMILY])
+            .map_err(rocksdb_error)?;
+
+        Ok(Self {
+            db,
+            stats: StorageStats::default(),
+        })
+    }
+
+    fn column_family(&self, name: &str) -> Result<&rocksdb::ColumnFamily> {
+        self.db
+            .cf_handle(name)
+            .ok_or_else(|| SpatioError::Other(format!("RocksDB column family '{}' missing", name)))
+    }
+
+    /// Column family a key is stored in
+    fn column_family_for(&self, key: &[u8]) -> Result<&rocksdb::ColumnFamily> {
+        if SpatialKey::is_spatial(key) {
+            self.column_family(SPATIAL_COLUMN_FAMILY)
+        } else {
+            self.column_family(KV_COLUMN_FAMILY)
+        }
+    }
+
+    /// Range scan a single column family, decoding every item
+    fn scan_column_family(
+        &self,
+        name: &str,
+        prefix: &[u8],
+        result: &mut BTreeMap<Bytes, DbItem>,
+    ) -> Result<()> {
+        let cf = self.column_family(name)?;
+
+        let mut read_opts = rocksdb::ReadOptions::default();
+        let prefix_end = calculate_prefix_end(prefix);
+        if !prefix_end.is_empty() {
+            // An empty bound means the prefix was empty or all 0xFF bytes,
+            // so the scan runs to the end of the column family
+            read_opts.set_iterate_upper_bound(prefix_end);
+        }
+
+        let mode = rocksdb::IteratorMode::From(prefix, rocksdb::Direction::Forward);
+        for entry in self.db.iterator_cf_opt(cf, read_opts, mode) {
+            let (key, value) = entry.map_err(rocksdb_error)?;
+            if !key.starts_with(prefix) {
+                break;
+            }
+            result.insert(Bytes::copy_from_slice(&key), decode_item(&value)?);
+        }
+        Ok(())
+    }
+
+    /// Scan the keys of a single column family in `[start, end)`
+    fn scan_column_family_range(
+        &self,
+        name: &str,
+        start: &[u8],
+        end: &[u8],
+        result: &mut BTreeMap<Bytes, DbItem>,
+    ) -> Result<()> {
+        let cf = self.column_family(name)?;
+
+        let mut read_opts = rocksdb::ReadOptions::default();
+        read_opts.set_iterate_upper_bound(end.to_vec());
+
+        let mode = rocksdb::IteratorMode::From(start, rocksdb::Direction::Forward);
+        for entry in self.db.iterator_cf_opt(cf, read_opts, mode) {
+            let (key, value) = entry.map_err(rocksdb_error)?;
+            result.insert(Bytes::copy_from_slice(&key), decode_item(&value)?);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rocksdb")]
+impl StorageBackend for RocksDBBackend {
+    fn put(&mut self, key: &[u8], item: &DbItem) -> Result<()> {
+        let cf = self.column_family_for(key)?;
+        self.db
+            .put_cf(cf, key, encode_item(item))
+            .map_err(rocksdb_error)?;
+        self.stats.operations_count += 1;
+        Ok(())
+    }
+
+    fn get(&self, key: &[u8]) -> Result<Option<DbItem>> {
+        let cf = self.column_family_for(key)?;
+        match self.db.get_cf(cf, key).map_err(rocksdb_error)? {
+            Some(value) => Ok(Some(decode_item(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    fn delete(&mut self, key: &[u8]) -> Result<Option<DbItem>> {
+        let old_item = self.get(key)?;
+
+        let cf = self.column_family_for(key)?;
+        self.db.delete_cf(cf, key).map_err(rocksdb_error)?;
+        self.stats.operations_count += 1;
+
+        Ok(old_item)
+    }
+
+    fn contains_key(&self, key: &[u8]) -> Result<bool> {
+        let cf = self.column_family_for(key)?;
+        Ok(self
+            .db
+            .get_pinned_cf(cf, key)
+            .map_err(rocksdb_error)?
+            .is_some())
+    }
+
+    fn keys_with_prefix(&self, prefix: &[u8]) -> Result<Vec<Bytes>> {
+        Ok(self.scan_prefix(prefix)?.into_keys().collect())
+    }
+
+    /// Range scan both column families, bounded above by the prefix's
+    /// successor key so RocksDB stops at the end of the matching range.
+    fn scan_prefix(&self, prefix: &[u8]) -> Result<BTreeMap<Bytes, DbItem>> {
+        let mut result = BTreeMap::new();
+        self.scan_column_family(KV_COLUMN_FAMILY, prefix, &mut result)?;
+        self.scan_column_family(SPATIAL_COLUMN_FAMILY, prefix, &mut result)?;
+        Ok(result)
+    }
+
+    fn len(&self) -> Result<usize> {
+        Ok(self.scan_prefix(&[])?.len())
+    }
+
+    fn is_empty(&self) -> Result<bool> {
+        Ok(self.len()? == 0)
+    }
+
+    fn sync(&mut self) -> Result<()> {
+        let mut flush_opts = rocksdb::FlushOptions::default();
+        flush_opts.set_wait(true);
+        for name in [KV_COLUMN_FAMILY, SPATIAL_COLUMN_FAMILY] {
+            let cf = self.column_family(name)?;
+            self.db
+                .flush_cf_opt(cf, &flush_opts)
+                .map_err(rocksdb_error)?;
+        }
+        Ok(())
+    }
+
+    fn close(&mut self) -> Result<()> {
+        // RocksDB releases its handles on drop; make sure memtables are on disk
+        self.sync()
+    }
+
+    fn stats(&self) -> Result<StorageStats> {
+        let data = self.scan_prefix(&[])?;
+        let mut stats = self.stats.clone();
+        stats.key_count = data.len();
+        stats.size_bytes = data.iter().map(|(k, v)| k.len() + v.value.len()).sum();
+        Ok(stats)
+    }
+
+    fn batch(&mut self, ops: &[StorageOp]) -> Result<()> {
+        // A single write batch applies atomically across column families
+        let mut batch = rocksdb::WriteBatch::default();
+        for op in ops {
+            match op {
+                StorageOp::Put { key, item } => {
+                    batch.put_cf(self.column_family_for(key)?, key, encode_item(item));
+                }
+                StorageOp::Delete { key } => {
+                    batch.delete_cf(self.column_family_for(key)?, key);
+                }
+            }
+        }
+
+        self.db.write(batch).map_err(rocksdb_error)?;
+        self.stats.operations_count += ops.len() as u64;
+        Ok(())
+    }
+
+    fn iter(&self) -> Result<Box<dyn Iterator<Item = (Bytes, DbItem)> + '_>> {
+        Ok(Box::new(self.scan_prefix(&[])?.into_iter()))
+    }
+
+    fn iter_range<'a>(
+        &'a self,
+        start: &[u8],
+        end: &[u8],
+    ) -> Result<Box<dyn Iterator<Item = (Bytes, DbItem)> + 'a>> {
+        let mut result = BTreeMap::new();
+        if start < end {
+            // Keys are split across column families, so merge both ranges
+            self.scan_column_family_range(KV_COLUMN_FAMILY, start, end, &mut result)?;
+            self.scan_column_family_range(SPATIAL_COLUMN_FAMILY, start, end, &mut result)?;
+        }
+        Ok(Box::new(result.into_iter()))
+    }
+
+    fn cleanup_expired(&mut self, now: SystemTime) -> Result<usize> {
+        let expired: Vec<StorageOp> = self
+            .scan_prefix(&[])?
+            .into_iter()
+            .filter(|(_, item)| item.expires_at.is_some_and(|expires_at| expires_at <= now))
+            .map(|(key, _)| StorageOp::Delete { key })
+            .collect();
+
+        let count = expired.len();
+        self.batch(&expired)?;
+        self.stats.expired_count += count;
+
+        Ok(count)
+    }
+
+    /// Copies both column families under one RocksDB snapshot, so the copy
+    /// reflects a single point in time
+    fn snapshot(&self) -> Result<Box<dyn StorageSnapshot>> {
+        let snapshot = self.db.snapshot();
+        let mut items = BTreeMap::new();
+        for name in [KV_COLUMN_FAMILY, SPATIAL_COLUMN_FAMILY] {
+            let cf = self.column_family(name)?;
+            for entry in snapshot.iterator_cf(cf, rocksdb::IteratorMode::Start) {
+                let (key, value) = entry.map_err(rocksdb_error)?;
+                items.insert(Bytes::copy_from_slice(&key), decode_item(&value)?);
+            }
+        }
+        Ok(Box::new(MemorySnapshot::from_items(items)))
+    }
+}
+
+#[cfg(feature = "rocksdb")]
+fn rocksdb_error(err: rocksdb::Error) -> SpatioError {
+    SpatioError::Other(format!("RocksDB error: {}", err))
+}
+
+/// Encode an item as a one-byte flags field, the optional expiration
+/// time (seconds and nanoseconds since the Unix epoch), the optional
+/// `ValueType` tag, then the value.
+///
+/// Bit 0 of the flags marks an expiration and bit 1 a type tag, so items
+/// written before types were recorded decode unchanged.
+#[cfg(feature = "rocksdb")]
+fn encode_item(item: &DbItem) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(14 + item.value.len());
+    let expires_at = item
+        .expires_at
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok());
+    buf.push(u8::from(expires_at.is_some()) | (u8::from(item.value_type.is_some()) << 1));
+    if let Some(since_epoch) = expires_at {
+        buf.extend_from_slice(&since_epoch.as_secs().to_le_bytes());
+        buf.extend_from_slice(&since_epoch.subsec_nanos().to_le_bytes());
+    }
+    if let Some(value_type) = item.value_type {
+        buf.push(value_type.tag());
+    }
+    buf.extend_from_slice(&item.value);
+    buf
+}
+
+#[cfg(feature = "rocksdb")]
+fn decode_item(buf: &[u8]) -> Result<DbItem> {
+    let (&flags, mut rest) = buf.split_first().ok_or(SpatioError::UnexpectedEof)?;
+    if flags > 0b11 {
+        return Err(SpatioError::InvalidFormat);
+    }
+
+    let mut expires_at = None;
+    if flags & 1 != 0 {
+        if rest.len() < 12 {
+            return Err(SpatioError::UnexpectedEof);
+        }
+        let secs = u64::from_le_bytes(rest[..8].try_into().expect("8 bytes"));
+        let nanos = u32::from_le_bytes(rest[8..12].try_into().expect("4 bytes"));
+        expires_at = Some(std::time::UNIX_EPOCH + std::time::Duration::new(secs, nanos));
+        rest = &rest[12..];
+    }
+
+    let mut value_type = None;
+    if flags & 2 != 0 {
+        let (&tag, value) = rest.split_first().ok_or(SpatioError::UnexpectedEof)?;
+        value_type = Some(ValueType::from_tag(tag).ok_or(SpatioError::InvalidFormat)?);
+        rest = value;
+    }
+
+    Ok(DbItem {
+        value: Bytes::copy_from_slice(rest),
+        expires_at,
+        value_type,
+    })
 }
 
 #[cfg(test)]
@@ -459,6 +947,7 @@ mod tests {
         let item = DbItem {
             value: b"test_value".to_vec().into(),
             expires_at: None,
+            value_type: None,
         };
 
         // Test put and get
@@ -483,6 +972,7 @@ mod tests {
         let item = DbItem {
             value: b"value".to_vec().into(),
             expires_at: None,
+            value_type: None,
         };
 
         backend.put(b"prefix:key1", &item).unwrap();
@@ -502,6 +992,7 @@ mod tests {
         let item = DbItem {
             value: b"value".to_vec().into(),
             expires_at: None,
+            value_type: None,
         };
 
         // Test empty prefix
@@ -542,6 +1033,7 @@ mod tests {
         let item = DbItem {
             value: b"value".to_vec().into(),
             expires_at: None,
+            value_type: None,
         };
 
         // Insert keys in non-sorted order
@@ -566,6 +1058,7 @@ mod tests {
         let item = DbItem {
             value: b"value".to_vec().into(),
             expires_at: None,
+            value_type: None,
         };
 
         // Insert a large number of keys with different prefixes
@@ -597,6 +1090,52 @@ mod tests {
         assert_eq!(backend.data.len(), 2010); // Total keys in database
     }
 
+    #[test]
+    fn test_iter_range() {
+        let mut backend = MemoryBackend::new();
+        for key in ["a", "b", "c", "d"] {
+            backend
+                .put(key.as_bytes(), &DbItem::new(key.as_bytes().to_vec()))
+                .unwrap();
+        }
+
+        let keys: Vec<Bytes> = backend
+            .iter_range(b"b", b"d")
+            .unwrap()
+            .map(|(key, _)| key)
+            .collect();
+        assert_eq!(keys, vec![Bytes::from("b"), Bytes::from("c")]);
+
+        // Inverted and empty ranges yield nothing
+        assert_eq!(backend.iter_range(b"d", b"b").unwrap().count(), 0);
+        assert_eq!(backend.iter_range(b"b", b"b").unwrap().count(), 0);
+    }
+
+    #[test]
+    fn test_snapshot_ignores_later_writes() {
+        let mut backend = MemoryBackend::new();
+        backend.put(b"a", &DbItem::new(b"1".to_vec())).unwrap();
+        backend.put(b"b", &DbItem::new(b"2".to_vec())).unwrap();
+
+        let mut snapshot = backend.snapshot().unwrap();
+        backend.put(b"a", &DbItem::new(b"10".to_vec())).unwrap();
+        backend.delete(b"b").unwrap();
+        backend.put(b"c", &DbItem::new(b"3".to_vec())).unwrap();
+
+        let items: Vec<_> = snapshot.iter().unwrap().collect();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].1.value.as_ref(), b"1");
+        assert_eq!(snapshot.scan_prefix(b"b").unwrap().len(), 1);
+
+        // Snapshots are read-only
+        assert!(matches!(snapshot.delete(b"a"), Err(SpatioError::ReadOnly)));
+        assert!(matches!(
+            snapshot.cleanup_expired(SystemTime::now()),
+            Err(SpatioError::ReadOnly)
+        ));
+        assert_eq!(snapshot.snapshot().unwrap().len().unwrap(), 2);
+    }
+
     #[test]
     fn test_memory_backend_ttl_cleanup() {
         let mut backend = MemoryBackend::new();
@@ -608,11 +1147,13 @@ mod tests {
         let expired_item = DbItem {
             value: b"expired".to_vec().into(),
             expires_at: Some(past),
+            value_type: None,
         };
 
         let valid_item = DbItem {
             value: b"valid".to_vec().into(),
             expires_at: Some(future),
+            value_type: None,
         };
 
         backend.put(b"expired_key", &expired_item).unwrap();
@@ -634,6 +1175,7 @@ mod tests {
                 item: DbItem {
                     value: b"value1".to_vec().into(),
                     expires_at: None,
+                    value_type: None,
                 },
             },
             StorageOp::Put {
@@ -641,6 +1183,7 @@ mod tests {
                 item: DbItem {
                     value: b"value2".to_vec().into(),
                     expires_at: None,
+                    value_type: None,
                 },
             },
             StorageOp::Delete {
@@ -653,4 +1196,49 @@ mod tests {
         assert!(!backend.contains_key(b"key1").unwrap());
         assert!(backend.contains_key(b"key2").unwrap());
     }
+
+    #[cfg(feature = "rocksdb")]
+    #[test]
+    fn test_rocksdb_backend_persists_items() {
+        let dir = tempfile::tempdir().unwrap();
+        let expires_at = SystemTime::now() + Duration::from_secs(3600);
+
+        {
+            let mut backend = RocksDBBackend::open(dir.path()).unwrap();
+            backend
+                .put(b"user:1", &DbItem::new(Bytes::from_static(b"alice")))
+                .unwrap();
+            backend
+                .put(
+                    b"cities:gh:dr5regw3",
+                    &DbItem::with_expiration(Bytes::from_static(b"NYC"), expires_at),
+                )
+                .unwrap();
+            backend
+                .batch(&[
+                    StorageOp::Put {
+                        key: Bytes::from_static(b"user:2"),
+                        item: DbItem::new(Bytes::from_static(b"bob")),
+                    },
+                    StorageOp::Delete {
+                        key: Bytes::from_static(b"user:1"),
+                    },
+                ])
+                .unwrap();
+            backend.close().unwrap();
+        }
+
+        let backend = RocksDBBackend::open(dir.path()).unwrap();
+        assert_eq!(backend.len().unwrap(), 2);
+        assert!(!backend.contains_key(b"user:1").unwrap());
+
+        let spatial = backend.get(b"cities:gh:dr5regw3").unwrap().unwrap();
+        assert_eq!(spatial.value.as_ref(), b"NYC");
+        assert_eq!(spatial.expires_at, Some(expires_at));
+
+        // Prefix scans cover both column families
+        let scanned = backend.scan_prefix(b"cities:").unwrap();
+        assert_eq!(scanned.len(), 1);
+        assert_eq!(backend.keys_with_prefix(b"user:").unwrap().len(), 1);
+    }
 }
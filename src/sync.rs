@@ -0,0 +1,108 @@
+//! Lock types used for the shared database state
+//!
+//! With the `parking_lot` feature the database lock is parking_lot's
+//! `RwLock`, which is faster under contention and cannot be poisoned.
+//! Otherwise `std::sync::RwLock` is used and a poisoned lock is reported
+//! as `SpatioError::LockError`.
+
+use crate::error::Result;
+#[cfg(not(feature = "parking_lot"))]
+use crate::error::SpatioError;
+
+#[cfg(feature = "parking_lot")]
+pub(crate) use parking_lot::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+#[cfg(not(feature = "parking_lot"))]
+pub(crate) use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::Duration;
+#[cfg(not(feature = "parking_lot"))]
+use std::time::Instant;
+
+/// Longest sleep between attempts while polling a std lock
+#[cfg(not(feature = "parking_lot"))]
+const MAX_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Acquire a shared lock
+#[cfg(feature = "parking_lot")]
+pub(crate) fn read<T>(lock: &RwLock<T>) -> Result<RwLockReadGuard<'_, T>> {
+    Ok(lock.read())
+}
+
+/// Acquire a shared lock
+#[cfg(not(feature = "parking_lot"))]
+pub(crate) fn read<T>(lock: &RwLock<T>) -> Result<RwLockReadGuard<'_, T>> {
+    lock.read().map_err(|_| SpatioError::LockError)
+}
+
+/// Acquire an exclusive lock
+#[cfg(feature = "parking_lot")]
+pub(crate) fn write<T>(lock: &RwLock<T>) -> Result<RwLockWriteGuard<'_, T>> {
+    Ok(lock.write())
+}
+
+/// Acquire an exclusive lock
+#[cfg(not(feature = "parking_lot"))]
+pub(crate) fn write<T>(lock: &RwLock<T>) -> Result<RwLockWriteGuard<'_, T>> {
+    lock.write().map_err(|_| SpatioError::LockError)
+}
+
+/// Acquire an exclusive lock, giving up after `timeout`
+#[cfg(feature = "parking_lot")]
+pub(crate) fn try_write_for<T>(
+    lock: &RwLock<T>,
+    timeout: Duration,
+) -> Result<Option<RwLockWriteGuard<'_, T>>> {
+    Ok(lock.try_write_for(timeout))
+}
+
+/// Acquire an exclusive lock, giving up after `timeout`.
+///
+/// std locks cannot wait with a deadline, so this polls with a backoff
+/// that grows up to `MAX_POLL_INTERVAL`.
+#[cfg(not(feature = "parking_lot"))]
+pub(crate) fn try_write_for<T>(
+    lock: &RwLock<T>,
+    timeout: Duration,
+) -> Result<Option<RwLockWriteGuard<'_, T>>> {
+    let deadline = Instant::now() + timeout;
+    let mut backoff = Duration::from_micros(10);
+    loop {
+        match lock.try_write() {
+            Ok(guard) => return Ok(Some(guard)),
+            Err(std::sync::TryLockError::Poisoned(_)) => return Err(SpatioError::LockError),
+            Err(std::sync::TryLockError::WouldBlock) => {}
+        }
+
+        let now = Instant::now();
+        if now >= deadline {
+            return Ok(None);
+        }
+        std::thread::sleep(backoff.min(deadline - now));
+        backoff = (backoff * 2).min(MAX_POLL_INTERVAL);
+    }
+}
+
+/// Borrow the value of a lock that is exclusively owned
+#[cfg(feature = "parking_lot")]
+pub(crate) fn get_mut<T>(lock: &mut RwLock<T>) -> &mut T {
+    lock.get_mut()
+}
+
+/// Borrow the value of a lock that is exclusively owned, ignoring poison
+/// since no other thread can be holding it
+#[cfg(not(feature = "parking_lot"))]
+pub(crate) fn get_mut<T>(lock: &mut RwLock<T>) -> &mut T {
+    lock.get_mut()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Acquire an exclusive lock if no other guard is held
+#[cfg(all(test, feature = "parking_lot"))]
+pub(crate) fn try_write<T>(lock: &RwLock<T>) -> Option<RwLockWriteGuard<'_, T>> {
+    lock.try_write()
+}
+
+/// Acquire an exclusive lock if no other guard is held
+#[cfg(all(test, not(feature = "parking_lot")))]
+pub(crate) fn try_write<T>(lock: &RwLock<T>) -> Option<RwLockWriteGuard<'_, T>> {
+    lock.try_write().ok()
+}
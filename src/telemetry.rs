@@ -0,0 +1,121 @@
+//! Operation metrics for Spatio
+//!
+//! With the `metrics` feature enabled, database operations are recorded
+//! through the `metrics` crate facade, so any recorder (Prometheus,
+//! StatsD, ...) installed by the application picks them up. Metric names
+//! are prefixed with `Config::metrics_prefix`:
+//!
+//! - `{prefix}_insert_total` - counter of inserted keys
+//! - `{prefix}_get_total` - counter of key lookups
+//! - `{prefix}_spatial_query_duration_seconds` - histogram of spatial query
+//!   latency, labelled by `query`
+//! - `{prefix}_key_count` - gauge of stored keys
+//! - `{prefix}_expired_total` - counter of keys removed by the TTL sweep
+//!
+//! Without the feature every method here compiles to nothing.
+
+#[cfg(feature = "metrics")]
+use metrics::SharedString;
+#[cfg(feature = "metrics")]
+use std::sync::Arc;
+#[cfg(feature = "metrics")]
+use std::time::Instant;
+
+/// Precomputed metric names for one database
+#[derive(Debug, Clone)]
+pub(crate) struct Metrics {
+    #[cfg(feature = "metrics")]
+    insert_total: SharedString,
+    #[cfg(feature = "metrics")]
+    get_total: SharedString,
+    #[cfg(feature = "metrics")]
+    spatial_query_duration: SharedString,
+    #[cfg(feature = "metrics")]
+    key_count: SharedString,
+    #[cfg(feature = "metrics")]
+    expired_total: SharedString,
+}
+
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+impl Metrics {
+    /// Create metric names under the given prefix
+    #[cfg(feature = "metrics")]
+    pub fn new(prefix: &str) -> Self {
+        let name =
+            |suffix: &str| SharedString::from_shared(Arc::from(format!("{prefix}_{suffix}")));
+        Self {
+            insert_total: name("insert_total"),
+            get_total: name("get_total"),
+            spatial_query_duration: name("spatial_query_duration_seconds"),
+            key_count: name("key_count"),
+            expired_total: name("expired_total"),
+        }
+    }
+
+    /// Create metric names under the given prefix
+    #[cfg(not(feature = "metrics"))]
+    pub fn new(prefix: &str) -> Self {
+        Self {}
+    }
+
+    /// Record inserted keys
+    pub fn record_inserts(&self, count: u64) {
+        #[cfg(feature = "metrics")]
+        metrics::counter!(self.insert_total.clone()).increment(count);
+    }
+
+    /// Record a key lookup
+    pub fn record_get(&self) {
+        #[cfg(feature = "metrics")]
+        metrics::counter!(self.get_total.clone()).increment(1);
+    }
+
+    /// Record keys removed by the TTL sweep
+    pub fn record_expired(&self, count: u64) {
+        #[cfg(feature = "metrics")]
+        metrics::counter!(self.expired_total.clone()).increment(count);
+    }
+
+    /// Update the stored key gauge
+    pub fn set_key_count(&self, count: usize) {
+        #[cfg(feature = "metrics")]
+        metrics::gauge!(self.key_count.clone()).set(count as f64);
+    }
+
+    /// Start timing a spatial query; the duration is recorded on drop
+    pub fn time_spatial_query(&self, query: &'static str) -> QueryTimer<'_> {
+        QueryTimer {
+            #[cfg(feature = "metrics")]
+            metrics: self,
+            #[cfg(feature = "metrics")]
+            query,
+            #[cfg(feature = "metrics")]
+            start: Instant::now(),
+            #[cfg(not(feature = "metrics"))]
+            _metrics: std::marker::PhantomData,
+        }
+    }
+}
+
+/// Guard recording a spatial query's duration when dropped
+pub(crate) struct QueryTimer<'a> {
+    #[cfg(feature = "metrics")]
+    metrics: &'a Metrics,
+    #[cfg(feature = "metrics")]
+    query: &'static str,
+    #[cfg(feature = "metrics")]
+    start: Instant,
+    #[cfg(not(feature = "metrics"))]
+    _metrics: std::marker::PhantomData<&'a Metrics>,
+}
+
+#[cfg(feature = "metrics")]
+impl Drop for QueryTimer<'_> {
+    fn drop(&mut self) {
+        metrics::histogram!(
+            self.metrics.spatial_query_duration.clone(),
+            "query" => self.query
+        )
+        .record(self.start.elapsed().as_secs_f64());
+    }
+}
@@ -0,0 +1,195 @@
+use crate::DB;
+use crate::batch::AtomicBatch;
+use crate::error::{Result, SpatioError};
+use crate::types::SetOptions;
+use bytes::Bytes;
+use std::collections::HashMap;
+
+/// Transaction with optimistic concurrency control.
+///
+/// Reads go straight to the database and record the version of each key
+/// read; writes are buffered. On commit the write lock is taken, every key
+/// read is checked to still have the version it was read at, and only then
+/// are the writes applied. If another writer changed one of those keys in
+/// the meantime, the commit fails with `SpatioError::TransactionConflict`
+/// and `DB::transaction` runs the closure again.
+///
+/// Only keys read with `get` are checked. Keys seen through other `DB`
+/// methods, such as scans, are not part of the transaction.
+///
+/// # Examples
+///
+/// ```rust
+/// use spatio::Spatio;
+///
+/// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// let db = Spatio::memory()?;
+/// db.insert("account:a", b"100", None)?;
+/// db.insert("account:b", b"0", None)?;
+///
+/// // Move 30 from a to b; a concurrent change to either balance makes
+/// // the transaction rerun with the new values
+/// db.transaction(|tx| {
+///     let balance = |v: Option<bytes::Bytes>| -> u64 {
+///         std::str::from_utf8(&v.unwrap()).unwrap().parse().unwrap()
+///     };
+///     let a = balance(tx.get("account:a")?);
+///     let b = balance(tx.get("account:b")?);
+///     tx.insert("account:a", (a - 30).to_string(), None)?;
+///     tx.insert("account:b", (b + 30).to_string(), None)
+/// })?;
+///
+/// assert_eq!(db.get("account:a")?.unwrap().as_ref(), b"70");
+/// assert_eq!(db.get("account:b")?.unwrap().as_ref(), b"30");
+/// # Ok(())
+/// # }
+/// ```
+pub struct Transaction {
+    db: DB,
+    /// Version of every key read from the database; `None` if it was
+    /// missing or expired
+    reads: HashMap<Bytes, Option<u64>>,
+    /// Buffered writes, applied on commit
+    writes: AtomicBatch,
+}
+
+impl Transaction {
+    pub(crate) fn new(db: DB) -> Self {
+        Self {
+            writes: AtomicBatch::new(db.clone()),
+            db,
+            reads: HashMap::new(),
+        }
+    }
+
+    /// Get a value by key, seeing writes made earlier in this transaction.
+    ///
+    /// Keys read from the database are checked at commit time, so the
+    /// transaction only commits if none of them changed since.
+    pub fn get(&mut self, key: impl AsRef<[u8]>) -> Result<Option<Bytes>> {
+        let key = key.as_ref();
+        if let Some(pending) = self.writes.pending(key) {
+            return Ok(pending);
+        }
+
+        let inner = self.db.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+        let version = inner.key_version(key);
+        let value = version.and_then(|_| inner.get_item(&Bytes::copy_from_slice(key)));
+        let value = value.map(|item| item.value.clone());
+        self.reads
+            .entry(Bytes::copy_from_slice(key))
+            .or_insert(version);
+        Ok(value)
+    }
+
+    /// Insert a key-value pair when the transaction commits
+    pub fn insert(
+        &mut self,
+        key: impl AsRef<[u8]>,
+        value: impl AsRef<[u8]>,
+        opts: Option<SetOptions>,
+    ) -> Result<()> {
+        self.writes.insert(key, value, opts)
+    }
+
+    /// Delete a key when the transaction commits
+    pub fn delete(&mut self, key: impl AsRef<[u8]>) -> Result<()> {
+        self.writes.delete(key)
+    }
+
+    /// Check the keys read are unchanged and apply the writes, all under
+    /// the write lock
+    pub(crate) fn commit(self) -> Result<()> {
+        let reads = self.reads;
+        self.writes.commit_if(|inner| {
+            let unchanged = reads
+                .iter()
+                .all(|(key, version)| inner.key_version(key) == *version);
+            if unchanged {
+                Ok(())
+            } else {
+                Err(SpatioError::TransactionConflict)
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DB;
+    use crate::error::SpatioError;
+    use crate::types::{Config, SetOptions};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::{Duration, SystemTime};
+
+    #[test]
+    fn test_transaction_retries_after_conflict() {
+        let db = DB::memory().unwrap();
+        db.insert("counter", b"1", None).unwrap();
+
+        // The first attempt is interrupted by a concurrent write
+        let attempts = AtomicUsize::new(0);
+        db.transaction(|tx| {
+            let value = tx.get("counter")?.unwrap();
+            if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                db.insert("counter", b"5", None)?;
+            }
+            let n: u64 = std::str::from_utf8(&value).unwrap().parse().unwrap();
+            tx.insert("counter", (n + 1).to_string(), None)
+        })
+        .unwrap();
+
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+        assert_eq!(db.get("counter").unwrap().unwrap().as_ref(), b"6");
+    }
+
+    #[test]
+    fn test_transaction_gives_up_after_max_retries() {
+        let config = Config::default().with_max_transaction_retries(2);
+        let db = DB::memory_with_config(config).unwrap();
+
+        let attempts = AtomicUsize::new(0);
+        let result = db.transaction(|tx| {
+            // Every attempt is interrupted by a concurrent write
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+            tx.get("lock")?;
+            db.insert("lock", attempt.to_string(), None)?;
+            tx.insert("result", b"done", None)
+        });
+        assert!(matches!(result, Err(SpatioError::TransactionConflict)));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        assert!(db.get("result").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_transaction_reads_own_writes_and_expiry() {
+        let db = DB::memory().unwrap();
+        let expired = SetOptions::with_expiration(SystemTime::UNIX_EPOCH);
+        db.insert("stale", b"old", Some(expired)).unwrap();
+
+        db.transaction(|tx| {
+            assert!(tx.get("stale")?.is_none());
+            tx.insert("stale", b"new", None)?;
+            assert_eq!(tx.get("stale")?.unwrap().as_ref(), b"new");
+            tx.delete("stale")?;
+            assert!(tx.get("stale")?.is_none());
+            Ok(())
+        })
+        .unwrap();
+
+        // A key read while live that expires before commit conflicts
+        let config = Config::default().with_max_transaction_retries(0);
+        let strict = DB::memory_with_config(config).unwrap();
+        let ttl = SetOptions::with_ttl(Duration::from_millis(20));
+        strict.insert("session", b"token", Some(ttl)).unwrap();
+        let result = strict.transaction(|tx| {
+            assert!(tx.get("session")?.is_some());
+            std::thread::sleep(Duration::from_millis(30));
+            tx.insert("seen", b"yes", None)
+        });
+        assert!(matches!(result, Err(SpatioError::TransactionConflict)));
+    }
+}
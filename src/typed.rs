@@ -0,0 +1,392 @@
+//! Typed values for Spatio
+//!
+//! The methods here store strings, raw bytes, geometries, trajectory points
+//! and JSON documents with the `ValueType` they were stored as, so a mixed
+//! database can tell what a key holds with `DB::type_check` and read it
+//! back with the matching typed getter. The type is kept next to the
+//! value, not in its bytes, so `DB::get` returns the stored payload.
+
+use crate::DB;
+use crate::error::{Result, SpatioError};
+use crate::geometry::Geometry;
+use crate::spatial::Point;
+use crate::types::{SetOptions, TypeCheckResult, ValueType};
+use bytes::Bytes;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+impl DB {
+    /// Store text under a key, tagged as `ValueType::Utf8String`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Spatio;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_string("user:1:name", "Alice", None)?;
+    ///
+    /// assert_eq!(db.get_string("user:1:name")?.as_deref(), Some("Alice"));
+    /// // Plain reads see the stored text
+    /// assert_eq!(db.get("user:1:name")?.unwrap().as_ref(), b"Alice");
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn insert_string(
+        &self,
+        key: impl AsRef<[u8]>,
+        value: &str,
+        opts: Option<SetOptions>,
+    ) -> Result<()> {
+        self.insert_typed(key, ValueType::Utf8String, value.as_bytes(), opts)
+    }
+
+    /// Store raw bytes under a key, tagged as `ValueType::Bytes`.
+    pub fn insert_bytes(
+        &self,
+        key: impl AsRef<[u8]>,
+        value: &[u8],
+        opts: Option<SetOptions>,
+    ) -> Result<()> {
+        self.insert_typed(key, ValueType::Bytes, value, opts)
+    }
+
+    /// Store a geometry under a key as WKB, tagged as
+    /// `ValueType::SerializedGeometry`.
+    ///
+    /// The geometry is only stored, not spatially indexed; use
+    /// `insert_geometry` to make it findable by spatial queries.
+    pub fn insert_geometry_value(
+        &self,
+        key: impl AsRef<[u8]>,
+        geometry: &Geometry,
+        opts: Option<SetOptions>,
+    ) -> Result<()> {
+        let wkb = geometry.to_bytes();
+        self.insert_typed(key, ValueType::SerializedGeometry, &wkb, opts)
+    }
+
+    /// Store a point with its timestamp under a key, tagged as
+    /// `ValueType::TrajectoryPoint`.
+    ///
+    /// The payload is encoded like the points written by
+    /// `insert_trajectory`.
+    pub fn insert_trajectory_point(
+        &self,
+        key: impl AsRef<[u8]>,
+        point: &Point,
+        timestamp: u64,
+        opts: Option<SetOptions>,
+    ) -> Result<()> {
+        let data = bincode::serialize(&(point, timestamp)).map_err(|e| {
+            SpatioError::SerializationErrorWithContext(format!(
+                "Failed to serialize trajectory point: {}",
+                e
+            ))
+        })?;
+        self.insert_typed(key, ValueType::TrajectoryPoint, &data, opts)
+    }
+
+    /// Store a value as JSON under a key, tagged as `ValueType::JsonObject`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use serde_json::json;
+    /// use spatio::Spatio;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_json("sensor:7", &json!({"temp": 21.5, "unit": "C"}), None)?;
+    ///
+    /// let reading: serde_json::Value = db.get_json("sensor:7")?.unwrap();
+    /// assert_eq!(reading["temp"], 21.5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn insert_json<T: Serialize + ?Sized>(
+        &self,
+        key: impl AsRef<[u8]>,
+        value: &T,
+        opts: Option<SetOptions>,
+    ) -> Result<()> {
+        let json = serde_json::to_vec(value).map_err(|e| {
+            SpatioError::SerializationErrorWithContext(format!("Failed to serialize JSON: {}", e))
+        })?;
+        self.insert_typed(key, ValueType::JsonObject, &json, opts)
+    }
+
+    /// Get text stored with `insert_string`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InvalidFormat` if the key holds a value of
+    /// another type.
+    pub fn get_string(&self, key: impl AsRef<[u8]>) -> Result<Option<String>> {
+        self.get_typed(key, ValueType::Utf8String)?
+            .map(|payload| {
+                String::from_utf8(payload.to_vec()).map_err(|_| SpatioError::InvalidFormat)
+            })
+            .transpose()
+    }
+
+    /// Get bytes stored with `insert_bytes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InvalidFormat` if the key holds a value of
+    /// another type.
+    pub fn get_bytes(&self, key: impl AsRef<[u8]>) -> Result<Option<Bytes>> {
+        self.get_typed(key, ValueType::Bytes)
+    }
+
+    /// Get a geometry stored with `insert_geometry_value`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InvalidFormat` if the key holds a value of
+    /// another type.
+    pub fn get_geometry_value(&self, key: impl AsRef<[u8]>) -> Result<Option<Geometry>> {
+        self.get_typed(key, ValueType::SerializedGeometry)?
+            .map(|payload| Geometry::from_bytes(&payload))
+            .transpose()
+    }
+
+    /// Get a point and timestamp stored with `insert_trajectory_point`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InvalidFormat` if the key holds a value of
+    /// another type.
+    pub fn get_trajectory_point(&self, key: impl AsRef<[u8]>) -> Result<Option<(Point, u64)>> {
+        self.get_typed(key, ValueType::TrajectoryPoint)?
+            .map(|payload| bincode::deserialize(&payload).map_err(|_| SpatioError::InvalidFormat))
+            .transpose()
+    }
+
+    /// Get a value stored with `insert_json`, deserialized as `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `SpatioError::InvalidFormat` if the key holds a value of
+    /// another type, and a serialization error if the JSON does not fit `T`.
+    pub fn get_json<T: DeserializeOwned>(&self, key: impl AsRef<[u8]>) -> Result<Option<T>> {
+        self.get_typed(key, ValueType::JsonObject)?
+            .map(|payload| {
+                serde_json::from_slice(&payload).map_err(|e| {
+                    SpatioError::SerializationErrorWithContext(format!(
+                        "Failed to deserialize JSON: {}",
+                        e
+                    ))
+                })
+            })
+            .transpose()
+    }
+
+    /// Check whether a key holds a value of the given type.
+    ///
+    /// Missing keys and values stored with plain `insert` never match.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::{Spatio, ValueType};
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let db = Spatio::memory()?;
+    /// db.insert_string("greeting", "hello", None)?;
+    ///
+    /// assert!(db.type_check("greeting", ValueType::Utf8String)?.matches);
+    ///
+    /// let check = db.type_check("greeting", ValueType::JsonObject)?;
+    /// assert!(!check.matches);
+    /// assert_eq!(check.actual_type(), Some(ValueType::Utf8String));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            name = "db.type_check",
+            skip_all,
+            fields(
+                db.key_length = key.as_ref().len() as i64,
+            ),
+            err
+        )
+    )]
+    pub fn type_check(
+        &self,
+        key: impl AsRef<[u8]>,
+        type_tag: ValueType,
+    ) -> Result<TypeCheckResult> {
+        let Some((_, actual_type)) = self.get_with_type(key)? else {
+            return Ok(TypeCheckResult::default());
+        };
+        Ok(TypeCheckResult {
+            matches: actual_type == Some(type_tag),
+            actual_prefix_bytes: actual_type.map(ValueType::tag).into_iter().collect(),
+        })
+    }
+
+    /// Store `payload` recorded as `value_type`
+    fn insert_typed(
+        &self,
+        key: impl AsRef<[u8]>,
+        value_type: ValueType,
+        payload: &[u8],
+        opts: Option<SetOptions>,
+    ) -> Result<()> {
+        let mut inner = self.write_mut()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+        inner.set_typed(key.as_ref(), payload, opts, value_type)
+    }
+
+    /// The payload of a value stored as `value_type`
+    fn get_typed(&self, key: impl AsRef<[u8]>, value_type: ValueType) -> Result<Option<Bytes>> {
+        match self.get_with_type(key)? {
+            Some((value, Some(actual))) if actual == value_type => Ok(Some(value)),
+            Some(_) => Err(SpatioError::InvalidFormat),
+            None => Ok(None),
+        }
+    }
+
+    /// A live value with the type it was stored as
+    fn get_with_type(&self, key: impl AsRef<[u8]>) -> Result<Option<(Bytes, Option<ValueType>)>> {
+        let inner = self.read()?;
+        if inner.closed {
+            return Err(SpatioError::DatabaseClosed);
+        }
+        inner.metrics.record_get();
+
+        let key_bytes = Bytes::copy_from_slice(key.as_ref());
+        match inner.get_item(&key_bytes) {
+            Some(item) if !item.is_expired() => {
+                inner.touch(&key_bytes);
+                Ok(Some((item.value.clone(), item.value_type)))
+            }
+            _ => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DB;
+    use crate::error::SpatioError;
+    use crate::geometry::{Coordinate, Geometry, LineString};
+    use crate::spatial::Point;
+    use crate::types::ValueType;
+    use serde_json::json;
+
+    #[test]
+    fn test_typed_values_round_trip() {
+        let db = DB::memory().unwrap();
+        let line = Geometry::LineString(
+            LineString::new(vec![Coordinate::new(0.0, 0.0), Coordinate::new(1.0, 1.0)]).unwrap(),
+        );
+        let point = Point::new(40.7128, -74.0060);
+
+        db.insert_string("s", "héllo", None).unwrap();
+        db.insert_bytes("b", &[0xF5, 1, 2], None).unwrap();
+        db.insert_geometry_value("g", &line, None).unwrap();
+        db.insert_trajectory_point("t", &point, 1640995200, None)
+            .unwrap();
+        db.insert_json("j", &json!({"a": 1}), None).unwrap();
+
+        assert_eq!(db.get_string("s").unwrap().unwrap(), "héllo");
+        assert_eq!(db.get_bytes("b").unwrap().unwrap().as_ref(), [0xF5, 1, 2]);
+        assert_eq!(db.get_geometry_value("g").unwrap().unwrap(), line);
+        assert_eq!(
+            db.get_trajectory_point("t").unwrap().unwrap(),
+            (point, 1640995200)
+        );
+        let value: serde_json::Value = db.get_json("j").unwrap().unwrap();
+        assert_eq!(value, json!({"a": 1}));
+
+        // `get` returns the payload as stored
+        assert_eq!(db.get("s").unwrap().unwrap().as_ref(), "héllo".as_bytes());
+        assert_eq!(db.get("b").unwrap().unwrap().as_ref(), [0xF5, 1, 2]);
+        db.insert("plain", b"text", None).unwrap();
+        assert_eq!(db.get("plain").unwrap().unwrap().as_ref(), b"text");
+
+        // A plain insert replaces the type along with the value
+        db.insert("s", b"raw", None).unwrap();
+        assert!(db.get_string("s").is_err());
+
+        assert!(db.get_string("missing").unwrap().is_none());
+        assert!(matches!(
+            db.get_string("j"),
+            Err(SpatioError::InvalidFormat)
+        ));
+        assert!(db.get_string("plain").is_err());
+    }
+
+    #[test]
+    fn test_type_check() {
+        let db = DB::memory().unwrap();
+        db.insert_json("j", &json!({"a": 1}), None).unwrap();
+        db.insert("plain", b"text", None).unwrap();
+
+        let check = db.type_check("j", ValueType::JsonObject).unwrap();
+        assert!(check.matches);
+        assert_eq!(check.actual_prefix_bytes, vec![ValueType::JsonObject.tag()]);
+
+        let check = db.type_check("j", ValueType::Utf8String).unwrap();
+        assert!(!check.matches);
+        assert_eq!(check.actual_type(), Some(ValueType::JsonObject));
+
+        let check = db.type_check("plain", ValueType::Bytes).unwrap();
+        assert!(!check.matches);
+        assert!(check.actual_prefix_bytes.is_empty());
+        assert_eq!(check.actual_type(), None);
+
+        let check = db.type_check("missing", ValueType::Bytes).unwrap();
+        assert!(!check.matches);
+        assert!(check.actual_prefix_bytes.is_empty());
+
+        for value_type in ValueType::ALL {
+            assert_eq!(ValueType::from_tag(value_type.tag()), Some(value_type));
+        }
+        assert_eq!(ValueType::from_tag(b'{'), None);
+    }
+
+    #[test]
+    fn test_plain_values_keep_tag_like_bytes() {
+        let db = DB::memory().unwrap();
+        db.insert("raw", b"\xF5\x01\x02\x03", None).unwrap();
+        assert_eq!(
+            db.get("raw").unwrap().unwrap().as_ref(),
+            b"\xF5\x01\x02\x03"
+        );
+
+        let point = Point::new(40.7128, -74.0060);
+        let encoded = bincode::serialize(&(point, 1640995200u64)).unwrap();
+        db.insert("traj", &encoded, None).unwrap();
+        assert_eq!(db.get("traj").unwrap().unwrap().as_ref(), encoded);
+
+        let check = db.type_check("raw", ValueType::Utf8String).unwrap();
+        assert!(!check.matches);
+        assert_eq!(check.actual_type(), None);
+    }
+
+    #[cfg(feature = "aof")]
+    #[test]
+    fn test_typed_values_survive_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("typed.db");
+        {
+            let db = DB::open(&path).unwrap();
+            db.insert_string("name", "Alice", None).unwrap();
+            db.insert("raw", b"\xF5raw", None).unwrap();
+        }
+
+        let db = DB::open(&path).unwrap();
+        assert_eq!(db.get_string("name").unwrap().as_deref(), Some("Alice"));
+        assert_eq!(db.get("raw").unwrap().unwrap().as_ref(), b"\xF5raw");
+        assert!(db.get_string("raw").is_err());
+    }
+}
@@ -3,27 +3,56 @@
 //! This module provides streamlined, serializable types for configuration
 //! and data management with minimal complexity.
 
+use crate::clock;
+use crate::error::SpatioError;
+use crate::spatial::Point;
 use bytes::Bytes;
 use serde::de::Error;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::Bound;
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 
 /// Synchronization policy for persistence
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SyncPolicy {
     /// Never sync to disk (fastest, least safe)
     Never,
     /// Sync every second (recommended default)
+    #[default]
     EverySecond,
     /// Sync after every write (slowest, safest)
     Always,
 }
 
-impl Default for SyncPolicy {
-    fn default() -> Self {
-        Self::EverySecond
-    }
+/// How AOF replay handles records it cannot read, e.g. one cut short by
+/// a crash during a write
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryMode {
+    /// Fail with the read error, leaving the file untouched
+    #[default]
+    StopAtError,
+    /// Skip past damaged bytes to the next readable record and carry on.
+    /// Best effort: records have no sync markers, so the next record is
+    /// found by trying each following offset.
+    SkipCorrupted,
+    /// Stop at the first damaged record and cut the file there, so new
+    /// writes follow the last good record
+    Truncate,
+}
+
+/// What to do when an insert would exceed `Config::max_memory_bytes`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EvictionPolicy {
+    /// Reject the write with `SpatioError::MemoryLimitExceeded`
+    #[default]
+    NoEviction,
+    /// Evict the least-recently-used non-spatial keys until the write fits
+    LeastRecentlyUsed,
 }
 
 /// Simplified database configuration
@@ -62,6 +91,97 @@ pub struct Config {
     /// Higher values = more precision but more memory usage
     #[serde(default = "Config::default_geohash_precision")]
     pub geohash_precision: usize,
+
+    /// Geohash precisions used to find candidate cells in radius searches
+    /// (each 1-12, default: [6, 7, 8])
+    #[serde(default = "Config::default_geohash_search_precisions")]
+    pub geohash_search_precisions: Vec<usize>,
+
+    /// Upper bound on the estimated size of keys and values (None means unlimited)
+    #[serde(default)]
+    pub max_memory_bytes: Option<usize>,
+
+    /// Policy applied when an insert would exceed `max_memory_bytes`
+    #[serde(default)]
+    pub eviction_policy: EvictionPolicy,
+
+    /// Run large full-scan spatial queries on multiple threads. Has no
+    /// effect without the `parallel` feature.
+    #[serde(default)]
+    pub parallel_queries: bool,
+
+    /// Prefix for metric names recorded by the `metrics` feature
+    /// (default: "spatio", giving e.g. `spatio_insert_total`)
+    #[serde(default = "Config::default_metrics_prefix")]
+    pub metrics_prefix: String,
+
+    /// How replaying the AOF handles damaged records (default: stop at
+    /// the first one with an error)
+    #[serde(default)]
+    pub aof_recovery_mode: RecoveryMode,
+
+    /// Times `DB::transaction` reruns a transaction whose reads were
+    /// changed by another writer before giving up (default: 10)
+    #[serde(default = "Config::default_max_transaction_retries")]
+    pub max_transaction_retries: usize,
+
+    /// Keep a counter for every leading key prefix ending in `:` so
+    /// `DB::count_prefix` answers those in O(1) (default: true). Costs one
+    /// map entry per distinct prefix, e.g. two for `cities:gh:<hash>` keys
+    /// but one per key when ids themselves contain `:`.
+    #[serde(default = "Config::default_track_prefix_counts")]
+    pub track_prefix_counts: bool,
+
+    /// Bytes `DB::bulk_load` writes to the AOF between syncs (default:
+    /// 64 MiB; 0 syncs only once, at the end)
+    #[serde(default = "Config::default_bulk_load_sync_after_bytes")]
+    pub bulk_load_sync_after_bytes: usize,
+
+    /// Save the spatial index next to the AOF on close, as a `.sidx` file,
+    /// so the next open only indexes records written after it
+    /// (default: true)
+    #[serde(default = "Config::default_persist_spatial_index")]
+    pub persist_spatial_index: bool,
+
+    /// Run `DB::compact_memory` on a background thread at this interval
+    /// (None disables automatic compaction)
+    #[serde(default)]
+    pub auto_compact_interval: Option<Duration>,
+
+    /// Run `DB::cleanup_expired` on a background thread at this interval
+    /// (None leaves expired keys until they are read or cleaned up manually)
+    #[serde(default)]
+    pub expiry_interval: Option<Duration>,
+
+    /// Run `DB::checkpoint` to `checkpoint_path` on a background thread at
+    /// this interval (None disables automatic checkpoints)
+    #[serde(default)]
+    pub checkpoint_interval: Option<Duration>,
+
+    /// Where automatic checkpoints are written; required with
+    /// `checkpoint_interval`
+    #[serde(default)]
+    pub checkpoint_path: Option<PathBuf>,
+
+    /// Largest radius `DB::find_k_nearest` expands its search to
+    /// (default: 500 km)
+    #[serde(default = "Config::default_max_knn_radius_meters")]
+    pub max_knn_radius_meters: f64,
+
+    /// Hash tables built for `DB::approximate_nearest_neighbor` (default: 5)
+    #[serde(default = "Config::default_lsh_num_tables")]
+    pub lsh_num_tables: usize,
+
+    /// Keep a Bloom filter of stored keys with this false positive rate, so
+    /// `DB::exists` answers most missing keys without taking the lock
+    /// (None disables the filter)
+    #[serde(default)]
+    pub bloom_filter_false_positive_rate: Option<f64>,
+
+    /// Keys the Bloom filter is sized for; past this many the false
+    /// positive rate rises (default: 1,000,000)
+    #[serde(default = "Config::default_bloom_filter_capacity")]
+    pub bloom_filter_capacity: usize,
 }
 
 impl Config {
@@ -70,7 +190,62 @@ impl Config {
         8
     }
 
-    /// Create a configuration with custom geohash precision
+    /// Default geohash search precisions
+    fn default_geohash_search_precisions() -> Vec<usize> {
+        crate::index::DEFAULT_SEARCH_PRECISIONS.to_vec()
+    }
+
+    /// Search precisions derived from a geohash precision: the precision
+    /// itself and up to two coarser levels
+    pub(crate) fn search_precisions_for(precision: usize) -> Vec<usize> {
+        let precision = precision.clamp(1, 12);
+        (precision.saturating_sub(2).max(1)..=precision).collect()
+    }
+
+    /// Default metrics prefix
+    fn default_metrics_prefix() -> String {
+        "spatio".to_string()
+    }
+
+    /// Default search radius cap for k-nearest queries
+    const fn default_max_knn_radius_meters() -> f64 {
+        500_000.0
+    }
+
+    /// Default number of LSH hash tables
+    const fn default_lsh_num_tables() -> usize {
+        5
+    }
+
+    /// Default number of keys the Bloom filter is sized for
+    const fn default_bloom_filter_capacity() -> usize {
+        1_000_000
+    }
+
+    /// Default number of transaction retries
+    const fn default_max_transaction_retries() -> usize {
+        10
+    }
+
+    /// Default for tracking per-prefix key counts
+    const fn default_track_prefix_counts() -> bool {
+        true
+    }
+
+    /// Default AOF bytes written between syncs during a bulk load
+    const fn default_bulk_load_sync_after_bytes() -> usize {
+        64 * 1024 * 1024
+    }
+
+    /// Default for persisting the spatial index
+    const fn default_persist_spatial_index() -> bool {
+        true
+    }
+
+    /// Create a configuration with custom geohash precision.
+    ///
+    /// The search precisions are set to the precision and the two levels
+    /// below it, e.g. `[8, 9, 10]` for precision 10.
     pub fn with_geohash_precision(precision: usize) -> Self {
         assert!(
             (1..=12).contains(&precision),
@@ -78,12 +253,28 @@ impl Config {
         );
 
         Self {
-            sync_policy: SyncPolicy::default(),
-            default_ttl_seconds: None,
             geohash_precision: precision,
+            geohash_search_precisions: Self::search_precisions_for(precision),
+            ..Self::default()
         }
     }
 
+    /// Set the geohash precisions used to find candidate cells in radius
+    /// searches
+    pub fn with_search_precisions(mut self, precisions: Vec<usize>) -> Self {
+        assert!(
+            !precisions.is_empty(),
+            "At least one geohash search precision is required"
+        );
+        assert!(
+            precisions.iter().all(|p| (1..=12).contains(p)),
+            "Geohash search precisions must be between 1 and 12"
+        );
+
+        self.geohash_search_precisions = precisions;
+        self
+    }
+
     /// Set default TTL
     pub fn with_default_ttl(mut self, ttl: Duration) -> Self {
         self.default_ttl_seconds = Some(ttl.as_secs_f64());
@@ -96,6 +287,99 @@ impl Config {
         self
     }
 
+    /// Set a memory limit and the policy applied when it is reached
+    pub fn with_max_memory(mut self, max_bytes: usize, policy: EvictionPolicy) -> Self {
+        self.max_memory_bytes = Some(max_bytes);
+        self.eviction_policy = policy;
+        self
+    }
+
+    /// Enable or disable parallel full-scan spatial queries
+    pub fn with_parallel_queries(mut self, enabled: bool) -> Self {
+        self.parallel_queries = enabled;
+        self
+    }
+
+    /// Set the prefix for metric names
+    pub fn with_metrics_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.metrics_prefix = prefix.into();
+        self
+    }
+
+    /// Compact memory automatically at the given interval
+    pub fn with_auto_compact_interval(mut self, interval: Duration) -> Self {
+        self.auto_compact_interval = Some(interval);
+        self
+    }
+
+    /// Remove expired keys automatically at the given interval
+    pub fn with_expiry_interval(mut self, interval: Duration) -> Self {
+        self.expiry_interval = Some(interval);
+        self
+    }
+
+    /// Write a checkpoint to `path` automatically at the given interval
+    pub fn with_checkpoint_interval(
+        mut self,
+        interval: Duration,
+        path: impl Into<PathBuf>,
+    ) -> Self {
+        self.checkpoint_interval = Some(interval);
+        self.checkpoint_path = Some(path.into());
+        self
+    }
+
+    /// Set the largest radius searched by `DB::find_k_nearest`
+    pub fn with_max_knn_radius(mut self, meters: f64) -> Self {
+        self.max_knn_radius_meters = meters;
+        self
+    }
+
+    /// Set the number of hash tables built for
+    /// `DB::approximate_nearest_neighbor`
+    pub fn with_lsh_num_tables(mut self, tables: usize) -> Self {
+        self.lsh_num_tables = tables;
+        self
+    }
+
+    /// Set how AOF replay handles damaged records
+    pub fn with_aof_recovery_mode(mut self, mode: RecoveryMode) -> Self {
+        self.aof_recovery_mode = mode;
+        self
+    }
+
+    /// Set how many times `DB::transaction` retries after a conflict
+    pub fn with_max_transaction_retries(mut self, retries: usize) -> Self {
+        self.max_transaction_retries = retries;
+        self
+    }
+
+    /// Enable or disable the per-prefix counters behind `DB::count_prefix`
+    pub fn with_track_prefix_counts(mut self, enabled: bool) -> Self {
+        self.track_prefix_counts = enabled;
+        self
+    }
+
+    /// Set how many bytes `DB::bulk_load` writes to the AOF between syncs
+    pub fn with_bulk_load_sync_after_bytes(mut self, bytes: usize) -> Self {
+        self.bulk_load_sync_after_bytes = bytes;
+        self
+    }
+
+    /// Enable or disable the spatial index snapshot written on close
+    pub fn with_persist_spatial_index(mut self, enabled: bool) -> Self {
+        self.persist_spatial_index = enabled;
+        self
+    }
+
+    /// Enable the Bloom filter behind `DB::exists`, sized for `capacity`
+    /// keys at `false_positive_rate`
+    pub fn with_bloom_filter(mut self, false_positive_rate: f64, capacity: usize) -> Self {
+        self.bloom_filter_false_positive_rate = Some(false_positive_rate);
+        self.bloom_filter_capacity = capacity;
+        self
+    }
+
     /// Get default TTL as Duration
     pub fn default_ttl(&self) -> Option<Duration> {
         self.default_ttl_seconds.and_then(|ttl| {
@@ -113,6 +397,18 @@ impl Config {
             return Err("Geohash precision must be between 1 and 12".to_string());
         }
 
+        if self.geohash_search_precisions.is_empty() {
+            return Err("At least one geohash search precision is required".to_string());
+        }
+
+        if !self
+            .geohash_search_precisions
+            .iter()
+            .all(|p| (1..=12).contains(p))
+        {
+            return Err("Geohash search precisions must be between 1 and 12".to_string());
+        }
+
         if let Some(ttl) = self.default_ttl_seconds {
             if !ttl.is_finite() {
                 return Err("Default TTL must be finite (not NaN or infinity)".to_string());
@@ -125,6 +421,53 @@ impl Config {
             }
         }
 
+        if self.max_memory_bytes == Some(0) {
+            return Err("Max memory must be greater than zero".to_string());
+        }
+
+        if self.metrics_prefix.is_empty() {
+            return Err("Metrics prefix must not be empty".to_string());
+        }
+
+        if self.auto_compact_interval == Some(Duration::ZERO) {
+            return Err("Auto compact interval must be greater than zero".to_string());
+        }
+
+        if self.expiry_interval == Some(Duration::ZERO) {
+            return Err("Expiry interval must be greater than zero".to_string());
+        }
+
+        if self.checkpoint_interval == Some(Duration::ZERO) {
+            return Err("Checkpoint interval must be greater than zero".to_string());
+        }
+
+        if self.checkpoint_interval.is_some() && self.checkpoint_path.is_none() {
+            return Err("Checkpoint interval requires a checkpoint path".to_string());
+        }
+
+        #[cfg(not(feature = "aof"))]
+        if self.checkpoint_interval.is_some() {
+            return Err("Checkpoints require the `aof` feature".to_string());
+        }
+
+        if !(self.max_knn_radius_meters.is_finite() && self.max_knn_radius_meters > 0.0) {
+            return Err("Max k-nearest radius must be a positive number".to_string());
+        }
+
+        if self.lsh_num_tables == 0 {
+            return Err("LSH table count must be at least 1".to_string());
+        }
+
+        if let Some(rate) = self.bloom_filter_false_positive_rate
+            && !(rate > 0.0 && rate < 1.0)
+        {
+            return Err("Bloom filter false positive rate must be between 0 and 1".to_string());
+        }
+
+        if self.bloom_filter_capacity == 0 {
+            return Err("Bloom filter capacity must be at least 1".to_string());
+        }
+
         Ok(())
     }
 
@@ -157,6 +500,122 @@ impl Config {
     pub fn to_toml(&self) -> Result<String, toml::ser::Error> {
         toml::to_string_pretty(self)
     }
+
+    /// Load configuration from a file, choosing the format by extension.
+    ///
+    /// `.json` files are parsed with `from_json` and `.toml` files with
+    /// `from_toml`, which requires the `toml` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use spatio::Config;
+    ///
+    /// # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    /// let path = std::env::temp_dir().join("spatio_config_demo.json");
+    /// std::fs::write(&path, r#"{"geohash_precision": 10}"#)?;
+    ///
+    /// let config = Config::from_file(&path)?;
+    /// assert_eq!(config.geohash_precision, 10);
+    /// # std::fs::remove_file(path)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn from_file(path: &Path) -> crate::error::Result<Self> {
+        let extension = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("json") => {
+                let contents = std::fs::read_to_string(path)?;
+                Self::from_json(&contents)
+                    .map_err(|e| SpatioError::SerializationErrorWithContext(e.to_string()))
+            }
+            #[cfg(feature = "toml")]
+            Some("toml") => {
+                let contents = std::fs::read_to_string(path)?;
+                Self::from_toml(&contents)
+                    .map_err(|e| SpatioError::SerializationErrorWithContext(e.to_string()))
+            }
+            #[cfg(not(feature = "toml"))]
+            Some("toml") => Err(SpatioError::Other(
+                "Loading TOML configuration requires the toml feature".to_string(),
+            )),
+            _ => Err(SpatioError::Other(format!(
+                "Unsupported configuration file extension: {}",
+                path.display()
+            ))),
+        }
+    }
+
+    /// Load configuration from `SPATIO_*` environment variables.
+    ///
+    /// Starts from `Config::default()` and overrides each setting whose
+    /// variable is present:
+    ///
+    /// - `SPATIO_SYNC_POLICY` - `never`, `every_second` or `always`
+    /// - `SPATIO_GEOHASH_PRECISION` - integer between 1 and 12
+    /// - `SPATIO_DEFAULT_TTL_SECONDS` - positive number of seconds
+    /// - `SPATIO_MAX_MEMORY_BYTES` - memory limit in bytes
+    /// - `SPATIO_METRICS_PREFIX` - prefix for metric names
+    pub fn from_env() -> crate::error::Result<Self> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Build a configuration from a variable lookup function
+    fn from_vars<F>(get: F) -> crate::error::Result<Self>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        fn invalid(name: &str, value: &str) -> SpatioError {
+            SpatioError::Other(format!("Invalid value for {}: '{}'", name, value))
+        }
+
+        let mut config = Self::default();
+
+        if let Some(value) = get("SPATIO_SYNC_POLICY") {
+            config.sync_policy = match value.trim().to_ascii_lowercase().as_str() {
+                "never" => SyncPolicy::Never,
+                "every_second" => SyncPolicy::EverySecond,
+                "always" => SyncPolicy::Always,
+                _ => return Err(invalid("SPATIO_SYNC_POLICY", &value)),
+            };
+        }
+
+        if let Some(value) = get("SPATIO_GEOHASH_PRECISION") {
+            config.geohash_precision = value
+                .trim()
+                .parse()
+                .map_err(|_| invalid("SPATIO_GEOHASH_PRECISION", &value))?;
+            config.geohash_search_precisions =
+                Self::search_precisions_for(config.geohash_precision);
+        }
+
+        if let Some(value) = get("SPATIO_DEFAULT_TTL_SECONDS") {
+            let ttl = value
+                .trim()
+                .parse()
+                .map_err(|_| invalid("SPATIO_DEFAULT_TTL_SECONDS", &value))?;
+            config.default_ttl_seconds = Some(ttl);
+        }
+
+        if let Some(value) = get("SPATIO_MAX_MEMORY_BYTES") {
+            let max_bytes = value
+                .trim()
+                .parse()
+                .map_err(|_| invalid("SPATIO_MAX_MEMORY_BYTES", &value))?;
+            config.max_memory_bytes = Some(max_bytes);
+        }
+
+        if let Some(value) = get("SPATIO_METRICS_PREFIX") {
+            config.metrics_prefix = value.trim().to_string();
+        }
+
+        config.validate().map_err(SpatioError::Other)?;
+        Ok(config)
+    }
 }
 
 impl Default for Config {
@@ -165,6 +624,24 @@ impl Default for Config {
             sync_policy: SyncPolicy::default(),
             default_ttl_seconds: None,
             geohash_precision: Self::default_geohash_precision(),
+            geohash_search_precisions: Self::default_geohash_search_precisions(),
+            max_memory_bytes: None,
+            eviction_policy: EvictionPolicy::default(),
+            parallel_queries: false,
+            metrics_prefix: Self::default_metrics_prefix(),
+            aof_recovery_mode: RecoveryMode::default(),
+            max_transaction_retries: Self::default_max_transaction_retries(),
+            track_prefix_counts: Self::default_track_prefix_counts(),
+            bulk_load_sync_after_bytes: Self::default_bulk_load_sync_after_bytes(),
+            persist_spatial_index: Self::default_persist_spatial_index(),
+            auto_compact_interval: None,
+            expiry_interval: None,
+            checkpoint_interval: None,
+            checkpoint_path: None,
+            max_knn_radius_meters: Self::default_max_knn_radius_meters(),
+            lsh_num_tables: Self::default_lsh_num_tables(),
+            bloom_filter_false_positive_rate: None,
+            bloom_filter_capacity: Self::default_bloom_filter_capacity(),
         }
     }
 }
@@ -198,7 +675,7 @@ impl SetOptions {
     /// Get the effective expiration time
     pub fn effective_expires_at(&self) -> Option<SystemTime> {
         self.expires_at
-            .or_else(|| self.ttl.map(|ttl| SystemTime::now() + ttl))
+            .or_else(|| self.ttl.map(|ttl| clock::now() + ttl))
     }
 }
 
@@ -209,6 +686,9 @@ pub struct DbItem {
     pub value: Bytes,
     /// Expiration time (if any)
     pub expires_at: Option<SystemTime>,
+    /// Type the value was stored as by a typed insert method, such as
+    /// `DB::insert_string`; `None` for values stored with plain `insert`
+    pub value_type: Option<ValueType>,
 }
 
 impl DbItem {
@@ -217,6 +697,7 @@ impl DbItem {
         Self {
             value: value.into(),
             expires_at: None,
+            value_type: None,
         }
     }
 
@@ -225,12 +706,13 @@ impl DbItem {
         Self {
             value: value.into(),
             expires_at: Some(expires_at),
+            value_type: None,
         }
     }
 
     /// Create an item with TTL
     pub fn with_ttl(value: impl Into<Bytes>, ttl: Duration) -> Self {
-        let expires_at = SystemTime::now() + ttl;
+        let expires_at = clock::now() + ttl;
         Self::with_expiration(value, expires_at)
     }
 
@@ -241,7 +723,11 @@ impl DbItem {
         match options {
             Some(opts) => {
                 let expires_at = opts.effective_expires_at();
-                Self { value, expires_at }
+                Self {
+                    value,
+                    expires_at,
+                    value_type: None,
+                }
             }
             None => Self::new(value),
         }
@@ -249,7 +735,7 @@ impl DbItem {
 
     /// Check if this item has expired
     pub fn is_expired(&self) -> bool {
-        self.is_expired_at(SystemTime::now())
+        self.is_expired_at(clock::now())
     }
 
     /// Check if this item has expired at a specific time
@@ -262,7 +748,7 @@ impl DbItem {
 
     /// Get remaining TTL
     pub fn remaining_ttl(&self) -> Option<Duration> {
-        self.remaining_ttl_at(SystemTime::now())
+        self.remaining_ttl_at(clock::now())
     }
 
     /// Get remaining TTL at a specific time
@@ -291,6 +777,9 @@ pub struct DbStats {
     pub operations_count: u64,
     /// Total size in bytes (approximate)
     pub size_bytes: usize,
+    /// Number of keys evicted to stay under the memory limit
+    #[serde(default)]
+    pub evicted_count: u64,
 }
 
 impl DbStats {
@@ -318,6 +807,366 @@ impl DbStats {
     pub fn set_size_bytes(&mut self, bytes: usize) {
         self.size_bytes = bytes;
     }
+
+    /// Record keys evicted under memory pressure
+    pub fn record_evicted(&mut self, count: u64) {
+        self.evicted_count += count;
+    }
+}
+
+/// Result of `DB::verify_integrity`
+#[derive(Debug, Clone, Default)]
+pub struct IntegrityReport {
+    /// Number of keys that passed every check
+    pub valid_keys: usize,
+    /// Keys whose stored data failed a check
+    pub corrupted_keys: Vec<Bytes>,
+    /// Whether every AOF checksum footer matched (always true in memory)
+    pub aof_checksum_ok: bool,
+}
+
+/// Spatial index entry whose point key is no longer stored, found by
+/// `DB::find_stale_spatial_entries`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StaleEntry {
+    /// Prefix of the index holding the entry
+    pub prefix: String,
+    /// Geohash the point's key would be stored under
+    pub geohash: String,
+    /// Point left in the index
+    pub orphaned_point: Point,
+}
+
+/// Result of `DB::compact_memory`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompactionStats {
+    /// Number of expired keys purged
+    pub keys_removed: usize,
+    /// Key and value bytes released by the purged keys
+    pub bytes_freed: usize,
+}
+
+/// Result of `DB::checkpoint`
+#[cfg(feature = "aof")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CheckpointStats {
+    /// Live keys written to the checkpoint, including spatial keys
+    pub keys_written: usize,
+    /// Size of the checkpoint file in bytes
+    pub bytes_written: u64,
+}
+
+/// Result of `DB::replay_aof_from_offset`
+#[cfg(feature = "aof")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReplayStats {
+    /// SET and DELETE commands read and applied
+    pub commands_replayed: usize,
+    /// Damaged stretches skipped with `RecoveryMode::SkipCorrupted`,
+    /// each counted once however many records it held
+    pub commands_skipped: usize,
+    /// Offset replay finished at: the end of the file, or where it was
+    /// truncated with `RecoveryMode::Truncate`
+    pub final_offset: u64,
+}
+
+/// Format of a value stored by one of the typed insert methods, such as
+/// `DB::insert_string`.
+///
+/// The type is kept next to the value rather than in its bytes, and is
+/// written to the AOF as a one byte tag with the value. `DB::get`, scans
+/// and iterators return the value exactly as it was stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ValueType {
+    /// Text, from `DB::insert_string`
+    Utf8String,
+    /// Raw bytes, from `DB::insert_bytes`
+    Bytes,
+    /// A `Geometry` in WKB, from `DB::insert_geometry_value`
+    SerializedGeometry,
+    /// A point and timestamp, from `DB::insert_trajectory_point`
+    TrajectoryPoint,
+    /// A JSON document, from `DB::insert_json`
+    JsonObject,
+}
+
+impl ValueType {
+    /// Every value type, in tag order
+    pub const ALL: [ValueType; 5] = [
+        ValueType::Utf8String,
+        ValueType::Bytes,
+        ValueType::SerializedGeometry,
+        ValueType::TrajectoryPoint,
+        ValueType::JsonObject,
+    ];
+
+    /// The byte recording this type in the AOF
+    pub const fn tag(self) -> u8 {
+        match self {
+            ValueType::Utf8String => 0xF5,
+            ValueType::Bytes => 0xF6,
+            ValueType::SerializedGeometry => 0xF7,
+            ValueType::TrajectoryPoint => 0xF8,
+            ValueType::JsonObject => 0xF9,
+        }
+    }
+
+    /// The type a tag byte stands for, if any
+    pub fn from_tag(tag: u8) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|value_type| value_type.tag() == tag)
+    }
+}
+
+/// Result of `DB::type_check`
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TypeCheckResult {
+    /// Whether the value was stored as the requested type
+    pub matches: bool,
+    /// Tag of the type the value was stored as; empty if the key is
+    /// missing or its value was stored with plain `insert`
+    pub actual_prefix_bytes: Vec<u8>,
+}
+
+impl TypeCheckResult {
+    /// The type the value was stored as, or `None` for a missing key or
+    /// an untyped value
+    pub fn actual_type(&self) -> Option<ValueType> {
+        ValueType::from_tag(*self.actual_prefix_bytes.first()?)
+    }
+}
+
+/// Result of `DB::bulk_load`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BulkLoadStats {
+    /// Distinct keys stored in the database
+    pub items_loaded: usize,
+    /// Bytes appended to the AOF
+    pub bytes_written: u64,
+    /// Time taken by the whole load
+    pub duration: Duration,
+}
+
+/// Result of `DB::import_geojson_file`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportStats {
+    /// Features stored in the database
+    pub features_imported: usize,
+    /// Features without a supported, valid geometry
+    pub features_skipped: usize,
+    /// Bytes read from the file
+    pub bytes_read: usize,
+}
+
+/// Result of `DB::snapshot_to_geojson`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExportStats {
+    /// Features written to the file
+    pub features_exported: usize,
+    /// Bytes written to the file
+    pub bytes_written: usize,
+}
+
+/// Result of `DB::import_from_redis`
+#[cfg(feature = "redis-import")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RedisImportStats {
+    /// Redis keys stored in the database; a geo set counts once
+    pub keys_imported: usize,
+    /// Keys of unsupported types, or that expired or were deleted during
+    /// the import
+    pub keys_skipped: usize,
+    /// Key, value and geo set member bytes received from Redis
+    pub bytes_transferred: usize,
+}
+
+/// A moment when two trajectories were within the proximity passed to
+/// `DB::find_trajectory_crossings`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TrajectoryCrossing {
+    /// Timestamp both trajectories were interpolated at
+    pub time: u64,
+    /// Position of the first object at `time`
+    pub point_a: Point,
+    /// Position of the second object at `time`
+    pub point_b: Point,
+    /// Distance between the two positions in meters
+    pub distance: f64,
+}
+
+/// An undirected edge of the graphs built by `DB::knn_graph` and
+/// `DB::mutual_knn_graph`. `from_point` is the lesser endpoint in `Point`
+/// ordering.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct KnnEdge {
+    /// First endpoint
+    pub from_point: Point,
+    /// Second endpoint
+    pub to_point: Point,
+    /// Distance between the endpoints in meters
+    pub distance: f64,
+}
+
+/// A pair of points from two prefixes found by `DB::spatial_join`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpatialJoinResult {
+    /// Point from the first prefix
+    pub point_a: Point,
+    /// Data stored with `point_a`
+    pub data_a: Bytes,
+    /// Point from the second prefix
+    pub point_b: Point,
+    /// Data stored with `point_b`
+    pub data_b: Bytes,
+    /// Distance between the two points in meters
+    pub distance: f64,
+}
+
+/// Direction of a geofence boundary crossing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeofenceEventType {
+    /// The object moved from outside the region to inside it
+    Enter,
+    /// The object moved from inside the region to outside it
+    Exit,
+}
+
+/// A past crossing of a region's boundary found by `DB::geofence_events`
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeofenceEvent {
+    /// Trajectory object id, as passed to `insert_trajectory`
+    pub object_id: String,
+    /// Whether the object entered or left the region
+    pub event_type: GeofenceEventType,
+    /// First second the object was on the new side of the boundary
+    pub timestamp: u64,
+    /// Interpolated position of the object at `timestamp`
+    pub crossing_point: Point,
+}
+
+/// A group of similar trajectories found by `DB::cluster_trajectories`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrajectoryCluster {
+    /// Objects whose trajectories are in the cluster, in the order given
+    pub member_ids: Vec<String>,
+    /// Trajectory of the member with the smallest total distance to the
+    /// others
+    pub representative_trajectory: Vec<(Point, u64)>,
+}
+
+/// A point near a reference trajectory, found by
+/// `DB::find_objects_near_trajectory`
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorridorResult {
+    /// Point found in the searched prefix
+    pub object_point: Point,
+    /// Data stored with `object_point`
+    pub data: Bytes,
+    /// When the reference object passed `nearest_trajectory_point`,
+    /// interpolated between its recorded points
+    pub timestamp: u64,
+    /// Point on the reference trajectory nearest `object_point`
+    pub nearest_trajectory_point: Point,
+    /// Distance between the two points in meters
+    pub distance_meters: f64,
+}
+
+/// A point left out of every other namespace point's radius, found by
+/// `DB::spatial_difference_with_nearest`
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnservedPoint {
+    /// Point from the first prefix
+    pub point: Point,
+    /// Data stored with `point`
+    pub data: Bytes,
+    /// Nearest point of the second prefix with its data and distance in
+    /// meters, or `None` if that prefix has no points
+    pub nearest: Option<(Point, Bytes, f64)>,
+}
+
+/// Breakdown of a radius query returned by `DB::explain_nearby`.
+///
+/// Every point of the prefix is either never looked at (outside the
+/// visited cells), excluded by distance, excluded as expired, or returned:
+/// `candidates_evaluated == distance_excluded + expired_excluded + results_returned`.
+#[cfg(feature = "debug_queries")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QueryExplanation {
+    /// Points stored under the prefix
+    pub prefix_point_count: usize,
+    /// Geohash cells visited by the ring search, in visiting order; empty
+    /// when the query scanned the whole prefix
+    pub candidate_geohashes: Vec<String>,
+    /// Points whose distance to the center was computed
+    pub candidates_evaluated: usize,
+    /// Candidates within the radius whose TTL has passed
+    pub expired_excluded: usize,
+    /// Candidates farther than the radius
+    pub distance_excluded: usize,
+    /// Live candidates within the radius
+    pub results_returned: usize,
+    /// How the candidates were found: `"geohash_ring_search"`,
+    /// `"full_scan"`, `"parallel_full_scan"`, or `"none"` when the prefix
+    /// has no points
+    pub index_strategy_used: &'static str,
+}
+
+/// One page of `DB::scan_prefix`: the key-value pairs and the cursor for
+/// the next page (`None` when the scan is complete)
+pub type ScanPage = (Vec<(Bytes, Bytes)>, Option<Bytes>);
+
+/// Predicate on stored values, such as the export filter of
+/// `DB::snapshot_to_geojson`
+pub type ValueFilter = Box<dyn Fn(&[u8]) -> bool>;
+
+/// Keys grouped by a field extracted from their values, built by
+/// `DB::build_inverted_index`.
+///
+/// The index is a snapshot: later writes to the database are not
+/// reflected until it is built again. Field values are kept in sorted
+/// order, so `range_lookup` compares them as strings.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InvertedIndex {
+    /// Keys by field value, each list in key order
+    entries: BTreeMap<String, Vec<Bytes>>,
+}
+
+impl InvertedIndex {
+    pub(crate) fn new(entries: BTreeMap<String, Vec<Bytes>>) -> Self {
+        Self { entries }
+    }
+
+    /// Keys whose value has exactly this field value, in key order
+    pub fn lookup(&self, field_value: &str) -> Vec<Bytes> {
+        self.entries.get(field_value).cloned().unwrap_or_default()
+    }
+
+    /// Keys whose field value is at least `start` and less than `end`.
+    ///
+    /// Field values are compared as strings, so numbers only sort
+    /// numerically if the extractor pads them to a fixed width. Keys are
+    /// grouped by field value, in field value order. An empty or reversed
+    /// range matches nothing.
+    pub fn range_lookup(&self, start: &str, end: &str) -> Vec<Bytes> {
+        if start >= end {
+            return Vec::new();
+        }
+        self.entries
+            .range::<str, _>((Bound::Included(start), Bound::Excluded(end)))
+            .flat_map(|(_, keys)| keys.iter().cloned())
+            .collect()
+    }
+
+    /// Number of distinct field values
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether no key produced a field value
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
 }
 
 #[cfg(test)]
@@ -330,7 +1179,9 @@ mod tests {
         let config = Config::default();
         assert_eq!(config.sync_policy, SyncPolicy::EverySecond);
         assert_eq!(config.geohash_precision, 8);
+        assert_eq!(config.geohash_search_precisions, vec![6, 7, 8]);
         assert!(config.default_ttl_seconds.is_none());
+        assert_eq!(config.metrics_prefix, "spatio");
     }
 
     #[test]
@@ -345,6 +1196,30 @@ mod tests {
         Config::with_geohash_precision(15);
     }
 
+    #[test]
+    fn test_config_with_search_precisions() {
+        let config = Config::default().with_search_precisions(vec![4, 6]);
+        assert_eq!(config.geohash_search_precisions, vec![4, 6]);
+        assert!(config.validate().is_ok());
+
+        let json = config.to_json().unwrap();
+        let deserialized = Config::from_json(&json).unwrap();
+        assert_eq!(deserialized.geohash_search_precisions, vec![4, 6]);
+
+        // Older files without the field get the default
+        let config = Config::from_json(r#"{"geohash_precision": 10}"#).unwrap();
+        assert_eq!(config.geohash_search_precisions, vec![6, 7, 8]);
+
+        assert!(Config::from_json(r#"{"geohash_search_precisions": [0, 6]}"#).is_err());
+        assert!(Config::from_json(r#"{"geohash_search_precisions": []}"#).is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "Geohash search precisions must be between 1 and 12")]
+    fn test_config_invalid_search_precisions() {
+        Config::default().with_search_precisions(vec![8, 13]);
+    }
+
     #[test]
     fn test_config_serialization() {
         let config = Config::with_geohash_precision(10)
@@ -362,6 +1237,88 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_config_from_file() {
+        let dir = std::env::temp_dir();
+
+        let json_path = dir.join("spatio_test_config.json");
+        std::fs::write(
+            &json_path,
+            r#"{"sync_policy": "never", "geohash_precision": 6}"#,
+        )
+        .unwrap();
+        let config = Config::from_file(&json_path).unwrap();
+        assert_eq!(config.sync_policy, SyncPolicy::Never);
+        assert_eq!(config.geohash_precision, 6);
+        let _ = std::fs::remove_file(json_path);
+
+        let invalid_path = dir.join("spatio_test_config_invalid.json");
+        std::fs::write(&invalid_path, r#"{"geohash_precision": 20}"#).unwrap();
+        assert!(Config::from_file(&invalid_path).is_err());
+        let _ = std::fs::remove_file(invalid_path);
+
+        assert!(matches!(
+            Config::from_file(&dir.join("spatio_test_config.yaml")),
+            Err(SpatioError::Other(_))
+        ));
+        assert!(matches!(
+            Config::from_file(&dir.join("spatio_missing_config.json")),
+            Err(SpatioError::Io(_))
+        ));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_config_from_toml_file() {
+        let path = std::env::temp_dir().join("spatio_test_config.toml");
+        std::fs::write(
+            &path,
+            "sync_policy = \"always\"\ndefault_ttl_seconds = 30.0\n",
+        )
+        .unwrap();
+
+        let config = Config::from_file(&path).unwrap();
+        assert_eq!(config.sync_policy, SyncPolicy::Always);
+        assert_eq!(config.default_ttl(), Some(Duration::from_secs(30)));
+        let _ = std::fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_config_from_vars() {
+        let vars = |pairs: &'static [(&'static str, &'static str)]| {
+            move |name: &str| {
+                pairs
+                    .iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            }
+        };
+
+        let config = Config::from_vars(vars(&[])).unwrap();
+        assert_eq!(config.geohash_precision, 8);
+        assert_eq!(config.sync_policy, SyncPolicy::EverySecond);
+
+        let config = Config::from_vars(vars(&[
+            ("SPATIO_SYNC_POLICY", "Always"),
+            ("SPATIO_GEOHASH_PRECISION", "11"),
+            ("SPATIO_DEFAULT_TTL_SECONDS", "90"),
+            ("SPATIO_MAX_MEMORY_BYTES", "1048576"),
+            ("SPATIO_METRICS_PREFIX", "geo"),
+        ]))
+        .unwrap();
+        assert_eq!(config.sync_policy, SyncPolicy::Always);
+        assert_eq!(config.geohash_precision, 11);
+        assert_eq!(config.default_ttl(), Some(Duration::from_secs(90)));
+        assert_eq!(config.max_memory_bytes, Some(1_048_576));
+        assert_eq!(config.metrics_prefix, "geo");
+
+        assert!(Config::from_vars(vars(&[("SPATIO_SYNC_POLICY", "sometimes")])).is_err());
+        assert!(Config::from_vars(vars(&[("SPATIO_GEOHASH_PRECISION", "13")])).is_err());
+        assert!(Config::from_vars(vars(&[("SPATIO_DEFAULT_TTL_SECONDS", "-1")])).is_err());
+        assert!(Config::from_vars(vars(&[("SPATIO_MAX_MEMORY_BYTES", "0")])).is_err());
+        assert!(Config::from_vars(vars(&[("SPATIO_METRICS_PREFIX", " ")])).is_err());
+    }
+
     #[test]
     fn test_set_options() {
         let ttl_opts = SetOptions::with_ttl(Duration::from_secs(60));
@@ -431,6 +1388,40 @@ mod tests {
         config.geohash_precision = 8;
         config.default_ttl_seconds = Some(-1.0);
         assert!(config.validate().is_err());
+
+        config.default_ttl_seconds = None;
+        config.auto_compact_interval = Some(Duration::ZERO);
+        assert!(config.validate().is_err());
+
+        config.auto_compact_interval = None;
+        config.expiry_interval = Some(Duration::ZERO);
+        assert!(config.validate().is_err());
+
+        config.expiry_interval = None;
+        config.checkpoint_interval = Some(Duration::from_secs(60));
+        assert!(config.validate().is_err());
+        config.checkpoint_path = Some(PathBuf::from("backup.aof"));
+        assert_eq!(config.validate().is_ok(), cfg!(feature = "aof"));
+        config.checkpoint_interval = Some(Duration::ZERO);
+        assert!(config.validate().is_err());
+
+        config.checkpoint_interval = None;
+        config.max_knn_radius_meters = 0.0;
+        assert!(config.validate().is_err());
+
+        config.max_knn_radius_meters = 1_000.0;
+        assert!(config.validate().is_ok());
+        config.lsh_num_tables = 0;
+        assert!(config.validate().is_err());
+        assert_eq!(Config::default().with_lsh_num_tables(8).lsh_num_tables, 8);
+
+        config.lsh_num_tables = 5;
+        config.bloom_filter_false_positive_rate = Some(1.0);
+        assert!(config.validate().is_err());
+        config.bloom_filter_false_positive_rate = Some(0.01);
+        assert!(config.validate().is_ok());
+        config.bloom_filter_capacity = 0;
+        assert!(config.validate().is_err());
     }
 
     #[test]
@@ -0,0 +1,136 @@
+//! Lock-scoped access for multi-step operations
+//!
+//! `DB::view` and `DB::view_mut` run a closure with the database lock held
+//! for its whole duration, giving it a `DbView` or `DbViewMut`. Several
+//! reads, or reads and writes, then see one consistent state without the
+//! lock being released in between.
+//!
+//! The closure must not call methods on the `DB` it was started from: the
+//! lock is already held, so doing so deadlocks. Debug builds detect this
+//! and panic instead.
+
+use crate::db::DBInner;
+use crate::error::Result;
+use crate::iter::{Items, Keys};
+use crate::spatial::Point;
+use crate::types::{Config, DbItem, DbStats, SetOptions};
+use bytes::Bytes;
+
+/// Read-only access to the database inside `DB::view`
+pub struct DbView<'a> {
+    inner: &'a DBInner,
+}
+
+impl<'a> DbView<'a> {
+    pub(crate) fn new(inner: &'a DBInner) -> Self {
+        Self { inner }
+    }
+
+    /// Get a live value by key
+    pub fn get(&self, key: impl AsRef<[u8]>) -> Option<&'a Bytes> {
+        self.get_item(key).map(|item| &item.value)
+    }
+
+    /// Get a live item, including its expiration, by key
+    pub fn get_item(&self, key: impl AsRef<[u8]>) -> Option<&'a DbItem> {
+        self.inner
+            .keys
+            .get(key.as_ref())
+            .filter(|item| !item.is_expired())
+    }
+
+    /// Whether a live value is stored under the key
+    pub fn contains_key(&self, key: impl AsRef<[u8]>) -> bool {
+        self.get_item(key).is_some()
+    }
+
+    /// Number of live keys
+    pub fn len(&self) -> usize {
+        self.keys().count()
+    }
+
+    /// Whether there are no live keys
+    pub fn is_empty(&self) -> bool {
+        self.keys().next().is_none()
+    }
+
+    /// Every live key in key order
+    pub fn keys(&self) -> Keys<'a> {
+        Keys::new(self.inner.keys.iter())
+    }
+
+    /// Every live key and item in key order
+    pub fn items(&self) -> Items<'a> {
+        Items::new(self.inner.keys.iter())
+    }
+
+    /// Live keys and items under a prefix, in key order
+    pub fn scan_prefix<'p>(
+        &self,
+        prefix: &'p [u8],
+    ) -> impl Iterator<Item = (&'a Bytes, &'a DbItem)> + use<'a, 'p> {
+        self.inner
+            .keys
+            .range::<[u8], _>((
+                std::ops::Bound::Included(prefix),
+                std::ops::Bound::Unbounded,
+            ))
+            .take_while(move |(key, _)| key.starts_with(prefix))
+            .filter(|(_, item)| !item.is_expired())
+    }
+
+    /// Find indexed points within a radius, like `DB::find_nearby`
+    pub fn find_nearby(
+        &self,
+        prefix: &str,
+        center: &Point,
+        radius_meters: f64,
+        limit: usize,
+    ) -> Result<Vec<(Point, Bytes)>> {
+        self.inner
+            .index_manager
+            .find_nearby(prefix, center, radius_meters, limit)
+    }
+
+    /// Database configuration
+    pub fn config(&self) -> &'a Config {
+        &self.inner.config
+    }
+
+    /// Database statistics
+    pub fn stats(&self) -> &'a DbStats {
+        &self.inner.stats
+    }
+}
+
+/// Read and write access to the database inside `DB::view_mut`
+pub struct DbViewMut<'a> {
+    inner: &'a mut DBInner,
+}
+
+impl<'a> DbViewMut<'a> {
+    pub(crate) fn new(inner: &'a mut DBInner) -> Self {
+        Self { inner }
+    }
+
+    /// Read-only access to the current state, including earlier writes
+    /// made through this view
+    pub fn view(&self) -> DbView<'_> {
+        DbView::new(self.inner)
+    }
+
+    /// Insert a key-value pair, like `DB::insert`
+    pub fn insert(
+        &mut self,
+        key: impl AsRef<[u8]>,
+        value: impl AsRef<[u8]>,
+        opts: Option<SetOptions>,
+    ) -> Result<Option<Bytes>> {
+        self.inner.set(key.as_ref(), value.as_ref(), opts)
+    }
+
+    /// Delete a key, like `DB::delete`
+    pub fn delete(&mut self, key: impl AsRef<[u8]>) -> Result<Option<Bytes>> {
+        self.inner.delete_key(key.as_ref())
+    }
+}
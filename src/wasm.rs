@@ -0,0 +1,160 @@
+//! JavaScript bindings for Spatio
+//!
+//! This module exposes an in-memory database to JavaScript through
+//! `wasm-bindgen`, for web applications that query points offline in the
+//! browser. Build it for `wasm32-unknown-unknown` without default
+//! features, for example:
+//!
+//! ```text
+//! wasm-pack build --target web -- --no-default-features --features wasm
+//! ```
+//!
+//! and use it from JavaScript:
+//!
+//! ```text
+//! import init, { Spatio } from "./pkg/spatio.js";
+//!
+//! await init();
+//! const db = Spatio.memory();
+//! const utf8 = new TextEncoder();
+//! db.insert("user:1", utf8.encode("alice"));
+//! db.insertPoint("cities", 40.7128, -74.0060, utf8.encode("New York"));
+//! for (const city of db.findNearby("cities", 40.7, -74.0, 10000, 10)) {
+//!   console.log(city.lat, city.lon, new TextDecoder().decode(city.value));
+//! }
+//! ```
+//!
+//! Values cross the boundary as `Uint8Array`s. Errors are thrown as JS
+//! `Error`s carrying the `SpatioError` message.
+//!
+//! Browsers have no threads for the database to start, so leave
+//! `Config::expiry_interval` and `Config::auto_compact_interval` unset;
+//! expired keys are still hidden from reads.
+
+use crate::DB;
+use crate::spatial::Point;
+use wasm_bindgen::prelude::*;
+
+/// An in-memory Spatio database, exported to JavaScript as `Spatio`
+#[wasm_bindgen(js_name = Spatio)]
+pub struct WasmSpatio {
+    db: DB,
+}
+
+#[wasm_bindgen(js_class = Spatio)]
+impl WasmSpatio {
+    /// Create an in-memory database
+    pub fn memory() -> Result<WasmSpatio, JsError> {
+        Ok(WasmSpatio { db: DB::memory()? })
+    }
+
+    /// Store `value` under `key`, replacing any previous value
+    pub fn insert(&self, key: &str, value: &[u8]) -> Result<(), JsError> {
+        self.db.insert(key, value, None)?;
+        Ok(())
+    }
+
+    /// Get the value stored under `key`, or `undefined` if there is none
+    pub fn get(&self, key: &str) -> Result<Option<Vec<u8>>, JsError> {
+        Ok(self.db.get(key)?.map(|value| value.to_vec()))
+    }
+
+    /// Delete `key`, returning its value if it existed
+    pub fn delete(&self, key: &str) -> Result<Option<Vec<u8>>, JsError> {
+        Ok(self.db.delete(key)?.map(|value| value.to_vec()))
+    }
+
+    /// Store a point under `prefix` with its value, indexed for
+    /// `findNearby`
+    #[wasm_bindgen(js_name = insertPoint)]
+    pub fn insert_point(
+        &self,
+        prefix: &str,
+        lat: f64,
+        lon: f64,
+        value: &[u8],
+    ) -> Result<(), JsError> {
+        let point = Point::try_new(lat, lon)?;
+        self.db.insert_point(prefix, &point, value, None)?;
+        Ok(())
+    }
+
+    /// Find up to `limit` points under `prefix` within `radius` meters of
+    /// (`lat`, `lon`), nearest first
+    #[wasm_bindgen(js_name = findNearby)]
+    pub fn find_nearby(
+        &self,
+        prefix: &str,
+        lat: f64,
+        lon: f64,
+        radius: f64,
+        limit: usize,
+    ) -> Result<Vec<NearbyPoint>, JsError> {
+        let center = Point::try_new(lat, lon)?;
+        Ok(self
+            .db
+            .find_nearby(prefix, &center, radius, limit)?
+            .into_iter()
+            .map(|(point, value)| NearbyPoint {
+                lat: point.lat,
+                lon: point.lon,
+                value: value.to_vec(),
+            })
+            .collect())
+    }
+}
+
+/// A point returned by `findNearby`, with its stored value
+#[wasm_bindgen]
+pub struct NearbyPoint {
+    lat: f64,
+    lon: f64,
+    value: Vec<u8>,
+}
+
+#[wasm_bindgen]
+impl NearbyPoint {
+    /// Latitude in degrees
+    #[wasm_bindgen(getter)]
+    pub fn lat(&self) -> f64 {
+        self.lat
+    }
+
+    /// Longitude in degrees
+    #[wasm_bindgen(getter)]
+    pub fn lon(&self) -> f64 {
+        self.lon
+    }
+
+    /// The value stored with the point
+    #[wasm_bindgen(getter)]
+    pub fn value(&self) -> Vec<u8> {
+        self.value.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_memory_insert_and_find_nearby() {
+        let db = WasmSpatio::memory().unwrap();
+        db.insert("user:1", b"alice").unwrap();
+        assert_eq!(db.get("user:1").unwrap().as_deref(), Some(&b"alice"[..]));
+        assert!(db.get("user:2").unwrap().is_none());
+
+        db.insert_point("cities", 40.7128, -74.0060, b"New York")
+            .unwrap();
+        db.insert_point("cities", 51.5074, -0.1278, b"London")
+            .unwrap();
+        let nearby = db.find_nearby("cities", 40.7, -74.0, 10_000.0, 10).unwrap();
+        assert_eq!(nearby.len(), 1);
+        assert_eq!(nearby[0].value(), b"New York");
+        assert!((nearby[0].lat() - 40.7128).abs() < 1e-9);
+        assert!((nearby[0].lon() + 74.0060).abs() < 1e-9);
+
+        assert_eq!(db.delete("user:1").unwrap().as_deref(), Some(&b"alice"[..]));
+        assert!(db.get("user:1").unwrap().is_none());
+    }
+}
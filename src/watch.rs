@@ -0,0 +1,394 @@
+//! Key change notifications for Spatio
+//!
+//! This module lets callers subscribe to changes on a single key or on every
+//! key under a prefix, or to the number of keys under a prefix crossing a
+//! threshold. Events are delivered over `std::sync::mpsc` channels, so a
+//! watcher can block on, poll, or iterate its receiver from any thread.
+
+#[cfg(feature = "async")]
+use crate::region::{RegionSubscription, WindowSubscription};
+#[cfg(feature = "async")]
+use crate::spatial::Point;
+use crate::types::DbItem;
+use bytes::Bytes;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// Kind of change reported by a `KeyEvent`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyEventType {
+    /// The key was inserted or overwritten
+    Set,
+    /// The key was deleted (including eviction under memory pressure)
+    Deleted,
+    /// The key was removed by the TTL sweep
+    Expired,
+}
+
+/// A change to a watched key
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyEvent {
+    /// The key that changed
+    pub key: Bytes,
+    /// What happened to the key
+    pub event_type: KeyEventType,
+    /// The new value for `Set` events, `None` otherwise
+    pub new_value: Option<Bytes>,
+}
+
+/// Direction of a threshold crossing reported by a `CountEvent`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CountEventType {
+    /// The number of keys under the prefix rose above the threshold
+    Exceeded,
+    /// The number of keys under the prefix fell back to the threshold or
+    /// below
+    RecoveredBelow,
+}
+
+/// The number of keys under a watched prefix crossed its threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CountEvent {
+    /// Keys under the prefix just after the crossing
+    pub current_count: usize,
+    /// Which way the count crossed the threshold
+    pub event: CountEventType,
+}
+
+/// Identifies a watch so it can be removed with `DB::unwatch_prefix_count`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WatchHandle(u64);
+
+/// A prefix count watch and the count it has seen so far
+struct CountWatch {
+    handle: WatchHandle,
+    prefix: Bytes,
+    threshold: usize,
+    count: usize,
+    sender: Sender<CountEvent>,
+}
+
+impl CountWatch {
+    /// Move the count to `count`, sending an event if that crosses the
+    /// threshold. Returns false if the receiver has been dropped.
+    fn update(&mut self, count: usize) -> bool {
+        let was_above = self.count > self.threshold;
+        self.count = count;
+        let event = match (was_above, count > self.threshold) {
+            (false, true) => CountEventType::Exceeded,
+            (true, false) => CountEventType::RecoveredBelow,
+            _ => return true,
+        };
+        self.sender
+            .send(CountEvent {
+                current_count: count,
+                event,
+            })
+            .is_ok()
+    }
+}
+
+/// Number of keys in `keys` starting with `prefix`
+fn count_prefix(keys: &BTreeMap<Bytes, DbItem>, prefix: &Bytes) -> usize {
+    keys.range(prefix.clone()..)
+        .take_while(|(key, _)| key.starts_with(prefix))
+        .count()
+}
+
+/// Registered key and prefix watchers
+#[derive(Default)]
+pub(crate) struct Watchers {
+    /// Senders for exact key watches
+    keys: HashMap<Bytes, Vec<Sender<KeyEvent>>>,
+    /// Senders for prefix watches
+    prefixes: HashMap<Bytes, Vec<Sender<KeyEvent>>>,
+    /// Prefix count threshold watches
+    counts: Vec<CountWatch>,
+    /// Next id handed out as a `WatchHandle`
+    next_handle: u64,
+    /// Geofencing region watches fed by trajectory writes
+    #[cfg(feature = "async")]
+    regions: Vec<RegionSubscription>,
+    /// Sliding-window watches fed by point writes
+    #[cfg(feature = "async")]
+    windows: Vec<WindowSubscription>,
+}
+
+impl Watchers {
+    /// Register a watcher for a single key
+    pub fn watch_key(&mut self, key: Bytes) -> Receiver<KeyEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.keys.entry(key).or_default().push(sender);
+        receiver
+    }
+
+    /// Register a watcher for every key starting with `prefix`
+    pub fn watch_prefix(&mut self, prefix: Bytes) -> Receiver<KeyEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.prefixes.entry(prefix).or_default().push(sender);
+        receiver
+    }
+
+    /// Register a watcher for the number of keys in `keys` starting with
+    /// `prefix` crossing `threshold`.
+    ///
+    /// If the count is already above the threshold an `Exceeded` event is
+    /// queued straight away.
+    pub fn watch_count(
+        &mut self,
+        prefix: Bytes,
+        threshold: usize,
+        keys: &BTreeMap<Bytes, DbItem>,
+    ) -> (WatchHandle, Receiver<CountEvent>) {
+        let (sender, receiver) = mpsc::channel();
+        let handle = WatchHandle(self.next_handle);
+        self.next_handle += 1;
+
+        let count = count_prefix(keys, &prefix);
+        let mut watch = CountWatch {
+            handle,
+            prefix,
+            threshold,
+            count: 0,
+            sender,
+        };
+        watch.update(count);
+        self.counts.push(watch);
+        (handle, receiver)
+    }
+
+    /// Remove a prefix count watch, returning whether it was registered
+    pub fn unwatch_count(&mut self, handle: WatchHandle) -> bool {
+        let before = self.counts.len();
+        self.counts.retain(|watch| watch.handle != handle);
+        self.counts.len() != before
+    }
+
+    /// Account for a key being added (`added`) or removed under every
+    /// matching count watch.
+    ///
+    /// Watchers whose receiver has been dropped are unregistered.
+    pub fn count_changed(&mut self, key: &Bytes, added: bool) {
+        self.counts.retain_mut(|watch| {
+            if !key.starts_with(&watch.prefix) {
+                return true;
+            }
+            let count = if added {
+                watch.count + 1
+            } else {
+                watch.count.saturating_sub(1)
+            };
+            watch.update(count)
+        });
+    }
+
+    /// Recount every count watch against `keys`, after changes that did
+    /// not go through `count_changed` such as a restore
+    pub fn recount(&mut self, keys: &BTreeMap<Bytes, DbItem>) {
+        self.counts
+            .retain_mut(|watch| watch.update(count_prefix(keys, &watch.prefix)));
+    }
+
+    /// Register a geofencing region watch
+    #[cfg(feature = "async")]
+    pub fn watch_region(&mut self, subscription: RegionSubscription) {
+        self.regions.push(subscription);
+    }
+
+    /// Register a sliding-window watch
+    #[cfg(feature = "async")]
+    pub fn watch_window(&mut self, subscription: WindowSubscription) {
+        self.windows.push(subscription);
+    }
+
+    /// Feed a point written by `insert_point` to the sliding-window
+    /// watches. `set_at` is when its TTL was set, `None` without a TTL.
+    #[cfg(feature = "async")]
+    pub fn point_written(
+        &mut self,
+        prefix: &str,
+        key: &Bytes,
+        point: &Point,
+        value: &Bytes,
+        set_at: Option<std::time::SystemTime>,
+    ) {
+        self.windows
+            .retain_mut(|window| window.observe(prefix, key, point, value, set_at));
+    }
+
+    /// Whether nothing is being watched
+    pub fn is_empty(&self) -> bool {
+        #[cfg(feature = "async")]
+        if !self.regions.is_empty() || !self.windows.is_empty() {
+            return false;
+        }
+        self.keys.is_empty() && self.prefixes.is_empty()
+    }
+
+    /// Deliver an event to every matching watcher.
+    ///
+    /// Watchers whose receiver has been dropped are unregistered.
+    pub fn notify(&mut self, key: &Bytes, event_type: KeyEventType, new_value: Option<&Bytes>) {
+        if self.is_empty() {
+            return;
+        }
+
+        #[cfg(feature = "async")]
+        match new_value {
+            Some(value) => self.regions.retain_mut(|region| region.observe(key, value)),
+            None => self.windows.retain_mut(|window| window.forget(key)),
+        }
+
+        let event = KeyEvent {
+            key: key.clone(),
+            event_type,
+            new_value: new_value.cloned(),
+        };
+
+        if let Some(senders) = self.keys.get_mut(key) {
+            senders.retain(|sender| sender.send(event.clone()).is_ok());
+            if senders.is_empty() {
+                self.keys.remove(key);
+            }
+        }
+
+        self.prefixes.retain(|prefix, senders| {
+            if key.starts_with(prefix) {
+                senders.retain(|sender| sender.send(event.clone()).is_ok());
+            }
+            !senders.is_empty()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DB;
+    use crate::types::SetOptions;
+    use crate::watch::{CountEvent, CountEventType, KeyEvent, KeyEventType};
+    use bytes::Bytes;
+    use std::time::Duration;
+
+    #[test]
+    fn test_watch_key_receives_set_and_delete() {
+        let db = DB::memory().unwrap();
+        let events = db.watch("user:1").unwrap();
+
+        db.insert("user:1", b"alice", None).unwrap();
+        db.insert("user:2", b"bob", None).unwrap();
+        db.delete("user:1").unwrap();
+
+        let received: Vec<KeyEvent> = events.try_iter().collect();
+        assert_eq!(
+            received,
+            vec![
+                KeyEvent {
+                    key: Bytes::from_static(b"user:1"),
+                    event_type: KeyEventType::Set,
+                    new_value: Some(Bytes::from_static(b"alice")),
+                },
+                KeyEvent {
+                    key: Bytes::from_static(b"user:1"),
+                    event_type: KeyEventType::Deleted,
+                    new_value: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_watch_prefix_and_expiry() {
+        let db = DB::memory().unwrap();
+        let events = db.watch_prefix(b"session:").unwrap();
+
+        let opts = SetOptions::with_ttl(Duration::from_millis(10));
+        db.insert("session:a", b"1", Some(opts)).unwrap();
+        db.insert("user:1", b"alice", None).unwrap();
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(db.cleanup_expired().unwrap(), 1);
+
+        let kinds: Vec<(Bytes, KeyEventType)> = events
+            .try_iter()
+            .map(|event| (event.key, event.event_type))
+            .collect();
+        assert_eq!(
+            kinds,
+            vec![
+                (Bytes::from_static(b"session:a"), KeyEventType::Set),
+                (Bytes::from_static(b"session:a"), KeyEventType::Expired),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dropped_receiver_is_unregistered() {
+        let db = DB::memory().unwrap();
+        drop(db.watch("user:1").unwrap());
+        let _prefix = db.watch_prefix(b"user:").unwrap();
+
+        db.insert("user:1", b"alice", None).unwrap();
+        let inner = db.read().unwrap();
+        assert!(inner.watchers.keys.is_empty());
+        assert_eq!(inner.watchers.prefixes.len(), 1);
+    }
+
+    #[test]
+    fn test_watch_prefix_count_crossings() {
+        let db = DB::memory().unwrap();
+        let (handle, events) = db.watch_prefix_count("queue:", 2).unwrap();
+
+        db.insert("queue:1", b"a", None).unwrap();
+        db.insert("queue:2", b"b", None).unwrap();
+        db.insert("other:1", b"c", None).unwrap();
+        let opts = SetOptions::with_ttl(Duration::from_millis(10));
+        db.insert("queue:3", b"d", Some(opts)).unwrap();
+        // Overwriting does not change the count
+        db.insert("queue:1", b"e", None).unwrap();
+
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(db.cleanup_expired().unwrap(), 1);
+        db.delete("queue:2").unwrap();
+
+        let received: Vec<CountEvent> = events.try_iter().collect();
+        assert_eq!(
+            received,
+            vec![
+                CountEvent {
+                    current_count: 3,
+                    event: CountEventType::Exceeded,
+                },
+                CountEvent {
+                    current_count: 2,
+                    event: CountEventType::RecoveredBelow,
+                },
+            ]
+        );
+
+        assert!(db.unwatch_prefix_count(handle).unwrap());
+        assert!(!db.unwatch_prefix_count(handle).unwrap());
+        db.insert("queue:4", b"f", None).unwrap();
+        db.insert("queue:5", b"g", None).unwrap();
+        assert!(events.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_watch_prefix_count_starts_above_threshold() {
+        let db = DB::memory().unwrap();
+        db.insert("queue:1", b"a", None).unwrap();
+        db.insert("queue:2", b"b", None).unwrap();
+
+        let (_handle, events) = db.watch_prefix_count("queue:", 1).unwrap();
+        assert_eq!(
+            events.try_recv().unwrap(),
+            CountEvent {
+                current_count: 2,
+                event: CountEventType::Exceeded,
+            }
+        );
+
+        drop(events);
+        db.delete("queue:1").unwrap();
+        assert!(db.read().unwrap().watchers.counts.is_empty());
+    }
+}
@@ -0,0 +1,258 @@
+#[cfg(feature = "aof")]
+use spatio::{Compression, StorageBackend};
+use spatio::{
+    Config, DBBuilder, EvictionPolicy, IndexStrategy, MemoryBackend, Point, SetOptions, SpatioError,
+};
+use std::time::Duration;
+#[cfg(feature = "aof")]
+use tempfile::TempDir;
+
+fn assert_invalid_configuration(builder: DBBuilder) {
+    match builder.build() {
+        Err(SpatioError::InvalidConfiguration(_)) => {}
+        other => panic!("Expected InvalidConfiguration, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[cfg(feature = "aof")]
+#[test]
+fn test_builder_aof_path_persists() {
+    let temp_dir = TempDir::new().unwrap();
+    let aof_path = temp_dir.path().join("builder.aof");
+
+    let db = DBBuilder::new().aof_path(&aof_path).build().unwrap();
+    db.insert("key", b"value", None).unwrap();
+    drop(db);
+
+    let db = DBBuilder::new().aof_path(&aof_path).build().unwrap();
+    assert_eq!(db.get("key").unwrap().unwrap().as_ref(), b"value");
+}
+
+#[test]
+fn test_builder_memory() {
+    let db = DBBuilder::new().memory().build().unwrap();
+    db.insert("key", b"value", None).unwrap();
+    assert_eq!(db.get("key").unwrap().unwrap().as_ref(), b"value");
+}
+
+#[cfg(feature = "aof")]
+#[test]
+fn test_builder_memory_conflicts_with_aof_path() {
+    let temp_dir = TempDir::new().unwrap();
+    let aof_path = temp_dir.path().join("conflict.aof");
+
+    assert_invalid_configuration(DBBuilder::new().memory().aof_path(&aof_path));
+    assert_invalid_configuration(DBBuilder::new().aof_path(&aof_path).memory());
+    assert!(!aof_path.exists());
+
+    // in_memory() is overridden rather than rejected
+    DBBuilder::new()
+        .in_memory()
+        .aof_path(&aof_path)
+        .build()
+        .unwrap();
+}
+
+#[test]
+fn test_builder_with_config() {
+    let config = Config::default().with_max_memory(64, EvictionPolicy::NoEviction);
+    let db = DBBuilder::new().with_config(config).build().unwrap();
+
+    db.insert("key", b"value", None).unwrap();
+    assert!(matches!(
+        db.insert("large", vec![0u8; 128], None),
+        Err(SpatioError::MemoryLimitExceeded)
+    ));
+}
+
+#[test]
+fn test_builder_rejects_invalid_config() {
+    let config = Config {
+        max_knn_radius_meters: -1.0,
+        ..Config::default()
+    };
+    assert_invalid_configuration(DBBuilder::new().with_config(config));
+}
+
+#[test]
+fn test_builder_with_backend() {
+    let db = DBBuilder::new()
+        .with_backend(Box::new(MemoryBackend::new()))
+        .build()
+        .unwrap();
+    db.insert("key", b"value", None).unwrap();
+    assert_eq!(db.get("key").unwrap().unwrap().as_ref(), b"value");
+}
+
+#[test]
+fn test_builder_with_index_strategy() {
+    let nyc = Point::new(40.7128, -74.0060);
+
+    let db = DBBuilder::new()
+        .with_index_strategy(IndexStrategy::Geohash { precision: 6 })
+        .build()
+        .unwrap();
+    db.insert_point("cities", &nyc, b"NYC", None).unwrap();
+    assert_eq!(db.find_nearby("cities", &nyc, 1000.0, 10).unwrap().len(), 1);
+
+    assert_invalid_configuration(
+        DBBuilder::new().with_index_strategy(IndexStrategy::Geohash { precision: 13 }),
+    );
+}
+
+#[cfg(feature = "s2")]
+#[test]
+fn test_builder_with_s2_index_strategy() {
+    let nyc = Point::new(40.7128, -74.0060);
+
+    let db = DBBuilder::new()
+        .with_index_strategy(IndexStrategy::S2 { level: 16 })
+        .build()
+        .unwrap();
+    db.insert_point("sensors", &nyc, b"s-1", None).unwrap();
+    let found = db
+        .find_within_bounds("sensors", 40.7, -74.1, 40.8, -73.9, 10)
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert!(
+        db.find_nearby("sensors", &nyc, 1000.0, 10)
+            .unwrap()
+            .is_empty()
+    );
+
+    assert_invalid_configuration(
+        DBBuilder::new().with_index_strategy(IndexStrategy::S2 { level: 31 }),
+    );
+}
+
+#[cfg(feature = "aof")]
+#[test]
+fn test_builder_with_compression() {
+    let temp_dir = TempDir::new().unwrap();
+    let plain_path = temp_dir.path().join("plain.aof");
+    let compressed_path = temp_dir.path().join("compressed.aof");
+    let value = vec![b'x'; 4096];
+
+    for (path, compression) in [
+        (&plain_path, Compression::None),
+        (&compressed_path, Compression::Lz4),
+    ] {
+        let db = DBBuilder::new()
+            .aof_path(path)
+            .with_compression(compression)
+            .build()
+            .unwrap();
+        db.insert("key", value.as_slice(), None).unwrap();
+    }
+
+    let plain_size = std::fs::metadata(&plain_path).unwrap().len();
+    let compressed_size = std::fs::metadata(&compressed_path).unwrap().len();
+    assert!(compressed_size < plain_size / 4);
+
+    // Compressed records replay without the builder option
+    let db = DBBuilder::new().aof_path(&compressed_path).build().unwrap();
+    assert_eq!(db.get("key").unwrap().unwrap().as_ref(), value.as_slice());
+
+    assert_invalid_configuration(DBBuilder::new().with_compression(Compression::Lz4));
+}
+
+#[cfg(feature = "aof")]
+#[test]
+fn test_builder_read_only() {
+    let temp_dir = TempDir::new().unwrap();
+    let aof_path = temp_dir.path().join("read_only.aof");
+
+    let db = DBBuilder::new().aof_path(&aof_path).build().unwrap();
+    db.insert("key", b"value", None).unwrap();
+    drop(db);
+    let size = std::fs::metadata(&aof_path).unwrap().len();
+
+    let db = DBBuilder::new()
+        .aof_path(&aof_path)
+        .read_only()
+        .build()
+        .unwrap();
+    assert_eq!(db.get("key").unwrap().unwrap().as_ref(), b"value");
+    assert!(matches!(
+        db.insert("key", b"other", None),
+        Err(SpatioError::ReadOnly)
+    ));
+    assert!(matches!(db.delete("key"), Err(SpatioError::ReadOnly)));
+    let err = db
+        .insert_point("cities", &Point::new(40.7, -74.0), b"NYC", None)
+        .unwrap_err();
+    assert!(matches!(err.root_cause(), SpatioError::ReadOnly));
+    assert!(matches!(
+        db.atomic(|batch| batch.insert("other", b"value", None)),
+        Err(SpatioError::ReadOnly)
+    ));
+    drop(db);
+    assert_eq!(std::fs::metadata(&aof_path).unwrap().len(), size);
+
+    // Backends can be opened read-only too
+    let mut backend = MemoryBackend::new();
+    backend
+        .put(b"key", &spatio::types::DbItem::new(b"value".to_vec()))
+        .unwrap();
+    let db = DBBuilder::new()
+        .with_backend(Box::new(backend))
+        .read_only()
+        .build()
+        .unwrap();
+    assert_eq!(db.get("key").unwrap().unwrap().as_ref(), b"value");
+}
+
+#[cfg(feature = "aof")]
+#[test]
+fn test_builder_read_only_conflicts() {
+    let temp_dir = TempDir::new().unwrap();
+    let existing = temp_dir.path().join("existing.aof");
+    DBBuilder::new().aof_path(&existing).build().unwrap();
+
+    assert_invalid_configuration(DBBuilder::new().read_only());
+    assert_invalid_configuration(
+        DBBuilder::new()
+            .aof_path(temp_dir.path().join("missing.aof"))
+            .read_only(),
+    );
+    assert_invalid_configuration(
+        DBBuilder::new()
+            .aof_path(&existing)
+            .read_only()
+            .with_expiry_interval(Duration::from_secs(1)),
+    );
+    assert_invalid_configuration(
+        DBBuilder::new()
+            .aof_path(&existing)
+            .read_only()
+            .with_config(Config::default().with_auto_compact_interval(Duration::from_secs(1))),
+    );
+}
+
+#[test]
+fn test_builder_with_metrics_prefix() {
+    // Builder options win over the config regardless of call order
+    let db = DBBuilder::new()
+        .with_metrics_prefix("tracker")
+        .with_config(Config::default().with_metrics_prefix("ignored"))
+        .build()
+        .unwrap();
+    db.insert("key", b"value", None).unwrap();
+
+    assert_invalid_configuration(DBBuilder::new().with_metrics_prefix(""));
+}
+
+#[test]
+fn test_builder_with_expiry_interval() {
+    let db = DBBuilder::new()
+        .with_expiry_interval(Duration::from_millis(10))
+        .build()
+        .unwrap();
+    let opts = SetOptions::with_ttl(Duration::from_millis(5));
+    db.insert("session", b"token", Some(opts)).unwrap();
+
+    std::thread::sleep(Duration::from_millis(100));
+    assert_eq!(db.stats().unwrap().expired_count, 1);
+
+    assert_invalid_configuration(DBBuilder::new().with_expiry_interval(Duration::ZERO));
+}
@@ -1,4 +1,4 @@
-use spatio::{Config, Point, SetOptions, Spatio};
+use spatio::{BoundingBox, Config, Coordinate, Namespace, Point, SetOptions, Spatio};
 use std::time::Duration;
 use tempfile::NamedTempFile;
 
@@ -54,6 +54,54 @@ fn test_atomic_operations() {
     assert_eq!(db.get("key3").unwrap().unwrap().as_ref(), b"value3");
 }
 
+#[test]
+fn test_atomic_read_your_own_writes() {
+    let db = Spatio::memory().unwrap();
+    db.insert("counter", b"1", None).unwrap();
+    db.insert("doomed", b"value", None).unwrap();
+
+    db.atomic(|batch| {
+        // Falls through to the database before any pending write
+        let current = batch.get("counter")?.unwrap();
+        let next: u32 = std::str::from_utf8(&current).unwrap().parse().unwrap();
+        batch.insert("counter", (next + 1).to_string(), None)?;
+
+        // Pending writes are visible within the batch
+        assert_eq!(batch.get("counter")?.unwrap().as_ref(), b"2");
+
+        // Pending deletes hide committed values
+        batch.delete("doomed")?;
+        assert!(batch.get("doomed")?.is_none());
+
+        // Nothing is applied until commit
+        assert_eq!(db.get("counter")?.unwrap().as_ref(), b"1");
+        Ok(())
+    })
+    .unwrap();
+
+    assert_eq!(db.get("counter").unwrap().unwrap().as_ref(), b"2");
+    assert!(db.get("doomed").unwrap().is_none());
+}
+
+#[test]
+fn test_atomic_point_inserts() {
+    let db = Spatio::memory().unwrap();
+    let nyc = Point::new(40.7128, -74.0060);
+    let brooklyn = Point::new(40.6782, -73.9442);
+
+    db.atomic(|batch| {
+        batch.insert_point("cities", &nyc, b"New York", None)?;
+        batch.insert_point("cities", &brooklyn, b"Brooklyn", None)?;
+        batch.insert("meta:count", b"2", None)?;
+        Ok(())
+    })
+    .unwrap();
+
+    let nearby = db.find_nearby("cities", &nyc, 20_000.0, 10).unwrap();
+    assert_eq!(nearby.len(), 2);
+    assert_eq!(db.stats().unwrap().key_count, 3);
+}
+
 #[test]
 fn test_spatial_operations() {
     let db = Spatio::memory().unwrap();
@@ -211,6 +259,41 @@ fn test_multiple_namespaces() {
     assert_eq!(airports[0].1.as_ref(), b"JFK Area");
 }
 
+#[test]
+fn test_namespace_delete_and_stats() {
+    let db = Spatio::memory().unwrap();
+    let tenant_a = Namespace::new("tenant_a");
+    let tenant_b = Namespace::new("tenant_b");
+
+    db.insert(tenant_a.key("user:1"), b"Alice", None).unwrap();
+    db.insert(tenant_a.key("user:2"), b"Bob", None).unwrap();
+    db.insert(tenant_b.key("user:1"), b"Carol", None).unwrap();
+    db.insert("plain_key", b"value", None).unwrap();
+
+    let stats = db.namespace_stats(&tenant_a).unwrap();
+    assert_eq!(stats.key_count, 2);
+    assert_eq!(stats.expired_count, 0);
+    assert_eq!(
+        stats.size_bytes,
+        "tenant_a::user:1Alice".len() + "tenant_a::user:2Bob".len()
+    );
+
+    assert_eq!(
+        db.list_namespace_prefixes().unwrap(),
+        vec!["tenant_a", "tenant_b"]
+    );
+
+    assert_eq!(db.delete_namespace(&tenant_a).unwrap(), 2);
+    assert!(db.get(tenant_a.key("user:1")).unwrap().is_none());
+    assert_eq!(
+        db.get(tenant_b.key("user:1")).unwrap().unwrap().as_ref(),
+        b"Carol"
+    );
+    assert_eq!(db.get("plain_key").unwrap().unwrap().as_ref(), b"value");
+    assert_eq!(db.list_namespace_prefixes().unwrap(), vec!["tenant_b"]);
+    assert_eq!(db.delete_namespace(&tenant_a).unwrap(), 0);
+}
+
 #[test]
 fn test_spatial_query_methods() {
     let db = Spatio::memory().unwrap();
@@ -347,3 +430,118 @@ fn test_geohash_precision_configuration() {
     assert!(custom_db.contains_point("cities", &point, 100.0).unwrap());
     assert!(default_db.contains_point("cities", &point, 100.0).unwrap());
 }
+
+#[test]
+fn test_convex_hull_of_prefix() {
+    let db = Spatio::memory().unwrap();
+
+    // Not enough points for a hull
+    assert!(db.convex_hull_of_prefix("sensors").unwrap().is_none());
+    db.insert_point("sensors", &Point::new(40.70, -74.02), b"a", None)
+        .unwrap();
+    db.insert_point("sensors", &Point::new(40.80, -74.02), b"b", None)
+        .unwrap();
+    assert!(db.convex_hull_of_prefix("sensors").unwrap().is_none());
+
+    db.insert_point("sensors", &Point::new(40.80, -73.90), b"c", None)
+        .unwrap();
+    db.insert_point("sensors", &Point::new(40.70, -73.90), b"d", None)
+        .unwrap();
+    db.insert_point("sensors", &Point::new(40.75, -73.96), b"inner", None)
+        .unwrap();
+
+    let hull = db.convex_hull_of_prefix("sensors").unwrap().unwrap();
+    assert_eq!(hull.exterior().coords().len(), 5);
+    for corner in [
+        Point::new(40.70, -74.02),
+        Point::new(40.80, -74.02),
+        Point::new(40.80, -73.90),
+        Point::new(40.70, -73.90),
+    ] {
+        assert!(
+            hull.exterior()
+                .coords()
+                .iter()
+                .any(|c| c.to_point() == corner)
+        );
+    }
+}
+
+#[test]
+fn test_voronoi_for_prefix() {
+    let db = Spatio::memory().unwrap();
+    let area = BoundingBox::new(40.60, -74.10, 40.80, -73.90);
+    assert!(db.voronoi_for_prefix("towers", &area).unwrap().is_empty());
+
+    db.insert_point("towers", &Point::new(40.65, -74.05), b"sw", None)
+        .unwrap();
+    db.insert_point("towers", &Point::new(40.75, -74.05), b"nw", None)
+        .unwrap();
+    db.insert_point("towers", &Point::new(40.70, -73.95), b"e", None)
+        .unwrap();
+    // Outside the area, but still takes part of it
+    db.insert_point("towers", &Point::new(40.70, -73.87), b"far", None)
+        .unwrap();
+
+    let cells = db.voronoi_for_prefix("towers", &area).unwrap();
+    assert_eq!(cells.len(), 4);
+    let total: f64 = cells.iter().map(|(_, cell)| cell.area()).sum();
+    assert!((total - 0.04).abs() < 1e-9);
+
+    let covering = |lat: f64, lon: f64| {
+        let location = Coordinate::new(lon, lat);
+        cells
+            .iter()
+            .find(|(_, cell)| cell.contains_point(&location))
+            .map(|(data, _)| data.as_ref().to_vec())
+            .unwrap()
+    };
+    assert_eq!(covering(40.62, -74.08), b"sw");
+    assert_eq!(covering(40.78, -74.08), b"nw");
+    assert_eq!(covering(40.70, -73.96), b"e");
+    assert_eq!(covering(40.70, -73.905), b"far");
+}
+
+#[cfg(feature = "h3")]
+#[test]
+fn test_h3_spatial_operations() {
+    let temp_file = NamedTempFile::new().unwrap();
+    let nyc = Point::new(40.7128, -74.0060);
+    let brooklyn = Point::new(40.6782, -73.9442);
+    let london = Point::new(51.5074, -0.1278);
+
+    {
+        let db = Spatio::open(temp_file.path()).unwrap();
+        db.insert_point_with_h3("cities", &nyc, 9, b"New York", None)
+            .unwrap();
+        db.insert_point_with_h3("cities", &brooklyn, 9, b"Brooklyn", None)
+            .unwrap();
+        db.insert_point_with_h3("cities", &london, 9, b"London", None)
+            .unwrap();
+
+        // Every prefix uses a single resolution
+        assert!(
+            db.insert_point_with_h3("cities", &nyc, 7, b"NYC", None)
+                .is_err()
+        );
+
+        let nearby = db.find_nearby_h3("cities", &nyc, 10_000.0, 10).unwrap();
+        assert_eq!(nearby.len(), 2);
+        assert_eq!(nearby[0].1.as_ref(), b"New York");
+        assert_eq!(nearby[1].1.as_ref(), b"Brooklyn");
+
+        // Geohash queries do not see H3-indexed points
+        assert!(
+            db.find_nearby("cities", &nyc, 10_000.0, 10)
+                .unwrap()
+                .is_empty()
+        );
+        db.sync().unwrap();
+    }
+
+    // The H3 index is rebuilt from cell keys on replay
+    let db = Spatio::open(temp_file.path()).unwrap();
+    let nearby = db.find_nearby_h3("cities", &london, 1_000.0, 10).unwrap();
+    assert_eq!(nearby.len(), 1);
+    assert_eq!(nearby[0].1.as_ref(), b"London");
+}
@@ -0,0 +1,68 @@
+//! Exposing Spatio metrics to Prometheus over HTTP.
+//!
+//! Install a `metrics` recorder once at startup; every database then reports
+//! through it. Here the Prometheus exporter serves the scrape endpoint.
+
+#![cfg(feature = "metrics")]
+
+use metrics_exporter_prometheus::PrometheusBuilder;
+use spatio::{Config, Point, SetOptions, Spatio};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::time::Duration;
+
+/// Fetch the exporter's scrape output, retrying while the listener starts
+fn scrape(addr: SocketAddr) -> String {
+    for _ in 0..50 {
+        if let Ok(mut stream) = TcpStream::connect(addr) {
+            stream
+                .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+                .unwrap();
+            let mut response = String::new();
+            stream.read_to_string(&mut response).unwrap();
+            return response;
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    panic!("metrics endpoint did not start");
+}
+
+#[test]
+fn test_prometheus_http_exporter() {
+    // Reserve a free port for the exporter
+    let addr = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap();
+    PrometheusBuilder::new()
+        .with_http_listener(addr)
+        .install()
+        .unwrap();
+
+    let db = Spatio::memory_with_config(Config::default().with_metrics_prefix("geo")).unwrap();
+    let nyc = Point::new(40.7128, -74.0060);
+
+    db.insert("user:1", b"alice", None).unwrap();
+    db.insert(
+        "session:1",
+        b"token",
+        Some(SetOptions::with_expiration(
+            std::time::SystemTime::UNIX_EPOCH,
+        )),
+    )
+    .unwrap();
+    db.insert_point("cities", &nyc, b"New York", None).unwrap();
+    db.get("user:1").unwrap();
+    db.find_nearby("cities", &nyc, 1000.0, 10).unwrap();
+    assert_eq!(db.cleanup_expired().unwrap(), 1);
+
+    let body = scrape(addr);
+    assert!(body.contains("geo_insert_total 3"), "{body}");
+    assert!(body.contains("geo_get_total 1"), "{body}");
+    assert!(body.contains("geo_expired_total 1"), "{body}");
+    assert!(body.contains("geo_key_count 2"), "{body}");
+    assert!(
+        body.contains("geo_spatial_query_duration_seconds_count{query=\"find_nearby\"} 1"),
+        "{body}"
+    );
+}
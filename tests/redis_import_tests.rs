@@ -0,0 +1,227 @@
+#![cfg(feature = "redis-import")]
+
+//! `DB::import_from_redis` against a minimal in-process server that speaks
+//! enough of the Redis protocol for the import.
+
+use spatio::{Point, SetOptions, Spatio};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+enum FakeValue {
+    String(&'static str),
+    /// Members with their `(longitude, latitude)`, `None` if `GEOPOS`
+    /// cannot place them
+    SortedSet(Vec<(&'static str, Option<(f64, f64)>)>),
+    Hash,
+}
+
+/// Keys served by the fake server, with their remaining TTL in
+/// milliseconds (-1 for none, -2 for a key that has just expired)
+fn fake_keys() -> Vec<(&'static str, FakeValue, i64)> {
+    vec![
+        ("user:1", FakeValue::String("alice"), -1),
+        ("user:2", FakeValue::String("bob"), 60_000),
+        (
+            "stores",
+            FakeValue::SortedSet(vec![
+                ("soho", Some((-74.0, 40.72))),
+                ("dumbo", Some((-73.99, 40.70))),
+            ]),
+            -1,
+        ),
+        (
+            "leaderboard",
+            FakeValue::SortedSet(vec![("player", None)]),
+            -1,
+        ),
+        ("profile:1", FakeValue::Hash, -1),
+        ("gone", FakeValue::String("stale"), -2),
+    ]
+}
+
+/// Start the fake server and return its URL
+fn start_fake_redis() -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("redis://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            thread::spawn(move || serve(stream));
+        }
+    });
+    url
+}
+
+fn serve(stream: TcpStream) {
+    let keys = fake_keys();
+    let mut writer = stream.try_clone().unwrap();
+    let mut reader = BufReader::new(stream);
+    while let Some(command) = read_command(&mut reader) {
+        let reply = respond(&keys, &command);
+        if writer.write_all(reply.as_bytes()).is_err() {
+            return;
+        }
+    }
+}
+
+fn read_command(reader: &mut impl BufRead) -> Option<Vec<String>> {
+    let mut line = String::new();
+    reader.read_line(&mut line).ok()?;
+    let count: usize = line.trim_end().strip_prefix('*')?.parse().ok()?;
+    let mut args = Vec::with_capacity(count);
+    for _ in 0..count {
+        line.clear();
+        reader.read_line(&mut line).ok()?;
+        let len: usize = line.trim_end().strip_prefix('$')?.parse().ok()?;
+        let mut arg = vec![0; len + 2];
+        reader.read_exact(&mut arg).ok()?;
+        arg.truncate(len);
+        args.push(String::from_utf8(arg).ok()?);
+    }
+    Some(args)
+}
+
+fn bulk(value: &str) -> String {
+    format!("${}\r\n{}\r\n", value.len(), value)
+}
+
+fn array(items: &[String]) -> String {
+    format!("*{}\r\n{}", items.len(), items.concat())
+}
+
+fn respond(keys: &[(&'static str, FakeValue, i64)], command: &[String]) -> String {
+    let find = |name: &str| keys.iter().find(|(key, _, _)| *key == name);
+    match command[0].to_uppercase().as_str() {
+        // Two pages, so the cursor is followed
+        "SCAN" => {
+            let pattern = &command[3];
+            let matching: Vec<_> = keys
+                .iter()
+                .map(|(key, _, _)| *key)
+                .filter(|key| match pattern.strip_suffix('*') {
+                    Some(prefix) => key.starts_with(prefix),
+                    None => key == pattern,
+                })
+                .collect();
+            let (page, next) = if command[1] == "0" {
+                (&matching[..matching.len() / 2], "3")
+            } else {
+                (&matching[matching.len() / 2..], "0")
+            };
+            let page: Vec<_> = page.iter().map(|key| bulk(key)).collect();
+            array(&[bulk(next), array(&page)])
+        }
+        "TYPE" => match find(&command[1]) {
+            Some((_, FakeValue::String(_), _)) => "+string\r\n".to_string(),
+            Some((_, FakeValue::SortedSet(_), _)) => "+zset\r\n".to_string(),
+            Some((_, FakeValue::Hash, _)) => "+hash\r\n".to_string(),
+            None => "+none\r\n".to_string(),
+        },
+        "PTTL" => format!(":{}\r\n", find(&command[1]).map_or(-2, |(_, _, ttl)| *ttl)),
+        "GET" => match find(&command[1]) {
+            Some((_, FakeValue::String(value), _)) => bulk(value),
+            _ => "$-1\r\n".to_string(),
+        },
+        "ZRANGE" => match find(&command[1]) {
+            Some((_, FakeValue::SortedSet(members), _)) => {
+                let members: Vec<_> = members.iter().map(|(member, _)| bulk(member)).collect();
+                array(&members)
+            }
+            _ => "*0\r\n".to_string(),
+        },
+        "GEOPOS" => {
+            let members = match find(&command[1]) {
+                Some((_, FakeValue::SortedSet(members), _)) => members.as_slice(),
+                _ => &[],
+            };
+            let positions: Vec<_> = command[2..]
+                .iter()
+                .map(
+                    |name| match members.iter().find(|(member, _)| member == name) {
+                        Some((_, Some((lon, lat)))) => {
+                            array(&[bulk(&lon.to_string()), bulk(&lat.to_string())])
+                        }
+                        _ => "*-1\r\n".to_string(),
+                    },
+                )
+                .collect();
+            array(&positions)
+        }
+        _ => "+OK\r\n".to_string(),
+    }
+}
+
+#[test]
+fn test_import_from_redis() {
+    let url = start_fake_redis();
+    let db = Spatio::memory().unwrap();
+
+    let stats = db.import_from_redis(&url, "*", None).unwrap();
+    assert_eq!(stats.keys_imported, 3);
+    // The plain sorted set, the hash and the expired key
+    assert_eq!(stats.keys_skipped, 3);
+    let expected_bytes = "user:1alice".len() + "user:2bob".len() + "storessohodumbo".len();
+    assert_eq!(stats.bytes_transferred, expected_bytes);
+
+    assert_eq!(db.get("user:1").unwrap().unwrap().as_ref(), b"alice");
+    assert_eq!(db.get("user:2").unwrap().unwrap().as_ref(), b"bob");
+    assert!(db.get("gone").unwrap().is_none());
+    assert!(db.get("profile:1").unwrap().is_none());
+
+    // Redis TTLs carry over; keys without one stay persistent
+    assert!(db.remaining_ttl("user:1").unwrap().is_none());
+    let ttl = db.remaining_ttl("user:2").unwrap().unwrap();
+    assert!(ttl <= Duration::from_secs(60) && ttl > Duration::from_secs(50));
+
+    // Geo set members become points under the set's key
+    let soho = Point::new(40.72, -74.0);
+    let nearby = db.find_nearby("stores", &soho, 100.0, 10).unwrap();
+    assert_eq!(nearby.len(), 1);
+    assert_eq!(nearby[0].1.as_ref(), b"soho");
+    assert_eq!(
+        db.find_nearby("stores", &soho, 5_000.0, 10).unwrap().len(),
+        2
+    );
+    assert!(
+        db.find_nearby("leaderboard", &soho, 1e7, 10)
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn test_import_from_redis_pattern_and_default_ttl() {
+    let url = start_fake_redis();
+    let db = Spatio::memory().unwrap();
+
+    let opts = SetOptions::with_ttl(Duration::from_secs(3600));
+    let stats = db.import_from_redis(&url, "user:*", Some(opts)).unwrap();
+    assert_eq!(stats.keys_imported, 2);
+    assert_eq!(stats.keys_skipped, 0);
+
+    // The default applies only where Redis had no TTL
+    let ttl = db.remaining_ttl("user:1").unwrap().unwrap();
+    assert!(ttl > Duration::from_secs(3500));
+    let ttl = db.remaining_ttl("user:2").unwrap().unwrap();
+    assert!(ttl <= Duration::from_secs(60));
+
+    let db = Spatio::memory().unwrap();
+    let stats = db.import_from_redis(&url, "nothing:*", None).unwrap();
+    assert_eq!(stats, Default::default());
+}
+
+#[test]
+fn test_import_from_redis_connection_error() {
+    // Bind then drop a listener to get a port nothing is listening on
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+    let db = Spatio::memory().unwrap();
+    let err = db
+        .import_from_redis(&format!("redis://127.0.0.1:{}", port), "*", None)
+        .unwrap_err();
+    assert!(err.to_string().contains("Redis error"));
+}
@@ -0,0 +1,160 @@
+#![cfg(feature = "resp-server")]
+
+use spatio::{Point, RespServer, RespValue, Spatio};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// Serve a fresh in-memory database on a free port
+async fn start_server() -> (Spatio, std::net::SocketAddr) {
+    let db = Spatio::memory().unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(RespServer::serve(listener, db.clone()));
+    (db, addr)
+}
+
+fn encode_request(args: &[&str]) -> Vec<u8> {
+    let request = RespValue::Array(Some(
+        args.iter()
+            .map(|arg| RespValue::BulkString(Some(arg.as_bytes().to_vec().into())))
+            .collect(),
+    ));
+    let mut buf = Vec::new();
+    request.encode(&mut buf);
+    buf
+}
+
+/// Read until `count` complete replies have arrived
+async fn read_replies(stream: &mut TcpStream, count: usize) -> Vec<RespValue> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    loop {
+        let mut replies = Vec::new();
+        let mut used = 0;
+        while let Some((reply, len)) = RespValue::parse(&buf[used..]).unwrap() {
+            replies.push(reply);
+            used += len;
+        }
+        if replies.len() >= count {
+            return replies;
+        }
+        let read = stream.read(&mut chunk).await.unwrap();
+        assert!(read > 0, "server closed the connection");
+        buf.extend_from_slice(&chunk[..read]);
+    }
+}
+
+fn bulk(value: &str) -> RespValue {
+    RespValue::BulkString(Some(value.as_bytes().to_vec().into()))
+}
+
+#[tokio::test]
+async fn test_pipelined_commands_over_tcp() {
+    let (db, addr) = start_server().await;
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    let mut pipeline = Vec::new();
+    for args in [
+        &["SET", "greeting", "hello", "EX", "60"][..],
+        &["GET", "greeting"],
+        &["GEOSET", "poi", "-74.0060", "40.7128", "city-hall"],
+        &["GEORADIUSMEMBER", "poi", "-74.0059", "40.7127", "100", "m"],
+        &["NOPE"],
+        &["DEL", "greeting"],
+    ] {
+        pipeline.extend(encode_request(args));
+    }
+    stream.write_all(&pipeline).await.unwrap();
+
+    let replies = read_replies(&mut stream, 6).await;
+    assert_eq!(
+        replies,
+        vec![
+            RespValue::SimpleString("OK".to_string()),
+            bulk("hello"),
+            RespValue::Integer(1),
+            RespValue::Array(Some(vec![bulk("city-hall")])),
+            RespValue::Error("ERR unknown command 'NOPE'".to_string()),
+            RespValue::Integer(1),
+        ]
+    );
+
+    // Commands went to the shared database
+    let nearby = db
+        .find_nearby("poi", &Point::new(40.7128, -74.0060), 10.0, 10)
+        .unwrap();
+    assert_eq!(nearby[0].1.as_ref(), b"city-hall");
+    assert!(db.get("greeting").unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_request_split_across_writes() {
+    let (_db, addr) = start_server().await;
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    let request = encode_request(&["PING", "split"]);
+    let (head, tail) = request.split_at(request.len() / 2);
+    stream.write_all(head).await.unwrap();
+    stream.flush().await.unwrap();
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    stream.write_all(tail).await.unwrap();
+
+    assert_eq!(read_replies(&mut stream, 1).await, vec![bulk("split")]);
+}
+
+#[tokio::test]
+async fn test_protocol_error_closes_connection() {
+    let (_db, addr) = start_server().await;
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+
+    stream.write_all(b"$-7\r\n").await.unwrap();
+    let replies = read_replies(&mut stream, 1).await;
+    assert!(matches!(&replies[0], RespValue::Error(msg) if msg.starts_with("ERR Protocol error")));
+
+    let mut rest = Vec::new();
+    stream.read_to_end(&mut rest).await.unwrap();
+    assert!(rest.is_empty());
+}
+
+/// A stock Redis client works without changes
+#[cfg(feature = "redis-import")]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_redis_client_compatibility() {
+    let (_db, addr) = start_server().await;
+
+    tokio::task::spawn_blocking(move || {
+        let client = redis::Client::open(format!("redis://{}", addr)).unwrap();
+        let mut con = client.get_connection().unwrap();
+
+        let _: () = redis::cmd("SET")
+            .arg("user:1")
+            .arg("alice")
+            .arg("EX")
+            .arg(30)
+            .query(&mut con)
+            .unwrap();
+        let value: Option<String> = redis::cmd("GET").arg("user:1").query(&mut con).unwrap();
+        assert_eq!(value.as_deref(), Some("alice"));
+        let ttl: i64 = redis::cmd("TTL").arg("user:1").query(&mut con).unwrap();
+        assert!((29..=30).contains(&ttl));
+
+        let (added, members): (i64, Vec<String>) = redis::pipe()
+            .cmd("GEOSET")
+            .arg("poi")
+            .arg(-0.1278)
+            .arg(51.5074)
+            .arg("london")
+            .cmd("GEORADIUSMEMBER")
+            .arg("poi")
+            .arg(-0.1)
+            .arg(51.5)
+            .arg(10)
+            .arg("km")
+            .query(&mut con)
+            .unwrap();
+        assert_eq!(added, 1);
+        assert_eq!(members, vec!["london".to_string()]);
+    })
+    .await
+    .unwrap();
+}
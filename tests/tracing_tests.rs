@@ -0,0 +1,113 @@
+#![cfg(feature = "tracing")]
+
+use opentelemetry::trace::{Status, TracerProvider as _};
+use opentelemetry::{Key, Value};
+use opentelemetry_sdk::trace::{InMemorySpanExporter, SdkTracerProvider, SpanData};
+use spatio::{Point, SetOptions, Spatio};
+use std::time::Duration;
+use tracing_subscriber::layer::SubscriberExt;
+
+/// Run `f` with an OpenTelemetry layer installed and return the exported spans
+fn capture_spans(f: impl FnOnce()) -> Vec<SpanData> {
+    let exporter = InMemorySpanExporter::default();
+    let provider = SdkTracerProvider::builder()
+        .with_simple_exporter(exporter.clone())
+        .build();
+    let layer = spatio::tracing_opentelemetry::layer().with_tracer(provider.tracer("spatio-tests"));
+    let subscriber = tracing_subscriber::registry().with(layer);
+
+    tracing::subscriber::with_default(subscriber, f);
+    exporter.get_finished_spans().unwrap()
+}
+
+fn find_span<'a>(spans: &'a [SpanData], name: &str) -> &'a SpanData {
+    spans
+        .iter()
+        .find(|span| span.name == name)
+        .unwrap_or_else(|| panic!("span {name} was not emitted"))
+}
+
+fn attribute(span: &SpanData, key: &str) -> Option<Value> {
+    span.attributes
+        .iter()
+        .find(|kv| kv.key == Key::from(key.to_string()))
+        .map(|kv| kv.value.clone())
+}
+
+#[test]
+fn test_insert_span_attributes() {
+    let spans = capture_spans(|| {
+        let db = Spatio::memory().unwrap();
+        db.insert("user:1", "hello world", None).unwrap();
+        db.insert(
+            "session:1",
+            "token",
+            Some(SetOptions::with_ttl(Duration::from_secs(60))),
+        )
+        .unwrap();
+    });
+
+    let inserts: Vec<_> = spans.iter().filter(|s| s.name == "db.insert").collect();
+    assert_eq!(inserts.len(), 2);
+
+    let plain = inserts
+        .iter()
+        .find(|s| attribute(s, "db.key_length") == Some(Value::I64(6)))
+        .unwrap();
+    assert_eq!(attribute(plain, "db.value_length"), Some(Value::I64(11)));
+    assert_eq!(attribute(plain, "db.has_ttl"), Some(Value::Bool(false)));
+
+    let expiring = inserts
+        .iter()
+        .find(|s| attribute(s, "db.key_length") == Some(Value::I64(9)))
+        .unwrap();
+    assert_eq!(attribute(expiring, "db.value_length"), Some(Value::I64(5)));
+    assert_eq!(attribute(expiring, "db.has_ttl"), Some(Value::Bool(true)));
+}
+
+#[test]
+fn test_spatial_span_attributes() {
+    let spans = capture_spans(|| {
+        let db = Spatio::memory().unwrap();
+        let nyc = Point::new(40.7128, -74.0060);
+        db.insert_point("cities", &nyc, b"NYC", None).unwrap();
+        let found = db.find_nearby("cities", &nyc, 5_000.0, 10).unwrap();
+        assert_eq!(found.len(), 1);
+        db.count_within_distance("cities", &nyc, 2_500.0).unwrap();
+    });
+
+    let insert = find_span(&spans, "db.insert_point");
+    assert_eq!(attribute(insert, "db.prefix"), Some(Value::from("cities")));
+    assert_eq!(attribute(insert, "db.value_length"), Some(Value::I64(3)));
+    assert_eq!(attribute(insert, "db.has_ttl"), Some(Value::Bool(false)));
+
+    let nearby = find_span(&spans, "db.find_nearby");
+    assert_eq!(attribute(nearby, "db.prefix"), Some(Value::from("cities")));
+    assert_eq!(
+        attribute(nearby, "db.radius_meters"),
+        Some(Value::F64(5_000.0))
+    );
+    assert_eq!(attribute(nearby, "db.limit"), Some(Value::I64(10)));
+
+    let count = find_span(&spans, "db.count_within_distance");
+    assert_eq!(
+        attribute(count, "db.radius_meters"),
+        Some(Value::F64(2_500.0))
+    );
+}
+
+#[test]
+fn test_failed_operation_marks_span_as_error() {
+    let spans = capture_spans(|| {
+        let db = Spatio::memory().unwrap();
+        let query = Point::new(40.7128, -74.0060);
+        assert!(
+            db.approximate_nearest_neighbor("cities", &query, 1, 0)
+                .is_err()
+        );
+    });
+
+    let span = find_span(&spans, "db.approximate_nearest_neighbor");
+    assert!(matches!(span.status, Status::Error { .. }));
+    assert_eq!(attribute(span, "db.num_hash_tables"), Some(Value::I64(0)));
+}